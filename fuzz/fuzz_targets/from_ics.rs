@@ -0,0 +1,27 @@
+// File: fuzz/fuzz_targets/from_ics.rs
+// Feeds arbitrary text into the ICS adapter. Run with: cargo +nightly fuzz run from_ics
+#![no_main]
+
+use cfait::model::Task;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &str| {
+    let Ok(task) = Task::from_ics(data, String::new(), String::new(), String::new()) else {
+        return;
+    };
+    // Anything we managed to parse must serialize and re-parse without panicking
+    let ics = task.to_ics();
+    let Ok(once) = Task::from_ics(&ics, String::new(), String::new(), String::new()) else {
+        return;
+    };
+    // Once written by us, the parts to_ics writes by hand (VALARMs, EXDATEs, the Nextcloud
+    // list state, VJOURNAL) must read back as they were
+    let again = Task::from_ics(&once.to_ics(), String::new(), String::new(), String::new())
+        .expect("our own ICS parses");
+    assert_eq!(again.kind, once.kind);
+    assert_eq!(again.reminders, once.reminders);
+    assert_eq!(again.exdates, once.exdates);
+    assert_eq!(again.hide_subtasks, once.hide_subtasks);
+    assert_eq!(again.sort_order, once.sort_order);
+    assert_eq!(again.categories, once.categories);
+});
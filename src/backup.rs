@@ -0,0 +1,158 @@
+// File: ./src/backup.rs
+// Snapshots of the local tasks, offline queue, config and mirror, taken once a day and
+// before destructive operations, so a bad migration or restore can be undone.
+use crate::paths::AppPaths;
+use crate::storage::LocalStorage;
+use anyhow::{Result, anyhow};
+use chrono::{Duration, Local, NaiveDateTime};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Older snapshots are deleted beyond this count
+pub const MAX_BACKUPS: usize = 10;
+/// Minimum age of the newest snapshot before `snapshot_if_due` takes another one
+const BACKUP_INTERVAL_HOURS: i64 = 24;
+const NAME_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+const CACHE_SUBDIR: &str = "cache";
+
+fn backup_dir() -> Result<PathBuf> {
+    let dir = AppPaths::get_data_dir()?.join("backups");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Shared files that live directly in the data and config directories.
+fn data_files() -> Result<Vec<PathBuf>> {
+    let mut files = vec![AppPaths::get_config_file_path()?];
+    files.extend(LocalStorage::get_path());
+    files.extend(AppPaths::get_journal_path());
+    Ok(files)
+}
+
+/// Mirror files (`tasks_*.json`, `calendars.json`), without locks or temporary files.
+fn cache_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_file()
+                && p.extension().is_some_and(|e| e == "json")
+                && p.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with("tasks_") || n == "calendars.json")
+        })
+        .collect()
+}
+
+fn copy_into(file: &Path, dir: &Path) -> Result<()> {
+    if let Some(name) = file.file_name()
+        && file.is_file()
+    {
+        fs::copy(file, dir.join(name))?;
+    }
+    Ok(())
+}
+
+/// Takes a snapshot now and returns its name. `reason` (e.g. "migrate") is appended to it.
+pub fn snapshot(reason: Option<&str>) -> Result<String> {
+    let name = take(reason)?;
+    prune(MAX_BACKUPS)?;
+    Ok(name)
+}
+
+fn take(reason: Option<&str>) -> Result<String> {
+    let mut name = Local::now().format(NAME_FORMAT).to_string();
+    if let Some(reason) = reason {
+        name = format!("{}-{}", name, reason);
+    }
+    let dir = backup_dir()?.join(&name);
+    let cache_dir = dir.join(CACHE_SUBDIR);
+    fs::create_dir_all(&cache_dir)?;
+
+    for file in data_files()? {
+        copy_into(&file, &dir)?;
+    }
+    for file in cache_files(&AppPaths::get_cache_dir()?) {
+        copy_into(&file, &cache_dir)?;
+    }
+    Ok(name)
+}
+
+/// The scheduled snapshot: only if the newest one is older than a day.
+pub fn snapshot_if_due() -> Result<Option<String>> {
+    let newest = list()?
+        .into_iter()
+        .find_map(|name| NaiveDateTime::parse_from_str(name.get(..19)?, NAME_FORMAT).ok());
+    match newest {
+        Some(t) if Local::now().naive_local() - t < Duration::hours(BACKUP_INTERVAL_HOURS) => {
+            Ok(None)
+        }
+        _ => snapshot(None).map(Some),
+    }
+}
+
+/// Snapshot names, newest first.
+pub fn list() -> Result<Vec<String>> {
+    let mut names: Vec<String> = fs::read_dir(backup_dir()?)?
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| e.file_name().into_string().ok())
+        .collect();
+    names.sort_unstable_by(|a, b| b.cmp(a));
+    Ok(names)
+}
+
+fn prune(keep: usize) -> Result<()> {
+    let dir = backup_dir()?;
+    for name in list()?.into_iter().skip(keep) {
+        fs::remove_dir_all(dir.join(name))?;
+    }
+    Ok(())
+}
+
+/// Copies `from` over `to`, or deletes `to` if the snapshot had no such file.
+fn restore_file(from: &Path, to: &Path) -> Result<()> {
+    LocalStorage::with_lock(to, || {
+        if from.is_file() {
+            LocalStorage::atomic_write(to, fs::read(from)?)
+        } else if to.exists() {
+            Ok(fs::remove_file(to)?)
+        } else {
+            Ok(())
+        }
+    })
+}
+
+/// Puts a snapshot back in place. The current state is saved first ("pre-restore"), so a
+/// restore can itself be undone.
+pub fn restore(name: &str) -> Result<()> {
+    let dir = backup_dir()?.join(name);
+    if name.contains(['/', '\\']) || name.starts_with('.') || !dir.is_dir() {
+        return Err(anyhow!("No backup named '{}'", name));
+    }
+    // Pruned only afterwards, which could otherwise delete the backup being restored
+    take(Some("pre-restore"))?;
+
+    for file in data_files()? {
+        if let Some(file_name) = file.file_name() {
+            restore_file(&dir.join(file_name), &file)?;
+        }
+    }
+    // Mirror files missing from the snapshot go too, so the mirror matches the restored queue
+    let cache_dir = AppPaths::get_cache_dir()?;
+    let snapshot_cache = dir.join(CACHE_SUBDIR);
+    let mut names: Vec<_> = cache_files(&cache_dir)
+        .into_iter()
+        .chain(cache_files(&snapshot_cache))
+        .filter_map(|f| f.file_name().map(|n| n.to_os_string()))
+        .collect();
+    names.sort();
+    names.dedup();
+    for name in names {
+        restore_file(&snapshot_cache.join(&name), &cache_dir.join(&name))?;
+    }
+    prune(MAX_BACKUPS)
+}
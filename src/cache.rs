@@ -1,13 +1,133 @@
 // File: src/cache.rs
+use crate::model::href;
 use crate::model::{CalendarListEntry, Task};
 use crate::paths::AppPaths;
 use crate::storage::LocalStorage;
 use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// How often `cfait daemon` syncs. Mirrors not refreshed for longer are shown as stale.
+pub const SYNC_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+/// A calendar whose mirror was last fetched before `now - SYNC_INTERVAL` (or never).
+pub fn is_stale(synced: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    let interval = Duration::from_std(SYNC_INTERVAL).unwrap_or_default();
+    synced.is_none_or(|at| now - at > interval)
+}
+
+/// "just now", "12 min ago", "3 h ago", "2 d ago"
+pub fn format_ago(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let mins = (now - at).num_minutes();
+    if mins < 1 {
+        "just now".to_string()
+    } else if mins < 60 {
+        format!("{} min ago", mins)
+    } else if mins < 24 * 60 {
+        format!("{} h ago", mins / 60)
+    } else {
+        format!("{} d ago", mins / (24 * 60))
+    }
+}
+
+// --- PRUNING ---
+
+static PRUNE_DAYS: RwLock<Option<u32>> = RwLock::new(None);
+
+/// Done tasks completed more than `days` ago are dropped from the local copies (None = kept).
+pub fn set_prune_days(days: Option<u32>) {
+    if let Ok(mut current) = PRUNE_DAYS.write() {
+        *current = days;
+    }
+}
+
+pub fn prune_days() -> Option<u32> {
+    PRUNE_DAYS.read().map(|d| *d).unwrap_or_default()
+}
+
+/// Splits `tasks` into the kept ones and the done tasks completed before `cutoff`. Parents of
+/// kept tasks stay, so subtasks keep their place in the tree.
+pub fn prune_completed(tasks: Vec<Task>, cutoff: DateTime<Utc>) -> (Vec<Task>, Vec<Task>) {
+    let old = |t: &Task| {
+        t.status.is_done()
+            && t.completed
+                .or(t.last_modified)
+                .is_some_and(|at| at < cutoff)
+    };
+    let mut pruned: HashSet<&str> = tasks
+        .iter()
+        .filter(|t| old(t))
+        .map(|t| t.uid.as_str())
+        .collect();
+    // Walks up from the kept tasks, for chains of done ancestors
+    loop {
+        let needed: Vec<&str> = tasks
+            .iter()
+            .filter(|t| !pruned.contains(t.uid.as_str()))
+            .filter_map(|t| t.parent_uid.as_deref())
+            .filter(|p| pruned.contains(p))
+            .collect();
+        if needed.is_empty() {
+            break;
+        }
+        for uid in needed {
+            pruned.remove(uid);
+        }
+    }
+    let pruned: HashSet<String> = pruned.into_iter().map(str::to_string).collect();
+    tasks.into_iter().partition(|t| !pruned.contains(&t.uid))
+}
+
+/// Prunes a calendar's tasks with `days`, recording the resources dropped in `pruned` (href
+/// -> ETag) so that syncs don't download them again.
+pub fn apply_pruning(
+    tasks: Vec<Task>,
+    days: Option<u32>,
+    pruned: &mut HashMap<String, String>,
+) -> Vec<Task> {
+    let Some(days) = days else {
+        return tasks;
+    };
+    let (kept, removed) = prune_completed(tasks, Utc::now() - Duration::days(days as i64));
+    for task in removed {
+        if !task.href.is_empty() && !task.etag.is_empty() {
+            pruned.insert(task.href, task.etag);
+        }
+    }
+    kept
+}
+
+// --- DISCOVERY ---
+
+/// How long a discovered server layout is trusted before it is looked up again.
+pub const DISCOVERY_TTL: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+/// Where the calendars of an account are: the principal, its calendar home sets and the
+/// collections found in them. Kept between sessions, so that a launch skips the lookups
+/// (several requests per calendar).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Discovery {
+    /// Server URL and user name it was found for
+    pub account: String,
+    pub principal: String,
+    pub home_sets: Vec<String>,
+    pub calendars: Vec<CalendarListEntry>,
+    pub discovered_at: DateTime<Utc>,
+}
+
+impl Discovery {
+    /// Whether it can stand in for a lookup of `account` at `now`.
+    pub fn is_fresh(&self, account: &str, now: DateTime<Utc>) -> bool {
+        let ttl = Duration::from_std(DISCOVERY_TTL).unwrap_or_default();
+        self.account == account && now - self.discovered_at < ttl
+    }
+}
 
 #[derive(Serialize, Deserialize)]
 struct CalendarCache {
@@ -24,11 +144,32 @@ impl Cache {
             .map(|p| p.join("calendars.json"))
     }
 
+    fn get_discovery_path() -> Option<PathBuf> {
+        AppPaths::get_cache_dir()
+            .ok()
+            .map(|p| p.join("discovery.json"))
+    }
+
+    fn get_sync_times_path() -> Option<PathBuf> {
+        AppPaths::get_cache_dir()
+            .ok()
+            .map(|p| p.join("synced.json"))
+    }
+
     fn get_path(key: &str) -> Option<PathBuf> {
+        Self::get_hashed_path("tasks", key)
+    }
+
+    /// Resources pruned from the mirror of `key`, see `apply_pruning`.
+    fn get_pruned_path(key: &str) -> Option<PathBuf> {
+        Self::get_hashed_path("pruned", key)
+    }
+
+    fn get_hashed_path(prefix: &str, key: &str) -> Option<PathBuf> {
         AppPaths::get_cache_dir().ok().map(|dir| {
             let mut hasher = DefaultHasher::new();
-            key.hash(&mut hasher);
-            let filename = format!("tasks_{:x}.json", hasher.finish());
+            href::normalize(key).hash(&mut hasher);
+            let filename = format!("{}_{:x}.json", prefix, hasher.finish());
             dir.join(filename)
         })
     }
@@ -67,6 +208,159 @@ impl Cache {
         Ok((vec![], None))
     }
 
+    /// Deletes the mirror of a calendar, e.g. once it's saved under a new href.
+    pub fn remove(key: &str) -> Result<()> {
+        for path in [Self::get_path(key), Self::get_pruned_path(key)]
+            .into_iter()
+            .flatten()
+        {
+            if path.exists() {
+                LocalStorage::with_lock(&path, || Ok(fs::remove_file(&path)?))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Resources left out of the mirror of `key` by pruning, href -> ETag.
+    pub fn load_pruned(key: &str) -> HashMap<String, String> {
+        let Some(path) = Self::get_pruned_path(key).filter(|p| p.exists()) else {
+            return HashMap::new();
+        };
+        LocalStorage::with_lock(&path, || {
+            let json = fs::read_to_string(&path)?;
+            Ok(serde_json::from_str(&json).unwrap_or_default())
+        })
+        .unwrap_or_default()
+    }
+
+    pub fn save_pruned(key: &str, pruned: &HashMap<String, String>) -> Result<()> {
+        if let Some(path) = Self::get_pruned_path(key) {
+            LocalStorage::with_lock(&path, || {
+                if pruned.is_empty() {
+                    if path.exists() {
+                        fs::remove_file(&path)?;
+                    }
+                    return Ok(());
+                }
+                LocalStorage::atomic_write(&path, serde_json::to_string(pruned)?)?;
+                Ok(())
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Deletes the mirrors (and their lock files) of calendars not in `keys`; returns how many
+    /// files went.
+    pub fn remove_orphans(keys: &[String]) -> Result<usize> {
+        let Some(dir) = AppPaths::get_cache_dir().ok().filter(|d| d.exists()) else {
+            return Ok(0);
+        };
+        let kept: HashSet<PathBuf> = keys
+            .iter()
+            .flat_map(|k| [Self::get_path(k), Self::get_pruned_path(k)])
+            .flatten()
+            .collect();
+        let mut removed = 0;
+        for entry in fs::read_dir(&dir)?.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let base = name.trim_end_matches(".lock");
+            let mirror = (base.starts_with("tasks_") || base.starts_with("pruned_"))
+                && base.ends_with(".json");
+            if mirror && !kept.contains(&dir.join(base)) {
+                fs::remove_file(entry.path())?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    // --- INVALIDATION ---
+    // For a mirror gone wrong. Only the server's copies are dropped: local tasks and the
+    // offline queue are kept, and the next sync downloads what was forgotten.
+
+    /// Forgets one resource of the mirror of `key`, and the sync token so that the next sync
+    /// lists the calendar. Returns whether it was mirrored.
+    pub fn invalidate_resource(key: &str, resource: &str) -> Result<bool> {
+        let (tasks, _) = Self::load(key)?;
+        let before = tasks.len();
+        let kept: Vec<Task> = tasks
+            .into_iter()
+            .filter(|t| !href::same(&t.href, resource))
+            .collect();
+        let dropped = kept.len() != before;
+        Self::save(key, &kept, None)?;
+
+        let mut pruned = Self::load_pruned(key);
+        let pruned_before = pruned.len();
+        pruned.retain(|h, _| !href::same(h, resource));
+        if pruned.len() != pruned_before {
+            Self::save_pruned(key, &pruned)?;
+        }
+        Ok(dropped || pruned.len() != pruned_before)
+    }
+
+    /// Forgets the mirror of `key`: the next sync downloads the whole calendar.
+    pub fn invalidate(key: &str) -> Result<()> {
+        Self::remove(key)?;
+        Self::forget_synced(Some(key))
+    }
+
+    /// Forgets every mirror and the discovery; the list of calendars is kept for offline use.
+    /// Returns how many files went.
+    pub fn invalidate_all() -> Result<usize> {
+        let removed = Self::remove_orphans(&[])?;
+        Self::forget_synced(None)?;
+        Self::forget_discovery()?;
+        Ok(removed)
+    }
+
+    /// Drops the sync time of `key`, or all of them.
+    fn forget_synced(key: Option<&str>) -> Result<()> {
+        let Some(path) = Self::get_sync_times_path().filter(|p| p.exists()) else {
+            return Ok(());
+        };
+        LocalStorage::with_lock(&path, || {
+            let mut times: HashMap<String, DateTime<Utc>> = fs::read_to_string(&path)
+                .ok()
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default();
+            match key {
+                Some(key) => times.retain(|k, _| k != key),
+                None => times.clear(),
+            }
+            LocalStorage::atomic_write(&path, serde_json::to_string_pretty(&times)?)?;
+            Ok(())
+        })
+    }
+
+    /// Records that the calendar `key` was just fetched from the server (or found unchanged).
+    pub fn mark_synced(key: &str) -> Result<()> {
+        if let Some(path) = Self::get_sync_times_path() {
+            LocalStorage::with_lock(&path, || {
+                let mut times: HashMap<String, DateTime<Utc>> = fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default();
+                times.insert(key.to_string(), Utc::now());
+                LocalStorage::atomic_write(&path, serde_json::to_string_pretty(&times)?)?;
+                Ok(())
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Last successful sync of each calendar, by href.
+    pub fn sync_times() -> HashMap<String, DateTime<Utc>> {
+        let Some(path) = Self::get_sync_times_path().filter(|p| p.exists()) else {
+            return HashMap::new();
+        };
+        LocalStorage::with_lock(&path, || {
+            let json = fs::read_to_string(&path)?;
+            Ok(serde_json::from_str(&json).unwrap_or_default())
+        })
+        .unwrap_or_default()
+    }
+
     pub fn save_calendars(cals: &[CalendarListEntry]) -> Result<()> {
         if let Some(path) = Self::get_calendars_path() {
             LocalStorage::with_lock(&path, || {
@@ -90,4 +384,84 @@ impl Cache {
         }
         Ok(vec![])
     }
+
+    pub fn save_discovery(discovery: &Discovery) -> Result<()> {
+        if let Some(path) = Self::get_discovery_path() {
+            LocalStorage::with_lock(&path, || {
+                let json = serde_json::to_string_pretty(discovery)?;
+                LocalStorage::atomic_write(&path, json)?;
+                Ok(())
+            })?;
+        }
+        Ok(())
+    }
+
+    /// The last discovery, however old; unreadable ones count as none.
+    pub fn load_discovery() -> Option<Discovery> {
+        let path = Self::get_discovery_path().filter(|p| p.exists())?;
+        LocalStorage::with_lock(&path, || {
+            let json = fs::read_to_string(&path)?;
+            Ok(serde_json::from_str(&json).ok())
+        })
+        .ok()
+        .flatten()
+    }
+
+    /// Makes the next listing of calendars look everything up again.
+    pub fn forget_discovery() -> Result<()> {
+        if let Some(path) = Self::get_discovery_path().filter(|p| p.exists()) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::TaskStatus;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_staleness() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        assert!(is_stale(None, now));
+        assert!(!is_stale(Some(now - Duration::minutes(4)), now));
+        assert!(is_stale(Some(now - Duration::minutes(6)), now));
+        assert_eq!(format_ago(now, now), "just now");
+        assert_eq!(format_ago(now - Duration::minutes(12), now), "12 min ago");
+        assert_eq!(format_ago(now - Duration::hours(3), now), "3 h ago");
+        assert_eq!(format_ago(now - Duration::days(2), now), "2 d ago");
+    }
+
+    #[test]
+    fn test_prune_completed() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let task = |uid: &str, done_days_ago: Option<i64>, parent: Option<&str>| {
+            let mut t = Task::new(uid, &HashMap::new());
+            t.uid = uid.to_string();
+            t.parent_uid = parent.map(str::to_string);
+            if let Some(days) = done_days_ago {
+                t.status = TaskStatus::Completed;
+                t.completed = Some(now - Duration::days(days));
+            }
+            t
+        };
+        let tasks = vec![
+            task("open", None, None),
+            task("recent", Some(3), None),
+            task("old", Some(100), None),
+            task("old_parent", Some(100), None),
+            task("old_child", Some(100), Some("old_parent")),
+            task("open_child", None, Some("old_child")),
+        ];
+        let (kept, pruned) = prune_completed(tasks, now - Duration::days(30));
+        let uids: Vec<_> = kept.iter().map(|t| t.uid.as_str()).collect();
+        assert_eq!(
+            uids,
+            vec!["open", "recent", "old_parent", "old_child", "open_child"]
+        );
+        assert_eq!(pruned.len(), 1);
+        assert_eq!(pruned[0].uid, "old");
+    }
 }
@@ -0,0 +1,79 @@
+// File: ./src/client/capabilities.rs
+// What the server says it supports (OPTIONS response), used to skip requests it would reject
+
+/// Parsed `DAV` and `Allow` headers of the server root.
+/// An unprobed value (server didn't answer OPTIONS) allows everything, so that
+/// features are still attempted as before.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerCapabilities {
+    pub probed: bool,
+    /// Compliance classes from the DAV header, lowercased (e.g. "calendar-access")
+    pub dav_classes: Vec<String>,
+    /// Methods from the Allow header, uppercased
+    pub methods: Vec<String>,
+}
+
+impl ServerCapabilities {
+    pub fn from_headers(dav: &str, allow: &str) -> Self {
+        let split = |s: &str| -> Vec<String> {
+            s.split(',')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        Self {
+            probed: true,
+            dav_classes: split(dav).into_iter().map(|c| c.to_lowercase()).collect(),
+            methods: split(allow).into_iter().map(|m| m.to_uppercase()).collect(),
+        }
+    }
+
+    fn has_class(&self, class: &str) -> bool {
+        !self.probed || self.dav_classes.iter().any(|c| c == class)
+    }
+
+    /// Servers that send no Allow header at all are given the benefit of the doubt.
+    pub fn allows(&self, method: &str) -> bool {
+        !self.probed || self.methods.is_empty() || self.methods.iter().any(|m| m == method)
+    }
+
+    /// RFC 6578 sync tokens (fallback when the server has no CTag)
+    pub fn sync_tokens(&self) -> bool {
+        self.has_class("sync-collection")
+    }
+
+    pub fn calendar_creation(&self) -> bool {
+        self.allows("MKCALENDAR")
+    }
+
+    /// RFC 6638 scheduling (invitations)
+    pub fn scheduling(&self) -> bool {
+        self.has_class("calendar-auto-schedule") || self.has_class("calendar-schedule")
+    }
+
+    /// WebDAV MOVE between collections; without it moves are done as create + delete.
+    pub fn moves(&self) -> bool {
+        self.allows("MOVE")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_radicale_like_headers() {
+        let caps = ServerCapabilities::from_headers(
+            "1, 2, 3, calendar-access, addressbook, extended-mkcol",
+            "DELETE, HEAD, GET, MKCALENDAR, MKCOL, MOVE, OPTIONS, PROPFIND, PROPPATCH, PUT, REPORT",
+        );
+        assert!(caps.calendar_creation());
+        assert!(caps.moves());
+        assert!(!caps.sync_tokens());
+        assert!(!caps.scheduling());
+
+        let unknown = ServerCapabilities::default();
+        assert!(unknown.sync_tokens() && unknown.scheduling() && unknown.moves());
+    }
+}
@@ -1,11 +1,24 @@
 // File: src/client/core.rs
 
-use crate::cache::Cache;
+use crate::backup;
+use crate::cache::{self, Cache, Discovery};
+use crate::client::capabilities::ServerCapabilities;
 use crate::client::cert::NoVerifier;
+#[cfg(feature = "faults")]
+use crate::client::faults::Faulty;
+use crate::client::quota::{self, StorageLimits};
+use crate::client::throttle::{self, Throttled};
+use crate::client::user_agent;
+use crate::client::verify::{self, VerifyReport};
+use crate::client::wirelog::Logged;
 use crate::config::Config;
+use crate::demo;
+use crate::i18n::tr_args;
 use crate::journal::{Action, Journal};
-use crate::model::{CalendarListEntry, Task, TaskStatus};
+use crate::model::validate::check_before_save;
+use crate::model::{CalendarListEntry, Task, TaskStatus, adapter, href};
 use crate::storage::{LOCAL_CALENDAR_HREF, LocalStorage};
+use crate::store::BulkAction;
 
 // Libdav imports
 use libdav::caldav::{FindCalendarHomeSet, FindCalendars, GetCalendarResources};
@@ -13,14 +26,20 @@ use libdav::dav::{Delete, GetProperty, ListResources, PutResource};
 use libdav::dav::{WebDavClient, WebDavError};
 use libdav::{CalDavClient, PropertyName, names};
 
+use chrono::Utc;
 use futures::stream::{self, StreamExt};
-use http::{Request, StatusCode, Uri};
+use http::header::USER_AGENT;
+use http::{HeaderValue, Request, StatusCode, Uri};
 use hyper_rustls::HttpsConnectorBuilder;
 use hyper_util::client::legacy::Client;
 use hyper_util::rt::TokioExecutor;
-use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
 use tower_http::auth::AddAuthorization;
+use tower_http::set_header::SetRequestHeader;
 use uuid::Uuid;
 
 #[cfg(not(target_os = "android"))]
@@ -32,69 +51,270 @@ use rustls_platform_verifier::BuilderVerifierExt;
 pub const GET_CTAG: PropertyName = PropertyName::new("http://calendarserver.org/ns/", "getctag");
 pub const APPLE_COLOR: PropertyName =
     PropertyName::new("http://apple.com/ns/ical/", "calendar-color");
+pub const OWNER_PRINCIPAL: PropertyName =
+    PropertyName::new("http://owncloud.org/ns", "owner-principal");
+pub const QUOTA_USED: PropertyName = PropertyName::new("DAV:", "quota-used-bytes");
+pub const QUOTA_AVAILABLE: PropertyName = PropertyName::new("DAV:", "quota-available-bytes");
+pub const MAX_RESOURCE_SIZE: PropertyName =
+    PropertyName::new("urn:ietf:params:xml:ns:caldav", "max-resource-size");
+
+/// Prefix of the ETags made up from the content, for servers without usable ones.
+pub const CONTENT_HASH_PREFIX: &str = "cfait-hash:";
+
+/// A request a sync would send, as reported by the dry runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedRequest {
+    pub method: &'static str,
+    pub href: String,
+    /// What it does, e.g. "create 'Buy milk'"
+    pub change: String,
+}
 
-type HttpsClient = AddAuthorization<
-    Client<
-        hyper_rustls::HttpsConnector<hyper_util::client::legacy::connect::HttpConnector>,
-        String,
-    >,
+impl fmt::Display for PlannedRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:<6} {}  {}", self.method, self.href, self.change)
+    }
+}
+
+/// The tasks of every calendar. Calendars the server failed to give are shown from their
+/// mirror and listed in `failures`, as (href, error).
+#[derive(Debug, Clone, Default)]
+pub struct FetchReport {
+    pub tasks: Vec<(String, Vec<Task>)>,
+    pub failures: Vec<(String, String)>,
+    /// How long each calendar took, waiting for a free request slot included
+    pub timings: Vec<(String, Duration)>,
+}
+
+impl FetchReport {
+    /// One line per failed calendar, e.g. "Work: 500 Internal Server Error".
+    pub fn failure_lines(&self, calendars: &[CalendarListEntry]) -> Vec<String> {
+        self.failures
+            .iter()
+            .map(|(href, error)| {
+                let name = calendars
+                    .iter()
+                    .find(|c| &c.href == href)
+                    .map_or(href.as_str(), |c| c.name.as_str());
+                format!("{}: {}", name, error)
+            })
+            .collect()
+    }
+}
+
+type RawClient = Client<
+    hyper_rustls::HttpsConnector<hyper_util::client::legacy::connect::HttpConnector>,
+    String,
 >;
+#[cfg(not(feature = "faults"))]
+type Transport = RawClient;
+#[cfg(feature = "faults")]
+type Transport = Faulty<RawClient>;
 
+type HttpsClient = AddAuthorization<SetRequestHeader<Throttled<Logged<Transport>>, HeaderValue>>;
+
+/// The path of `href`, normalized: unencoded spaces or accents would not parse as a URI.
 fn strip_host(href: &str) -> String {
-    if let Ok(uri) = href.parse::<Uri>()
-        && (uri.scheme().is_some() || uri.authority().is_some())
+    href::to_path(href, "")
+}
+
+type TaskSignature = (String, String, Option<chrono::DateTime<chrono::Utc>>);
+
+/// Enough to tell whether a calendar changed: every save updates the ETag or LAST-MODIFIED.
+fn signature(tasks: &[Task]) -> Vec<TaskSignature> {
+    let mut sig: Vec<_> = tasks
+        .iter()
+        .map(|t| (t.uid.clone(), t.etag.clone(), t.last_modified))
+        .collect();
+    sig.sort();
+    sig
+}
+
+/// FNV-1a: stable across runs and Rust versions, unlike `DefaultHasher`.
+fn content_hash(data: &str) -> String {
+    let hash = data.bytes().fold(0xcbf29ce484222325u64, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{}{:016x}", CONTENT_HASH_PREFIX, hash)
+}
+
+/// Writes for such tasks can't use If-Match and go through `send_unconditional`.
+fn lacks_etag(task: &Task) -> bool {
+    task.etag.is_empty() || task.etag.starts_with(CONTENT_HASH_PREFIX)
+}
+
+/// `task` with the other VTODOs of its resource as the local replica has them now, so that a
+/// sibling saved or removed since `task` was read isn't written back as it was.
+fn with_current_siblings(task: &Task) -> Task {
+    let mut task = task.clone();
+    if !task.has_sibling_todos() {
+        return task;
+    }
+    let replica = RustyClient::local_tasks(&task.calendar_href);
+    // Without the resource in the replica, a removed sibling can't be told from an unknown one
+    if !replica.iter().any(|t| t.uid == task.uid) {
+        return task;
+    }
+    for uid in task.sibling_uids() {
+        let current = replica
+            .iter()
+            .find(|t| t.uid == uid && href::same(&t.href, &task.href));
+        task.refresh_sibling(&uid, current);
+    }
+    task
+}
+
+/// After a write to a resource shared by several VTODOs: `written` is cached as sent (or
+/// dropped if `removed`), and the cached copies of the others take the new ETag and the new
+/// version of `written`, which their next save carries along.
+fn record_shared_write(written: &Task, removed: bool, etag: Option<&str>) {
+    let Ok((mut tasks, token)) = Cache::load(&written.calendar_href) else {
+        return;
+    };
+    for task in tasks
+        .iter_mut()
+        .filter(|t| href::same(&t.href, &written.href))
+    {
+        if task.uid == written.uid {
+            *task = written.clone();
+        } else {
+            task.refresh_sibling(&written.uid, (!removed).then_some(written));
+        }
+        if let Some(etag) = etag {
+            task.etag = etag.to_string();
+        }
+    }
+    if removed {
+        tasks.retain(|t| t.uid != written.uid);
+    }
+    let _ = Cache::save(&written.calendar_href, &tasks, token);
+}
+
+/// Strips the weak marker and quotes, which some servers add inconsistently.
+pub(crate) fn normalize_etag(etag: &str) -> &str {
+    etag.trim_start_matches("W/").trim_matches('"')
+}
+
+/// Compares an owner reference (absolute URL, path or Nextcloud-style
+/// "principals/users/x") against the current user's principal path, whole segments at a
+/// time so "users/bob" doesn't match "users/alice-bob".
+fn is_same_principal(owner: &str, principal_path: &str) -> bool {
+    let owner = strip_host(owner);
+    let segments = |path: &str| -> Vec<String> {
+        path.split('/')
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    let (owner, principal) = (segments(&owner), segments(principal_path));
+    owner.is_empty() || principal.ends_with(&owner) || owner.ends_with(&principal)
+}
+
+/// Whether a failed fetch hints that the saved discovery is out of date: the login was
+/// refused (401, 403) or the calendar is gone (404).
+fn discovery_outdated(error: &str) -> bool {
+    [
+        StatusCode::UNAUTHORIZED,
+        StatusCode::FORBIDDEN,
+        StatusCode::NOT_FOUND,
+    ]
+    .iter()
+    .any(|status| {
+        error.contains(&format!("({})", status.as_u16())) || error.contains(&status.to_string())
+    })
+}
+
+/// Scans a PROPFIND response for the privileges granted in current-user-privilege-set.
+/// Returns None when the property wasn't reported at all.
+fn privileges_allow_write(xml: &str) -> Option<bool> {
+    let start = xml.find("current-user-privilege-set")?;
+    let section = &xml[start..];
+    let end = section[1..]
+        .find("current-user-privilege-set")
+        .map(|i| i + 1)
+        .unwrap_or(section.len());
+
+    let mut saw_privilege = false;
+    for tag in section[..end].split('<').skip(1) {
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c == '>' || c == '/' || c.is_whitespace())
+            .next()
+            .unwrap_or("");
+        match name.rsplit(':').next().unwrap_or(name) {
+            "write" | "write-content" | "all" => return Some(true),
+            "privilege" => saw_privilege = true,
+            _ => {}
+        }
+    }
+    if saw_privilege { Some(false) } else { None }
+}
+
+/// TLS settings shared by the CalDAV client and the push listener.
+pub(crate) fn tls_config(insecure: bool) -> Result<rustls::ClientConfig, String> {
+    let tls_config_builder = rustls::ClientConfig::builder();
+
+    if insecure {
+        return Ok(tls_config_builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(NoVerifier))
+            .with_no_client_auth());
+    }
+
+    #[cfg(not(target_os = "android"))]
     {
-        return uri
-            .path_and_query()
-            .map(|pq| pq.as_str().to_string())
-            .unwrap_or_else(|| uri.path().to_string());
+        let mut root_store = rustls::RootCertStore::empty();
+        let result = rustls_native_certs::load_native_certs();
+        root_store.add_parsable_certificates(result.certs);
+        if root_store.is_empty() {
+            return Err("No valid system certificates found.".to_string());
+        }
+        Ok(tls_config_builder
+            .with_root_certificates(root_store)
+            .with_no_client_auth())
+    }
+
+    #[cfg(target_os = "android")]
+    {
+        Ok(tls_config_builder
+            .with_platform_verifier()
+            .map_err(|e| format!("Failed to init platform verifier: {}", e))? // Handle the Result
+            .with_no_client_auth())
     }
-    href.to_string()
 }
 
 #[derive(Clone, Debug)]
 pub struct RustyClient {
     pub client: Option<CalDavClient<HttpsClient>>,
+    /// Probed once per connection and shared by clones
+    capabilities: Arc<OnceCell<ServerCapabilities>>,
+    /// Calendars with missing or unstable ETags -> whether the user was warned
+    etagless: Arc<Mutex<HashMap<String, bool>>>,
+    /// Calendars the user stopped syncing; left out of full fetches
+    disabled: Arc<Mutex<HashSet<String>>>,
+    /// Calendars already reported as nearly full
+    near_full: Arc<Mutex<HashSet<String>>>,
+    /// Server URL and user name, which the saved discovery is for
+    account: String,
 }
 
 impl RustyClient {
     pub fn new(url: &str, user: &str, pass: &str, insecure: bool) -> Result<Self, String> {
         if url.is_empty() {
-            return Ok(Self { client: None });
+            return Ok(Self {
+                client: None,
+                capabilities: Arc::default(),
+                etagless: Arc::default(),
+                disabled: Arc::default(),
+                near_full: Arc::default(),
+                account: String::new(),
+            });
         }
         let uri: Uri = url
             .parse()
             .map_err(|e: http::uri::InvalidUri| e.to_string())?;
 
-        let tls_config_builder = rustls::ClientConfig::builder();
-
-        let tls_config = if insecure {
-            tls_config_builder
-                .dangerous()
-                .with_custom_certificate_verifier(Arc::new(NoVerifier))
-                .with_no_client_auth()
-        } else {
-            #[cfg(not(target_os = "android"))]
-            {
-                let mut root_store = rustls::RootCertStore::empty();
-                let result = rustls_native_certs::load_native_certs();
-                root_store.add_parsable_certificates(result.certs);
-                if root_store.is_empty() {
-                    return Err("No valid system certificates found.".to_string());
-                }
-                tls_config_builder
-                    .with_root_certificates(root_store)
-                    .with_no_client_auth()
-            }
-
-            #[cfg(target_os = "android")]
-            {
-                tls_config_builder
-                    .with_platform_verifier()
-                    .map_err(|e| format!("Failed to init platform verifier: {}", e))? // Handle the Result
-                    .with_no_client_auth()
-            }
-        };
+        let tls_config = tls_config(insecure)?;
 
         let https_connector = HttpsConnectorBuilder::new()
             .with_tls_config(tls_config)
@@ -102,15 +322,82 @@ impl RustyClient {
             .enable_http1()
             .build();
 
-        let http_client = Client::builder(TokioExecutor::new()).build(https_connector);
+        let transport = Client::builder(TokioExecutor::new()).build(https_connector);
+        #[cfg(feature = "faults")]
+        let transport = Faulty::new(transport);
+        let http_client = SetRequestHeader::overriding(
+            Throttled::new(Logged::new(transport)),
+            USER_AGENT,
+            user_agent::header_value(),
+        );
         let auth_client = AddAuthorization::basic(http_client.clone(), user, pass);
         let webdav = WebDavClient::new(uri, auth_client.clone());
         let caldav = CalDavClient::new(webdav);
         Ok(Self {
             client: Some(caldav),
+            capabilities: Arc::default(),
+            etagless: Arc::default(),
+            disabled: Arc::default(),
+            near_full: Arc::default(),
+            account: format!("{} {}", url, user),
         })
     }
 
+    /// Replaces the set of calendars skipped by `get_all_tasks` (shared by clones).
+    /// Returns the calendars that were just re-enabled, so callers can fetch them.
+    pub fn set_disabled_calendars(&self, hrefs: impl IntoIterator<Item = String>) -> Vec<String> {
+        let new: HashSet<String> = hrefs.into_iter().collect();
+        let Ok(mut disabled) = self.disabled.lock() else {
+            return Vec::new();
+        };
+        let enabled = disabled.difference(&new).cloned().collect();
+        *disabled = new;
+        enabled
+    }
+
+    pub fn is_sync_disabled(&self, href: &str) -> bool {
+        self.disabled.lock().is_ok_and(|set| set.contains(href))
+    }
+
+    /// What the server advertises in its OPTIONS response. Probed on first use;
+    /// if the probe fails everything is assumed supported.
+    pub async fn capabilities(&self) -> ServerCapabilities {
+        self.capabilities
+            .get_or_init(|| async { self.probe_capabilities().await.unwrap_or_default() })
+            .await
+            .clone()
+    }
+
+    async fn probe_capabilities(&self) -> Option<ServerCapabilities> {
+        let client = self.client.as_ref()?;
+        let uri = client
+            .webdav_client
+            .relative_uri(client.base_url().path())
+            .ok()?;
+        let req = Request::builder()
+            .method("OPTIONS")
+            .uri(uri)
+            .body(String::new())
+            .ok()?;
+        let (parts, _) = client.webdav_client.request_raw(req).await.ok()?;
+        if !parts.status.is_success() {
+            return None;
+        }
+        let header = |name: &str| {
+            parts
+                .headers
+                .get_all(name)
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        Some(ServerCapabilities::from_headers(
+            &header("DAV"),
+            &header("Allow"),
+        ))
+    }
+
     // --- DISCOVERY & CONNECTION ---
 
     pub async fn discover_calendar(&self) -> Result<String, String> {
@@ -135,6 +422,45 @@ impl RustyClient {
         }
     }
 
+    /// The client for the configured server, without reaching it yet: the UIs show the cache
+    /// with it at once, then call `calendars_or_cached` and sync in the background.
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let client = Self::new(
+            &config.url,
+            &config.username,
+            &config.password,
+            config.allow_insecure_certs,
+        )?;
+        client.set_disabled_calendars(config.disabled_calendars.iter().cloned());
+        Ok(client)
+    }
+
+    /// The calendars listed by the server (and cached), or the cached ones with an "Offline
+    /// Mode" warning when it can't be reached. Only a certificate refused is an error: the
+    /// cache won't get fresher until the settings change. A discovery younger than
+    /// `DISCOVERY_TTL` is used without asking; a refresh or a refused login redoes it.
+    pub async fn calendars_or_cached(
+        &self,
+    ) -> Result<(Vec<CalendarListEntry>, Option<String>), String> {
+        if self.client.is_some()
+            && let Some(found) = Cache::load_discovery()
+            && found.is_fresh(&self.account, Utc::now())
+        {
+            return Ok((found.calendars, None));
+        }
+        match self.get_calendars().await {
+            Ok(c) => {
+                let _ = Cache::save_calendars(&c);
+                Ok((c, None))
+            }
+            Err(e) if e.contains("InvalidCertificate") => Err(format!("Connection failed: {}", e)),
+            Err(_) => Ok((
+                Cache::load_calendars().unwrap_or_default(),
+                Some("Offline Mode".to_string()),
+            )),
+        }
+    }
+
     pub async fn connect_with_fallback(
         config: Config,
     ) -> Result<
@@ -147,31 +473,9 @@ impl RustyClient {
         ),
         String,
     > {
-        let client = Self::new(
-            &config.url,
-            &config.username,
-            &config.password,
-            config.allow_insecure_certs,
-        )
-        .map_err(|e| e.to_string())?;
-
+        let client = Self::from_config(&config)?;
         let _ = client.sync_journal().await;
-
-        let (calendars, warning) = match client.get_calendars().await {
-            Ok(c) => {
-                let _ = Cache::save_calendars(&c);
-                (c, None)
-            }
-            Err(e) => {
-                if e.contains("InvalidCertificate") {
-                    return Err(format!("Connection failed: {}", e));
-                }
-                (
-                    Cache::load_calendars().unwrap_or_default(),
-                    Some("Offline Mode".to_string()),
-                )
-            }
-        };
+        let (calendars, warning) = client.calendars_or_cached().await?;
 
         let mut active_href = None;
         if let Some(def_cal) = &config.default_calendar
@@ -204,6 +508,7 @@ impl RustyClient {
         Ok((client, calendars, tasks, active_href, warning))
     }
 
+    /// Looks the calendars up on the server, and saves what was found as the discovery.
     pub async fn get_calendars(&self) -> Result<Vec<CalendarListEntry>, String> {
         if let Some(client) = &self.client {
             let principal = client
@@ -217,55 +522,307 @@ impl RustyClient {
                 .await
                 .map_err(|e| format!("{:?}", e))?;
 
-            let home_url = home_set_resp.home_sets.first().ok_or("No home set")?;
-
-            let cals_resp = client
-                .request(FindCalendars::new(home_url))
-                .await
-                .map_err(|e| format!("{:?}", e))?;
+            if home_set_resp.home_sets.is_empty() {
+                return Err("No home set".to_string());
+            }
 
+            let principal_path = principal.path().to_string();
             let mut calendars = Vec::new();
-            for col in cals_resp.calendars {
-                let name = client
-                    .request(GetProperty::new(&col.href, &names::DISPLAY_NAME))
-                    .await
-                    .ok()
-                    .and_then(|r| r.value)
-                    .unwrap_or_else(|| col.href.clone());
+            let mut seen_hrefs = HashSet::new();
+
+            // The first home set belongs to the user; any further ones are delegated
+            // (e.g. calendar-proxy on SOGo/Cyrus), so their collections count as shared.
+            for (idx, home_url) in home_set_resp.home_sets.iter().enumerate() {
+                let cals_resp = match client.request(FindCalendars::new(home_url)).await {
+                    Ok(r) => r,
+                    // A delegated home set being unreachable shouldn't hide our own calendars
+                    Err(e) if idx == 0 => return Err(format!("{:?}", e)),
+                    Err(_) => continue,
+                };
+
+                for col in cals_resp.calendars {
+                    let href = href::to_path(&col.href, home_url.path());
+                    if !seen_hrefs.insert(href.clone()) {
+                        continue;
+                    }
 
-                // Fetch Color
-                let color = client
-                    .request(GetProperty::new(&col.href, &APPLE_COLOR))
-                    .await
-                    .ok()
-                    .and_then(|r| r.value);
+                    let name = client
+                        .request(GetProperty::new(&href, &names::DISPLAY_NAME))
+                        .await
+                        .ok()
+                        .and_then(|r| r.value)
+                        .unwrap_or_else(|| href.clone());
 
-                calendars.push(CalendarListEntry {
-                    name,
-                    href: col.href,
-                    color, // Store it
-                });
+                    // Fetch Color
+                    let color = client
+                        .request(GetProperty::new(&href, &APPLE_COLOR))
+                        .await
+                        .ok()
+                        .and_then(|r| r.value);
+
+                    // Shares (Nextcloud/ownCloud) live inside our own home set, so ask for the owner
+                    let owner = client
+                        .request(GetProperty::new(&href, &OWNER_PRINCIPAL))
+                        .await
+                        .ok()
+                        .and_then(|r| r.value);
+                    let is_shared =
+                        idx > 0 || owner.is_some_and(|o| !is_same_principal(&o, &principal_path));
+
+                    // Only shared collections can realistically be read-only; skip the extra
+                    // PROPFIND for our own calendars.
+                    let is_read_only = is_shared && self.fetch_is_read_only(&href).await;
+
+                    calendars.push(CalendarListEntry {
+                        name,
+                        href,
+                        color, // Store it
+                        is_shared,
+                        is_read_only,
+                    });
+                }
             }
+            let _ = Cache::save_discovery(&Discovery {
+                account: self.account.clone(),
+                principal: principal.to_string(),
+                home_sets: home_set_resp
+                    .home_sets
+                    .iter()
+                    .map(|u| u.to_string())
+                    .collect(),
+                calendars: calendars.clone(),
+                discovered_at: Utc::now(),
+            });
             Ok(calendars)
+        } else if demo::is_active() {
+            Ok(demo::calendars())
         } else {
             Ok(vec![])
         }
     }
 
+    /// Asks the server for the current-user-privilege-set (RFC 3744) of a collection.
+    /// Servers that don't report it are assumed writable.
+    async fn fetch_is_read_only(&self, href: &str) -> bool {
+        let Some(client) = &self.client else {
+            return false;
+        };
+        let Ok(uri) = client.webdav_client.relative_uri(&strip_host(href)) else {
+            return false;
+        };
+        let body = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:current-user-privilege-set/></d:prop></d:propfind>"#;
+        let Ok(req) = Request::builder()
+            .method("PROPFIND")
+            .uri(uri)
+            .header("Depth", "0")
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(body.to_string())
+        else {
+            return false;
+        };
+        match client.webdav_client.request_raw(req).await {
+            Ok((parts, bytes)) if parts.status.is_success() => {
+                privileges_allow_write(&String::from_utf8_lossy(&bytes)) == Some(false)
+            }
+            _ => false,
+        }
+    }
+
+    // --- CACHE INVALIDATION ---
+    // See `Cache::invalidate`; what was learnt about the calendars goes with their mirror.
+
+    /// Forgets one resource of a calendar's mirror; the next sync downloads it again.
+    pub fn invalidate_resource(&self, calendar_href: &str, href: &str) -> Result<bool, String> {
+        Cache::invalidate_resource(calendar_href, href).map_err(|e| e.to_string())
+    }
+
+    /// Forgets a calendar's mirror; the next sync downloads the whole calendar.
+    pub fn invalidate_calendar(&self, calendar_href: &str) -> Result<(), String> {
+        if let Ok(mut map) = self.etagless.lock() {
+            map.remove(calendar_href);
+        }
+        if let Ok(mut set) = self.near_full.lock() {
+            set.remove(calendar_href);
+        }
+        Cache::invalidate(calendar_href).map_err(|e| e.to_string())
+    }
+
+    /// Forgets every mirror; the next sync downloads everything.
+    pub fn invalidate_all(&self) -> Result<usize, String> {
+        if let Ok(mut map) = self.etagless.lock() {
+            map.clear();
+        }
+        if let Ok(mut set) = self.near_full.lock() {
+            set.clear();
+        }
+        Cache::invalidate_all().map_err(|e| e.to_string())
+    }
+
+    // --- STORAGE LIMITS ---
+
+    /// The quota and size limit of a calendar; servers without them report nothing.
+    pub async fn storage_limits(&self, calendar_href: &str) -> Result<StorageLimits, String> {
+        let client = self.client.as_ref().ok_or("Offline")?;
+        let path = strip_host(calendar_href);
+        let mut values = Vec::new();
+        for name in [&QUOTA_USED, &QUOTA_AVAILABLE, &MAX_RESOURCE_SIZE] {
+            let value = client
+                .request(GetProperty::new(&path, name))
+                .await
+                .ok()
+                .and_then(|r| r.value);
+            values.push(value);
+        }
+        Ok(StorageLimits::from_properties(
+            values[0].as_deref(),
+            values[1].as_deref(),
+            values[2].as_deref(),
+        ))
+    }
+
+    /// A warning the first time a calendar written to is found nearly full.
+    async fn near_full_warning(&self, calendar_href: &str) -> Option<String> {
+        if self
+            .near_full
+            .lock()
+            .is_ok_and(|set| set.contains(calendar_href))
+        {
+            return None;
+        }
+        let limits = self.storage_limits(calendar_href).await.ok()?;
+        if !limits.is_near_full() {
+            return None;
+        }
+        if let Ok(mut set) = self.near_full.lock() {
+            set.insert(calendar_href.to_string());
+        }
+        Some(quota::near_full_warning(
+            &calendar_name(calendar_href),
+            &limits,
+        ))
+    }
+
+    // --- SCHEDULING ---
+
+    /// Servers scheduling on their own (RFC 6638) would send invitations, replies or
+    /// cancellations for every write of a task with attendees: they are told not to.
+    async fn without_server_scheduling(
+        &self,
+        mut action: Action,
+        warnings: &mut Vec<String>,
+    ) -> Action {
+        if let Action::Create(task) | Action::Update(task) = &mut action
+            && task.is_scheduling_object()
+        {
+            let caps = self.capabilities().await;
+            if caps.probed && caps.scheduling() && task.disable_server_scheduling() {
+                warnings.push(format!(
+                    "'{}' has an organizer or attendees: the server was asked not to send \
+                     them invitations or replies for it (SCHEDULE-AGENT=CLIENT).",
+                    task.summary
+                ));
+            }
+        }
+        action
+    }
+
+    // --- ETAG-LESS SERVERS ---
+
+    fn is_etagless(&self, calendar_href: &str) -> bool {
+        self.etagless
+            .lock()
+            .is_ok_and(|map| map.contains_key(calendar_href))
+    }
+
+    fn mark_etagless(&self, calendar_href: &str) {
+        if let Ok(mut map) = self.etagless.lock() {
+            map.entry(calendar_href.to_string()).or_insert(false);
+        }
+    }
+
+    /// True only for the first write without ETag check to a calendar, so it's reported once.
+    fn first_unchecked_write(&self, calendar_href: &str) -> bool {
+        let Ok(mut map) = self.etagless.lock() else {
+            return false;
+        };
+        !std::mem::replace(map.entry(calendar_href.to_string()).or_insert(false), true)
+    }
+
+    async fn fetch_content_hash(&self, path: &str) -> Option<String> {
+        let client = self.client.as_ref()?;
+        let uri = client.webdav_client.relative_uri(path).ok()?;
+        let req = Request::builder()
+            .method("GET")
+            .uri(uri)
+            .body(String::new())
+            .ok()?;
+        let (parts, bytes) = client.webdav_client.request_raw(req).await.ok()?;
+        parts
+            .status
+            .is_success()
+            .then(|| content_hash(&String::from_utf8_lossy(&bytes)))
+    }
+
+    /// PUT or DELETE without If-Match. When `expected` is a content hash the resource is
+    /// compared to it first, and a change on the server is reported as 412 like If-Match would.
+    async fn send_unconditional(
+        &self,
+        method: &str,
+        path: &str,
+        body: String,
+        expected: &str,
+    ) -> Result<StatusCode, String> {
+        let client = self.client.as_ref().ok_or("Offline")?;
+        if expected.starts_with(CONTENT_HASH_PREFIX)
+            && let Some(current) = self.fetch_content_hash(path).await
+            && current != expected
+        {
+            return Ok(StatusCode::PRECONDITION_FAILED);
+        }
+        let uri = client
+            .webdav_client
+            .relative_uri(path)
+            .map_err(|e| format!("Invalid URI: {}", e))?;
+        let mut req = Request::builder().method(method).uri(uri);
+        if !body.is_empty() {
+            req = req.header("Content-Type", adapter::content_type(&body));
+        }
+        let req = req.body(body).map_err(|e| e.to_string())?;
+        let (parts, _) = client
+            .webdav_client
+            .request_raw(req)
+            .await
+            .map_err(|e| format!("{:?}", e))?;
+        Ok(parts.status)
+    }
+
     // --- TASK FETCHING ---
 
     async fn fetch_calendar_tasks_internal(
         &self,
         calendar_href: &str,
     ) -> Result<Vec<Task>, String> {
+        let days = cache::prune_days();
         if calendar_href == LOCAL_CALENDAR_HREF {
+            if let Some(days) = days {
+                let _ = LocalStorage::prune(days);
+            }
             return LocalStorage::load().map_err(|e| e.to_string());
         }
 
         let (cached_tasks, cached_token) = Cache::load(calendar_href).unwrap_or((vec![], None));
+        // Turned off: the pruned resources are downloaded again
+        let old_pruned = if days.is_some() {
+            Cache::load_pruned(calendar_href)
+        } else {
+            HashMap::new()
+        };
+        let mut pruned = old_pruned.clone();
 
         if let Some(client) = &self.client {
             let path_href = strip_host(calendar_href);
+            let caps = self.capabilities().await;
 
             let remote_token = if let Ok(resp) = client
                 .request(GetProperty::new(&path_href, &GET_CTAG))
@@ -273,9 +830,10 @@ impl RustyClient {
                 && let Some(val) = resp.value
             {
                 Some(val)
-            } else if let Ok(resp) = client
-                .request(GetProperty::new(&path_href, &names::SYNC_TOKEN))
-                .await
+            } else if caps.sync_tokens()
+                && let Ok(resp) = client
+                    .request(GetProperty::new(&path_href, &names::SYNC_TOKEN))
+                    .await
                 && let Some(val) = resp.value
             {
                 Some(val)
@@ -287,7 +845,13 @@ impl RustyClient {
                 && let Some(c_tok) = &cached_token
                 && r_tok == c_tok
             {
-                return Ok(cached_tasks);
+                let kept = cache::apply_pruning(cached_tasks, days, &mut pruned);
+                if pruned != old_pruned {
+                    let _ = Cache::save(calendar_href, &kept, remote_token.clone());
+                    let _ = Cache::save_pruned(calendar_href, &pruned);
+                }
+                let _ = Cache::mark_synced(calendar_href);
+                return Ok(kept);
             }
 
             let list_resp = client
@@ -295,39 +859,64 @@ impl RustyClient {
                 .await
                 .map_err(|e| format!("PROPFIND: {:?}", e))?;
 
-            let mut cache_map: HashMap<String, Task> = HashMap::new();
+            // A resource may hold several VTODOs, so several cached tasks can share an href
+            let mut cache_map: HashMap<String, Vec<Task>> = HashMap::new();
             for t in cached_tasks {
-                cache_map.insert(t.href.clone(), t);
+                cache_map
+                    .entry(href::to_path(&t.href, &path_href))
+                    .or_default()
+                    .push(t);
             }
 
             let mut final_tasks = Vec::new();
             let mut to_fetch = Vec::new();
             let mut server_hrefs = HashSet::new();
+            let mut listed_etags = HashMap::new();
+            let mut etagless = self.is_etagless(calendar_href);
 
             for resource in list_resp.resources {
                 if !resource.href.ends_with(".ics") {
                     continue;
                 }
-                server_hrefs.insert(resource.href.clone());
+                let href = href::to_path(&resource.href, &path_href);
+                server_hrefs.insert(href.clone());
                 let remote_etag = resource.etag;
+                match &remote_etag {
+                    Some(etag) if !etag.is_empty() => {
+                        listed_etags.insert(href.clone(), etag.clone());
+                    }
+                    _ => etagless = true,
+                }
 
-                if let Some(local_task) = cache_map.remove(&resource.href) {
+                // Pruned and unchanged since: not downloaded again
+                if !cache_map.contains_key(&href)
+                    && let Some(etag) = &remote_etag
+                    && old_pruned.get(&href) == Some(etag)
+                {
+                    continue;
+                }
+
+                if let Some(local_tasks) = cache_map.remove(&href) {
                     if let Some(r_etag) = &remote_etag
                         && !r_etag.is_empty()
-                        && *r_etag == local_task.etag
+                        && local_tasks.iter().all(|t| t.etag == *r_etag)
                     {
-                        final_tasks.push(local_task);
+                        final_tasks.extend(local_tasks);
                     } else {
-                        to_fetch.push(strip_host(&resource.href));
+                        to_fetch.push(href);
                     }
                 } else {
-                    to_fetch.push(strip_host(&resource.href));
+                    to_fetch.push(href);
                 }
             }
 
-            for (href, task) in cache_map {
-                if !server_hrefs.contains(&href) && (task.etag.is_empty() || task.href.is_empty()) {
-                    final_tasks.push(task);
+            for (href, tasks) in cache_map {
+                for task in tasks {
+                    if !server_hrefs.contains(&href)
+                        && (task.etag.is_empty() || task.href.is_empty())
+                    {
+                        final_tasks.push(task);
+                    }
                 }
             }
 
@@ -337,69 +926,230 @@ impl RustyClient {
                     .await
                     .map_err(|e| format!("MULTIGET: {:?}", e))?;
 
-                for item in fetched_resp.resources {
-                    if let Ok(content) = item.content
-                        && let Ok(task) = Task::from_ics(
-                            &content.data,
-                            content.etag,
-                            item.href,
-                            calendar_href.to_string(),
-                        )
+                let fetched: Vec<_> = fetched_resp
+                    .resources
+                    .into_iter()
+                    .filter_map(|item| {
+                        let href = href::to_path(&item.href, &path_href);
+                        item.content.ok().map(|content| (href, content))
+                    })
+                    .collect();
+
+                // An ETag that already changed between two requests can't be used for If-Match
+                etagless |= fetched.iter().any(|(href, content)| {
+                    listed_etags.get(href).is_some_and(|listed| {
+                        normalize_etag(listed) != normalize_etag(&content.etag)
+                    })
+                });
+
+                for (href, content) in fetched {
+                    let etag = if etagless || content.etag.is_empty() {
+                        content_hash(&content.data)
+                    } else {
+                        content.etag
+                    };
+                    if let Ok(tasks) =
+                        Task::from_ics_all(&content.data, etag, href, calendar_href.to_string())
                     {
-                        final_tasks.push(task);
+                        final_tasks.extend(tasks);
                     }
                 }
             }
 
+            if etagless {
+                self.mark_etagless(calendar_href);
+            }
+
+            // Resources changed or gone since they were pruned are forgotten
+            pruned.retain(|href, etag| listed_etags.get(href) == Some(etag));
+            let final_tasks = cache::apply_pruning(final_tasks, days, &mut pruned);
+            if pruned != old_pruned {
+                let _ = Cache::save_pruned(calendar_href, &pruned);
+            }
             let _ = Cache::save(calendar_href, &final_tasks, remote_token);
+            let _ = Cache::mark_synced(calendar_href);
             Ok(final_tasks)
         } else {
             Err("Offline".to_string())
         }
     }
 
+    /// Compares the mirror of a calendar with the server, downloading at most `sample` of the
+    /// resources whose ETag matches; `repair` rewrites the mirror with the server's copies.
+    pub async fn verify_calendar(
+        &self,
+        calendar_href: &str,
+        sample: Option<usize>,
+        repair: bool,
+    ) -> Result<VerifyReport, String> {
+        let client = self.client.as_ref().ok_or("Offline")?;
+        let path_href = strip_host(calendar_href);
+        let (cached, _) = Cache::load(calendar_href).map_err(|e| e.to_string())?;
+
+        let list_resp = client
+            .request(ListResources::new(&path_href))
+            .await
+            .map_err(|e| format!("PROPFIND: {:?}", e))?;
+        let listed: BTreeMap<String, String> = list_resp
+            .resources
+            .into_iter()
+            .filter(|r| r.href.ends_with(".ics"))
+            .map(|r| {
+                let etag = r.etag.unwrap_or_default();
+                (href::to_path(&r.href, &path_href), etag)
+            })
+            .collect();
+
+        let hrefs = verify::to_download(&cached, &listed, sample);
+        let mut fetched = HashMap::new();
+        if !hrefs.is_empty() {
+            let hrefs: Vec<String> = hrefs.iter().map(|h| strip_host(h)).collect();
+            let resp = client
+                .request(GetCalendarResources::new(&path_href).with_hrefs(hrefs))
+                .await
+                .map_err(|e| format!("MULTIGET: {:?}", e))?;
+            // The same ETags as a sync would store
+            let etagless = self.is_etagless(calendar_href)
+                || cached
+                    .iter()
+                    .any(|t| t.etag.starts_with(CONTENT_HASH_PREFIX));
+            for item in resp.resources {
+                let Ok(content) = item.content else {
+                    continue;
+                };
+                let etag = if etagless || content.etag.is_empty() {
+                    content_hash(&content.data)
+                } else {
+                    content.etag
+                };
+                let href = href::to_path(&item.href, &path_href);
+                if let Ok(tasks) =
+                    Task::from_ics_all(&content.data, etag, href.clone(), calendar_href.to_string())
+                {
+                    fetched.insert(href, tasks);
+                }
+            }
+        }
+
+        let findings = verify::compare(&cached, &listed, &fetched);
+        let repaired = repair && !findings.is_empty();
+        if repaired {
+            // Without the sync token, the next sync lists the calendar instead of trusting it
+            let tasks = verify::repair(&cached, &findings, &fetched);
+            Cache::save(calendar_href, &tasks, None).map_err(|e| e.to_string())?;
+        }
+        Ok(VerifyReport {
+            listed: listed.len(),
+            downloaded: fetched.len(),
+            findings,
+            repaired,
+        })
+    }
+
+    /// The local replica of a calendar: its cache (or the local storage) with the pending
+    /// journal replayed on top. Needs no connection.
+    pub fn local_tasks(calendar_href: &str) -> Vec<Task> {
+        if calendar_href == LOCAL_CALENDAR_HREF {
+            return LocalStorage::load().unwrap_or_default();
+        }
+        let (mut tasks, _) = Cache::load(calendar_href).unwrap_or_default();
+        Journal::load().apply_to(calendar_href, &mut tasks);
+        tasks
+    }
+
+    /// Local-first read: the server's state with the unsynced changes on top, or the
+    /// local replica when the server can't be reached.
     pub async fn get_tasks(&self, calendar_href: &str) -> Result<Vec<Task>, String> {
         let _ = self.sync_journal().await;
-        self.fetch_calendar_tasks_internal(calendar_href).await
+        match self.fetch_calendar_tasks_internal(calendar_href).await {
+            Ok(mut tasks) => {
+                if calendar_href != LOCAL_CALENDAR_HREF {
+                    let _ = Journal::mark_synced();
+                }
+                Journal::load().apply_to(calendar_href, &mut tasks);
+                Ok(tasks)
+            }
+            Err(_) => Ok(Self::local_tasks(calendar_href)),
+        }
     }
 
     pub async fn get_all_tasks(
         &self,
         calendars: &[CalendarListEntry],
-    ) -> Result<Vec<(String, Vec<Task>)>, String> {
+    ) -> Result<FetchReport, String> {
         let _ = self.sync_journal().await;
 
-        let hrefs: Vec<String> = calendars.iter().map(|c| c.href.clone()).collect();
+        let hrefs: Vec<String> = calendars
+            .iter()
+            .filter(|c| !self.is_sync_disabled(&c.href))
+            .map(|c| c.href.clone())
+            .collect();
         let futures = hrefs.into_iter().map(|href| {
             let client = self.clone();
             async move {
-                (
-                    href.clone(),
-                    client.fetch_calendar_tasks_internal(&href).await,
-                )
+                let started = Instant::now();
+                let res = client.fetch_calendar_tasks_internal(&href).await;
+                (href, res, started.elapsed())
             }
         });
 
-        let mut stream = stream::iter(futures).buffer_unordered(4);
-        let mut final_results = Vec::new();
-
-        while let Some((href, res)) = stream.next().await {
-            if let Ok(tasks) = res {
-                final_results.push((href, tasks));
+        let mut stream = stream::iter(futures).buffer_unordered(throttle::limits().concurrent);
+        let mut report = FetchReport::default();
+        let mut reached_server = false;
+        let journal = Journal::load();
+
+        while let Some((href, res, elapsed)) = stream.next().await {
+            report.timings.push((href.clone(), elapsed));
+            match res {
+                Ok(mut tasks) => {
+                    reached_server |= href != LOCAL_CALENDAR_HREF;
+                    journal.apply_to(&href, &mut tasks);
+                    report.tasks.push((href, tasks));
+                }
+                Err(e) => {
+                    let tasks = Self::local_tasks(&href);
+                    report.tasks.push((href.clone(), tasks));
+                    report.failures.push((href, e));
+                }
             }
         }
+        if reached_server {
+            let _ = Journal::mark_synced();
+        }
+        // The saved discovery may be what is wrong: the next launch looks it up again
+        if report.failures.iter().any(|(_, e)| discovery_outdated(e)) {
+            let _ = Cache::forget_discovery();
+        }
+        // Stable order, the UIs list them
+        report.failures.sort();
 
-        Ok(final_results)
+        Ok(report)
+    }
+
+    /// Background reconciliation: pushes the journal, pulls the server's changes and
+    /// returns only the calendars whose content differs from the local replica (failures are
+    /// all kept).
+    pub async fn reconcile(&self, calendars: &[CalendarListEntry]) -> Result<FetchReport, String> {
+        // Captured before fetching, since fetching rewrites the cache
+        let before: HashMap<String, Vec<TaskSignature>> = calendars
+            .iter()
+            .map(|c| (c.href.clone(), signature(&Self::local_tasks(&c.href))))
+            .collect();
+        let mut report = self.get_all_tasks(calendars).await?;
+        report
+            .tasks
+            .retain(|(href, tasks)| before.get(href) != Some(&signature(tasks)));
+        Ok(report)
     }
 
     // --- TASK OPERATIONS ---
 
     pub async fn create_task(&self, task: &mut Task) -> Result<Vec<String>, String> {
+        let mut warnings = check_before_save(task)?;
+        task.touch(true);
         if task.calendar_href == LOCAL_CALENDAR_HREF {
-            let mut all = LocalStorage::load().map_err(|e| e.to_string())?;
-            all.push(task.clone());
-            LocalStorage::save(&all).map_err(|e| e.to_string())?;
-            return Ok(vec![]);
+            LocalStorage::modify(|all| all.push(task.clone())).map_err(|e| e.to_string())?;
+            return Ok(warnings);
         }
 
         let cal_path = task.calendar_href.clone();
@@ -412,28 +1162,32 @@ impl RustyClient {
         task.href = full_href;
 
         Journal::push(Action::Create(task.clone())).map_err(|e| e.to_string())?;
-        self.sync_journal().await
+        warnings.extend(self.sync_journal().await?);
+        Ok(warnings)
     }
 
     pub async fn update_task(&self, task: &mut Task) -> Result<Vec<String>, String> {
+        let mut warnings = check_before_save(task)?;
+        task.touch(false);
         if task.calendar_href == LOCAL_CALENDAR_HREF {
-            let mut all = LocalStorage::load().map_err(|e| e.to_string())?;
-            if let Some(idx) = all.iter().position(|t| t.uid == task.uid) {
-                all[idx] = task.clone();
-                LocalStorage::save(&all).map_err(|e| e.to_string())?;
-            }
-            return Ok(vec![]);
+            LocalStorage::modify(|all| {
+                if let Some(idx) = all.iter().position(|t| t.uid == task.uid) {
+                    all[idx] = task.clone();
+                }
+            })
+            .map_err(|e| e.to_string())?;
+            return Ok(warnings);
         }
 
         Journal::push(Action::Update(task.clone())).map_err(|e| e.to_string())?;
-        self.sync_journal().await
+        warnings.extend(self.sync_journal().await?);
+        Ok(warnings)
     }
 
     pub async fn delete_task(&self, task: &Task) -> Result<Vec<String>, String> {
         if task.calendar_href == LOCAL_CALENDAR_HREF {
-            let mut all = LocalStorage::load().map_err(|e| e.to_string())?;
-            all.retain(|t| t.uid != task.uid);
-            LocalStorage::save(&all).map_err(|e| e.to_string())?;
+            LocalStorage::modify(|all| all.retain(|t| t.uid != task.uid))
+                .map_err(|e| e.to_string())?;
             return Ok(vec![]);
         }
 
@@ -452,14 +1206,16 @@ impl RustyClient {
         };
 
         if task.calendar_href == LOCAL_CALENDAR_HREF {
-            let mut all = LocalStorage::load().map_err(|e| e.to_string())?;
-            if let Some(idx) = all.iter().position(|t| t.uid == task.uid) {
-                all[idx] = task.clone();
-            }
-            if let Some(new_t) = &next_task {
-                all.push(new_t.clone());
-            }
-            LocalStorage::save(&all).map_err(|e| e.to_string())?;
+            task.touch(false);
+            LocalStorage::modify(|all| {
+                if let Some(idx) = all.iter().position(|t| t.uid == task.uid) {
+                    all[idx] = task.clone();
+                }
+                if let Some(new_t) = &next_task {
+                    all.push(new_t.clone());
+                }
+            })
+            .map_err(|e| e.to_string())?;
             return Ok((task.clone(), next_task, vec![]));
         }
 
@@ -498,18 +1254,128 @@ impl RustyClient {
         Ok((t, logs))
     }
 
+    /// Saves the changes made to `task` while triaging it and moves it to
+    /// `new_calendar_href`. Both are queued before pushing, so the move follows the ETag the
+    /// update gets, offline too.
+    pub async fn file_task(
+        &self,
+        task: &mut Task,
+        new_calendar_href: &str,
+    ) -> Result<(Task, Vec<String>), String> {
+        let mut warnings = check_before_save(task)?;
+        task.touch(false);
+        if task.calendar_href == LOCAL_CALENDAR_HREF {
+            // Created anew in the target, with the changes
+            let (moved, logs) = self.move_task(task, new_calendar_href).await?;
+            warnings.extend(logs);
+            return Ok((moved, warnings));
+        }
+
+        Journal::push(Action::Update(task.clone())).map_err(|e| e.to_string())?;
+        Journal::push(Action::Move(task.clone(), new_calendar_href.to_string()))
+            .map_err(|e| e.to_string())?;
+        let mut moved = task.clone();
+        moved.calendar_href = new_calendar_href.to_string();
+        warnings.extend(self.sync_journal().await?);
+        Ok((moved, warnings))
+    }
+
+    /// Saves what `TaskStore::apply_bulk` did to `tasks`: every change is queued first, then
+    /// pushed in one sync. Moved tasks are expected in their former calendar. Returns the
+    /// tasks to put back in the store (saved ones with their new timestamps, next
+    /// occurrences of completed recurring tasks, moved tasks) and the warnings.
+    pub async fn apply_bulk(
+        &self,
+        tasks: Vec<Task>,
+        action: &BulkAction,
+    ) -> Result<(Vec<Task>, Vec<String>), String> {
+        let mut changed = Vec::new();
+        let mut warnings = Vec::new();
+        let mut queued = Vec::new();
+        for mut task in tasks {
+            // Local tasks have no journal: they go through the single-task paths
+            if task.calendar_href == LOCAL_CALENDAR_HREF {
+                match action {
+                    BulkAction::Complete => {
+                        let (_, next, logs) = self.toggle_task(&mut task).await?;
+                        changed.push(task);
+                        changed.extend(next);
+                        warnings.extend(logs);
+                    }
+                    BulkAction::Delete => warnings.extend(self.delete_task(&task).await?),
+                    BulkAction::Move(target) => {
+                        let (moved, logs) = self.move_task(&task, target).await?;
+                        changed.push(moved);
+                        warnings.extend(logs);
+                    }
+                    BulkAction::AddTag(_) => {
+                        warnings.extend(self.update_task(&mut task).await?);
+                        changed.push(task);
+                    }
+                }
+                continue;
+            }
+            match action {
+                BulkAction::Complete => {
+                    if let Some(mut next) = task.respawn() {
+                        warnings.extend(check_before_save(&next)?);
+                        next.touch(true);
+                        next.href = resource_href(&next.calendar_href, &next.uid);
+                        queued.push(Action::Create(next.clone()));
+                        changed.push(next);
+                    }
+                    warnings.extend(check_before_save(&task)?);
+                    task.touch(false);
+                    queued.push(Action::Update(task.clone()));
+                    changed.push(task);
+                }
+                BulkAction::Delete => queued.push(Action::Delete(task)),
+                BulkAction::Move(target) if target == LOCAL_CALENDAR_HREF => {
+                    let mut copy = task.clone();
+                    copy.calendar_href = target.clone();
+                    copy.href = String::new();
+                    copy.etag = String::new();
+                    LocalStorage::modify(|all| all.push(copy.clone()))
+                        .map_err(|e| e.to_string())?;
+                    queued.push(Action::Delete(task));
+                    changed.push(copy);
+                }
+                BulkAction::Move(target) => {
+                    queued.push(Action::Move(task.clone(), target.clone()));
+                    task.calendar_href = target.clone();
+                    changed.push(task);
+                }
+                BulkAction::AddTag(_) => {
+                    warnings.extend(check_before_save(&task)?);
+                    task.touch(false);
+                    queued.push(Action::Update(task.clone()));
+                    changed.push(task);
+                }
+            }
+        }
+
+        if !queued.is_empty() {
+            for action in queued {
+                Journal::push(action).map_err(|e| e.to_string())?;
+            }
+            warnings.extend(self.sync_journal().await?);
+        }
+        Ok((changed, warnings))
+    }
+
     pub async fn migrate_tasks(
         &self,
         tasks: Vec<Task>,
         target_calendar_href: &str,
     ) -> Result<usize, String> {
+        backup::snapshot(Some("migrate")).map_err(|e| format!("Backup failed: {}", e))?;
         let futures = tasks.into_iter().map(|task| {
             let client = self.clone();
             let target = target_calendar_href.to_string();
             async move { client.move_task(&task, &target).await.ok() }
         });
 
-        let mut stream = stream::iter(futures).buffer_unordered(4);
+        let mut stream = stream::iter(futures).buffer_unordered(throttle::limits().concurrent);
         let mut count = 0;
         while let Some(res) = stream.next().await {
             if res.is_some() {
@@ -519,8 +1385,44 @@ impl RustyClient {
         Ok(count)
     }
 
+    /// What `migrate_tasks` would send, without sending anything or taking the backup.
+    /// Moving also pushes the queued changes, see `sync_journal_dry_run`.
+    pub fn migrate_tasks_dry_run(
+        &self,
+        tasks: &[Task],
+        target_calendar_href: &str,
+    ) -> Vec<PlannedRequest> {
+        let moves = self.capabilities.get().cloned().unwrap_or_default().moves();
+        tasks
+            .iter()
+            .flat_map(|task| {
+                let action = if task.calendar_href == LOCAL_CALENDAR_HREF {
+                    let mut created = task.clone();
+                    created.calendar_href = target_calendar_href.to_string();
+                    Action::Create(created)
+                } else {
+                    Action::Move(task.clone(), target_calendar_href.to_string())
+                };
+                plan_action(&action, moves)
+            })
+            .collect()
+    }
+
     // --- JOURNAL SYNC ---
 
+    /// What `sync_journal` would send, in order, without touching the server. Held tasks
+    /// are left out. Servers not probed yet are assumed to do MOVE.
+    pub fn sync_journal_dry_run(&self) -> Vec<PlannedRequest> {
+        let journal = Journal::load();
+        let moves = self.capabilities.get().cloned().unwrap_or_default().moves();
+        journal
+            .queue
+            .iter()
+            .filter(|a| !journal.held.contains(&a.task().uid))
+            .flat_map(|a| plan_action(a, moves))
+            .collect()
+    }
+
     // NEW HELPER: Fetch ETag explicitly if missing in PUT response
     async fn fetch_etag(&self, path: &str) -> Option<String> {
         if let Some(client) = &self.client
@@ -533,20 +1435,104 @@ impl RustyClient {
         None
     }
 
+    /// After a move to another server (see `crate::rehome`) the queue holds the old server's
+    /// ETags. The first queued action of each task gets the ETag of its copy on the new
+    /// server; changes to tasks missing there become creations and their deletions are
+    /// dropped. Returns the number of actions revised.
+    pub async fn revalidate_journal(&self) -> Result<usize, String> {
+        let client = self.client.as_ref().ok_or("Offline")?;
+        let mut current: HashMap<String, Option<String>> = HashMap::new();
+        let mut seen = HashSet::new();
+        for action in Journal::load().queue {
+            let task = action.task();
+            if !seen.insert(task.uid.clone()) || matches!(action, Action::Create(_)) {
+                continue;
+            }
+            let path = strip_host(&task.href);
+            let etag = match client
+                .request(GetProperty::new(&path, &names::GETETAG))
+                .await
+            {
+                Ok(resp) => Some(resp.value.unwrap_or_default()),
+                Err(WebDavError::BadStatusCode(StatusCode::NOT_FOUND)) => None,
+                Err(e) => return Err(format!("{}: {:?}", path, e)),
+            };
+            current.insert(task.uid.clone(), etag);
+        }
+
+        let mut revised = 0;
+        Journal::modify(|queue| {
+            let mut first = HashSet::new();
+            for action in std::mem::take(queue) {
+                let uid = action.task().uid.clone();
+                let Some(etag) = current.get(&uid).filter(|_| first.insert(uid.clone())) else {
+                    queue.push(action);
+                    continue;
+                };
+                revised += 1;
+                match (action, etag) {
+                    (Action::Update(mut t), Some(etag)) => {
+                        t.etag = etag.clone();
+                        queue.push(Action::Update(t));
+                    }
+                    (Action::Update(t), None) => queue.push(Action::Create(t)),
+                    (Action::Delete(mut t), Some(etag)) => {
+                        t.etag = etag.clone();
+                        queue.push(Action::Delete(t));
+                    }
+                    (Action::Delete(_), None) => {}
+                    (Action::Move(mut t, target), Some(etag)) => {
+                        t.etag = etag.clone();
+                        queue.push(Action::Move(t, target));
+                    }
+                    (Action::Move(mut t, target), None) => {
+                        t.calendar_href = target;
+                        queue.push(Action::Create(t));
+                    }
+                    (create @ Action::Create(_), _) => queue.push(create),
+                }
+            }
+        })
+        .map_err(|e| e.to_string())?;
+        Ok(revised)
+    }
+
     pub async fn sync_journal(&self) -> Result<Vec<String>, String> {
         let client = self.client.as_ref().ok_or("Offline")?;
         let mut warnings = Vec::new();
+        let mut pushed = false;
+        // Calendars written to, checked for space once everything is pushed
+        let mut written = BTreeSet::new();
 
         loop {
-            let next_action = {
+            // Actions of held tasks (see `Journal::hold`) wait for a later push
+            let (index, next_action) = {
                 let j = Journal::load();
-                if j.queue.is_empty() {
+                let Some(index) = j.next_index() else {
+                    if pushed {
+                        let _ = Journal::mark_synced();
+                    }
+                    for href in written {
+                        warnings.extend(self.near_full_warning(&href).await);
+                    }
                     return Ok(warnings);
-                }
-                j.queue[0].clone()
+                };
+                (index, j.queue[index].clone())
+            };
+            pushed = true;
+            let next_action = self
+                .without_server_scheduling(next_action, &mut warnings)
+                .await;
+            // The other VTODOs of a shared resource are written as they are now
+            let next_action = match next_action {
+                Action::Update(t) => Action::Update(with_current_siblings(&t)),
+                Action::Delete(t) => Action::Delete(with_current_siblings(&t)),
+                other => other,
             };
 
             let mut conflict_resolved_action = None;
+            // A shared resource written: the task as sent, and whether it left the resource
+            let mut shared_write: Option<(Task, bool)> = None;
             let mut new_etag_to_propagate: Option<String> = None;
             let mut new_href_to_propagate: Option<(String, String)> = None;
             let mut path_for_refresh: Option<String> = None;
@@ -573,16 +1559,60 @@ impl RustyClient {
                             }
                             Ok(())
                         }
+                        Err(WebDavError::BadStatusCode(StatusCode::FORBIDDEN)) => {
+                            warnings.push(read_only_warning(task));
+                            Ok(())
+                        }
                         Err(e) => Err(format!("{:?}", e)),
                     }
                 }
+                Action::Update(task) if lacks_etag(task) => {
+                    let path = strip_host(&task.href);
+                    if self.first_unchecked_write(&task.calendar_href) {
+                        warnings.push(etagless_warning(task));
+                    }
+                    match self
+                        .send_unconditional("PUT", &path, task.to_ics(), &task.etag)
+                        .await
+                    {
+                        Ok(status) if status.is_success() => {
+                            path_for_refresh = Some(path.clone());
+                            if task.has_sibling_todos() {
+                                shared_write = Some((task.clone(), false));
+                            }
+                            Ok(())
+                        }
+                        Ok(StatusCode::PRECONDITION_FAILED) => {
+                            if let Some((resolution, msg)) =
+                                self.attempt_conflict_resolution(task).await
+                            {
+                                warnings.push(msg);
+                                conflict_resolved_action = Some(resolution);
+                            } else {
+                                warnings.push(tr_args(
+                                    "error.changed_on_server",
+                                    &[("task", &task.summary)],
+                                ));
+                                conflict_resolved_action = Some(conflict_copy(task));
+                            }
+                            Ok(())
+                        }
+                        Ok(StatusCode::FORBIDDEN) => {
+                            warnings.push(read_only_warning(task));
+                            Ok(())
+                        }
+                        Ok(status) => Err(format!("PUT failed: {}", status)),
+                        Err(e) => Err(e),
+                    }
+                }
                 Action::Update(task) => {
                     let path = strip_host(&task.href);
                     let ics_string = task.to_ics();
+                    let content_type = adapter::content_type(&ics_string);
                     match client
                         .request(PutResource::new(&path).update(
                             ics_string,
-                            "text/calendar; charset=utf-8; component=VTODO",
+                            content_type,
                             &task.etag,
                         ))
                         .await
@@ -593,6 +1623,9 @@ impl RustyClient {
                             } else {
                                 path_for_refresh = Some(path.clone());
                             }
+                            if task.has_sibling_todos() {
+                                shared_write = Some((task.clone(), false));
+                            }
                             Ok(())
                         }
                         Err(WebDavError::BadStatusCode(StatusCode::PRECONDITION_FAILED))
@@ -604,18 +1637,10 @@ impl RustyClient {
                                 conflict_resolved_action = Some(resolution);
                                 Ok(())
                             } else {
-                                let msg = format!(
-                                    "Conflict (412) on task '{}'. Merge failed. Creating copy.",
-                                    task.summary
-                                );
+                                let msg =
+                                    tr_args("error.conflict_copy", &[("task", &task.summary)]);
                                 warnings.push(msg);
-
-                                let mut conflict_copy = task.clone();
-                                conflict_copy.uid = Uuid::new_v4().to_string();
-                                conflict_copy.summary = format!("{} (Conflict Copy)", task.summary);
-                                conflict_copy.href = String::new();
-                                conflict_copy.etag = String::new();
-                                conflict_resolved_action = Some(Action::Create(conflict_copy));
+                                conflict_resolved_action = Some(conflict_copy(task));
                                 Ok(())
                             }
                         }
@@ -623,21 +1648,17 @@ impl RustyClient {
                             conflict_resolved_action = Some(Action::Create(task.clone()));
                             Ok(())
                         }
+                        Err(WebDavError::BadStatusCode(StatusCode::FORBIDDEN)) => {
+                            warnings.push(read_only_warning(task));
+                            Ok(())
+                        }
                         Err(e) => {
                             let msg = format!("{:?}", e);
                             if msg.contains("412") || msg.contains("PreconditionFailed") {
-                                let w = format!(
-                                    "Conflict (412-Fallback) on task '{}'. Creating copy.",
-                                    task.summary
-                                );
+                                let w =
+                                    tr_args("error.conflict_fallback", &[("task", &task.summary)]);
                                 warnings.push(w);
-
-                                let mut conflict_copy = task.clone();
-                                conflict_copy.uid = Uuid::new_v4().to_string();
-                                conflict_copy.summary = format!("{} (Conflict Copy)", task.summary);
-                                conflict_copy.href = String::new();
-                                conflict_copy.etag = String::new();
-                                conflict_resolved_action = Some(Action::Create(conflict_copy));
+                                conflict_resolved_action = Some(conflict_copy(task));
                                 Ok(())
                             } else {
                                 Err(msg)
@@ -645,24 +1666,89 @@ impl RustyClient {
                         }
                     }
                 }
+                Action::Delete(task) if lacks_etag(task) => {
+                    let path = strip_host(&task.href);
+                    let status = match task.siblings_ics() {
+                        Some(rest) => self.send_unconditional("PUT", &path, rest, &task.etag),
+                        None => self.send_unconditional("DELETE", &path, String::new(), &task.etag),
+                    }
+                    .await;
+                    match status {
+                        Ok(s) if s.is_success() || s == StatusCode::NOT_FOUND => {
+                            if task.has_sibling_todos() {
+                                path_for_refresh = Some(path.clone());
+                                shared_write = Some((task.clone(), true));
+                            }
+                            Ok(())
+                        }
+                        Ok(StatusCode::FORBIDDEN) => {
+                            warnings.push(read_only_warning(task));
+                            Ok(())
+                        }
+                        Ok(StatusCode::PRECONDITION_FAILED) => {
+                            warnings
+                                .push(tr_args("error.conflict_delete", &[("task", &task.summary)]));
+                            Ok(())
+                        }
+                        Ok(s) => Err(format!("DELETE failed: {}", s)),
+                        Err(e) => Err(e),
+                    }
+                }
                 Action::Delete(task) => {
                     let path = strip_host(&task.href);
-                    match client
-                        .request(Delete::new(&path).with_etag(&task.etag))
-                        .await
-                    {
-                        Ok(_) => Ok(()),
+                    let request = match task.siblings_ics() {
+                        // Other VTODOs live in this resource: rewrite it without this one
+                        Some(rest) => {
+                            let content_type = adapter::content_type(&rest);
+                            client
+                                .request(PutResource::new(&path).update(
+                                    rest,
+                                    content_type,
+                                    &task.etag,
+                                ))
+                                .await
+                                .map(|_| ())
+                        }
+                        None => client
+                            .request(Delete::new(&path).with_etag(&task.etag))
+                            .await
+                            .map(|_| ()),
+                    };
+                    match request {
+                        Ok(_) => {
+                            if task.has_sibling_todos() {
+                                path_for_refresh = Some(path.clone());
+                                shared_write = Some((task.clone(), true));
+                            }
+                            Ok(())
+                        }
                         Err(WebDavError::BadStatusCode(StatusCode::NOT_FOUND)) => Ok(()),
+                        Err(WebDavError::BadStatusCode(StatusCode::FORBIDDEN)) => {
+                            warnings.push(read_only_warning(task));
+                            Ok(())
+                        }
                         Err(WebDavError::BadStatusCode(StatusCode::PRECONDITION_FAILED)) => {
-                            warnings.push(format!(
-                                "Conflict on delete task '{}'. Already modified/deleted.",
-                                task.summary
-                            ));
+                            warnings
+                                .push(tr_args("error.conflict_delete", &[("task", &task.summary)]));
                             Ok(())
                         }
                         Err(e) => Err(format!("{:?}", e)),
                     }
                 }
+                // MOVE would drag the other VTODOs of the resource along: split this one out.
+                // Same when the server doesn't do MOVE at all.
+                Action::Move(task, new_cal)
+                    if task.has_sibling_todos() || !self.capabilities().await.moves() =>
+                {
+                    let mut moved = task.without_siblings();
+                    moved.calendar_href = new_cal.clone();
+                    moved.href = String::new();
+                    moved.etag = String::new();
+                    conflict_resolved_action = Some(Action::Create(moved));
+                    // Queue the removal from the old resource right after the create
+                    Journal::modify(|queue| queue.insert(index + 1, Action::Delete(task.clone())))
+                        .map_err(|e| e.to_string())
+                }
                 Action::Move(task, new_cal) => match self.execute_move(task, new_cal).await {
                     Ok(_) => {
                         let filename = format!("{}.ics", task.uid);
@@ -686,17 +1772,24 @@ impl RustyClient {
                     // --- FIX: Fetch ETag if needed ---
                     if new_etag_to_propagate.is_none()
                         && let Some(path) = path_for_refresh
-                            && let Some(fetched) = self.fetch_etag(&path).await {
-                                new_etag_to_propagate = Some(fetched);
-                            }
+                    {
+                        // Servers without ETags get one made up from the stored content
+                        new_etag_to_propagate = match self.fetch_etag(&path).await {
+                            Some(etag) if !etag.is_empty() => Some(etag),
+                            _ => self.fetch_content_hash(&path).await,
+                        };
+                    }
+                    if let Some((task, removed)) = &shared_write {
+                        record_shared_write(task, *removed, new_etag_to_propagate.as_deref());
+                    }
 
                     let commit_res = Journal::modify(|queue| {
-                        if !queue.is_empty() {
-                            queue.remove(0);
+                        if index < queue.len() {
+                            queue.remove(index);
                         }
 
                         if let Some(act) = conflict_resolved_action {
-                            queue.insert(0, act);
+                            queue.insert(index.min(queue.len()), act);
                         }
 
                         if let Some(etag) = new_etag_to_propagate {
@@ -705,21 +1798,22 @@ impl RustyClient {
                                 Action::Move(t, _) => t.uid.clone(),
                                 _ => String::new(),
                             };
-                            if !target_uid.is_empty() {
-                                for item in queue.iter_mut() {
-                                    match item {
-                                        Action::Update(t) | Action::Delete(t) => {
-                                            if t.uid == target_uid {
-                                                t.etag = etag.clone();
-                                            }
-                                        }
-                                        Action::Move(t, _) => {
-                                            if t.uid == target_uid {
-                                                t.etag = etag.clone();
-                                            }
+                            // The other VTODOs of a shared resource changed ETag with it
+                            let shares_resource = |t: &Task| {
+                                shared_write
+                                    .as_ref()
+                                    .is_some_and(|(w, _)| href::same(&t.href, &w.href))
+                            };
+                            for item in queue.iter_mut() {
+                                match item {
+                                    Action::Update(t) | Action::Delete(t) | Action::Move(t, _) => {
+                                        if (!target_uid.is_empty() && t.uid == target_uid)
+                                            || shares_resource(t)
+                                        {
+                                            t.etag = etag.clone();
                                         }
-                                        _ => {}
                                     }
+                                    _ => {}
                                 }
                             }
                         }
@@ -754,6 +1848,19 @@ impl RustyClient {
                     if let Err(e) = commit_res {
                         return Err(e.to_string());
                     }
+                    if !matches!(next_action, Action::Delete(_)) {
+                        written.insert(target_calendar(&next_action).to_string());
+                    }
+                }
+                // Retrying can't help until space is freed: the changes to that calendar wait
+                Err(e) if quota::is_storage_full(&e) => {
+                    let calendar = target_calendar(&next_action);
+                    for action in Journal::load().queue {
+                        if target_calendar(&action) == calendar {
+                            Journal::hold(&action.task().uid, true).map_err(|e| e.to_string())?;
+                        }
+                    }
+                    warnings.push(quota::full_warning(&calendar_name(calendar)));
                 }
                 Err(e) => {
                     return Err(e);
@@ -773,10 +1880,7 @@ impl RustyClient {
         let server_task = server_tasks.iter().find(|t| t.uid == local_task.uid)?;
 
         if let Some(merged) = three_way_merge(base_task, local_task, server_task) {
-            let msg = format!(
-                "Conflict (412) on '{}' resolved via 3-way merge.",
-                local_task.summary
-            );
+            let msg = tr_args("error.conflict_merged", &[("task", &local_task.summary)]);
             return Some((Action::Update(merged), msg));
         }
 
@@ -820,6 +1924,95 @@ impl RustyClient {
     }
 }
 
+fn conflict_copy(task: &Task) -> Action {
+    let mut copy = task.without_siblings();
+    copy.uid = Uuid::new_v4().to_string();
+    copy.summary = format!("{} (Conflict Copy)", task.summary);
+    copy.href = String::new();
+    copy.etag = String::new();
+    Action::Create(copy)
+}
+
+fn resource_href(calendar_href: &str, uid: &str) -> String {
+    if calendar_href.ends_with('/') {
+        format!("{}{}.ics", calendar_href, uid)
+    } else {
+        format!("{}/{}.ics", calendar_href, uid)
+    }
+}
+
+/// The requests `sync_journal` sends for `action`, unless a conflict changes its plans.
+/// `moves`: whether the server does MOVE.
+fn plan_action(action: &Action, moves: bool) -> Vec<PlannedRequest> {
+    let request = |method, href: &str, change: String| PlannedRequest {
+        method,
+        href: strip_host(href),
+        change,
+    };
+    match action {
+        Action::Create(t) => vec![request(
+            "PUT",
+            &resource_href(&t.calendar_href, &t.uid),
+            format!("create '{}'", t.summary),
+        )],
+        Action::Update(t) if lacks_etag(t) => vec![request(
+            "PUT",
+            &t.href,
+            format!("update '{}' (no ETag: unconditional)", t.summary),
+        )],
+        Action::Update(t) => vec![request("PUT", &t.href, format!("update '{}'", t.summary))],
+        Action::Delete(t) if t.has_sibling_todos() => vec![request(
+            "PUT",
+            &t.href,
+            format!("delete '{}' (rewrites its shared resource)", t.summary),
+        )],
+        Action::Delete(t) => vec![request(
+            "DELETE",
+            &t.href,
+            format!("delete '{}'", t.summary),
+        )],
+        Action::Move(t, new_cal) if t.has_sibling_todos() || !moves => {
+            let mut plan = vec![request(
+                "PUT",
+                &resource_href(new_cal, &t.uid),
+                format!("copy '{}' to {}", t.summary, new_cal),
+            )];
+            plan.extend(plan_action(&Action::Delete(t.clone()), moves));
+            plan
+        }
+        Action::Move(t, new_cal) => vec![request(
+            "MOVE",
+            &t.href,
+            format!("move '{}' to {}", t.summary, new_cal),
+        )],
+    }
+}
+
+fn etagless_warning(task: &Task) -> String {
+    tr_args("error.no_stable_etags", &[("task", &task.summary)])
+}
+
+// A 403 will never succeed on retry, so drop the action instead of blocking the queue forever.
+/// The calendar an action writes to.
+fn target_calendar(action: &Action) -> &str {
+    match action {
+        Action::Move(_, new_cal) => new_cal,
+        other => &other.task().calendar_href,
+    }
+}
+
+/// The name of a cached calendar, else its href.
+fn calendar_name(href: &str) -> String {
+    Cache::load_calendars()
+        .ok()
+        .and_then(|cals| cals.into_iter().find(|c| c.href == href))
+        .map_or_else(|| href.to_string(), |c| c.name)
+}
+
+fn read_only_warning(task: &Task) -> String {
+    tr_args("error.permission_denied", &[("task", &task.summary)])
+}
+
 fn three_way_merge(base: &Task, local: &Task, server: &Task) -> Option<Task> {
     let mut merged = server.clone();
 
@@ -843,11 +2036,142 @@ fn three_way_merge(base: &Task, local: &Task, server: &Task) -> Option<Task> {
     merge_field!(dtstart);
     merge_field!(estimated_duration);
     merge_field!(rrule);
+    merge_field!(recur_from_completion);
+    merge_field!(exdates);
+    merge_field!(hide_subtasks);
+    merge_field!(sort_order);
+    merge_field!(reminders);
     merge_field!(categories);
     merge_field!(dependencies);
     merge_field!(parent_uid);
     merge_field!(unmapped_properties);
     merge_field!(raw_components);
 
+    // Timestamps follow the merged content rather than being merged themselves
+    merged.last_modified = local.last_modified.max(server.last_modified);
+    merged.completed = if merged.status == TaskStatus::Completed {
+        local.completed.or(server.completed)
+    } else {
+        None
+    };
+
     Some(merged)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_privilege_set_parsing() {
+        let read_only = r#"<d:multistatus xmlns:d="DAV:"><d:response><d:propstat><d:prop>
+            <d:current-user-privilege-set>
+                <d:privilege><d:read/></d:privilege>
+                <d:privilege><d:read-current-user-privilege-set/></d:privilege>
+            </d:current-user-privilege-set>
+            </d:prop></d:propstat></d:response></d:multistatus>"#;
+        assert_eq!(privileges_allow_write(read_only), Some(false));
+
+        let writable = r#"<multistatus xmlns="DAV:"><response><propstat><prop>
+            <current-user-privilege-set><privilege><read/></privilege><privilege><write-content /></privilege></current-user-privilege-set>
+            </prop></propstat></response></multistatus>"#;
+        assert_eq!(privileges_allow_write(writable), Some(true));
+
+        let missing = r#"<d:multistatus xmlns:d="DAV:"><d:response><d:propstat><d:prop>
+            <d:current-user-privilege-set/></d:prop><d:status>HTTP/1.1 404 Not Found</d:status>
+            </d:propstat></d:response></d:multistatus>"#;
+        assert_eq!(privileges_allow_write(missing), None);
+    }
+
+    #[test]
+    fn test_same_principal() {
+        let me = "/remote.php/dav/principals/users/bob/";
+        assert!(is_same_principal("principals/users/bob", me));
+        let url = "https://cloud.example.com/remote.php/dav/principals/users/bob";
+        assert!(is_same_principal(url, me));
+        assert!(is_same_principal("/", me));
+        assert!(!is_same_principal("principals/users/alice-bob", me));
+        assert!(!is_same_principal("/dav/principals/users/ob/", me));
+    }
+
+    #[test]
+    fn test_etag_fallbacks() {
+        let hash = content_hash("BEGIN:VCALENDAR");
+        assert_eq!(hash, content_hash("BEGIN:VCALENDAR"));
+        assert_ne!(hash, content_hash("BEGIN:VCALENDAR "));
+
+        let mut task = Task::new("Task", &HashMap::new());
+        assert!(lacks_etag(&task));
+        task.etag = hash;
+        assert!(lacks_etag(&task));
+        task.etag = "\"abc\"".to_string();
+        assert!(!lacks_etag(&task));
+        assert_eq!(normalize_etag("W/\"abc\""), normalize_etag(&task.etag));
+    }
+
+    #[test]
+    fn test_disabled_calendars_shared_by_clones() {
+        let client = RustyClient::new("", "", "", false).unwrap();
+        let clone = client.clone();
+        let none = client.set_disabled_calendars(["/a/".to_string(), "/b/".to_string()]);
+        assert!(none.is_empty());
+        assert!(clone.is_sync_disabled("/a/"));
+
+        let enabled = clone.set_disabled_calendars(["/b/".to_string()]);
+        assert_eq!(enabled, vec!["/a/".to_string()]);
+        assert!(!client.is_sync_disabled("/a/") && client.is_sync_disabled("/b/"));
+    }
+
+    #[test]
+    fn test_fetch_failure_lines() {
+        let work = CalendarListEntry {
+            name: "Work".to_string(),
+            href: "/cal/work/".to_string(),
+            color: None,
+            is_shared: false,
+            is_read_only: false,
+        };
+        let report = FetchReport {
+            tasks: Vec::new(),
+            failures: vec![
+                ("/cal/gone/".to_string(), "404 Not Found".to_string()),
+                ("/cal/work/".to_string(), "timed out".to_string()),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            report.failure_lines(&[work]),
+            vec!["/cal/gone/: 404 Not Found", "Work: timed out"]
+        );
+    }
+
+    #[test]
+    fn test_dry_run_plans() {
+        let mut task = Task::new("Buy milk", &HashMap::new());
+        task.calendar_href = "/cal/home/".to_string();
+        let create = plan_action(&Action::Create(task.clone()), true);
+        let href = format!("/cal/home/{}.ics", task.uid);
+        assert_eq!(
+            (create[0].method, create[0].href.as_str()),
+            ("PUT", href.as_str())
+        );
+        assert_eq!(create[0].change, "create 'Buy milk'");
+
+        task.href = format!("https://dav.example.com{}", href);
+        task.etag = "\"1\"".to_string();
+        let update = plan_action(&Action::Update(task.clone()), true);
+        assert_eq!(update[0].href, href);
+        assert_eq!(
+            update[0].to_string(),
+            format!("PUT    {}  update 'Buy milk'", href)
+        );
+
+        let moved = Action::Move(task.clone(), "/cal/work/".to_string());
+        assert_eq!(plan_action(&moved, true)[0].method, "MOVE");
+        let methods: Vec<_> = plan_action(&moved, false)
+            .iter()
+            .map(|r| r.method)
+            .collect();
+        assert_eq!(methods, vec!["PUT", "DELETE"]);
+    }
+}
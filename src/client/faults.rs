@@ -0,0 +1,261 @@
+// File: ./src/client/faults.rs
+// Fault injection for tests and demos (`faults` feature): a share of the requests fails,
+// either answered with a chosen status (412, 429, 500…) or left without any answer, as when
+// offline, and every request can be delayed. Set from code with `set`, or at startup with
+// `CFAIT_FAULTS`, e.g. "fail=30,status=429,delay=500,only=PUT" or "offline".
+//
+// The HTTP client's answers can't be built by hand, so failing requests are sent to a small
+// server of our own on 127.0.0.1 instead, which answers the status asked for or hangs up.
+// Its thread outlives the async runtimes, each test having its own.
+use futures::future::BoxFuture;
+use http::{HeaderValue, Method, Request, StatusCode, Uri};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, OnceLock, RwLock};
+use std::task::{Context, Poll};
+use std::thread;
+use std::time::Duration;
+use tower_service::Service;
+
+pub const ENV_VAR: &str = "CFAIT_FAULTS";
+/// Tells the fault server what to answer: a status, or "none" to hang up
+const FAULT_HEADER: &str = "x-cfait-fault";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Faults {
+    /// Requests failing out of 100, spread evenly and starting with the first
+    pub fail_percent: u32,
+    /// What failing requests get; None = no answer at all, as when offline
+    pub status: Option<StatusCode>,
+    /// Waited before every request is sent
+    pub delay: Duration,
+    /// Only requests with these methods may fail (empty = all)
+    pub only: Vec<Method>,
+}
+
+impl Faults {
+    /// Every request fails without an answer.
+    pub fn offline() -> Self {
+        Self {
+            fail_percent: 100,
+            ..Default::default()
+        }
+    }
+
+    /// Reads the `CFAIT_FAULTS` syntax: "offline", or comma-separated "fail=<percent>",
+    /// "status=<code>", "delay=<ms>" and "only=<METHOD>[+<METHOD>…]".
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut faults = Self::default();
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            if part == "offline" {
+                faults.fail_percent = 100;
+                continue;
+            }
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Expected key=value, got '{}'", part))?;
+            let invalid = || format!("Invalid {}: '{}'", key, value);
+            match key {
+                "fail" => {
+                    faults.fail_percent = value
+                        .trim_end_matches('%')
+                        .parse()
+                        .ok()
+                        .filter(|p| *p <= 100)
+                        .ok_or_else(invalid)?
+                }
+                "status" => {
+                    faults.status = Some(value.parse().map_err(|_| invalid())?);
+                    if faults.fail_percent == 0 {
+                        faults.fail_percent = 100;
+                    }
+                }
+                "delay" => {
+                    faults.delay = Duration::from_millis(value.parse().map_err(|_| invalid())?)
+                }
+                "only" => {
+                    faults.only = value
+                        .split('+')
+                        .map(|m| m.to_uppercase().parse::<Method>().map_err(|_| invalid()))
+                        .collect::<Result<_, _>>()?
+                }
+                _ => return Err(format!("Unknown fault '{}'", key)),
+            }
+        }
+        Ok(faults)
+    }
+
+    fn applies_to(&self, method: &Method) -> bool {
+        self.only.is_empty() || self.only.contains(method)
+    }
+}
+
+static FAULTS: LazyLock<RwLock<Faults>> = LazyLock::new(|| {
+    let faults = std::env::var(ENV_VAR)
+        .ok()
+        .map(|spec| {
+            Faults::parse(&spec).unwrap_or_else(|e| {
+                eprintln!("Ignoring {}: {}", ENV_VAR, e);
+                Faults::default()
+            })
+        })
+        .unwrap_or_default();
+    RwLock::new(faults)
+});
+/// Requests the faults applied to since they were last set
+static SEEN: AtomicU64 = AtomicU64::new(0);
+
+/// Replaces the faults of every client of the process.
+pub fn set(faults: Faults) {
+    if let Ok(mut current) = FAULTS.write() {
+        *current = faults;
+    }
+    SEEN.store(0, Ordering::Relaxed);
+}
+
+pub fn current() -> Faults {
+    FAULTS.read().map(|f| f.clone()).unwrap_or_default()
+}
+
+/// Whether the `n`th request fails: `percent` of them, evenly spread (30 fails the 1st, 5th
+/// and 8th of every ten).
+fn fails(n: u64, percent: u32) -> bool {
+    (n * u64::from(percent)) % 100 < u64::from(percent)
+}
+
+// --- FAULT SERVER ---
+
+static SERVER: OnceLock<Option<SocketAddr>> = OnceLock::new();
+
+fn server() -> Option<SocketAddr> {
+    *SERVER.get_or_init(|| {
+        let listener = TcpListener::bind("127.0.0.1:0").ok()?;
+        let addr = listener.local_addr().ok()?;
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                thread::spawn(move || {
+                    let _ = answer(stream);
+                });
+            }
+        });
+        Some(addr)
+    })
+}
+
+/// Reads the whole request, so that the client sees the answer and not a reset connection.
+fn answer(stream: TcpStream) -> std::io::Result<()> {
+    let mut reader = BufReader::new(&stream);
+    let mut status = None;
+    let mut length = 0;
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 && line != "\r\n" {
+        if let Some((name, value)) = line.split_once(':') {
+            let value = value.trim();
+            if name.eq_ignore_ascii_case(FAULT_HEADER) {
+                status = value.parse::<StatusCode>().ok();
+            } else if name.eq_ignore_ascii_case("content-length") {
+                length = value.parse().unwrap_or(0);
+            }
+        }
+        line.clear();
+    }
+    reader.read_exact(&mut vec![0; length])?;
+    let Some(status) = status else {
+        return Ok(());
+    };
+    // Retry-After 0: the throttle retries at once instead of backing off for seconds
+    let mut out = &stream;
+    write!(
+        out,
+        "HTTP/1.1 {} {}\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        status.as_u16(),
+        status.canonical_reason().unwrap_or_default()
+    )
+}
+
+/// Sends `req` to the fault server, with what it should answer.
+fn redirect(req: &mut Request<String>, addr: SocketAddr, status: Option<StatusCode>) {
+    let path = req
+        .uri()
+        .path_and_query()
+        .map_or("/", |pq| pq.as_str())
+        .to_string();
+    if let Ok(uri) = format!("http://{}{}", addr, path).parse::<Uri>() {
+        *req.uri_mut() = uri;
+    }
+    let fault = status.map_or("none".to_string(), |s| s.as_u16().to_string());
+    if let Ok(value) = HeaderValue::from_str(&fault) {
+        req.headers_mut().insert(FAULT_HEADER, value);
+    }
+}
+
+// --- SERVICE ---
+
+/// Wraps the HTTP client below the wire log, so that injected answers are logged and
+/// retried like the server's own.
+#[derive(Clone, Debug)]
+pub struct Faulty<S> {
+    inner: S,
+}
+
+impl<S> Faulty<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+impl<S> Service<Request<String>> for Faulty<S>
+where
+    S: Service<Request<String>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<String>) -> Self::Future {
+        let faults = current();
+        if faults.fail_percent > 0
+            && faults.applies_to(req.method())
+            && fails(SEEN.fetch_add(1, Ordering::Relaxed), faults.fail_percent)
+            && let Some(addr) = server()
+        {
+            redirect(&mut req, addr, faults.status);
+        }
+        let call = self.inner.call(req);
+        Box::pin(async move {
+            if !faults.delay.is_zero() {
+                tokio::time::sleep(faults.delay).await;
+            }
+            call.await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_spread() {
+        assert_eq!(Faults::parse("offline"), Ok(Faults::offline()));
+        let faults = Faults::parse("fail=30%, status=412, delay=250, only=put+delete").unwrap();
+        assert_eq!(faults.fail_percent, 30);
+        assert_eq!(faults.status, Some(StatusCode::PRECONDITION_FAILED));
+        assert_eq!(faults.delay, Duration::from_millis(250));
+        assert!(faults.applies_to(&Method::DELETE) && !faults.applies_to(&Method::GET));
+        // A status alone fails every request
+        assert_eq!(Faults::parse("status=500").unwrap().fail_percent, 100);
+        assert!(Faults::parse("fail=120").is_err());
+        assert!(Faults::parse("jitter=5").is_err());
+
+        let failed: Vec<u64> = (0..10).filter(|n| fails(*n, 30)).collect();
+        assert_eq!(failed, [0, 4, 7]);
+        assert!((0..100).all(|n| fails(n, 100) && !fails(n, 0)));
+    }
+}
@@ -0,0 +1,189 @@
+// File: ./src/client/imap.rs
+// Mail to tasks: polls one IMAP folder and turns its unread or flagged mails into tasks
+// (subject -> summary, text body -> description). Each task keeps the mail's Message-ID as
+// a `mid:` URL (RFC 2392), which links back to the mail and prevents importing it twice.
+use crate::client::core::tls_config;
+use crate::config::{ImapConfig, MailSelect};
+use crate::model::{Task, TaskBuilder};
+use futures::TryStreamExt;
+use mailparse::{DispositionType, MailHeaderMap, ParsedMail};
+use rustls::pki_types::ServerName;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+
+const LINK_PROPERTY: &str = "URL";
+/// Longer bodies are cut, a task is not a mail archive
+const MAX_DESCRIPTION: usize = 4000;
+
+/// The Message-ID of the mail a task was made from.
+pub fn message_id(task: &Task) -> Option<&str> {
+    task.unmapped_properties
+        .iter()
+        .find(|p| p.key == LINK_PROPERTY)
+        .and_then(|p| p.value.strip_prefix("mid:"))
+}
+
+/// The first text/plain part, depth first (multipart/alternative lists it before the HTML).
+fn text_body(mail: &ParsedMail) -> Option<String> {
+    if mail.subparts.is_empty() {
+        let is_text = mail.ctype.mimetype.eq_ignore_ascii_case("text/plain");
+        let is_attachment =
+            mail.get_content_disposition().disposition == DispositionType::Attachment;
+        if !is_text || is_attachment {
+            return None;
+        }
+        return mail.get_body().ok();
+    }
+    mail.subparts.iter().find_map(text_body)
+}
+
+/// None for mails without a Message-ID, which could not be told apart from the next poll's.
+pub fn task_from_mail(raw: &[u8], calendar_href: &str) -> Result<Option<Task>, String> {
+    let mail = mailparse::parse_mail(raw).map_err(|e| e.to_string())?;
+    let headers = mail.get_headers();
+    let Some(id) = headers.get_first_value("Message-ID") else {
+        return Ok(None);
+    };
+    let id = id.trim().trim_start_matches('<').trim_end_matches('>');
+
+    // Not `Task::new`: subjects are not smart syntax ("Re: #42 @ noon")
+    let summary = headers
+        .get_first_value("Subject")
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "(no subject)".to_string());
+    let mut description = String::new();
+    if let Some(from) = headers.get_first_value("From") {
+        description = format!("From: {}\n\n", from.trim());
+    }
+    if let Some(body) = text_body(&mail) {
+        let body = body.trim();
+        match body.char_indices().nth(MAX_DESCRIPTION) {
+            Some((cut, _)) => description.push_str(&format!("{}…", &body[..cut])),
+            None => description.push_str(body),
+        }
+    }
+    TaskBuilder::new(summary)
+        .description(description.trim_end())
+        .calendar(calendar_href)
+        .property(LINK_PROPERTY, format!("mid:{}", id))
+        .build()
+        .map(Some)
+}
+
+/// Fetches the mails selected by `config` and returns the tasks made from those whose
+/// Message-ID is not in `known`, with a warning per skipped mail. Unread mails are marked
+/// as read once imported.
+pub async fn fetch_tasks(
+    config: &ImapConfig,
+    calendar_href: &str,
+    known: &HashSet<String>,
+) -> Result<(Vec<Task>, Vec<String>), String> {
+    let host = config.host.trim();
+    let tcp = TcpStream::connect((host, config.port))
+        .await
+        .map_err(|e| format!("{}: {}", host, e))?;
+    let name = ServerName::try_from(host.to_string()).map_err(|e| e.to_string())?;
+    let tls = TlsConnector::from(Arc::new(tls_config(false)?))
+        .connect(name, tcp)
+        .await
+        .map_err(|e| format!("{}: {}", host, e))?;
+
+    let mut client = async_imap::Client::new(tls);
+    // The server speaks first
+    let _ = client.read_response().await;
+    let mut session = client
+        .login(&config.username, &config.password)
+        .await
+        .map_err(|(e, _)| format!("IMAP login: {}", e))?;
+    session
+        .select(&config.folder)
+        .await
+        .map_err(|e| format!("{}: {}", config.folder, e))?;
+
+    let query = match config.select {
+        MailSelect::Unread => "UNSEEN",
+        MailSelect::Flagged => "FLAGGED",
+    };
+    let uids = session.uid_search(query).await.map_err(|e| e.to_string())?;
+    let mut tasks = Vec::new();
+    let mut warnings = Vec::new();
+    if !uids.is_empty() {
+        let set = uids
+            .iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        // PEEK: fetching must not mark them read, only importing does
+        let fetched: Vec<_> = session
+            .uid_fetch(&set, "BODY.PEEK[]")
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+        // Mails that could not be imported stay unread
+        let mut imported = Vec::new();
+        for fetch in &fetched {
+            let Some(raw) = fetch.body() else { continue };
+            match task_from_mail(raw, calendar_href) {
+                Ok(Some(task)) => {
+                    imported.extend(fetch.uid.map(|uid| uid.to_string()));
+                    if !message_id(&task).is_some_and(|id| known.contains(id)) {
+                        tasks.push(task);
+                    }
+                }
+                Ok(None) => warnings.push("Skipped a mail without Message-ID".to_string()),
+                Err(e) => warnings.push(format!("Skipped a mail: {}", e)),
+            }
+        }
+        if config.select == MailSelect::Unread && !imported.is_empty() {
+            let _: Vec<_> = session
+                .uid_store(imported.join(","), "+FLAGS.SILENT (\\Seen)")
+                .await
+                .map_err(|e| e.to_string())?
+                .try_collect()
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    let _ = session.logout().await;
+    Ok((tasks, warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_from_mail() {
+        let raw = b"From: Alice <alice@example.com>\r\n\
+            Subject: Re: #42 review @ noon\r\n\
+            Message-ID: <abc.123@example.com>\r\n\
+            Content-Type: multipart/alternative; boundary=\"b\"\r\n\
+            \r\n\
+            --b\r\n\
+            Content-Type: text/plain; charset=utf-8\r\n\
+            \r\n\
+            Could you look at the patch?\r\n\
+            --b\r\n\
+            Content-Type: text/html\r\n\
+            \r\n\
+            <p>Could you look at the patch?</p>\r\n\
+            --b--\r\n";
+        let task = task_from_mail(raw, "/cal/inbox/").unwrap().unwrap();
+        assert_eq!(task.summary, "Re: #42 review @ noon");
+        assert!(task.categories.is_empty());
+        assert_eq!(
+            task.description,
+            "From: Alice <alice@example.com>\n\nCould you look at the patch?"
+        );
+        assert_eq!(task.calendar_href, "/cal/inbox/");
+        assert_eq!(message_id(&task), Some("abc.123@example.com"));
+
+        let anonymous = b"Subject: No id\r\n\r\nBody\r\n";
+        assert!(task_from_mail(anonymous, "/cal/inbox/").unwrap().is_none());
+    }
+}
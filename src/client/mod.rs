@@ -1,6 +1,19 @@
 // File: ./src/client/mod.rs
 // re-exports the cleaned up client modules
+pub mod capabilities;
 pub mod cert;
 pub mod core;
+#[cfg(feature = "faults")]
+pub mod faults;
+#[cfg(feature = "imap")]
+pub mod imap;
+pub mod push;
+pub mod quota;
+pub mod throttle;
+pub mod user_agent;
+pub mod verify;
+pub mod wirelog;
 
-pub use self::core::{GET_CTAG, RustyClient};
+pub use self::capabilities::ServerCapabilities;
+pub use self::core::{FetchReport, GET_CTAG, PlannedRequest, RustyClient};
+pub use self::push::PushTarget;
@@ -0,0 +1,158 @@
+// File: ./src/client/push.rs
+// Listens for change notifications (Nextcloud notify_push or any WebSocket) instead of polling
+use crate::client::core::tls_config;
+use crate::config::Config;
+use futures::{SinkExt, StreamExt};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{Connector, connect_async_tls_with_config};
+
+/// Wait before reconnecting after the socket dropped
+const RETRY_DELAY: Duration = Duration::from_secs(30);
+/// Notifications arriving this close together trigger a single sync
+const DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Where to listen and how to authenticate (notify_push takes the DAV credentials).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PushTarget {
+    pub url: String,
+    pub username: String,
+    pub password: String,
+    pub insecure: bool,
+}
+
+impl PushTarget {
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let url = config.push_url.as_ref().filter(|u| !u.trim().is_empty())?;
+        Some(Self {
+            url: url.trim().to_string(),
+            username: config.username.clone(),
+            password: config.password.clone(),
+            insecure: config.allow_insecure_certs,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum PushMessage {
+    Authenticated,
+    Rejected(String),
+    /// Calendar href if the server said which one changed, None to check all of them
+    Changed(Option<String>),
+    Ignored,
+}
+
+fn parse_message(text: &str) -> PushMessage {
+    let text = text.trim();
+    if text.is_empty() {
+        return PushMessage::Ignored;
+    }
+    if text == "authenticated" {
+        return PushMessage::Authenticated;
+    }
+    if let Some(err) = text.strip_prefix("err:") {
+        return PushMessage::Rejected(err.trim().to_string());
+    }
+    // notify_push: file changes never touch calendars; activities do
+    if text.starts_with("notify_file") {
+        return PushMessage::Ignored;
+    }
+    // Generic endpoints may send the href of the changed calendar or resource
+    if text.starts_with('/') && !text.contains(char::is_whitespace) {
+        let calendar = if text.ends_with('/') {
+            text.to_string()
+        } else {
+            text.rsplit_once('/')
+                .map(|(dir, _)| format!("{}/", dir))
+                .unwrap_or_default()
+        };
+        return PushMessage::Changed(Some(calendar));
+    }
+    PushMessage::Changed(None)
+}
+
+/// Keeps a connection open and calls `on_change` with the calendar that changed
+/// (None = unknown, sync everything). Reconnects after network errors.
+/// Returns when `on_change` returns false, or with an error if the server rejects us.
+pub async fn listen(
+    target: PushTarget,
+    mut on_change: impl FnMut(Option<String>) -> bool + Send,
+) -> Result<(), String> {
+    loop {
+        match session(&target, &mut on_change).await {
+            Ok(false) => return Ok(()),
+            Ok(true) => {}
+            Err(Some(e)) => return Err(e),
+            Err(None) => {}
+        }
+        tokio::time::sleep(RETRY_DELAY).await;
+    }
+}
+
+/// Ok(keep listening) when the connection ends; Err(Some) when authentication failed.
+async fn session(
+    target: &PushTarget,
+    on_change: &mut (impl FnMut(Option<String>) -> bool + Send),
+) -> Result<bool, Option<String>> {
+    let connector = Connector::Rustls(Arc::new(tls_config(target.insecure).map_err(Some)?));
+    let (mut ws, _) =
+        connect_async_tls_with_config(target.url.as_str(), None, false, Some(connector))
+            .await
+            .map_err(|_| None)?;
+
+    // notify_push expects the username and password as the first two messages
+    ws.send(Message::text(target.username.clone()))
+        .await
+        .map_err(|_| None)?;
+    ws.send(Message::text(target.password.clone()))
+        .await
+        .map_err(|_| None)?;
+
+    while let Some(msg) = ws.next().await {
+        let Ok(Message::Text(text)) = msg else {
+            continue;
+        };
+        let mut hint = match parse_message(&text) {
+            PushMessage::Changed(hint) => hint,
+            PushMessage::Rejected(e) => return Err(Some(format!("Push server: {}", e))),
+            PushMessage::Authenticated | PushMessage::Ignored => continue,
+        };
+
+        // Saving several tasks produces a burst of notifications
+        while let Ok(Some(Ok(Message::Text(text)))) =
+            tokio::time::timeout(DEBOUNCE, ws.next()).await
+        {
+            if let PushMessage::Changed(next) = parse_message(&text)
+                && next != hint
+            {
+                hint = None;
+            }
+        }
+
+        if !on_change(hint) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_push_messages() {
+        assert_eq!(parse_message("authenticated"), PushMessage::Authenticated);
+        assert_eq!(
+            parse_message("err: Invalid credentials"),
+            PushMessage::Rejected("Invalid credentials".to_string())
+        );
+        assert_eq!(parse_message("notify_file_id [42]"), PushMessage::Ignored);
+        assert_eq!(parse_message("notify_activity"), PushMessage::Changed(None));
+        assert_eq!(
+            parse_message("/calendars/me/work/task.ics"),
+            PushMessage::Changed(Some("/calendars/me/work/".to_string()))
+        );
+    }
+}
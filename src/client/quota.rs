@@ -0,0 +1,145 @@
+// File: ./src/client/quota.rs
+// Storage limits of a calendar, as far as the server tells them: the WebDAV quota (RFC 4331)
+// and the largest task it accepts (CalDAV max-resource-size). A calendar at its quota refuses
+// writes with 507 Insufficient Storage, which no retry fixes: the sync holds those changes and
+// says why instead of failing on them forever.
+use http::StatusCode;
+
+/// Used share from which a calendar is reported as nearly full
+pub const NEAR_FULL_PERCENT: u64 = 90;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StorageLimits {
+    pub used_bytes: Option<u64>,
+    pub available_bytes: Option<u64>,
+    /// Largest resource (task) accepted
+    pub max_resource_size: Option<u64>,
+}
+
+impl StorageLimits {
+    /// Reads the property values as sent ("12345"); missing or malformed ones stay unknown.
+    pub fn from_properties(
+        used: Option<&str>,
+        available: Option<&str>,
+        max_resource_size: Option<&str>,
+    ) -> Self {
+        let bytes = |v: Option<&str>| v.and_then(|v| v.trim().parse().ok());
+        Self {
+            used_bytes: bytes(used),
+            available_bytes: bytes(available),
+            max_resource_size: bytes(max_resource_size),
+        }
+    }
+
+    pub fn is_known(&self) -> bool {
+        *self != Self::default()
+    }
+
+    /// None without a quota (or when the server reports only half of it).
+    pub fn percent_used(&self) -> Option<u64> {
+        let (used, available) = (self.used_bytes?, self.available_bytes?);
+        let total = used.saturating_add(available);
+        Some(if total == 0 {
+            100
+        } else {
+            used.saturating_mul(100) / total
+        })
+    }
+
+    pub fn is_near_full(&self) -> bool {
+        self.percent_used().is_some_and(|p| p >= NEAR_FULL_PERCENT)
+    }
+
+    /// E.g. "4.6 MB of 5.0 MB used (92%), 1.0 MB at most per task".
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        match (self.used_bytes, self.available_bytes, self.percent_used()) {
+            (Some(used), Some(available), Some(percent)) => parts.push(format!(
+                "{} of {} used ({}%)",
+                format_bytes(used),
+                format_bytes(used.saturating_add(available)),
+                percent
+            )),
+            (Some(used), None, _) => parts.push(format!("{} used, no quota", format_bytes(used))),
+            (None, Some(available), _) => {
+                parts.push(format!("{} available", format_bytes(available)))
+            }
+            _ => {}
+        }
+        if let Some(max) = self.max_resource_size {
+            parts.push(format!("{} at most per task", format_bytes(max)));
+        }
+        if parts.is_empty() {
+            "no limits reported".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// "512 B", "4.6 MB"…
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Whether a failed request was refused for lack of space, from its error message.
+pub fn is_storage_full(error: &str) -> bool {
+    let status = StatusCode::INSUFFICIENT_STORAGE;
+    // "BadStatusCode(507)" from libdav, "PUT failed: 507 Insufficient Storage" from our own
+    error.contains(&format!("({})", status.as_u16())) || error.contains(&status.to_string())
+}
+
+pub fn full_warning(calendar: &str) -> String {
+    format!(
+        "Calendar '{}' is full on the server (507 Insufficient Storage). Its pending changes \
+         are kept and held: free some space, then release them in the sync preview.",
+        calendar
+    )
+}
+
+pub fn near_full_warning(calendar: &str, limits: &StorageLimits) -> String {
+    format!(
+        "Calendar '{}' is nearly full on the server: {}.",
+        calendar,
+        limits.describe()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits() {
+        let limits = StorageLimits::from_properties(Some("4823450"), Some(" 419430 "), None);
+        assert_eq!(limits.percent_used(), Some(92));
+        assert!(limits.is_near_full());
+        assert_eq!(limits.describe(), "4.6 MB of 5.0 MB used (92%)");
+
+        let roomy = StorageLimits::from_properties(Some("1024"), None, Some("1048576"));
+        assert!(!roomy.is_near_full());
+        assert_eq!(
+            roomy.describe(),
+            "1.0 KB used, no quota, 1.0 MB at most per task"
+        );
+
+        let unknown = StorageLimits::from_properties(None, Some("lots"), None);
+        assert!(!unknown.is_known());
+        assert_eq!(unknown.describe(), "no limits reported");
+        assert_eq!(format_bytes(512), "512 B");
+
+        assert!(is_storage_full("BadStatusCode(507)"));
+        assert!(is_storage_full("PUT failed: 507 Insufficient Storage"));
+        assert!(!is_storage_full("BadStatusCode(500)"));
+    }
+}
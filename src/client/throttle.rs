@@ -0,0 +1,367 @@
+// File: ./src/client/throttle.rs
+// Politeness towards the server: every HTTP request of every client goes through `Throttled`,
+// which caps the requests in flight, spaces them out (`[request_limits]` in the config) and
+// waits and retries when the server answers 429 Too Many Requests (Nextcloud's brute-force
+// protection does on large syncs). Overloaded answers also lower the cap on requests in
+// flight, which climbs back as requests go through. The limits are process-wide, like the
+// server's budget.
+use chrono::{DateTime, Utc};
+use futures::future::{BoxFuture, poll_fn};
+use http::{HeaderMap, Request, Response, StatusCode, header::RETRY_AFTER};
+use serde::{Deserialize, Serialize};
+use std::sync::{LazyLock, Mutex, RwLock};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::{Instant, sleep_until};
+use tower_service::Service;
+
+/// Retries of a throttled request before its 429 is passed on
+const MAX_RETRIES: u32 = 3;
+/// A server asking to wait longer is treated as a refusal, a sync is not worth blocking on
+const MAX_WAIT: Duration = Duration::from_secs(60);
+/// Answers in a row without overload before a lowered cap goes up by one
+const RECOVER_AFTER: u32 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RequestLimits {
+    /// Requests started per second (0 = as fast as the server answers)
+    pub per_second: f64,
+    /// Requests waiting for an answer at the same time
+    pub concurrent: usize,
+}
+
+impl RequestLimits {
+    const DEFAULT: Self = Self {
+        per_second: 0.0,
+        concurrent: 4,
+    };
+
+    pub fn is_valid(&self) -> bool {
+        self.concurrent > 0 && self.per_second.is_finite() && self.per_second >= 0.0
+    }
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+static LIMITS: RwLock<RequestLimits> = RwLock::new(RequestLimits::DEFAULT);
+
+pub fn set_limits(limits: RequestLimits) {
+    if let Ok(mut current) = LIMITS.write() {
+        *current = limits;
+    }
+}
+
+pub fn limits() -> RequestLimits {
+    LIMITS.read().map(|l| *l).unwrap_or_default()
+}
+
+// --- GATE ---
+
+struct Gate {
+    in_flight: usize,
+    /// No request starts before this: the rate limit, or a pause asked for by the server
+    next_start: Option<Instant>,
+    /// Requests in flight allowed after 429/503 answers, None = `RequestLimits::concurrent`
+    lowered: Option<usize>,
+    /// Answers without overload since `lowered` last changed
+    calm: u32,
+}
+
+impl Gate {
+    const fn new() -> Self {
+        Self {
+            in_flight: 0,
+            next_start: None,
+            lowered: None,
+            calm: 0,
+        }
+    }
+
+    fn cap(&self, limits: RequestLimits) -> usize {
+        let configured = limits.concurrent.max(1);
+        self.lowered.map_or(configured, |c| c.min(configured))
+    }
+
+    /// Halves the cap when the server is overloaded, raises it back slowly otherwise.
+    fn answered(&mut self, limits: RequestLimits, overloaded: bool) {
+        if overloaded {
+            self.lowered = Some((self.cap(limits) / 2).max(1));
+            self.calm = 0;
+            return;
+        }
+        let Some(lowered) = self.lowered else {
+            return;
+        };
+        self.calm += 1;
+        if self.calm >= RECOVER_AFTER {
+            self.calm = 0;
+            self.lowered = Some(lowered + 1).filter(|c| *c < limits.concurrent);
+        }
+    }
+}
+
+static GATE: Mutex<Gate> = Mutex::new(Gate::new());
+static FREED: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// A place among the requests in flight, given back when dropped.
+struct Slot;
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        if let Ok(mut gate) = GATE.lock() {
+            gate.in_flight = gate.in_flight.saturating_sub(1);
+        }
+        FREED.notify_waiters();
+    }
+}
+
+/// The start time of a request given a free slot, None when all are taken.
+fn reserve(limits: RequestLimits) -> Option<Instant> {
+    let Ok(mut gate) = GATE.lock() else {
+        return Some(Instant::now());
+    };
+    if gate.in_flight >= gate.cap(limits) {
+        return None;
+    }
+    gate.in_flight += 1;
+    let now = Instant::now();
+    let start = gate.next_start.map_or(now, |t| t.max(now));
+    let interval = if limits.per_second > 0.0 {
+        Duration::from_secs_f64(1.0 / limits.per_second)
+    } else {
+        Duration::ZERO
+    };
+    gate.next_start = Some(start + interval);
+    Some(start)
+}
+
+async fn acquire() -> Slot {
+    loop {
+        // Registered before looking, so a slot freed in between still wakes us
+        let freed = FREED.notified();
+        if let Some(start) = reserve(limits()) {
+            let slot = Slot;
+            sleep_until(start).await;
+            return slot;
+        }
+        freed.await;
+    }
+}
+
+/// Requests in flight allowed right now: the configured limit, or less after 429/503 answers.
+pub fn concurrency() -> usize {
+    let limits = limits();
+    GATE.lock()
+        .map(|gate| gate.cap(limits))
+        .unwrap_or(limits.concurrent)
+}
+
+fn answered(status: StatusCode) {
+    let overloaded = matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+    );
+    if let Ok(mut gate) = GATE.lock() {
+        gate.answered(limits(), overloaded);
+    }
+}
+
+/// Holds back every request until `until`, not only the retried one.
+fn pause(until: Instant) {
+    if let Ok(mut gate) = GATE.lock() {
+        gate.next_start = Some(gate.next_start.map_or(until, |t| t.max(until)));
+    }
+}
+
+/// How long the server asks to wait (Retry-After in seconds or as an HTTP date). 429s without
+/// the header back off exponentially; other statuses are only retried when it is present.
+pub fn retry_delay(status: StatusCode, headers: &HeaderMap, attempt: u32) -> Option<Duration> {
+    let header = headers.get(RETRY_AFTER).and_then(|v| v.to_str().ok());
+    let asked = header.and_then(|value| {
+        let value = value.trim();
+        if let Ok(seconds) = value.parse::<u64>() {
+            return Some(Duration::from_secs(seconds));
+        }
+        let date = DateTime::parse_from_rfc2822(value)
+            .ok()?
+            .with_timezone(&Utc);
+        Some((date - Utc::now()).to_std().unwrap_or_default())
+    });
+    match status {
+        StatusCode::TOO_MANY_REQUESTS => {
+            Some(asked.unwrap_or(Duration::from_secs(1u64 << attempt.min(6))))
+        }
+        StatusCode::SERVICE_UNAVAILABLE => asked,
+        _ => None,
+    }
+}
+
+// --- SERVICE ---
+
+/// Wraps the HTTP client below libdav, so every request is throttled, raw ones included.
+#[derive(Clone, Debug)]
+pub struct Throttled<S> {
+    inner: S,
+}
+
+impl<S> Throttled<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+/// Requests are consumed when sent, retries need a fresh copy (extensions are not kept).
+fn copy_request(parts: &http::request::Parts, body: &str) -> Request<String> {
+    let mut req = Request::new(body.to_string());
+    *req.method_mut() = parts.method.clone();
+    *req.uri_mut() = parts.uri.clone();
+    *req.version_mut() = parts.version;
+    *req.headers_mut() = parts.headers.clone();
+    req
+}
+
+impl<S, B> Service<Request<String>> for Throttled<S>
+where
+    S: Service<Request<String>, Response = Response<B>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Send + 'static,
+    B: Send + 'static,
+{
+    type Response = Response<B>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response<B>, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<String>) -> Self::Future {
+        // The clone may not be ready, the one polled by `poll_ready` is
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        Box::pin(async move {
+            let (parts, body) = req.into_parts();
+            let mut attempt = 0;
+            loop {
+                let slot = acquire().await;
+                if attempt > 0 {
+                    poll_fn(|cx| inner.poll_ready(cx)).await?;
+                }
+                let response = inner.call(copy_request(&parts, &body)).await?;
+                drop(slot);
+                answered(response.status());
+                let delay = retry_delay(response.status(), response.headers(), attempt);
+                match delay {
+                    Some(wait) if attempt < MAX_RETRIES && wait <= MAX_WAIT => {
+                        attempt += 1;
+                        pause(Instant::now() + wait);
+                    }
+                    _ => return Ok(response),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+    use std::future::{Ready, ready};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_retry_delay() {
+        let mut headers = HeaderMap::new();
+        let too_many = StatusCode::TOO_MANY_REQUESTS;
+        let unavailable = StatusCode::SERVICE_UNAVAILABLE;
+        assert_eq!(
+            retry_delay(too_many, &headers, 2),
+            Some(Duration::from_secs(4))
+        );
+        assert_eq!(retry_delay(unavailable, &headers, 0), None);
+        assert_eq!(retry_delay(StatusCode::OK, &headers, 0), None);
+
+        headers.insert(RETRY_AFTER, "7".parse().unwrap());
+        assert_eq!(
+            retry_delay(unavailable, &headers, 0),
+            Some(Duration::from_secs(7))
+        );
+        let past = "Wed, 21 Oct 2015 07:28:00 GMT";
+        headers.insert(RETRY_AFTER, past.parse().unwrap());
+        assert_eq!(retry_delay(too_many, &headers, 0), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_cap_lowered_on_overload() {
+        let limits = RequestLimits {
+            per_second: 0.0,
+            concurrent: 4,
+        };
+        let mut gate = Gate::new();
+        gate.answered(limits, true);
+        assert_eq!(gate.cap(limits), 2);
+        gate.answered(limits, true);
+        gate.answered(limits, true);
+        assert_eq!(gate.cap(limits), 1);
+
+        for _ in 0..RECOVER_AFTER {
+            gate.answered(limits, false);
+        }
+        assert_eq!(gate.cap(limits), 2);
+        for _ in 0..2 * RECOVER_AFTER {
+            gate.answered(limits, false);
+        }
+        assert_eq!(gate.cap(limits), 4);
+        assert_eq!(gate.lowered, None);
+    }
+
+    /// Answers 429 (retry at once) to the first request, 200 to the others.
+    #[derive(Clone)]
+    struct Throttling {
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl Service<Request<String>> for Throttling {
+        type Response = Response<String>;
+        type Error = Infallible;
+        type Future = Ready<Result<Response<String>, Infallible>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, req: Request<String>) -> Self::Future {
+            let mut response = Response::new(req.into_body());
+            if self.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                *response.status_mut() = StatusCode::TOO_MANY_REQUESTS;
+                response
+                    .headers_mut()
+                    .insert(RETRY_AFTER, "0".parse().unwrap());
+            }
+            ready(Ok(response))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_429_is_retried() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut client = Throttled::new(Throttling {
+            calls: calls.clone(),
+        });
+        let response = client
+            .call(Request::new("BEGIN:VCALENDAR".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body(), "BEGIN:VCALENDAR");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,53 @@
+// File: ./src/client/user_agent.rs
+// The User-Agent sent with every request to the server: "cfait/<version>", or `user_agent`
+// from the config. Some gateways let only known clients through, and server admins can tell
+// cfait apart from other HTTP traffic in their logs.
+use http::HeaderValue;
+use std::sync::RwLock;
+
+pub const DEFAULT: &str = concat!("cfait/", env!("CARGO_PKG_VERSION"));
+
+static OVERRIDE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Replaces the default for the clients created from now on (None = back to the default).
+pub fn set(agent: Option<String>) {
+    if let Ok(mut current) = OVERRIDE.write() {
+        *current = agent.filter(|a| !a.trim().is_empty());
+    }
+}
+
+/// Whether `agent` can be sent as a header: visible ASCII and spaces, not empty.
+pub fn is_valid(agent: &str) -> bool {
+    !agent.trim().is_empty() && agent.bytes().all(|b| b == b' ' || b.is_ascii_graphic())
+}
+
+/// The header value to send; an invalid override falls back to the default.
+pub fn header_value() -> HeaderValue {
+    OVERRIDE
+        .read()
+        .ok()
+        .and_then(|agent| agent.as_deref().filter(|a| is_valid(a)).map(str::to_string))
+        .and_then(|agent| HeaderValue::from_str(&agent).ok())
+        .unwrap_or(HeaderValue::from_static(DEFAULT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_agent() {
+        assert!(DEFAULT.starts_with("cfait/"));
+        assert!(is_valid("AcmeGateway-Approved/1.0 (cfait)"));
+        assert!(!is_valid("  "));
+        assert!(!is_valid("cfait\r\nX-Injected: 1"));
+        assert!(!is_valid("café/1.0"));
+
+        set(Some("Corp/2.0".to_string()));
+        assert_eq!(header_value(), "Corp/2.0");
+        set(Some("bad\nagent".to_string()));
+        assert_eq!(header_value(), DEFAULT);
+        set(None);
+        assert_eq!(header_value(), DEFAULT);
+    }
+}
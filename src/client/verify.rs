@@ -0,0 +1,247 @@
+// File: ./src/client/verify.rs
+// Audit of a calendar's mirror against the server (`cfait verify`): tasks the server deleted
+// but the mirror kept (they come back after a delete while the CTag matches), resources never
+// mirrored, outdated ETags and content that differs under the same ETag.
+use crate::client::core::{CONTENT_HASH_PREFIX, normalize_etag};
+use crate::model::Task;
+use crate::model::href;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Drift {
+    /// On the server, not in the mirror
+    Missing,
+    /// In the mirror, deleted from the server
+    Ghost,
+    /// The mirror's ETag is not the server's: changed since it was mirrored
+    Outdated,
+    /// Same ETag, different content: the mirror is corrupt
+    Corrupt,
+}
+
+impl Drift {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Drift::Missing => "on the server, not in the local copy",
+            Drift::Ghost => "deleted on the server, still in the local copy",
+            Drift::Outdated => "changed on the server since it was copied",
+            Drift::Corrupt => "differs from the server under the same ETag",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub href: String,
+    pub summary: String,
+    pub drift: Drift,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VerifyReport {
+    /// Resources listed by the server
+    pub listed: usize,
+    /// Resources downloaded and compared
+    pub downloaded: usize,
+    pub findings: Vec<Finding>,
+    /// The mirror was rewritten with the server's copies
+    pub repaired: bool,
+}
+
+/// Content hashes stand in for missing ETags and can't be compared with the listing.
+fn same_etag(cached: &str, listed: &str) -> bool {
+    cached.starts_with(CONTENT_HASH_PREFIX) || normalize_etag(cached) == normalize_etag(listed)
+}
+
+/// Mirrored tasks by href; those without one are queued creations, not the server's.
+fn by_href(cached: &[Task]) -> BTreeMap<String, Vec<&Task>> {
+    let mut map: BTreeMap<String, Vec<&Task>> = BTreeMap::new();
+    for task in cached.iter().filter(|t| !t.href.is_empty()) {
+        map.entry(href::normalize(&task.href))
+            .or_default()
+            .push(task);
+    }
+    map
+}
+
+/// The hrefs to download: missing and outdated resources, and the ones whose ETag still
+/// matches (where corruption hides), at most `sample` of those spread over the calendar.
+pub fn to_download(
+    cached: &[Task],
+    listed: &BTreeMap<String, String>,
+    sample: Option<usize>,
+) -> Vec<String> {
+    let mirrored = by_href(cached);
+    let (mut unchanged, mut changed) = (Vec::new(), Vec::new());
+    for (href, etag) in listed {
+        match mirrored.get(href) {
+            Some(tasks) if tasks.iter().all(|t| same_etag(&t.etag, etag)) => {
+                unchanged.push(href.clone())
+            }
+            _ => changed.push(href.clone()),
+        }
+    }
+    if let Some(n) = sample
+        && n < unchanged.len()
+    {
+        let len = unchanged.len();
+        unchanged = (0..n).map(|i| unchanged[i * len / n].clone()).collect();
+    }
+    changed.extend(unchanged);
+    changed
+}
+
+/// The fields kept by the mirror, without the view state and ETag formatting.
+fn comparable(tasks: &[&Task]) -> Vec<Task> {
+    let mut tasks: Vec<Task> = tasks
+        .iter()
+        .map(|t| Task {
+            etag: String::new(),
+            depth: 0,
+            hidden_subtasks: 0,
+            ..(*t).clone()
+        })
+        .collect();
+    tasks.sort_by(|a, b| a.uid.cmp(&b.uid));
+    tasks
+}
+
+/// Compares the mirror with the server's listing (href -> ETag) and the downloaded resources.
+pub fn compare(
+    cached: &[Task],
+    listed: &BTreeMap<String, String>,
+    fetched: &HashMap<String, Vec<Task>>,
+) -> Vec<Finding> {
+    let mirrored = by_href(cached);
+    let mut findings = Vec::new();
+    let finding = |href: &str, summary: &str, drift| Finding {
+        href: href.to_string(),
+        summary: summary.to_string(),
+        drift,
+    };
+    for (href, tasks) in &mirrored {
+        let Some(etag) = listed.get(href) else {
+            // Without an ETag it was never on the server: a queued creation
+            for task in tasks.iter().filter(|t| !t.etag.is_empty()) {
+                findings.push(finding(href, &task.summary, Drift::Ghost));
+            }
+            continue;
+        };
+        if !tasks.iter().all(|t| same_etag(&t.etag, etag)) {
+            findings.push(finding(href, &tasks[0].summary, Drift::Outdated));
+        } else if let Some(server) = fetched.get(href)
+            && comparable(tasks) != comparable(&server.iter().collect::<Vec<_>>())
+        {
+            findings.push(finding(href, &tasks[0].summary, Drift::Corrupt));
+        }
+    }
+    for href in listed.keys().filter(|h| !mirrored.contains_key(*h)) {
+        let summary = fetched
+            .get(href)
+            .and_then(|tasks| tasks.first())
+            .map_or("", |t| t.summary.as_str());
+        findings.push(finding(href, summary, Drift::Missing));
+    }
+    findings
+}
+
+/// The mirror with the findings fixed: ghosts dropped, the others replaced by the server's
+/// copies (or dropped when not downloaded, so the next sync fetches them).
+pub fn repair(
+    cached: &[Task],
+    findings: &[Finding],
+    fetched: &HashMap<String, Vec<Task>>,
+) -> Vec<Task> {
+    let drifted: HashSet<&str> = findings.iter().map(|f| f.href.as_str()).collect();
+    let mut tasks: Vec<Task> = cached
+        .iter()
+        .filter(|t| !drifted.contains(href::normalize(&t.href).as_str()))
+        .cloned()
+        .collect();
+    for href in &drifted {
+        if let Some(server) = fetched.get(*href) {
+            tasks.extend(server.iter().cloned());
+        }
+    }
+    tasks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(uid: &str, href: &str, etag: &str) -> Task {
+        let mut t = Task::new(uid, &HashMap::new());
+        t.uid = uid.to_string();
+        t.href = href.to_string();
+        t.etag = etag.to_string();
+        t
+    }
+
+    #[test]
+    fn test_compare_and_repair() {
+        let cached = vec![
+            task("kept", "/c/kept.ics", "\"1\""),
+            task("ghost", "/c/ghost.ics", "\"1\""),
+            task("old", "/c/old.ics", "\"1\""),
+            task("corrupt", "/c/corrupt.ics", "\"1\""),
+            task("queued", "", ""),
+        ];
+        let listed: BTreeMap<String, String> = [
+            ("/c/kept.ics", "W/\"1\""),
+            ("/c/old.ics", "\"2\""),
+            ("/c/corrupt.ics", "\"1\""),
+            ("/c/new.ics", "\"1\""),
+        ]
+        .into_iter()
+        .map(|(h, e)| (h.to_string(), e.to_string()))
+        .collect();
+
+        let all = to_download(&cached, &listed, None);
+        assert_eq!(all.len(), 4);
+        let sampled = to_download(&cached, &listed, Some(1));
+        assert_eq!(sampled, vec!["/c/new.ics", "/c/old.ics", "/c/corrupt.ics"]);
+
+        let mut corrupt = task("corrupt", "/c/corrupt.ics", "\"1\"");
+        corrupt.summary = "Changed".to_string();
+        let fetched: HashMap<String, Vec<Task>> = [
+            task("kept", "/c/kept.ics", "W/\"1\""),
+            task("old", "/c/old.ics", "\"2\""),
+            corrupt,
+            task("new", "/c/new.ics", "\"1\""),
+        ]
+        .into_iter()
+        .map(|t| (t.href.clone(), vec![t]))
+        .collect();
+        let findings = compare(&cached, &listed, &fetched);
+        let drifts: Vec<_> = findings
+            .iter()
+            .map(|f| (f.href.as_str(), f.drift))
+            .collect();
+        assert_eq!(
+            drifts,
+            vec![
+                ("/c/corrupt.ics", Drift::Corrupt),
+                ("/c/ghost.ics", Drift::Ghost),
+                ("/c/old.ics", Drift::Outdated),
+                ("/c/new.ics", Drift::Missing),
+            ]
+        );
+
+        let mut repaired: Vec<_> = repair(&cached, &findings, &fetched)
+            .into_iter()
+            .map(|t| (t.uid, t.etag))
+            .collect();
+        repaired.sort();
+        assert_eq!(
+            repaired,
+            vec![
+                ("corrupt".to_string(), "\"1\"".to_string()),
+                ("kept".to_string(), "\"1\"".to_string()),
+                ("new".to_string(), "\"1\"".to_string()),
+                ("old".to_string(), "\"2\"".to_string()),
+                ("queued".to_string(), String::new()),
+            ]
+        );
+    }
+}
@@ -1,5 +1,113 @@
 // File: src/color_utils.rs
+use crate::model::Priority;
+use crate::store::Urgency;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static PALETTE: RwLock<Palette> = RwLock::new(Palette::Default);
+static UNDERLINE_PRIORITY: AtomicBool = AtomicBool::new(false);
+
+/// Colors of priorities and tags (`palette` in the config)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Palette {
+    #[default]
+    Default,
+    /// Brighter, fully saturated colors
+    HighContrast,
+    /// Blues and oranges only, told apart without red-green vision
+    Deuteranopia,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 3] = [
+        Palette::Default,
+        Palette::HighContrast,
+        Palette::Deuteranopia,
+    ];
+}
+
+impl fmt::Display for Palette {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Palette::Default => "Default",
+            Palette::HighContrast => "High contrast",
+            Palette::Deuteranopia => "Colorblind safe (deuteranopia)",
+        })
+    }
+}
+
+/// Installs the config's palette and priority display (done on every config load).
+pub fn set_accessibility(palette: Palette, underline_priority: bool) {
+    if let Ok(mut current) = PALETTE.write() {
+        *current = palette;
+    }
+    UNDERLINE_PRIORITY.store(underline_priority, Ordering::Relaxed);
+}
+
+pub fn palette() -> Palette {
+    PALETTE.read().map(|p| *p).unwrap_or_default()
+}
+
+/// Priorities are shown by underlining instead of colors
+pub fn underline_priority() -> bool {
+    UNDERLINE_PRIORITY.load(Ordering::Relaxed)
+}
+
+/// Text color of a priority (1-9) in the current palette; None = the UI's own gradient.
+pub fn priority_color(priority: Priority) -> Option<(u8, u8, u8)> {
+    palette_priority_color(palette(), priority.value())
+}
+
+fn palette_priority_color(palette: Palette, priority: u8) -> Option<(u8, u8, u8)> {
+    const HIGH_CONTRAST: [(u8, u8, u8); 9] = [
+        (255, 80, 80),
+        (255, 140, 0),
+        (255, 190, 0),
+        (255, 230, 0),
+        (255, 255, 255),
+        (160, 220, 255),
+        (160, 200, 255),
+        (190, 190, 255),
+        (210, 210, 210),
+    ];
+    // Okabe-Ito: vermillion and orange for urgent, sky blue for low
+    const DEUTERANOPIA: [(u8, u8, u8); 9] = [
+        (213, 94, 0),
+        (230, 159, 0),
+        (240, 190, 60),
+        (240, 228, 66),
+        (255, 255, 255),
+        (86, 180, 233),
+        (120, 170, 230),
+        (150, 160, 220),
+        (180, 180, 200),
+    ];
+    let colors = match palette {
+        Palette::Default => return None,
+        Palette::HighContrast => &HIGH_CONTRAST,
+        Palette::Deuteranopia => &DEUTERANOPIA,
+    };
+    colors.get((priority as usize).checked_sub(1)?).copied()
+}
+
+/// Color of a due date in the current palette: overdue, due today, due soon.
+pub fn urgency_color(urgency: Urgency) -> Option<(u8, u8, u8)> {
+    let [overdue, today, soon] = match palette() {
+        Palette::Default => [(255, 85, 85), (255, 165, 0), (240, 220, 110)],
+        Palette::HighContrast => [(255, 80, 80), (255, 190, 0), (255, 255, 160)],
+        Palette::Deuteranopia => [(213, 94, 0), (230, 159, 0), (240, 228, 66)],
+    };
+    match urgency {
+        Urgency::None => None,
+        Urgency::Soon => Some(soon),
+        Urgency::Today => Some(today),
+        Urgency::Overdue => Some(overdue),
+    }
+}
 
 /// Generates a deterministic color tuple (r, g, b) in [0.0, 1.0] range based on the input string.
 /// Ranges updated to S: 40-90, L: 65-90 per user request.
@@ -24,7 +132,18 @@ pub fn generate_color(tag: &str) -> (f32, f32, f32) {
     // 0.65 + 0.25 = 0.90
     let l = 0.65 + ((hash_l % 26) as f32 / 100.0);
 
-    hsl_to_rgb(h, s, l)
+    match palette() {
+        Palette::Default => hsl_to_rgb(h, s, l),
+        Palette::HighContrast => hsl_to_rgb(h, 1.0, 0.75 + ((hash_l % 11) as f32 / 100.0)),
+        Palette::Deuteranopia => {
+            let h = if hash % 2 == 0 {
+                190.0 + (hash_s % 60) as f32
+            } else {
+                25.0 + (hash_s % 30) as f32
+            };
+            hsl_to_rgb(h, s, l)
+        }
+    }
 }
 
 /// Helper: HSL to RGB conversion
@@ -79,3 +198,20 @@ pub fn parse_hex_to_u8(hex: &str) -> Option<(u8, u8, u8)> {
 
     Some((r, g, b))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_palette_priority_colors() {
+        assert_eq!(palette_priority_color(Palette::Default, 1), None);
+        for palette in [Palette::HighContrast, Palette::Deuteranopia] {
+            assert_eq!(palette_priority_color(palette, 0), None);
+            assert!((1..=9).all(|p| palette_priority_color(palette, p).is_some()));
+            assert_eq!(palette_priority_color(palette, 10), None);
+        }
+        let parsed: Palette = serde_json::from_str("\"high-contrast\"").unwrap();
+        assert_eq!(parsed, Palette::HighContrast);
+    }
+}
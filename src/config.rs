@@ -1,10 +1,26 @@
 // File: src/config.rs
+use crate::cache;
+use crate::client::throttle::{self, RequestLimits};
+use crate::client::{user_agent, wirelog};
+use crate::color_utils::{self, Palette};
+use crate::i18n::{self, tr, tr_args};
+use crate::model::due::DueDisplay;
+use crate::model::priority::{self, Priority, PriorityBand};
+use crate::model::syntax::{self, SmartSyntax};
+use crate::model::template::TaskTemplate;
+use crate::model::{Duration, Task};
 use crate::paths::AppPaths;
 use crate::storage::LocalStorage;
+use crate::store::{NewTaskPlacement, NotStarted};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::sync::Mutex;
+
+/// Last config contents written or applied, so the watcher ignores our own saves
+/// and the duplicate events editors tend to produce.
+static LAST_SEEN: Mutex<Option<String>> = Mutex::new(None);
 
 fn default_true() -> bool {
     true
@@ -12,6 +28,153 @@ fn default_true() -> bool {
 fn default_cutoff() -> Option<u32> {
     Some(6)
 }
+fn default_review_weeks() -> u32 {
+    4
+}
+fn default_due_soon_days() -> u32 {
+    3
+}
+fn default_tui_columns() -> Vec<TuiColumn> {
+    DEFAULT_TUI_COLUMNS.to_vec()
+}
+fn default_font_size() -> u16 {
+    20
+}
+fn default_imap_port() -> u16 {
+    993
+}
+fn default_imap_folder() -> String {
+    "INBOX".to_string()
+}
+fn default_imap_interval() -> u32 {
+    5
+}
+
+/// Task titles in the GUI, in pixels; the smaller texts of the list follow them
+pub const FONT_SIZES: std::ops::RangeInclusive<u16> = 12..=32;
+
+/// Spacing of the GUI task list
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Density {
+    #[default]
+    Comfortable,
+    /// Less padding around rows and buttons, to fit more tasks on screen
+    Compact,
+}
+
+/// A field of the TUI task list rows. Tags and the calendar are aligned to the right edge,
+/// the others follow each other from the left, both in the order listed.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TuiColumn {
+    /// "[x]", the brackets in the calendar's color
+    Checkbox,
+    /// "!1" to "!9"
+    Priority,
+    /// With "[B]" when blocked and "[+n]" when subtasks are folded; always shown
+    Title,
+    Duration,
+    Due,
+    /// "(R)" on recurring tasks
+    Recurrence,
+    Tags,
+    /// "@name" (shortened) in the calendar's color, only when the list mixes calendars
+    Calendar,
+}
+
+pub const DEFAULT_TUI_COLUMNS: [TuiColumn; 7] = [
+    TuiColumn::Checkbox,
+    TuiColumn::Title,
+    TuiColumn::Duration,
+    TuiColumn::Due,
+    TuiColumn::Recurrence,
+    TuiColumn::Tags,
+    TuiColumn::Calendar,
+];
+
+/// Which mails of the polled folder become tasks
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MailSelect {
+    /// Marked as read once imported
+    #[default]
+    Unread,
+    /// Left as they are; the Message-ID keeps them from being imported twice
+    Flagged,
+}
+
+/// Mail account polled by `cfait daemon` (built with the `imap` feature).
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct ImapConfig {
+    pub host: String,
+    /// IMAP over TLS
+    #[serde(default = "default_imap_port")]
+    pub port: u16,
+    pub username: String,
+    pub password: String,
+    #[serde(default = "default_imap_folder")]
+    pub folder: String,
+    #[serde(default)]
+    pub select: MailSelect,
+    /// Calendar name or href of the new tasks (None = default_calendar)
+    #[serde(default)]
+    pub calendar: Option<String>,
+    #[serde(default = "default_imap_interval")]
+    pub interval_minutes: u32,
+}
+
+/// Values given to tasks created in a calendar, unless the smart input sets them itself.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CalendarDefaults {
+    #[serde(default)]
+    pub priority: Option<Priority>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Estimated duration, in minutes in the file
+    #[serde(default)]
+    pub duration: Option<Duration>,
+}
+
+impl CalendarDefaults {
+    /// Reads the priority, tags and duration out of smart syntax (e.g. "!5 #work ~30m").
+    pub fn from_smart_string(input: &str) -> Self {
+        let parsed = Task::new(input, &HashMap::new());
+        Self {
+            priority: parsed.priority.is_set().then_some(parsed.priority),
+            tags: parsed.categories,
+            duration: parsed.estimated_duration,
+        }
+    }
+
+    pub fn to_smart_string(&self) -> String {
+        let mut t = Task::new("", &HashMap::new());
+        t.priority = self.priority.unwrap_or_default();
+        t.categories = self.tags.clone();
+        t.estimated_duration = self.duration;
+        t.to_smart_string().trim().to_string()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.priority.is_none() && self.tags.is_empty() && self.duration.is_none()
+    }
+
+    pub fn apply(&self, task: &mut Task) {
+        if !task.priority.is_set()
+            && let Some(p) = self.priority
+        {
+            task.priority = p;
+        }
+        if task.estimated_duration.is_none() {
+            task.estimated_duration = self.duration;
+        }
+        for tag in &self.tags {
+            if !task.categories.contains(tag) {
+                task.categories.push(tag.clone());
+            }
+        }
+    }
+}
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Config {
@@ -21,6 +184,9 @@ pub struct Config {
     pub default_calendar: Option<String>,
     #[serde(default)]
     pub allow_insecure_certs: bool,
+    /// WebSocket announcing server changes, e.g. Nextcloud notify_push (wss://host/push/ws)
+    #[serde(default)]
+    pub push_url: Option<String>,
     #[serde(default)]
     pub hidden_calendars: Vec<String>,
     #[serde(default)]
@@ -31,8 +197,80 @@ pub struct Config {
     pub hide_fully_completed_tags: bool,
     #[serde(default = "default_cutoff")]
     pub sort_cutoff_months: Option<u32>,
+    /// Only list done tasks completed within this many days (None = all)
+    #[serde(default)]
+    pub completed_retention_days: Option<u32>,
+    /// Desktop notification this many minutes before a task is due (None = off)
+    #[serde(default)]
+    pub notify_before_minutes: Option<u32>,
     #[serde(default)]
     pub tag_aliases: HashMap<String, Vec<String>>,
+    /// Keyed by calendar href
+    #[serde(default)]
+    pub calendar_defaults: HashMap<String, CalendarDefaults>,
+    #[serde(default)]
+    pub templates: Vec<TaskTemplate>,
+    /// Moving a task to another calendar brings its subtasks along
+    #[serde(default = "default_true")]
+    pub move_subtree: bool,
+    /// Where new tasks go in the list: sorted, top, bottom or after-selection
+    #[serde(default)]
+    pub new_task_placement: NewTaskPlacement,
+    /// Names and colors of priority ranges (empty = High 1-4, Medium 5, Low 6-9)
+    #[serde(default)]
+    pub priority_bands: Vec<PriorityBand>,
+    /// Extra prefixes and local words for the smart input
+    #[serde(default)]
+    pub smart_syntax: SmartSyntax,
+    /// The review goes through open tasks not modified for this many weeks
+    #[serde(default = "default_review_weeks")]
+    pub review_after_weeks: u32,
+    #[serde(default)]
+    pub gui_density: Density,
+    #[serde(default = "default_font_size")]
+    pub gui_font_size: u16,
+    /// Colors of priorities and tags: "default", "high-contrast" or "deuteranopia"
+    #[serde(default)]
+    pub palette: Palette,
+    /// Underline prioritized tasks (bold for the most urgent band) instead of coloring them
+    #[serde(default)]
+    pub underline_priority: bool,
+    /// Due dates as "tomorrow" / "in 3 d" ("relative") or in full ("absolute")
+    #[serde(default)]
+    pub due_display: DueDisplay,
+    /// Open tasks due within this many days are highlighted as due soon (0 = off)
+    #[serde(default = "default_due_soon_days")]
+    pub due_soon_days: u32,
+    /// Open tasks starting on a later day: "show", "dim" or "hide" them until then
+    #[serde(default)]
+    pub not_started: NotStarted,
+    /// Fields of the TUI task list rows and their order, see `TuiColumn`
+    #[serde(default = "default_tui_columns")]
+    pub tui_columns: Vec<TuiColumn>,
+    /// Language of the UI ("en", "de", "fr"); None = the system's
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Turns mails into tasks, see `ImapConfig`
+    #[serde(default)]
+    pub imap: Option<ImapConfig>,
+    /// Politeness towards the server: requests per second and at the same time
+    #[serde(default)]
+    pub request_limits: RequestLimits,
+    /// Logs every request to the server (credentials blanked) to `wire.log` in the data dir
+    #[serde(default)]
+    pub wire_log: bool,
+    /// Done tasks completed more than this many days ago are dropped from the local copies
+    /// after each sync (None = kept)
+    #[serde(default)]
+    pub prune_completed_days: Option<u32>,
+    /// Calendar name or href quick-added tasks land in until the triage files them
+    /// (None = the calendar shown)
+    #[serde(default)]
+    pub inbox_calendar: Option<String>,
+    /// Sent as User-Agent instead of "cfait/<version>", for gateways letting only known
+    /// clients through (None = the default)
+    #[serde(default)]
+    pub user_agent: Option<String>,
 }
 
 // --- ADDED THIS IMPLEMENTATION ---
@@ -44,18 +282,79 @@ impl Default for Config {
             password: String::new(),
             default_calendar: None,
             allow_insecure_certs: false,
+            push_url: None,
             hidden_calendars: Vec::new(),
             disabled_calendars: Vec::new(),
             hide_completed: false,
             // Match the serde defaults
             hide_fully_completed_tags: true,
             sort_cutoff_months: Some(6),
+            completed_retention_days: None,
+            notify_before_minutes: None,
             tag_aliases: HashMap::new(),
+            calendar_defaults: HashMap::new(),
+            templates: Vec::new(),
+            move_subtree: true,
+            new_task_placement: NewTaskPlacement::Sorted,
+            priority_bands: Vec::new(),
+            smart_syntax: SmartSyntax::default(),
+            review_after_weeks: 4,
+            gui_density: Density::Comfortable,
+            gui_font_size: 20,
+            palette: Palette::Default,
+            underline_priority: false,
+            due_display: DueDisplay::Relative,
+            due_soon_days: 3,
+            not_started: NotStarted::Show,
+            tui_columns: default_tui_columns(),
+            language: None,
+            imap: None,
+            request_limits: RequestLimits::default(),
+            wire_log: false,
+            prune_completed_days: None,
+            inbox_calendar: None,
+            user_agent: None,
         }
     }
 }
 // --------------------------------
 
+pub fn validate_alias(key: &str, tags: &[String]) -> std::result::Result<(), String> {
+    if key.is_empty() || key.contains(char::is_whitespace) {
+        return Err(format!("Invalid alias '#{}': use a single word", key));
+    }
+    if tags.is_empty() {
+        return Err(format!("Alias '#{}' needs at least one tag", key));
+    }
+    if tags.iter().any(|t| t == key) {
+        return Err(format!("Alias '#{}' can't expand to itself", key));
+    }
+    Ok(())
+}
+
+/// `existing` are the templates defined before this one (names must be unique).
+pub fn validate_template(
+    template: &TaskTemplate,
+    existing: &[TaskTemplate],
+) -> std::result::Result<(), String> {
+    if template.name.is_empty() || template.name.contains(char::is_whitespace) {
+        return Err(format!(
+            "Invalid template name '{}': use a single word",
+            template.name
+        ));
+    }
+    if template.title.trim().is_empty() {
+        return Err(format!("Template '{}' needs a title", template.name));
+    }
+    if existing
+        .iter()
+        .any(|t| t.name.eq_ignore_ascii_case(&template.name))
+    {
+        return Err(format!("Template '{}' already exists", template.name));
+    }
+    Ok(())
+}
+
 impl Config {
     // ... keep existing implementation ...
     pub fn load() -> Result<Self> {
@@ -63,6 +362,17 @@ impl Config {
         if path.exists() {
             let contents = fs::read_to_string(path)?;
             let config: Config = toml::from_str(&contents)?;
+            if let Ok(mut seen) = LAST_SEEN.lock() {
+                *seen = Some(contents);
+            }
+            priority::set_bands(config.priority_bands.clone());
+            syntax::set(config.smart_syntax.clone());
+            color_utils::set_accessibility(config.palette, config.underline_priority);
+            i18n::set_language(config.language.as_deref());
+            throttle::set_limits(config.request_limits);
+            wirelog::set_enabled(config.wire_log);
+            cache::set_prune_days(config.prune_completed_days);
+            user_agent::set(config.user_agent.clone());
             return Ok(config);
         }
         Err(anyhow::anyhow!("Config file not found"))
@@ -72,14 +382,222 @@ impl Config {
         let path = AppPaths::get_config_file_path()?;
         LocalStorage::with_lock(&path, || {
             let toml_str = toml::to_string_pretty(self)?;
+            if let Ok(mut seen) = LAST_SEEN.lock() {
+                *seen = Some(toml_str.clone());
+            }
             LocalStorage::atomic_write(&path, toml_str)?;
             Ok(())
         })?;
         Ok(())
     }
 
+    /// Checks the values that can't be expressed by the types alone.
+    /// Returns a message meant to be shown to the user.
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        let url = self.url.trim();
+        if !url.is_empty() && !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err("Server URL must start with http:// or https://".to_string());
+        }
+        if let Some(push) = &self.push_url
+            && !push.starts_with("ws://")
+            && !push.starts_with("wss://")
+        {
+            return Err("Push URL must start with ws:// or wss://".to_string());
+        }
+        for (key, tags) in &self.tag_aliases {
+            validate_alias(key, tags)?;
+        }
+        for (i, template) in self.templates.iter().enumerate() {
+            validate_template(template, &self.templates[..i])?;
+        }
+        priority::validate_bands(&self.priority_bands)?;
+        self.smart_syntax.validate()?;
+        if self.review_after_weeks == 0 {
+            return Err(tr("error.review_weeks").to_string());
+        }
+        let mut columns = HashSet::new();
+        if !self.tui_columns.contains(&TuiColumn::Title)
+            || !self.tui_columns.iter().all(|c| columns.insert(*c))
+        {
+            return Err(tr("error.tui_columns").to_string());
+        }
+        if !FONT_SIZES.contains(&self.gui_font_size) {
+            return Err(tr_args(
+                "error.font_size",
+                &[("min", FONT_SIZES.start()), ("max", FONT_SIZES.end())],
+            ));
+        }
+        if let Some(language) = &self.language
+            && !i18n::is_available(language)
+        {
+            return Err(tr_args(
+                "error.unknown_language",
+                &[
+                    ("language", language),
+                    ("available", &i18n::available().join(", ")),
+                ],
+            ));
+        }
+        if let Some(imap) = &self.imap {
+            if imap.host.trim().is_empty() || imap.username.is_empty() {
+                return Err(tr("error.imap_host").to_string());
+            }
+            if imap.interval_minutes == 0 {
+                return Err(tr("error.imap_interval").to_string());
+            }
+        }
+        if !self.request_limits.is_valid() {
+            return Err(tr("error.request_limits").to_string());
+        }
+        if let Some(agent) = &self.user_agent
+            && !user_agent::is_valid(agent)
+        {
+            return Err(tr("error.user_agent").to_string());
+        }
+        Ok(())
+    }
+
+    /// Calls `on_change` whenever another program modifies the config file.
+    /// The file is parsed and validated; errors are passed on so the UI can show them.
+    /// Watching stops when the returned watcher is dropped.
+    #[cfg(any(feature = "tui", feature = "gui"))]
+    pub fn watch(
+        on_change: impl Fn(std::result::Result<Config, String>) + Send + 'static,
+    ) -> Result<notify::RecommendedWatcher> {
+        use notify::{RecursiveMode, Watcher};
+
+        let path = AppPaths::get_config_file_path()?;
+        let dir = path
+            .parent()
+            .map(|p| p.to_path_buf())
+            .ok_or_else(|| anyhow::anyhow!("Config file has no parent directory"))?;
+        let file_name = path.file_name().map(|n| n.to_os_string());
+
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+                let Ok(event) = res else { return };
+                if !(event.kind.is_modify() || event.kind.is_create())
+                    || !event
+                        .paths
+                        .iter()
+                        .any(|p| p.file_name() == file_name.as_deref())
+                {
+                    return;
+                }
+                let Ok(contents) = fs::read_to_string(&path) else {
+                    return;
+                };
+                if let Ok(mut seen) = LAST_SEEN.lock() {
+                    if seen.as_deref() == Some(contents.as_str()) {
+                        return;
+                    }
+                    *seen = Some(contents.clone());
+                }
+                let parsed = toml::from_str::<Config>(&contents).map_err(|e| e.to_string());
+                let parsed = parsed.and_then(|c| c.validate().map(|_| c));
+                if let Ok(c) = &parsed {
+                    priority::set_bands(c.priority_bands.clone());
+                    syntax::set(c.smart_syntax.clone());
+                    color_utils::set_accessibility(c.palette, c.underline_priority);
+                    i18n::set_language(c.language.as_deref());
+                    throttle::set_limits(c.request_limits);
+                    wirelog::set_enabled(c.wire_log);
+                    cache::set_prune_days(c.prune_completed_days);
+                    user_agent::set(c.user_agent.clone());
+                }
+                on_change(parsed);
+            })?;
+        // Editors and our own atomic writes replace the file, so watch its directory
+        watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+        Ok(watcher)
+    }
+
     pub fn get_path_string() -> Result<String> {
         let path = AppPaths::get_config_file_path()?;
         Ok(path.to_string_lossy().to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calendar_defaults_fill_only_unset_fields() {
+        let defaults = CalendarDefaults::from_smart_string("!5 #work ~30m");
+        assert_eq!(defaults.priority, Some(Priority::MEDIUM));
+        assert_eq!(defaults.tags, vec!["work".to_string()]);
+        assert_eq!(defaults.duration, Some(Duration::from_minutes(30)));
+        assert_eq!(defaults.to_smart_string(), "!5 ~30m #work");
+
+        let mut task = Task::new("Review PR !1 #rust", &HashMap::new());
+        defaults.apply(&mut task);
+        assert_eq!(task.priority, Priority::HIGH);
+        assert_eq!(task.estimated_duration, defaults.duration);
+        assert_eq!(
+            task.categories,
+            vec!["rust".to_string(), "work".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_values() {
+        let mut config = Config {
+            url: "https://dav.example.com".to_string(),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.url = "dav.example.com".to_string();
+        assert!(config.validate().is_err());
+        config.url.clear();
+
+        config.push_url = Some("https://cloud.example.com/push/ws".to_string());
+        assert!(config.validate().is_err());
+        config.push_url = None;
+
+        config
+            .tag_aliases
+            .insert("cfait".to_string(), vec!["cfait".to_string()]);
+        assert!(config.validate().is_err());
+        config.tag_aliases.clear();
+
+        let imap: ImapConfig =
+            toml::from_str("host = \"imap.example.com\"\nusername = \"me\"\npassword = \"\"")
+                .unwrap();
+        assert_eq!((imap.port, imap.folder.as_str()), (993, "INBOX"));
+        config.imap = Some(ImapConfig {
+            interval_minutes: 0,
+            ..imap
+        });
+        assert!(config.validate().is_err());
+        config.imap = None;
+
+        config.request_limits.concurrent = 0;
+        assert!(config.validate().is_err());
+        config.request_limits = RequestLimits {
+            per_second: -1.0,
+            concurrent: 8,
+        };
+        assert!(config.validate().is_err());
+        config.request_limits.per_second = 0.5;
+        assert!(config.validate().is_ok());
+        config.request_limits = RequestLimits::default();
+
+        config.tui_columns = vec![TuiColumn::Due, TuiColumn::Tags];
+        assert!(config.validate().is_err());
+        config.tui_columns = vec![TuiColumn::Title, TuiColumn::Due, TuiColumn::Due];
+        assert!(config.validate().is_err());
+        config.tui_columns = vec![TuiColumn::Priority, TuiColumn::Title, TuiColumn::Calendar];
+        assert!(config.validate().is_ok());
+        config.tui_columns = default_tui_columns();
+
+        let template = TaskTemplate {
+            name: "release".to_string(),
+            title: "Release {title}".to_string(),
+            ..Default::default()
+        };
+        config.templates = vec![template.clone(), template];
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,311 @@
+// File: ./src/demo.rs
+// Demo mode (`--demo` or `CFAIT_DEMO=1`): the TUI or GUI opens on generated calendars and
+// tasks, for screenshots, UI work and a first look without a server. Everything is kept in a
+// throwaway directory, so the real config, tasks and caches are neither read nor written, and
+// there is no server to reach: the sample calendars stand in for it.
+use crate::cache::Cache;
+use crate::config::Config;
+use crate::model::recurrence::{Frequency, Recurrence};
+use crate::model::{
+    CalendarListEntry, Duration, Priority, Reminder, Task, TaskBuilder, TaskStatus,
+};
+use crate::paths::AppPaths;
+use crate::storage::{LOCAL_CALENDAR_HREF, LocalStorage};
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate, TimeDelta, Utc};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub const FLAG: &str = "--demo";
+pub const ENV_VAR: &str = "CFAIT_DEMO";
+
+const PERSONAL: &str = "/demo/personal/";
+const WORK: &str = "/demo/work/";
+const FAMILY: &str = "/demo/family/";
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Whether this process shows the demo.
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// `--demo` on the command line, or `CFAIT_DEMO` set to anything but "0".
+pub fn requested(args: &[String]) -> bool {
+    args.iter().skip(1).any(|a| a == FLAG)
+        || env::var(ENV_VAR).is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Moves every path to a fresh directory and fills it with the sample data. Must be called
+/// before the first path lookup, instead of `AppPaths::init_from_args`.
+pub fn start() -> Result<PathBuf> {
+    let dir = env::temp_dir().join(format!("cfait-demo-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir)?;
+    AppPaths::init_portable(Some(dir.clone()));
+    ACTIVE.store(true, Ordering::Relaxed);
+
+    let config = Config {
+        default_calendar: Some("Work".to_string()),
+        tag_aliases: HashMap::from([(
+            "groceries".to_string(),
+            vec!["shopping".to_string(), "home".to_string()],
+        )]),
+        ..Default::default()
+    };
+    config.save()?;
+
+    Cache::save_calendars(&calendars())?;
+    for (href, tasks) in tasks(Local::now().date_naive()) {
+        if href == LOCAL_CALENDAR_HREF {
+            LocalStorage::save(&tasks)?;
+        } else {
+            Cache::save(&href, &tasks, None)?;
+        }
+    }
+    Ok(dir)
+}
+
+/// Deletes the demo directory, with whatever was changed during the demo.
+pub fn finish(dir: &Path) {
+    let _ = fs::remove_dir_all(dir);
+}
+
+/// What the server would list: the calendars of the demo (the Local one aside).
+pub fn calendars() -> Vec<CalendarListEntry> {
+    let calendar = |name: &str, href: &str, color: &str, shared: bool| CalendarListEntry {
+        name: name.to_string(),
+        href: href.to_string(),
+        color: Some(color.to_string()),
+        is_shared: shared,
+        is_read_only: shared,
+    };
+    vec![
+        calendar("Work", WORK, "#e67e22", false),
+        calendar("Personal", PERSONAL, "#2e86de", false),
+        calendar("Family", FAMILY, "#27ae60", true),
+    ]
+}
+
+// --- SAMPLE TASKS ---
+
+/// End of the day `days` from `today`, as the smart syntax sets due dates.
+fn day(today: NaiveDate, days: i64) -> DateTime<Utc> {
+    (today + TimeDelta::days(days))
+        .and_hms_opt(23, 59, 59)
+        .unwrap()
+        .and_utc()
+}
+
+/// A task as if fetched from the server, with a fixed uid so that subtasks and dependencies
+/// can point to it.
+fn sample(href: &str, slug: &str, summary: &str) -> TaskBuilder {
+    let uid = format!("demo-{}", slug);
+    TaskBuilder::new(summary)
+        .stored(format!("{}{}.ics", href, uid), "\"demo\"")
+        .uid(uid)
+        .calendar(href)
+}
+
+fn minutes(n: u32) -> Duration {
+    Duration::from_minutes(n)
+}
+
+fn done(builder: TaskBuilder, when: DateTime<Utc>) -> Option<Task> {
+    let mut task = builder.status(TaskStatus::Completed).build().ok()?;
+    task.completed = Some(when);
+    Some(task)
+}
+
+/// The tasks of each calendar, dated from `today` so that the lists always look current.
+pub fn tasks(today: NaiveDate) -> Vec<(String, Vec<Task>)> {
+    let weekly = Recurrence {
+        freq: Frequency::Weekly,
+        ..Default::default()
+    };
+    let every_other_day = Recurrence {
+        interval: 2,
+        ..Default::default()
+    };
+    let monthly = Recurrence {
+        freq: Frequency::Monthly,
+        by_month_day: Some(1),
+        ..Default::default()
+    };
+    let next_first = (1..=31)
+        .find(|d| (today + TimeDelta::days(*d)).format("%d").to_string() == "01")
+        .unwrap_or(1);
+
+    let work = vec![
+        sample(WORK, "report", "Prepare the quarterly report")
+            .description("Numbers for Q3, with the comparison to last year.\nSend to Dana.")
+            .due(day(today, 2))
+            .priority(Priority::HIGH)
+            .duration(minutes(180))
+            .tag("finance")
+            .build()
+            .ok(),
+        done(
+            sample(WORK, "figures", "Collect the sales figures").parent("demo-report"),
+            day(today, -1),
+        ),
+        sample(WORK, "summary", "Draft the summary")
+            .parent("demo-report")
+            .due(day(today, 1))
+            .duration(minutes(90))
+            .build()
+            .ok(),
+        sample(WORK, "review", "Review Alice's pull request")
+            .due(day(today, 0))
+            .priority(Priority::from(3))
+            .duration(minutes(30))
+            .tag("dev")
+            .build()
+            .ok(),
+        sample(WORK, "timeout", "Fix the login timeout bug")
+            .status(TaskStatus::InProcess)
+            .start(day(today, -3))
+            .priority(Priority::from(2))
+            .tag("dev")
+            .tag("bug")
+            .build()
+            .ok(),
+        sample(WORK, "deploy", "Deploy 2.0 to production")
+            .due(day(today, 5))
+            .depends_on("demo-timeout")
+            .tag("dev")
+            .build()
+            .ok(),
+        sample(WORK, "standup", "Write the weekly team update")
+            .due(day(today, 3))
+            .recur(&weekly)
+            .duration(minutes(30))
+            .tag("meetings")
+            .build()
+            .ok(),
+        sample(WORK, "flights", "Book flights for the conference")
+            .due(day(today, -2))
+            .priority(Priority::from(4))
+            .tag("travel")
+            .build()
+            .ok(),
+        sample(WORK, "branches", "Clean up old branches")
+            .priority(Priority::LOW)
+            .tag("dev")
+            .build()
+            .ok(),
+    ];
+
+    let personal = vec![
+        sample(PERSONAL, "rent", "Pay rent")
+            .due(day(today, next_first))
+            .recur(&monthly)
+            .reminder(Reminder::BeforeDue(24 * 60))
+            .tag("home")
+            .tag("finance")
+            .build()
+            .ok(),
+        sample(PERSONAL, "dentist", "Call the dentist")
+            .due(day(today, 1))
+            .duration(minutes(10))
+            .tag("health")
+            .build()
+            .ok(),
+        sample(PERSONAL, "passport", "Renew the passport")
+            .start(day(today, 10))
+            .due(day(today, 30))
+            .tag("admin")
+            .build()
+            .ok(),
+        sample(PERSONAL, "run", "Go for a run")
+            .due(day(today, 0))
+            .recur(&every_other_day)
+            .recur_from_completion()
+            .duration(minutes(45))
+            .tag("health")
+            .build()
+            .ok(),
+        sample(PERSONAL, "book", "Read \"The Pragmatic Programmer\"")
+            .description("Chapters 3 to 5 next.")
+            .tag("reading")
+            .build()
+            .ok(),
+        done(
+            sample(PERSONAL, "tyre", "Fix the bike's back tyre").tag("home"),
+            day(today, -3),
+        ),
+    ];
+
+    let family = vec![
+        sample(FAMILY, "milk", "Buy milk")
+            .due(day(today, 0))
+            .tag("groceries")
+            .tag("shopping")
+            .build()
+            .ok(),
+        sample(FAMILY, "holiday", "Plan the summer holiday")
+            .description("Seaside or mountains? Ask everyone before booking.")
+            .due(day(today, 14))
+            .build()
+            .ok(),
+        sample(FAMILY, "present", "Grandma's birthday present")
+            .due(day(today, 6))
+            .priority(Priority::HIGH)
+            .build()
+            .ok(),
+    ];
+
+    let local = vec![
+        sample(
+            LOCAL_CALENDAR_HREF,
+            "syntax",
+            "Try the smart syntax: Call Bob @tomorrow !1 #phone ~15m",
+        )
+        .stored("", "")
+        .build()
+        .ok(),
+        sample(LOCAL_CALENDAR_HREF, "keys", "Press ? to see the keys")
+            .stored("", "")
+            .build()
+            .ok(),
+    ];
+
+    [(WORK, work), (PERSONAL, personal), (FAMILY, family)]
+        .into_iter()
+        .chain([(LOCAL_CALENDAR_HREF, local)])
+        .map(|(href, tasks)| (href.to_string(), tasks.into_iter().flatten().collect()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_tasks() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 4).unwrap();
+        let tasks = tasks(today);
+        let count = |href: &str| tasks.iter().find(|(h, _)| h == href).map(|(_, t)| t.len());
+        // No sample is dropped for failing the builder's checks
+        assert_eq!(count(WORK), Some(9));
+        assert_eq!(count(PERSONAL), Some(6));
+        assert_eq!(count(FAMILY), Some(3));
+        assert_eq!(count(LOCAL_CALENDAR_HREF), Some(2));
+
+        let all: Vec<&Task> = tasks.iter().flat_map(|(_, t)| t).collect();
+        let rent = all.iter().find(|t| t.uid == "demo-rent").unwrap();
+        assert_eq!(
+            rent.due.unwrap().format("%Y-%m-%d").to_string(),
+            "2025-04-01"
+        );
+        assert_eq!(rent.href, "/demo/personal/demo-rent.ics");
+        assert!(
+            all.iter()
+                .flat_map(|t| t.parent_uid.iter().chain(&t.dependencies))
+                .all(|uid| all.iter().any(|t| t.uid == *uid))
+        );
+    }
+}
@@ -1,7 +1,10 @@
 // File: ./src/gui/async_ops.rs
-use crate::client::RustyClient;
+use crate::client::{FetchReport, RustyClient};
 use crate::config::Config;
+use crate::ipc::{self, Reply, Request};
 use crate::model::{CalendarListEntry, Task as TodoTask};
+use crate::notifier::{ReminderAction, notify_blocking, notify_unblocked};
+use crate::store::BulkAction;
 use std::sync::OnceLock;
 use tokio::runtime::Runtime;
 
@@ -41,6 +44,15 @@ pub async fn connect_and_fetch_wrapper(
         .map_err(|e| e.to_string())?
 }
 
+pub async fn async_calendars_wrapper(
+    client: RustyClient,
+) -> Result<(Vec<CalendarListEntry>, Option<String>), String> {
+    let rt = get_runtime();
+    rt.spawn(async move { client.calendars_or_cached().await })
+        .await
+        .map_err(|e| e.to_string())?
+}
+
 pub async fn async_fetch_wrapper(
     client: RustyClient,
     href: String,
@@ -57,7 +69,7 @@ pub async fn async_fetch_wrapper(
 pub async fn async_fetch_all_wrapper(
     client: RustyClient,
     cals: Vec<CalendarListEntry>,
-) -> Result<Vec<(String, Vec<TodoTask>)>, String> {
+) -> Result<FetchReport, String> {
     let rt = get_runtime();
     rt.spawn(async move { client.get_all_tasks(&cals).await })
         .await
@@ -102,6 +114,24 @@ pub async fn async_move_wrapper(
     Ok(t)
 }
 
+pub async fn async_file_wrapper(
+    client: RustyClient,
+    mut task: TodoTask,
+    new_href: String,
+) -> Result<TodoTask, String> {
+    let (t, _) = client.file_task(&mut task, &new_href).await?;
+    Ok(t)
+}
+
+pub async fn async_bulk_wrapper(
+    client: RustyClient,
+    tasks: Vec<TodoTask>,
+    action: BulkAction,
+) -> Result<Vec<TodoTask>, String> {
+    let (changed, _) = client.apply_bulk(tasks, &action).await?;
+    Ok(changed)
+}
+
 pub async fn async_migrate_wrapper(
     client: RustyClient,
     tasks: Vec<TodoTask>,
@@ -112,3 +142,33 @@ pub async fn async_migrate_wrapper(
         .await
         .map_err(|e| e.to_string())?
 }
+
+/// Asks the running `cfait daemon` to sync every calendar.
+pub async fn async_daemon_sync_wrapper() -> Result<(), String> {
+    let rt = get_runtime();
+    rt.spawn(async {
+        match ipc::request(&Request::Sync { calendar: None }).await {
+            Ok(Reply::Synced) => Ok(()),
+            Ok(Reply::Error { message }) => Err(message),
+            Ok(other) => Err(format!("Unexpected reply: {:?}", other)),
+            Err(e) => Err(e.to_string()),
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Announces the tasks a completion unblocked, without waiting for the notification.
+pub fn spawn_unblocked_notice(tasks: Vec<TodoTask>) {
+    if !tasks.is_empty() {
+        get_runtime().spawn_blocking(move || notify_unblocked(&tasks));
+    }
+}
+
+/// Shows the reminder on a blocking thread, resolving once the user answers it.
+pub async fn async_notify_wrapper(task: TodoTask) -> ReminderAction {
+    get_runtime()
+        .spawn_blocking(move || notify_blocking(&task))
+        .await
+        .unwrap_or(ReminderAction::Dismissed)
+}
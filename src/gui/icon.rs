@@ -35,6 +35,9 @@ pub const SHIELD: char = '\u{f32a}'; // 
 pub const CHILD_ARROW: char = '\u{f149}'; // 
 pub const INFO: char = '\u{f129}'; // 
 pub const REPEAT: char = '\u{f0b6}'; // 
+pub const SKIP_NEXT: char = '\u{f04ad}'; // nf-md-skip_next
+pub const CHEVRON_RIGHT: char = '\u{f054}'; // nf-fa-chevron_right
+pub const CHEVRON_DOWN: char = '\u{f078}'; // nf-fa-chevron_down
 pub const ARROW_RIGHT: char = '\u{f061}'; // 
 pub const CHECK_SQUARE: char = '\u{f14a}'; // 
 pub const SQUARE: char = '\u{f096}'; // 
@@ -56,3 +59,8 @@ pub const WINDOW_MINIMIZE: char = '\u{f2d1}'; // nf-fa-window_minimize
 pub const CONTENT_SAVE_EDIT: char = '\u{f0cfb}'; // nf-md-content_save_edit
 pub const EYE: char = '\u{ea70}'; // nf-cod-eye
 pub const EYE_CLOSED: char = '\u{eae7}'; // nf-cod-eye_closed
+pub const SHARED: char = '\u{f0c0}'; // nf-fa-users
+pub const CLOCK: char = '\u{f017}'; // nf-fa-clock_o
+pub const INBOX: char = '\u{f01c}'; // nf-fa-inbox
+pub const WIRE_LOG: char = '\u{f0f6}'; // nf-fa-file_text_o
+pub const NOTE: char = '\u{f24a}'; // nf-fa-sticky_note_o
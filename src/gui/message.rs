@@ -1,8 +1,13 @@
 // File: src/gui/message.rs
-use crate::client::RustyClient;
-use crate::config::Config;
+use crate::client::{FetchReport, RustyClient};
+use crate::color_utils::Palette;
+use crate::config::{Config, Density};
 use crate::gui::state::{ResizeDirection, SidebarMode};
+use crate::model::recurrence::Frequency;
+use crate::model::share::{DeepLink, ShareFormat};
 use crate::model::{CalendarListEntry, Task as TodoTask};
+use crate::notifier::ReminderAction;
+use crate::store::{BulkAction, GroupBy, ListKey, QuickFilter, StoreEvent};
 use iced::widget::text_editor;
 
 pub type LoadedResult = Result<
@@ -16,12 +21,59 @@ pub type LoadedResult = Result<
     String,
 >;
 
+/// What to do with the task shown by the review.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReviewChoice {
+    Keep,
+    /// To the date typed in the review's due date field
+    Reschedule,
+    Complete,
+    Delete,
+    Skip,
+}
+
+/// What to do with the inbox task shown by the triage.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TriageChoice {
+    /// With the tags and dates typed, into the calendar picked
+    File,
+    Complete,
+    Delete,
+    Skip,
+}
+
+/// A change in the inline editor of an expanded task.
+#[derive(Debug, Clone)]
+pub enum CardEdit {
+    Summary(String),
+    Description(text_editor::Action),
+    Due(String),
+    Start(String),
+    PriorityStep(i8),
+    Duration(String),
+    TagInput(String),
+    /// The tags typed in the tag field
+    AddTags,
+    /// A suggested tag
+    AddTag(String),
+    RemoveTag(String),
+    Frequency(Frequency),
+    IntervalStep(i32),
+    DayToggled(chrono::Weekday),
+    MonthDaySelected(u32),
+    ClearRecurrence,
+    DependencyInput(String),
+    AddDependency(String),
+    RemoveDependency(String),
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
     ObUrlChanged(String),
     ObUserChanged(String),
     ObPassChanged(String),
     ObInsecureToggled(bool),
+    ObPushUrlChanged(String),
     ToggleCalendarVisibility(String, bool),
     ToggleCalendarDisabled(String, bool),
     ObDefaultCalChanged(String),
@@ -36,21 +88,63 @@ pub enum Message {
 
     SearchChanged(String),
     SubmitTask,
+    /// The text of the input as the title of a new note
+    SubmitNote,
     ToggleTask(usize, bool),
     DeleteTask(usize),
     EditTaskStart(usize),
     CancelEdit,
     ChangePriority(usize, i8),
     SetTaskStatus(usize, crate::model::TaskStatus),
+    SkipOccurrence(usize),
+    ToggleSubtasksHidden(usize),
+    RescheduleInputChanged(String),
+    RescheduleOccurrence(String),
+    RecurrenceFreqSelected(Frequency),
+    RecurrenceIntervalStep(i32),
+    RecurrenceDayToggled(chrono::Weekday),
+    RecurrenceMonthDaySelected(u32),
+    ClearRecurrence,
     SetMinDuration(Option<u32>),
     SetMaxDuration(Option<u32>),
     ToggleIncludeUnsetDuration(bool),
     ToggleDetails(String),
+    ShowFullDescription(String),
+    /// Edit every field of an expanded task in place, saved as one update
+    StartCardEdit(String),
+    CardEdited(CardEdit),
+    SaveCardEdit,
+    CancelCardEdit,
     ConfigLoaded(Result<Config, String>),
+    ConfigChanged(Result<Config, String>),
     ObSortMonthsChanged(String),
+    ObRetentionDaysChanged(String),
+    ObNotifyBeforeChanged(String),
 
     Loaded(LoadedResult),
+    /// Startup: the calendars listed in the background while the cache is shown, with the
+    /// "Offline Mode" warning when they are the cached ones
+    CalendarsListed(Result<(Vec<CalendarListEntry>, Option<String>), String>),
     Refresh,
+    /// Re-download from scratch: one calendar, None = all of them
+    ForceRefresh(Option<String>),
+    /// The push listener reported a change (calendar href, or None for all)
+    RemoteChanged(Option<String>),
+    /// `cfait daemon` syncs for us: re-read the replica it maintains
+    ReloadReplica,
+    DaemonSynced(Result<(), String>),
+    /// Reload if another instance wrote the journal, local tasks or cache
+    CheckDataStamp,
+    /// Tasks changed in the store (those arrived together)
+    StoreChanged(Vec<StoreEvent>),
+    /// Preview of the offline queue, before it is pushed
+    OpenPendingChanges,
+    ClosePendingChanges,
+    /// Task uid, true to keep its changes out of the next syncs
+    HoldPendingChange(String, bool),
+    /// The last requests to the server (`wire_log`), newest first
+    OpenWireLog,
+    CloseWireLog,
 
     SyncSaved(Result<TodoTask, String>),
     SyncToggleComplete(Box<Result<(TodoTask, Option<TodoTask>), String>>),
@@ -64,10 +158,26 @@ pub enum Message {
     CategoryToggled(String),
     ClearAllTags,
     CategoryMatchModeChanged(bool),
-    RefreshedAll(Result<Vec<(String, Vec<TodoTask>)>, String>),
+    QuickFilterToggled(QuickFilter),
+    /// Shows, dims or hides the tasks starting on a later day, in turn
+    CycleNotStarted,
+    /// Switches the list between all tasks and the next actions
+    ToggleNextActions,
+    RefreshedAll(Result<FetchReport, String>),
 
     ToggleHideCompleted(bool),
     ToggleHideFullyCompletedTags(bool),
+    ToggleMoveSubtree(bool),
+    DensityChanged(Density),
+    FontSizeChanged(u16),
+    PaletteChanged(Palette),
+    ToggleUnderlinePriority(bool),
+    ToggleRelativeDue(bool),
+    /// Quick toggle: temporarily ignore the completed-tasks retention window
+    ToggleShowAllCompleted,
+    GroupByChanged(GroupBy),
+    /// Collapse / expand a group of the task list, by key
+    ToggleGroup(String),
 
     YankTask(String),
     ClearYank,
@@ -81,9 +191,54 @@ pub enum Message {
     AliasValueInput(String),
     AddAlias,
     RemoveAlias(String),
+    EditAlias(String),
+    TemplateNameInput(String),
+    TemplateTitleInput(String),
+    TemplateSubtasksInput(String),
+    AddTemplate,
+    RemoveTemplate(String),
+    BackupNow,
+    RestoreBackup(String),
+    ReportFromChanged(String),
+    ReportToChanged(String),
+    ExportReport,
+    PrintList,
+    PrintAgenda,
+    CalendarDefaultsChanged(String, String),
     MoveTask(String, String),
+    DuplicateTask(String, String),
+    /// A click on a task: selects it; with ctrl in the multi-selection or out of it, with
+    /// shift the tasks from the selected one to it
+    TaskClicked(usize),
+    ModifiersChanged(iced::keyboard::Modifiers),
+    /// A key for the task list, pressed outside of the text fields
+    ListKeyPressed(ListKey),
+    ClearSelection,
+    BulkTagInput(String),
+    /// On every task of the multi-selection
+    Bulk(BulkAction),
+    /// Tasks to put back in the store
+    BulkDone(Result<Vec<TodoTask>, String>),
+    ShareTask(String, ShareFormat),
+    ToggleDuplicateSubtree(bool),
 
     JumpToTag(String),
+    OpenDeepLink(DeepLink),
+
+    CheckReminders,
+    ReminderAnswered(String, ReminderAction),
+
+    /// Walk through the tasks not modified for `review_after_weeks`
+    StartReview,
+    StopReview,
+    ReviewDueChanged(String),
+    ReviewAnswered(ReviewChoice),
+    /// File the tasks of the inbox (`inbox_calendar`) one by one
+    StartTriage,
+    StopTriage,
+    TriageInputChanged(String),
+    TriageCalendarChanged(String),
+    TriageAnswered(TriageChoice),
 
     TaskMoved(Result<TodoTask, String>),
     ObSubmitOffline,
@@ -92,6 +247,7 @@ pub enum Message {
     MigrationComplete(Result<usize, String>),
     FontLoaded(Result<(), String>),
     DismissError,
+    DismissFetchFailures,
     ToggleAllCalendars(bool),
 
     TabPressed(bool),
@@ -101,6 +257,8 @@ pub enum Message {
     MinimizeWindow,
     CloseWindow,
     WindowResized(iced::Size),
+    /// Relative position of the task list, saved with the session
+    ListScrolled(f32),
 
     // Resize
     ResizeStart(ResizeDirection),
@@ -7,20 +7,45 @@ pub mod update;
 pub mod view;
 
 use crate::config::Config;
+use crate::demo;
 use crate::gui::message::Message;
 use crate::gui::state::GuiApp;
+use crate::gui::update::common::restore_session;
+use crate::model::share::DeepLink;
+use crate::paths::AppPaths;
+use crate::session::Session;
 use iced::{Element, Subscription, Task, Theme, font, window};
 
 pub fn run() -> iced::Result {
     // Initialize the Tokio runtime managed in async_ops
     async_ops::init_runtime();
 
-    iced::application(GuiApp::new, GuiApp::update, GuiApp::view)
+    let args: Vec<String> = std::env::args().collect();
+    // The demo keeps everything in a directory of its own, whatever the other flags say
+    let demo_dir = if demo::requested(&args) {
+        match demo::start() {
+            Ok(dir) => Some(dir),
+            Err(e) => {
+                eprintln!("Could not set up the demo: {}", e);
+                return Ok(());
+            }
+        }
+    } else {
+        AppPaths::init_from_args(&args);
+        None
+    };
+    if let Err(e) = crate::backup::snapshot_if_due() {
+        eprintln!("Backup failed: {}", e);
+    }
+
+    let result = iced::application(GuiApp::new, GuiApp::update, GuiApp::view)
         .title(GuiApp::title)
         .subscription(GuiApp::subscription)
         .theme(GuiApp::theme)
         .window(window::Settings {
             decorations: false, // <--- Disable OS Top Bar
+            // Closing goes through `CloseWindow`, which saves the session first
+            exit_on_close_request: false,
             platform_specific: window::settings::PlatformSpecific {
                 #[cfg(target_os = "linux")]
                 application_id: String::from("cfait"),
@@ -29,13 +54,26 @@ pub fn run() -> iced::Result {
             },
             ..Default::default()
         })
-        .run()
+        .run();
+    if let Some(dir) = demo_dir {
+        demo::finish(&dir);
+    }
+    result
 }
 
 impl GuiApp {
     fn new() -> (Self, Task<Message>) {
+        // Started by the OS as the rustache:// handler
+        let link = std::env::args().skip(1).find_map(|a| DeepLink::parse(&a));
+        let mut app = Self::default();
+        restore_session(&mut app, Session::load("gui"));
+        if let Some(DeepLink::Add(text)) = &link {
+            app.input_value = text.clone();
+        } else {
+            app.pending_link = link;
+        }
         (
-            Self::default(),
+            app,
             Task::batch(vec![
                 // Load config
                 Task::perform(
@@ -53,7 +91,18 @@ impl GuiApp {
     }
 
     fn title(&self) -> String {
-        "Cfait | 🗹 Take control of your TODO list".to_string()
+        let title = if demo::is_active() {
+            "Cfait | Demo with sample tasks"
+        } else {
+            "Cfait | 🗹 Take control of your TODO list"
+        };
+        match self
+            .store
+            .overdue_count(&self.disabled_calendars, chrono::Utc::now())
+        {
+            0 => title.to_string(),
+            overdue => format!("({} overdue) {}", overdue, title),
+        }
     }
 
     fn theme(&self) -> Theme {
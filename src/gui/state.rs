@@ -1,7 +1,23 @@
 // File: src/gui/state.rs
 use crate::client::RustyClient;
+use crate::client::throttle::RequestLimits;
+use crate::client::wirelog::WireEntry;
+use crate::color_utils::Palette;
+use crate::config::{CalendarDefaults, DEFAULT_TUI_COLUMNS, Density, ImapConfig, TuiColumn};
+use crate::journal::{PendingChange, SyncStatus};
+use crate::model::draft::TaskDraft;
+use crate::model::due::DueDisplay;
+use crate::model::priority::PriorityBand;
+use crate::model::share::DeepLink;
+use crate::model::syntax::SmartSyntax;
+use crate::model::template::TaskTemplate;
 use crate::model::{CalendarListEntry, Task as TodoTask};
-use crate::store::TaskStore;
+use crate::notifier::Reminders;
+use crate::storage::DataStamp;
+use crate::store::{
+    CalendarStats, GroupBy, ListRow, NewTaskPlacement, NotStarted, QuickFilter, TaskStore,
+};
+use chrono::{DateTime, Utc};
 use iced::widget::text_editor;
 use std::collections::{HashMap, HashSet};
 
@@ -41,6 +57,56 @@ pub struct GuiApp {
     pub calendars: Vec<CalendarListEntry>,
     pub client: Option<RustyClient>,
     pub tag_aliases: HashMap<String, Vec<String>>,
+    pub calendar_defaults: HashMap<String, CalendarDefaults>,
+    pub templates: Vec<TaskTemplate>,
+    /// Kept to save them back; edited in the config file
+    pub priority_bands: Vec<PriorityBand>,
+    pub smart_syntax: SmartSyntax,
+    pub review_after_weeks: u32,
+    /// Tasks left in the review, current one first (empty = no review)
+    pub review_queue: Vec<String>,
+    pub review_total: usize,
+    pub review_due_input: String,
+    /// Inbox tasks left in the triage, current one first (empty = no triage)
+    pub triage_queue: Vec<String>,
+    pub triage_total: usize,
+    pub triage_filed: usize,
+    /// Smart syntax of the task shown, edited before filing it
+    pub triage_input: String,
+    /// Name of the calendar picked to file into
+    pub triage_target: Option<String>,
+    /// The offline queue as previewed before a sync (None = preview closed)
+    pub pending_changes: Option<Vec<PendingChange>>,
+    /// Requests shown by the wire log panel (None = panel closed)
+    pub wire_entries: Option<Vec<WireEntry>>,
+    pub move_subtree: bool,
+    pub new_task_placement: NewTaskPlacement,
+    pub density: Density,
+    /// Size of the task titles, see `config::FONT_SIZES`
+    pub font_size: u16,
+    /// Kept to save them back; applied through `color_utils`
+    pub palette: Palette,
+    pub underline_priority: bool,
+    pub due_display: DueDisplay,
+    pub due_soon_days: u32,
+    pub not_started: NotStarted,
+    /// Kept to save it back; applied through `i18n`
+    pub language: Option<String>,
+    /// Not edited in the GUI, kept to save it back
+    pub imap: Option<ImapConfig>,
+    /// TUI only, kept to save it back
+    pub tui_columns: Vec<TuiColumn>,
+    /// Not edited in the GUI, kept to save it back; applied through `client::throttle`
+    pub request_limits: RequestLimits,
+    pub wire_log: bool,
+    pub prune_completed_days: Option<u32>,
+    pub inbox_calendar: Option<String>,
+    pub user_agent: Option<String>,
+    /// Snapshot names, newest first (refreshed when the settings open)
+    pub backups: Vec<String>,
+    /// Date range of the completed tasks report (YYYY-MM-DD)
+    pub report_from: String,
+    pub report_to: String,
 
     // UI State
     pub sidebar_mode: SidebarMode,
@@ -53,16 +119,40 @@ pub struct GuiApp {
 
     // Track selected task for highlighting
     pub selected_uid: Option<String>,
+    /// Tasks picked for the bulk actions (ctrl/shift-click), besides `selected_uid`
+    pub selected_uids: HashSet<String>,
+    pub bulk_tag_input: String,
+    /// Held keys, read by clicks on the list
+    pub modifiers: iced::keyboard::Modifiers,
+    /// `rustache://` link from the command line, opened once tasks are loaded
+    pub pending_link: Option<DeepLink>,
 
     // Preferences
     pub hide_completed: bool,
     pub hide_fully_completed_tags: bool,
     pub sort_cutoff_months: Option<u32>,
+    pub completed_retention_days: Option<u32>,
+    /// Session-only override of the retention window (header toggle)
+    pub show_all_completed: bool,
+    /// Session-only: the list shows the next actions (quick-filter chip)
+    pub next_actions: bool,
+    pub notify_before_minutes: Option<u32>,
+    pub reminders: Reminders,
 
     // Filter State
     pub filter_min_duration: Option<u32>,
     pub filter_max_duration: Option<u32>,
     pub filter_include_unset_duration: bool,
+    /// Chips switched on in the quick filters bar
+    pub quick_filters: Vec<QuickFilter>,
+    /// Open tasks and estimates per calendar, for the sidebar
+    pub calendar_stats: HashMap<String, CalendarStats>,
+    /// The list holds tasks of several calendars: rows get a calendar badge
+    pub mixed_calendars: bool,
+    /// Grouping of the task list; `task_rows` stays empty when there is none
+    pub group_by: GroupBy,
+    pub collapsed_groups: HashSet<String>,
+    pub task_rows: Vec<ListRow>,
 
     // Inputs - Main
     pub input_value: String,
@@ -71,12 +161,41 @@ pub struct GuiApp {
     pub editing_uid: Option<String>,
     pub creating_child_of: Option<String>,
     pub expanded_tasks: HashSet<String>,
-    pub unsynced_changes: bool,
+    /// Expanded tasks whose long description is shown whole, not just its beginning
+    pub full_descriptions: HashSet<String>,
+    /// Inline editor of an expanded task: its uid and the fields as typed
+    pub card_edit: Option<(String, TaskDraft)>,
+    pub card_description: text_editor::Content,
+    pub card_tag_input: String,
+    pub card_dependency_input: String,
+    pub reschedule_input: String,
+    pub duplicate_with_subtree: bool,
+    /// Moves sent to the server and not yet confirmed (progress of subtree moves)
+    pub pending_moves: usize,
+    pub sync_status: SyncStatus,
+    /// Last successful sync of each calendar, by href
+    pub sync_times: HashMap<String, DateTime<Utc>>,
+    /// Calendars the last sync could not fetch, "Name: error"
+    pub fetch_failures: Vec<String>,
+    /// Closed by the user, shown again when the failures change
+    pub fetch_failures_dismissed: bool,
+    /// A `cfait daemon` owns syncing and reminders
+    pub daemon_running: bool,
+    /// Shared files as of the last reload, see `CheckDataStamp`
+    pub data_stamp: DataStamp,
 
     // Inputs - Settings (Aliases)
     pub alias_input_key: String,
     pub alias_input_values: String,
 
+    // Inputs - Settings (Templates)
+    pub template_input_name: String,
+    pub template_input_title: String,
+    /// Subtask titles separated by ';'
+    pub template_input_subtasks: String,
+    /// Raw smart-syntax text of the per-calendar defaults fields, keyed by calendar href
+    pub calendar_defaults_inputs: HashMap<String, String>,
+
     // System
     pub loading: bool,
     pub error_msg: Option<String>,
@@ -87,9 +206,14 @@ pub struct GuiApp {
     pub ob_pass: String,
     pub ob_default_cal: Option<String>,
     pub ob_sort_months_input: String,
+    pub ob_retention_input: String,
+    pub ob_notify_input: String,
     pub ob_insecure: bool,
+    pub ob_push_url: String,
     pub scrollable_id: iced::widget::Id,
     pub sidebar_scrollable_id: iced::widget::Id, // Added for tag scrolling
+    /// Relative scroll position of the task list (kept for the session)
+    pub list_scroll_y: f32,
 
     // Window Resizing State
     pub resize_direction: Option<ResizeDirection>,
@@ -105,6 +229,41 @@ impl Default for GuiApp {
             calendars: vec![],
             client: None,
             tag_aliases: HashMap::new(),
+            calendar_defaults: HashMap::new(),
+            templates: Vec::new(),
+            priority_bands: Vec::new(),
+            smart_syntax: SmartSyntax::default(),
+            review_after_weeks: 4,
+            review_queue: Vec::new(),
+            review_total: 0,
+            review_due_input: String::new(),
+            triage_queue: Vec::new(),
+            triage_total: 0,
+            triage_filed: 0,
+            triage_input: String::new(),
+            triage_target: None,
+            pending_changes: None,
+            wire_entries: None,
+            move_subtree: true,
+            new_task_placement: NewTaskPlacement::Sorted,
+            density: Density::Comfortable,
+            font_size: 20,
+            palette: Palette::Default,
+            underline_priority: false,
+            due_display: DueDisplay::Relative,
+            due_soon_days: 3,
+            not_started: NotStarted::Show,
+            language: None,
+            imap: None,
+            tui_columns: DEFAULT_TUI_COLUMNS.to_vec(),
+            request_limits: RequestLimits::default(),
+            wire_log: false,
+            prune_completed_days: None,
+            inbox_calendar: None,
+            user_agent: None,
+            backups: Vec::new(),
+            report_from: String::new(),
+            report_to: String::new(),
 
             sidebar_mode: SidebarMode::Calendars,
             active_cal_href: None,
@@ -114,15 +273,32 @@ impl Default for GuiApp {
             match_all_categories: false,
             yanked_uid: None,
             selected_uid: None,
+            selected_uids: HashSet::new(),
+            bulk_tag_input: String::new(),
+            modifiers: iced::keyboard::Modifiers::default(),
+            pending_link: None,
 
             hide_completed: false,
             hide_fully_completed_tags: true,
             sort_cutoff_months: Some(6),
             ob_sort_months_input: "6".to_string(),
+            completed_retention_days: None,
+            show_all_completed: false,
+            next_actions: false,
+            ob_retention_input: String::new(),
+            notify_before_minutes: None,
+            ob_notify_input: String::new(),
+            reminders: Reminders::default(),
 
             filter_min_duration: None,
             filter_max_duration: None,
             filter_include_unset_duration: true,
+            quick_filters: Vec::new(),
+            calendar_stats: HashMap::new(),
+            mixed_calendars: false,
+            group_by: GroupBy::None,
+            collapsed_groups: HashSet::new(),
+            task_rows: Vec::new(),
 
             input_value: String::new(),
             description_value: text_editor::Content::new(),
@@ -130,10 +306,27 @@ impl Default for GuiApp {
             editing_uid: None,
             creating_child_of: None,
             expanded_tasks: HashSet::new(),
-            unsynced_changes: false,
+            full_descriptions: HashSet::new(),
+            card_edit: None,
+            card_description: text_editor::Content::new(),
+            card_tag_input: String::new(),
+            card_dependency_input: String::new(),
+            reschedule_input: String::new(),
+            duplicate_with_subtree: false,
+            pending_moves: 0,
+            sync_status: SyncStatus::default(),
+            sync_times: HashMap::new(),
+            fetch_failures: Vec::new(),
+            fetch_failures_dismissed: false,
+            daemon_running: false,
+            data_stamp: DataStamp::default(),
 
             alias_input_key: String::new(),
             alias_input_values: String::new(),
+            template_input_name: String::new(),
+            template_input_title: String::new(),
+            template_input_subtasks: String::new(),
+            calendar_defaults_inputs: HashMap::new(),
 
             loading: true,
             error_msg: None,
@@ -142,8 +335,10 @@ impl Default for GuiApp {
             ob_pass: String::new(),
             ob_default_cal: None,
             ob_insecure: false,
+            ob_push_url: String::new(),
             scrollable_id: iced::widget::Id::unique(),
             sidebar_scrollable_id: iced::widget::Id::unique(), // Initialize unique ID
+            list_scroll_y: 0.0,
 
             resize_direction: None,
             current_window_size: iced::Size::new(800.0, 600.0),
@@ -1,7 +1,24 @@
 // File: src/gui/subscription.rs
+use crate::client::PushTarget;
+use crate::client::push;
+use crate::config::Config;
+use crate::gui::async_ops::get_runtime;
 use crate::gui::message::Message;
 use crate::gui::state::{AppState, GuiApp};
+use crate::gui::update::common::push_url_input;
+use crate::ipc;
+use crate::notifier::CHECK_INTERVAL;
+use crate::store::{ChangeFeed, ListKey};
+use futures::{SinkExt, Stream, StreamExt};
 use iced::{Subscription, event, keyboard, window};
+use std::time::Duration;
+
+/// Wait before subscribing to the daemon again
+const REPLICA_POLL: Duration = Duration::from_secs(30);
+/// How often to look for writes by other cfait processes
+const STAMP_POLL: Duration = Duration::from_secs(2);
+/// Store changes handled at once at most
+const STORE_BATCH: usize = 256;
 
 pub fn subscription(app: &GuiApp) -> Subscription<Message> {
     use iced::keyboard::key;
@@ -19,11 +36,154 @@ pub fn subscription(app: &GuiApp) -> Subscription<Message> {
         }));
     }
 
+    // Held keys, for the ctrl/shift-clicks of the task list
+    if matches!(app.state, AppState::Active) {
+        subs.push(keyboard::listen().filter_map(|event| match event {
+            keyboard::Event::ModifiersChanged(modifiers) => {
+                Some(Message::ModifiersChanged(modifiers))
+            }
+            _ => None,
+        }));
+    }
+
+    // The task list without a mouse, with the keys of the TUI. Keys typed into a field are
+    // captured by it and never get here.
+    if matches!(app.state, AppState::Active) {
+        subs.push(event::listen_with(|evt, status, _window_id| {
+            if let iced::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) = evt
+                && matches!(status, event::Status::Ignored)
+                && !modifiers.command()
+                && !modifiers.alt()
+            {
+                return list_key(&key).map(Message::ListKeyPressed);
+            }
+            None
+        }));
+    }
+
     // Track window metrics (Size)
     subs.push(event::listen_with(|evt, _status, _window_id| match evt {
         iced::Event::Window(window::Event::Resized(size)) => Some(Message::WindowResized(size)),
+        iced::Event::Window(window::Event::CloseRequested) => Some(Message::CloseWindow),
         _ => None,
     }));
 
+    // Reload the config when it is edited by hand (or by another instance)
+    if matches!(app.state, AppState::Active | AppState::Settings) {
+        subs.push(Subscription::run(config_watcher));
+    }
+
+    // A running `cfait daemon` syncs and reminds; we only follow its replica
+    if matches!(app.state, AppState::Active) && app.daemon_running {
+        subs.push(Subscription::run(daemon_feed));
+    }
+    if matches!(app.state, AppState::Active) {
+        subs.push(iced::time::every(STAMP_POLL).map(|_| Message::CheckDataStamp));
+        subs.push(Subscription::run_with(app.store.changes(), store_feed));
+    }
+
+    // Due-date reminders and the tasks' own
+    if matches!(app.state, AppState::Active) && !app.daemon_running {
+        subs.push(iced::time::every(CHECK_INTERVAL).map(|_| Message::CheckReminders));
+    }
+
+    // Restarted whenever the endpoint or credentials change
+    if matches!(app.state, AppState::Active)
+        && !app.daemon_running
+        && app.client.is_some()
+        && let Some(url) = push_url_input(&app.ob_push_url)
+    {
+        let target = PushTarget {
+            url,
+            username: app.ob_user.clone(),
+            password: app.ob_pass.clone(),
+            insecure: app.ob_insecure,
+        };
+        subs.push(Subscription::run_with(target, push_listener));
+    }
+
     Subscription::batch(subs)
 }
+
+/// The key as `ListKey::from_key` names it.
+fn list_key(key: &keyboard::Key) -> Option<ListKey> {
+    use iced::keyboard::key::Named;
+    match key {
+        keyboard::Key::Named(named) => ListKey::from_key(match named {
+            Named::ArrowDown => "Down",
+            Named::ArrowUp => "Up",
+            Named::PageDown => "PageDown",
+            Named::PageUp => "PageUp",
+            Named::Space => " ",
+            Named::Enter => "Enter",
+            Named::Delete => "Delete",
+            _ => return None,
+        }),
+        keyboard::Key::Character(c) => ListKey::from_key(c.as_str()),
+        _ => None,
+    }
+}
+
+fn push_listener(target: &PushTarget) -> impl Stream<Item = Message> {
+    let target = target.clone();
+    iced::stream::channel(10, async move |mut output| {
+        let (tx, mut rx) = futures::channel::mpsc::unbounded();
+        // Stops by itself once this stream (and so the receiver) is dropped
+        get_runtime().spawn(push::listen(target, move |hint| {
+            tx.unbounded_send(hint).is_ok()
+        }));
+        while let Some(hint) = rx.next().await {
+            let _ = output.send(Message::RemoteChanged(hint)).await;
+        }
+    })
+}
+
+/// Reloads the replica whenever the daemon announces a sync. While it is unreachable,
+/// `CheckDataStamp` still notices its writes.
+fn daemon_feed() -> impl Stream<Item = Message> {
+    iced::stream::channel(10, async move |mut output| {
+        loop {
+            let (tx, mut rx) = futures::channel::mpsc::unbounded();
+            get_runtime().spawn(ipc::subscribe(move |_| tx.unbounded_send(()).is_ok()));
+            while rx.next().await.is_some() {
+                let _ = output.send(Message::ReloadReplica).await;
+            }
+            let _ = get_runtime().spawn(tokio::time::sleep(REPLICA_POLL)).await;
+        }
+    })
+}
+
+/// The changes of the store, those that come together (a sync loads every calendar) as one.
+fn store_feed(changes: &ChangeFeed) -> impl Stream<Item = Message> {
+    let events = changes.subscribe();
+    iced::stream::channel(10, async move |mut output| {
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        // Waiting on the store blocks: on a thread of its own, which ends with the stream
+        get_runtime().spawn_blocking(move || {
+            for event in events {
+                if tx.unbounded_send(event).is_err() {
+                    break;
+                }
+            }
+        });
+        let mut batches = rx.ready_chunks(STORE_BATCH);
+        while let Some(events) = batches.next().await {
+            let _ = output.send(Message::StoreChanged(events)).await;
+        }
+    })
+}
+
+fn config_watcher() -> impl Stream<Item = Message> {
+    iced::stream::channel(10, async move |mut output| {
+        let (tx, mut rx) = futures::channel::mpsc::unbounded();
+        // The watcher must live as long as the stream
+        let Ok(_watcher) = Config::watch(move |res| {
+            let _ = tx.unbounded_send(res);
+        }) else {
+            return;
+        };
+        while let Some(res) = rx.next().await {
+            let _ = output.send(Message::ConfigChanged(res)).await;
+        }
+    })
+}
@@ -1,11 +1,13 @@
 // File: src/gui/update/common.rs
-use crate::config::Config;
+use crate::config::{CalendarDefaults, Config};
 use crate::gui::async_ops::*;
 use crate::gui::message::Message;
-use crate::gui::state::GuiApp;
-use crate::store::FilterOptions;
-use chrono::{Duration, Utc};
+use crate::gui::state::{GuiApp, SidebarMode};
+use crate::session::{Session, SidebarView};
+use crate::store::{self, FilterOptions, GroupBy, NotStarted, flatten_groups, group_tasks};
+use chrono::{Duration, Local, Utc};
 use iced::Task;
+use std::collections::HashMap;
 
 pub fn refresh_filtered_tasks(app: &mut GuiApp) {
     let cal_filter = None;
@@ -18,18 +20,51 @@ pub fn refresh_filtered_tasks(app: &mut GuiApp) {
         None
     };
 
-    app.tasks = app.store.filter(FilterOptions {
+    let completed_cutoff = app
+        .completed_retention_days
+        .filter(|_| !app.show_all_completed)
+        .map(|days| Utc::now() - Duration::days(days as i64));
+
+    let names: HashMap<String, String> = app
+        .calendars
+        .iter()
+        .map(|c| (c.href.clone(), c.name.clone()))
+        .collect();
+    let options = FilterOptions {
         active_cal_href: cal_filter,
         hidden_calendars: &app.hidden_calendars,
         selected_categories: &app.selected_categories,
         match_all_categories: app.match_all_categories,
         search_term: &app.search_value,
+        calendar_names: &names,
         hide_completed_global: app.hide_completed,
         cutoff_date,
         min_duration: app.filter_min_duration,
         max_duration: app.filter_max_duration,
         include_unset_duration: app.filter_include_unset_duration,
-    });
+        completed_cutoff,
+        fold_subtasks: app.search_value.is_empty(),
+        quick_filters: &app.quick_filters,
+        hide_not_started: app.not_started == NotStarted::Hide,
+    };
+    app.calendar_stats = app.store.calendar_stats(options);
+    app.tasks = if app.next_actions {
+        app.store.next_actions(options, Utc::now())
+    } else {
+        app.store.filter(options)
+    };
+    app.mixed_calendars = store::mixes_calendars(&app.tasks);
+
+    app.task_rows.clear();
+    if app.group_by != GroupBy::None {
+        let groups = group_tasks(
+            std::mem::take(&mut app.tasks),
+            app.group_by,
+            &names,
+            Local::now().date_naive(),
+        );
+        (app.tasks, app.task_rows) = flatten_groups(groups, &app.collapsed_groups);
+    }
 }
 
 pub fn save_config(app: &GuiApp) {
@@ -41,14 +76,98 @@ pub fn save_config(app: &GuiApp) {
         hide_completed: app.hide_completed,
         hide_fully_completed_tags: app.hide_fully_completed_tags,
         allow_insecure_certs: app.ob_insecure,
+        push_url: push_url_input(&app.ob_push_url),
         hidden_calendars: app.hidden_calendars.iter().cloned().collect(),
         disabled_calendars: app.disabled_calendars.iter().cloned().collect(),
         tag_aliases: app.tag_aliases.clone(),
+        calendar_defaults: app.calendar_defaults.clone(),
+        templates: app.templates.clone(),
+        move_subtree: app.move_subtree,
+        new_task_placement: app.new_task_placement,
         sort_cutoff_months: app.sort_cutoff_months,
+        completed_retention_days: app.completed_retention_days,
+        notify_before_minutes: app.notify_before_minutes,
+        priority_bands: app.priority_bands.clone(),
+        smart_syntax: app.smart_syntax.clone(),
+        review_after_weeks: app.review_after_weeks,
+        gui_density: app.density,
+        gui_font_size: app.font_size,
+        palette: app.palette,
+        underline_priority: app.underline_priority,
+        due_display: app.due_display,
+        due_soon_days: app.due_soon_days,
+        not_started: app.not_started,
+        language: app.language.clone(),
+        imap: app.imap.clone(),
+        tui_columns: app.tui_columns.clone(),
+        request_limits: app.request_limits,
+        wire_log: app.wire_log,
+        prune_completed_days: app.prune_completed_days,
+        inbox_calendar: app.inbox_calendar.clone(),
+        user_agent: app.user_agent.clone(),
     }
     .save();
 }
 
+/// Saves where the user is, for the next start (best-effort: the app is closing).
+pub fn save_session(app: &GuiApp) {
+    let session = Session {
+        active_calendar: app.active_cal_href.clone(),
+        selected_task: app.selected_uid.clone(),
+        sidebar: match app.sidebar_mode {
+            SidebarMode::Calendars => SidebarView::Calendars,
+            SidebarMode::Categories => SidebarView::Tags,
+        },
+        selected_tags: app.selected_categories.iter().cloned().collect(),
+        match_all_tags: app.match_all_categories,
+        group_by: app.group_by,
+        collapsed_groups: app.collapsed_groups.iter().cloned().collect(),
+        scroll_y: app.list_scroll_y,
+        ..Default::default()
+    };
+    if let Err(e) = session.save("gui") {
+        eprintln!("Could not save the session: {}", e);
+    }
+}
+
+/// Puts back a saved session. `Loaded` checks the calendar and scrolls to `list_scroll_y`.
+pub fn restore_session(app: &mut GuiApp, session: Session) {
+    app.active_cal_href = session.active_calendar;
+    app.selected_uid = session.selected_task;
+    app.sidebar_mode = match session.sidebar {
+        SidebarView::Tags => SidebarMode::Categories,
+        SidebarView::Calendars | SidebarView::Agenda => SidebarMode::Calendars,
+    };
+    app.selected_categories = session.selected_tags.into_iter().collect();
+    app.match_all_categories = session.match_all_tags;
+    app.group_by = session.group_by;
+    app.collapsed_groups = session.collapsed_groups.into_iter().collect();
+    app.list_scroll_y = session.scroll_y;
+}
+
+/// Empty settings field = no push listener
+pub fn push_url_input(input: &str) -> Option<String> {
+    let url = input.trim();
+    (!url.is_empty()).then(|| url.to_string())
+}
+
+/// Opens the link the app was started with, once the replica or the server filled the store.
+pub fn open_pending_link(app: &mut GuiApp) -> Task<Message> {
+    match app.pending_link.take() {
+        Some(link) => Task::done(Message::OpenDeepLink(link)),
+        None => Task::none(),
+    }
+}
+
+/// Sets the per-calendar defaults and the text shown in their settings fields.
+pub fn load_calendar_defaults(app: &mut GuiApp, defaults: HashMap<String, CalendarDefaults>) {
+    app.calendar_defaults_inputs = defaults
+        .iter()
+        .map(|(href, d)| (href.clone(), d.to_smart_string()))
+        .collect();
+    app.calendar_defaults = defaults;
+}
+
 /// Helper: Find all tasks with a specific alias tag and ensure they have all target tags.
 /// Returns an Iced Task batch if network operations are needed.
 pub fn apply_alias_retroactively(
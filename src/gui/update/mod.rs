@@ -15,11 +15,13 @@ pub fn update(app: &mut GuiApp, message: Message) -> Task<Message> {
         Message::DeleteComplete(_) => Task::none(),
 
         Message::ConfigLoaded(_)
+        | Message::ConfigChanged(_)
         | Message::ObUrlChanged(_)
         | Message::ObUserChanged(_)
         | Message::ObPassChanged(_)
         | Message::ObDefaultCalChanged(_)
         | Message::ObInsecureToggled(_)
+        | Message::ObPushUrlChanged(_)
         | Message::ObSubmit
         | Message::OpenSettings
         | Message::CancelSettings
@@ -28,18 +30,44 @@ pub fn update(app: &mut GuiApp, message: Message) -> Task<Message> {
         | Message::AliasValueInput(_)
         | Message::AddAlias
         | Message::RemoveAlias(_)
-        | Message::ObSortMonthsChanged(_) => settings::handle(app, message),
+        | Message::EditAlias(_)
+        | Message::TemplateNameInput(_)
+        | Message::TemplateTitleInput(_)
+        | Message::TemplateSubtasksInput(_)
+        | Message::AddTemplate
+        | Message::RemoveTemplate(_)
+        | Message::BackupNow
+        | Message::RestoreBackup(_)
+        | Message::ReportFromChanged(_)
+        | Message::ReportToChanged(_)
+        | Message::ExportReport
+        | Message::PrintList
+        | Message::PrintAgenda
+        | Message::CalendarDefaultsChanged(_, _)
+        | Message::ObSortMonthsChanged(_)
+        | Message::ObRetentionDaysChanged(_)
+        | Message::ObNotifyBeforeChanged(_) => settings::handle(app, message),
 
         Message::InputChanged(_)
         | Message::DescriptionChanged(_)
         | Message::StartCreateChild(_)
         | Message::SubmitTask
+        | Message::SubmitNote
         | Message::ToggleTask(_, _)
         | Message::EditTaskStart(_)
         | Message::CancelEdit
         | Message::DeleteTask(_)
         | Message::ChangePriority(_, _)
         | Message::SetTaskStatus(_, _)
+        | Message::SkipOccurrence(_)
+        | Message::ToggleSubtasksHidden(_)
+        | Message::RescheduleInputChanged(_)
+        | Message::RescheduleOccurrence(_)
+        | Message::RecurrenceFreqSelected(_)
+        | Message::RecurrenceIntervalStep(_)
+        | Message::RecurrenceDayToggled(_)
+        | Message::RecurrenceMonthDaySelected(_)
+        | Message::ClearRecurrence
         | Message::YankTask(_)
         | Message::ClearYank
         | Message::MakeChild(_)
@@ -47,10 +75,31 @@ pub fn update(app: &mut GuiApp, message: Message) -> Task<Message> {
         | Message::RemoveDependency(_, _)
         | Message::AddDependency(_)
         | Message::MoveTask(_, _)
+        | Message::DuplicateTask(_, _)
+        | Message::ShareTask(_, _)
+        | Message::CheckReminders
+        | Message::ReminderAnswered(_, _)
+        | Message::StartReview
+        | Message::StopReview
+        | Message::ReviewDueChanged(_)
+        | Message::ReviewAnswered(_)
+        | Message::StartTriage
+        | Message::StopTriage
+        | Message::TriageInputChanged(_)
+        | Message::TriageCalendarChanged(_)
+        | Message::TriageAnswered(_)
+        | Message::StartCardEdit(_)
+        | Message::CardEdited(_)
+        | Message::SaveCardEdit
+        | Message::CancelCardEdit
+        | Message::ToggleDuplicateSubtree(_)
+        | Message::BulkTagInput(_)
+        | Message::Bulk(_)
         | Message::MigrateLocalTo(_) => tasks::handle(app, message),
 
         Message::TabPressed(_)
         | Message::DismissError
+        | Message::DismissFetchFailures
         | Message::ToggleAllCalendars(_)
         | Message::ToggleCalendarVisibility(_, _)
         | Message::IsolateCalendar(_)
@@ -58,8 +107,20 @@ pub fn update(app: &mut GuiApp, message: Message) -> Task<Message> {
         | Message::CategoryToggled(_)
         | Message::ClearAllTags
         | Message::CategoryMatchModeChanged(_)
+        | Message::QuickFilterToggled(_)
+        | Message::CycleNotStarted
+        | Message::ToggleNextActions
         | Message::ToggleHideCompleted(_)
         | Message::ToggleHideFullyCompletedTags(_)
+        | Message::ToggleMoveSubtree(_)
+        | Message::DensityChanged(_)
+        | Message::FontSizeChanged(_)
+        | Message::PaletteChanged(_)
+        | Message::ToggleUnderlinePriority(_)
+        | Message::ToggleRelativeDue(_)
+        | Message::ToggleShowAllCompleted
+        | Message::GroupByChanged(_)
+        | Message::ToggleGroup(_)
         | Message::SelectCalendar(_)
         | Message::ToggleCalendarDisabled(_, _)
         | Message::SearchChanged(_)
@@ -67,6 +128,7 @@ pub fn update(app: &mut GuiApp, message: Message) -> Task<Message> {
         | Message::SetMaxDuration(_)
         | Message::ToggleIncludeUnsetDuration(_)
         | Message::ToggleDetails(_)
+        | Message::ShowFullDescription(_)
         | Message::OpenHelp
         | Message::CloseHelp
         | Message::WindowDragged
@@ -74,15 +136,34 @@ pub fn update(app: &mut GuiApp, message: Message) -> Task<Message> {
         | Message::CloseWindow
         | Message::ResizeStart(_)
         | Message::WindowResized(_)
-        | Message::JumpToTag(_) => view::handle(app, message),
+        | Message::ListScrolled(_)
+        | Message::StoreChanged(_)
+        | Message::JumpToTag(_)
+        | Message::TaskClicked(_)
+        | Message::ModifiersChanged(_)
+        | Message::ListKeyPressed(_)
+        | Message::ClearSelection
+        | Message::OpenDeepLink(_) => view::handle(app, message),
 
         Message::Refresh
+        | Message::ForceRefresh(_)
+        | Message::RemoteChanged(_)
+        | Message::ReloadReplica
+        | Message::DaemonSynced(_)
+        | Message::CheckDataStamp
         | Message::Loaded(_)
+        | Message::CalendarsListed(_)
         | Message::RefreshedAll(_)
         | Message::TasksRefreshed(_)
         | Message::SyncSaved(_)
         | Message::SyncToggleComplete(_)
         | Message::TaskMoved(_)
-        | Message::MigrationComplete(_) => network::handle(app, message),
+        | Message::BulkDone(_)
+        | Message::MigrationComplete(_)
+        | Message::OpenPendingChanges
+        | Message::ClosePendingChanges
+        | Message::HoldPendingChange(_, _)
+        | Message::OpenWireLog
+        | Message::CloseWireLog => network::handle(app, message),
     }
 }
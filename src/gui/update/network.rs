@@ -1,21 +1,30 @@
 // File: src/gui/update/network.rs
 use crate::cache::Cache;
+use crate::client::RustyClient;
+use crate::client::wirelog;
 use crate::config::Config;
 use crate::gui::async_ops::*;
 use crate::gui::message::Message;
 use crate::gui::state::{AppState, GuiApp};
-use crate::gui::update::common::{refresh_filtered_tasks, save_config};
+use crate::gui::update::common::{open_pending_link, refresh_filtered_tasks, save_config};
+use crate::i18n::tr_args;
 use crate::journal::Journal;
 use crate::model::CalendarListEntry;
-use crate::storage::{LOCAL_CALENDAR_HREF, LOCAL_CALENDAR_NAME};
+use crate::storage::{DataStamp, LOCAL_CALENDAR_HREF, LOCAL_CALENDAR_NAME, LocalStorage};
 use iced::Task;
+use iced::widget::operation;
+use iced::widget::scrollable::RelativeOffset;
 
 pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
     match message {
         Message::Refresh => {
             app.loading = true;
             app.error_msg = None;
+            app.pending_changes = None;
 
+            if app.daemon_running {
+                return Task::perform(async_daemon_sync_wrapper(), Message::DaemonSynced);
+            }
             if app.client.is_some()
                 && let Ok(cfg) = Config::load()
             {
@@ -23,6 +32,61 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             }
             Task::none()
         }
+        Message::ForceRefresh(href) => {
+            let Some(client) = app.client.clone().filter(|c| c.client.is_some()) else {
+                app.error_msg = Some("Offline: the local copy is kept.".to_string());
+                return Task::none();
+            };
+            // Local tasks and the offline queue are kept, only the mirrors go
+            let res = match href {
+                Some(href) => client.invalidate_calendar(&href),
+                None => client.invalidate_all().map(|_| ()),
+            };
+            match res {
+                Ok(()) => handle(app, Message::Refresh),
+                Err(e) => {
+                    app.error_msg = Some(e);
+                    Task::none()
+                }
+            }
+        }
+        Message::RemoteChanged(hint) => {
+            let Some(client) = app.client.clone() else {
+                return Task::none();
+            };
+            match hint {
+                Some(href) if app.calendars.iter().any(|c| c.href == href) => {
+                    if client.is_sync_disabled(&href) {
+                        return Task::none();
+                    }
+                    Task::perform(async_fetch_wrapper(client, href), Message::TasksRefreshed)
+                }
+                _ => Task::perform(
+                    async_fetch_all_wrapper(client, app.calendars.clone()),
+                    Message::RefreshedAll,
+                ),
+            }
+        }
+        Message::ReloadReplica => {
+            if !app.loading {
+                reload_replica(app);
+            }
+            Task::none()
+        }
+        Message::CheckDataStamp => {
+            if !app.loading && DataStamp::current() != app.data_stamp {
+                reload_replica(app);
+            }
+            Task::none()
+        }
+        Message::DaemonSynced(res) => {
+            app.loading = false;
+            match res {
+                Ok(()) => reload_replica(app),
+                Err(e) => app.error_msg = Some(e),
+            }
+            Task::none()
+        }
         Message::Loaded(Ok((client, mut cals, tasks, mut active, warning))) => {
             app.client = Some(client.clone());
 
@@ -32,12 +96,15 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                 app.error_msg = None;
             }
 
-            app.unsynced_changes = !Journal::load().is_empty();
+            app.sync_status = Journal::status();
+            app.sync_times = Cache::sync_times();
 
             let local_entry = CalendarListEntry {
                 name: LOCAL_CALENDAR_NAME.to_string(),
                 href: LOCAL_CALENDAR_HREF.to_string(),
                 color: None,
+                is_shared: false,
+                is_read_only: false,
             };
 
             if !cals.iter().any(|c| c.href == LOCAL_CALENDAR_HREF) {
@@ -57,9 +124,8 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                 if cal.href == LOCAL_CALENDAR_HREF {
                     continue;
                 }
-                if let Ok((cached_tasks, _)) = Cache::load(&cal.href) {
-                    app.store.insert(cal.href.clone(), cached_tasks);
-                }
+                app.store
+                    .insert(cal.href.clone(), RustyClient::local_tasks(&cal.href));
             }
 
             let mut valid_active = None;
@@ -95,6 +161,28 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                 app.hide_completed = cfg.hide_completed;
                 app.hide_fully_completed_tags = cfg.hide_fully_completed_tags;
                 app.tag_aliases = cfg.tag_aliases;
+                app.calendar_defaults = cfg.calendar_defaults;
+                app.templates = cfg.templates;
+                app.priority_bands = cfg.priority_bands;
+                app.smart_syntax = cfg.smart_syntax;
+                app.review_after_weeks = cfg.review_after_weeks;
+                app.density = cfg.gui_density;
+                app.font_size = cfg.gui_font_size;
+                app.palette = cfg.palette;
+                app.underline_priority = cfg.underline_priority;
+                app.due_display = cfg.due_display;
+                app.due_soon_days = cfg.due_soon_days;
+                app.not_started = cfg.not_started;
+                app.language = cfg.language.clone();
+                app.imap = cfg.imap.clone();
+                app.tui_columns = cfg.tui_columns.clone();
+                app.request_limits = cfg.request_limits;
+                app.wire_log = cfg.wire_log;
+                app.prune_completed_days = cfg.prune_completed_days;
+                app.inbox_calendar = cfg.inbox_calendar.clone();
+                app.user_agent = cfg.user_agent.clone();
+                app.move_subtree = cfg.move_subtree;
+                app.new_task_placement = cfg.new_task_placement;
                 app.disabled_calendars = cfg.disabled_calendars.into_iter().collect();
             }
 
@@ -106,35 +194,109 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             refresh_filtered_tasks(app);
             app.loading = false;
 
-            if app.error_msg.is_none() {
+            let fetch = if app.error_msg.is_none() {
                 app.loading = true;
                 Task::perform(async_fetch_all_wrapper(client, cals), Message::RefreshedAll)
             } else {
                 Task::none()
-            }
+            };
+            // Back where the list was in the last session (or before reconnecting)
+            let scroll = operation::snap_to(
+                app.scrollable_id.clone(),
+                RelativeOffset {
+                    x: 0.0,
+                    y: app.list_scroll_y,
+                },
+            );
+            Task::batch([fetch, scroll, open_pending_link(app)])
         }
         Message::Loaded(Err(e)) => {
-            app.error_msg = Some(format!("Connection Failed: {}", e));
+            app.error_msg = Some(tr_args("error.connection_failed", &[("error", &e)]));
             app.state = AppState::Onboarding;
             app.loading = false;
             Task::none()
         }
-        Message::RefreshedAll(Ok(results)) => {
-            for (href, tasks) in results {
-                app.store.insert(href.clone(), tasks.clone());
+        Message::CalendarsListed(Ok((mut cals, warning))) => {
+            app.error_msg = warning;
+            if !cals.iter().any(|c| c.href == LOCAL_CALENDAR_HREF) {
+                cals.push(CalendarListEntry {
+                    name: LOCAL_CALENDAR_NAME.to_string(),
+                    href: LOCAL_CALENDAR_HREF.to_string(),
+                    color: None,
+                    is_shared: false,
+                    is_read_only: false,
+                });
+            }
+            // The cached tasks stay as shown (edited meanwhile, maybe): only calendars new
+            // or gone since the cache change the store
+            let gone: Vec<String> = app
+                .store
+                .calendars
+                .keys()
+                .filter(|href| !cals.iter().any(|c| c.href == **href))
+                .cloned()
+                .collect();
+            for href in gone {
+                app.store.insert(href, Vec::new());
+            }
+            for cal in &cals {
+                if !app.store.calendars.contains_key(&cal.href) {
+                    app.store
+                        .insert(cal.href.clone(), RustyClient::local_tasks(&cal.href));
+                }
+            }
+            app.calendars = cals.clone();
+            if app
+                .active_cal_href
+                .as_ref()
+                .is_none_or(|href| !app.calendars.iter().any(|c| c.href == *href))
+            {
+                app.active_cal_href = Some(LOCAL_CALENDAR_HREF.to_string());
             }
             refresh_filtered_tasks(app);
+
+            // Then the sync itself: the offline queue first, then what changed
+            match app.client.clone() {
+                Some(client) if app.error_msg.is_none() => {
+                    Task::perform(async_fetch_all_wrapper(client, cals), Message::RefreshedAll)
+                }
+                _ => {
+                    app.loading = false;
+                    Task::none()
+                }
+            }
+        }
+        Message::CalendarsListed(Err(e)) => {
+            app.error_msg = Some(tr_args("error.connection_failed", &[("error", &e)]));
+            app.state = AppState::Onboarding;
             app.loading = false;
             Task::none()
         }
+        Message::RefreshedAll(Ok(report)) => {
+            let failures = report.failure_lines(&app.calendars);
+            if failures != app.fetch_failures {
+                app.fetch_failures = failures;
+                app.fetch_failures_dismissed = false;
+            }
+            for (href, tasks) in report.tasks {
+                app.store.insert(href, tasks);
+            }
+            app.sync_status = Journal::status();
+            app.sync_times = Cache::sync_times();
+            refresh_filtered_tasks(app);
+            app.loading = false;
+            open_pending_link(app)
+        }
         Message::RefreshedAll(Err(e)) => {
-            app.error_msg = Some(format!("Sync warning: {}", e));
+            app.error_msg = Some(tr_args("error.sync_warning", &[("error", &e)]));
             app.loading = false;
-            Task::none()
+            open_pending_link(app)
         }
         Message::TasksRefreshed(Ok((href, tasks))) => {
             app.error_msg = None;
             app.store.insert(href.clone(), tasks.clone());
+            app.sync_status = Journal::status();
+            app.sync_times = Cache::sync_times();
 
             if app.active_cal_href.as_deref() == Some(&href) {
                 refresh_filtered_tasks(app);
@@ -151,8 +313,9 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             // Fix: Use update_or_add_task to ensure index is updated
             app.store.update_or_add_task(updated);
 
-            app.unsynced_changes = !Journal::load().is_empty();
-            if app.unsynced_changes {
+            app.sync_status = Journal::status();
+            app.sync_times = Cache::sync_times();
+            if !app.sync_status.is_synced() {
                 app.error_msg = Some("Offline: Changes queued.".to_string());
             }
             refresh_filtered_tasks(app);
@@ -179,6 +342,15 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             }
         },
         Message::TaskMoved(Ok(new_task)) => {
+            app.pending_moves = app.pending_moves.saturating_sub(1);
+            if app
+                .error_msg
+                .as_deref()
+                .is_some_and(|m| m.starts_with("Moving "))
+            {
+                app.error_msg = (app.pending_moves > 0)
+                    .then(|| format!("Moving tasks... {} left", app.pending_moves));
+            }
             if let Some(list) = app.store.calendars.get_mut(&new_task.calendar_href) {
                 if let Some(idx) = list.iter().position(|t| t.uid == new_task.uid) {
                     list[idx] = new_task.clone();
@@ -192,9 +364,26 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             Task::none()
         }
         Message::TaskMoved(Err(e)) => {
+            app.pending_moves = app.pending_moves.saturating_sub(1);
             app.error_msg = Some(format!("Move failed: {}", e));
             Task::none()
         }
+        Message::BulkDone(Ok(changed)) => {
+            for task in changed {
+                app.store.update_or_add_task(task);
+            }
+            app.sync_status = Journal::status();
+            app.sync_times = Cache::sync_times();
+            if !app.sync_status.is_synced() {
+                app.error_msg = Some("Offline: Changes queued.".to_string());
+            }
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
+        Message::BulkDone(Err(e)) => {
+            app.error_msg = Some(format!("Bulk action failed: {}", e));
+            Task::none()
+        }
         Message::MigrationComplete(Ok(count)) => {
             app.loading = false;
             app.error_msg = Some(format!("Exported {} tasks successfully.", count));
@@ -212,6 +401,47 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             app.error_msg = Some(format!("Export failed: {}", e));
             Task::none()
         }
+        Message::OpenPendingChanges => {
+            app.pending_changes = Some(Journal::load().preview());
+            Task::none()
+        }
+        Message::ClosePendingChanges => {
+            app.pending_changes = None;
+            Task::none()
+        }
+        Message::HoldPendingChange(uid, held) => {
+            if let Err(e) = Journal::hold(&uid, held) {
+                app.error_msg = Some(format!("Could not update the queue: {}", e));
+            }
+            app.pending_changes = Some(Journal::load().preview());
+            Task::none()
+        }
+        Message::OpenWireLog => {
+            app.wire_entries = Some(wirelog::recent().into_iter().rev().collect());
+            Task::none()
+        }
+        Message::CloseWireLog => {
+            app.wire_entries = None;
+            Task::none()
+        }
         _ => Task::none(),
     }
 }
+
+/// Re-reads the replica (and journal) that `cfait daemon` keeps up to date.
+fn reload_replica(app: &mut GuiApp) {
+    for cal in &app.calendars {
+        if cal.href == LOCAL_CALENDAR_HREF {
+            if let Ok(local) = LocalStorage::load() {
+                app.store.insert(cal.href.clone(), local);
+            }
+        } else if !app.disabled_calendars.contains(&cal.href) {
+            app.store
+                .insert(cal.href.clone(), RustyClient::local_tasks(&cal.href));
+        }
+    }
+    app.sync_status = Journal::status();
+    app.sync_times = Cache::sync_times();
+    app.data_stamp = DataStamp::current();
+    refresh_filtered_tasks(app);
+}
@@ -1,12 +1,25 @@
 // File: src/gui/update/settings.rs
+use crate::backup;
 use crate::cache::Cache;
-use crate::config::Config;
+use crate::client::RustyClient;
+use crate::config::{CalendarDefaults, Config, validate_alias, validate_template};
 use crate::gui::async_ops::*;
 use crate::gui::message::Message;
 use crate::gui::state::{AppState, GuiApp};
-use crate::gui::update::common::{apply_alias_retroactively, refresh_filtered_tasks, save_config};
-use crate::storage::{LOCAL_CALENDAR_HREF, LOCAL_CALENDAR_NAME, LocalStorage};
+use crate::gui::update::common::{
+    apply_alias_retroactively, load_calendar_defaults, open_pending_link, push_url_input,
+    refresh_filtered_tasks, save_config,
+};
+use crate::model::agenda;
+use crate::model::report::{completed_tasks_csv, this_month};
+use crate::model::template::TaskTemplate;
+use crate::paths::AppPaths;
+use crate::storage::{DaemonLock, LOCAL_CALENDAR_HREF, LOCAL_CALENDAR_NAME, LocalStorage};
+use chrono::{Local, NaiveDate};
 use iced::Task;
+use iced::widget::operation;
+use iced::widget::scrollable::RelativeOffset;
+use std::collections::HashMap;
 
 pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
     match message {
@@ -18,8 +31,37 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                 Some(m) => m.to_string(),
                 None => "".to_string(),
             };
+            app.completed_retention_days = config.completed_retention_days;
+            app.ob_retention_input = config
+                .completed_retention_days
+                .map(|d| d.to_string())
+                .unwrap_or_default();
+            set_notify_before(app, config.notify_before_minutes);
             app.ob_insecure = config.allow_insecure_certs;
+            app.ob_push_url = config.push_url.clone().unwrap_or_default();
             app.tag_aliases = config.tag_aliases.clone();
+            load_calendar_defaults(app, config.calendar_defaults.clone());
+            app.templates = config.templates.clone();
+            app.priority_bands = config.priority_bands.clone();
+            app.smart_syntax = config.smart_syntax.clone();
+            app.review_after_weeks = config.review_after_weeks;
+            app.density = config.gui_density;
+            app.font_size = config.gui_font_size;
+            app.palette = config.palette;
+            app.underline_priority = config.underline_priority;
+            app.due_display = config.due_display;
+            app.due_soon_days = config.due_soon_days;
+            app.not_started = config.not_started;
+            app.language = config.language.clone();
+            app.imap = config.imap.clone();
+            app.tui_columns = config.tui_columns.clone();
+            app.request_limits = config.request_limits;
+            app.wire_log = config.wire_log;
+            app.prune_completed_days = config.prune_completed_days;
+            app.inbox_calendar = config.inbox_calendar.clone();
+            app.user_agent = config.user_agent.clone();
+            app.move_subtree = config.move_subtree;
+            app.new_task_placement = config.new_task_placement;
             app.hide_completed = config.hide_completed;
             app.hide_fully_completed_tags = config.hide_fully_completed_tags;
 
@@ -27,6 +69,7 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             app.ob_user = config.username.clone();
             app.ob_pass = config.password.clone();
             app.ob_default_cal = config.default_calendar.clone();
+            app.daemon_running = DaemonLock::is_held();
 
             let mut cached_cals = Cache::load_calendars().unwrap_or_default();
 
@@ -35,6 +78,8 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                     name: LOCAL_CALENDAR_NAME.to_string(),
                     href: LOCAL_CALENDAR_HREF.to_string(),
                     color: None,
+                    is_shared: false,
+                    is_read_only: false,
                 });
             }
             app.calendars = cached_cals;
@@ -46,11 +91,11 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                     .insert(LOCAL_CALENDAR_HREF.to_string(), local_tasks);
             }
 
+            // Local-first: show the replica (with unsynced changes) while connecting
             for cal in &app.calendars {
-                if cal.href != LOCAL_CALENDAR_HREF
-                    && let Ok((tasks, _)) = Cache::load(&cal.href)
-                {
-                    app.store.insert(cal.href.clone(), tasks);
+                if cal.href != LOCAL_CALENDAR_HREF {
+                    app.store
+                        .insert(cal.href.clone(), RustyClient::local_tasks(&cal.href));
                 }
             }
 
@@ -76,13 +121,108 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
 
             refresh_filtered_tasks(app);
             app.state = AppState::Active;
+            // The client takes edits right away (queued until the server answers), the
+            // calendars are listed and synced in the background
+            let client = match RustyClient::from_config(&config) {
+                Ok(client) => client,
+                Err(e) => return Task::done(Message::Loaded(Err(e))),
+            };
+            app.client = Some(client.clone());
             app.loading = true;
-            Task::perform(connect_and_fetch_wrapper(config), Message::Loaded)
+            // Back where the list was in the last session
+            let scroll = operation::snap_to(
+                app.scrollable_id.clone(),
+                RelativeOffset {
+                    x: 0.0,
+                    y: app.list_scroll_y,
+                },
+            );
+            Task::batch([
+                Task::perform(async_calendars_wrapper(client), Message::CalendarsListed),
+                scroll,
+                open_pending_link(app),
+            ])
         }
         Message::ConfigLoaded(Err(_)) => {
             app.state = AppState::Onboarding;
             Task::none()
         }
+        Message::ConfigChanged(Ok(cfg)) => {
+            let reconnect = cfg.url != app.ob_url
+                || cfg.username != app.ob_user
+                || cfg.password != app.ob_pass
+                || cfg.allow_insecure_certs != app.ob_insecure;
+            // The push listener follows the config through the subscription
+            app.ob_push_url = cfg.push_url.clone().unwrap_or_default();
+
+            app.hide_completed = cfg.hide_completed;
+            app.hide_fully_completed_tags = cfg.hide_fully_completed_tags;
+            app.tag_aliases = cfg.tag_aliases.clone();
+            load_calendar_defaults(app, cfg.calendar_defaults.clone());
+            app.templates = cfg.templates.clone();
+            app.priority_bands = cfg.priority_bands.clone();
+            app.smart_syntax = cfg.smart_syntax.clone();
+            app.review_after_weeks = cfg.review_after_weeks;
+            app.density = cfg.gui_density;
+            app.font_size = cfg.gui_font_size;
+            app.palette = cfg.palette;
+            app.underline_priority = cfg.underline_priority;
+            app.due_display = cfg.due_display;
+            app.due_soon_days = cfg.due_soon_days;
+            app.not_started = cfg.not_started;
+            app.language = cfg.language.clone();
+            app.imap = cfg.imap.clone();
+            app.tui_columns = cfg.tui_columns.clone();
+            app.request_limits = cfg.request_limits;
+            app.wire_log = cfg.wire_log;
+            app.prune_completed_days = cfg.prune_completed_days;
+            app.inbox_calendar = cfg.inbox_calendar.clone();
+            app.user_agent = cfg.user_agent.clone();
+            app.move_subtree = cfg.move_subtree;
+            app.new_task_placement = cfg.new_task_placement;
+            app.sort_cutoff_months = cfg.sort_cutoff_months;
+            app.ob_sort_months_input = cfg
+                .sort_cutoff_months
+                .map(|m| m.to_string())
+                .unwrap_or_default();
+            app.completed_retention_days = cfg.completed_retention_days;
+            app.ob_retention_input = cfg
+                .completed_retention_days
+                .map(|d| d.to_string())
+                .unwrap_or_default();
+            set_notify_before(app, cfg.notify_before_minutes);
+            app.hidden_calendars = cfg.hidden_calendars.iter().cloned().collect();
+            app.disabled_calendars = cfg.disabled_calendars.iter().cloned().collect();
+            if let Some(active) = &app.active_cal_href {
+                app.hidden_calendars.remove(active);
+            }
+            app.ob_default_cal = cfg.default_calendar.clone();
+            refresh_filtered_tasks(app);
+
+            if reconnect && matches!(app.state, AppState::Active) {
+                app.ob_url = cfg.url.clone();
+                app.ob_user = cfg.username.clone();
+                app.ob_pass = cfg.password.clone();
+                app.ob_insecure = cfg.allow_insecure_certs;
+                app.loading = true;
+                app.error_msg = Some("Config changed, reconnecting...".to_string());
+                return Task::perform(connect_and_fetch_wrapper(cfg), Message::Loaded);
+            }
+            if let Some(client) = &app.client {
+                let enabled = client.set_disabled_calendars(cfg.disabled_calendars);
+                return Task::batch(enabled.into_iter().map(|href| {
+                    Task::perform(
+                        async_fetch_wrapper(client.clone(), href),
+                        Message::TasksRefreshed,
+                    )
+                }));
+            }
+            Task::none()
+        }
+        Message::ConfigChanged(Err(e)) => {
+            app.error_msg = Some(format!("Config not reloaded: {}", e));
+            Task::none()
+        }
         Message::ObUrlChanged(v) => {
             app.ob_url = v;
             Task::none()
@@ -97,18 +237,25 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
         }
         Message::ObDefaultCalChanged(v) => {
             app.ob_default_cal = Some(v);
+            hot_apply(app);
             Task::none()
         }
         Message::ObInsecureToggled(val) => {
             app.ob_insecure = val;
             Task::none()
         }
+        Message::ObPushUrlChanged(v) => {
+            app.ob_push_url = v;
+            Task::none()
+        }
         Message::ObSubmit => {
             if app.ob_sort_months_input.trim().is_empty() {
                 app.sort_cutoff_months = None;
             } else if let Ok(n) = app.ob_sort_months_input.trim().parse::<u32>() {
                 app.sort_cutoff_months = Some(n);
             }
+            app.completed_retention_days = app.ob_retention_input.trim().parse::<u32>().ok();
+            app.notify_before_minutes = app.ob_notify_input.trim().parse::<u32>().ok();
 
             let mut config_to_save = Config::load().unwrap_or_else(|_| Config {
                 url: String::new(),
@@ -116,12 +263,37 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                 password: String::new(),
                 default_calendar: None,
                 allow_insecure_certs: false,
+                push_url: None,
                 hidden_calendars: Vec::new(),
                 disabled_calendars: Vec::new(),
                 hide_completed: app.hide_completed,
                 hide_fully_completed_tags: app.hide_fully_completed_tags,
                 tag_aliases: app.tag_aliases.clone(),
+                calendar_defaults: app.calendar_defaults.clone(),
+                templates: app.templates.clone(),
+                move_subtree: app.move_subtree,
+                new_task_placement: app.new_task_placement,
                 sort_cutoff_months: Some(6),
+                completed_retention_days: None,
+                notify_before_minutes: None,
+                priority_bands: app.priority_bands.clone(),
+                smart_syntax: app.smart_syntax.clone(),
+                review_after_weeks: app.review_after_weeks,
+                gui_density: app.density,
+                gui_font_size: app.font_size,
+                palette: app.palette,
+                underline_priority: app.underline_priority,
+                due_display: app.due_display,
+                due_soon_days: app.due_soon_days,
+                not_started: app.not_started,
+                language: app.language.clone(),
+                imap: app.imap.clone(),
+                tui_columns: app.tui_columns.clone(),
+                request_limits: app.request_limits,
+                wire_log: app.wire_log,
+                prune_completed_days: app.prune_completed_days,
+                inbox_calendar: app.inbox_calendar.clone(),
+                user_agent: app.user_agent.clone(),
             });
 
             config_to_save.url = app.ob_url.clone();
@@ -129,13 +301,42 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             config_to_save.password = app.ob_pass.clone();
             config_to_save.default_calendar = app.ob_default_cal.clone();
             config_to_save.allow_insecure_certs = app.ob_insecure;
+            config_to_save.push_url = push_url_input(&app.ob_push_url);
             config_to_save.hidden_calendars = app.hidden_calendars.iter().cloned().collect();
             config_to_save.disabled_calendars = app.disabled_calendars.iter().cloned().collect();
             config_to_save.hide_completed = app.hide_completed;
             config_to_save.hide_fully_completed_tags = app.hide_fully_completed_tags;
             config_to_save.tag_aliases = app.tag_aliases.clone();
+            config_to_save.calendar_defaults = app.calendar_defaults.clone();
+            config_to_save.templates = app.templates.clone();
+            config_to_save.move_subtree = app.move_subtree;
+            config_to_save.new_task_placement = app.new_task_placement;
             config_to_save.sort_cutoff_months = app.sort_cutoff_months;
+            config_to_save.completed_retention_days = app.completed_retention_days;
+            config_to_save.notify_before_minutes = app.notify_before_minutes;
+            config_to_save.priority_bands = app.priority_bands.clone();
+            config_to_save.smart_syntax = app.smart_syntax.clone();
+            config_to_save.review_after_weeks = app.review_after_weeks;
+            config_to_save.gui_density = app.density;
+            config_to_save.gui_font_size = app.font_size;
+            config_to_save.palette = app.palette;
+            config_to_save.underline_priority = app.underline_priority;
+            config_to_save.due_display = app.due_display;
+            config_to_save.due_soon_days = app.due_soon_days;
+            config_to_save.not_started = app.not_started;
+            config_to_save.language = app.language.clone();
+            config_to_save.imap = app.imap.clone();
+            config_to_save.tui_columns = app.tui_columns.clone();
+            config_to_save.request_limits = app.request_limits;
+            config_to_save.wire_log = app.wire_log;
+            config_to_save.prune_completed_days = app.prune_completed_days;
+            config_to_save.inbox_calendar = app.inbox_calendar.clone();
+            config_to_save.user_agent = app.user_agent.clone();
 
+            if let Err(e) = config_to_save.validate() {
+                app.error_msg = Some(e);
+                return Task::none();
+            }
             let _ = config_to_save.save();
 
             app.state = AppState::Loading;
@@ -152,13 +353,48 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                 app.hide_completed = cfg.hide_completed;
                 app.hide_fully_completed_tags = cfg.hide_fully_completed_tags;
                 app.ob_insecure = cfg.allow_insecure_certs;
+                app.ob_push_url = cfg.push_url.unwrap_or_default();
                 app.hidden_calendars = cfg.hidden_calendars.into_iter().collect();
                 app.tag_aliases = cfg.tag_aliases;
+                load_calendar_defaults(app, cfg.calendar_defaults);
+                app.templates = cfg.templates;
+                app.priority_bands = cfg.priority_bands;
+                app.smart_syntax = cfg.smart_syntax;
+                app.review_after_weeks = cfg.review_after_weeks;
+                app.density = cfg.gui_density;
+                app.font_size = cfg.gui_font_size;
+                app.palette = cfg.palette;
+                app.underline_priority = cfg.underline_priority;
+                app.due_display = cfg.due_display;
+                app.due_soon_days = cfg.due_soon_days;
+                app.not_started = cfg.not_started;
+                app.language = cfg.language.clone();
+                app.imap = cfg.imap.clone();
+                app.tui_columns = cfg.tui_columns.clone();
+                app.request_limits = cfg.request_limits;
+                app.wire_log = cfg.wire_log;
+                app.prune_completed_days = cfg.prune_completed_days;
+                app.inbox_calendar = cfg.inbox_calendar.clone();
+                app.user_agent = cfg.user_agent.clone();
+                app.move_subtree = cfg.move_subtree;
+                app.new_task_placement = cfg.new_task_placement;
                 app.sort_cutoff_months = cfg.sort_cutoff_months;
                 app.ob_sort_months_input = match cfg.sort_cutoff_months {
                     Some(m) => m.to_string(),
                     None => "".to_string(),
                 };
+                app.completed_retention_days = cfg.completed_retention_days;
+                app.ob_retention_input = cfg
+                    .completed_retention_days
+                    .map(|d| d.to_string())
+                    .unwrap_or_default();
+                set_notify_before(app, cfg.notify_before_minutes);
+            }
+            app.backups = backup::list().unwrap_or_default();
+            if app.report_from.is_empty() {
+                let (from, to) = this_month();
+                app.report_from = from.to_string();
+                app.report_to = to.to_string();
             }
             app.state = AppState::Settings;
             Task::none()
@@ -171,6 +407,7 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             app.ob_url.clear();
             app.ob_user.clear();
             app.ob_pass.clear();
+            app.ob_push_url.clear();
 
             let config_to_save = Config {
                 url: String::new(),
@@ -178,12 +415,37 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                 password: String::new(),
                 default_calendar: None,
                 allow_insecure_certs: false,
+                push_url: None,
                 hidden_calendars: Vec::new(),
                 disabled_calendars: Vec::new(),
                 hide_completed: app.hide_completed,
                 hide_fully_completed_tags: app.hide_fully_completed_tags,
                 tag_aliases: app.tag_aliases.clone(),
+                calendar_defaults: app.calendar_defaults.clone(),
+                templates: app.templates.clone(),
+                move_subtree: app.move_subtree,
+                new_task_placement: app.new_task_placement,
                 sort_cutoff_months: app.sort_cutoff_months,
+                completed_retention_days: app.completed_retention_days,
+                notify_before_minutes: app.notify_before_minutes,
+                priority_bands: app.priority_bands.clone(),
+                smart_syntax: app.smart_syntax.clone(),
+                review_after_weeks: app.review_after_weeks,
+                gui_density: app.density,
+                gui_font_size: app.font_size,
+                palette: app.palette,
+                underline_priority: app.underline_priority,
+                due_display: app.due_display,
+                due_soon_days: app.due_soon_days,
+                not_started: app.not_started,
+                language: app.language.clone(),
+                imap: app.imap.clone(),
+                tui_columns: app.tui_columns.clone(),
+                request_limits: app.request_limits,
+                wire_log: app.wire_log,
+                prune_completed_days: app.prune_completed_days,
+                inbox_calendar: app.inbox_calendar.clone(),
+                user_agent: app.user_agent.clone(),
             };
 
             let _ = config_to_save.save();
@@ -207,22 +469,25 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                     .map(|s| s.trim().trim_start_matches('#').to_string())
                     .filter(|s| !s.is_empty())
                     .collect();
+                let key = app
+                    .alias_input_key
+                    .trim()
+                    .trim_start_matches('#')
+                    .to_string();
+
+                if let Err(e) = validate_alias(&key, &tags) {
+                    app.error_msg = Some(e);
+                    return Task::none();
+                }
+                app.error_msg = None;
+                app.tag_aliases.insert(key.clone(), tags.clone());
+                app.alias_input_key.clear();
+                app.alias_input_values.clear();
+                save_config(app);
 
-                if !tags.is_empty() {
-                    let key = app
-                        .alias_input_key
-                        .trim()
-                        .trim_start_matches('#')
-                        .to_string();
-                    app.tag_aliases.insert(key.clone(), tags.clone());
-                    app.alias_input_key.clear();
-                    app.alias_input_values.clear();
-                    save_config(app);
-
-                    // Use common helper
-                    if let Some(task) = apply_alias_retroactively(app, &key, &tags) {
-                        return task;
-                    }
+                // Use common helper
+                if let Some(task) = apply_alias_retroactively(app, &key, &tags) {
+                    return task;
                 }
             }
             Task::none()
@@ -232,12 +497,191 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             save_config(app);
             Task::none()
         }
+        Message::EditAlias(key) => {
+            // Adding it again under the same key replaces it
+            if let Some(tags) = app.tag_aliases.get(&key) {
+                app.alias_input_values = tags.join(", ");
+                app.alias_input_key = key;
+            }
+            Task::none()
+        }
+        Message::TemplateNameInput(v) => {
+            app.template_input_name = v;
+            Task::none()
+        }
+        Message::TemplateTitleInput(v) => {
+            app.template_input_title = v;
+            Task::none()
+        }
+        Message::TemplateSubtasksInput(v) => {
+            app.template_input_subtasks = v;
+            Task::none()
+        }
+        Message::AddTemplate => {
+            let template = TaskTemplate {
+                name: app.template_input_name.trim().to_string(),
+                title: app.template_input_title.trim().to_string(),
+                subtasks: app
+                    .template_input_subtasks
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(|title| TaskTemplate {
+                        title: title.to_string(),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            };
+            if let Err(e) = validate_template(&template, &app.templates) {
+                app.error_msg = Some(e);
+                return Task::none();
+            }
+            app.error_msg = None;
+            app.templates.push(template);
+            app.template_input_name.clear();
+            app.template_input_title.clear();
+            app.template_input_subtasks.clear();
+            save_config(app);
+            Task::none()
+        }
+        Message::RemoveTemplate(name) => {
+            app.templates.retain(|t| t.name != name);
+            save_config(app);
+            Task::none()
+        }
+        Message::BackupNow => {
+            if let Err(e) = backup::snapshot(None) {
+                app.error_msg = Some(format!("Backup failed: {}", e));
+            }
+            app.backups = backup::list().unwrap_or_default();
+            Task::none()
+        }
+        Message::ReportFromChanged(v) => {
+            app.report_from = v;
+            Task::none()
+        }
+        Message::ReportToChanged(v) => {
+            app.report_to = v;
+            Task::none()
+        }
+        Message::ExportReport => {
+            let parse = |s: &str| NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d");
+            let (Ok(from), Ok(to)) = (parse(&app.report_from), parse(&app.report_to)) else {
+                app.error_msg = Some("Dates must look like 2025-03-31".to_string());
+                return Task::none();
+            };
+            let names = app
+                .calendars
+                .iter()
+                .map(|c| (c.href.clone(), c.name.clone()))
+                .collect();
+            let csv = completed_tasks_csv(app.store.calendars.values().flatten(), &names, from, to);
+            let file_name = format!("cfait-completed-{}-{}.csv", from, to);
+            let res = AppPaths::get_export_dir().and_then(|dir| {
+                let path = dir.join(file_name);
+                LocalStorage::atomic_write(&path, csv)?;
+                Ok(path)
+            });
+            app.error_msg = Some(match res {
+                Ok(path) => format!("Saved to {}", path.display()),
+                Err(e) => format!("Export failed: {}", e),
+            });
+            Task::none()
+        }
+        Message::PrintList | Message::PrintAgenda => {
+            let names: HashMap<String, String> = app
+                .calendars
+                .iter()
+                .map(|c| (c.href.clone(), c.name.clone()))
+                .collect();
+            let (name, html) = if matches!(message, Message::PrintAgenda) {
+                let start = agenda::week_start(Local::now().date_naive());
+                let tasks = app
+                    .store
+                    .calendars
+                    .iter()
+                    .filter(|(href, _)| {
+                        !app.hidden_calendars.contains(*href)
+                            && !app.disabled_calendars.contains(*href)
+                    })
+                    .flat_map(|(_, tasks)| tasks);
+                let name = format!("cfait_week_{}", start.format("%Y-%m-%d"));
+                (name, agenda::week_html(tasks, &names, start))
+            } else {
+                let title = app
+                    .active_cal_href
+                    .as_ref()
+                    .and_then(|href| names.get(href))
+                    .map_or("Tasks", |n| n.as_str());
+                (
+                    "cfait_list".to_string(),
+                    agenda::list_html(title, &app.tasks, &names),
+                )
+            };
+            app.error_msg = Some(match LocalStorage::export_page(&name, &html) {
+                Ok(path) => format!("Saved to {}", path.display()),
+                Err(e) => format!("Export failed: {}", e),
+            });
+            Task::none()
+        }
+        Message::RestoreBackup(name) => match backup::restore(&name) {
+            // Show the restored settings; the tasks reload through CheckDataStamp
+            Ok(()) => Task::done(Message::OpenSettings),
+            Err(e) => {
+                app.error_msg = Some(format!("Restore failed: {}", e));
+                Task::none()
+            }
+        },
+        Message::CalendarDefaultsChanged(href, value) => {
+            let defaults = CalendarDefaults::from_smart_string(&value);
+            if defaults.is_empty() {
+                app.calendar_defaults.remove(&href);
+            } else {
+                app.calendar_defaults.insert(href.clone(), defaults);
+            }
+            app.calendar_defaults_inputs.insert(href, value);
+            save_config(app);
+            Task::none()
+        }
         Message::ObSortMonthsChanged(val) => {
             if val.is_empty() || val.chars().all(|c| c.is_numeric()) {
+                app.sort_cutoff_months = val.parse().ok();
                 app.ob_sort_months_input = val;
+                hot_apply(app);
+            }
+            Task::none()
+        }
+        Message::ObRetentionDaysChanged(val) => {
+            if val.is_empty() || val.chars().all(|c| c.is_numeric()) {
+                app.completed_retention_days = val.parse().ok();
+                app.ob_retention_input = val;
+                hot_apply(app);
+            }
+            Task::none()
+        }
+        Message::ObNotifyBeforeChanged(val) => {
+            if val.is_empty() || val.chars().all(|c| c.is_numeric()) {
+                app.notify_before_minutes = val.parse().ok();
+                app.ob_notify_input = val;
+                hot_apply(app);
             }
             Task::none()
         }
         _ => Task::none(),
     }
 }
+
+/// Preferences edited in the settings screen are saved and take effect right away.
+/// Connection fields only apply on "Save & Connect"; onboarding saves on submit.
+fn set_notify_before(app: &mut GuiApp, minutes: Option<u32>) {
+    app.notify_before_minutes = minutes;
+    app.ob_notify_input = minutes.map(|m| m.to_string()).unwrap_or_default();
+}
+
+fn hot_apply(app: &mut GuiApp) {
+    if matches!(app.state, AppState::Settings) {
+        save_config(app);
+        refresh_filtered_tasks(app);
+    }
+}
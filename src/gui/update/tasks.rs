@@ -1,14 +1,31 @@
 // File: src/gui/update/tasks.rs
 use crate::gui::async_ops::*;
-use crate::gui::message::Message;
+use crate::gui::message::{CardEdit, Message, ReviewChoice, TriageChoice};
 use crate::gui::state::{GuiApp, SidebarMode};
 use crate::gui::update::common::{apply_alias_retroactively, refresh_filtered_tasks, save_config};
+use crate::i18n::tr_args;
+use crate::model::draft::TaskDraft;
+use crate::model::inbox::{filing_targets, inbox_href, triage_queue};
+use crate::model::parser::parse_smart_date;
+use crate::model::recurrence::{Frequency, Recurrence};
+use crate::model::share::ShareFormat;
+use crate::model::template::{find_template, parse_template_command};
+use crate::model::validate::check_before_save;
 use crate::model::{Task as TodoTask, extract_inline_aliases};
+use crate::notifier::ReminderAction;
+use crate::store::BulkAction;
+use chrono::Utc;
 use iced::Task;
 use iced::widget::operation;
 use iced::widget::scrollable::RelativeOffset;
+use std::collections::HashMap;
 
 pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
+    if let Some(name) = read_only_target(app, &message) {
+        app.error_msg = Some(tr_args("error.read_only", &[("name", &name)]));
+        return Task::none();
+    }
+
     match message {
         Message::InputChanged(value) => {
             app.input_value = value;
@@ -36,6 +53,28 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             Task::none()
         }
         Message::SubmitTask => handle_submit(app),
+        Message::SubmitNote => {
+            let mut note = TodoTask::new_note(&app.input_value);
+            let target_href = app
+                .active_cal_href
+                .clone()
+                .or_else(|| app.calendars.first().map(|c| c.href.clone()));
+            let Some(href) = target_href.filter(|_| !note.summary.is_empty()) else {
+                return Task::none();
+            };
+            note.calendar_href = href;
+            app.store.add_task(note.clone());
+            app.selected_uid = Some(note.uid.clone());
+            app.input_value.clear();
+            refresh_filtered_tasks(app);
+            match &app.client {
+                Some(client) => Task::perform(
+                    async_create_wrapper(client.clone(), note),
+                    Message::SyncSaved,
+                ),
+                None => Task::none(),
+            }
+        }
 
         Message::EditTaskStart(index) => {
             if let Some(task) = app.tasks.get(index) {
@@ -60,7 +99,7 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                 let uid = view_task.uid.clone();
                 app.selected_uid = Some(uid.clone());
                 if let Some(updated) = app.store.toggle_task(&uid) {
-                    refresh_filtered_tasks(app);
+                    spawn_unblocked_notice(app.store.unblocked_by(&uid));
                     if let Some(client) = &app.client {
                         return Task::perform(
                             async_toggle_wrapper(client.clone(), updated),
@@ -75,7 +114,6 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             if let Some(view_task) = app.tasks.get(index)
                 && let Some(deleted) = app.store.delete_task(&view_task.uid)
             {
-                refresh_filtered_tasks(app);
                 if let Some(client) = &app.client {
                     return Task::perform(
                         async_delete_wrapper(client.clone(), deleted),
@@ -89,7 +127,6 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             if let Some(view_task) = app.tasks.get(index) {
                 app.selected_uid = Some(view_task.uid.clone());
                 if let Some(updated) = app.store.change_priority(&view_task.uid, delta) {
-                    refresh_filtered_tasks(app);
                     if let Some(client) = &app.client {
                         return Task::perform(
                             async_update_wrapper(client.clone(), updated),
@@ -104,7 +141,7 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             if let Some(view_task) = app.tasks.get(index) {
                 app.selected_uid = Some(view_task.uid.clone());
                 if let Some(updated) = app.store.set_status(&view_task.uid, new_status) {
-                    refresh_filtered_tasks(app);
+                    spawn_unblocked_notice(app.store.unblocked_by(&updated.uid));
                     if let Some(client) = &app.client {
                         return Task::perform(
                             async_update_wrapper(client.clone(), updated),
@@ -115,6 +152,103 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             }
             Task::none()
         }
+        // --- RECURRENCE Handlers ---
+        Message::SkipOccurrence(index) => {
+            if let Some(view_task) = app.tasks.get(index) {
+                app.selected_uid = Some(view_task.uid.clone());
+                if let Some(updated) = app.store.skip_occurrence(&view_task.uid) {
+                    if let Some(client) = &app.client {
+                        return Task::perform(
+                            async_update_wrapper(client.clone(), updated),
+                            Message::SyncSaved,
+                        );
+                    }
+                }
+            }
+            Task::none()
+        }
+        Message::ToggleSubtasksHidden(index) => {
+            if let Some(view_task) = app.tasks.get(index) {
+                app.selected_uid = Some(view_task.uid.clone());
+                if let Some(updated) = app.store.toggle_subtasks_hidden(&view_task.uid) {
+                    if let Some(client) = &app.client {
+                        return Task::perform(
+                            async_update_wrapper(client.clone(), updated),
+                            Message::SyncSaved,
+                        );
+                    }
+                }
+            }
+            Task::none()
+        }
+        Message::RescheduleInputChanged(value) => {
+            app.reschedule_input = value;
+            Task::none()
+        }
+        Message::RescheduleOccurrence(uid) => {
+            let Some(new_due) = parse_smart_date(app.reschedule_input.trim(), true) else {
+                app.error_msg = Some(format!("Invalid date: '{}'", app.reschedule_input));
+                return Task::none();
+            };
+            if let Some((updated, next)) = app.store.reschedule_occurrence(&uid, new_due) {
+                app.reschedule_input.clear();
+                app.selected_uid = Some(uid);
+                if let Some(client) = &app.client {
+                    return Task::batch(vec![
+                        Task::perform(
+                            async_update_wrapper(client.clone(), updated),
+                            Message::SyncSaved,
+                        ),
+                        Task::perform(
+                            async_create_wrapper(client.clone(), next),
+                            Message::SyncSaved,
+                        ),
+                    ]);
+                }
+            }
+            Task::none()
+        }
+        Message::RecurrenceFreqSelected(freq) => {
+            edit_input_recurrence(app, |rec| {
+                let r = rec.get_or_insert_with(Recurrence::default);
+                r.freq = freq;
+                if freq != Frequency::Weekly {
+                    r.by_day.clear();
+                }
+                if freq != Frequency::Monthly {
+                    r.by_month_day = None;
+                }
+            });
+            Task::none()
+        }
+        Message::RecurrenceIntervalStep(delta) => {
+            edit_input_recurrence(app, |rec| {
+                if let Some(r) = rec {
+                    r.interval = (r.interval as i32 + delta).max(1) as u32;
+                }
+            });
+            Task::none()
+        }
+        Message::RecurrenceDayToggled(day) => {
+            edit_input_recurrence(app, |rec| {
+                if let Some(r) = rec {
+                    r.toggle_day(day);
+                }
+            });
+            Task::none()
+        }
+        Message::RecurrenceMonthDaySelected(day) => {
+            edit_input_recurrence(app, |rec| {
+                if let Some(r) = rec {
+                    r.by_month_day = Some(day);
+                }
+            });
+            Task::none()
+        }
+        Message::ClearRecurrence => {
+            set_input_rrule(app, None);
+            Task::none()
+        }
         // --- YANK / LINKING Handlers ---
         Message::YankTask(uid) => {
             app.yanked_uid = Some(uid);
@@ -133,7 +267,6 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             {
                 app.selected_uid = Some(target_uid);
                 app.yanked_uid = None; // Clear yank state
-                refresh_filtered_tasks(app);
                 if let Some(client) = &app.client {
                     return Task::perform(
                         async_update_wrapper(client.clone(), updated),
@@ -146,7 +279,6 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
         Message::RemoveParent(child_uid) => {
             if let Some(updated) = app.store.set_parent(&child_uid, None) {
                 app.selected_uid = Some(child_uid);
-                refresh_filtered_tasks(app);
                 if let Some(client) = &app.client {
                     return Task::perform(
                         async_update_wrapper(client.clone(), updated),
@@ -159,7 +291,6 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
         Message::RemoveDependency(task_uid, dep_uid) => {
             if let Some(updated) = app.store.remove_dependency(&task_uid, &dep_uid) {
                 app.selected_uid = Some(task_uid);
-                refresh_filtered_tasks(app);
                 if let Some(client) = &app.client {
                     return Task::perform(
                         async_update_wrapper(client.clone(), updated),
@@ -178,7 +309,6 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             {
                 app.selected_uid = Some(target_uid);
                 app.yanked_uid = None; // Clear yank state
-                refresh_filtered_tasks(app);
                 if let Some(client) = &app.client {
                     return Task::perform(
                         async_update_wrapper(client.clone(), updated),
@@ -189,18 +319,322 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             Task::none()
         }
         Message::MoveTask(task_uid, target_href) => {
-            if let Some(updated) = app.store.move_task(&task_uid, target_href.clone()) {
-                app.selected_uid = Some(task_uid);
-                refresh_filtered_tasks(app);
-                if let Some(client) = &app.client {
-                    return Task::perform(
-                        async_move_wrapper(client.clone(), updated, target_href),
+            let moved = if app.move_subtree {
+                app.store.move_subtree(&task_uid, &target_href)
+            } else {
+                app.store
+                    .move_task(&task_uid, target_href.clone())
+                    .into_iter()
+                    .collect()
+            };
+            if moved.is_empty() {
+                return Task::none();
+            }
+            app.selected_uid = Some(task_uid);
+            if let Some(client) = &app.client {
+                app.pending_moves += moved.len();
+                if moved.len() > 1 {
+                    app.error_msg = Some(format!("Moving {} tasks...", moved.len()));
+                }
+                return Task::batch(moved.into_iter().map(|task| {
+                    Task::perform(
+                        async_move_wrapper(client.clone(), task, target_href.clone()),
                         Message::TaskMoved,
-                    );
+                    )
+                }));
+            }
+            Task::none()
+        }
+        Message::BulkTagInput(value) => {
+            app.bulk_tag_input = value;
+            Task::none()
+        }
+        Message::Bulk(action) => {
+            let action = match action {
+                BulkAction::AddTag(tag) => {
+                    let tag = tag.trim().trim_start_matches('#').to_string();
+                    if tag.is_empty() {
+                        return Task::none();
+                    }
+                    BulkAction::AddTag(tag)
+                }
+                other => other,
+            };
+            // The selected tasks still listed, in the list's order
+            let mut uids: Vec<String> = app
+                .tasks
+                .iter()
+                .filter(|t| app.selected_uids.contains(&t.uid))
+                .map(|t| t.uid.clone())
+                .collect();
+            if app.move_subtree && matches!(action, BulkAction::Move(_)) {
+                let mut seen = std::collections::HashSet::new();
+                uids = uids
+                    .iter()
+                    .flat_map(|uid| app.store.subtree(uid))
+                    .map(|t| t.uid)
+                    .filter(|uid| seen.insert(uid.clone()))
+                    .collect();
+            }
+            let changed = app.store.apply_bulk(&uids, &action);
+            app.selected_uids.clear();
+            app.bulk_tag_input.clear();
+            if changed.is_empty() {
+                return Task::none();
+            }
+            if action == BulkAction::Complete {
+                for task in &changed {
+                    spawn_unblocked_notice(app.store.unblocked_by(&task.uid));
+                }
+            }
+            match &app.client {
+                Some(client) => Task::perform(
+                    async_bulk_wrapper(client.clone(), changed, action),
+                    Message::BulkDone,
+                ),
+                None => Task::none(),
+            }
+        }
+        Message::ToggleDuplicateSubtree(value) => {
+            app.duplicate_with_subtree = value;
+            Task::none()
+        }
+        Message::DuplicateTask(task_uid, target_href) => {
+            let copies =
+                app.store
+                    .duplicate_task(&task_uid, app.duplicate_with_subtree, &target_href);
+            app.selected_uid = copies.first().map(|t| t.uid.clone());
+            if let Some(client) = &app.client {
+                return Task::batch(copies.into_iter().map(|copy| {
+                    Task::perform(
+                        async_create_wrapper(client.clone(), copy),
+                        Message::SyncSaved,
+                    )
+                }));
+            }
+            Task::none()
+        }
+        Message::ShareTask(task_uid, format) => {
+            let Some(task) = app.store.get_task(&task_uid) else {
+                return Task::none();
+            };
+            if format == ShareFormat::Ics {
+                app.error_msg = Some(match crate::storage::LocalStorage::export_ics(task) {
+                    Ok(path) => format!("Saved to {}", path.display()),
+                    Err(e) => format!("Export failed: {}", e),
+                });
+                return Task::none();
+            }
+            let content = task.share(format);
+            app.error_msg = Some("Copied to clipboard.".to_string());
+            iced::clipboard::write(content)
+        }
+        Message::CheckReminders => {
+            let due = app.reminders.due(
+                app.store.calendars.values().flatten(),
+                app.notify_before_minutes,
+                Utc::now(),
+            );
+            Task::batch(due.into_iter().map(|task| {
+                let uid = task.uid.clone();
+                Task::perform(async_notify_wrapper(task), move |action| {
+                    Message::ReminderAnswered(uid.clone(), action)
+                })
+            }))
+        }
+        Message::ReminderAnswered(uid, ReminderAction::Snooze) => {
+            app.reminders.snooze(&uid, Utc::now());
+            Task::none()
+        }
+        Message::ReminderAnswered(uid, ReminderAction::Done) => {
+            if app.store.is_task_done(&uid) != Some(false) {
+                return Task::none();
+            }
+            if let Some(updated) = app.store.toggle_task(&uid) {
+                spawn_unblocked_notice(app.store.unblocked_by(&uid));
+                if let Some(client) = &app.client {
+                    return Task::perform(async_toggle_wrapper(client.clone(), updated), |res| {
+                        Message::SyncToggleComplete(Box::new(res))
+                    });
+                }
+            }
+            Task::none()
+        }
+        Message::StartReview => {
+            let mut excluded = app.hidden_calendars.clone();
+            excluded.extend(app.disabled_calendars.iter().cloned());
+            excluded.extend(
+                app.calendars
+                    .iter()
+                    .filter(|c| c.is_read_only)
+                    .map(|c| c.href.clone()),
+            );
+            app.review_queue = app.store.review_queue(&excluded, app.review_after_weeks);
+            app.review_total = app.review_queue.len();
+            app.review_due_input.clear();
+            if app.review_queue.is_empty() {
+                app.error_msg = Some(format!(
+                    "Nothing to review: every open task changed in the last {} weeks.",
+                    app.review_after_weeks
+                ));
+            }
+            Task::none()
+        }
+        Message::StopReview => {
+            app.review_queue.clear();
+            Task::none()
+        }
+        Message::ReviewDueChanged(value) => {
+            app.review_due_input = value;
+            Task::none()
+        }
+        Message::ReviewAnswered(choice) => {
+            // Tasks deleted meanwhile (e.g. by a sync) are dropped
+            app.review_queue
+                .retain(|uid| app.store.get_task(uid).is_some());
+            let Some(uid) = app.review_queue.first().cloned() else {
+                return Task::none();
+            };
+            let due = if choice == ReviewChoice::Reschedule {
+                let input = app.review_due_input.trim();
+                match parse_smart_date(input, true) {
+                    None if !input.is_empty() => {
+                        app.error_msg = Some(format!("Invalid date: '{}'", input));
+                        return Task::none();
+                    }
+                    due => due,
                 }
+            } else {
+                None
+            };
+            app.review_queue.remove(0);
+            app.review_due_input.clear();
+
+            let task = match choice {
+                ReviewChoice::Skip => return Task::none(),
+                ReviewChoice::Keep => app.store.mark_reviewed(&uid),
+                ReviewChoice::Reschedule => app.store.set_due(&uid, due),
+                ReviewChoice::Complete => app.store.toggle_task(&uid),
+                ReviewChoice::Delete => app.store.delete_task(&uid),
+            };
+            let Some(task) = task else {
+                return Task::none();
+            };
+            let Some(client) = &app.client else {
+                return Task::none();
+            };
+            match choice {
+                ReviewChoice::Complete => {
+                    Task::perform(async_toggle_wrapper(client.clone(), task), |res| {
+                        Message::SyncToggleComplete(Box::new(res))
+                    })
+                }
+                ReviewChoice::Delete => Task::perform(
+                    async_delete_wrapper(client.clone(), task),
+                    Message::DeleteComplete,
+                ),
+                _ => Task::perform(
+                    async_update_wrapper(client.clone(), task),
+                    Message::SyncSaved,
+                ),
             }
+        }
+        Message::StartCardEdit(uid) => {
+            if let Some(task) = app.store.get_task(&uid) {
+                app.card_description =
+                    iced::widget::text_editor::Content::with_text(&task.description);
+                app.card_edit = Some((uid.clone(), TaskDraft::from_task(task)));
+                app.card_tag_input.clear();
+                app.card_dependency_input.clear();
+                app.expanded_tasks.insert(uid.clone());
+                app.selected_uid = Some(uid);
+            }
+            Task::none()
+        }
+        Message::CardEdited(edit) => {
+            edit_card(app, edit);
+            Task::none()
+        }
+        Message::SaveCardEdit => save_card(app),
+        Message::CancelCardEdit => {
+            app.card_edit = None;
+            Task::none()
+        }
+        Message::StartTriage => {
+            let Some(inbox) = inbox_href(app.inbox_calendar.as_deref(), &app.calendars) else {
+                app.error_msg = Some("No inbox: set inbox_calendar in the config.".to_string());
+                return Task::none();
+            };
+            app.triage_queue = app
+                .store
+                .calendars
+                .get(&inbox)
+                .map(|tasks| triage_queue(tasks))
+                .unwrap_or_default();
+            app.triage_total = app.triage_queue.len();
+            app.triage_filed = 0;
+            app.triage_target = None;
+            if app.triage_queue.is_empty() {
+                app.error_msg = Some("The inbox is empty.".to_string());
+            }
+            next_triage_task(app);
+            Task::none()
+        }
+        Message::StopTriage => {
+            app.error_msg = Some(format!(
+                "Triage stopped, {} of {} task(s) filed.",
+                app.triage_filed, app.triage_total
+            ));
+            app.triage_queue.clear();
             Task::none()
         }
+        Message::TriageInputChanged(value) => {
+            app.triage_input = value;
+            Task::none()
+        }
+        Message::TriageCalendarChanged(name) => {
+            app.triage_target = Some(name);
+            Task::none()
+        }
+        Message::TriageAnswered(choice) => {
+            if !next_triage_task(app) {
+                return Task::none();
+            }
+            let uid = app.triage_queue[0].clone();
+            let task = match choice {
+                TriageChoice::Skip => None,
+                TriageChoice::Complete => app.store.toggle_task(&uid),
+                TriageChoice::Delete => app.store.delete_task(&uid),
+                TriageChoice::File => {
+                    let Some(target) = app
+                        .calendars
+                        .iter()
+                        .find(|c| app.triage_target.as_ref() == Some(&c.name))
+                        .map(|c| c.href.clone())
+                    else {
+                        app.error_msg = Some("Pick the calendar to file it in.".to_string());
+                        return Task::none();
+                    };
+                    return file_triaged_task(app, &uid, target);
+                }
+            };
+            app.triage_queue.remove(0);
+            next_triage_task(app);
+            refresh_filtered_tasks(app);
+            let (Some(task), Some(client)) = (task, &app.client) else {
+                return Task::none();
+            };
+            if choice == TriageChoice::Complete {
+                Task::perform(async_toggle_wrapper(client.clone(), task), |res| {
+                    Message::SyncToggleComplete(Box::new(res))
+                })
+            } else {
+                Task::perform(
+                    async_delete_wrapper(client.clone(), task),
+                    Message::DeleteComplete,
+                )
+            }
+        }
         Message::MigrateLocalTo(target_href) => {
             if let Some(local_tasks) = app.store.calendars.get(crate::storage::LOCAL_CALENDAR_HREF)
             {
@@ -222,6 +656,266 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
     }
 }
 
+/// Returns the name of the read-only calendar a message would write to, if any.
+fn read_only_target(app: &GuiApp, message: &Message) -> Option<String> {
+    let by_uid = |uid: &String| app.store.index.get(uid).cloned();
+    let hrefs: Vec<String> = match message {
+        Message::ToggleTask(idx, _)
+        | Message::DeleteTask(idx)
+        | Message::EditTaskStart(idx)
+        | Message::ChangePriority(idx, _)
+        | Message::SetTaskStatus(idx, _)
+        | Message::SkipOccurrence(idx)
+        | Message::ToggleSubtasksHidden(idx) => app
+            .tasks
+            .get(*idx)
+            .map(|t| t.calendar_href.clone())
+            .into_iter()
+            .collect(),
+        Message::StartCreateChild(uid)
+        | Message::MakeChild(uid)
+        | Message::RemoveParent(uid)
+        | Message::AddDependency(uid)
+        | Message::RemoveDependency(uid, _)
+        | Message::RescheduleOccurrence(uid)
+        | Message::StartCardEdit(uid)
+        | Message::ReminderAnswered(uid, ReminderAction::Done) => by_uid(uid).into_iter().collect(),
+        Message::MoveTask(uid, target) => by_uid(uid)
+            .into_iter()
+            .chain(std::iter::once(target.clone()))
+            .collect(),
+        Message::Bulk(action) => {
+            let target = match action {
+                BulkAction::Move(target) => Some(target.clone()),
+                _ => None,
+            };
+            app.selected_uids
+                .iter()
+                .filter_map(by_uid)
+                .chain(target)
+                .collect()
+        }
+        Message::MigrateLocalTo(target) | Message::DuplicateTask(_, target) => {
+            vec![target.clone()]
+        }
+        Message::SaveCardEdit => match &app.card_edit {
+            Some((uid, _)) => by_uid(uid).into_iter().collect(),
+            None => vec![],
+        },
+        Message::SubmitTask => match &app.editing_uid {
+            Some(uid) => by_uid(uid).into_iter().collect(),
+            None => app.active_cal_href.clone().into_iter().collect(),
+        },
+        Message::SubmitNote => app.active_cal_href.clone().into_iter().collect(),
+        _ => vec![],
+    };
+
+    hrefs.iter().find_map(|href| {
+        app.calendars
+            .iter()
+            .find(|c| c.href == *href && c.is_read_only)
+            .map(|c| c.name.clone())
+    })
+}
+
+/// Applies a builder edit to the recurrence typed in the smart input.
+/// Rules the builder cannot model are replaced once the user picks a frequency.
+fn edit_input_recurrence(app: &mut GuiApp, edit: impl FnOnce(&mut Option<Recurrence>)) {
+    let current = parse_input_draft(app).rrule;
+    let before = current.as_deref().and_then(Recurrence::from_rrule);
+    let mut rec = before.clone();
+    edit(&mut rec);
+    if rec != before {
+        set_input_rrule(app, rec.map(|r| r.to_rrule()));
+    }
+}
+
+/// Rewrites the recurrence part of the smart input, leaving the other tokens in place.
+fn set_input_rrule(app: &mut GuiApp, rrule: Option<String>) {
+    let mut draft = parse_input_draft(app);
+    if rrule.is_none() {
+        draft.recur_from_completion = false;
+    }
+    draft.rrule = rrule;
+    app.input_value = draft.to_smart_string();
+}
+
+fn parse_input_draft(app: &GuiApp) -> TodoTask {
+    // No aliases here: expanding them would write the implied tags back into the field
+    TodoTask::new(&app.input_value, &HashMap::new())
+}
+
+/// Applies a change made in the card editor to its draft.
+fn edit_card(app: &mut GuiApp, edit: CardEdit) {
+    let Some((_, draft)) = &mut app.card_edit else {
+        return;
+    };
+    match edit {
+        CardEdit::Summary(value) => draft.summary = value,
+        CardEdit::Description(action) => app.card_description.perform(action),
+        CardEdit::Due(value) => draft.due = value,
+        CardEdit::Start(value) => draft.start = value,
+        CardEdit::PriorityStep(delta) => draft.priority = draft.priority.stepped(delta > 0),
+        CardEdit::Duration(value) => draft.duration = value,
+        CardEdit::TagInput(value) => app.card_tag_input = value,
+        CardEdit::AddTags => {
+            draft.add_tags(&app.card_tag_input, &app.tag_aliases);
+            app.card_tag_input.clear();
+        }
+        CardEdit::AddTag(tag) => {
+            draft.add_tags(&tag, &app.tag_aliases);
+            app.card_tag_input.clear();
+        }
+        CardEdit::RemoveTag(tag) => draft.tags.retain(|t| *t != tag),
+        CardEdit::Frequency(freq) => {
+            // Rules the builder cannot model are replaced once a frequency is picked
+            let current = draft.rrule.as_deref().and_then(Recurrence::from_rrule);
+            let rec = match current {
+                Some(rec) if rec.freq == freq => rec,
+                other => Recurrence {
+                    freq,
+                    interval: other.map_or(1, |r| r.interval),
+                    ..Recurrence::default()
+                },
+            };
+            draft.rrule = Some(rec.to_rrule());
+        }
+        CardEdit::IntervalStep(delta) => edit_draft_recurrence(draft, |rec| {
+            rec.interval = (rec.interval as i32 + delta).max(1) as u32
+        }),
+        CardEdit::DayToggled(day) => edit_draft_recurrence(draft, |rec| rec.toggle_day(day)),
+        CardEdit::MonthDaySelected(day) => {
+            edit_draft_recurrence(draft, |rec| rec.by_month_day = Some(day))
+        }
+        CardEdit::ClearRecurrence => draft.rrule = None,
+        CardEdit::DependencyInput(value) => app.card_dependency_input = value,
+        CardEdit::AddDependency(uid) => {
+            if !draft.dependencies.contains(&uid) {
+                draft.dependencies.push(uid);
+            }
+            app.card_dependency_input.clear();
+        }
+        CardEdit::RemoveDependency(uid) => draft.dependencies.retain(|d| *d != uid),
+    }
+}
+
+/// Edits the recurrence of the card editor, when the builder can model it.
+fn edit_draft_recurrence(draft: &mut TaskDraft, edit: impl FnOnce(&mut Recurrence)) {
+    if let Some(mut rec) = draft.rrule.as_deref().and_then(Recurrence::from_rrule) {
+        edit(&mut rec);
+        draft.rrule = Some(rec.to_rrule());
+    }
+}
+
+/// Saves the card editor as one update. It stays open on what does not parse.
+fn save_card(app: &mut GuiApp) -> Task<Message> {
+    let Some((uid, draft)) = &app.card_edit else {
+        return Task::none();
+    };
+    let Some(task) = app.store.get_task(uid) else {
+        app.card_edit = None;
+        return Task::none();
+    };
+    let draft = TaskDraft {
+        description: app.card_description.text(),
+        ..draft.clone()
+    };
+    let (updated, notes) = match draft
+        .apply(task)
+        .and_then(|t| check_before_save(&t).map(|notes| (t, notes)))
+    {
+        Ok(saved) => saved,
+        Err(e) => {
+            app.error_msg = Some(e);
+            return Task::none();
+        }
+    };
+    app.error_msg = (!notes.is_empty()).then(|| notes.join("; "));
+    if let Some((task, _)) = app.store.get_task_mut(&updated.uid) {
+        *task = updated.clone();
+    }
+    app.card_edit = None;
+    refresh_filtered_tasks(app);
+    match &app.client {
+        Some(client) => Task::perform(
+            async_update_wrapper(client.clone(), updated),
+            Message::SyncSaved,
+        ),
+        None => Task::none(),
+    }
+}
+
+/// Drops the tasks gone from the inbox (filed, or deleted by a sync) from the head of the
+/// triage and fills the fields for the next one. Returns whether there is one.
+fn next_triage_task(app: &mut GuiApp) -> bool {
+    let inbox = inbox_href(app.inbox_calendar.as_deref(), &app.calendars);
+    app.triage_queue.retain(|uid| {
+        app.store
+            .get_task(uid)
+            .is_some_and(|t| inbox.as_ref() == Some(&t.calendar_href))
+    });
+    let Some(task) = app
+        .triage_queue
+        .first()
+        .and_then(|uid| app.store.get_task(uid))
+    else {
+        if app.triage_total > 0 {
+            app.error_msg = Some(format!(
+                "Triage done: {} of {} task(s) filed.",
+                app.triage_filed, app.triage_total
+            ));
+            app.triage_total = 0;
+        }
+        return false;
+    };
+    app.triage_input = task.to_smart_string();
+    true
+}
+
+/// Files the inbox task with the tags and dates typed in the triage, its subtasks along.
+fn file_triaged_task(app: &mut GuiApp, uid: &str, target: String) -> Task<Message> {
+    let Some(mut task) = app.store.get_task(uid).cloned() else {
+        return Task::none();
+    };
+    task.apply_smart_input(app.triage_input.trim(), &app.tag_aliases);
+    if let Err(e) = check_before_save(&task) {
+        app.error_msg = Some(e);
+        return Task::none();
+    }
+    if let Some((t, _)) = app.store.get_task_mut(uid) {
+        *t = task.clone();
+    }
+    let mut moved = if app.move_subtree {
+        app.store.move_subtree(uid, &target)
+    } else {
+        app.store
+            .move_task(uid, target.clone())
+            .into_iter()
+            .collect()
+    };
+    // The task goes with its changes, its subtasks as they are
+    moved.retain(|t| t.uid != uid);
+    app.triage_filed += 1;
+    app.triage_queue.remove(0);
+    next_triage_task(app);
+    refresh_filtered_tasks(app);
+
+    let Some(client) = &app.client else {
+        return Task::none();
+    };
+    app.pending_moves += moved.len() + 1;
+    let filed = Task::perform(
+        async_file_wrapper(client.clone(), task, target.clone()),
+        Message::TaskMoved,
+    );
+    Task::batch(std::iter::once(filed).chain(moved.into_iter().map(|child| {
+        Task::perform(
+            async_move_wrapper(client.clone(), child, target.clone()),
+            Message::TaskMoved,
+        )
+    })))
+}
+
 fn handle_submit(app: &mut GuiApp) -> Task<Message> {
     if app.input_value.is_empty() {
         return Task::none();
@@ -280,8 +974,17 @@ fn handle_submit(app: &mut GuiApp) -> Task<Message> {
 
     if let Some(edit_uid) = &app.editing_uid {
         if let Some((task, _)) = app.store.get_task_mut(edit_uid) {
-            task.apply_smart_input(&clean_input, &app.tag_aliases);
-            task.description = app.description_value.text();
+            let mut candidate = task.clone();
+            candidate.apply_smart_input(&clean_input, &app.tag_aliases);
+            candidate.description = app.description_value.text();
+            match check_before_save(&candidate) {
+                Ok(notes) => app.error_msg = (!notes.is_empty()).then(|| notes.join("; ")),
+                Err(e) => {
+                    app.error_msg = Some(e);
+                    return Task::batch(retroactive_sync_batch);
+                }
+            }
+            *task = candidate;
             let task_copy = task.clone();
 
             app.input_value.clear();
@@ -299,6 +1002,49 @@ fn handle_submit(app: &mut GuiApp) -> Task<Message> {
                 return Task::batch(retroactive_sync_batch);
             }
         }
+    } else if let Some((name, title)) = parse_template_command(&clean_input) {
+        let target_href = app
+            .active_cal_href
+            .clone()
+            .or_else(|| app.calendars.first().map(|c| c.href.clone()))
+            .unwrap_or_default();
+        if target_href.is_empty() {
+            return Task::batch(retroactive_sync_batch);
+        }
+        let Some(mut tasks) = find_template(&app.templates, name)
+            .map(|tpl| tpl.instantiate(title, &target_href, &app.tag_aliases))
+        else {
+            app.error_msg = Some(format!("No template named '{}'", name));
+            return Task::batch(retroactive_sync_batch);
+        };
+        if let Some(root) = tasks.first_mut() {
+            root.parent_uid = app.creating_child_of.take();
+        }
+        for task in &mut tasks {
+            if let Some(defaults) = app.calendar_defaults.get(&target_href) {
+                defaults.apply(task);
+            }
+            if let Err(e) = check_before_save(task) {
+                app.error_msg = Some(e);
+                return Task::batch(retroactive_sync_batch);
+            }
+        }
+
+        for task in &tasks {
+            app.store.add_task(task.clone());
+        }
+        app.selected_uid = tasks.first().map(|t| t.uid.clone());
+        app.input_value.clear();
+
+        if let Some(client) = &app.client {
+            for task in tasks {
+                retroactive_sync_batch.push(Task::perform(
+                    async_create_wrapper(client.clone(), task),
+                    Message::SyncSaved,
+                ));
+            }
+        }
+        return Task::batch(retroactive_sync_batch);
     } else if !clean_input.is_empty() {
         let mut new_task = TodoTask::new(&clean_input, &app.tag_aliases);
         if let Some(parent) = &app.creating_child_of {
@@ -306,14 +1052,35 @@ fn handle_submit(app: &mut GuiApp) -> Task<Message> {
             app.creating_child_of = None;
         }
 
-        let target_href = app
-            .active_cal_href
+        // Quick-added tasks wait in the inbox for the triage
+        let inbox = inbox_href(app.inbox_calendar.as_deref(), &app.calendars)
+            .filter(|_| new_task.parent_uid.is_none());
+        let target_href = inbox
             .clone()
+            .or_else(|| app.active_cal_href.clone())
             .or_else(|| app.calendars.first().map(|c| c.href.clone()))
             .unwrap_or_default();
 
         if !target_href.is_empty() {
             new_task.calendar_href = target_href.clone();
+            if let Some(defaults) = app.calendar_defaults.get(&target_href) {
+                defaults.apply(&mut new_task);
+            }
+            app.store.place_new_task(
+                &mut new_task,
+                app.new_task_placement,
+                app.selected_uid.as_deref(),
+            );
+            match check_before_save(&new_task) {
+                Ok(notes) => app.error_msg = (!notes.is_empty()).then(|| notes.join("; ")),
+                Err(e) => {
+                    app.error_msg = Some(e);
+                    return Task::batch(retroactive_sync_batch);
+                }
+            }
+            if app.error_msg.is_none() && inbox.is_some_and(|h| app.active_cal_href != Some(h)) {
+                app.error_msg = Some("Added to the inbox.".to_string());
+            }
 
             // Fix: Use add_task to maintain index
             app.store.add_task(new_task.clone());
@@ -1,9 +1,15 @@
 // File: src/gui/update/view.rs
+use crate::color_utils;
+use crate::config::FONT_SIZES;
 use crate::gui::async_ops::*;
 use crate::gui::message::Message;
 use crate::gui::state::{AppState, GuiApp, ResizeDirection, SidebarMode};
-use crate::gui::update::common::{refresh_filtered_tasks, save_config};
+use crate::gui::update::common::{refresh_filtered_tasks, save_config, save_session};
+use crate::model::due::DueDisplay;
+use crate::model::share::DeepLink;
+use crate::store::ListKey;
 use iced::widget::operation;
+use iced::widget::scrollable::RelativeOffset;
 use iced::{Task, window};
 
 pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
@@ -19,6 +25,10 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             app.error_msg = None;
             Task::none()
         }
+        Message::DismissFetchFailures => {
+            app.fetch_failures_dismissed = true;
+            Task::none()
+        }
         Message::ToggleAllCalendars(show_all) => {
             if show_all {
                 app.hidden_calendars.clear();
@@ -85,6 +95,26 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             refresh_filtered_tasks(app);
             Task::none()
         }
+        Message::QuickFilterToggled(filter) => {
+            if let Some(pos) = app.quick_filters.iter().position(|f| *f == filter) {
+                app.quick_filters.remove(pos);
+            } else {
+                app.quick_filters.push(filter);
+            }
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
+        Message::CycleNotStarted => {
+            app.not_started = app.not_started.next();
+            save_config(app);
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
+        Message::ToggleNextActions => {
+            app.next_actions = !app.next_actions;
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
         Message::ToggleHideCompleted(val) => {
             app.hide_completed = val;
             save_config(app);
@@ -97,6 +127,65 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             refresh_filtered_tasks(app);
             Task::none()
         }
+        Message::ToggleMoveSubtree(val) => {
+            app.move_subtree = val;
+            save_config(app);
+            Task::none()
+        }
+        Message::DensityChanged(density) => {
+            app.density = density;
+            save_config(app);
+            Task::none()
+        }
+        Message::FontSizeChanged(size) => {
+            if FONT_SIZES.contains(&size) {
+                app.font_size = size;
+                save_config(app);
+            }
+            Task::none()
+        }
+        Message::PaletteChanged(palette) => {
+            app.palette = palette;
+            color_utils::set_accessibility(app.palette, app.underline_priority);
+            save_config(app);
+            Task::none()
+        }
+        Message::ToggleUnderlinePriority(val) => {
+            app.underline_priority = val;
+            color_utils::set_accessibility(app.palette, app.underline_priority);
+            save_config(app);
+            Task::none()
+        }
+        Message::ToggleRelativeDue(val) => {
+            app.due_display = if val {
+                DueDisplay::Relative
+            } else {
+                DueDisplay::Absolute
+            };
+            save_config(app);
+            Task::none()
+        }
+        Message::StoreChanged(_) => {
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
+        Message::ToggleShowAllCompleted => {
+            app.show_all_completed = !app.show_all_completed;
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
+        Message::GroupByChanged(by) => {
+            app.group_by = by;
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
+        Message::ToggleGroup(key) => {
+            if !app.collapsed_groups.remove(&key) {
+                app.collapsed_groups.insert(key);
+            }
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
         Message::SelectCalendar(href) => {
             if app.sidebar_mode == SidebarMode::Categories {
                 app.sidebar_mode = SidebarMode::Calendars;
@@ -129,6 +218,16 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             }
             save_config(app);
             refresh_filtered_tasks(app);
+
+            if let Some(client) = &app.client {
+                let enabled = client.set_disabled_calendars(app.disabled_calendars.iter().cloned());
+                if enabled.contains(&href) {
+                    return Task::perform(
+                        async_fetch_wrapper(client.clone(), href),
+                        Message::TasksRefreshed,
+                    );
+                }
+            }
             Task::none()
         }
         Message::ToggleCalendarVisibility(href, is_visible) => {
@@ -167,12 +266,17 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
         Message::ToggleDetails(uid) => {
             if app.expanded_tasks.contains(&uid) {
                 app.expanded_tasks.remove(&uid);
+                app.full_descriptions.remove(&uid);
             } else {
                 app.expanded_tasks.insert(uid.clone());
             }
             app.selected_uid = Some(uid);
             Task::none()
         }
+        Message::ShowFullDescription(uid) => {
+            app.full_descriptions.insert(uid);
+            Task::none()
+        }
         Message::OpenHelp => {
             app.state = AppState::Help;
             Task::none()
@@ -195,13 +299,16 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                 Task::none()
             }
         }),
-        Message::CloseWindow => window::latest().then(|id| {
-            if let Some(id) = id {
-                window::close(id)
-            } else {
-                Task::none()
-            }
-        }),
+        Message::CloseWindow => {
+            save_session(app);
+            window::latest().then(|id| {
+                if let Some(id) = id {
+                    window::close(id)
+                } else {
+                    Task::none()
+                }
+            })
+        }
         Message::ResizeStart(direction) => {
             let dir = match direction {
                 ResizeDirection::North => window::Direction::North,
@@ -225,6 +332,13 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             app.current_window_size = size;
             Task::none()
         }
+        Message::ListScrolled(y) => {
+            // NaN when the whole list fits
+            if y.is_finite() {
+                app.list_scroll_y = y;
+            }
+            Task::none()
+        }
         Message::JumpToTag(tag) => {
             app.sidebar_mode = SidebarMode::Categories;
             app.selected_categories.clear();
@@ -257,6 +371,123 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
 
             Task::none()
         }
+        Message::ModifiersChanged(modifiers) => {
+            app.modifiers = modifiers;
+            Task::none()
+        }
+        Message::TaskClicked(index) => {
+            let Some(uid) = app.tasks.get(index).map(|t| t.uid.clone()) else {
+                return Task::none();
+            };
+            if app.modifiers.shift() {
+                // From the selected task, which stays the anchor of the next ranges
+                let anchor = app
+                    .selected_uid
+                    .as_ref()
+                    .and_then(|s| app.tasks.iter().position(|t| t.uid == *s))
+                    .unwrap_or(index);
+                let range = anchor.min(index)..=anchor.max(index);
+                app.selected_uids
+                    .extend(app.tasks[range].iter().map(|t| t.uid.clone()));
+                return Task::none();
+            }
+            if app.modifiers.command() {
+                // The task selected so far is part of the multi-selection it starts
+                if app.selected_uids.is_empty()
+                    && let Some(selected) = app.selected_uid.clone()
+                {
+                    app.selected_uids.insert(selected);
+                }
+                if !app.selected_uids.remove(&uid) {
+                    app.selected_uids.insert(uid.clone());
+                }
+            } else {
+                app.selected_uids.clear();
+            }
+            app.selected_uid = Some(uid);
+            Task::none()
+        }
+        Message::ListKeyPressed(key) => {
+            // Not while the review, the triage or a log covers the list
+            let covered = !app.review_queue.is_empty()
+                || !app.triage_queue.is_empty()
+                || app.pending_changes.is_some()
+                || app.wire_log;
+            if covered || app.tasks.is_empty() {
+                return Task::none();
+            }
+            let current = app
+                .selected_uid
+                .as_ref()
+                .and_then(|uid| app.tasks.iter().position(|t| t.uid == *uid));
+            let last = app.tasks.len() - 1;
+            let target = match (key, current) {
+                (ListKey::Toggle | ListKey::Expand | ListKey::Edit | ListKey::Delete, None) => {
+                    return Task::none();
+                }
+                // Nothing selected yet: moving starts at the top
+                (_, None) => 0,
+                (ListKey::Next, Some(i)) => (i + 1).min(last),
+                (ListKey::Previous, Some(i)) => i.saturating_sub(1),
+                (ListKey::PageDown, Some(i)) => (i + ListKey::PAGE).min(last),
+                (ListKey::PageUp, Some(i)) => i.saturating_sub(ListKey::PAGE),
+                (ListKey::Toggle, Some(i)) => return Task::done(Message::ToggleTask(i, true)),
+                (ListKey::Expand, Some(i)) => {
+                    return Task::done(Message::ToggleDetails(app.tasks[i].uid.clone()));
+                }
+                (ListKey::Edit, Some(i)) => return Task::done(Message::EditTaskStart(i)),
+                (ListKey::Delete, Some(i)) => return Task::done(Message::DeleteTask(i)),
+            };
+            app.selected_uid = Some(app.tasks[target].uid.clone());
+            app.selected_uids.clear();
+            operation::snap_to(
+                app.scrollable_id.clone(),
+                RelativeOffset {
+                    x: 0.0,
+                    y: target as f32 / last.max(1) as f32,
+                },
+            )
+        }
+        Message::ClearSelection => {
+            app.selected_uids.clear();
+            Task::none()
+        }
+        Message::OpenDeepLink(DeepLink::Add(text)) => {
+            app.editing_uid = None;
+            app.creating_child_of = None;
+            app.input_value = text;
+            Task::none()
+        }
+        Message::OpenDeepLink(DeepLink::OpenTask(uid)) => {
+            let Some(href) = app.store.index.get(&uid).cloned() else {
+                if app.loading {
+                    // Not in the replica yet: retried when the sync finishes
+                    app.pending_link = Some(DeepLink::OpenTask(uid));
+                } else {
+                    app.error_msg = Some("Linked task not found.".to_string());
+                }
+                return Task::none();
+            };
+            app.hidden_calendars.remove(&href);
+            app.active_cal_href = Some(href);
+            app.sidebar_mode = SidebarMode::Calendars;
+            app.selected_categories.clear();
+            app.search_value.clear();
+            app.show_all_completed = true;
+            app.expanded_tasks.insert(uid.clone());
+            app.selected_uid = Some(uid.clone());
+            refresh_filtered_tasks(app);
+
+            let len = app.tasks.len().max(1) as f32;
+            let idx = app.tasks.iter().position(|t| t.uid == uid).unwrap_or(0) as f32;
+            operation::snap_to(
+                app.scrollable_id.clone(),
+                RelativeOffset {
+                    x: 0.0,
+                    y: idx / len,
+                },
+            )
+        }
         _ => Task::none(),
     }
 }
@@ -1,6 +1,7 @@
 // File: src/gui/view/help.rs
 use crate::gui::message::Message;
-use iced::widget::{button, column, container, row, scrollable, text, Space};
+use crate::i18n::{tr, tr_args};
+use iced::widget::{Space, button, column, container, row, scrollable, text};
 use iced::{Color, Element, Length, Theme};
 
 // --- STYLE CONSTANTS ---
@@ -11,72 +12,145 @@ const COL_CARD_BG: Color = Color::from_rgb(0.15, 0.15, 0.17); // Slightly lighte
 
 pub fn view_help() -> Element<'static, Message> {
     let title = row![
-        crate::gui::icon::icon(crate::gui::icon::HELP_RHOMBUS).size(28).style(|_: &Theme| text::Style { color: Some(COL_ACCENT) }),
-        text("Syntax guide").size(28).style(|_: &Theme| text::Style { color: Some(Color::WHITE) })
+        crate::gui::icon::icon(crate::gui::icon::HELP_RHOMBUS)
+            .size(28)
+            .style(|_: &Theme| text::Style {
+                color: Some(COL_ACCENT)
+            }),
+        text(tr("gui.syntax_guide"))
+            .size(28)
+            .style(|_: &Theme| text::Style {
+                color: Some(Color::WHITE)
+            })
     ]
     .spacing(15)
     .align_y(iced::Alignment::Center);
 
     let content = column![
         title,
-        
         // 1. FUNDAMENTALS
         help_card(
-            "Organization", 
+            tr("guide.organization"),
             crate::gui::icon::TAG,
             vec![
-                entry("!1", "Priority High (1) to Low (9)", "!1, !5, !9"),
-                entry("#tag", "Add category. Use ':' for sub-tags.", "#work, #dev:backend"),
-                entry("#a=#b,#c", "Define/update alias inline.", "#groceries=#home,#shopping"),
-                entry("~30m", "Estimated Duration (m/h/d/w).", "~30m, ~1.5h, ~2d"),
+                entry("!1", tr("guide.priority"), "!1, !5, !9"),
+                entry("#tag", tr("guide.tag"), "#work, #dev:backend"),
+                entry("#a=#b,#c", tr("guide.alias"), "#groceries=#home,#shopping"),
+                entry("~30m", tr("guide.duration"), "~30m, ~1.5h, ~2d"),
+                entry("tpl:name", tr("guide.template"), "tpl:release v1.2.0"),
             ]
         ),
-
         // 2. TIMELINE
         help_card(
-            "Timeline & Scheduling", 
+            tr("guide.timeline"),
             crate::gui::icon::CALENDAR,
             vec![
-                entry("@date", "Due Date. Deadline for the task.", "@tomorrow, @2025-12-31"),
-                entry("^date", "Start Date. Hides/sorts lower until date.", "^next week, ^2025-01-01"),
-                entry("Offsets", "Add time from today.", "1d (1 day), 2w (2 weeks), 3mo (3 months), 4y (4 years)"),
-                entry("Keywords", "Relative dates supported.", "today, tomorrow, next week, next year"),
-
-
+                entry("@date", tr("guide.due"), "@tomorrow, @2025-12-31"),
+                entry("^date", tr("guide.start"), "^next week, ^2025-01-01"),
+                entry(
+                    tr("guide.offsets_label"),
+                    tr("guide.offsets"),
+                    tr("guide.offsets_example")
+                ),
+                entry(
+                    tr("guide.keywords_label"),
+                    tr("guide.keywords"),
+                    "today, tomorrow, next week, next year"
+                ),
+                entry(
+                    "rem:",
+                    tr("guide.reminder"),
+                    "rem:2d, rem:^15m, rem:2025-03-01T09:00"
+                ),
             ]
         ),
-
         // 3. RECURRENCE
         help_card(
-            "Recurrence", 
+            tr("guide.recurrence"),
             crate::gui::icon::REPEAT,
             vec![
-                entry("@daily", "Quick presets.", "@daily, @weekly, @monthly, @yearly"),
-                entry("@every X", "Custom intervals.", "@every 3 days, @every 2 weeks"),
-                entry("Note", "Recurrence calculates next date based on Start Date if present, else Due Date.", ""),
+                entry(
+                    "@daily",
+                    tr("guide.presets"),
+                    "@daily, @weekly, @monthly, @yearly"
+                ),
+                entry(
+                    "@every X",
+                    tr("guide.every"),
+                    "@every 3 days, @every 2 weeks"
+                ),
+                entry(
+                    "@fromdone",
+                    tr("guide.fromdone"),
+                    "@every 2 weeks @fromdone"
+                ),
+                entry(
+                    "rec:RRULE",
+                    tr("guide.rrule"),
+                    "rec:FREQ=WEEKLY;BYDAY=MO,WE"
+                ),
+                entry(tr("guide.note_label"), tr("guide.recurrence_note"), ""),
             ]
         ),
-
         // 4. POWER SEARCH
         help_card(
-            "Search & Filtering", 
-            crate::gui::icon::SHIELD, 
+            tr("guide.search"),
+            crate::gui::icon::SHIELD,
             vec![
-                entry("text", "Matches summary or description.", "buy cat food"),
-                entry("#tag", "Filter by specific tag.", "#gardening"),
-                entry("is:status", "Filter by state.", "is:done, is:ongoing, is:active"),
-                entry("Operators", "Compare values (<, >, <=, >=).", "~<20m (less than 20 minutes), <!4 (urgent tasks)"),
-                entry("  Dates", "Filter by timeframe.", "@<today (Overdue), ^>tomorrow"),
-                entry("  Priority", "Filter by priority range.", "!<3 (High prio), !>=5"),
-                entry("  Duration", "Filter by effort.", "~<15m (Quick tasks)"),
+                entry("text", tr("guide.text"), "buy cat food"),
+                entry("#tag", tr("guide.tag_filter"), "#gardening"),
+                entry(
+                    "is:status",
+                    tr("guide.status_filter"),
+                    "is:done, is:ongoing, is:active"
+                ),
+                entry(
+                    tr("guide.operators_label"),
+                    tr("guide.operators"),
+                    tr("guide.operators_example")
+                ),
+                entry(
+                    tr("guide.dates_label"),
+                    tr("guide.dates"),
+                    tr("guide.dates_example")
+                ),
+                entry(
+                    tr("guide.priority_label"),
+                    tr("guide.priority_filter"),
+                    tr("guide.priority_example")
+                ),
+                entry(
+                    tr("guide.duration_label"),
+                    tr("guide.duration_filter"),
+                    tr("guide.duration_example")
+                ),
+                entry(
+                    tr("guide.words_label"),
+                    tr("guide.words"),
+                    "tag:work due<2024-07-01 prio>=5"
+                ),
+                entry("cal:name", tr("guide.calendar_filter"), "cal:home"),
+            ]
+        ),
+        // 5. KEYBOARD (the keys of the TUI, outside of the text fields)
+        help_card(
+            tr("guide.keys"),
+            crate::gui::icon::CHECK_SQUARE,
+            vec![
+                entry(tr("guide.key_down_up"), tr("guide.key_move"), ""),
+                entry("PgDn / PgUp", tr("guide.key_page"), ""),
+                entry(tr("guide.key_space_label"), tr("guide.key_space"), ""),
+                entry(tr("guide.key_enter_label"), tr("guide.key_enter"), ""),
+                entry("e", tr("guide.key_e"), ""),
+                entry(tr("guide.key_delete_label"), tr("guide.key_delete"), ""),
+                entry(tr("guide.key_select_label"), tr("guide.key_select"), ""),
             ]
         ),
-
         // FOOTER
         container(
             column![
                 button(
-                    text("Close help")
+                    text(tr("guide.close"))
                         .size(16)
                         .width(Length::Fill)
                         .align_x(iced::alignment::Horizontal::Center)
@@ -85,10 +159,14 @@ pub fn view_help() -> Element<'static, Message> {
                 .width(Length::Fixed(200.0))
                 .style(iced::widget::button::primary)
                 .on_press(Message::CloseHelp),
-                
-                text(format!("Cfait v{} \u{2022} GPL3 \u{2022} Trougnouf (Benoit Brummer)", env!("CARGO_PKG_VERSION")))
-                     .size(12)
-                     .style(|_: &Theme| text::Style { color: Some(COL_MUTED) })
+                text(format!(
+                    "Cfait v{} \u{2022} GPL3 \u{2022} Trougnouf (Benoit Brummer)",
+                    env!("CARGO_PKG_VERSION")
+                ))
+                .size(12)
+                .style(|_: &Theme| text::Style {
+                    color: Some(COL_MUTED)
+                })
             ]
             .spacing(15)
             .align_x(iced::Alignment::Center)
@@ -104,7 +182,7 @@ pub fn view_help() -> Element<'static, Message> {
     scrollable(
         container(content)
             .width(Length::Fill)
-            .center_x(Length::Fill)
+            .center_x(Length::Fill),
     )
     .height(Length::Fill)
     .into()
@@ -119,33 +197,51 @@ struct HelpEntry {
 }
 
 fn entry(syntax: &'static str, desc: &'static str, example: &'static str) -> HelpEntry {
-    HelpEntry { syntax, desc, example }
+    HelpEntry {
+        syntax,
+        desc,
+        example,
+    }
 }
 
-fn help_card(title: &'static str, icon_char: char, items: Vec<HelpEntry>) -> Element<'static, Message> {
+fn help_card(
+    title: &'static str,
+    icon_char: char,
+    items: Vec<HelpEntry>,
+) -> Element<'static, Message> {
     let header = row![
-        crate::gui::icon::icon(icon_char).size(20).style(|_: &Theme| text::Style { color: Some(COL_ACCENT) }),
-        text(title).size(18).style(|_: &Theme| text::Style { color: Some(COL_ACCENT) })
+        crate::gui::icon::icon(icon_char)
+            .size(20)
+            .style(|_: &Theme| text::Style {
+                color: Some(COL_ACCENT)
+            }),
+        text(title).size(18).style(|_: &Theme| text::Style {
+            color: Some(COL_ACCENT)
+        })
     ]
     .spacing(10)
     .align_y(iced::Alignment::Center);
 
-    let mut rows = column![header, iced::widget::rule::horizontal(1).style(|_: &Theme| iced::widget::rule::Style { 
-        color: Color::from_rgb(0.3, 0.3, 0.3),
-        radius: 0.0.into(),
-        fill_mode: iced::widget::rule::FillMode::Full,
-        snap: true,
-    })].spacing(12);
+    let mut rows = column![
+        header,
+        iced::widget::rule::horizontal(1).style(|_: &Theme| iced::widget::rule::Style {
+            color: Color::from_rgb(0.3, 0.3, 0.3),
+            radius: 0.0.into(),
+            fill_mode: iced::widget::rule::FillMode::Full,
+            snap: true,
+        })
+    ]
+    .spacing(12);
 
     for item in items {
-        let syntax_pill = container(
-            text::<Theme, iced::Renderer>(item.syntax)
-                .size(14)
-                .style(|_: &Theme| text::Style { color: Some(COL_SYNTAX) })
-        )
+        let syntax_pill = container(text::<Theme, iced::Renderer>(item.syntax).size(14).style(
+            |_: &Theme| text::Style {
+                color: Some(COL_SYNTAX),
+            },
+        ))
         .padding([2, 6])
         .style(|_: &Theme| container::Style {
-            background: Some(Color::from_rgba(1.0, 0.85, 0.4, 0.1).into()), 
+            background: Some(Color::from_rgba(1.0, 0.85, 0.4, 0.1).into()),
             border: iced::Border {
                 radius: 4.0.into(),
                 ..Default::default()
@@ -156,20 +252,32 @@ fn help_card(title: &'static str, icon_char: char, items: Vec<HelpEntry>) -> Ele
         let content = column![
             row![
                 syntax_pill.width(Length::Fixed(110.0)),
-                text::<Theme, iced::Renderer>(item.desc).size(14).width(Length::Fill).style(|_: &Theme| text::Style { color: Some(Color::WHITE) }),
-            ].spacing(10).align_y(iced::Alignment::Center),
-            
+                text::<Theme, iced::Renderer>(item.desc)
+                    .size(14)
+                    .width(Length::Fill)
+                    .style(|_: &Theme| text::Style {
+                        color: Some(Color::WHITE)
+                    }),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
             if !item.example.is_empty() {
                 Element::new(row![
                     Space::new().width(Length::Fixed(110.0)),
-                    text::<Theme, iced::Renderer>(format!("e.g.: {}", item.example))
-                        .size(12)
-                        .style(|_: &Theme| text::Style { color: Some(COL_MUTED) })
+                    text::<Theme, iced::Renderer>(tr_args(
+                        "guide.example",
+                        &[("example", &item.example)]
+                    ))
+                    .size(12)
+                    .style(|_: &Theme| text::Style {
+                        color: Some(COL_MUTED)
+                    })
                 ])
             } else {
                 Element::new(Space::new().height(0))
             }
-        ].spacing(2);
+        ]
+        .spacing(2);
 
         rows = rows.push(content);
     }
@@ -187,4 +295,4 @@ fn help_card(title: &'static str, icon_char: char, items: Vec<HelpEntry>) -> Ele
         })
         .width(Length::Fill)
         .into()
-}
\ No newline at end of file
+}
@@ -5,14 +5,23 @@ pub mod settings;
 pub mod sidebar;
 pub mod task_row;
 
+use crate::config::Density;
 use crate::gui::icon;
-use crate::gui::message::Message;
+use crate::gui::message::{Message, ReviewChoice, TriageChoice};
 use crate::gui::state::{AppState, GuiApp, ResizeDirection, SidebarMode};
 use crate::gui::view::help::view_help;
 use crate::gui::view::settings::view_settings;
 use crate::gui::view::sidebar::{view_sidebar_calendars, view_sidebar_categories};
-use crate::gui::view::task_row::view_task_row;
+use crate::gui::view::task_row::{view_group_header, view_task_row};
+use crate::i18n::{tr, tr_args};
+use crate::model::Task as TodoTask;
+use crate::model::alias_preview;
+use crate::model::due;
+use crate::model::inbox::filing_targets;
+use crate::model::item::DESCRIPTION_PREVIEW_LEN;
+use crate::model::recurrence::{Frequency, Recurrence, WEEKDAYS, describe_rrule};
 use crate::storage::LOCAL_CALENDAR_HREF;
+use crate::store::{BulkAction, GroupBy, ListRow, NotStarted, QuickFilter, UNCATEGORIZED_ID};
 
 use iced::widget::scrollable::{Direction, Scrollbar};
 use iced::widget::{
@@ -345,6 +354,8 @@ fn view_sidebar(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
 fn view_main_content(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
     let title_text = if app.loading {
         "Loading...".to_string()
+    } else if app.next_actions {
+        "Next Actions".to_string()
     } else if app.active_cal_href.is_none() {
         if app.selected_categories.is_empty() {
             "All Tasks".to_string()
@@ -392,18 +403,29 @@ fn view_main_content(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
         .spacing(10)
         .align_y(iced::Alignment::Center);
 
-    if app.unsynced_changes {
-        left_section = left_section.push(
-            container(text("Unsynced").size(10).color(Color::WHITE))
-                .style(|_| container::Style {
-                    background: Some(Color::from_rgb(0.8, 0.5, 0.0).into()),
-                    border: iced::Border {
-                        radius: 4.0.into(),
-                        ..Default::default()
-                    },
+    if !app.sync_status.is_synced() {
+        let badge = container(text(app.sync_status.label()).size(10).color(Color::WHITE))
+            .style(|_| container::Style {
+                background: Some(Color::from_rgb(0.8, 0.5, 0.0).into()),
+                border: iced::Border {
+                    radius: 4.0.into(),
                     ..Default::default()
-                })
-                .padding(3),
+                },
+                ..Default::default()
+            })
+            .padding(3);
+        let badge_btn = iced::widget::button(badge)
+            .style(iced::widget::button::text)
+            .padding(0)
+            .on_press(Message::OpenPendingChanges);
+        left_section = left_section.push(
+            tooltip(
+                badge_btn,
+                text("Preview the changes to push").size(12),
+                tooltip::Position::Bottom,
+            )
+            .style(tooltip_style)
+            .delay(Duration::from_millis(700)),
         );
     }
 
@@ -416,13 +438,84 @@ fn view_main_content(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
     left_section = left_section.push(
         tooltip(
             refresh_btn,
-            text("Force sync").size(12),
+            text(format!("Force sync ({})", app.sync_status.label())).size(12),
+            tooltip::Position::Bottom,
+        )
+        .style(tooltip_style)
+        .delay(Duration::from_millis(700)),
+    );
+
+    let review_btn = iced::widget::button(icon::icon(icon::EYE).size(16))
+        .style(iced::widget::button::text)
+        .padding(4)
+        .on_press(Message::StartReview);
+    left_section = left_section.push(
+        tooltip(
+            review_btn,
+            text(format!(
+                "Review tasks untouched for {} weeks",
+                app.review_after_weeks
+            ))
+            .size(12),
             tooltip::Position::Bottom,
         )
         .style(tooltip_style)
         .delay(Duration::from_millis(700)),
     );
 
+    if app.inbox_calendar.is_some() {
+        let triage_btn = iced::widget::button(icon::icon(icon::INBOX).size(16))
+            .style(iced::widget::button::text)
+            .padding(4)
+            .on_press(Message::StartTriage);
+        left_section = left_section.push(
+            tooltip(
+                triage_btn,
+                text("File the tasks of the inbox").size(12),
+                tooltip::Position::Bottom,
+            )
+            .style(tooltip_style)
+            .delay(Duration::from_millis(700)),
+        );
+    }
+
+    if app.wire_log {
+        let log_btn = iced::widget::button(icon::icon(icon::WIRE_LOG).size(16))
+            .style(iced::widget::button::text)
+            .padding(4)
+            .on_press(Message::OpenWireLog);
+        left_section = left_section.push(
+            tooltip(
+                log_btn,
+                text("Last requests to the server").size(12),
+                tooltip::Position::Bottom,
+            )
+            .style(tooltip_style)
+            .delay(Duration::from_millis(700)),
+        );
+    }
+
+    if let Some(days) = app.completed_retention_days {
+        let tip = if app.show_all_completed {
+            tr("gui.showing_all_completed").to_string()
+        } else {
+            tr_args("gui.completed_last_days", &[("days", &days)])
+        };
+        let retention_btn = iced::widget::button(icon::icon(icon::CHECK_SQUARE).size(16))
+            .style(if app.show_all_completed {
+                iced::widget::button::primary
+            } else {
+                iced::widget::button::text
+            })
+            .padding(4)
+            .on_press(Message::ToggleShowAllCompleted);
+        left_section = left_section.push(
+            tooltip(retention_btn, text(tip).size(12), tooltip::Position::Bottom)
+                .style(tooltip_style)
+                .delay(Duration::from_millis(700)),
+        );
+    }
+
     let subtitle_text = text(subtitle)
         .size(14)
         .color(Color::from_rgb(0.6, 0.6, 0.6));
@@ -432,7 +525,7 @@ fn view_main_content(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
         .center_x(Length::Fill)
         .center_y(Length::Shrink);
 
-    let search_input = iced::widget::text_input("Search...", &app.search_value)
+    let search_input = iced::widget::text_input(tr("gui.search"), &app.search_value)
         .on_input(Message::SearchChanged)
         .padding(5)
         .size(14)
@@ -450,7 +543,15 @@ fn view_main_content(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
     ]
     .spacing(0);
 
-    let right_section = row![search_input, window_controls]
+    let group_picker = iced::widget::pick_list(
+        &GroupBy::ALL[..],
+        Some(app.group_by),
+        Message::GroupByChanged,
+    )
+    .text_size(14)
+    .padding(5);
+
+    let right_section = row![group_picker, search_input, window_controls]
         .spacing(10)
         .align_y(iced::Alignment::Center);
 
@@ -550,18 +651,101 @@ fn view_main_content(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
         );
     }
 
-    let tasks_view = column(
+    // Calendars the last sync could not fetch: their mirror is shown meanwhile
+    if !app.fetch_failures.is_empty() && !app.fetch_failures_dismissed {
+        let mut lines = column![
+            text("Could not fetch (showing the last synced copy):")
+                .color(Color::WHITE)
+                .size(14)
+        ];
+        for line in &app.fetch_failures {
+            lines = lines.push(text(format!("  {}", line)).color(Color::WHITE).size(12));
+        }
+        let warning_content = row![
+            lines.width(Length::Fill),
+            iced::widget::button(icon::icon(icon::CROSS).size(14).color(Color::WHITE))
+                .style(iced::widget::button::text)
+                .padding(2)
+                .on_press(Message::DismissFetchFailures)
+        ]
+        .align_y(iced::Alignment::Start);
+        main_col = main_col.push(
+            container(warning_content)
+                .width(Length::Fill)
+                .padding(5)
+                .style(|_| container::Style {
+                    background: Some(Color::from_rgb(0.8, 0.5, 0.1).into()),
+                    ..Default::default()
+                }),
+        );
+    }
+
+    if let Some(pending) = view_pending(app) {
+        main_col = main_col.push(pending);
+        return container(main_col)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into();
+    }
+
+    if let Some(wire_log) = view_wire_log(app) {
+        main_col = main_col.push(wire_log);
+        return container(main_col)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into();
+    }
+
+    if let Some(review) = view_review(app) {
+        main_col = main_col.push(review);
+        return container(main_col)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into();
+    }
+
+    if let Some(triage) = view_triage(app) {
+        main_col = main_col.push(triage);
+        return container(main_col)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into();
+    }
+
+    main_col = main_col.push(view_quick_filters(app));
+    if let Some(bulk) = view_bulk_bar(app) {
+        main_col = main_col.push(bulk);
+    }
+
+    let rows: Vec<Element<'_, Message>> = if app.task_rows.is_empty() {
         app.tasks
             .iter()
             .enumerate()
             .map(|(real_index, task)| view_task_row(app, real_index, task))
-            .collect::<Vec<_>>(),
-    )
-    .spacing(1);
+            .collect()
+    } else {
+        app.task_rows
+            .iter()
+            .map(|row| match row {
+                ListRow::Group {
+                    key,
+                    label,
+                    count,
+                    collapsed,
+                } => view_group_header(app, key, label, *count, *collapsed),
+                ListRow::Task(i) => view_task_row(app, *i, &app.tasks[*i]),
+            })
+            .collect()
+    };
+    let tasks_view = column(rows).spacing(match app.density {
+        Density::Comfortable => 1.0,
+        Density::Compact => 0.0,
+    });
     main_col = main_col.push(
         scrollable(tasks_view)
             .height(Length::Fill)
             .id(app.scrollable_id.clone())
+            .on_scroll(|viewport| Message::ListScrolled(viewport.relative_offset().y))
             .direction(Direction::Vertical(
                 Scrollbar::new().width(10).scroller_width(10).margin(0),
             )),
@@ -577,6 +761,422 @@ fn view_main_content(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
         .into()
 }
 
+/// Actions on the multi-selection (ctrl/shift-click), above the list while there is one.
+fn view_bulk_bar(app: &GuiApp) -> Option<Element<'_, Message>> {
+    if app.selected_uids.is_empty() {
+        return None;
+    }
+    use iced::widget::button::{Status, Style};
+    let action = |label: &str, style: fn(&Theme, Status) -> Style, action: BulkAction| {
+        iced::widget::button(text(label.to_string()).size(12))
+            .style(style)
+            .padding([3, 8])
+            .on_press(Message::Bulk(action))
+    };
+    let tag = app.bulk_tag_input.trim().trim_start_matches('#');
+    let actions = row![
+        text(format!("{} selected", app.selected_uids.len())).size(12),
+        action(
+            "Complete",
+            iced::widget::button::primary,
+            BulkAction::Complete
+        ),
+        action("Delete", iced::widget::button::danger, BulkAction::Delete),
+        iced::widget::text_input("tag", &app.bulk_tag_input)
+            .on_input(Message::BulkTagInput)
+            .on_submit(Message::Bulk(BulkAction::AddTag(tag.to_string())))
+            .padding(3)
+            .size(12)
+            .width(Length::Fixed(120.0)),
+        iced::widget::button(text("Add tag").size(12))
+            .style(iced::widget::button::secondary)
+            .padding([3, 8])
+            .on_press_maybe(
+                (!tag.is_empty()).then(|| Message::Bulk(BulkAction::AddTag(tag.to_string())))
+            ),
+        Space::new().width(Length::Fill),
+        iced::widget::button(text("Clear selection").size(12))
+            .style(iced::widget::button::text)
+            .on_press(Message::ClearSelection),
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center);
+
+    let label = text("Move to:")
+        .size(12)
+        .color(Color::from_rgb(0.6, 0.6, 0.6));
+    let mut moves = row![label].spacing(5).align_y(iced::Alignment::Center);
+    for cal in app
+        .calendars
+        .iter()
+        .filter(|c| !c.is_read_only && !app.disabled_calendars.contains(&c.href))
+    {
+        moves = moves.push(action(
+            &cal.name,
+            iced::widget::button::secondary,
+            BulkAction::Move(cal.href.clone()),
+        ));
+    }
+
+    Some(
+        container(column![actions, moves].spacing(6))
+            .padding([6, 10])
+            .width(Length::Fill)
+            .into(),
+    )
+}
+
+/// Chips above the list: the quick filters, then one per tag (shared with the sidebar).
+fn view_quick_filters(app: &GuiApp) -> Element<'_, Message> {
+    let chip = |label: String, on: bool, message: Message| {
+        let style = if on {
+            iced::widget::button::primary
+        } else {
+            iced::widget::button::secondary
+        };
+        iced::widget::button(text(label).size(12))
+            .style(style)
+            .padding([2, 10])
+            .on_press(message)
+    };
+
+    let mut chips = row![].spacing(5).align_y(iced::Alignment::Center);
+    chips = chips.push(chip(
+        tr("gui.next_actions").to_string(),
+        app.next_actions,
+        Message::ToggleNextActions,
+    ));
+    for filter in QuickFilter::ALL {
+        let label = match filter {
+            QuickFilter::DueToday => tr("gui.quick_today"),
+            QuickFilter::Overdue => tr("gui.quick_overdue"),
+            QuickFilter::Urgent => tr("gui.quick_urgent"),
+        };
+        chips = chips.push(chip(
+            label.to_string(),
+            app.quick_filters.contains(&filter),
+            Message::QuickFilterToggled(filter),
+        ));
+    }
+    let not_started = match app.not_started {
+        NotStarted::Show => tr("gui.not_started_show"),
+        NotStarted::Dim => tr("gui.not_started_dim"),
+        NotStarted::Hide => tr("gui.not_started_hide"),
+    };
+    chips = chips.push(chip(
+        not_started.to_string(),
+        app.not_started != NotStarted::Show,
+        Message::CycleNotStarted,
+    ));
+    let tags = app.store.get_all_categories(
+        app.hide_completed,
+        app.hide_fully_completed_tags,
+        &app.selected_categories,
+        &app.hidden_calendars,
+    );
+    for (tag, _) in tags.into_iter().filter(|(t, _)| t != UNCATEGORIZED_ID) {
+        let on = app.selected_categories.contains(&tag);
+        chips = chips.push(chip(format!("#{}", tag), on, Message::CategoryToggled(tag)));
+    }
+
+    container(scrollable(chips).direction(Direction::Horizontal(
+        Scrollbar::new().width(4).scroller_width(4),
+    )))
+    .padding(iced::Padding {
+        left: 10.0,
+        right: 10.0,
+        bottom: 5.0,
+        ..Default::default()
+    })
+    .into()
+}
+
+/// The offline queue with a diff of each change; None when the preview is closed.
+fn view_pending(app: &GuiApp) -> Option<Element<'_, Message>> {
+    let changes = app.pending_changes.as_ref()?;
+    let grey = Color::from_rgb(0.6, 0.6, 0.6);
+
+    let mut list = column![].spacing(16);
+    if changes.is_empty() {
+        list = list.push(text("Nothing waiting to be pushed.").size(14).color(grey));
+    }
+    for change in changes {
+        let uid = change.uid.clone();
+        let header = row![
+            iced::widget::checkbox(!change.held)
+                .label("Push")
+                .on_toggle(move |push| Message::HoldPendingChange(uid.clone(), !push)),
+            text(change.kind).size(12).color(grey),
+            text(&change.summary).size(16),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+        let diff = change.lines.iter().fold(column![].spacing(2), |col, line| {
+            let color = if line.starts_with('+') {
+                Color::from_rgb(0.2, 0.6, 0.2)
+            } else {
+                Color::from_rgb(0.8, 0.2, 0.2)
+            };
+            col.push(text(line).size(13).font(iced::Font::MONOSPACE).color(color))
+        });
+        list = list.push(column![header, container(diff).padding([0, 30])].spacing(6));
+    }
+
+    let held = changes.iter().filter(|c| c.held).count();
+    let summary = if held > 0 {
+        format!("{} change(s) queued, {} held back", changes.len(), held)
+    } else {
+        format!("{} change(s) queued", changes.len())
+    };
+    let actions = row![
+        text(summary).size(12).color(grey),
+        Space::new().width(Length::Fill),
+        iced::widget::button(text("Sync now").size(14))
+            .style(iced::widget::button::primary)
+            .padding([5, 10])
+            .on_press(Message::Refresh),
+        iced::widget::button(text("Close").size(14))
+            .style(iced::widget::button::text)
+            .on_press(Message::ClosePendingChanges),
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center);
+
+    Some(
+        container(column![actions, scrollable(list).height(Length::Fill),].spacing(20))
+            .padding(20)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into(),
+    )
+}
+
+/// The last requests to the server with their answers; None when the panel is closed.
+fn view_wire_log(app: &GuiApp) -> Option<Element<'_, Message>> {
+    let entries = app.wire_entries.as_ref()?;
+    let grey = Color::from_rgb(0.6, 0.6, 0.6);
+
+    let mut list = column![].spacing(16);
+    if entries.is_empty() {
+        list = list.push(text("No request to the server yet.").size(14).color(grey));
+    }
+    for entry in entries {
+        let color = if entry.is_failure() {
+            Color::from_rgb(0.8, 0.2, 0.2)
+        } else {
+            Color::from_rgb(0.2, 0.6, 0.2)
+        };
+        let exchange = entry
+            .lines()
+            .into_iter()
+            .fold(column![].spacing(2), |col, line| {
+                col.push(text(line).size(12).font(iced::Font::MONOSPACE).color(grey))
+            });
+        list = list.push(
+            column![
+                text(entry.summary()).size(14).color(color),
+                container(exchange).padding([0, 30]),
+            ]
+            .spacing(6),
+        );
+    }
+
+    let actions = row![
+        text(format!("{} request(s), newest first", entries.len()))
+            .size(12)
+            .color(grey),
+        Space::new().width(Length::Fill),
+        iced::widget::button(text("Reload").size(14))
+            .style(iced::widget::button::secondary)
+            .padding([5, 10])
+            .on_press(Message::OpenWireLog),
+        iced::widget::button(text("Close").size(14))
+            .style(iced::widget::button::text)
+            .on_press(Message::CloseWireLog),
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center);
+
+    Some(
+        container(column![actions, scrollable(list).height(Length::Fill)].spacing(20))
+            .padding(20)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into(),
+    )
+}
+
+/// The task under review with its choices; None when no review is running.
+fn view_review(app: &GuiApp) -> Option<Element<'_, Message>> {
+    let task = app
+        .review_queue
+        .iter()
+        .find_map(|uid| app.store.get_task(uid))?;
+    let position = app.review_total - app.review_queue.len() + 1;
+    let grey = Color::from_rgb(0.6, 0.6, 0.6);
+
+    let mut details = column![
+        text(format!("Review {}/{}", position, app.review_total))
+            .size(12)
+            .color(grey),
+        text(&task.summary).size(22),
+    ]
+    .spacing(6);
+    if let Some(cal) = app.calendars.iter().find(|c| c.href == task.calendar_href) {
+        details = details.push(text(format!("Calendar: {}", cal.name)).size(14));
+    }
+    let changed = match task.last_modified.or(task.created) {
+        Some(d) => format!(
+            "Last changed: {} ({} weeks ago)",
+            d.with_timezone(&chrono::Local).format("%Y-%m-%d"),
+            (chrono::Utc::now() - d).num_weeks()
+        ),
+        None => "Last changed: unknown".to_string(),
+    };
+    details = details.push(text(changed).size(14).color(grey));
+    if let Some(due) = task.due {
+        details = details.push(text(format!("Due: {}", due::full(due))).size(14));
+    }
+    if !task.categories.is_empty() {
+        let tags: Vec<String> = task.categories.iter().map(|c| format!("#{}", c)).collect();
+        details = details.push(text(tags.join(" ")).size(14));
+    }
+    if !task.description.is_empty() {
+        let description = match task.description_preview(DESCRIPTION_PREVIEW_LEN) {
+            Some(preview) => format!("{}…", preview),
+            None => task.description.clone(),
+        };
+        details = details.push(text(description).size(14));
+    }
+
+    let choice = |label: &'static str, choice: ReviewChoice| {
+        let style = match choice {
+            ReviewChoice::Keep => iced::widget::button::primary,
+            ReviewChoice::Delete => iced::widget::button::danger,
+            _ => iced::widget::button::secondary,
+        };
+        iced::widget::button(text(label).size(14))
+            .style(style)
+            .padding([5, 10])
+            .on_press(Message::ReviewAnswered(choice))
+    };
+    let actions = row![
+        choice("Keep", ReviewChoice::Keep),
+        choice("Complete", ReviewChoice::Complete),
+        choice("Delete", ReviewChoice::Delete),
+        choice("Skip", ReviewChoice::Skip),
+        Space::new().width(Length::Fill),
+        iced::widget::button(text("Stop").size(14))
+            .style(iced::widget::button::text)
+            .on_press(Message::StopReview),
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center);
+    let reschedule = row![
+        iced::widget::text_input(
+            "New due date (tomorrow, 2w, 2025-06-01)",
+            &app.review_due_input
+        )
+        .on_input(Message::ReviewDueChanged)
+        .on_submit(Message::ReviewAnswered(ReviewChoice::Reschedule))
+        .padding(5)
+        .size(14)
+        .width(Length::Fixed(280.0)),
+        choice("Reschedule", ReviewChoice::Reschedule),
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center);
+
+    Some(
+        container(column![details, reschedule, actions].spacing(20))
+            .padding(20)
+            .width(Length::Fill)
+            .into(),
+    )
+}
+
+fn view_triage(app: &GuiApp) -> Option<Element<'_, Message>> {
+    let task = app
+        .triage_queue
+        .first()
+        .and_then(|uid| app.store.get_task(uid))?;
+    let position = app.triage_total - app.triage_queue.len() + 1;
+    let grey = Color::from_rgb(0.6, 0.6, 0.6);
+
+    let mut details = column![
+        text(format!("Inbox {}/{}", position, app.triage_total))
+            .size(12)
+            .color(grey),
+        text(&task.summary).size(22),
+    ]
+    .spacing(6);
+    if let Some(created) = task.created {
+        let created = created.with_timezone(&chrono::Local).format("%Y-%m-%d");
+        details = details.push(text(format!("Added: {}", created)).size(14).color(grey));
+    }
+    if !task.description.is_empty() {
+        let description = match task.description_preview(DESCRIPTION_PREVIEW_LEN) {
+            Some(preview) => format!("{}…", preview),
+            None => task.description.clone(),
+        };
+        details = details.push(text(description).size(14));
+    }
+
+    let inbox = task.calendar_href.as_str();
+    let names: Vec<String> = filing_targets(&app.calendars, inbox, &app.disabled_calendars)
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+    let choice = |label: &'static str, choice: TriageChoice| {
+        let style = match choice {
+            TriageChoice::File => iced::widget::button::primary,
+            TriageChoice::Delete => iced::widget::button::danger,
+            _ => iced::widget::button::secondary,
+        };
+        iced::widget::button(text(label).size(14))
+            .style(style)
+            .padding([5, 10])
+            .on_press(Message::TriageAnswered(choice))
+    };
+    let filing = row![
+        iced::widget::text_input("#tags @due !priority", &app.triage_input)
+            .on_input(Message::TriageInputChanged)
+            .on_submit(Message::TriageAnswered(TriageChoice::File))
+            .padding(5)
+            .size(14)
+            .width(Length::Fixed(280.0)),
+        iced::widget::pick_list(
+            names,
+            app.triage_target.clone(),
+            Message::TriageCalendarChanged
+        )
+        .placeholder("Calendar")
+        .text_size(14)
+        .padding(5),
+        choice("File", TriageChoice::File),
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center);
+    let actions = row![
+        choice("Complete", TriageChoice::Complete),
+        choice("Delete", TriageChoice::Delete),
+        choice("Skip", TriageChoice::Skip),
+        Space::new().width(Length::Fill),
+        iced::widget::button(text("Stop").size(14))
+            .style(iced::widget::button::text)
+            .on_press(Message::StopTriage),
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center);
+
+    Some(
+        container(column![details, filing, actions].spacing(20))
+            .padding(20)
+            .width(Length::Fill)
+            .into(),
+    )
+}
+
 fn view_input_area(app: &GuiApp) -> Element<'_, Message> {
     let input_placeholder = if app.editing_uid.is_some() {
         "Edit Title...".to_string()
@@ -605,6 +1205,13 @@ fn view_input_area(app: &GuiApp) -> Element<'_, Message> {
         .padding(10)
         .size(20);
 
+    // Live preview of the tags the aliases will add
+    let alias_hint = alias_preview(&app.input_value, &app.tag_aliases).map(|preview| {
+        text(format!("Aliases: {}", preview))
+            .size(12)
+            .color(Color::from_rgb(0.6, 0.6, 0.6))
+    });
+
     let inner_content: Element<'_, Message> = if app.editing_uid.is_some() {
         let input_desc = iced::widget::text_editor(&app.description_value)
             .placeholder("Notes...")
@@ -658,11 +1265,29 @@ fn view_input_area(app: &GuiApp) -> Element<'_, Message> {
                     .into();
             }
         }
-        column![top_bar, input_title, input_desc, move_element]
-            .spacing(10)
+        let mut editor = column![top_bar, input_title].spacing(10);
+        if let Some(hint) = alias_hint {
+            editor = editor.push(hint);
+        }
+        editor
+            .push(view_recurrence_builder(app))
+            .push(input_desc)
+            .push(move_element)
             .into()
     } else {
-        column![input_title].spacing(5).into()
+        let note_btn = iced::widget::button(text("Add as note").size(14))
+            .style(iced::widget::button::secondary)
+            .on_press_maybe((!app.input_value.trim().is_empty()).then_some(Message::SubmitNote));
+        let mut input_col = column![
+            row![input_title, note_btn]
+                .spacing(10)
+                .align_y(iced::Alignment::Center)
+        ]
+        .spacing(5);
+        if let Some(hint) = alias_hint {
+            input_col = input_col.push(hint);
+        }
+        input_col.into()
     };
 
     container(inner_content)
@@ -674,3 +1299,95 @@ fn view_input_area(app: &GuiApp) -> Element<'_, Message> {
         })
         .into()
 }
+
+/// Point-and-click editor for the recurrence part of the smart input being edited.
+fn view_recurrence_builder(app: &GuiApp) -> Element<'_, Message> {
+    let draft = TodoTask::new(&app.input_value, &std::collections::HashMap::new());
+    let rec = draft.rrule.as_deref().and_then(Recurrence::from_rrule);
+
+    let label = text("Repeat:")
+        .size(12)
+        .color(Color::from_rgb(0.6, 0.6, 0.6));
+    let freq_picker = iced::widget::pick_list(
+        Frequency::ALL,
+        rec.as_ref().map(|r| r.freq),
+        Message::RecurrenceFreqSelected,
+    )
+    .placeholder("Never")
+    .text_size(12)
+    .padding(4);
+
+    let mut builder = row![label, freq_picker]
+        .spacing(8)
+        .align_y(iced::Alignment::Center);
+
+    if let Some(r) = &rec {
+        let step_btn = |t: &'static str, delta: i32| {
+            iced::widget::button(text(t).size(12))
+                .style(iced::widget::button::secondary)
+                .padding([2, 8])
+                .on_press(Message::RecurrenceIntervalStep(delta))
+        };
+        builder = builder.push(
+            row![
+                text("every").size(12),
+                step_btn("-", -1),
+                text(r.interval.to_string()).size(12),
+                step_btn("+", 1),
+                text(r.freq.unit()).size(12),
+            ]
+            .spacing(4)
+            .align_y(iced::Alignment::Center),
+        );
+
+        match r.freq {
+            Frequency::Weekly => {
+                let mut days = row![].spacing(2);
+                for day in WEEKDAYS {
+                    let style = if r.by_day.contains(&day) {
+                        iced::widget::button::primary
+                    } else {
+                        iced::widget::button::secondary
+                    };
+                    days = days.push(
+                        iced::widget::button(text(day.to_string()).size(11))
+                            .style(style)
+                            .padding([2, 5])
+                            .on_press(Message::RecurrenceDayToggled(day)),
+                    );
+                }
+                builder = builder.push(days);
+            }
+            Frequency::Monthly => {
+                builder = builder.push(
+                    iced::widget::pick_list(
+                        (1..=31).collect::<Vec<u32>>(),
+                        r.by_month_day,
+                        Message::RecurrenceMonthDaySelected,
+                    )
+                    .placeholder("Day of due date")
+                    .text_size(12)
+                    .padding(4),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(rrule) = &draft.rrule {
+        builder = builder.push(Space::new().width(Length::Fill));
+        builder = builder.push(
+            text(describe_rrule(rrule))
+                .size(12)
+                .color(Color::from_rgb(0.6, 0.6, 0.9)),
+        );
+        builder = builder.push(
+            iced::widget::button(icon::icon(icon::CROSS).size(10))
+                .style(iced::widget::button::danger)
+                .padding(4)
+                .on_press(Message::ClearRecurrence),
+        );
+    }
+
+    builder.into()
+}
@@ -1,6 +1,12 @@
+use crate::backup;
+use crate::color_utils::Palette;
+use crate::config::{Density, FONT_SIZES};
 use crate::gui::icon;
 use crate::gui::message::Message;
 use crate::gui::state::{AppState, GuiApp};
+use crate::i18n::tr;
+use crate::model::due::DueDisplay;
+use crate::storage::LOCAL_CALENDAR_HREF;
 
 use iced::widget::{Space, button, checkbox, column, container, row, scrollable, text, text_input};
 use iced::{Color, Element, Length};
@@ -8,9 +14,9 @@ use iced::{Color, Element, Length};
 pub fn view_settings(app: &GuiApp) -> Element<'_, Message> {
     let is_settings = matches!(app.state, AppState::Settings);
     let title = text(if is_settings {
-        "Settings"
+        tr("gui.settings")
     } else {
-        "Welcome to Cfait"
+        tr("gui.welcome")
     })
     .size(40);
     let error = if let Some(e) = &app.error_msg {
@@ -22,7 +28,7 @@ pub fn view_settings(app: &GuiApp) -> Element<'_, Message> {
     let cal_names: Vec<String> = app.calendars.iter().map(|c| c.name.clone()).collect();
     let picker: Element<_> = if !cal_names.is_empty() && is_settings {
         column![
-            text("Default calendar:"),
+            text(tr("gui.default_calendar")),
             iced::widget::pick_list(
                 cal_names,
                 app.ob_default_cal.clone(),
@@ -42,20 +48,81 @@ pub fn view_settings(app: &GuiApp) -> Element<'_, Message> {
             column![
                 std::convert::Into::<Element<'_, Message>>::into(
                     checkbox(app.hide_completed)
-                        .label("Hide Completed Tasks (Everywhere)")
+                        .label(tr("gui.hide_completed"))
                         .on_toggle(Message::ToggleHideCompleted),
                 ),
                 // Conditional checkbox: only visible when 'Hide Completed Tasks (Everywhere)' is off
                 if !app.hide_completed {
                     std::convert::Into::<Element<'_, Message>>::into(
                         checkbox(app.hide_fully_completed_tags)
-                            .label("Hide Tags containing ONLY completed tasks")
+                            .label(tr("gui.hide_fully_completed_tags"))
                             .on_toggle(Message::ToggleHideFullyCompletedTags),
                     )
                 } else {
                     // Placeholder to keep spacing
                     std::convert::Into::<Element<'_, Message>>::into(Space::new().width(0))
                 },
+                std::convert::Into::<Element<'_, Message>>::into(
+                    checkbox(app.move_subtree)
+                        .label(tr("gui.move_subtree"))
+                        .on_toggle(Message::ToggleMoveSubtree),
+                ),
+                std::convert::Into::<Element<'_, Message>>::into(
+                    checkbox(app.density == Density::Compact)
+                        .label(tr("gui.compact"))
+                        .on_toggle(|compact| {
+                            Message::DensityChanged(if compact {
+                                Density::Compact
+                            } else {
+                                Density::Comfortable
+                            })
+                        }),
+                ),
+                std::convert::Into::<Element<'_, Message>>::into(
+                    row![
+                        text(tr("gui.font_size")),
+                        button(icon::icon(icon::MINUS).size(12))
+                            .style(button::secondary)
+                            .padding(5)
+                            .on_press_maybe(
+                                (app.font_size > *FONT_SIZES.start())
+                                    .then(|| Message::FontSizeChanged(app.font_size - 1))
+                            ),
+                        text(app.font_size.to_string()),
+                        button(icon::icon(icon::PLUS).size(12))
+                            .style(button::secondary)
+                            .padding(5)
+                            .on_press_maybe(
+                                (app.font_size < *FONT_SIZES.end())
+                                    .then(|| Message::FontSizeChanged(app.font_size + 1))
+                            ),
+                    ]
+                    .spacing(10)
+                    .align_y(iced::Alignment::Center),
+                ),
+                std::convert::Into::<Element<'_, Message>>::into(
+                    row![
+                        text(tr("gui.colors")),
+                        iced::widget::pick_list(
+                            Palette::ALL,
+                            Some(app.palette),
+                            Message::PaletteChanged
+                        )
+                        .padding(5),
+                    ]
+                    .spacing(10)
+                    .align_y(iced::Alignment::Center),
+                ),
+                std::convert::Into::<Element<'_, Message>>::into(
+                    checkbox(app.underline_priority)
+                        .label(tr("gui.underline_priority"))
+                        .on_toggle(Message::ToggleUnderlinePriority),
+                ),
+                std::convert::Into::<Element<'_, Message>>::into(
+                    checkbox(app.due_display == DueDisplay::Relative)
+                        .label(tr("gui.relative_due"))
+                        .on_toggle(Message::ToggleRelativeDue),
+                ),
             ]
             .spacing(10),
         ))
@@ -65,13 +132,29 @@ pub fn view_settings(app: &GuiApp) -> Element<'_, Message> {
 
     let sorting_ui: Element<_> = if is_settings {
         column![
-            text("Sorting priority cutoff (months):"),
-            text("(Tasks due within this range are shown first. Blank = all timed first)")
+            text(tr("gui.sort_cutoff")),
+            text(tr("gui.sort_cutoff_hint"))
                 .size(12)
                 .color(Color::from_rgb(0.6, 0.6, 0.6)),
             text_input("6", &app.ob_sort_months_input)
                 .on_input(Message::ObSortMonthsChanged)
                 .padding(10)
+                .width(Length::Fixed(100.0)),
+            text(tr("gui.retention")),
+            text(tr("gui.retention_hint"))
+                .size(12)
+                .color(Color::from_rgb(0.6, 0.6, 0.6)),
+            text_input("All", &app.ob_retention_input)
+                .on_input(Message::ObRetentionDaysChanged)
+                .padding(10)
+                .width(Length::Fixed(100.0)),
+            text(tr("gui.notify")),
+            text(tr("gui.notify_hint"))
+                .size(12)
+                .color(Color::from_rgb(0.6, 0.6, 0.6)),
+            text_input("Off", &app.ob_notify_input)
+                .on_input(Message::ObNotifyBeforeChanged)
+                .padding(10)
                 .width(Length::Fixed(100.0))
         ]
         .spacing(5)
@@ -82,15 +165,21 @@ pub fn view_settings(app: &GuiApp) -> Element<'_, Message> {
 
     // Alias Section
     let aliases_ui: Element<_> = if is_settings {
-        let mut list_col = column![text("Tag aliases").size(20)].spacing(10);
+        let mut list_col = column![text(tr("gui.tag_aliases")).size(20)].spacing(10);
 
         // Existing Aliases List
-        for (key, vals) in &app.tag_aliases {
+        let mut aliases: Vec<_> = app.tag_aliases.iter().collect();
+        aliases.sort_unstable_by_key(|(key, _)| key.to_lowercase());
+        for (key, vals) in aliases {
             let val_str = vals.join(", ");
             let row_item = row![
                 text(format!("#{}", key)).width(Length::FillPortion(1)),
                 text("->").width(Length::Fixed(20.0)),
                 text(val_str).width(Length::FillPortion(2)),
+                button(icon::icon(icon::EDIT).size(12))
+                    .style(button::secondary)
+                    .padding(5)
+                    .on_press(Message::EditAlias(key.clone())),
                 button(icon::icon(icon::CROSS).size(12))
                     .style(button::danger)
                     .padding(5)
@@ -101,7 +190,11 @@ pub fn view_settings(app: &GuiApp) -> Element<'_, Message> {
             list_col = list_col.push(row_item);
         }
 
-        // Add New Alias Form
+        // Add New Alias Form (saving an existing alias again replaces it)
+        let editing = app
+            .tag_aliases
+            .contains_key(app.alias_input_key.trim().trim_start_matches('#'));
+        let add_label = if editing { "Save" } else { "Add" };
         let input_row = row![
             text_input("Alias (#cfait)", &app.alias_input_key)
                 .on_input(Message::AliasKeyInput)
@@ -111,7 +204,7 @@ pub fn view_settings(app: &GuiApp) -> Element<'_, Message> {
                 .on_input(Message::AliasValueInput)
                 .padding(5)
                 .width(Length::FillPortion(2)),
-            button("Add").padding(5).on_press(Message::AddAlias)
+            button(add_label).padding(5).on_press(Message::AddAlias)
         ]
         .spacing(10);
 
@@ -132,19 +225,259 @@ pub fn view_settings(app: &GuiApp) -> Element<'_, Message> {
         Space::new().width(0).into()
     };
 
+    // Templates Section (nested subtasks can only be written in the config file)
+    let templates_ui: Element<_> = if is_settings {
+        let mut list_col = column![
+            text("Templates").size(20),
+            text("Type tpl:<name> <title> in the task input. {title} and {date} are replaced")
+                .size(12)
+                .color(Color::from_rgb(0.6, 0.6, 0.6)),
+        ]
+        .spacing(10);
+
+        for template in &app.templates {
+            let row_item = row![
+                text(format!("tpl:{}", template.name)).width(Length::FillPortion(1)),
+                text(format!(
+                    "{} ({} subtasks)",
+                    template.title,
+                    template.subtasks.len()
+                ))
+                .width(Length::FillPortion(2)),
+                button(icon::icon(icon::CROSS).size(12))
+                    .style(button::danger)
+                    .padding(5)
+                    .on_press(Message::RemoveTemplate(template.name.clone()))
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center);
+            list_col = list_col.push(row_item);
+        }
+
+        let input_col = column![
+            row![
+                text_input("Name (release)", &app.template_input_name)
+                    .on_input(Message::TemplateNameInput)
+                    .padding(5)
+                    .width(Length::FillPortion(1)),
+                text_input("Title (Release {title} !2)", &app.template_input_title)
+                    .on_input(Message::TemplateTitleInput)
+                    .padding(5)
+                    .width(Length::FillPortion(2)),
+            ]
+            .spacing(10),
+            row![
+                text_input(
+                    "Subtasks (Bump version; Write changelog)",
+                    &app.template_input_subtasks
+                )
+                .on_input(Message::TemplateSubtasksInput)
+                .padding(5)
+                .width(Length::Fill),
+                button("Add").padding(5).on_press(Message::AddTemplate)
+            ]
+            .spacing(10),
+        ]
+        .spacing(10);
+
+        container(column![list_col, iced::widget::rule::horizontal(1), input_col].spacing(15))
+            .padding(10)
+            .style(|_| container::Style {
+                border: iced::Border {
+                    radius: 4.0.into(),
+                    width: 1.0,
+                    color: Color::from_rgb(0.3, 0.3, 0.3),
+                },
+                ..Default::default()
+            })
+            .into()
+    } else {
+        Space::new().width(0).into()
+    };
+
+    let backups_ui: Element<_> = if is_settings {
+        let mut col = column![
+            row![
+                text("Backups").size(20).width(Length::Fill),
+                button("Back up now")
+                    .padding(5)
+                    .style(button::secondary)
+                    .on_press(Message::BackupNow),
+            ]
+            .align_y(iced::Alignment::Center),
+            text(format!(
+                "Local tasks, offline changes, settings and mirror. Taken daily and before \
+                 migrations; the last {} are kept. Restoring backs up the current state first",
+                backup::MAX_BACKUPS
+            ))
+            .size(12)
+            .color(Color::from_rgb(0.6, 0.6, 0.6)),
+        ]
+        .spacing(10);
+
+        for name in &app.backups {
+            col = col.push(
+                row![
+                    text(name).width(Length::Fill),
+                    button("Restore")
+                        .style(button::danger)
+                        .padding(5)
+                        .on_press(Message::RestoreBackup(name.clone())),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+
+        container(col)
+            .padding(10)
+            .style(|_| container::Style {
+                border: iced::Border {
+                    radius: 4.0.into(),
+                    width: 1.0,
+                    color: Color::from_rgb(0.3, 0.3, 0.3),
+                },
+                ..Default::default()
+            })
+            .into()
+    } else {
+        Space::new().width(0).into()
+    };
+
+    let report_ui: Element<_> = if is_settings {
+        container(
+            column![
+                text("Completed tasks report").size(20),
+                text("CSV with calendar, tags, completion date and estimate, e.g. for invoicing")
+                    .size(12)
+                    .color(Color::from_rgb(0.6, 0.6, 0.6)),
+                row![
+                    text_input("From (YYYY-MM-DD)", &app.report_from)
+                        .on_input(Message::ReportFromChanged)
+                        .padding(5),
+                    text_input("To (YYYY-MM-DD)", &app.report_to)
+                        .on_input(Message::ReportToChanged)
+                        .padding(5),
+                    button("Export CSV")
+                        .padding(5)
+                        .on_press(Message::ExportReport),
+                ]
+                .spacing(10),
+            ]
+            .spacing(10),
+        )
+        .padding(10)
+        .style(|_| container::Style {
+            border: iced::Border {
+                radius: 4.0.into(),
+                width: 1.0,
+                color: Color::from_rgb(0.3, 0.3, 0.3),
+            },
+            ..Default::default()
+        })
+        .into()
+    } else {
+        Space::new().width(0).into()
+    };
+
+    let print_ui: Element<_> = if is_settings {
+        container(
+            column![
+                text("Printable pages").size(20),
+                text("HTML saved to the export folder, to print or save as PDF from a browser")
+                    .size(12)
+                    .color(Color::from_rgb(0.6, 0.6, 0.6)),
+                row![
+                    button("List as shown")
+                        .padding(5)
+                        .on_press(Message::PrintList),
+                    button("This week's agenda")
+                        .padding(5)
+                        .on_press(Message::PrintAgenda),
+                ]
+                .spacing(10),
+            ]
+            .spacing(10),
+        )
+        .padding(10)
+        .style(|_| container::Style {
+            border: iced::Border {
+                radius: 4.0.into(),
+                width: 1.0,
+                color: Color::from_rgb(0.3, 0.3, 0.3),
+            },
+            ..Default::default()
+        })
+        .into()
+    } else {
+        Space::new().width(0).into()
+    };
+
     let cal_mgmt_ui: Element<_> = if is_settings && !app.calendars.is_empty() {
-        let mut col = column![text("Manage calendars").size(20)].spacing(10);
+        let mut col = column![
+            row![
+                text("Manage calendars").size(20).width(Length::Fill),
+                button("Download all again")
+                    .padding(5)
+                    .style(button::secondary)
+                    .on_press(Message::ForceRefresh(None)),
+            ]
+            .align_y(iced::Alignment::Center),
+            text("Unchecked calendars are not synced at all; the eye only hides them")
+                .size(12)
+                .color(Color::from_rgb(0.6, 0.6, 0.6)),
+            text("Defaults apply to new tasks unless the input sets them (e.g. !5 #work ~30m)")
+                .size(12)
+                .color(Color::from_rgb(0.6, 0.6, 0.6)),
+            text(
+                "Downloading again replaces a local copy gone wrong with the server's; local \
+                 tasks and unsynced changes are kept"
+            )
+            .size(12)
+            .color(Color::from_rgb(0.6, 0.6, 0.6)),
+        ]
+        .spacing(10);
 
         for cal in &app.calendars {
-            // Logic inverted: Checkbox checked = Enabled (!Disabled)
+            // Logic inverted: Checkbox checked = Synced (!Disabled)
             let is_enabled = !app.disabled_calendars.contains(&cal.href);
+            let is_visible = !app.hidden_calendars.contains(&cal.href);
+            let (eye, eye_color) = if is_visible {
+                (icon::EYE, Color::WHITE)
+            } else {
+                (icon::EYE_CLOSED, Color::from_rgb(0.4, 0.4, 0.4))
+            };
 
             let row_content = row![
                 checkbox(is_enabled)
                     .label(&cal.name)
                     // When toggled, we send !v because the msg is "ToggleDisabled"
                     .on_toggle(move |v| Message::ToggleCalendarDisabled(cal.href.clone(), !v))
-                    .width(Length::Fill)
+                    .width(Length::FillPortion(1)),
+                button(icon::icon(eye).size(14).color(eye_color))
+                    .style(button::text)
+                    .padding(5)
+                    .on_press(Message::ToggleCalendarVisibility(
+                        cal.href.clone(),
+                        !is_visible
+                    )),
+                text_input(
+                    "Defaults",
+                    app.calendar_defaults_inputs
+                        .get(&cal.href)
+                        .map(String::as_str)
+                        .unwrap_or("")
+                )
+                .on_input(move |v| Message::CalendarDefaultsChanged(cal.href.clone(), v))
+                .padding(5)
+                .width(Length::FillPortion(1)),
+                button(icon::icon(icon::REFRESH).size(14))
+                    .style(button::text)
+                    .padding(5)
+                    .on_press_maybe(
+                        (cal.href != LOCAL_CALENDAR_HREF)
+                            .then(|| Message::ForceRefresh(Some(cal.href.clone())))
+                    ),
             ];
 
             col = col.push(row_content.spacing(10).align_y(iced::Alignment::Center));
@@ -204,6 +537,22 @@ pub fn view_settings(app: &GuiApp) -> Element<'_, Message> {
         .size(16)
         .text_size(14);
 
+    let push_ui: Element<_> = if is_settings {
+        column![
+            text("Push notifications URL (optional):"),
+            text("(Syncs as soon as the server reports a change, e.g. wss://host/push/ws)")
+                .size(12)
+                .color(Color::from_rgb(0.6, 0.6, 0.6)),
+            text_input("wss://...", &app.ob_push_url)
+                .on_input(Message::ObPushUrlChanged)
+                .padding(10),
+        ]
+        .spacing(5)
+        .into()
+    } else {
+        Space::new().width(0).into()
+    };
+
     let form = column![
         text("CalDAV server URL:"),
         text_input("https://...", &app.ob_url)
@@ -219,11 +568,16 @@ pub fn view_settings(app: &GuiApp) -> Element<'_, Message> {
             .secure(true)
             .padding(10),
         insecure_check,
+        push_ui,
         picker,
         prefs,
         sorting_ui,
         aliases_ui,
+        templates_ui,
         cal_mgmt_ui,
+        report_ui,
+        print_ui,
+        backups_ui,
         buttons
     ]
     .spacing(15)
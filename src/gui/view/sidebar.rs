@@ -1,9 +1,11 @@
 // File: src/gui/view/sidebar.rs
 use super::tooltip_style;
+use crate::cache;
 use crate::color_utils;
 use crate::gui::icon;
 use crate::gui::message::Message;
 use crate::gui::state::GuiApp;
+use crate::storage::LOCAL_CALENDAR_HREF;
 use crate::store::UNCATEGORIZED_ID;
 use iced::never;
 use iced::widget::{Space, button, checkbox, column, container, row, text, toggler, tooltip};
@@ -40,6 +42,7 @@ pub fn view_sidebar_calendars(app: &GuiApp) -> Element<'_, Message> {
         .on_toggle(Message::ToggleAllCalendars)
         .style(toggler_style);
     let toggle_container = container(toggle_all).padding(5);
+    let now = chrono::Utc::now();
 
     let list = column(
         app.calendars
@@ -125,8 +128,69 @@ pub fn view_sidebar_calendars(app: &GuiApp) -> Element<'_, Message> {
                 .style(tooltip_style)
                 .delay(Duration::from_millis(700));
 
-                row![vis_tooltip, label, focus_tooltip]
-                    .spacing(0)
+                let mut cal_row = row![vis_tooltip, label].spacing(0);
+                if let Some(stats) = app.calendar_stats.get(&cal.href)
+                    && stats.open > 0
+                {
+                    cal_row = cal_row.push(
+                        text(stats.label())
+                            .size(12)
+                            .color(Color::from_rgb(0.6, 0.6, 0.6)),
+                    );
+                }
+                if cal.is_shared || cal.is_read_only {
+                    let (badge_icon, badge_text) = if cal.is_read_only {
+                        (icon::LOCK, "Read-only")
+                    } else {
+                        (icon::SHARED, "Shared with you")
+                    };
+                    let shared_tooltip = tooltip(
+                        icon::icon(badge_icon).size(12).style(|_| text::Style {
+                            color: Some(Color::from_rgb(0.6, 0.6, 0.6)),
+                        }),
+                        text(badge_text).size(12),
+                        tooltip::Position::Top,
+                    )
+                    .style(tooltip_style)
+                    .delay(Duration::from_millis(700));
+                    cal_row = cal_row.push(shared_tooltip);
+                }
+
+                if cal.href != LOCAL_CALENDAR_HREF {
+                    // Not refreshed by the last syncs: what is shown may be out of date
+                    let synced = app.sync_times.get(&cal.href).copied();
+                    if cache::is_stale(synced, now) {
+                        let stale_text = match synced {
+                            Some(at) => format!("Last synced {}", cache::format_ago(at, now)),
+                            None => "Never synced".to_string(),
+                        };
+                        let stale_tooltip = tooltip(
+                            icon::icon(icon::CLOCK).size(12).style(|_| text::Style {
+                                color: Some(Color::from_rgb(0.8, 0.6, 0.2)),
+                            }),
+                            text(stale_text).size(12),
+                            tooltip::Position::Top,
+                        )
+                        .style(tooltip_style)
+                        .delay(Duration::from_millis(700));
+                        cal_row = cal_row.push(stale_tooltip);
+                    }
+                    let sync_btn = button(icon::icon(icon::UNSYNCED).size(12))
+                        .style(button::text)
+                        .padding(5)
+                        .on_press(Message::ToggleCalendarDisabled(cal.href.clone(), true));
+                    let sync_tooltip = tooltip(
+                        sync_btn,
+                        text("Stop syncing").size(12),
+                        tooltip::Position::Top,
+                    )
+                    .style(tooltip_style)
+                    .delay(Duration::from_millis(700));
+                    cal_row = cal_row.push(sync_tooltip);
+                }
+
+                cal_row
+                    .push(focus_tooltip)
                     .align_y(iced::Alignment::Center)
                     .into()
             })
@@ -135,7 +199,51 @@ pub fn view_sidebar_calendars(app: &GuiApp) -> Element<'_, Message> {
     .spacing(2)
     .width(Length::Fill);
 
-    column![toggle_container, list].spacing(5).into()
+    // Calendars with sync turned off: kept out of the list above, one click to resume
+    let mut disabled_list = column![].spacing(2).width(Length::Fill);
+    let disabled: Vec<_> = app
+        .calendars
+        .iter()
+        .filter(|c| app.disabled_calendars.contains(&c.href))
+        .collect();
+    if !disabled.is_empty() {
+        disabled_list = disabled_list.push(
+            container(
+                text("Not synced")
+                    .size(12)
+                    .color(Color::from_rgb(0.5, 0.5, 0.5)),
+            )
+            .padding(5),
+        );
+    }
+    for cal in disabled {
+        let resume_btn = button(icon::icon(icon::UNSYNCED).size(12))
+            .style(button::text)
+            .padding(5)
+            .on_press(Message::ToggleCalendarDisabled(cal.href.clone(), false));
+        let resume_tooltip = tooltip(
+            resume_btn,
+            text("Resume syncing").size(12),
+            tooltip::Position::Top,
+        )
+        .style(tooltip_style)
+        .delay(Duration::from_millis(700));
+        disabled_list = disabled_list.push(
+            row![
+                text(&cal.name)
+                    .size(14)
+                    .color(Color::from_rgb(0.5, 0.5, 0.5))
+                    .width(Length::Fill),
+                resume_tooltip
+            ]
+            .padding([0, 10])
+            .align_y(iced::Alignment::Center),
+        );
+    }
+
+    column![toggle_container, list, disabled_list]
+        .spacing(5)
+        .into()
 }
 
 // ... DurationOpt (unchanged) ...
@@ -146,21 +254,6 @@ impl std::fmt::Display for DurationOpt {
         write!(f, "{}", self.1)
     }
 }
-fn format_mins(m: u32) -> String {
-    if m >= 525600 {
-        format!("{}y", m / 525600)
-    } else if m >= 43200 {
-        format!("{}mo", m / 43200)
-    } else if m >= 10080 {
-        format!("{}w", m / 10080)
-    } else if m >= 1440 {
-        format!("{}d", m / 1440)
-    } else if m >= 60 {
-        format!("{}h", m / 60)
-    } else {
-        format!("{}m", m)
-    }
-}
 
 pub fn view_sidebar_categories(app: &GuiApp) -> Element<'_, Message> {
     // ... [setup: No Change] ...
@@ -283,11 +376,11 @@ pub fn view_sidebar_categories(app: &GuiApp) -> Element<'_, Message> {
             }
         }
     }
-    let mut sorted_durs: Vec<u32> = dur_set.into_iter().collect();
+    let mut sorted_durs: Vec<_> = dur_set.into_iter().collect();
     sorted_durs.sort();
     let mut opts = vec![DurationOpt(None, "Any".to_string())];
     for d in sorted_durs {
-        opts.push(DurationOpt(Some(d), format_mins(d)));
+        opts.push(DurationOpt(Some(d.minutes()), d.to_string()));
     }
     let current_min = opts
         .iter()
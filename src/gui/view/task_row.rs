@@ -1,30 +1,103 @@
 // File: src/gui/view/task_row.rs
 
 use crate::color_utils;
+use crate::config::Density;
 use crate::gui::icon;
-use crate::gui::message::Message;
+use crate::gui::message::{CardEdit, Message};
 use crate::gui::state::GuiApp;
+use crate::i18n::{tr, tr_args};
 use crate::model::Task as TodoTask;
+use crate::model::draft::{TaskDraft, suggest_dependencies, suggest_tags};
+use crate::model::due;
+use crate::model::item::{BADGE_NAME_LEN, DESCRIPTION_PREVIEW_LEN};
+use crate::model::parser::parse_smart_date;
+use crate::model::priority;
+use crate::model::recurrence::{Frequency, Recurrence, WEEKDAYS, describe_rrule};
+use crate::model::share::ShareFormat;
+use crate::store::{self, NotStarted, Urgency};
 use std::collections::HashSet;
 use std::time::Duration;
 
 use super::tooltip_style;
-use iced::widget::{Space, button, column, container, row, scrollable, text, tooltip};
+use iced::widget::{
+    Space, button, checkbox, column, container, mouse_area, row, scrollable, text, text_input,
+    tooltip,
+};
 pub use iced::widget::{rich_text, span};
 use iced::{Border, Color, Element, Length, Theme};
 
+/// Clickable header of a group in a grouped task list.
+pub fn view_group_header<'a>(
+    app: &GuiApp,
+    key: &str,
+    label: &'a str,
+    count: usize,
+    collapsed: bool,
+) -> Element<'a, Message> {
+    let chevron = if collapsed {
+        icon::CHEVRON_RIGHT
+    } else {
+        icon::CHEVRON_DOWN
+    };
+    button(
+        row![
+            icon::icon(chevron).size(12),
+            text(label).size(app.font_size.saturating_sub(5)),
+            text(format!("({})", count))
+                .size(app.font_size.saturating_sub(7))
+                .color(Color::from_rgb(0.6, 0.6, 0.6)),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center),
+    )
+    .style(button::text)
+    .padding(match app.density {
+        Density::Comfortable => iced::Padding {
+            top: 8.0,
+            bottom: 4.0,
+            left: 6.0,
+            right: 6.0,
+        },
+        Density::Compact => iced::Padding {
+            top: 4.0,
+            bottom: 2.0,
+            left: 6.0,
+            right: 6.0,
+        },
+    })
+    .width(Length::Fill)
+    .on_press(Message::ToggleGroup(key.to_string()))
+    .into()
+}
+
 pub fn view_task_row<'a>(
     app: &'a GuiApp,
     index: usize,
     task: &'a TodoTask,
 ) -> Element<'a, Message> {
     let is_blocked = app.store.is_blocked(task);
-    let is_selected = app.selected_uid.as_ref() == Some(&task.uid);
+    let is_selected =
+        app.selected_uid.as_ref() == Some(&task.uid) || app.selected_uids.contains(&task.uid);
+    let (button_padding, row_padding, row_spacing) = match app.density {
+        Density::Comfortable => (4.0, 2.0, 10.0),
+        Density::Compact => (2.0, 0.0, 6.0),
+    };
+    let band_color = priority::band_color(task.priority)
+        .and_then(|hex| color_utils::parse_hex_to_floats(&hex))
+        .map(|(r, g, b)| Color::from_rgb(r, g, b))
+        .or_else(|| {
+            color_utils::priority_color(task.priority).map(|(r, g, b)| Color::from_rgb8(r, g, b))
+        });
+    let underline = color_utils::underline_priority() && task.priority.is_set() && !is_blocked;
     let color = if is_blocked {
         Color::from_rgb(0.5, 0.5, 0.5)
+    } else if color_utils::underline_priority() {
+        Color::WHITE
+    } else if let Some(c) = band_color {
+        c
     } else {
         // Priority Gradient: Red (Hot) -> Yellow (Normal) -> Purple/Slate (Cold)
-        match task.priority {
+        match task.priority.value() {
             // 1: Red
             1 => Color::from_rgb(1.0, 0.2, 0.2),
             // 2: Orange-Red
@@ -47,6 +120,13 @@ pub fn view_task_row<'a>(
             _ => Color::WHITE,
         }
     };
+    // Not started yet: faded when so configured (hidden ones are filtered out)
+    let dimmed = app.not_started == NotStarted::Dim && store::not_started(task, chrono::Utc::now());
+    let color = if dimmed {
+        color.scale_alpha(0.45)
+    } else {
+        color
+    };
     let show_indent = app.active_cal_href.is_some() && app.search_value.is_empty();
     let indent_size = if show_indent { task.depth * 12 } else { 0 };
     let indent = Space::new().width(Length::Fixed(indent_size as f32));
@@ -214,20 +294,8 @@ pub fn view_task_row<'a>(
                     .on_press(Message::JumpToTag(cat.clone())),
             );
         }
-        if let Some(mins) = task.estimated_duration {
-            let label = if mins >= 525600 {
-                format!("{}y", mins / 525600)
-            } else if mins >= 43200 {
-                format!("{}mo", mins / 43200)
-            } else if mins >= 10080 {
-                format!("{}w", mins / 10080)
-            } else if mins >= 1440 {
-                format!("{}d", mins / 1440)
-            } else if mins >= 60 {
-                format!("{}h", mins / 60)
-            } else {
-                format!("{}m", mins)
-            };
+        if let Some(d) = task.estimated_duration {
+            let label = d.to_string();
             tags_row = tags_row.push(
                 container(text(label).size(10).color(Color::WHITE))
                     .style(|_| container::Style {
@@ -244,55 +312,107 @@ pub fn view_task_row<'a>(
         if task.rrule.is_some() {
             tags_row = tags_row.push(container(icon::icon(icon::REPEAT).size(14)).padding(0));
         }
+        // Which calendar the task is in, when the list mixes them
+        if app.mixed_calendars
+            && let Some(cal) = app.calendars.iter().find(|c| c.href == task.calendar_href)
+        {
+            let (r, g, b) = cal
+                .color
+                .as_deref()
+                .and_then(color_utils::parse_hex_to_floats)
+                .unwrap_or((0.5, 0.5, 0.5));
+            let text_color = if color_utils::is_dark(r, g, b) {
+                Color::WHITE
+            } else {
+                Color::BLACK
+            };
+            let badge = container(
+                text(cal.short_name(BADGE_NAME_LEN, "…"))
+                    .size(10)
+                    .color(text_color),
+            )
+            .style(move |_| container::Style {
+                background: Some(Color::from_rgb(r, g, b).into()),
+                border: iced::Border {
+                    radius: 4.0.into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .padding(3);
+            tags_row = tags_row.push(
+                tooltip(badge, text(&cal.name).size(12), tooltip::Position::Top)
+                    .style(tooltip_style)
+                    .delay(Duration::from_millis(700)),
+            );
+        }
         tags_row.into()
     };
 
+    // Due dates have tiers of their own, whatever the priority: bold for today and overdue
+    let urgency = store::urgency(task, chrono::Utc::now(), app.due_soon_days);
+    let due_color = color_utils::urgency_color(urgency)
+        .filter(|_| !is_blocked)
+        .map_or(Color::from_rgb(0.5, 0.5, 0.5), |(r, g, b)| {
+            Color::from_rgb8(r, g, b)
+        });
+    let due_weight = if urgency >= Urgency::Today && !is_blocked {
+        iced::font::Weight::Bold
+    } else {
+        iced::font::Weight::Normal
+    };
     let date_text: Element<'a, Message> = match task.due {
-        Some(d) => container(
-            text(d.format("%Y-%m-%d").to_string())
-                .size(14)
-                .color(Color::from_rgb(0.5, 0.5, 0.5)),
+        // The full date on hover
+        Some(d) => tooltip(
+            container(
+                text(due::label(d, app.due_display))
+                    .size(app.font_size.saturating_sub(6))
+                    .color(due_color)
+                    .font(iced::Font {
+                        weight: due_weight,
+                        ..iced::Font::DEFAULT
+                    }),
+            )
+            .width(Length::Fixed(100.0)),
+            text(due::full(d)).size(12),
+            tooltip::Position::Top,
         )
-        .width(Length::Fixed(80.0))
+        .style(tooltip_style)
+        .delay(Duration::from_millis(700))
         .into(),
         None => Space::new().width(Length::Fixed(0.0)).into(),
     };
 
-    let has_desc = !task.description.is_empty();
-    let has_deps = !task.dependencies.is_empty();
+    let is_recurring = task.rrule.is_some();
     let is_expanded = app.expanded_tasks.contains(&task.uid);
 
     let mut actions = row![].spacing(3);
 
-    if has_desc || has_deps {
-        let info_btn = button(icon::icon(icon::INFO).size(12))
-            .style(if is_expanded {
-                button::primary
-            } else {
-                action_style
-            })
-            .padding(4)
-            .width(Length::Fixed(25.0))
-            .on_press(Message::ToggleDetails(task.uid.clone()));
-        // Apply tooltip_style
-        actions = actions.push(
-            tooltip(
-                info_btn,
-                text("Show details").size(12),
-                tooltip::Position::Top,
-            )
-            .style(tooltip_style)
-            .delay(Duration::from_millis(700)),
-        );
-    } else {
-        actions = actions.push(Space::new().width(Length::Fixed(25.0)));
-    }
+    // Always there: the expanded card is also where every field is edited
+    let info_btn = button(icon::icon(icon::INFO).size(12))
+        .style(if is_expanded {
+            button::primary
+        } else {
+            action_style
+        })
+        .padding(button_padding)
+        .width(Length::Fixed(25.0))
+        .on_press(Message::ToggleDetails(task.uid.clone()));
+    actions = actions.push(
+        tooltip(
+            info_btn,
+            text("Show details").size(12),
+            tooltip::Position::Top,
+        )
+        .style(tooltip_style)
+        .delay(Duration::from_millis(700)),
+    );
 
     if let Some(yanked) = &app.yanked_uid {
         if *yanked != task.uid {
             let block_btn = button(icon::icon(icon::BLOCKED).size(14))
                 .style(action_style)
-                .padding(4)
+                .padding(button_padding)
                 .on_press(Message::AddDependency(task.uid.clone()));
             actions = actions.push(
                 tooltip(
@@ -305,7 +425,7 @@ pub fn view_task_row<'a>(
             );
             let child_btn = button(icon::icon(icon::CHILD).size(14))
                 .style(action_style)
-                .padding(4)
+                .padding(button_padding)
                 .on_press(Message::MakeChild(task.uid.clone()));
             actions = actions.push(
                 tooltip(
@@ -319,7 +439,7 @@ pub fn view_task_row<'a>(
         } else {
             let unlink_btn = button(icon::icon(icon::UNLINK).size(14))
                 .style(button::primary)
-                .padding(4)
+                .padding(button_padding)
                 .on_press(Message::ClearYank);
             actions = actions.push(
                 tooltip(unlink_btn, text("Unlink").size(12), tooltip::Position::Top)
@@ -328,7 +448,7 @@ pub fn view_task_row<'a>(
             );
             let create_child_btn = button(icon::icon(icon::CREATE_CHILD).size(14))
                 .style(button::primary)
-                .padding(4)
+                .padding(button_padding)
                 .on_press(Message::StartCreateChild(task.uid.clone()));
             actions = actions.push(
                 tooltip(
@@ -344,7 +464,7 @@ pub fn view_task_row<'a>(
             if task.parent_uid.is_some() {
                 let lift_btn = button(icon::icon(icon::ELEVATOR_UP).size(14))
                     .style(action_style)
-                    .padding(4)
+                    .padding(button_padding)
                     .on_press(Message::RemoveParent(task.uid.clone()));
                 actions = actions.push(
                     tooltip(
@@ -360,7 +480,7 @@ pub fn view_task_row<'a>(
     } else {
         let link_btn = button(icon::icon(icon::LINK).size(14))
             .style(action_style)
-            .padding(4)
+            .padding(button_padding)
             .on_press(Message::YankTask(task.uid.clone()));
         actions = actions.push(
             tooltip(
@@ -373,9 +493,7 @@ pub fn view_task_row<'a>(
         );
     }
 
-    if task.status != crate::model::TaskStatus::Completed
-        && task.status != crate::model::TaskStatus::Cancelled
-    {
+    if !task.status.is_done() && !task.is_note() {
         let (action_icon, msg_status, tooltip_text) =
             if task.status == crate::model::TaskStatus::InProcess {
                 (
@@ -392,7 +510,7 @@ pub fn view_task_row<'a>(
             };
         let status_toggle_btn = button(icon::icon(action_icon).size(14))
             .style(action_style)
-            .padding(4)
+            .padding(button_padding)
             .on_press(Message::SetTaskStatus(index, msg_status));
         actions = actions.push(
             tooltip(
@@ -405,37 +523,83 @@ pub fn view_task_row<'a>(
         );
     }
 
-    let plus_btn = button(icon::icon(icon::PLUS).size(14))
-        .style(action_style)
-        .padding(4)
-        .on_press(Message::ChangePriority(index, 1));
-    actions = actions.push(
-        tooltip(
-            plus_btn,
-            text("Increase priority").size(12),
-            tooltip::Position::Top,
-        )
-        .style(tooltip_style)
-        .delay(Duration::from_millis(700)),
-    );
+    if is_recurring
+        && task.status != crate::model::TaskStatus::Completed
+        && task.status != crate::model::TaskStatus::Cancelled
+    {
+        let skip_btn = button(icon::icon(icon::SKIP_NEXT).size(14))
+            .style(action_style)
+            .padding(button_padding)
+            .on_press(Message::SkipOccurrence(index));
+        actions = actions.push(
+            tooltip(
+                skip_btn,
+                text("Skip this occurrence").size(12),
+                tooltip::Position::Top,
+            )
+            .style(tooltip_style)
+            .delay(Duration::from_millis(700)),
+        );
+    }
 
-    let minus_btn = button(icon::icon(icon::MINUS).size(14))
-        .style(action_style)
-        .padding(4)
-        .on_press(Message::ChangePriority(index, -1));
-    actions = actions.push(
-        tooltip(
-            minus_btn,
-            text("Decrease priority").size(12),
-            tooltip::Position::Top,
-        )
-        .style(tooltip_style)
-        .delay(Duration::from_millis(700)),
-    );
+    let has_visible_children = app
+        .tasks
+        .get(index + 1)
+        .is_some_and(|next| next.parent_uid.as_ref() == Some(&task.uid));
+    if show_indent && (task.hidden_subtasks > 0 || has_visible_children) {
+        let (fold_icon, fold_tip) = if task.hidden_subtasks > 0 {
+            (
+                icon::CHEVRON_RIGHT,
+                tr_args("gui.show_subtasks", &[("n", &task.hidden_subtasks)]),
+            )
+        } else {
+            (icon::CHEVRON_DOWN, tr("gui.hide_subtasks").to_string())
+        };
+        let fold_btn = button(icon::icon(fold_icon).size(14))
+            .style(action_style)
+            .padding(button_padding)
+            .on_press(Message::ToggleSubtasksHidden(index));
+        actions = actions.push(
+            tooltip(fold_btn, text(fold_tip).size(12), tooltip::Position::Top)
+                .style(tooltip_style)
+                .delay(Duration::from_millis(700)),
+        );
+    }
+
+    // Notes have no priority
+    if !task.is_note() {
+        let plus_btn = button(icon::icon(icon::PLUS).size(14))
+            .style(action_style)
+            .padding(button_padding)
+            .on_press(Message::ChangePriority(index, 1));
+        actions = actions.push(
+            tooltip(
+                plus_btn,
+                text("Increase priority").size(12),
+                tooltip::Position::Top,
+            )
+            .style(tooltip_style)
+            .delay(Duration::from_millis(700)),
+        );
+
+        let minus_btn = button(icon::icon(icon::MINUS).size(14))
+            .style(action_style)
+            .padding(button_padding)
+            .on_press(Message::ChangePriority(index, -1));
+        actions = actions.push(
+            tooltip(
+                minus_btn,
+                text("Decrease priority").size(12),
+                tooltip::Position::Top,
+            )
+            .style(tooltip_style)
+            .delay(Duration::from_millis(700)),
+        );
+    }
 
     let edit_btn = button(icon::icon(icon::EDIT).size(14))
         .style(action_style)
-        .padding(4)
+        .padding(button_padding)
         .on_press(Message::EditTaskStart(index));
     actions = actions.push(
         tooltip(edit_btn, text("Edit").size(12), tooltip::Position::Top)
@@ -445,7 +609,7 @@ pub fn view_task_row<'a>(
 
     let delete_btn = button(icon::icon(icon::TRASH).size(14))
         .style(danger_style)
-        .padding(4)
+        .padding(button_padding)
         .on_press(Message::DeleteTask(index));
     actions = actions.push(
         tooltip(delete_btn, text("Delete").size(12), tooltip::Position::Top)
@@ -453,12 +617,10 @@ pub fn view_task_row<'a>(
             .delay(Duration::from_millis(700)),
     );
 
-    if task.status != crate::model::TaskStatus::Completed
-        && task.status != crate::model::TaskStatus::Cancelled
-    {
+    if !task.status.is_done() && !task.is_note() {
         let cancel_btn = button(icon::icon(icon::CROSS).size(14))
             .style(danger_style)
-            .padding(4)
+            .padding(button_padding)
             .on_press(Message::SetTaskStatus(
                 index,
                 crate::model::TaskStatus::Cancelled,
@@ -471,6 +633,11 @@ pub fn view_task_row<'a>(
     }
 
     let (icon_char, bg_color, default_border_color) = match task.status {
+        _ if task.is_note() => (
+            icon::NOTE,
+            Color::from_rgb(0.4, 0.5, 0.7),
+            Color::from_rgb(0.4, 0.5, 0.7),
+        ),
         crate::model::TaskStatus::InProcess => (
             icon::PLAY_FA,
             Color::from_rgb(0.6, 0.8, 0.6),
@@ -547,6 +714,31 @@ pub fn view_task_row<'a>(
         }
     });
 
+    // Underlined instead of colored for colorblind users; bold for the most urgent band
+    let title = || -> Element<'a, Message> {
+        if underline {
+            let weight = if priority::is_urgent(task.priority) {
+                iced::font::Weight::Bold
+            } else {
+                iced::font::Weight::Normal
+            };
+            rich_text![span(&task.summary).underline(true).font(iced::Font {
+                weight,
+                ..iced::Font::DEFAULT
+            })]
+            .size(app.font_size)
+            .color(color)
+            .width(Length::Fill)
+            .on_link_click(iced::never)
+            .into()
+        } else {
+            text(&task.summary)
+                .size(app.font_size)
+                .color(color)
+                .width(Length::Fill)
+                .into()
+        }
+    };
     let title_chars = task.summary.chars().count();
     let est_tags_len = task.categories.len() * 4
         + if task.estimated_duration.is_some() {
@@ -564,10 +756,7 @@ pub fn view_task_row<'a>(
 
     let title_row = if place_inline {
         row![
-            text(&task.summary)
-                .size(20)
-                .color(color)
-                .width(Length::Fill),
+            title(),
             if has_metadata {
                 build_tags()
             } else {
@@ -577,14 +766,7 @@ pub fn view_task_row<'a>(
         .spacing(6)
         .align_y(iced::Alignment::Center)
     } else {
-        row![
-            text(&task.summary)
-                .size(20)
-                .color(color)
-                .width(Length::Fill)
-        ]
-        .spacing(6)
-        .align_y(iced::Alignment::Center)
+        row![title()].spacing(6).align_y(iced::Alignment::Center)
     };
     let main_text_col = column![
         title_row,
@@ -596,14 +778,16 @@ pub fn view_task_row<'a>(
     ]
     .width(Length::Fill)
     .spacing(1);
+    // Selects; ctrl and shift build the multi-selection of the bulk actions
+    let main_text_col = mouse_area(main_text_col).on_press(Message::TaskClicked(index));
     let row_main = row![indent, status_btn, main_text_col, date_text, actions]
-        .spacing(10)
+        .spacing(row_spacing)
         .align_y(iced::Alignment::Center);
 
     let mut padded_row = container(row_main).padding(iced::Padding {
-        top: 2.0,
+        top: row_padding,
         right: 16.0,
-        bottom: 2.0,
+        bottom: row_padding,
         left: 6.0,
     });
     if is_selected {
@@ -634,12 +818,42 @@ pub fn view_task_row<'a>(
 
     if is_expanded {
         let mut details_col = column![].spacing(5);
-        if !task.description.is_empty() {
+        let editing = app.card_edit.as_ref().filter(|(uid, _)| *uid == task.uid);
+        if let Some((_, draft)) = editing {
+            details_col = details_col.push(view_card_editor(app, task, draft));
+        } else {
+            details_col = details_col.push(
+                button(
+                    row![
+                        icon::icon(icon::EDIT).size(10),
+                        text("Edit all fields").size(10)
+                    ]
+                    .spacing(5)
+                    .align_y(iced::Alignment::Center),
+                )
+                .style(button::secondary)
+                .padding(3)
+                .on_press(Message::StartCardEdit(task.uid.clone())),
+            );
+        }
+        if editing.is_none() && !task.description.is_empty() {
+            let preview = task
+                .description_preview(DESCRIPTION_PREVIEW_LEN)
+                .filter(|_| !app.full_descriptions.contains(&task.uid));
             details_col = details_col.push(
-                text(&task.description)
+                text(preview.map_or(task.description.clone(), |p| format!("{}…", p)))
                     .size(14)
                     .color(Color::from_rgb(0.7, 0.7, 0.7)),
             );
+            if preview.is_some() {
+                let kib = task.description.len() / 1024;
+                details_col = details_col.push(
+                    button(text(format!("Show the whole description ({} KiB)", kib)).size(10))
+                        .style(button::secondary)
+                        .padding(3)
+                        .on_press(Message::ShowFullDescription(task.uid.clone())),
+                );
+            }
         }
         if let Some(p_uid) = &task.parent_uid {
             let p_name = app
@@ -668,7 +882,7 @@ pub fn view_task_row<'a>(
             .align_y(iced::Alignment::Center);
             details_col = details_col.push(row);
         }
-        if !task.dependencies.is_empty() {
+        if editing.is_none() && !task.dependencies.is_empty() {
             details_col = details_col.push(
                 text("[Blocked By]:")
                     .size(12)
@@ -703,6 +917,37 @@ pub fn view_task_row<'a>(
                 details_col = details_col.push(dep_row);
             }
         }
+        if let Some(rrule) = &task.rrule
+            && editing.is_none()
+        {
+            let mut repeats = format!("Repeats {}", describe_rrule(rrule));
+            if task.recur_from_completion {
+                repeats.push_str(" (from completion)");
+            }
+            details_col =
+                details_col.push(text(repeats).size(12).color(Color::from_rgb(0.6, 0.6, 0.9)));
+        }
+        if is_recurring {
+            let reschedule_input = text_input(
+                "New date (e.g. 2025-01-31, tomorrow, 3d)",
+                &app.reschedule_input,
+            )
+            .on_input(Message::RescheduleInputChanged)
+            .on_submit(Message::RescheduleOccurrence(task.uid.clone()))
+            .size(12)
+            .padding(3)
+            .width(Length::Fixed(260.0));
+            details_col = details_col.push(
+                row![
+                    text("Reschedule this occurrence:")
+                        .size(12)
+                        .color(Color::from_rgb(0.5, 0.5, 0.5)),
+                    reschedule_input
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
+            );
+        }
         if app.calendars.len() > 1 {
             let current_cal_href = task.calendar_href.clone();
             let targets: Vec<_> = app
@@ -728,6 +973,63 @@ pub fn view_task_row<'a>(
                     .align_y(iced::Alignment::Center),
             );
         }
+
+        // Duplicate: current calendar first, then the other writable ones
+        let mut dup_targets: Vec<_> = app
+            .calendars
+            .iter()
+            .filter(|c| !c.is_read_only && !app.disabled_calendars.contains(&c.href))
+            .collect();
+        dup_targets.sort_by_key(|c| c.href != task.calendar_href);
+        if !dup_targets.is_empty() {
+            let dup_label = text("Duplicate to:")
+                .size(12)
+                .color(Color::from_rgb(0.5, 0.5, 0.5));
+            let mut dup_row = row![].spacing(5).align_y(iced::Alignment::Center);
+            for cal in dup_targets {
+                dup_row = dup_row.push(
+                    button(text(&cal.name).size(10))
+                        .style(button::secondary)
+                        .padding(3)
+                        .on_press(Message::DuplicateTask(task.uid.clone(), cal.href.clone())),
+                );
+            }
+            let subtree_check = checkbox(app.duplicate_with_subtree)
+                .label("With subtasks")
+                .on_toggle(Message::ToggleDuplicateSubtree)
+                .size(12)
+                .text_size(10);
+            details_col = details_col.push(
+                row![
+                    dup_label,
+                    scrollable(dup_row).height(Length::Fixed(30.0)),
+                    subtree_check
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+
+        let share_label = text("Share:")
+            .size(12)
+            .color(Color::from_rgb(0.5, 0.5, 0.5));
+        let mut share_row = row![share_label]
+            .spacing(5)
+            .align_y(iced::Alignment::Center);
+        for (label, format) in [
+            ("Text", ShareFormat::Text),
+            (".ics file", ShareFormat::Ics),
+            ("Link", ShareFormat::Link),
+        ] {
+            share_row = share_row.push(
+                button(text(label).size(10))
+                    .style(button::secondary)
+                    .padding(3)
+                    .on_press(Message::ShareTask(task.uid.clone(), format)),
+            );
+        }
+        details_col = details_col.push(share_row);
+
         let desc_row = row![
             Space::new().width(Length::Fixed(indent_size as f32 + 30.0)),
             details_col
@@ -740,3 +1042,277 @@ pub fn view_task_row<'a>(
         padded_row.id(row_id).into()
     }
 }
+
+/// The expanded card while its fields are edited; saved as one update.
+fn view_card_editor<'a>(
+    app: &'a GuiApp,
+    task: &'a TodoTask,
+    draft: &'a TaskDraft,
+) -> Element<'a, Message> {
+    let edit = |e: CardEdit| Message::CardEdited(e);
+    let field = |label: &'static str, content: Element<'a, Message>| -> Element<'a, Message> {
+        row![
+            text(label)
+                .size(12)
+                .color(Color::from_rgb(0.5, 0.5, 0.5))
+                .width(Length::Fixed(80.0)),
+            content
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center)
+        .into()
+    };
+    let small_btn = |label: String, msg: Message| {
+        button(text(label).size(10))
+            .style(button::secondary)
+            .padding(3)
+            .on_press(msg)
+    };
+    let chip = |label: String, msg: Message| {
+        button(
+            row![text(label).size(10), icon::icon(icon::CROSS).size(8)]
+                .spacing(4)
+                .align_y(iced::Alignment::Center),
+        )
+        .style(button::secondary)
+        .padding([2, 6])
+        .on_press(msg)
+    };
+    let hint = |s: String| text(s).size(11).color(Color::from_rgb(0.6, 0.6, 0.6));
+
+    // Dates are typed like in the smart input, with shortcuts and what they resolve to
+    let date_field = |value: &'a str, end_of_day: bool, on: fn(String) -> CardEdit| {
+        let preview = if value.trim().is_empty() {
+            hint("None".to_string())
+        } else {
+            match parse_smart_date(value.trim(), end_of_day) {
+                Some(date) => hint(due::full(date)),
+                None => text("Invalid date")
+                    .size(11)
+                    .color(Color::from_rgb(0.9, 0.3, 0.3)),
+            }
+        };
+        let mut date_row = row![
+            text_input("2025-01-31, tomorrow, 3d", value)
+                .on_input(move |v| edit(on(v)))
+                .size(12)
+                .padding(3)
+                .width(Length::Fixed(180.0))
+        ]
+        .spacing(5)
+        .align_y(iced::Alignment::Center);
+        for (label, quick) in [
+            ("Today", "today"),
+            ("Tomorrow", "tomorrow"),
+            ("+1 week", "1w"),
+            ("Clear", ""),
+        ] {
+            date_row = date_row.push(small_btn(label.to_string(), edit(on(quick.to_string()))));
+        }
+        date_row.push(preview)
+    };
+
+    let mut col = column![
+        field(
+            "Title",
+            text_input("Title", &draft.summary)
+                .on_input(move |v| edit(CardEdit::Summary(v)))
+                .size(12)
+                .padding(3)
+                .into()
+        ),
+        field(
+            "Description",
+            iced::widget::text_editor(&app.card_description)
+                .on_action(move |a| edit(CardEdit::Description(a)))
+                .size(12)
+                .height(Length::Fixed(100.0))
+                .into()
+        ),
+        field("Due", date_field(&draft.due, true, CardEdit::Due).into()),
+        field(
+            "Start",
+            date_field(&draft.start, false, CardEdit::Start).into()
+        ),
+    ]
+    .spacing(6);
+
+    let priority_text = if !draft.priority.is_set() {
+        "None".to_string()
+    } else {
+        format!("{} ({})", draft.priority, priority::label(draft.priority))
+    };
+    let step = |glyph: char, delta: i8| {
+        button(icon::icon(glyph).size(12))
+            .style(button::secondary)
+            .padding([2, 6])
+            .on_press(edit(CardEdit::PriorityStep(delta)))
+    };
+    col = col.push(field(
+        "Priority",
+        row![
+            step(icon::MINUS, -1),
+            text(priority_text).size(12),
+            step(icon::PLUS, 1)
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center)
+        .into(),
+    ));
+    col = col.push(field(
+        "Duration",
+        text_input("30m, 2h, 1d", &draft.duration)
+            .on_input(move |v| edit(CardEdit::Duration(v)))
+            .size(12)
+            .padding(3)
+            .width(Length::Fixed(180.0))
+            .into(),
+    ));
+
+    // --- TAGS ---
+    let mut tags_row = row![].spacing(4).align_y(iced::Alignment::Center);
+    for tag in &draft.tags {
+        tags_row = tags_row.push(chip(
+            format!("#{}", tag),
+            edit(CardEdit::RemoveTag(tag.clone())),
+        ));
+    }
+    tags_row = tags_row.push(
+        text_input("Add tags", &app.card_tag_input)
+            .on_input(move |v| edit(CardEdit::TagInput(v)))
+            .on_submit(edit(CardEdit::AddTags))
+            .size(12)
+            .padding(3)
+            .width(Length::Fixed(140.0)),
+    );
+    let all_tasks = || app.store.calendars.values().flatten();
+    for tag in suggest_tags(all_tasks(), &app.card_tag_input, &draft.tags) {
+        tags_row = tags_row.push(small_btn(
+            format!("#{}", tag),
+            edit(CardEdit::AddTag(tag.clone())),
+        ));
+    }
+    col = col.push(field("Tags", tags_row.wrap().into()));
+
+    // --- RECURRENCE ---
+    let rec = draft.rrule.as_deref().and_then(Recurrence::from_rrule);
+    let mut rec_row = row![
+        iced::widget::pick_list(Frequency::ALL, rec.as_ref().map(|r| r.freq), move |f| {
+            edit(CardEdit::Frequency(f))
+        })
+        .placeholder("Never")
+        .text_size(12)
+        .padding(4)
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center);
+    if let Some(r) = &rec {
+        let interval_btn = |t: &'static str, delta: i32| {
+            button(text(t).size(12))
+                .style(button::secondary)
+                .padding([2, 8])
+                .on_press(edit(CardEdit::IntervalStep(delta)))
+        };
+        rec_row = rec_row.push(
+            row![
+                text("every").size(12),
+                interval_btn("-", -1),
+                text(r.interval.to_string()).size(12),
+                interval_btn("+", 1),
+                text(r.freq.unit()).size(12),
+            ]
+            .spacing(4)
+            .align_y(iced::Alignment::Center),
+        );
+        match r.freq {
+            Frequency::Weekly => {
+                let mut days = row![].spacing(2);
+                for day in WEEKDAYS {
+                    let style = if r.by_day.contains(&day) {
+                        button::primary
+                    } else {
+                        button::secondary
+                    };
+                    days = days.push(
+                        button(text(day.to_string()).size(11))
+                            .style(style)
+                            .padding([2, 5])
+                            .on_press(edit(CardEdit::DayToggled(day))),
+                    );
+                }
+                rec_row = rec_row.push(days);
+            }
+            Frequency::Monthly => {
+                rec_row = rec_row.push(
+                    iced::widget::pick_list(
+                        (1..=31).collect::<Vec<u32>>(),
+                        r.by_month_day,
+                        move |d| edit(CardEdit::MonthDaySelected(d)),
+                    )
+                    .placeholder("Day of due date")
+                    .text_size(12)
+                    .padding(4),
+                );
+            }
+            _ => {}
+        }
+    }
+    if let Some(rrule) = &draft.rrule {
+        rec_row = rec_row.push(
+            text(describe_rrule(rrule))
+                .size(12)
+                .color(Color::from_rgb(0.6, 0.6, 0.9)),
+        );
+        rec_row = rec_row.push(
+            button(icon::icon(icon::CROSS).size(10))
+                .style(button::danger)
+                .padding(4)
+                .on_press(edit(CardEdit::ClearRecurrence)),
+        );
+    }
+    col = col.push(field("Repeat", rec_row.into()));
+
+    // --- DEPENDENCIES ---
+    let mut deps_row = row![].spacing(4).align_y(iced::Alignment::Center);
+    for dep_uid in &draft.dependencies {
+        let name = app
+            .store
+            .get_summary(dep_uid)
+            .unwrap_or_else(|| "Unknown Task".to_string());
+        deps_row = deps_row.push(chip(
+            name,
+            edit(CardEdit::RemoveDependency(dep_uid.clone())),
+        ));
+    }
+    deps_row = deps_row.push(
+        text_input("Search tasks", &app.card_dependency_input)
+            .on_input(move |v| edit(CardEdit::DependencyInput(v)))
+            .size(12)
+            .padding(3)
+            .width(Length::Fixed(140.0)),
+    );
+    for (uid, summary) in suggest_dependencies(
+        all_tasks(),
+        &app.card_dependency_input,
+        &task.uid,
+        &draft.dependencies,
+    ) {
+        deps_row = deps_row.push(small_btn(summary, edit(CardEdit::AddDependency(uid))));
+    }
+    col = col.push(field("Blocked by", deps_row.wrap().into()));
+
+    col.push(
+        row![
+            button(text("Save").size(12))
+                .style(button::primary)
+                .padding([3, 12])
+                .on_press(Message::SaveCardEdit),
+            button(text("Cancel").size(12))
+                .style(button::secondary)
+                .padding([3, 12])
+                .on_press(Message::CancelCardEdit),
+        ]
+        .spacing(8),
+    )
+    .into()
+}
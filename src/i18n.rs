@@ -0,0 +1,150 @@
+// File: src/i18n.rs
+// UI strings by language (`language` in the config, else the system's), looked up by key in
+// the catalogs of assets/locales. English is the fallback for any missing key.
+// The catalogs cover help, the syntax guide, headings, settings, due dates, recurrence
+// descriptions, TUI status messages, read-only notices and sync conflict warnings; the GUI's
+// status messages and the CLI are still English only.
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::sync::{OnceLock, RwLock};
+
+const CATALOGS: [(&str, &str); 3] = [
+    ("en", include_str!("../assets/locales/en.toml")),
+    ("de", include_str!("../assets/locales/de.toml")),
+    ("fr", include_str!("../assets/locales/fr.toml")),
+];
+
+static STRINGS: OnceLock<HashMap<&'static str, HashMap<String, String>>> = OnceLock::new();
+static LANGUAGE: RwLock<Option<&'static str>> = RwLock::new(None);
+
+/// Catalog sections become key prefixes: `[gui] search = ".."` is "gui.search".
+fn flatten(prefix: &str, table: &toml::Table, out: &mut HashMap<String, String>) {
+    for (name, value) in table {
+        let key = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+        match value {
+            toml::Value::String(s) => {
+                out.insert(key, s.clone());
+            }
+            toml::Value::Table(t) => flatten(&key, t, out),
+            _ => {}
+        }
+    }
+}
+
+fn strings() -> &'static HashMap<&'static str, HashMap<String, String>> {
+    STRINGS.get_or_init(|| {
+        CATALOGS
+            .iter()
+            .map(|(language, source)| {
+                let mut out = HashMap::new();
+                if let Ok(table) = toml::from_str::<toml::Table>(source) {
+                    flatten("", &table, &mut out);
+                }
+                (*language, out)
+            })
+            .collect()
+    })
+}
+
+/// Language codes with a catalog
+pub fn available() -> Vec<&'static str> {
+    CATALOGS.iter().map(|(language, _)| *language).collect()
+}
+
+fn find(language: &str) -> Option<&'static str> {
+    available()
+        .into_iter()
+        .find(|l| l.eq_ignore_ascii_case(language))
+}
+
+pub fn is_available(language: &str) -> bool {
+    find(language).is_some()
+}
+
+/// From LC_ALL, LC_MESSAGES or LANG, e.g. "fr_BE.UTF-8" -> "fr"
+fn detect() -> Option<&'static str> {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|v| !v.is_empty())?;
+    find(locale.split(['_', '.', '@', '-']).next()?)
+}
+
+/// Installs the config's language (done on every config load). None = the system's.
+pub fn set_language(language: Option<&str>) {
+    let language = language.and_then(find).or_else(detect).unwrap_or("en");
+    if let Ok(mut current) = LANGUAGE.write() {
+        *current = Some(language);
+    }
+}
+
+pub fn current() -> &'static str {
+    if let Ok(current) = LANGUAGE.read()
+        && let Some(language) = *current
+    {
+        return language;
+    }
+    let detected = detect().unwrap_or("en");
+    if let Ok(mut current) = LANGUAGE.write() {
+        *current = Some(detected);
+    }
+    detected
+}
+
+fn lookup(language: &str, key: &str) -> Option<&'static str> {
+    strings().get(language)?.get(key).map(String::as_str)
+}
+
+/// The string for `key` in the current language, else in English, else the key itself.
+pub fn tr(key: &'static str) -> &'static str {
+    lookup(current(), key)
+        .or_else(|| lookup("en", key))
+        .unwrap_or(key)
+}
+
+/// `tr` with its `{name}` placeholders filled in.
+pub fn tr_args(key: &'static str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    args.iter().fold(tr(key).to_string(), |s, (name, value)| {
+        s.replace(&format!("{{{}}}", name), &value.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalogs_parse_and_match_english() {
+        let strings = strings();
+        let english = &strings["en"];
+        assert!(english.contains_key("help.global"));
+        for language in available() {
+            let catalog = &strings[language];
+            assert!(!catalog.is_empty(), "{} did not parse", language);
+            for key in catalog.keys() {
+                assert!(
+                    english.contains_key(key),
+                    "{}: unknown key {}",
+                    language,
+                    key
+                );
+            }
+        }
+        assert_eq!(lookup("fr", "gui.settings"), Some("Paramètres"));
+        assert_eq!(tr("no.such.key"), "no.such.key");
+        assert!(is_available("FR"));
+        assert!(!is_available("xx"));
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let text = tr_args("error.font_size", &[("min", &12), ("max", &32)]);
+        assert!(text.contains("12") && text.contains("32"));
+        assert!(!text.contains('{'));
+    }
+}
@@ -0,0 +1,227 @@
+// File: ./src/ipc.rs
+// Local protocol between `cfait daemon` and the UIs / CLI: one JSON object per line over a
+// unix domain socket (a named pipe on Windows)
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::broadcast;
+
+#[cfg(windows)]
+const PIPE_NAME: &str = r"\\.\pipe\cfait-daemon";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Request {
+    Status,
+    /// Sync now: one calendar, or all of them
+    Sync {
+        calendar: Option<String>,
+    },
+    /// Keep the connection open and receive `Changed` after every sync
+    Subscribe,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Reply {
+    Status {
+        pending: usize,
+        last_sync: Option<DateTime<Utc>>,
+    },
+    Synced,
+    /// The replica of these calendars was updated (empty = reload everything)
+    Changed {
+        calendars: Vec<String>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+async fn write_line<W: AsyncWrite + Unpin>(writer: &mut W, reply: &Reply) -> Result<()> {
+    let mut line = serde_json::to_string(reply)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await?;
+    Ok(())
+}
+
+/// Answers the requests of one client; `Subscribe` turns the connection into a change feed.
+async fn handle_connection<S, F, Fut>(stream: S, changes: broadcast::Sender<Vec<String>>, handle: F)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    F: Fn(Request) -> Fut,
+    Fut: Future<Output = Reply>,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let reply = match serde_json::from_str::<Request>(&line) {
+            Ok(Request::Subscribe) => {
+                let mut rx = changes.subscribe();
+                loop {
+                    let calendars = match rx.recv().await {
+                        Ok(calendars) => calendars,
+                        Err(broadcast::error::RecvError::Lagged(_)) => Vec::new(),
+                        Err(broadcast::error::RecvError::Closed) => return,
+                    };
+                    if write_line(&mut writer, &Reply::Changed { calendars })
+                        .await
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+            }
+            Ok(request) => handle(request).await,
+            Err(e) => Reply::Error {
+                message: format!("Bad request: {}", e),
+            },
+        };
+        if write_line(&mut writer, &reply).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Accepts clients until the process exits. `changes` feeds the subscribers.
+pub async fn serve<F, Fut>(changes: broadcast::Sender<Vec<String>>, handle: F) -> Result<()>
+where
+    F: Fn(Request) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = Reply> + Send,
+{
+    #[cfg(unix)]
+    {
+        let path = socket_path()?;
+        // Left over by a daemon that died; the daemon lock guarantees it is not in use
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path)?;
+        loop {
+            let (stream, _) = listener.accept().await?;
+            tokio::spawn(handle_connection(stream, changes.clone(), handle.clone()));
+        }
+    }
+    #[cfg(windows)]
+    {
+        use tokio::net::windows::named_pipe::ServerOptions;
+        let mut server = ServerOptions::new()
+            .first_pipe_instance(true)
+            .create(PIPE_NAME)?;
+        loop {
+            server.connect().await?;
+            let connected = server;
+            server = ServerOptions::new().create(PIPE_NAME)?;
+            tokio::spawn(handle_connection(
+                connected,
+                changes.clone(),
+                handle.clone(),
+            ));
+        }
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = (changes, handle);
+        Err(anyhow!("IPC is not supported on this platform"))
+    }
+}
+
+#[cfg(unix)]
+fn socket_path() -> Result<std::path::PathBuf> {
+    Ok(crate::paths::AppPaths::get_data_dir()?.join("daemon.sock"))
+}
+
+#[cfg(unix)]
+async fn connect() -> Result<tokio::net::UnixStream> {
+    Ok(tokio::net::UnixStream::connect(socket_path()?).await?)
+}
+
+#[cfg(windows)]
+async fn connect() -> Result<tokio::net::windows::named_pipe::NamedPipeClient> {
+    Ok(tokio::net::windows::named_pipe::ClientOptions::new().open(PIPE_NAME)?)
+}
+
+#[cfg(not(any(unix, windows)))]
+async fn connect() -> Result<tokio::io::DuplexStream> {
+    Err(anyhow!("IPC is not supported on this platform"))
+}
+
+/// Sends one request to the running daemon and waits for its answer.
+pub async fn request(request: &Request) -> Result<Reply> {
+    let stream = connect()
+        .await
+        .map_err(|_| anyhow!("cfait daemon is not running"))?;
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut line = serde_json::to_string(request)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await?;
+    let answer = BufReader::new(reader)
+        .lines()
+        .next_line()
+        .await?
+        .ok_or_else(|| anyhow!("The daemon closed the connection"))?;
+    Ok(serde_json::from_str(&answer)?)
+}
+
+/// Calls `on_change` with the calendars the daemon refreshed, until it returns false.
+/// Errors when the daemon cannot be reached or goes away.
+pub async fn subscribe(mut on_change: impl FnMut(Vec<String>) -> bool + Send) -> Result<()> {
+    let stream = connect().await?;
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut line = serde_json::to_string(&Request::Subscribe)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await?;
+
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await? {
+        if let Ok(Reply::Changed { calendars }) = serde_json::from_str::<Reply>(&line)
+            && !on_change(calendars)
+        {
+            return Ok(());
+        }
+    }
+    Err(anyhow!("The daemon stopped"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_requests_and_change_feed() {
+        let (changes, _) = broadcast::channel(4);
+        let (client, server) = tokio::io::duplex(1024);
+        tokio::spawn(handle_connection(
+            server,
+            changes.clone(),
+            |req| async move {
+                match req {
+                    Request::Sync { calendar: None } => Reply::Synced,
+                    _ => Reply::Error {
+                        message: "unexpected".to_string(),
+                    },
+                }
+            },
+        ));
+
+        let (reader, mut writer) = tokio::io::split(client);
+        let mut lines = BufReader::new(reader).lines();
+        writer
+            .write_all(b"{\"type\":\"sync\",\"calendar\":null}\n{\"type\":\"subscribe\"}\n")
+            .await
+            .unwrap();
+        let reply = lines.next_line().await.unwrap().unwrap();
+        assert_eq!(
+            serde_json::from_str::<Reply>(&reply).unwrap(),
+            Reply::Synced
+        );
+
+        // Subscribed once the server reads the second line
+        while changes.receiver_count() == 0 {
+            tokio::task::yield_now().await;
+        }
+        changes.send(vec!["/cal/work/".to_string()]).unwrap();
+        let reply = lines.next_line().await.unwrap().unwrap();
+        assert_eq!(reply, r#"{"type":"changed","calendars":["/cal/work/"]}"#);
+    }
+}
@@ -1,9 +1,13 @@
 // File: src/journal.rs
+use crate::cache::Cache;
 use crate::model::Task;
+use crate::model::href;
 use crate::paths::AppPaths;
 use crate::storage::LocalStorage;
 use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -15,9 +19,163 @@ pub enum Action {
     Move(Task, String),
 }
 
+impl Action {
+    /// The task acted on (as it was before the move, for moves).
+    pub fn task(&self) -> &Task {
+        match self {
+            Action::Create(t) | Action::Update(t) | Action::Delete(t) | Action::Move(t, _) => t,
+        }
+    }
+
+    /// Queued hrefs are spelled as the fetched ones, see `href::normalize`.
+    fn normalize_hrefs(&mut self) {
+        let task = match self {
+            Action::Create(t) | Action::Update(t) | Action::Delete(t) => t,
+            Action::Move(t, new_cal) => {
+                *new_cal = href::normalize(new_cal);
+                t
+            }
+        };
+        task.href = href::normalize(&task.href);
+        task.calendar_href = href::normalize(&task.calendar_href);
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Journal {
     pub queue: Vec<Action>,
+    /// Last time the server was reached (tasks fetched or the queue pushed)
+    #[serde(default)]
+    pub last_sync: Option<DateTime<Utc>>,
+    /// Uids of the tasks whose actions stay queued when the rest is pushed
+    #[serde(default)]
+    pub held: Vec<String>,
+}
+
+/// A queued action as the sync preview shows it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingChange {
+    pub uid: String,
+    /// "create", "update", "delete" or "move"
+    pub kind: &'static str,
+    pub summary: String,
+    /// "+ field: value" for what the server gets, "- field: value" for what it loses
+    pub lines: Vec<String>,
+    pub held: bool,
+}
+
+/// What the preview compares, one entry per field set on the task.
+fn fields(task: &Task) -> Vec<(&'static str, String)> {
+    let date = |d: DateTime<Utc>| d.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string();
+    let mut fields = vec![
+        ("summary", task.summary.clone()),
+        ("status", format!("{:?}", task.status)),
+    ];
+    let optional = [
+        ("description", task.description.clone()),
+        ("due", task.due.map(date).unwrap_or_default()),
+        ("start", task.dtstart.map(date).unwrap_or_default()),
+        (
+            "priority",
+            task.priority
+                .is_set()
+                .then(|| task.priority.to_string())
+                .unwrap_or_default(),
+        ),
+        (
+            "tags",
+            task.categories
+                .iter()
+                .map(|c| format!("#{}", c))
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        (
+            "duration",
+            task.estimated_duration
+                .map(|d| d.to_string())
+                .unwrap_or_default(),
+        ),
+        ("recurrence", task.rrule.clone().unwrap_or_default()),
+        ("parent", task.parent_uid.clone().unwrap_or_default()),
+        ("depends on", task.dependencies.join(" ")),
+        (
+            "reminders",
+            task.reminder_times()
+                .into_iter()
+                .map(date)
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        ("calendar", task.calendar_href.clone()),
+    ];
+    fields.extend(optional.into_iter().filter(|(_, value)| !value.is_empty()));
+    fields
+}
+
+fn field_lines(sign: char, name: &str, value: &str) -> impl Iterator<Item = String> {
+    let mut lines = value.lines();
+    let first = format!("{} {}: {}", sign, name, lines.next().unwrap_or_default());
+    let rest: Vec<String> = lines.map(|l| format!("{}   {}", sign, l)).collect();
+    std::iter::once(first).chain(rest)
+}
+
+/// Field-level diff from `old` to `new`; None on one side lists every field of the other.
+pub fn diff_lines(old: Option<&Task>, new: Option<&Task>) -> Vec<String> {
+    let old = old.map(fields).unwrap_or_default();
+    let new = new.map(fields).unwrap_or_default();
+    let value = |fields: &[(&str, String)], name: &str| {
+        fields
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v.clone())
+    };
+    let mut names: Vec<&str> = old.iter().map(|(n, _)| *n).collect();
+    for (name, _) in &new {
+        if !names.contains(name) {
+            names.push(name);
+        }
+    }
+
+    let mut lines = Vec::new();
+    for name in names {
+        let (before, after) = (value(&old, name), value(&new, name));
+        if before == after {
+            continue;
+        }
+        if let Some(v) = before {
+            lines.extend(field_lines('-', name, &v));
+        }
+        if let Some(v) = after {
+            lines.extend(field_lines('+', name, &v));
+        }
+    }
+    lines
+}
+
+/// What the frontends show about the offline queue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncStatus {
+    pub pending: usize,
+    pub last_sync: Option<DateTime<Utc>>,
+}
+
+impl SyncStatus {
+    /// Nothing is waiting for the server, so going offline loses nothing.
+    pub fn is_synced(&self) -> bool {
+        self.pending == 0
+    }
+
+    /// Short status text, e.g. "3 unsynced" or "Synced 5 min ago".
+    pub fn label(&self) -> String {
+        if self.pending > 0 {
+            format!("{} unsynced", self.pending)
+        } else if let Some(at) = self.last_sync {
+            format!("Synced {}", crate::cache::format_ago(at, Utc::now()))
+        } else {
+            "Never synced".to_string()
+        }
+    }
 }
 
 impl Journal {
@@ -48,15 +206,12 @@ impl Journal {
         Self::default()
     }
 
-    /// Transactional modification of the journal queue.
-    pub fn modify<F>(f: F) -> Result<()>
-    where
-        F: FnOnce(&mut Vec<Action>),
-    {
+    /// Transactional modification of the whole journal.
+    fn update(f: impl FnOnce(&mut Journal)) -> Result<()> {
         if let Some(path) = Self::get_path() {
             LocalStorage::with_lock(&path, || {
                 let mut journal = Self::load_internal(&path);
-                f(&mut journal.queue);
+                f(&mut journal);
                 let json = serde_json::to_string_pretty(&journal)?;
                 LocalStorage::atomic_write(&path, json)?;
                 Ok(())
@@ -65,11 +220,232 @@ impl Journal {
         Ok(())
     }
 
-    pub fn push(action: Action) -> Result<()> {
+    /// Transactional modification of the journal queue.
+    pub fn modify<F>(f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Vec<Action>),
+    {
+        Self::update(|journal| f(&mut journal.queue))
+    }
+
+    pub fn push(mut action: Action) -> Result<()> {
+        action.normalize_hrefs();
         Self::modify(|queue| queue.push(action))
     }
 
     pub fn is_empty(&self) -> bool {
         self.queue.is_empty()
     }
+
+    pub fn status() -> SyncStatus {
+        let journal = Self::load();
+        SyncStatus {
+            pending: journal.queue.len(),
+            last_sync: journal.last_sync,
+        }
+    }
+
+    /// Records that the server was just reached.
+    pub fn mark_synced() -> Result<()> {
+        Self::update(|journal| journal.last_sync = Some(Utc::now()))
+    }
+
+    /// Keeps the actions on `uid` out of the next pushes, or lets them go again.
+    pub fn hold(uid: &str, held: bool) -> Result<()> {
+        Self::update(|journal| {
+            journal.held.retain(|u| u != uid);
+            if held {
+                journal.held.push(uid.to_string());
+            }
+            // Tasks with nothing queued anymore need no hold
+            let queue = &journal.queue;
+            journal
+                .held
+                .retain(|u| queue.iter().any(|a| a.task().uid == *u));
+        })
+    }
+
+    /// Position of the next action to push, skipping those of held tasks.
+    pub fn next_index(&self) -> Option<usize> {
+        self.queue
+            .iter()
+            .position(|a| !self.held.contains(&a.task().uid))
+    }
+
+    /// The queued actions with what each changes on the server. Updates are compared with
+    /// the previous queued version of the task, else with the cached server copy.
+    pub fn preview(&self) -> Vec<PendingChange> {
+        let mut cached: HashMap<String, Vec<Task>> = HashMap::new();
+        let mut latest: HashMap<String, Task> = HashMap::new();
+        let mut changes = Vec::new();
+        for action in &self.queue {
+            let task = action.task();
+            let (kind, lines) = match action {
+                Action::Create(t) => ("create", diff_lines(None, Some(t))),
+                Action::Delete(t) => ("delete", diff_lines(Some(t), None)),
+                Action::Move(t, new_cal) => (
+                    "move",
+                    vec![
+                        format!("- calendar: {}", t.calendar_href),
+                        format!("+ calendar: {}", new_cal),
+                    ],
+                ),
+                Action::Update(t) => {
+                    let base = match latest.get(&t.uid) {
+                        Some(previous) => Some(previous.clone()),
+                        None => cached
+                            .entry(t.calendar_href.clone())
+                            .or_insert_with(|| Cache::load(&t.calendar_href).unwrap_or_default().0)
+                            .iter()
+                            .find(|c| c.uid == t.uid)
+                            .cloned(),
+                    };
+                    ("update", diff_lines(base.as_ref(), Some(t)))
+                }
+            };
+            let mut after = task.clone();
+            if let Action::Move(_, new_cal) = action {
+                after.calendar_href = new_cal.clone();
+            }
+            latest.insert(task.uid.clone(), after);
+            changes.push(PendingChange {
+                uid: task.uid.clone(),
+                kind,
+                summary: task.summary.clone(),
+                lines,
+                held: self.held.contains(&task.uid),
+            });
+        }
+        changes
+    }
+
+    /// Replays the pending actions on the tasks of `calendar_href`, so that reads
+    /// show local changes the server hasn't received yet.
+    pub fn apply_to(&self, calendar_href: &str, tasks: &mut Vec<Task>) {
+        fn upsert(tasks: &mut Vec<Task>, task: Task) {
+            match tasks.iter_mut().find(|t| t.uid == task.uid) {
+                Some(existing) => *existing = task,
+                None => tasks.push(task),
+            }
+        }
+
+        // Entries queued before hrefs were normalized may be spelled differently
+        let calendar_href = href::normalize(calendar_href);
+        let here = |href: &str| href::normalize(href) == calendar_href;
+        for action in &self.queue {
+            match action {
+                Action::Create(t) | Action::Update(t) if here(&t.calendar_href) => {
+                    upsert(tasks, t.clone());
+                }
+                Action::Delete(t) if here(&t.calendar_href) => {
+                    tasks.retain(|x| x.uid != t.uid);
+                }
+                Action::Move(t, new_cal) => {
+                    if here(&t.calendar_href) {
+                        tasks.retain(|x| x.uid != t.uid);
+                    }
+                    if here(new_cal) {
+                        let mut moved = t.clone();
+                        moved.calendar_href = new_cal.clone();
+                        upsert(tasks, moved);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Priority;
+
+    #[test]
+    fn test_apply_pending_actions() {
+        let task = |name: &str, cal: &str| {
+            let mut t = Task::new(name, &HashMap::new());
+            t.calendar_href = cal.to_string();
+            t
+        };
+        let kept = task("Kept", "/a/");
+        let deleted = task("Deleted", "/a/");
+        let moved = task("Moved", "/a/");
+        let mut edited = kept.clone();
+        edited.summary = "Kept (edited)".to_string();
+
+        let journal = Journal {
+            queue: vec![
+                Action::Create(task("Created offline", "/a/")),
+                Action::Update(edited),
+                Action::Delete(deleted.clone()),
+                Action::Move(moved.clone(), "/b/".to_string()),
+            ],
+            last_sync: None,
+            held: Vec::new(),
+        };
+
+        let mut a = vec![kept, deleted, moved.clone()];
+        journal.apply_to("/a/", &mut a);
+        let names: Vec<_> = a.iter().map(|t| t.summary.as_str()).collect();
+        assert_eq!(names, vec!["Kept (edited)", "Created offline"]);
+
+        let mut b = Vec::new();
+        journal.apply_to("/b/", &mut b);
+        assert_eq!(b.len(), 1);
+        assert_eq!(b[0].uid, moved.uid);
+        assert_eq!(b[0].calendar_href, "/b/");
+
+        // However the server spells the calendar
+        let journal = Journal {
+            queue: vec![Action::Create(task("Encoded", "/cal/Meine%20Aufgaben/"))],
+            last_sync: None,
+            held: Vec::new(),
+        };
+        let mut c = Vec::new();
+        journal.apply_to("/cal/Meine Aufgaben/", &mut c);
+        assert_eq!(c.len(), 1);
+    }
+
+    #[test]
+    fn test_preview_diffs_and_held_tasks() {
+        let mut created = Task::new("Write report #work", &HashMap::new());
+        created.calendar_href = "/a/".to_string();
+        let mut edited = created.clone();
+        edited.summary = "Write the report".to_string();
+        edited.priority = Priority::HIGH;
+        let mut gone = Task::new("Old idea", &HashMap::new());
+        gone.calendar_href = "/a/".to_string();
+
+        let journal = Journal {
+            queue: vec![
+                Action::Delete(gone.clone()),
+                Action::Create(created.clone()),
+                Action::Update(edited),
+            ],
+            last_sync: None,
+            held: vec![gone.uid.clone()],
+        };
+        assert_eq!(journal.next_index(), Some(1));
+
+        let preview = journal.preview();
+        assert_eq!(preview.len(), 3);
+        assert!(preview[0].held && preview[0].kind == "delete");
+        assert!(
+            preview[0]
+                .lines
+                .contains(&"- summary: Old idea".to_string())
+        );
+        assert!(preview[1].lines.contains(&"+ tags: #work".to_string()));
+        // The update is compared with the queued create, not the server copy
+        assert_eq!(
+            preview[2].lines,
+            vec![
+                "- summary: Write report",
+                "+ summary: Write the report",
+                "+ priority: 1"
+            ]
+        );
+        assert!(!preview[2].held);
+    }
 }
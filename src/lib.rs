@@ -1,11 +1,36 @@
 // File: src/lib.rs
+// The model and the store build everywhere, wasm32 included; the rest needs the `native`
+// feature (files, network, runtime), which the TUI and GUI features turn on.
+#[cfg(feature = "native")]
+pub mod backup;
+#[cfg(feature = "native")]
 pub mod cache;
+#[cfg(feature = "native")]
 pub mod client;
 pub mod color_utils;
+#[cfg(feature = "native")]
 pub mod config;
+#[cfg(feature = "native")]
+pub mod demo;
+pub mod i18n;
+#[cfg(all(feature = "native", not(target_os = "android")))]
+pub mod ipc;
+#[cfg(feature = "native")]
 pub mod journal;
+#[cfg(feature = "mcp")]
+pub mod mcp;
 pub mod model;
+#[cfg(any(feature = "tui", feature = "gui"))]
+pub mod notifier;
+#[cfg(feature = "native")]
 pub mod paths;
+#[cfg(feature = "native")]
+pub mod rehome;
+#[cfg(feature = "native")]
+pub mod server;
+#[cfg(feature = "native")]
+pub mod session;
+#[cfg(feature = "native")]
 pub mod storage;
 pub mod store;
 
@@ -21,3 +46,7 @@ pub mod mobile;
 
 #[cfg(target_os = "android")]
 uniffi::setup_scaffolding!();
+
+// --- BROWSER SUPPORT ---
+#[cfg(target_arch = "wasm32")]
+pub mod web;
@@ -0,0 +1,225 @@
+// File: ./src/mcp.rs
+// `cfait mcp`: a Model Context Protocol server on stdin/stdout, so LLM assistants can look up
+// and add tasks through cfait's offline-capable sync layer instead of talking CalDAV. MCP is
+// JSON-RPC 2.0: the tools are thin wrappers around the calls of `crate::server`.
+use crate::model::Task;
+use crate::server::{
+    self, Call, INVALID_PARAMS, METHOD_NOT_FOUND, Responder, RpcError, Server, parse_request,
+    response,
+};
+use anyhow::Result;
+use serde_json::{Value, json};
+
+pub const USAGE: &str = "cfait mcp     (Model Context Protocol server on stdin/stdout)";
+
+/// Answered when the client does not say which version it speaks
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+fn tools() -> Value {
+    let uid = json!({
+        "type": "object",
+        "properties": {"uid": {"type": "string", "description": "Task uid, from search_tasks"}},
+        "required": ["uid"]
+    });
+    json!([
+        {
+            "name": "list_calendars",
+            "description": "List the task calendars (name and href).",
+            "inputSchema": {"type": "object", "properties": {}}
+        },
+        {
+            "name": "search_tasks",
+            "description": "Find tasks. The query uses cfait's search syntax: words, #tag, \
+                            is:done, is:ongoing, @<1w (due within a week), @<2025-01-01, \
+                            !<3 (priority 1 or 2), cal:name. Empty query: every open task.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": {"type": "string"},
+                    "calendar": {"type": "string", "description": "Calendar name or href"},
+                    "include_done": {"type": "boolean"}
+                }
+            }
+        },
+        {
+            "name": "add_task",
+            "description": "Add a task written in cfait's smart syntax, e.g. \
+                            'Call Bob @tomorrow !1 #work ~30m @every week'.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "text": {"type": "string"},
+                    "calendar": {
+                        "type": "string",
+                        "description": "Calendar name or href (default: the user's default)"
+                    }
+                },
+                "required": ["text"]
+            }
+        },
+        {
+            "name": "toggle_task",
+            "description": "Mark a task done, or open again if it is done.",
+            "inputSchema": uid.clone()
+        },
+        {
+            "name": "delete_task",
+            "description": "Delete a task.",
+            "inputSchema": uid
+        },
+        {
+            "name": "sync",
+            "description": "Fetch the latest tasks from the server (all calendars by default).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {"calendar": {"type": "string"}}
+            }
+        }
+    ])
+}
+
+/// Tasks as assistants read them best: one line of smart syntax each, with what to act on.
+fn brief(task: &Task) -> Value {
+    json!({
+        "uid": task.uid,
+        "text": task.to_smart_string(),
+        "status": task.status,
+        "calendar": task.calendar_href,
+        "description": task.description,
+    })
+}
+
+fn tool_text(value: &Value) -> String {
+    let tasks = match value {
+        Value::Array(items) => serde_json::from_value::<Vec<Task>>(value.clone())
+            .ok()
+            .filter(|_| !items.is_empty())
+            .map(|tasks| Value::Array(tasks.iter().map(brief).collect())),
+        _ => serde_json::from_value::<Task>(value.clone())
+            .ok()
+            .map(|task| brief(&task)),
+    };
+    let value = tasks.as_ref().unwrap_or(value);
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+pub struct McpServer {
+    server: Server,
+}
+
+impl McpServer {
+    pub fn new(server: Server) -> Self {
+        Self { server }
+    }
+
+    async fn call_tool(&mut self, params: Value) -> Result<Value, RpcError> {
+        let name = params["name"].as_str().unwrap_or_default();
+        let method = match name {
+            "list_calendars" => "calendars",
+            "search_tasks" => "tasks",
+            "add_task" => "create",
+            "toggle_task" => "toggle",
+            "delete_task" => "delete",
+            "sync" => "sync",
+            other => {
+                return Err(RpcError::new(
+                    INVALID_PARAMS,
+                    format!("Unknown tool: {}", other),
+                ));
+            }
+        };
+        let arguments = params.get("arguments").cloned().unwrap_or(Value::Null);
+        // Failures of the tool itself are results, for the model to read and react to
+        let (text, is_error) = match Call::parse(method, arguments) {
+            Ok(call) => match self.server.call(call).await {
+                Ok(value) => (tool_text(&value), false),
+                Err(e) => (e.message, true),
+            },
+            Err(e) => (e.message, true),
+        };
+        Ok(json!({
+            "content": [{"type": "text", "text": text}],
+            "isError": is_error
+        }))
+    }
+}
+
+impl Responder for McpServer {
+    async fn respond(&mut self, line: &str) -> Option<Value> {
+        let request = match parse_request(line) {
+            Ok(request) => request,
+            Err(error) => return Some(error),
+        };
+        let result = match request.method.as_str() {
+            "initialize" => {
+                let version = request.params["protocolVersion"]
+                    .as_str()
+                    .unwrap_or(PROTOCOL_VERSION);
+                Ok(json!({
+                    "protocolVersion": version,
+                    "capabilities": {"tools": {}},
+                    "serverInfo": {"name": "cfait", "version": env!("CARGO_PKG_VERSION")}
+                }))
+            }
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({"tools": tools()})),
+            "tools/call" => self.call_tool(request.params).await,
+            other => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Unknown method: {}", other),
+            )),
+        };
+        // Notifications (e.g. notifications/initialized) get no answer, not even errors
+        response(request.id, result)
+    }
+}
+
+pub async fn run(_args: &[String]) -> Result<()> {
+    let mut mcp = McpServer::new(server::connect().await?);
+    server::serve(&mut mcp, tokio::io::stdin(), tokio::io::stdout()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::RustyClient;
+    use crate::config::Config;
+
+    #[tokio::test]
+    async fn test_handshake_and_tool_list() {
+        let client = RustyClient::new("", "", "", false).unwrap();
+        let mut mcp = McpServer::new(Server::new(client, vec![], Config::default()));
+
+        let init = r#"{"jsonrpc":"2.0","id":1,"method":"initialize",
+            "params":{"protocolVersion":"2024-11-05","capabilities":{}}}"#;
+        let reply = mcp.respond(init).await.unwrap();
+        assert_eq!(reply["result"]["protocolVersion"], "2024-11-05");
+        assert!(reply["result"]["capabilities"]["tools"].is_object());
+
+        let initialized = r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+        assert!(mcp.respond(initialized).await.is_none());
+
+        let list = r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#;
+        let reply = mcp.respond(list).await.unwrap();
+        let tools = reply["result"]["tools"].as_array().unwrap();
+        assert!(tools.iter().any(|t| t["name"] == "add_task"));
+        assert!(tools.iter().all(|t| t["inputSchema"]["type"] == "object"));
+
+        let call = r#"{"jsonrpc":"2.0","id":3,"method":"tools/call",
+            "params":{"name":"add_task","arguments":{}}}"#;
+        let reply = mcp.respond(call).await.unwrap();
+        assert_eq!(reply["result"]["isError"], true);
+
+        let unknown = r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"x"}}"#;
+        let reply = mcp.respond(unknown).await.unwrap();
+        assert_eq!(reply["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_tasks_are_briefed() {
+        let task = Task::new("Call Bob !1 #work", &Default::default());
+        let text = tool_text(&serde_json::to_value(vec![task]).unwrap());
+        assert!(text.contains("\"text\": \"Call Bob"));
+        assert!(!text.contains("etag"));
+    }
+}
@@ -2,10 +2,17 @@
 use crate::cache::Cache;
 use crate::client::RustyClient;
 use crate::config::Config;
+use crate::i18n::tr_args;
+use crate::journal::Journal;
 use crate::model::Task;
+use crate::model::due::{self, DueDisplay};
+use crate::model::inbox::inbox_href;
+use crate::model::ingest::{IngestOp, parse_itip, plan_ingest};
+use crate::model::priority;
+use crate::model::share::{DeepLink, ShareFormat};
 use crate::paths::AppPaths;
 use crate::storage::{LOCAL_CALENDAR_HREF, LOCAL_CALENDAR_NAME, LocalStorage};
-use crate::store::{FilterOptions, TaskStore, UNCATEGORIZED_ID};
+use crate::store::{FilterOptions, NotStarted, StoreEvent, TaskStore, UNCATEGORIZED_ID};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -57,7 +64,14 @@ pub struct MobileTask {
     pub description: String,
     pub is_done: bool,
     pub priority: u8,
+    /// Name of the priority band ("High", …, "None") and its configured color
+    pub priority_label: String,
+    pub priority_color: Option<String>,
     pub due_date_iso: Option<String>,
+    /// "tomorrow", "2 w overdue" or the full date, per `due_display`
+    pub due_label: Option<String>,
+    /// The full date in the language's format, to show on a long press
+    pub due_full: Option<String>,
     pub start_date_iso: Option<String>,
     pub duration_mins: Option<u32>,
     pub calendar_href: String,
@@ -70,6 +84,7 @@ pub struct MobileTask {
     pub status_string: String,
     pub blocked_by_names: Vec<String>,
     pub blocked_by_uids: Vec<String>,
+    pub hidden_subtasks: u32,
 }
 
 #[derive(uniffi::Record)]
@@ -80,6 +95,8 @@ pub struct MobileCalendar {
     pub is_visible: bool,
     pub is_local: bool,
     pub is_disabled: bool,
+    pub is_shared: bool,
+    pub is_read_only: bool,
 }
 
 #[derive(uniffi::Record)]
@@ -89,6 +106,67 @@ pub struct MobileTag {
     pub is_uncategorized: bool,
 }
 
+#[derive(uniffi::Record)]
+pub struct MobileSyncStatus {
+    pub pending_actions: u32,
+    pub last_sync_iso: Option<String>,
+    /// Nothing waits for the server: safe to go offline
+    pub is_synced: bool,
+    pub label: String,
+}
+
+#[derive(uniffi::Enum)]
+pub enum MobileShareFormat {
+    Text,
+    Ics,
+    Link,
+}
+
+#[derive(uniffi::Enum)]
+pub enum MobileDeepLink {
+    OpenTask {
+        uid: String,
+    },
+    /// Pre-fills the quick-add field; nothing is saved until the user confirms
+    AddTask {
+        text: String,
+    },
+}
+
+/// A change to the tasks, see `StoreObserver`
+#[derive(uniffi::Enum)]
+pub enum MobileStoreEvent {
+    TaskAdded { uid: String },
+    TaskUpdated { uid: String },
+    TaskRemoved { uid: String },
+    CalendarLoaded { href: String },
+}
+
+impl From<StoreEvent> for MobileStoreEvent {
+    fn from(event: StoreEvent) -> Self {
+        match event {
+            StoreEvent::TaskAdded(uid) => Self::TaskAdded { uid },
+            StoreEvent::TaskUpdated(uid) => Self::TaskUpdated { uid },
+            StoreEvent::TaskRemoved(uid) => Self::TaskRemoved { uid },
+            StoreEvent::CalendarLoaded(href) => Self::CalendarLoaded { href },
+        }
+    }
+}
+
+/// Implemented by the app to reload what it shows when the tasks change.
+#[uniffi::export(with_foreign)]
+pub trait StoreObserver: Send + Sync {
+    fn on_store_changed(&self, event: MobileStoreEvent);
+}
+
+#[derive(uniffi::Record)]
+pub struct MobileShare {
+    pub content: String,
+    /// File name to attach the content under (for the ICS format)
+    pub file_name: String,
+    pub mime_type: String,
+}
+
 #[derive(uniffi::Record)]
 pub struct MobileConfig {
     pub url: String,
@@ -100,7 +178,7 @@ pub struct MobileConfig {
     pub disabled_calendars: Vec<String>,
 }
 
-fn task_to_mobile(t: &Task, store: &TaskStore) -> MobileTask {
+fn task_to_mobile(t: &Task, store: &TaskStore, due_display: DueDisplay) -> MobileTask {
     let smart = t.to_smart_string();
     let status_str = format!("{:?}", t.status);
     let is_blocked = store.is_blocked(t);
@@ -115,10 +193,14 @@ fn task_to_mobile(t: &Task, store: &TaskStore) -> MobileTask {
         summary: t.summary.clone(),
         description: t.description.clone(),
         is_done: t.status.is_done(),
-        priority: t.priority,
+        priority: t.priority.value(),
+        priority_label: priority::label(t.priority),
+        priority_color: priority::band_color(t.priority),
         due_date_iso: t.due.map(|d| d.to_rfc3339()),
+        due_label: t.due.map(|d| due::label(d, due_display)),
+        due_full: t.due.map(due::full),
         start_date_iso: t.dtstart.map(|d| d.to_rfc3339()),
-        duration_mins: t.estimated_duration,
+        duration_mins: t.estimated_duration.map(|d| d.minutes()),
         calendar_href: t.calendar_href.clone(),
         categories: t.categories.clone(),
         is_recurring: t.rrule.is_some(),
@@ -129,7 +211,22 @@ fn task_to_mobile(t: &Task, store: &TaskStore) -> MobileTask {
         status_string: status_str,
         blocked_by_names,
         blocked_by_uids: t.dependencies.clone(),
+        hidden_subtasks: t.hidden_subtasks as u32,
+    }
+}
+
+fn ensure_writable(calendar_href: &str) -> Result<(), MobileError> {
+    let cals = crate::cache::Cache::load_calendars().unwrap_or_default();
+    if let Some(cal) = cals
+        .iter()
+        .find(|c| c.href == calendar_href && c.is_read_only)
+    {
+        return Err(MobileError::from(tr_args(
+            "error.read_only",
+            &[("name", &cal.name)],
+        )));
     }
+    Ok(())
 }
 
 // --- MAIN OBJECT ---
@@ -161,6 +258,27 @@ impl CfaitMobile {
         }
     }
 
+    /// `observer` hears of every change to the tasks from now on, on a thread of its own.
+    /// Observers add up: set it once.
+    pub fn set_store_observer(&self, observer: Arc<dyn StoreObserver>) {
+        let events = self.store.blocking_lock().subscribe();
+        std::thread::spawn(move || {
+            for event in events {
+                observer.on_store_changed(event.into());
+            }
+        });
+    }
+
+    pub fn get_sync_status(&self) -> MobileSyncStatus {
+        let status = Journal::status();
+        MobileSyncStatus {
+            pending_actions: status.pending as u32,
+            last_sync_iso: status.last_sync.map(|d| d.to_rfc3339()),
+            is_synced: status.is_synced(),
+            label: status.label(),
+        }
+    }
+
     pub fn get_config(&self) -> MobileConfig {
         let c = Config::load().unwrap_or_default();
         MobileConfig {
@@ -284,6 +402,17 @@ impl CfaitMobile {
         }
         config.save().map_err(MobileError::from)
     }
+
+    /// Turns syncing a calendar on or off; takes effect on the next sync.
+    pub fn set_calendar_sync(&self, href: String, enabled: bool) -> Result<(), MobileError> {
+        let mut config = Config::load().map_err(MobileError::from)?;
+        if enabled {
+            config.disabled_calendars.retain(|h| h != &href);
+        } else if !config.disabled_calendars.contains(&href) {
+            config.disabled_calendars.push(href);
+        }
+        config.save().map_err(MobileError::from)
+    }
     pub fn load_from_cache(&self) {
         let mut store = self.store.blocking_lock();
         store.clear();
@@ -295,9 +424,8 @@ impl CfaitMobile {
                 if cal.href == LOCAL_CALENDAR_HREF {
                     continue;
                 }
-                if let Ok((tasks, _)) = Cache::load(&cal.href) {
-                    store.insert(cal.href, tasks);
-                }
+                let tasks = RustyClient::local_tasks(&cal.href);
+                store.insert(cal.href, tasks);
             }
         }
     }
@@ -305,6 +433,26 @@ impl CfaitMobile {
         let config = Config::load().map_err(MobileError::from)?;
         self.apply_connection(config).await
     }
+    /// Downloads one calendar (or all of them, without `calendar_href`) again from scratch.
+    /// Local tasks and pending changes are kept.
+    pub async fn force_full_sync(
+        &self,
+        calendar_href: Option<String>,
+    ) -> Result<String, MobileError> {
+        {
+            let guard = self.client.lock().await;
+            let Some(client) = guard.as_ref().filter(|c| c.client.is_some()) else {
+                return Err(MobileError::from("Offline: the local copy is kept."));
+            };
+            match calendar_href {
+                Some(href) => client.invalidate_calendar(&href)?,
+                None => {
+                    client.invalidate_all()?;
+                }
+            }
+        }
+        self.sync().await
+    }
     pub async fn connect(
         &self,
         url: String,
@@ -336,6 +484,8 @@ impl CfaitMobile {
             is_visible: !config.hidden_calendars.contains(&local_href),
             is_local: true,
             is_disabled: false,
+            is_shared: false,
+            is_read_only: false,
         });
         if let Ok(cals) = crate::cache::Cache::load_calendars() {
             for c in cals {
@@ -349,6 +499,8 @@ impl CfaitMobile {
                     is_visible: !config.hidden_calendars.contains(&c.href),
                     is_local: false,
                     is_disabled: disabled_set.contains(&c.href),
+                    is_shared: c.is_shared,
+                    is_read_only: c.is_read_only,
                 });
             }
         }
@@ -395,21 +547,45 @@ impl CfaitMobile {
         } else {
             None
         };
+        let completed_cutoff = config
+            .completed_retention_days
+            .map(|days| chrono::Utc::now() - chrono::Duration::days(days as i64));
+        let names: HashMap<String, String> = Cache::load_calendars()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|c| (c.href, c.name))
+            .collect();
         let filtered = store.filter(FilterOptions {
             active_cal_href: None,
             hidden_calendars: &hidden,
             selected_categories: &selected_categories,
             match_all_categories: false,
             search_term: &search_query,
+            calendar_names: &names,
             hide_completed_global: config.hide_completed,
             cutoff_date,
             min_duration: None,
             max_duration: None,
             include_unset_duration: true,
+            completed_cutoff,
+            fold_subtasks: search_query.is_empty(),
+            quick_filters: &[],
+            hide_not_started: config.not_started == NotStarted::Hide,
         });
         filtered
             .into_iter()
-            .map(|t| task_to_mobile(&t, &store))
+            .map(|t| task_to_mobile(&t, &store, config.due_display))
+            .collect()
+    }
+
+    /// The tasks that finishing `uid` unblocked, for the app to announce after a toggle.
+    pub async fn get_unblocked_by(&self, uid: String) -> Vec<MobileTask> {
+        let store = self.store.lock().await;
+        let due_display = Config::load().unwrap_or_default().due_display;
+        store
+            .unblocked_by(&uid)
+            .iter()
+            .map(|t| task_to_mobile(t, &store, due_display))
             .collect()
     }
 
@@ -424,11 +600,20 @@ impl CfaitMobile {
         let mut task = Task::new(&input, &aliases);
         let guard = self.client.lock().await;
         let config = Config::load().unwrap_or_default();
-        let target_href = config
-            .default_calendar
-            .clone()
+        // Quick-added tasks wait in the inbox, when there is one
+        let calendars = Cache::load_calendars().unwrap_or_default();
+        let target_href = inbox_href(config.inbox_calendar.as_deref(), &calendars)
+            .or(config.default_calendar.clone())
             .unwrap_or(LOCAL_CALENDAR_HREF.to_string());
+        if let Some(defaults) = config.calendar_defaults.get(&target_href) {
+            defaults.apply(&mut task);
+        }
         task.calendar_href = target_href.clone();
+        ensure_writable(&task.calendar_href)?;
+        self.store
+            .lock()
+            .await
+            .place_new_task(&mut task, config.new_task_placement, None);
         if let Some(client) = &*guard {
             client
                 .create_task(&mut task)
@@ -436,37 +621,112 @@ impl CfaitMobile {
                 .map(|_| ())
                 .map_err(MobileError::from)?;
         } else {
-            let mut all = LocalStorage::load().unwrap_or_default();
-            all.push(task.clone());
-            LocalStorage::save(&all).map_err(MobileError::from)?;
+            LocalStorage::modify(|all| all.push(task.clone())).map_err(MobileError::from)?;
         }
         self.store.lock().await.add_task(task);
         Ok(())
     }
 
+    pub fn get_template_names(&self) -> Vec<String> {
+        Config::load()
+            .unwrap_or_default()
+            .templates
+            .into_iter()
+            .map(|t| t.name)
+            .collect()
+    }
+
+    pub async fn instantiate_template(
+        &self,
+        name: String,
+        title: String,
+    ) -> Result<(), MobileError> {
+        let config = Config::load().unwrap_or_default();
+        let target_href = config
+            .default_calendar
+            .clone()
+            .unwrap_or(LOCAL_CALENDAR_HREF.to_string());
+        ensure_writable(&target_href)?;
+        let template = crate::model::template::find_template(&config.templates, &name)
+            .ok_or(MobileError::from(format!("No template named '{}'", name)))?;
+        let mut tasks = template.instantiate(&title, &target_href, &config.tag_aliases);
+        for task in &mut tasks {
+            if let Some(defaults) = config.calendar_defaults.get(&target_href) {
+                defaults.apply(task);
+            }
+        }
+
+        let guard = self.client.lock().await;
+        if let Some(client) = &*guard {
+            for task in &mut tasks {
+                client.create_task(task).await.map_err(MobileError::from)?;
+            }
+        } else {
+            LocalStorage::modify(|all| all.extend(tasks.iter().cloned()))
+                .map_err(MobileError::from)?;
+        }
+        let mut store = self.store.lock().await;
+        for task in tasks {
+            store.add_task(task);
+        }
+        Ok(())
+    }
+
+    /// Imports a shared .ics file or invitation (METHOD:REQUEST/CANCEL) into the default
+    /// calendar. Returns how many tasks were created or updated.
+    pub async fn ingest_ics(&self, raw: String) -> Result<u32, MobileError> {
+        let config = Config::load().unwrap_or_default();
+        let target_href = config
+            .default_calendar
+            .clone()
+            .unwrap_or(LOCAL_CALENDAR_HREF.to_string());
+        let (method, incoming) = parse_itip(&raw, &target_href).map_err(MobileError::from)?;
+        let existing: Vec<Task> = self
+            .store
+            .lock()
+            .await
+            .calendars
+            .values()
+            .flatten()
+            .cloned()
+            .collect();
+
+        let guard = self.client.lock().await;
+        let mut count = 0;
+        for op in plan_ingest(method, incoming, &existing) {
+            let (mut task, is_new) = match op {
+                IngestOp::Create(task) => (task, true),
+                IngestOp::Update(task) => (task, false),
+            };
+            ensure_writable(&task.calendar_href)?;
+            if let Some(client) = &*guard {
+                let res = if is_new {
+                    client.create_task(&mut task).await
+                } else {
+                    client.update_task(&mut task).await
+                };
+                res.map_err(MobileError::from)?;
+            } else {
+                LocalStorage::modify(|all| {
+                    all.retain(|t| t.uid != task.uid);
+                    all.push(task.clone());
+                })
+                .map_err(MobileError::from)?;
+            }
+            self.store.lock().await.update_or_add_task(task);
+            count += 1;
+        }
+        Ok(count)
+    }
+
     pub async fn change_priority(&self, uid: String, delta: i8) -> Result<(), MobileError> {
         self.modify_task_and_sync(uid, |t| {
-            t.priority = if delta > 0 {
-                match t.priority {
-                    0 => 9,
-                    9 => 5,
-                    5 => 1,
-                    1 => 1,
-                    _ => 5,
-                }
-            } else {
-                match t.priority {
-                    1 => 5,
-                    5 => 9,
-                    9 => 0,
-                    0 => 0,
-                    _ => 0,
-                }
-            };
+            t.priority = t.priority.cycled(delta > 0);
         })
         .await
     }
     pub async fn set_status_process(&self, uid: String) -> Result<(), MobileError> {
+        self.ensure_not_note(&uid).await?;
         self.modify_task_and_sync(uid, |t| {
             t.status = if t.status == crate::model::TaskStatus::InProcess {
                 crate::model::TaskStatus::NeedsAction
@@ -477,6 +737,7 @@ impl CfaitMobile {
         .await
     }
     pub async fn set_status_cancelled(&self, uid: String) -> Result<(), MobileError> {
+        self.ensure_not_note(&uid).await?;
         self.modify_task_and_sync(uid, |t| {
             t.status = if t.status == crate::model::TaskStatus::Cancelled {
                 crate::model::TaskStatus::NeedsAction
@@ -508,6 +769,7 @@ impl CfaitMobile {
         .await
     }
     pub async fn toggle_task(&self, uid: String) -> Result<(), MobileError> {
+        self.ensure_not_note(&uid).await?;
         self.modify_task_and_sync(uid, |t| {
             if t.status.is_done() {
                 t.status = crate::model::TaskStatus::NeedsAction;
@@ -517,24 +779,145 @@ impl CfaitMobile {
         })
         .await
     }
-    pub async fn move_task(&self, uid: String, new_cal_href: String) -> Result<(), MobileError> {
+    pub async fn toggle_subtasks_hidden(&self, uid: String) -> Result<(), MobileError> {
+        self.modify_task_and_sync(uid, |t| {
+            t.hide_subtasks = !t.hide_subtasks;
+        })
+        .await
+    }
+    pub async fn skip_occurrence(&self, uid: String) -> Result<(), MobileError> {
+        self.modify_task_and_sync(uid, |t| {
+            t.skip_occurrence();
+        })
+        .await
+    }
+    pub async fn reschedule_occurrence(
+        &self,
+        uid: String,
+        new_date: String,
+    ) -> Result<(), MobileError> {
+        let new_due = crate::model::parser::parse_smart_date(new_date.trim(), true)
+            .ok_or(MobileError::from("Invalid date"))?;
         let mut store = self.store.lock().await;
-        let updated_task = store
-            .move_task(&uid, new_cal_href.clone())
-            .ok_or(MobileError::from("Task not found"))?;
+        if let Some(href) = store.index.get(&uid) {
+            ensure_writable(href)?;
+        }
+        let (updated, mut next) = store
+            .reschedule_occurrence(&uid, new_due)
+            .ok_or(MobileError::from("Task is not recurring"))?;
+        drop(store);
         let client_guard = self.client.lock().await;
         if let Some(client) = &*client_guard {
+            let mut saved = updated.clone();
             client
-                .move_task(&updated_task, &new_cal_href)
+                .update_task(&mut saved)
                 .await
                 .map_err(MobileError::from)?;
+            self.store.lock().await.stamp(&saved);
+            client
+                .create_task(&mut next)
+                .await
+                .map_err(MobileError::from)?;
+        } else if updated.calendar_href == LOCAL_CALENDAR_HREF {
+            LocalStorage::modify(|local| {
+                if let Some(idx) = local.iter().position(|t| t.uid == uid) {
+                    local[idx] = updated;
+                }
+                local.push(next);
+            })
+            .map_err(MobileError::from)?;
+        }
+        Ok(())
+    }
+    pub async fn move_task(&self, uid: String, new_cal_href: String) -> Result<(), MobileError> {
+        let mut store = self.store.lock().await;
+        if let Some(href) = store.index.get(&uid) {
+            ensure_writable(href)?;
+        }
+        ensure_writable(&new_cal_href)?;
+        let moved: Vec<Task> = if Config::load().unwrap_or_default().move_subtree {
+            store.move_subtree(&uid, &new_cal_href)
+        } else {
+            store
+                .move_task(&uid, new_cal_href.clone())
+                .into_iter()
+                .collect()
+        };
+        if moved.is_empty() {
+            return Err(MobileError::from("Task not found"));
+        }
+        drop(store);
+        let client_guard = self.client.lock().await;
+        if let Some(client) = &*client_guard {
+            for task in &moved {
+                client
+                    .move_task(task, &new_cal_href)
+                    .await
+                    .map_err(MobileError::from)?;
+            }
         } else {
             return Err(MobileError::from("Client offline"));
         }
         Ok(())
     }
+    pub async fn duplicate_task(
+        &self,
+        uid: String,
+        with_subtree: bool,
+        target_cal_href: String,
+    ) -> Result<(), MobileError> {
+        ensure_writable(&target_cal_href)?;
+        let mut copies =
+            self.store
+                .lock()
+                .await
+                .duplicate_task(&uid, with_subtree, &target_cal_href);
+        if copies.is_empty() {
+            return Err(MobileError::from("Task not found"));
+        }
+        let client_guard = self.client.lock().await;
+        if let Some(client) = &*client_guard {
+            for copy in &mut copies {
+                client.create_task(copy).await.map_err(MobileError::from)?;
+            }
+        } else {
+            return Err(MobileError::from("Client offline"));
+        }
+        Ok(())
+    }
+    /// Decodes a `rustache://` URI received through an intent.
+    pub fn parse_deep_link(&self, uri: String) -> Option<MobileDeepLink> {
+        match DeepLink::parse(&uri)? {
+            DeepLink::OpenTask(uid) => Some(MobileDeepLink::OpenTask { uid }),
+            DeepLink::Add(text) => Some(MobileDeepLink::AddTask { text }),
+        }
+    }
+    /// Content for the Android share sheet.
+    pub async fn share_task(
+        &self,
+        uid: String,
+        format: MobileShareFormat,
+    ) -> Result<MobileShare, MobileError> {
+        let store = self.store.lock().await;
+        let task = store
+            .get_task(&uid)
+            .ok_or(MobileError::from("Task not found"))?;
+        let (format, mime_type) = match format {
+            MobileShareFormat::Text => (ShareFormat::Text, "text/plain"),
+            MobileShareFormat::Ics => (ShareFormat::Ics, "text/calendar"),
+            MobileShareFormat::Link => (ShareFormat::Link, "text/plain"),
+        };
+        Ok(MobileShare {
+            content: task.share(format),
+            file_name: task.share_file_name(),
+            mime_type: mime_type.to_string(),
+        })
+    }
     pub async fn delete_task(&self, uid: String) -> Result<(), MobileError> {
         let mut store = self.store.lock().await;
+        if let Some(href) = store.index.get(&uid) {
+            ensure_writable(href)?;
+        }
         let task = store
             .delete_task(&uid)
             .ok_or(MobileError::from("Task not found"))?;
@@ -542,11 +925,8 @@ impl CfaitMobile {
         if let Some(client) = &*client_guard {
             client.delete_task(&task).await.map_err(MobileError::from)?;
         } else if task.calendar_href == LOCAL_CALENDAR_HREF {
-            let mut local = LocalStorage::load().unwrap_or_default();
-            if let Some(pos) = local.iter().position(|t| t.uid == uid) {
-                local.remove(pos);
-                LocalStorage::save(&local).map_err(MobileError::from)?;
-            }
+            LocalStorage::modify(|local| local.retain(|t| t.uid != uid))
+                .map_err(MobileError::from)?;
         }
         Ok(())
     }
@@ -569,17 +949,23 @@ impl CfaitMobile {
         }
 
         match client.get_all_tasks(&cals).await {
-            Ok(results) => {
-                for (href, tasks) in results {
+            Ok(report) => {
+                let failures = report.failure_lines(&cals);
+                for (href, tasks) in report.tasks {
                     store.insert(href, tasks);
                 }
+                if !failures.is_empty() {
+                    let failed = format!("Could not fetch {}", failures.join("; "));
+                    return Ok(match warning {
+                        Some(w) => format!("{}. {}", w, failed),
+                        None => failed,
+                    });
+                }
             }
             Err(e) => {
                 for cal in &cals {
                     if cal.href != LOCAL_CALENDAR_HREF && !store.calendars.contains_key(&cal.href) {
-                        if let Ok((cached, _)) = crate::cache::Cache::load(&cal.href) {
-                            store.insert(cal.href.clone(), cached);
-                        }
+                        store.insert(cal.href.clone(), RustyClient::local_tasks(&cal.href));
                     }
                 }
                 if warning.is_none() {
@@ -590,11 +976,22 @@ impl CfaitMobile {
         Ok(warning.unwrap_or_else(|| "Connected".to_string()))
     }
 
+    /// Notes (VJOURNAL) have no status to change.
+    async fn ensure_not_note(&self, uid: &str) -> Result<(), MobileError> {
+        match self.store.lock().await.get_task(uid) {
+            Some(task) if task.is_note() => Err(MobileError::from("Notes are never done")),
+            _ => Ok(()),
+        }
+    }
+
     async fn modify_task_and_sync<F>(&self, uid: String, mut modifier: F) -> Result<(), MobileError>
     where
         F: FnMut(&mut Task),
     {
         let mut store = self.store.lock().await;
+        if let Some(href) = store.index.get(&uid) {
+            ensure_writable(href)?;
+        }
         let (task, _) = store
             .get_task_mut(&uid)
             .ok_or(MobileError::from("Task not found"))?;
@@ -603,16 +1000,19 @@ impl CfaitMobile {
         drop(store);
         let client_guard = self.client.lock().await;
         if let Some(client) = &*client_guard {
+            let mut saved = task_copy.clone();
             client
-                .update_task(&mut task_copy.clone())
+                .update_task(&mut saved)
                 .await
                 .map_err(MobileError::from)?;
+            self.store.lock().await.stamp(&saved);
         } else if task_copy.calendar_href == LOCAL_CALENDAR_HREF {
-            let mut local = LocalStorage::load().unwrap_or_default();
-            if let Some(idx) = local.iter().position(|t| t.uid == uid) {
-                local[idx] = task_copy;
-                LocalStorage::save(&local).map_err(MobileError::from)?;
-            }
+            LocalStorage::modify(|local| {
+                if let Some(idx) = local.iter().position(|t| t.uid == uid) {
+                    local[idx] = task_copy;
+                }
+            })
+            .map_err(MobileError::from)?;
         }
         Ok(())
     }
@@ -1,6 +1,8 @@
 // File: src/model/adapter.rs
-use crate::model::item::{RawProperty, Task, TaskStatus};
-use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use crate::model::duration::{self, Duration};
+use crate::model::item::{ItemKind, RawProperty, Reminder, Task, TaskStatus};
+use crate::model::priority::Priority;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
 use icalendar::{Calendar, CalendarComponent, Component, Todo, TodoStatus};
 use rrule::RRuleSet;
 use std::str::FromStr;
@@ -15,6 +17,10 @@ const HANDLED_KEYS: &[&str] = &[
     "DUE",
     "DTSTART",
     "RRULE",
+    "X-CFAIT-RECUR-FROM",
+    "EXDATE",
+    "X-OC-HIDESUBTASKS",
+    "X-APPLE-SORT-ORDER",
     "DURATION",
     "X-ESTIMATED-DURATION",
     "CATEGORIES",
@@ -22,33 +28,59 @@ const HANDLED_KEYS: &[&str] = &[
     "DTSTAMP",
     "CREATED",
     "LAST-MODIFIED",
+    "COMPLETED",
     "SEQUENCE",
     "PRODID",
     "VERSION",
     "CALSCALE",
 ];
 
+/// RFC 5545 3.1: lines SHOULD NOT be longer than 75 octets, excluding the line break.
+pub const FOLD_OCTETS: usize = 75;
+
 impl Task {
     pub fn respawn(&self) -> Option<Task> {
+        self.next_occurrence(self.recur_from_completion)
+    }
+
+    /// The following occurrence, counted from today (completion-based recurrence) or from
+    /// the current start/due date.
+    fn next_occurrence(&self, from_today: bool) -> Option<Task> {
         let rule_str = self.rrule.as_ref()?;
         let seed_date = self.dtstart.or(self.due)?;
 
-        let dtstart_str = seed_date.format("%Y%m%dT%H%M%SZ").to_string();
+        // Completion-based recurrence: restart the rule from today, keeping the time of day
+        let anchor = if from_today {
+            Utc::now().date_naive().and_time(seed_date.time()).and_utc()
+        } else {
+            seed_date
+        };
+
+        let dtstart_str = anchor.format("%Y%m%dT%H%M%SZ").to_string();
         let rrule_string = format!("DTSTART:{}\nRRULE:{}", dtstart_str, rule_str);
 
         if let Ok(rrule_set) = RRuleSet::from_str(&rrule_string) {
-            let result = rrule_set.all(2);
-            let dates = result.dates;
-            if dates.len() > 1 {
-                let next_occurrence = dates[1];
-                let next_start = Utc.from_utc_datetime(&next_occurrence.naive_utc());
-
+            // The first date is the anchor itself; excluded occurrences are passed over
+            let limit = u16::try_from(self.exdates.len() + 2).unwrap_or(u16::MAX);
+            let next_start = rrule_set
+                .all(limit)
+                .dates
+                .iter()
+                .skip(1)
+                .map(|d| Utc.from_utc_datetime(&d.naive_utc()))
+                .find(|d| !self.is_excluded(*d));
+            if let Some(next_start) = next_start {
                 let mut next_task = self.clone();
                 next_task.uid = Uuid::new_v4().to_string();
                 next_task.href = String::new();
                 next_task.etag = String::new();
                 next_task.status = TaskStatus::NeedsAction;
                 next_task.dependencies.clear();
+                // Exceptions and sibling VTODOs belong to the old resource
+                next_task.raw_components.clear();
+                next_task.created = None;
+                next_task.last_modified = None;
+                next_task.completed = None;
 
                 if self.dtstart.is_some() {
                     next_task.dtstart = Some(next_start);
@@ -59,35 +91,89 @@ impl Task {
                     next_task.due = Some(next_start + duration);
                 }
 
+                // Fixed-time reminders move along with the occurrence
+                for reminder in &mut next_task.reminders {
+                    if let Reminder::At(t) = reminder {
+                        *t += next_start - seed_date;
+                    }
+                }
+
                 return Some(next_task);
             }
         }
         None
     }
 
+    /// Whether the occurrence at `date` is left out of the series by an EXDATE.
+    pub fn is_excluded(&self, date: DateTime<Utc>) -> bool {
+        self.exdates.iter().any(|x| {
+            *x == date || (x.time() == NaiveTime::MIN && x.date_naive() == date.date_naive())
+        })
+    }
+
+    /// Advances a recurring task to its following occurrence without completing it. The
+    /// skipped occurrence is recorded as an EXDATE, so other clients leave it out too.
+    /// Nothing is completed, so the next date follows the current one even when the task
+    /// recurs from completion.
+    pub fn skip_occurrence(&mut self) -> bool {
+        let Some(next) = self.next_occurrence(false) else {
+            return false;
+        };
+        if let Some(skipped) = self.dtstart.or(self.due)
+            && !self.exdates.contains(&skipped)
+        {
+            self.exdates.push(skipped);
+        }
+        self.due = next.due;
+        self.dtstart = next.dtstart;
+        true
+    }
+
+    /// Moves only the current occurrence to `new_due`. The task is detached from the series
+    /// and the returned task carries the recurrence on from the next scheduled date.
+    pub fn reschedule_occurrence(&mut self, new_due: DateTime<Utc>) -> Option<Task> {
+        let next = self.respawn()?;
+
+        if let (Some(start), Some(old_due)) = (self.dtstart, self.due) {
+            self.dtstart = Some(start + (new_due - old_due));
+        }
+        self.due = Some(new_due);
+        self.rrule = None;
+        self.recur_from_completion = false;
+        self.exdates.clear();
+
+        Some(next)
+    }
+
     pub fn to_ics(&self) -> String {
+        self.to_ics_folded(FOLD_OCTETS)
+    }
+
+    /// Serializes with hand-written lines folded at `fold_octets` (0 disables folding).
+    /// Notes are written as a VJOURNAL, without the properties only a VTODO may have.
+    pub fn to_ics_folded(&self, fold_octets: usize) -> String {
+        let is_note = self.is_note();
         let mut todo = Todo::new();
         todo.uid(&self.uid);
-        todo.summary(&self.summary);
-        if !self.description.is_empty() {
-            todo.description(&self.description);
-        }
         todo.timestamp(Utc::now());
 
-        match self.status {
-            TaskStatus::NeedsAction => todo.status(TodoStatus::NeedsAction),
-            TaskStatus::InProcess => todo.status(TodoStatus::InProcess),
-            TaskStatus::Completed => todo.status(TodoStatus::Completed),
-            TaskStatus::Cancelled => todo.status(TodoStatus::Cancelled),
-        };
+        if !is_note {
+            match self.status {
+                TaskStatus::NeedsAction => todo.status(TodoStatus::NeedsAction),
+                TaskStatus::InProcess => todo.status(TodoStatus::InProcess),
+                TaskStatus::Completed => todo.status(TodoStatus::Completed),
+                TaskStatus::Cancelled => todo.status(TodoStatus::Cancelled),
+            };
+        }
 
-        fn format_iso_duration(mins: u32) -> String {
-            if mins.is_multiple_of(24 * 60) {
-                format!("P{}D", mins / (24 * 60))
-            } else if mins.is_multiple_of(60) {
-                format!("PT{}H", mins / 60)
-            } else {
-                format!("PT{}M", mins)
+        for (key, value) in [
+            ("CREATED", self.created),
+            ("LAST-MODIFIED", self.last_modified),
+            ("COMPLETED", self.completed.filter(|_| !is_note)),
+        ] {
+            if let Some(dt) = value {
+                let formatted = dt.format("%Y%m%dT%H%M%SZ").to_string();
+                todo.add_property(key, &formatted);
             }
         }
 
@@ -96,22 +182,39 @@ impl Task {
             todo.add_property("DTSTART", &formatted);
         }
 
-        if let Some(dt) = self.due {
+        if let Some(dt) = self.due.filter(|_| !is_note) {
             let formatted = dt.format("%Y%m%dT%H%M%SZ").to_string();
             todo.add_property("DUE", &formatted);
-            if let Some(mins) = self.estimated_duration {
-                let val = format_iso_duration(mins);
-                todo.add_property("X-ESTIMATED-DURATION", &val);
+            if let Some(d) = self.estimated_duration {
+                todo.add_property("X-ESTIMATED-DURATION", &d.to_iso8601());
             }
-        } else if let Some(mins) = self.estimated_duration {
-            let val = format_iso_duration(mins);
-            todo.add_property("DURATION", &val);
+        } else if let Some(d) = self.estimated_duration.filter(|_| !is_note) {
+            todo.add_property("DURATION", &d.to_iso8601());
         }
-        if self.priority > 0 {
-            todo.priority(self.priority.into());
+        if self.priority.is_set() && !is_note {
+            todo.priority(self.priority.value().into());
         }
         if let Some(rrule) = &self.rrule {
             todo.add_property("RRULE", rrule.as_str());
+            if self.recur_from_completion {
+                todo.add_property("X-CFAIT-RECUR-FROM", "COMPLETION");
+            }
+            if !self.exdates.is_empty() {
+                let dates: Vec<String> = self
+                    .exdates
+                    .iter()
+                    .map(|d| d.format("%Y%m%dT%H%M%SZ").to_string())
+                    .collect();
+                todo.add_property("EXDATE", &dates.join(","));
+            }
+        }
+
+        // --- NEXTCLOUD TASKS UI STATE ---
+        if self.hide_subtasks {
+            todo.add_property("X-OC-HIDESUBTASKS", "1");
+        }
+        if let Some(order) = self.sort_order {
+            todo.add_property("X-APPLE-SORT-ORDER", &order.to_string());
         }
 
         // --- HIERARCHY & DEPENDENCIES ---
@@ -139,48 +242,157 @@ impl Task {
         calendar.push(todo);
         let mut ics = calendar.to_string();
 
-        // 1. Manual injection of CATEGORIES
+        // 1. SUMMARY and DESCRIPTION, escaped here like every other TEXT value
+        let mut extra = String::new();
+        for (key, value) in [
+            ("SUMMARY", &self.summary),
+            ("DESCRIPTION", &self.description),
+        ] {
+            if key == "SUMMARY" || !value.is_empty() {
+                let line = format!("{}:{}", key, escape_text(value));
+                extra.push_str(&fold_line(&line, fold_octets));
+                extra.push_str("\r\n");
+            }
+        }
+
+        // 2. CATEGORIES, written as a single multi-value line (escaped per value, then folded)
         if !self.categories.is_empty() {
-            let escaped_cats: Vec<String> = self
-                .categories
-                .iter()
-                .map(|c| c.replace(',', "\\,"))
-                .collect();
-            let cat_line = format!("CATEGORIES:{}", escaped_cats.join(","));
-
-            if let Some(idx) = ics.rfind("END:VTODO") {
-                let (start, end) = ics.split_at(idx);
-                let mut buffer = String::with_capacity(ics.len() + cat_line.len() + 2);
-                buffer.push_str(start);
-                buffer.push_str(&cat_line);
-                buffer.push_str("\r\n");
-                buffer.push_str(end);
-                ics = buffer;
+            let escaped_cats: Vec<String> =
+                self.categories.iter().map(|c| escape_text(c)).collect();
+            let cat_line = fold_line(
+                &format!("CATEGORIES:{}", escaped_cats.join(",")),
+                fold_octets,
+            );
+            extra.push_str(&cat_line);
+            extra.push_str("\r\n");
+        }
+
+        // 3. One VALARM per reminder
+        let alarm_text = fold_line(
+            &format!("DESCRIPTION:{}", escape_text(&self.summary)),
+            fold_octets,
+        );
+        for reminder in self.reminders.iter().filter(|_| !is_note) {
+            extra.push_str("BEGIN:VALARM\r\nACTION:DISPLAY\r\n");
+            extra.push_str(&alarm_text);
+            extra.push_str("\r\n");
+            extra.push_str(&format_trigger(reminder));
+            extra.push_str("\r\nEND:VALARM\r\n");
+        }
+
+        if let Some(idx) = ics.rfind("END:VTODO") {
+            ics.insert_str(idx, &extra);
+        }
+        if is_note {
+            ics = ics.replacen("BEGIN:VTODO", "BEGIN:VJOURNAL", 1);
+            ics = ics.replacen("END:VTODO", "END:VJOURNAL", 1);
+        }
+
+        // 4. Inject Raw Components (Exceptions, Timezones, sibling VTODOs, etc.)
+        inject_components(ics, &self.raw_components)
+    }
+
+    /// Whether this task shares its resource with other (non-exception) VTODOs.
+    pub fn has_sibling_todos(&self) -> bool {
+        self.raw_components
+            .iter()
+            .any(|c| is_sibling_todo(c, &self.uid))
+    }
+
+    /// This task alone, without the other VTODOs of its resource (for when it leaves the resource).
+    pub fn without_siblings(&self) -> Task {
+        let mut task = self.clone();
+        task.raw_components
+            .retain(|c| !is_sibling_todo(c, &self.uid));
+        task
+    }
+
+    /// UIDs of the other VTODOs sharing this task's resource.
+    pub fn sibling_uids(&self) -> Vec<String> {
+        self.raw_components
+            .iter()
+            .filter(|c| is_sibling_todo(c, &self.uid))
+            .filter_map(|c| component_uid(c).map(str::to_string))
+            .collect()
+    }
+
+    /// Replaces the copy of the sibling `uid` kept in this task's resource with `current`, or
+    /// drops it when None, so that saving this task doesn't write back an outdated sibling.
+    pub fn refresh_sibling(&mut self, uid: &str, current: Option<&Task>) {
+        let Some(idx) = self
+            .raw_components
+            .iter()
+            .position(|c| is_sibling_todo(c, &self.uid) && component_uid(c) == Some(uid))
+        else {
+            return;
+        };
+        let block = current.and_then(|t| {
+            split_item_blocks(&t.without_siblings().to_ics())
+                .into_iter()
+                .find(|b| !has_recurrence_id(b))
+        });
+        match block {
+            Some(block) => self.raw_components[idx] = block,
+            None => {
+                self.raw_components.remove(idx);
             }
         }
+    }
 
-        // 2. Inject Raw Components (Exceptions, Timezones, etc.)
-        if !self.raw_components.is_empty() {
-            let trimmed = ics.trim_end();
-            if let Some(idx) = trimmed.rfind("END:VCALENDAR") {
-                let (start, end) = trimmed.split_at(idx);
+    /// The resource as it should read once this task is removed from it.
+    /// Returns None when no other VTODO lives there, i.e. the resource can simply be deleted.
+    pub fn siblings_ics(&self) -> Option<String> {
+        if !self.has_sibling_todos() {
+            return None;
+        }
+        let remaining: Vec<String> = self
+            .raw_components
+            .iter()
+            .filter(|c| component_uid(c) != Some(self.uid.as_str()))
+            .cloned()
+            .collect();
+        Some(inject_components(Calendar::new().to_string(), &remaining))
+    }
 
-                let extra_len: usize = self.raw_components.iter().map(|s| s.len() + 2).sum();
-                let mut buffer = String::with_capacity(trimmed.len() + extra_len);
+    /// With an ORGANIZER or ATTENDEE, servers doing the scheduling themselves (RFC 6638) send
+    /// invitations, replies or cancellations when the task is written.
+    pub fn is_scheduling_object(&self) -> bool {
+        self.unmapped_properties
+            .iter()
+            .any(|p| is_scheduling_property(&p.key))
+    }
 
-                buffer.push_str(start);
-                for raw in &self.raw_components {
-                    buffer.push_str(raw);
-                    if !raw.ends_with("\r\n") && !raw.ends_with('\n') {
-                        buffer.push_str("\r\n");
-                    }
+    /// Sets SCHEDULE-AGENT=CLIENT on the ORGANIZER and ATTENDEE (and drops
+    /// SCHEDULE-FORCE-SEND), so that the server sends no iTIP messages for this copy.
+    /// An agent already chosen other than the server is kept. Returns whether anything changed.
+    pub fn disable_server_scheduling(&mut self) -> bool {
+        let mut changed = false;
+        for prop in self
+            .unmapped_properties
+            .iter_mut()
+            .filter(|p| is_scheduling_property(&p.key))
+        {
+            let before = prop.params.len();
+            prop.params
+                .retain(|(k, _)| !k.eq_ignore_ascii_case("SCHEDULE-FORCE-SEND"));
+            changed |= prop.params.len() != before;
+
+            let agent = prop
+                .params
+                .iter_mut()
+                .find(|(k, _)| k.eq_ignore_ascii_case("SCHEDULE-AGENT"));
+            match agent {
+                Some((_, v)) if v.eq_ignore_ascii_case("SERVER") => *v = "CLIENT".to_string(),
+                Some(_) => continue,
+                None => {
+                    prop.params
+                        .push(("SCHEDULE-AGENT".to_string(), "CLIENT".to_string()));
+                    prop.params.sort_unstable();
                 }
-                buffer.push_str(end);
-                ics = buffer;
             }
+            changed = true;
         }
-
-        ics
+        changed
     }
 
     pub fn from_ics(
@@ -189,40 +401,128 @@ impl Task {
         href: String,
         calendar_href: String,
     ) -> Result<Self, String> {
+        Self::from_ics_all(raw_ics, etag, href, calendar_href)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| "No Master VTODO or VJOURNAL found in ICS".to_string())
+    }
+
+    /// Parses every master VTODO (and VJOURNAL, as a note) of a resource into its own Task, all
+    /// sharing the same href. Each task keeps the other components in `raw_components`, so
+    /// writing any of them rewrites the whole resource.
+    pub fn from_ics_all(
+        raw_ics: &str,
+        etag: String,
+        href: String,
+        calendar_href: String,
+    ) -> Result<Vec<Self>, String> {
         let calendar: Calendar = raw_ics.parse().map_err(|e| format!("Parse: {}", e))?;
 
-        let mut master_todo: Option<&Todo> = None;
-        let mut raw_components: Vec<String> = Vec::with_capacity(calendar.components.len());
+        // (index into `masters` if this is a master VTODO or VJOURNAL, serialized component)
+        let mut masters: Vec<&CalendarComponent> = Vec::new();
+        let mut components: Vec<(Option<usize>, String)> =
+            Vec::with_capacity(calendar.components.len());
 
         for component in &calendar.components {
-            match component {
+            let (raw, is_master) = match component {
                 CalendarComponent::Todo(t) => {
-                    let is_exception = t.properties().contains_key("RECURRENCE-ID");
-
-                    if is_exception {
-                        raw_components.push(t.to_string());
-                    } else if master_todo.is_none() {
-                        master_todo = Some(t);
-                    } else {
-                        raw_components.push(t.to_string());
-                    }
+                    (t.to_string(), !t.properties().contains_key("RECURRENCE-ID"))
+                }
+                CalendarComponent::Other(o) if o.component_kind() == "VJOURNAL" => {
+                    (o.to_string(), !o.properties().contains_key("RECURRENCE-ID"))
                 }
-                CalendarComponent::Event(e) => raw_components.push(e.to_string()),
-                CalendarComponent::Venue(v) => raw_components.push(v.to_string()),
-                _ => {}
+                CalendarComponent::Event(e) => (e.to_string(), false),
+                CalendarComponent::Venue(v) => (v.to_string(), false),
+                _ => continue,
+            };
+            if is_master {
+                components.push((Some(masters.len()), raw));
+                masters.push(component);
+            } else {
+                components.push((None, raw));
             }
         }
 
-        let todo = match master_todo {
-            Some(t) => t,
-            None => return Err("No Master VTODO found in ICS".to_string()),
+        if masters.is_empty() {
+            return Err("No Master VTODO or VJOURNAL found in ICS".to_string());
+        }
+
+        let blocks = if masters.len() > 1 {
+            split_item_blocks(raw_ics)
+        } else {
+            Vec::new()
         };
 
-        let summary = todo.get_summary().unwrap_or("No Title").to_string();
-        let description = todo.get_description().unwrap_or("").to_string();
+        let tasks = masters
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, master)| {
+                let raw_components = components
+                    .iter()
+                    .filter(|(master_idx, _)| *master_idx != Some(idx))
+                    .map(|(_, raw)| raw.clone())
+                    .collect();
+
+                // Relations are read from this component's own lines, not the whole resource
+                let own_raw = |uid: Option<&str>| {
+                    blocks
+                        .iter()
+                        .find(|b| component_uid(b) == uid && !has_recurrence_id(b))
+                        .map(String::as_str)
+                        .unwrap_or(raw_ics)
+                };
+                let (etag, href, calendar_href) =
+                    (etag.clone(), href.clone(), calendar_href.clone());
+
+                Some(match master {
+                    CalendarComponent::Todo(t) => Self::from_component(
+                        t,
+                        ItemKind::Todo,
+                        own_raw(t.get_uid()),
+                        raw_components,
+                        etag,
+                        href,
+                        calendar_href,
+                    ),
+                    CalendarComponent::Other(o) => Self::from_component(
+                        o,
+                        ItemKind::Journal,
+                        own_raw(o.get_uid()),
+                        raw_components,
+                        etag,
+                        href,
+                        calendar_href,
+                    ),
+                    _ => return None,
+                })
+            })
+            .collect();
+
+        Ok(tasks)
+    }
+
+    fn from_component<C: Component>(
+        todo: &C,
+        kind: ItemKind,
+        raw_ics: &str,
+        raw_components: Vec<String>,
+        etag: String,
+        href: String,
+        calendar_href: String,
+    ) -> Self {
+        // Read from the raw lines so escaped commas, semicolons and newlines survive
+        let summary = parse_text_manually(raw_ics, "SUMMARY")
+            .or_else(|| todo.get_summary().map(str::to_string))
+            .unwrap_or_else(|| "No Title".to_string());
+        let description = parse_text_manually(raw_ics, "DESCRIPTION")
+            .or_else(|| todo.get_description().map(str::to_string))
+            .unwrap_or_default();
         let uid = todo.get_uid().unwrap_or_default().to_string();
 
-        let status = if let Some(prop) = todo.properties().get("STATUS") {
+        // The STATUS of a note (DRAFT, FINAL…) is kept as it is, with the unmapped properties
+        let status = if kind == ItemKind::Journal {
+            TaskStatus::NeedsAction
+        } else if let Some(prop) = todo.properties().get("STATUS") {
             match prop.value().trim().to_uppercase().as_str() {
                 "COMPLETED" => TaskStatus::Completed,
                 "IN-PROCESS" => TaskStatus::InProcess,
@@ -236,7 +536,8 @@ impl Task {
             .properties()
             .get("PRIORITY")
             .and_then(|p| p.value().parse::<u8>().ok())
-            .unwrap_or(0);
+            .map(Priority::from)
+            .unwrap_or_default();
 
         let parse_date_prop = |val: &str| -> Option<DateTime<Utc>> {
             if val.len() == 8 {
@@ -275,76 +576,52 @@ impl Task {
             .get("DTSTART")
             .and_then(|p| parse_date_prop(p.value()));
 
+        let created = todo
+            .properties()
+            .get("CREATED")
+            .and_then(|p| parse_date_prop(p.value()));
+        let last_modified = todo
+            .properties()
+            .get("LAST-MODIFIED")
+            .and_then(|p| parse_date_prop(p.value()));
+        let completed = todo
+            .properties()
+            .get("COMPLETED")
+            .and_then(|p| parse_date_prop(p.value()));
+
         let rrule = todo
             .properties()
             .get("RRULE")
             .map(|p| p.value().to_string());
 
-        let parse_dur = |val: &str| -> Option<u32> {
-            let mut minutes = 0;
-            let mut num_buf = String::new();
-            let mut in_time = false;
-            for c in val.chars() {
-                if c == 'T' {
-                    in_time = true;
-                } else if c.is_numeric() {
-                    num_buf.push(c);
-                } else if !num_buf.is_empty() {
-                    let n = num_buf.parse::<u32>().unwrap_or(0);
-                    match c {
-                        'D' => minutes += n * 24 * 60,
-                        'H' => {
-                            if in_time {
-                                minutes += n * 60
-                            }
-                        }
-                        'M' => {
-                            if in_time {
-                                minutes += n
-                            }
-                        }
-                        'W' => minutes += n * 7 * 24 * 60,
-                        _ => {}
-                    }
-                    num_buf.clear();
-                }
-            }
-            if minutes > 0 { Some(minutes) } else { None }
-        };
+        let recur_from_completion = todo
+            .properties()
+            .get("X-CFAIT-RECUR-FROM")
+            .is_some_and(|p| p.value().trim().eq_ignore_ascii_case("COMPLETION"));
+
+        let hide_subtasks = todo
+            .properties()
+            .get("X-OC-HIDESUBTASKS")
+            .is_some_and(|p| p.value().trim() == "1");
+        let sort_order = todo
+            .properties()
+            .get("X-APPLE-SORT-ORDER")
+            .and_then(|p| p.value().trim().parse::<i64>().ok());
 
         let mut estimated_duration = todo
             .properties()
             .get("X-ESTIMATED-DURATION")
-            .and_then(|p| parse_dur(p.value()));
+            .and_then(|p| Duration::from_iso8601(p.value()));
 
         if estimated_duration.is_none() {
             estimated_duration = todo
                 .properties()
                 .get("DURATION")
-                .and_then(|p| parse_dur(p.value()));
+                .and_then(|p| Duration::from_iso8601(p.value()));
         }
 
-        let mut categories = Vec::new();
-        if let Some(multi_props) = todo.multi_properties().get("CATEGORIES") {
-            for prop in multi_props {
-                let parts: Vec<String> = prop
-                    .value()
-                    .split(',')
-                    .map(|s| s.trim().to_string())
-                    .filter(|s| !s.is_empty())
-                    .collect();
-                categories.extend(parts);
-            }
-        }
-        if let Some(prop) = todo.properties().get("CATEGORIES") {
-            let parts: Vec<String> = prop
-                .value()
-                .split(',')
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect();
-            categories.extend(parts);
-        }
+        // Parsed from the raw lines so escaped commas inside a tag survive
+        let mut categories = parse_categories_manually(raw_ics);
         categories.sort();
         categories.dedup();
 
@@ -352,6 +629,8 @@ impl Task {
         // Use manual parsing to avoid issues where icalendar library overwrites duplicate keys
         // (e.g. RELATED-TO) when they are not explicitly handled as multi-properties.
         let (parent_uid, dependencies) = parse_related_to_manually(raw_ics);
+        let reminders = parse_alarms_manually(raw_ics);
+        let exdates = parse_exdates_manually(raw_ics);
 
         // --- CAPTURE UNMAPPED PROPERTIES ---
         let mut unmapped_properties = Vec::new();
@@ -373,13 +652,17 @@ impl Task {
         };
 
         // Use case-insensitive check for handled keys
+        let is_handled = |key: &str| {
+            let key = key.to_uppercase();
+            HANDLED_KEYS.contains(&key.as_str()) && !(kind == ItemKind::Journal && key == "STATUS")
+        };
         for (key, prop) in todo.properties() {
-            if !HANDLED_KEYS.contains(&key.to_uppercase().as_str()) {
+            if !is_handled(key) {
                 unmapped_properties.push(to_raw(prop));
             }
         }
         for (key, props) in todo.multi_properties() {
-            if !HANDLED_KEYS.contains(&key.to_uppercase().as_str()) {
+            if !is_handled(key) {
                 for prop in props {
                     unmapped_properties.push(to_raw(prop));
                 }
@@ -391,8 +674,9 @@ impl Task {
                 .sort_unstable_by(|a, b| a.key.cmp(&b.key).then(a.value.cmp(&b.value)));
         }
 
-        Ok(Task {
+        Task {
             uid,
+            kind,
             summary,
             description,
             status,
@@ -408,10 +692,353 @@ impl Task {
             categories,
             depth: 0,
             rrule,
+            recur_from_completion,
+            exdates,
+            created,
+            last_modified,
+            completed,
+            hide_subtasks,
+            sort_order,
+            reminders,
+            hidden_subtasks: 0,
             unmapped_properties,
             raw_components,
+        }
+    }
+}
+
+/// Content-Type of a resource about to be written: a VJOURNAL-only one is announced as such.
+pub fn content_type(ics: &str) -> &'static str {
+    if ics.contains("BEGIN:VJOURNAL") && !ics.contains("BEGIN:VTODO") {
+        "text/calendar; charset=utf-8; component=VJOURNAL"
+    } else {
+        "text/calendar; charset=utf-8; component=VTODO"
+    }
+}
+
+/// Minutes of a signed ISO 8601 duration ("-PT15M", "P1DT2H").
+fn parse_signed_duration(value: &str) -> Option<i64> {
+    let (sign, rest) = match value.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let mut minutes = 0;
+    let mut num = String::new();
+    let mut in_time = false;
+    for c in rest.strip_prefix('P')?.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' => num.push(c),
+            _ => {
+                let n: i64 = std::mem::take(&mut num).parse().ok()?;
+                minutes += match c {
+                    'W' => n * 7 * 24 * 60,
+                    'D' => n * 24 * 60,
+                    'H' if in_time => n * 60,
+                    'M' if in_time => n,
+                    'S' if in_time => n / 60,
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(sign * minutes)
+}
+
+/// TRIGGER line of a reminder: a negative offset goes off before the date.
+fn format_trigger(reminder: &Reminder) -> String {
+    let offset = |mins: i64| {
+        let sign = if mins > 0 { "-" } else { "" };
+        format!("{}{}", sign, duration::format_iso8601(mins.unsigned_abs()))
+    };
+    match *reminder {
+        Reminder::At(t) => format!("TRIGGER;VALUE=DATE-TIME:{}", t.format("%Y%m%dT%H%M%SZ")),
+        Reminder::BeforeDue(m) => format!("TRIGGER;RELATED=END:{}", offset(m)),
+        Reminder::BeforeStart(m) => format!("TRIGGER:{}", offset(m)),
+    }
+}
+
+fn inject_components(ics: String, components: &[String]) -> String {
+    if components.is_empty() {
+        return ics;
+    }
+    let trimmed = ics.trim_end();
+    let Some(idx) = trimmed.rfind("END:VCALENDAR") else {
+        return ics;
+    };
+    let (start, end) = trimmed.split_at(idx);
+
+    let extra_len: usize = components.iter().map(|s| s.len() + 2).sum();
+    let mut buffer = String::with_capacity(trimmed.len() + extra_len);
+
+    buffer.push_str(start);
+    for raw in components {
+        buffer.push_str(raw);
+        if !raw.ends_with("\r\n") && !raw.ends_with('\n') {
+            buffer.push_str("\r\n");
+        }
+    }
+    buffer.push_str(end);
+    buffer
+}
+
+/// UID of a serialized component, if it has one.
+fn component_uid(raw: &str) -> Option<&str> {
+    raw.lines()
+        .find_map(|l| l.strip_prefix("UID:"))
+        .map(str::trim)
+}
+
+fn has_recurrence_id(raw: &str) -> bool {
+    raw.lines()
+        .any(|l| l.to_uppercase().starts_with("RECURRENCE-ID"))
+}
+
+fn is_scheduling_property(key: &str) -> bool {
+    key.eq_ignore_ascii_case("ORGANIZER") || key.eq_ignore_ascii_case("ATTENDEE")
+}
+
+fn is_sibling_todo(raw: &str, own_uid: &str) -> bool {
+    let raw_start = raw.trim_start();
+    (raw_start.starts_with("BEGIN:VTODO") || raw_start.starts_with("BEGIN:VJOURNAL"))
+        && !has_recurrence_id(raw)
+        && component_uid(raw) != Some(own_uid)
+}
+
+/// Splits a raw ICS string into the raw text of each VTODO and VJOURNAL block (nested VALARMs
+/// included).
+fn split_item_blocks(raw_ics: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current: Option<String> = None;
+
+    for line in raw_ics.lines() {
+        let upper = line.trim_end().to_uppercase();
+        if upper == "BEGIN:VTODO" || upper == "BEGIN:VJOURNAL" {
+            current = Some(String::new());
+        }
+        if let Some(buf) = current.as_mut() {
+            buf.push_str(line);
+            buf.push_str("\r\n");
+        }
+        if (upper == "END:VTODO" || upper == "END:VJOURNAL")
+            && let Some(buf) = current.take()
+        {
+            blocks.push(buf);
+        }
+    }
+    blocks
+}
+
+/// Escapes a TEXT value (RFC 5545 3.3.11).
+fn escape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Splits a TEXT list on unescaped commas and unescapes each value.
+fn split_text_list(value: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') | Some('N') => current.push('\n'),
+                Some(other) => current.push(other),
+                None => {}
+            },
+            ',' => items.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    items.push(current);
+    items
+}
+
+/// Unescapes a single TEXT value.
+fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') | Some('N') => out.push('\n'),
+                Some(other) => out.push(other),
+                None => {}
+            },
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Folds a content line so no physical line exceeds `max_octets`, never splitting a UTF-8 character.
+fn fold_line(line: &str, max_octets: usize) -> String {
+    if max_octets < 2 || line.len() <= max_octets {
+        return line.to_string();
+    }
+    let mut out = String::with_capacity(line.len() + line.len() / max_octets * 3);
+    let mut used = 0;
+    for c in line.chars() {
+        if used + c.len_utf8() > max_octets {
+            out.push_str("\r\n ");
+            used = 1; // The leading space counts towards the limit
+        }
+        out.push(c);
+        used += c.len_utf8();
+    }
+    out
+}
+
+/// Unfolds content lines, removing exactly one leading whitespace per continuation (RFC 5545 3.1).
+fn unfold_lines(raw_ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw_line in raw_ics.lines() {
+        if let Some(rest) = raw_line
+            .strip_prefix(' ')
+            .or_else(|| raw_line.strip_prefix('\t'))
+            && let Some(last) = lines.last_mut()
+        {
+            last.push_str(rest);
+        } else {
+            lines.push(raw_line.to_string());
+        }
+    }
+    lines
+}
+
+/// Reads every CATEGORIES line of the raw ICS (several lines and comma lists are both allowed).
+fn parse_categories_manually(raw_ics: &str) -> Vec<String> {
+    // Exceptions (RECURRENCE-ID) carry their own CATEGORIES; only the master's count
+    let master = split_item_blocks(raw_ics)
+        .into_iter()
+        .find(|b| !has_recurrence_id(b));
+    unfold_lines(master.as_deref().unwrap_or(raw_ics))
+        .iter()
+        .filter(|l| {
+            let upper = l.to_uppercase();
+            upper.starts_with("CATEGORIES:") || upper.starts_with("CATEGORIES;")
         })
+        .filter_map(|l| l.split_once(':').map(|(_, v)| v))
+        .flat_map(split_text_list)
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .collect()
+}
+
+/// Reads a TEXT property of the master item from the raw ICS, skipping its VALARMs (whose
+/// DESCRIPTION is the alarm's own).
+fn parse_text_manually(raw_ics: &str, key: &str) -> Option<String> {
+    let master = split_item_blocks(raw_ics)
+        .into_iter()
+        .find(|b| !has_recurrence_id(b));
+    let mut in_alarm = false;
+    for line in unfold_lines(master.as_deref().unwrap_or(raw_ics)) {
+        let upper = line.trim_end().to_uppercase();
+        if upper == "BEGIN:VALARM" {
+            in_alarm = true;
+        } else if upper == "END:VALARM" {
+            in_alarm = false;
+        } else if !in_alarm
+            && let Some(rest) = upper.strip_prefix(key)
+            && (rest.starts_with(':') || rest.starts_with(';'))
+        {
+            // The value starts after the first colon outside a quoted parameter
+            let mut quoted = false;
+            let start = line.char_indices().find_map(|(i, c)| match c {
+                '"' => {
+                    quoted = !quoted;
+                    None
+                }
+                ':' if !quoted => Some(i + 1),
+                _ => None,
+            })?;
+            return Some(unescape_text(&line[start..]));
+        }
     }
+    None
+}
+
+/// Reads the TRIGGER of every VALARM of the master VTODO. Alarms of any ACTION become
+/// reminders (and are written back as DISPLAY).
+fn parse_alarms_manually(raw_ics: &str) -> Vec<Reminder> {
+    let master = split_item_blocks(raw_ics)
+        .into_iter()
+        .find(|b| !has_recurrence_id(b));
+    let mut reminders = Vec::new();
+    let mut in_alarm = false;
+    for line in unfold_lines(master.as_deref().unwrap_or(raw_ics)) {
+        let upper = line.trim_end().to_uppercase();
+        if upper == "BEGIN:VALARM" {
+            in_alarm = true;
+        } else if upper == "END:VALARM" {
+            in_alarm = false;
+        } else if in_alarm
+            && (upper.starts_with("TRIGGER:") || upper.starts_with("TRIGGER;"))
+            && let Some((params, value)) = upper.split_once(':')
+            && let Some(reminder) = parse_trigger(params, value.trim())
+            && !reminders.contains(&reminder)
+        {
+            reminders.push(reminder);
+        }
+    }
+    reminders
+}
+
+/// Reads every EXDATE line of the master VTODO (several lines and comma lists are both
+/// allowed). TZID is ignored, like for DTSTART; date-only values become midnight.
+fn parse_exdates_manually(raw_ics: &str) -> Vec<DateTime<Utc>> {
+    let master = split_item_blocks(raw_ics)
+        .into_iter()
+        .find(|b| !has_recurrence_id(b));
+    let mut dates: Vec<DateTime<Utc>> = unfold_lines(master.as_deref().unwrap_or(raw_ics))
+        .iter()
+        .filter(|l| {
+            let upper = l.to_uppercase();
+            upper.starts_with("EXDATE:") || upper.starts_with("EXDATE;")
+        })
+        .filter_map(|l| l.split_once(':').map(|(_, v)| v.trim().to_string()))
+        .flat_map(|v| v.split(',').map(str::to_string).collect::<Vec<_>>())
+        .filter_map(|v| {
+            let v = v.trim().trim_end_matches('Z');
+            if v.len() == 8 {
+                NaiveDate::parse_from_str(v, "%Y%m%d")
+                    .ok()
+                    .map(|d| d.and_time(NaiveTime::MIN).and_utc())
+            } else {
+                NaiveDateTime::parse_from_str(v, "%Y%m%dT%H%M%S")
+                    .ok()
+                    .map(|d| d.and_utc())
+            }
+        })
+        .collect();
+    dates.sort();
+    dates.dedup();
+    dates
+}
+
+/// An absolute TRIGGER (VALUE=DATE-TIME) or an offset from DTSTART (default) or DUE (RELATED=END).
+fn parse_trigger(params: &str, value: &str) -> Option<Reminder> {
+    if params.contains("VALUE=DATE-TIME") {
+        let at = NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), "%Y%m%dT%H%M%S");
+        return Some(Reminder::At(at.ok()?.and_utc()));
+    }
+    let mins = -parse_signed_duration(value)?;
+    Some(if params.contains("RELATED=END") {
+        Reminder::BeforeDue(mins)
+    } else {
+        Reminder::BeforeStart(mins)
+    })
 }
 
 /// Helper: Manually parse RELATED-TO from raw ICS string.
@@ -460,6 +1087,7 @@ fn parse_related_to_manually(raw_ics: &str) -> (Option<String>, Vec<String>) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
 
     #[test]
     fn test_relationships_parsing_duplicate_protection() {
@@ -553,4 +1181,393 @@ END:VCALENDAR";
             "Folded lines should be unwrapped correctly"
         );
     }
+
+    #[test]
+    fn test_multiple_vtodos_in_one_resource() {
+        let ics = "BEGIN:VCALENDAR
+VERSION:2.0
+BEGIN:VTODO
+UID:first
+SUMMARY:First
+RELATED-TO;RELTYPE=DEPENDS-ON:second
+END:VTODO
+BEGIN:VTODO
+UID:second
+SUMMARY:Second
+END:VTODO
+END:VCALENDAR";
+
+        let tasks = Task::from_ics_all(
+            ics,
+            "etag".to_string(),
+            "/cal/shared.ics".to_string(),
+            "/cal/".to_string(),
+        )
+        .unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert!(tasks.iter().all(|t| t.href == "/cal/shared.ics"));
+
+        let (first, second) = (&tasks[0], &tasks[1]);
+        assert_eq!(first.summary, "First");
+        assert_eq!(first.dependencies, vec!["second".to_string()]);
+        // Relations of one VTODO must not leak into its siblings
+        assert!(second.dependencies.is_empty());
+
+        // Writing one task keeps the other in the resource
+        assert!(first.has_sibling_todos());
+        let reparsed = Task::from_ics_all(
+            &first.to_ics(),
+            "etag".to_string(),
+            "/cal/shared.ics".to_string(),
+            "/cal/".to_string(),
+        )
+        .unwrap();
+        assert_eq!(reparsed.len(), 2);
+
+        // Removing one leaves only the other
+        let rest = first.siblings_ics().unwrap();
+        let remaining =
+            Task::from_ics_all(&rest, String::new(), String::new(), String::new()).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].uid, "second");
+        assert!(!first.without_siblings().has_sibling_todos());
+
+        // A sibling saved since is written as it is now, and one removed since stays gone
+        let mut first = first.clone();
+        let mut second = second.clone();
+        assert_eq!(first.sibling_uids(), ["second"]);
+        second.summary = "Second, renamed".to_string();
+        first.refresh_sibling("second", Some(&second));
+        let reparsed =
+            Task::from_ics_all(&first.to_ics(), "e".into(), "/s".into(), "/c/".into()).unwrap();
+        assert_eq!(reparsed.len(), 2);
+        assert_eq!(reparsed[1].summary, "Second, renamed");
+        first.refresh_sibling("second", None);
+        assert!(!first.has_sibling_todos());
+    }
+
+    #[test]
+    fn test_recur_from_completion_roundtrip_and_respawn() {
+        let mut task = Task::new("Water plants @every 3 days @fromdone", &HashMap::new());
+        assert!(task.recur_from_completion);
+        let long_ago = Utc::now() - chrono::Duration::days(30);
+        task.due = Some(long_ago);
+
+        let parsed = Task::from_ics(
+            &task.to_ics(),
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse ICS");
+        assert!(parsed.recur_from_completion);
+        assert!(parsed.unmapped_properties.is_empty());
+        assert!(parsed.to_smart_string().contains("@fromdone"));
+
+        // Schedule-based would land 27 days in the past; completion-based lands ~3 days ahead
+        let next = parsed.respawn().expect("Should respawn");
+        let next_due = next.due.unwrap();
+        assert!(next_due > Utc::now() + chrono::Duration::days(2));
+        assert!(next_due < Utc::now() + chrono::Duration::days(4));
+    }
+
+    #[test]
+    fn test_skip_and_reschedule_occurrence() {
+        let mut task = Task::new("Standup @daily", &HashMap::new());
+        let due = Utc.with_ymd_and_hms(2025, 3, 10, 9, 0, 0).unwrap();
+        task.due = Some(due);
+
+        assert!(task.skip_occurrence());
+        assert_eq!(task.due, Some(due + chrono::Duration::days(1)));
+        assert_eq!(task.exdates, [due]);
+        assert_eq!(task.status, TaskStatus::NeedsAction);
+        assert!(task.rrule.is_some());
+
+        let moved_to = Utc.with_ymd_and_hms(2025, 3, 11, 15, 0, 0).unwrap();
+        let next = task
+            .reschedule_occurrence(moved_to)
+            .expect("Recurring task should spawn the rest of the series");
+        assert_eq!(task.due, Some(moved_to));
+        assert!(task.rrule.is_none());
+        assert_eq!(next.due, Some(due + chrono::Duration::days(2)));
+        assert_eq!(next.rrule.as_deref(), Some("FREQ=DAILY"));
+        assert_ne!(next.uid, task.uid);
+    }
+
+    #[test]
+    fn test_skip_occurrence_recurring_from_completion() {
+        let due = Utc.with_ymd_and_hms(2020, 3, 10, 17, 0, 0).unwrap();
+        let mut task = Task::new("Water plants rec:weekly", &HashMap::new());
+        task.due = Some(due);
+        task.recur_from_completion = true;
+
+        // Skipping moves on from the current due date, not from today
+        assert!(task.skip_occurrence());
+        assert_eq!(task.due, Some(due + chrono::Duration::weeks(1)));
+        assert_eq!(task.exdates, [due]);
+
+        // Completing still restarts the rule from today
+        let next = task.respawn().unwrap();
+        assert!(next.due.unwrap() > Utc::now());
+    }
+
+    #[test]
+    fn test_exdates_roundtrip_and_respawn() {
+        let ics = "BEGIN:VCALENDAR
+VERSION:2.0
+BEGIN:VTODO
+UID:standup
+SUMMARY:Standup
+DTSTART:20250310T090000Z
+RRULE:FREQ=DAILY
+EXDATE:20250311T090000Z,20250312T090000Z
+EXDATE;VALUE=DATE:20250313
+END:VTODO
+END:VCALENDAR";
+        let task = Task::from_ics(ics, "e".into(), "/s".into(), "/c/".into()).unwrap();
+        assert_eq!(task.exdates.len(), 3);
+        assert!(task.unmapped_properties.is_empty());
+
+        // The three excluded days are passed over
+        let next = task.respawn().unwrap();
+        let expected = Utc.with_ymd_and_hms(2025, 3, 14, 9, 0, 0).unwrap();
+        assert_eq!(next.dtstart, Some(expected));
+        assert_eq!(next.exdates, task.exdates);
+
+        let parsed = Task::from_ics(&task.to_ics(), "e".into(), "/s".into(), "/c/".into());
+        assert_eq!(parsed.unwrap().exdates, task.exdates);
+    }
+
+    #[test]
+    fn test_text_escaping() {
+        let ics = "BEGIN:VCALENDAR
+VERSION:2.0
+BEGIN:VTODO
+UID:milk
+SUMMARY;LANGUAGE=en:Buy milk\\, eggs\\; bread
+DESCRIPTION:First line\\nC:\\\\temp
+BEGIN:VALARM
+ACTION:DISPLAY
+DESCRIPTION:Reminder
+TRIGGER:-PT15M
+END:VALARM
+END:VTODO
+END:VCALENDAR";
+        let task = Task::from_ics(ics, "e".into(), "/m".into(), "/c/".into()).unwrap();
+        assert_eq!(task.summary, "Buy milk, eggs; bread");
+        assert_eq!(task.description, "First line\nC:\\temp");
+
+        let written = task.to_ics();
+        assert!(written.contains("SUMMARY:Buy milk\\, eggs\\; bread\r\n"));
+        let reparsed = Task::from_ics(&written, "e".into(), "/m".into(), "/c/".into()).unwrap();
+        assert_eq!(reparsed.summary, task.summary);
+        assert_eq!(reparsed.description, task.description);
+    }
+
+    #[test]
+    fn test_categories_escaping_and_folding() {
+        let mut task = Task::new("Tagged", &HashMap::new());
+        task.categories = vec![
+            "a,b".to_string(),
+            "semi;colon".to_string(),
+            "back\\slash".to_string(),
+            "two\nlines".to_string(),
+            "🦀 rust".to_string(),
+            "日本語のタグ".to_string(),
+        ];
+        task.categories
+            .extend((0..40).map(|i| format!("project:very-long-tag-name-{}", i)));
+        task.categories.sort();
+
+        let ics = task.to_ics();
+        for line in ics.split("\r\n") {
+            assert!(line.len() <= FOLD_OCTETS, "line too long: {:?}", line);
+        }
+
+        let parsed =
+            Task::from_ics(&ics, "e".to_string(), "/h".to_string(), "/c/".to_string()).unwrap();
+        assert_eq!(parsed.categories, task.categories);
+
+        // Folding can be disabled for clients that choke on continuation lines
+        let unfolded = task.to_ics_folded(0);
+        let cat_line = unfolded
+            .lines()
+            .find(|l| l.starts_with("CATEGORIES:"))
+            .unwrap();
+        assert!(cat_line.len() > FOLD_OCTETS);
+        assert!(cat_line.contains("a\\,b") && cat_line.contains("semi\\;colon"));
+        assert!(cat_line.contains("two\\nlines"));
+    }
+
+    #[test]
+    fn test_nextcloud_hide_subtasks_and_sort_order() {
+        let parent_ics = "BEGIN:VCALENDAR
+VERSION:2.0
+BEGIN:VTODO
+UID:parent
+SUMMARY:Parent
+X-OC-HIDESUBTASKS:1
+X-APPLE-SORT-ORDER:42
+END:VTODO
+END:VCALENDAR";
+        let parent = Task::from_ics(parent_ics, "e".into(), "/p".into(), "/c/".into()).unwrap();
+        assert!(parent.hide_subtasks);
+        assert_eq!(parent.sort_order, Some(42));
+        assert!(parent.unmapped_properties.is_empty());
+
+        let ics = parent.to_ics();
+        assert!(ics.contains("X-OC-HIDESUBTASKS:1"));
+        assert!(ics.contains("X-APPLE-SORT-ORDER:42"));
+
+        let mut child = Task::new("Child", &HashMap::new());
+        child.parent_uid = Some("parent".to_string());
+        let folded = Task::organize_hierarchy(vec![parent.clone(), child.clone()], None, true);
+        assert_eq!(folded.len(), 1);
+        assert_eq!(folded[0].hidden_subtasks, 1);
+
+        let flat = Task::organize_hierarchy(vec![parent, child], None, false);
+        assert_eq!(flat.len(), 2);
+        assert_eq!(flat[1].depth, 1);
+    }
+
+    #[test]
+    fn test_valarm_reminders_roundtrip() {
+        let ics = "BEGIN:VCALENDAR
+VERSION:2.0
+BEGIN:VTODO
+UID:dentist
+SUMMARY:Dentist
+DUE:20250301T090000Z
+BEGIN:VALARM
+ACTION:DISPLAY
+DESCRIPTION:Default
+TRIGGER;RELATED=END:-P1D
+END:VALARM
+BEGIN:VALARM
+ACTION:AUDIO
+TRIGGER;VALUE=DATE-TIME:20250227T180000Z
+END:VALARM
+BEGIN:VALARM
+ACTION:DISPLAY
+TRIGGER:-PT1H30M
+END:VALARM
+END:VTODO
+END:VCALENDAR";
+        let task = Task::from_ics(ics, "e".into(), "/d".into(), "/c/".into()).unwrap();
+        let at = Utc.with_ymd_and_hms(2025, 2, 27, 18, 0, 0).unwrap();
+        assert_eq!(
+            task.reminders,
+            vec![
+                Reminder::BeforeDue(1440),
+                Reminder::At(at),
+                Reminder::BeforeStart(90)
+            ]
+        );
+        assert!(task.description.is_empty());
+        assert!(task.unmapped_properties.is_empty());
+
+        let written = task.to_ics();
+        assert_eq!(written.matches("BEGIN:VALARM").count(), 3);
+        assert!(written.contains("TRIGGER;RELATED=END:-P1D"));
+        let reparsed = Task::from_ics(&written, "e".into(), "/d".into(), "/c/".into()).unwrap();
+        assert_eq!(reparsed.reminders, task.reminders);
+
+        // A fixed-time reminder follows its occurrence
+        let mut daily = task.clone();
+        daily.rrule = Some("FREQ=DAILY".to_string());
+        let next = daily.respawn().unwrap();
+        assert_eq!(
+            next.reminders[1],
+            Reminder::At(at + chrono::Duration::days(1))
+        );
+    }
+
+    #[test]
+    fn test_disable_server_scheduling() {
+        let ics = "BEGIN:VCALENDAR
+VERSION:2.0
+BEGIN:VTODO
+UID:minutes
+SUMMARY:Send the minutes
+ORGANIZER;CN=Dana;SCHEDULE-AGENT=NONE:mailto:dana@example.com
+ATTENDEE;PARTSTAT=NEEDS-ACTION;SCHEDULE-FORCE-SEND=REQUEST:mailto:me@example.com
+END:VTODO
+END:VCALENDAR";
+        let mut task = Task::from_ics(ics, "e".into(), "/d".into(), "/c/".into()).unwrap();
+        assert!(task.is_scheduling_object());
+        assert!(task.disable_server_scheduling());
+        assert!(!task.disable_server_scheduling());
+
+        let written = task.to_ics();
+        assert_eq!(written.matches("SCHEDULE-AGENT=CLIENT").count(), 1);
+        assert!(written.contains("SCHEDULE-AGENT=NONE"));
+        assert!(!written.contains("SCHEDULE-FORCE-SEND"));
+        assert!(written.contains("PARTSTAT=NEEDS-ACTION"));
+
+        let mut plain = Task::new("No attendees", &HashMap::new());
+        assert!(!plain.is_scheduling_object());
+        assert!(!plain.disable_server_scheduling());
+    }
+
+    #[test]
+    fn test_vjournal_notes() {
+        let ics = "BEGIN:VCALENDAR
+VERSION:2.0
+BEGIN:VJOURNAL
+UID:note-1
+SUMMARY:Meeting notes
+DESCRIPTION:Decided on the venue.
+STATUS:FINAL
+DTSTART;VALUE=DATE:20250304
+CATEGORIES:work
+END:VJOURNAL
+BEGIN:VTODO
+UID:todo-1
+SUMMARY:Book the venue
+END:VTODO
+END:VCALENDAR";
+
+        let items = Task::from_ics_all(
+            ics,
+            "etag".to_string(),
+            "/cal/mixed.ics".to_string(),
+            "/cal/".to_string(),
+        )
+        .unwrap();
+        assert_eq!(items.len(), 2);
+        let note = items.iter().find(|t| t.uid == "note-1").unwrap();
+        assert!(note.is_note());
+        assert_eq!(note.summary, "Meeting notes");
+        assert_eq!(note.description, "Decided on the venue.");
+        assert_eq!(note.categories, vec!["work".to_string()]);
+        assert_eq!(note.status, TaskStatus::NeedsAction);
+        assert!(note.has_sibling_todos());
+        let todo = items.iter().find(|t| t.uid == "todo-1").unwrap();
+        assert!(!todo.is_note() && todo.categories.is_empty());
+
+        // Its own STATUS is written back, none of a VTODO's properties are added
+        let mut edited = note.without_siblings();
+        edited.due = Some(Utc::now());
+        edited.priority = Priority::HIGH;
+        let out = edited.to_ics();
+        assert!(out.contains("BEGIN:VJOURNAL") && out.contains("END:VJOURNAL"));
+        assert!(out.contains("STATUS:FINAL") && !out.contains("NEEDS-ACTION"));
+        assert!(!out.contains("VTODO") && !out.contains("DUE") && !out.contains("PRIORITY"));
+        assert_eq!(
+            content_type(&out),
+            "text/calendar; charset=utf-8; component=VJOURNAL"
+        );
+        let reparsed = Task::from_ics(&out, String::new(), String::new(), String::new()).unwrap();
+        assert!(reparsed.is_note());
+        assert_eq!(reparsed.summary, "Meeting notes");
+
+        // Notes are typed as they are, without smart syntax
+        let mut new = Task::new_note(" Ideas @tomorrow !1 ");
+        assert_eq!(new.summary, "Ideas @tomorrow !1");
+        assert!(new.due.is_none() && !new.priority.is_set());
+        assert!(new.to_ics().contains("BEGIN:VJOURNAL"));
+        new.apply_smart_input("More ideas ~1h", &HashMap::new());
+        assert_eq!(new.to_smart_string(), "More ideas ~1h");
+        assert!(new.estimated_duration.is_none());
+    }
 }
@@ -0,0 +1,255 @@
+// File: ./src/model/agenda.rs
+// Printable pages: a task list as shown, or the agenda of a week, for a paper copy of the plan.
+// Self-contained HTML (inline style, no scripts) that any browser prints, or saves as PDF.
+use crate::model::Task;
+use chrono::{DateTime, Datelike, Local, NaiveDate, TimeDelta, Timelike, Utc};
+use std::collections::HashMap;
+
+const STYLE: &str = "body { font: 11pt sans-serif; margin: 2em; }
+h1 { font-size: 16pt; margin-bottom: 0.2em; }
+h2 { font-size: 12pt; border-bottom: 1px solid #999; margin: 1.2em 0 0.4em;
+     break-after: avoid; }
+ul { list-style: none; margin: 0; padding: 0; }
+li { padding: 0.15em 0; break-inside: avoid; }
+li::before { content: \"\\2610\"; margin-right: 0.5em; }
+li.done { color: #777; text-decoration: line-through; }
+li.done::before { content: \"\\2611\"; }
+.meta { color: #555; font-size: 9pt; margin-left: 0.6em; }
+.empty { color: #999; font-style: italic; }
+@page { margin: 1.5cm; }";
+
+/// Escapes the characters HTML reads as markup.
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Monday of the week of `day`.
+pub fn week_start(day: NaiveDate) -> NaiveDate {
+    day - TimeDelta::days(day.weekday().num_days_from_monday() as i64)
+}
+
+/// "Mar 4" for a due date at the end of the day (the default), "Mar 4 14:30" otherwise.
+fn format_due(due: DateTime<Utc>, with_date: bool) -> String {
+    let local = due.with_timezone(&Local);
+    let end_of_day = local.hour() == 23 && local.minute() == 59;
+    match (with_date, end_of_day) {
+        (true, true) => local.format("%b %-d").to_string(),
+        (true, false) => local.format("%b %-d %H:%M").to_string(),
+        (false, true) => String::new(),
+        (false, false) => local.format("%H:%M").to_string(),
+    }
+}
+
+/// One line: the summary, then calendar, priority, due date, estimate and tags.
+fn task_item(task: &Task, names: &HashMap<String, String>, with_date: bool) -> String {
+    let mut meta = Vec::new();
+    if let Some(name) = names.get(&task.calendar_href) {
+        meta.push(escape(name));
+    }
+    if task.priority.is_set() {
+        meta.push(format!("!{}", task.priority));
+    }
+    if let Some(due) = task.due {
+        let due = format_due(due, with_date);
+        if !due.is_empty() {
+            meta.push(due);
+        }
+    }
+    if let Some(duration) = task.estimated_duration {
+        meta.push(format!("~{}", duration));
+    }
+    meta.extend(task.categories.iter().map(|t| format!("#{}", escape(t))));
+
+    let class = if task.status.is_done() {
+        " class=\"done\""
+    } else {
+        ""
+    };
+    let indent = if task.depth > 0 {
+        format!(" style=\"margin-left: {}em\"", task.depth as f32 * 1.5)
+    } else {
+        String::new()
+    };
+    let meta = if meta.is_empty() {
+        String::new()
+    } else {
+        format!("<span class=\"meta\">{}</span>", meta.join(" · "))
+    };
+    format!(
+        "<li{}{}>{}{}</li>",
+        class,
+        indent,
+        escape(&task.summary),
+        meta
+    )
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{0}</title>\n\
+         <style>\n{1}\n</style></head>\n<body>\n<h1>{0}</h1>\n{2}</body></html>\n",
+        escape(title),
+        STYLE,
+        body
+    )
+}
+
+fn list(items: &[String]) -> String {
+    if items.is_empty() {
+        "<p class=\"empty\">Nothing</p>\n".to_string()
+    } else {
+        format!("<ul>\n{}\n</ul>\n", items.join("\n"))
+    }
+}
+
+/// The tasks in the order given, e.g. the filtered list of a UI (subtasks indented by their
+/// `depth`). `calendar_names` maps calendar hrefs to the names printed.
+pub fn list_html<'a>(
+    title: &str,
+    tasks: impl IntoIterator<Item = &'a Task>,
+    calendar_names: &HashMap<String, String>,
+) -> String {
+    let items: Vec<String> = tasks
+        .into_iter()
+        .map(|t| task_item(t, calendar_names, true))
+        .collect();
+    page(title, &list(&items))
+}
+
+/// The week starting on `start`, one section per day with the open tasks due that day (by
+/// time, then priority), after those overdue.
+pub fn week_html<'a>(
+    tasks: impl IntoIterator<Item = &'a Task>,
+    calendar_names: &HashMap<String, String>,
+    start: NaiveDate,
+) -> String {
+    let mut due: Vec<(&Task, DateTime<Local>)> = tasks
+        .into_iter()
+        .filter(|t| in_week(t, start))
+        .filter_map(|t| Some((t, t.due?.with_timezone(&Local))))
+        .collect();
+    due.sort_by(|(a, a_at), (b, b_at)| {
+        a_at.cmp(b_at)
+            .then(a.priority.rank().cmp(&b.priority.rank()))
+            .then(a.summary.cmp(&b.summary))
+    });
+
+    let mut body = String::new();
+    let overdue: Vec<String> = due
+        .iter()
+        .filter(|(_, at)| at.date_naive() < start)
+        .map(|(t, _)| task_item(t, calendar_names, true))
+        .collect();
+    if !overdue.is_empty() {
+        body.push_str("<h2>Overdue</h2>\n");
+        body.push_str(&list(&overdue));
+    }
+    for offset in 0..7 {
+        let day = start + TimeDelta::days(offset);
+        let items: Vec<String> = due
+            .iter()
+            .filter(|(_, at)| at.date_naive() == day)
+            .map(|(t, _)| task_item(t, calendar_names, false))
+            .collect();
+        body.push_str(&format!("<h2>{}</h2>\n", day.format("%A, %B %-d")));
+        body.push_str(&list(&items));
+    }
+    let title = format!("Week of {}", start.format("%B %-d, %Y"));
+    page(&title, &body)
+}
+
+/// Whether `week_html` prints `task`: open, and due by the end of the week.
+fn in_week(task: &Task, start: NaiveDate) -> bool {
+    !task.status.is_done()
+        && task
+            .due
+            .is_some_and(|d| d.with_timezone(&Local).date_naive() < start + TimeDelta::days(7))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::TaskStatus;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_week_html() {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        assert_eq!(
+            week_start(NaiveDate::from_ymd_opt(2025, 3, 6).unwrap()),
+            start
+        );
+        assert_eq!(week_start(start), start);
+
+        let at = |d, h, m| {
+            Local
+                .with_ymd_and_hms(2025, 3, d, h, m, 0)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        let mut call = Task::new("Call <Bob> !1 #phone", &HashMap::new());
+        call.calendar_href = "/cal/work/".to_string();
+        call.due = Some(at(4, 23, 59));
+        let mut meeting = Task::new("Standup", &HashMap::new());
+        meeting.due = Some(at(4, 9, 30));
+        let mut late = Task::new("Taxes", &HashMap::new());
+        late.due = Some(at(1, 23, 59));
+        let mut done = Task::new("Done already", &HashMap::new());
+        done.due = Some(at(5, 23, 59));
+        done.status = TaskStatus::Completed;
+        let mut next_week = Task::new("Later", &HashMap::new());
+        next_week.due = Some(at(10, 23, 59));
+
+        let names = HashMap::from([("/cal/work/".to_string(), "Work".to_string())]);
+        let tasks = [&call, &meeting, &late, &done, &next_week];
+        assert_eq!(tasks.iter().filter(|t| in_week(t, start)).count(), 3);
+        let html = week_html(tasks, &names, start);
+
+        assert!(html.contains("<title>Week of March 3, 2025</title>"));
+        let overdue = html.find("<h2>Overdue</h2>").unwrap();
+        let tuesday = html.find("<h2>Tuesday, March 4</h2>").unwrap();
+        assert!(overdue < html.find("Taxes").unwrap());
+        assert!(html.find("Taxes").unwrap() < tuesday);
+        // By time on the day, the end-of-day one last and without a time
+        let standup = html
+            .find("<li>Standup<span class=\"meta\">09:30</span></li>")
+            .unwrap();
+        let call = html
+            .find("<li>Call &lt;Bob&gt;<span class=\"meta\">Work · !1 · #phone</span></li>")
+            .unwrap();
+        assert!(tuesday < standup && standup < call);
+        assert!(!html.contains("Done already") && !html.contains("Later"));
+        assert!(html.contains("<h2>Sunday, March 9</h2>\n<p class=\"empty\">Nothing</p>"));
+    }
+
+    #[test]
+    fn test_list_html() {
+        let mut parent = Task::new("Trip", &HashMap::new());
+        parent.status = TaskStatus::Completed;
+        let mut child = Task::new("Pack ~30m", &HashMap::new());
+        child.depth = 1;
+        let html = list_html("Today & tomorrow", [&parent, &child], &HashMap::new());
+        assert!(html.contains("<h1>Today &amp; tomorrow</h1>"));
+        assert!(html.contains("<li class=\"done\">Trip</li>"));
+        assert!(html.contains(
+            "<li style=\"margin-left: 1.5em\">Pack<span class=\"meta\">~30m</span></li>"
+        ));
+        assert_eq!(
+            list_html("Empty", [], &HashMap::new())
+                .matches("Nothing")
+                .count(),
+            1
+        );
+    }
+}
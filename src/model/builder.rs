@@ -0,0 +1,178 @@
+// File: ./src/model/builder.rs
+// Putting a task together in code (CLI, integrations, tests): typed setters for the fields,
+// checked by `build`, so callers need not know which fields go together. The title is taken
+// as is, unlike `Task::new`, which reads it as smart syntax.
+use crate::model::duration::Duration;
+use crate::model::item::RawProperty;
+use crate::model::priority::Priority;
+use crate::model::recurrence::Recurrence;
+use crate::model::{Reminder, Task, TaskStatus};
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone)]
+pub struct TaskBuilder {
+    task: Task,
+}
+
+impl TaskBuilder {
+    pub fn new(summary: impl Into<String>) -> Self {
+        let mut task = Task::new("", &Default::default());
+        task.summary = summary.into();
+        Self { task }
+    }
+
+    /// Instead of a random one, e.g. to update a known task.
+    pub fn uid(mut self, uid: impl Into<String>) -> Self {
+        self.task.uid = uid.into();
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.task.description = description.into();
+        self
+    }
+
+    pub fn status(mut self, status: TaskStatus) -> Self {
+        self.task.status = status;
+        self
+    }
+
+    pub fn due(mut self, due: DateTime<Utc>) -> Self {
+        self.task.due = Some(due);
+        self
+    }
+
+    pub fn start(mut self, start: DateTime<Utc>) -> Self {
+        self.task.dtstart = Some(start);
+        self
+    }
+
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.task.priority = priority;
+        self
+    }
+
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.task.estimated_duration = Some(duration);
+        self
+    }
+
+    pub fn recur(mut self, recurrence: &Recurrence) -> Self {
+        self.task.rrule = Some(recurrence.to_rrule());
+        self
+    }
+
+    /// Next occurrences count from the completion instead of the schedule (needs `recur`).
+    pub fn recur_from_completion(mut self) -> Self {
+        self.task.recur_from_completion = true;
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        let tag = tag.into();
+        if !self.task.categories.contains(&tag) {
+            self.task.categories.push(tag);
+        }
+        self
+    }
+
+    pub fn parent(mut self, uid: impl Into<String>) -> Self {
+        self.task.parent_uid = Some(uid.into());
+        self
+    }
+
+    /// Blocked until the task `uid` is done.
+    pub fn depends_on(mut self, uid: impl Into<String>) -> Self {
+        let uid = uid.into();
+        if !self.task.dependencies.contains(&uid) {
+            self.task.dependencies.push(uid);
+        }
+        self
+    }
+
+    pub fn reminder(mut self, reminder: Reminder) -> Self {
+        self.task.reminders.push(reminder);
+        self
+    }
+
+    pub fn calendar(mut self, href: impl Into<String>) -> Self {
+        self.task.calendar_href = href.into();
+        self
+    }
+
+    /// A task that already has a resource on the server: its href and etag go together.
+    pub fn stored(mut self, href: impl Into<String>, etag: impl Into<String>) -> Self {
+        self.task.href = href.into();
+        self.task.etag = etag.into();
+        self
+    }
+
+    /// An ICS property cfait does not handle itself, written back as is.
+    pub fn property(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.task.unmapped_properties.push(RawProperty {
+            key: key.into(),
+            value: value.into(),
+            params: Vec::new(),
+        });
+        self
+    }
+
+    /// The task, or what makes it unfit to be saved.
+    pub fn build(self) -> Result<Task, String> {
+        let task = self.task;
+        if task.summary.trim().is_empty() {
+            return Err("A task needs a title".to_string());
+        }
+        if task.recur_from_completion && task.rrule.is_none() {
+            return Err("Recurring from completion needs a recurrence".to_string());
+        }
+        let blocking: Vec<String> = task
+            .validate()
+            .iter()
+            .filter(|w| w.is_blocking())
+            .map(|w| w.to_string())
+            .collect();
+        if !blocking.is_empty() {
+            return Err(blocking.join("; "));
+        }
+        Ok(task)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::recurrence::Frequency;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_builder() {
+        let due = Utc.with_ymd_and_hms(2025, 3, 7, 23, 59, 59).unwrap();
+        let weekly = Recurrence {
+            freq: Frequency::Weekly,
+            ..Default::default()
+        };
+        let task = TaskBuilder::new("Review @ noon #not-a-tag")
+            .due(due)
+            .priority(Priority::HIGH)
+            .recur(&weekly)
+            .tag("work")
+            .tag("work")
+            .calendar("/cal/work/")
+            .build()
+            .unwrap();
+        assert_eq!(task.summary, "Review @ noon #not-a-tag");
+        assert_eq!(task.due, Some(due));
+        assert_eq!(task.priority, Priority::HIGH);
+        assert_eq!(task.rrule.as_deref(), Some("FREQ=WEEKLY"));
+        assert_eq!(task.categories, ["work"]);
+        assert!(task.href.is_empty() && task.etag.is_empty());
+
+        assert!(TaskBuilder::new(" ").build().is_err());
+        let no_rule = TaskBuilder::new("Gym").recur_from_completion();
+        assert!(no_rule.build().is_err());
+        let uid = "self".to_string();
+        let looped = TaskBuilder::new("Loop").uid(&uid).depends_on(&uid).build();
+        assert!(looped.unwrap_err().contains("depend"));
+    }
+}
@@ -0,0 +1,272 @@
+// File: ./src/model/dedupe.rs
+// Finds probable duplicates (the same task in two calendars after a bad migration, or the same
+// title added twice) and plans their merge into one task, keeping subtasks and dependencies.
+use crate::model::Task;
+use chrono::Duration;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateKind {
+    /// Same UID in several calendars
+    SameUid,
+    /// Open tasks with the same title and due dates within the window (or none at all)
+    SameSummary,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateGroup {
+    pub kind: DuplicateKind,
+    /// The task kept comes first
+    pub tasks: Vec<Task>,
+}
+
+/// The writes a merge takes: the kept task, the tasks pointing to the extras, the extras.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergePlan {
+    pub keep: Task,
+    pub relink: Vec<Task>,
+    pub delete: Vec<Task>,
+}
+
+fn normalized_summary(task: &Task) -> String {
+    task.summary
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// The most recently modified goes first, it's the one kept.
+fn keeper_first(mut tasks: Vec<Task>) -> Vec<Task> {
+    tasks.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
+    tasks
+}
+
+/// Groups of probable duplicates among `tasks`; every task is in one group at most.
+pub fn find_duplicates(tasks: &[Task], window: Duration) -> Vec<DuplicateGroup> {
+    let mut groups = Vec::new();
+    let mut grouped: HashSet<(&str, &str)> = HashSet::new();
+
+    let mut by_uid: HashMap<&str, Vec<&Task>> = HashMap::new();
+    for task in tasks {
+        let copies = by_uid.entry(task.uid.as_str()).or_default();
+        // The same task listed twice is not a duplicate
+        if !copies.iter().any(|t| t.calendar_href == task.calendar_href) {
+            copies.push(task);
+        }
+    }
+    let mut uids: Vec<_> = by_uid.into_iter().filter(|(_, c)| c.len() > 1).collect();
+    uids.sort_by_key(|(uid, _)| *uid);
+    for (uid, copies) in uids {
+        grouped.extend(copies.iter().map(|t| (uid, t.calendar_href.as_str())));
+        groups.push(DuplicateGroup {
+            kind: DuplicateKind::SameUid,
+            tasks: keeper_first(copies.into_iter().cloned().collect()),
+        });
+    }
+
+    // Done tasks are left alone: a finished occurrence shares the title of the next one
+    let mut open: Vec<&Task> = tasks
+        .iter()
+        .filter(|t| !t.status.is_done() && !t.summary.trim().is_empty())
+        .filter(|t| !grouped.contains(&(t.uid.as_str(), t.calendar_href.as_str())))
+        .collect();
+    open.sort_by_key(|t| (normalized_summary(t), t.due.is_some(), t.due));
+    let mut current: Vec<&Task> = Vec::new();
+    for task in open {
+        let joins = current.last().is_some_and(|last| {
+            normalized_summary(last) == normalized_summary(task)
+                && last.uid != task.uid
+                && match (last.due, task.due) {
+                    (Some(a), Some(b)) => b - a <= window,
+                    (None, None) => true,
+                    _ => false,
+                }
+        });
+        if !joins {
+            if current.len() > 1 {
+                groups.push(DuplicateGroup {
+                    kind: DuplicateKind::SameSummary,
+                    tasks: keeper_first(current.into_iter().cloned().collect()),
+                });
+            }
+            current = Vec::new();
+        }
+        current.push(task);
+    }
+    if current.len() > 1 {
+        groups.push(DuplicateGroup {
+            kind: DuplicateKind::SameSummary,
+            tasks: keeper_first(current.into_iter().cloned().collect()),
+        });
+    }
+    groups
+}
+
+/// Folds the extras of `group` into its first task: empty fields are filled, tags and
+/// dependencies united, the most urgent priority and earliest due date win and differing
+/// descriptions are appended. Subtasks and dependents of the extras (among `all`) are moved
+/// over to the kept task.
+pub fn plan_merge(group: &DuplicateGroup, all: &[Task]) -> MergePlan {
+    let mut keep = group.tasks[0].clone();
+    let extras = &group.tasks[1..];
+    for extra in extras {
+        let description = extra.description.trim();
+        if keep.description.trim().is_empty() {
+            keep.description = extra.description.clone();
+        } else if !description.is_empty() && !keep.description.contains(description) {
+            keep.description = format!("{}\n\n{}", keep.description.trim_end(), description);
+        }
+        for tag in &extra.categories {
+            if !keep.categories.contains(tag) {
+                keep.categories.push(tag.clone());
+            }
+        }
+        for dep in &extra.dependencies {
+            if !keep.dependencies.contains(dep) {
+                keep.dependencies.push(dep.clone());
+            }
+        }
+        for reminder in &extra.reminders {
+            if !keep.reminders.contains(reminder) {
+                keep.reminders.push(*reminder);
+            }
+        }
+        if extra.priority.is_set()
+            && (!keep.priority.is_set() || extra.priority.value() < keep.priority.value())
+        {
+            keep.priority = extra.priority;
+        }
+        keep.due = match (keep.due, extra.due) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        keep.dtstart = keep.dtstart.or(extra.dtstart);
+        keep.estimated_duration = keep.estimated_duration.or(extra.estimated_duration);
+        keep.rrule = keep.rrule.clone().or_else(|| extra.rrule.clone());
+        keep.parent_uid = keep.parent_uid.clone().or_else(|| extra.parent_uid.clone());
+    }
+
+    // Same-UID copies are referred to by the kept task's UID already
+    let merged: HashSet<&str> = extras
+        .iter()
+        .map(|t| t.uid.as_str())
+        .filter(|uid| *uid != keep.uid)
+        .collect();
+    keep.dependencies
+        .retain(|d| *d != keep.uid && !merged.contains(d.as_str()));
+    if keep
+        .parent_uid
+        .as_deref()
+        .is_some_and(|p| p == keep.uid || merged.contains(p))
+    {
+        keep.parent_uid = None;
+    }
+
+    let mut relink = Vec::new();
+    for task in all {
+        if group.tasks.iter().any(|t| t.uid == task.uid) {
+            continue;
+        }
+        let mut changed = task.clone();
+        if let Some(parent) = &task.parent_uid
+            && merged.contains(parent.as_str())
+        {
+            changed.parent_uid = Some(keep.uid.clone());
+        }
+        if task
+            .dependencies
+            .iter()
+            .any(|d| merged.contains(d.as_str()))
+        {
+            changed.dependencies = Vec::new();
+            for dep in &task.dependencies {
+                let dep = if merged.contains(dep.as_str()) {
+                    &keep.uid
+                } else {
+                    dep
+                };
+                if !changed.dependencies.contains(dep) {
+                    changed.dependencies.push(dep.clone());
+                }
+            }
+        }
+        if changed != *task {
+            relink.push(changed);
+        }
+    }
+
+    MergePlan {
+        keep,
+        relink,
+        delete: extras.to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Priority;
+    use chrono::{TimeZone, Utc};
+    use std::collections::HashMap;
+
+    fn task(uid: &str, summary: &str, cal: &str) -> Task {
+        let mut t = Task::new(summary, &HashMap::new());
+        t.uid = uid.to_string();
+        t.calendar_href = cal.to_string();
+        t
+    }
+
+    #[test]
+    fn test_find_and_merge_duplicates() {
+        let due = Utc.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap();
+        let mut milk = task("a", "Buy milk", "/home/");
+        milk.due = Some(due);
+        milk.categories = vec!["shop".to_string()];
+        let mut milk_again = task("b", "buy  Milk", "/home/");
+        milk_again.due = Some(due + Duration::hours(3));
+        milk_again.priority = Priority::Level(2);
+        milk_again.description = "Oat milk".to_string();
+        let mut next_month = task("c", "Buy milk", "/home/");
+        next_month.due = Some(due + Duration::days(30));
+        let mut child = task("d", "Find a shop", "/home/");
+        child.parent_uid = Some("b".to_string());
+        child.dependencies = vec!["b".to_string(), "a".to_string()];
+        let migrated = task("e", "Call Bob", "/old/");
+        let mut migrated_copy = migrated.clone();
+        migrated_copy.calendar_href = "/new/".to_string();
+        let all = vec![
+            milk.clone(),
+            milk_again.clone(),
+            next_month,
+            child,
+            migrated.clone(),
+            migrated_copy,
+            migrated,
+        ];
+
+        let groups = find_duplicates(&all, Duration::days(1));
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].kind, DuplicateKind::SameUid);
+        assert_eq!(groups[0].tasks.len(), 2);
+        assert_eq!(groups[1].kind, DuplicateKind::SameSummary);
+        let uids: Vec<_> = groups[1].tasks.iter().map(|t| t.uid.as_str()).collect();
+        assert_eq!(uids.len(), 2);
+        assert!(uids.contains(&"a") && uids.contains(&"b"));
+
+        let group = DuplicateGroup {
+            kind: DuplicateKind::SameSummary,
+            tasks: vec![milk, milk_again],
+        };
+        let plan = plan_merge(&group, &all);
+        assert_eq!(plan.keep.uid, "a");
+        assert_eq!(plan.keep.due, Some(due));
+        assert_eq!(plan.keep.priority, Priority::Level(2));
+        assert_eq!(plan.keep.description, "Oat milk");
+        assert_eq!(plan.keep.categories, vec!["shop".to_string()]);
+        assert_eq!(plan.relink.len(), 1);
+        assert_eq!(plan.relink[0].parent_uid.as_deref(), Some("a"));
+        assert_eq!(plan.relink[0].dependencies, vec!["a".to_string()]);
+        assert_eq!(plan.delete[0].uid, "b");
+    }
+}
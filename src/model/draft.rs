@@ -0,0 +1,218 @@
+// File: ./src/model/draft.rs
+// A task's fields as the GUI's inline editor (the expanded card) holds them: dates and
+// duration stay text as typed until the whole draft is saved in one update.
+use crate::model::parser::{expand_aliases, parse_smart_date};
+use crate::model::{Duration, Priority, Task};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeSet, HashMap};
+
+/// Suggestions shown under the tag and dependency fields
+const SUGGESTIONS: usize = 6;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TaskDraft {
+    pub summary: String,
+    pub description: String,
+    /// "2025-03-01", "tomorrow", "2w"…; empty = none
+    pub due: String,
+    pub start: String,
+    pub priority: Priority,
+    /// "30m", "2h"…; empty = none
+    pub duration: String,
+    pub tags: Vec<String>,
+    pub rrule: Option<String>,
+    /// UIDs of the tasks this one waits for
+    pub dependencies: Vec<String>,
+}
+
+fn date_text(date: Option<DateTime<Utc>>) -> String {
+    date.map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// Exact, so that saving an untouched field keeps the minutes.
+fn duration_text(duration: Option<Duration>) -> String {
+    duration.map(Duration::exact).unwrap_or_default()
+}
+
+/// An untouched field keeps the exact time it came from (other clients set hours).
+fn parse_date(
+    typed: &str,
+    current: Option<DateTime<Utc>>,
+    end_of_day: bool,
+    field: &str,
+) -> Result<Option<DateTime<Utc>>, String> {
+    let typed = typed.trim();
+    if typed.is_empty() {
+        return Ok(None);
+    }
+    if typed == date_text(current) {
+        return Ok(current);
+    }
+    parse_smart_date(typed, end_of_day)
+        .map(Some)
+        .ok_or_else(|| format!("Invalid {} date: '{}'", field, typed))
+}
+
+impl TaskDraft {
+    pub fn from_task(task: &Task) -> Self {
+        Self {
+            summary: task.summary.clone(),
+            description: task.description.clone(),
+            due: date_text(task.due),
+            start: date_text(task.dtstart),
+            priority: task.priority,
+            duration: duration_text(task.estimated_duration),
+            tags: task.categories.clone(),
+            rrule: task.rrule.clone(),
+            dependencies: task.dependencies.clone(),
+        }
+    }
+
+    /// `task` with the values of the draft, or what does not parse.
+    pub fn apply(&self, task: &Task) -> Result<Task, String> {
+        let summary = self.summary.trim();
+        if summary.is_empty() {
+            return Err("The title can't be empty.".to_string());
+        }
+        let duration = self.duration.trim();
+        let estimated_duration = if duration.is_empty() {
+            None
+        } else if duration == duration_text(task.estimated_duration) {
+            task.estimated_duration
+        } else {
+            Some(
+                Duration::parse(duration)
+                    .ok_or_else(|| format!("Invalid duration: '{}'", duration))?,
+            )
+        };
+        let mut updated = task.clone();
+        updated.summary = summary.to_string();
+        updated.description = self.description.clone();
+        updated.due = parse_date(&self.due, task.due, true, "due")?;
+        updated.dtstart = parse_date(&self.start, task.dtstart, false, "start")?;
+        updated.priority = Priority::from(self.priority.value().min(9));
+        updated.estimated_duration = estimated_duration;
+        updated.categories = self.tags.clone();
+        updated.rrule = self.rrule.clone();
+        updated.recur_from_completion &= updated.rrule.is_some();
+        updated.dependencies = self.dependencies.clone();
+        Ok(updated)
+    }
+
+    /// Adds the tags typed (with or without '#') and those their aliases imply.
+    pub fn add_tags(&mut self, typed: &str, aliases: &HashMap<String, Vec<String>>) {
+        for tag in typed.split_whitespace().map(|t| t.trim_start_matches('#')) {
+            if tag.is_empty() {
+                continue;
+            }
+            let implied = expand_aliases(&format!("#{}", tag), aliases)
+                .into_iter()
+                .flat_map(|(_, tags)| tags);
+            for tag in std::iter::once(tag.to_string()).chain(implied) {
+                if !self.tags.contains(&tag) {
+                    self.tags.push(tag);
+                }
+            }
+        }
+    }
+}
+
+/// Known tags starting with what is typed in the tag field, but those already set.
+pub fn suggest_tags<'a>(
+    tasks: impl IntoIterator<Item = &'a Task>,
+    typed: &str,
+    taken: &[String],
+) -> Vec<String> {
+    let typed = typed.trim().trim_start_matches('#').to_lowercase();
+    if typed.is_empty() {
+        return Vec::new();
+    }
+    tasks
+        .into_iter()
+        .flat_map(|t| &t.categories)
+        .filter(|tag| tag.to_lowercase().starts_with(&typed) && !taken.contains(tag))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .take(SUGGESTIONS)
+        .cloned()
+        .collect()
+}
+
+/// Open tasks whose title contains what is typed in the dependency field, as (uid, title),
+/// but `uid` itself and the tasks it already waits for.
+pub fn suggest_dependencies<'a>(
+    tasks: impl IntoIterator<Item = &'a Task>,
+    typed: &str,
+    uid: &str,
+    taken: &[String],
+) -> Vec<(String, String)> {
+    let typed = typed.trim().to_lowercase();
+    if typed.is_empty() {
+        return Vec::new();
+    }
+    tasks
+        .into_iter()
+        .filter(|t| !t.status.is_done() && t.uid != uid && !taken.contains(&t.uid))
+        .filter(|t| t.summary.to_lowercase().contains(&typed))
+        .take(SUGGESTIONS)
+        .map(|t| (t.uid.clone(), t.summary.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_draft_apply() {
+        let mut task = Task::new("Pay rent !2 @2025-03-01 ~90m #home", &HashMap::new());
+        // Set by another client: the time survives while the field is left alone
+        let nine = Utc.with_ymd_and_hms(2025, 2, 20, 9, 0, 0).unwrap();
+        task.dtstart = Some(nine);
+        let mut draft = TaskDraft::from_task(&task);
+        assert_eq!(draft.due, "2025-03-01");
+        assert_eq!(draft.duration, "90m");
+        assert_eq!(draft.apply(&task), Ok(task.clone()));
+
+        let aliases = HashMap::from([("home".to_string(), vec!["personal".to_string()])]);
+        draft.tags.clear();
+        draft.add_tags("#home money", &aliases);
+        assert_eq!(draft.tags, ["home", "personal", "money"]);
+        draft.due = "2025-03-05".to_string();
+        draft.duration = "2h".to_string();
+        draft.priority = Priority::HIGH;
+        draft.dependencies.push("other".to_string());
+        let updated = draft.apply(&task).unwrap();
+        assert_eq!(
+            updated.due.unwrap().format("%Y-%m-%d").to_string(),
+            "2025-03-05"
+        );
+        assert_eq!(updated.dtstart, Some(nine));
+        assert_eq!(updated.estimated_duration, Duration::parse("120m"));
+        assert_eq!(updated.priority, Priority::HIGH);
+        assert_eq!(updated.dependencies, ["other"]);
+
+        draft.due = "someday".to_string();
+        assert!(draft.apply(&task).unwrap_err().contains("due"));
+        draft.due.clear();
+        draft.summary = " ".to_string();
+        assert!(draft.apply(&task).is_err());
+
+        let mut other = Task::new("Call the landlord #house", &HashMap::new());
+        other.uid = "other".to_string();
+        let tasks = [task.clone(), other];
+        assert_eq!(
+            suggest_tags(&tasks, "#ho", &["home".to_string()]),
+            ["house"]
+        );
+        assert_eq!(
+            suggest_dependencies(&tasks, "LANDLORD", &task.uid, &[]),
+            [("other".to_string(), "Call the landlord".to_string())]
+        );
+        assert!(
+            suggest_dependencies(&tasks, "landlord", &task.uid, &draft.dependencies).is_empty()
+        );
+    }
+}
@@ -0,0 +1,78 @@
+// File: ./src/model/due.rs
+// How due dates read in the lists: relative to today ("tomorrow", "in 3 d", "2 w overdue")
+// or as the full date in the language's format, per `due_display`.
+use crate::i18n::{tr, tr_args};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DueDisplay {
+    #[default]
+    Relative,
+    Absolute,
+}
+
+impl DueDisplay {
+    pub fn toggled(self) -> Self {
+        match self {
+            DueDisplay::Relative => DueDisplay::Absolute,
+            DueDisplay::Absolute => DueDisplay::Relative,
+        }
+    }
+}
+
+/// `due` counted from `today` in days, then in weeks past two of them.
+pub fn relative(due: NaiveDate, today: NaiveDate) -> String {
+    let days = (due - today).num_days();
+    match days {
+        0 => tr("due.today").to_string(),
+        1 => tr("due.tomorrow").to_string(),
+        -1 => tr("due.yesterday").to_string(),
+        2..=13 => tr_args("due.in_days", &[("n", &days)]),
+        14.. => tr_args("due.in_weeks", &[("n", &(days / 7))]),
+        -13..=-2 => tr_args("due.overdue_days", &[("n", &-days)]),
+        _ => tr_args("due.overdue_weeks", &[("n", &(-days / 7))]),
+    }
+}
+
+/// The date in the language's format, e.g. "Tue Mar 4, 2025" or "04.03.2025". Due dates
+/// are kept as the end of their day in UTC (as the smart input writes them), hence no
+/// conversion to the local time, which would move them to the next day east of UTC.
+pub fn full(due: DateTime<Utc>) -> String {
+    due.format(tr("due.format")).to_string()
+}
+
+/// What the lists show for `due`.
+pub fn label(due: DateTime<Utc>, display: DueDisplay) -> String {
+    match display {
+        DueDisplay::Relative => relative(due.date_naive(), Local::now().date_naive()),
+        DueDisplay::Absolute => full(due),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_relative_and_full() {
+        i18n::set_language(Some("en"));
+        let today = NaiveDate::from_ymd_opt(2025, 3, 4).unwrap();
+        let days = |n: i64| today + chrono::Duration::days(n);
+        assert_eq!(relative(today, today), "today");
+        assert_eq!(relative(days(1), today), "tomorrow");
+        assert_eq!(relative(days(-1), today), "yesterday");
+        assert_eq!(relative(days(3), today), "in 3 d");
+        assert_eq!(relative(days(20), today), "in 2 w");
+        assert_eq!(relative(days(-5), today), "5 d overdue");
+        assert_eq!(relative(days(-15), today), "2 w overdue");
+
+        let due = Utc.with_ymd_and_hms(2025, 3, 4, 23, 59, 59).unwrap();
+        assert_eq!(full(due), "Tue Mar 4, 2025");
+        assert_eq!(label(due, DueDisplay::Absolute), "Tue Mar 4, 2025");
+        assert_eq!(DueDisplay::Relative.toggled(), DueDisplay::Absolute);
+    }
+}
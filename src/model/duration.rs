@@ -0,0 +1,137 @@
+// File: ./src/model/duration.rs
+// Estimated durations, in whole minutes: typed in the smart syntax ("~90m", "~2h"), shown
+// rounded in the lists and written to the ICS in ISO 8601 ("PT90M", "P1D").
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Units of the smart syntax in minutes, largest first. A month is 30 days, a year 365.
+const UNITS: [(u32, &str); 6] = [
+    (525600, "y"),
+    (43200, "mo"),
+    (10080, "w"),
+    (1440, "d"),
+    (60, "h"),
+    (1, "m"),
+];
+
+/// Stored as its minutes in the caches and the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Duration(u32);
+
+impl Duration {
+    pub const fn from_minutes(minutes: u32) -> Self {
+        Duration(minutes)
+    }
+
+    pub const fn minutes(self) -> u32 {
+        self.0
+    }
+
+    /// "30m" (or "30min"), "2h", "3d", "1w", "2mo" or "1y".
+    pub fn parse(text: &str) -> Option<Self> {
+        let lower = text.to_lowercase();
+        let lower = lower
+            .strip_suffix("in")
+            .filter(|n| n.ends_with('m'))
+            .unwrap_or(&lower);
+        UNITS.iter().find_map(|(size, unit)| {
+            let n: u32 = lower.strip_suffix(unit)?.parse().ok()?;
+            n.checked_mul(*size).map(Duration)
+        })
+    }
+
+    /// Rounded down to the largest unit: "45m", "2h", "3d", "1w", "2mo", "1y".
+    pub fn humanized(self) -> String {
+        let (size, unit) = UNITS
+            .iter()
+            .find(|(size, _)| self.0 >= *size)
+            .unwrap_or(&(1, "m"));
+        format!("{}{}", self.0 / size, unit)
+    }
+
+    /// In the largest unit that divides it, so that `parse` gives the same minutes back.
+    pub fn exact(self) -> String {
+        let (size, unit) = UNITS
+            .iter()
+            .find(|(size, _)| self.0 > 0 && self.0.is_multiple_of(*size))
+            .unwrap_or(&(1, "m"));
+        format!("{}{}", self.0 / size, unit)
+    }
+
+    pub fn to_iso8601(self) -> String {
+        format_iso8601(self.0.into())
+    }
+
+    /// Reads "PT1H30M", "P2D", "P1W"…; units it does not know are skipped. None for zero.
+    pub fn from_iso8601(text: &str) -> Option<Self> {
+        let mut minutes: u32 = 0;
+        let mut num = String::new();
+        let mut in_time = false;
+        for c in text.chars() {
+            if c == 'T' {
+                in_time = true;
+            } else if c.is_ascii_digit() {
+                num.push(c);
+            } else if !num.is_empty() {
+                let n = std::mem::take(&mut num).parse::<u32>().unwrap_or(0);
+                let size = match c {
+                    'W' => 10080,
+                    'D' => 1440,
+                    'H' if in_time => 60,
+                    'M' if in_time => 1,
+                    _ => 0,
+                };
+                minutes = minutes.saturating_add(n.saturating_mul(size));
+            }
+        }
+        (minutes > 0).then_some(Duration(minutes))
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.humanized())
+    }
+}
+
+/// Minutes in ISO 8601, in days or hours when they divide them: "P2D", "PT3H", "PT90M".
+/// Also used for the (unsigned) offsets of reminders.
+pub fn format_iso8601(minutes: u64) -> String {
+    if minutes.is_multiple_of(24 * 60) {
+        format!("P{}D", minutes / (24 * 60))
+    } else if minutes.is_multiple_of(60) {
+        format!("PT{}H", minutes / 60)
+    } else {
+        format!("PT{}M", minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duration_formats() {
+        let parse = |s: &str| Duration::parse(s).map(Duration::minutes);
+        assert_eq!(parse("30m"), Some(30));
+        assert_eq!(parse("45MIN"), Some(45));
+        assert_eq!(parse("2h"), Some(120));
+        assert_eq!(parse("2mo"), Some(86400));
+        assert_eq!(parse("1y"), Some(525600));
+        assert_eq!(parse("soon"), None);
+        assert_eq!(parse("9999999y"), None);
+
+        let ninety = Duration::from_minutes(90);
+        assert_eq!(ninety.to_string(), "1h");
+        assert_eq!(ninety.exact(), "90m");
+        assert_eq!(Duration::from_minutes(2880).exact(), "2d");
+        assert_eq!(Duration::parse(&ninety.exact()), Some(ninety));
+
+        assert_eq!(ninety.to_iso8601(), "PT90M");
+        assert_eq!(Duration::from_minutes(1440).to_iso8601(), "P1D");
+        assert_eq!(Duration::from_iso8601("PT1H30M"), Some(ninety));
+        assert_eq!(Duration::from_iso8601("P1W"), Duration::parse("1w"));
+        assert_eq!(Duration::from_iso8601("PT0S"), None);
+    }
+}
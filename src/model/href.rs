@@ -0,0 +1,125 @@
+// File: ./src/model/href.rs
+// One spelling per href. Servers list "/cal/Meine%20Aufgaben/", "/cal/Meine Aufgaben/" or
+// "/cal/M%c3%bcll/" for the same collection depending on the request, and some refuse the
+// spelling they did not list. Hrefs are normalized wherever they enter (server answers, the
+// cache keys, the offline queue), so comparisons hold and requests get a valid URL.
+
+/// Characters never percent-encoded (RFC 3986 "unreserved").
+fn is_unreserved(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~')
+}
+
+/// Delimiters kept as they are: encoding them could change what the href points to.
+fn is_delimiter(b: u8) -> bool {
+    b"/:@?#!$&'()*+,;=".contains(&b)
+}
+
+/// Percent-escapes in upper case, unreserved characters decoded, and spaces, non-ASCII and
+/// whatever else a URL can't hold encoded (as UTF-8). A '%' not starting an escape is
+/// encoded too. Idempotent; scheme and host, if any, are left alone.
+pub fn normalize(href: &str) -> String {
+    let bytes = href.as_bytes();
+    let mut out = String::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let escaped = if b == b'%' {
+            href.get(i + 1..i + 3)
+                .filter(|hex| hex.bytes().all(|c| c.is_ascii_hexdigit()))
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        } else {
+            None
+        };
+        match escaped {
+            Some(decoded) if is_unreserved(decoded) => out.push(decoded as char),
+            Some(decoded) => out.push_str(&format!("%{:02X}", decoded)),
+            None if is_unreserved(b) || is_delimiter(b) => out.push(b as char),
+            None => out.push_str(&format!("%{:02X}", b)),
+        }
+        i += if escaped.is_some() { 3 } else { 1 };
+    }
+    out
+}
+
+/// The normalized path of `href`, the form hrefs are stored in. Some servers answer with
+/// absolute URLs ("https://host/cal/x.ics"), whose host can be an internal one when behind a
+/// proxy, and a few with hrefs relative to the `collection` listed ("x.ics"). An empty
+/// `collection` leaves relative hrefs as they are.
+pub fn to_path(href: &str, collection: &str) -> String {
+    let href = normalize(href);
+    if let Some((scheme, rest)) = href.split_once("://")
+        && !scheme.is_empty()
+        && scheme
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.'))
+    {
+        return rest
+            .find('/')
+            .map_or("/".to_string(), |i| rest[i..].to_string());
+    }
+    if href.is_empty() || href.starts_with('/') || collection.is_empty() {
+        return href;
+    }
+    format!("{}/{}", to_path(collection, "").trim_end_matches('/'), href)
+}
+
+/// Whether two hrefs name the same resource.
+pub fn same(a: &str, b: &str) -> bool {
+    a == b || normalize(a) == normalize(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let canonical = "/dav/cal/Meine%20Aufgaben/M%C3%BCll.ics";
+        assert_eq!(normalize("/dav/cal/Meine Aufgaben/Müll.ics"), canonical);
+        assert_eq!(
+            normalize("/dav/cal/Meine%20Aufgaben/M%c3%bcll.ics"),
+            canonical
+        );
+        assert_eq!(normalize(canonical), canonical);
+        assert!(same(
+            "/dav/cal/Meine Aufgaben/",
+            "/dav/cal/Meine%20Aufgaben/"
+        ));
+
+        // Unreserved characters are decoded, delimiters keep their spelling
+        assert_eq!(normalize("/cal/%7Euser/a%2Db.ics"), "/cal/~user/a-b.ics");
+        assert_eq!(normalize("/cal/a%2fb/x+y@z.ics"), "/cal/a%2Fb/x+y@z.ics");
+        // A stray '%' is not an escape, a lone one at the end neither
+        assert_eq!(normalize("/cal/100%/x%zz%"), "/cal/100%25/x%25zz%25");
+        assert_eq!(
+            normalize("https://dav.example.com:8443/cal/Ä b/"),
+            "https://dav.example.com:8443/cal/%C3%84%20b/"
+        );
+        assert_eq!(normalize(""), "");
+    }
+
+    #[test]
+    fn test_to_path() {
+        let path = "/dav/cal/work/x.ics";
+        assert_eq!(
+            to_path("https://dav.example.com/dav/cal/work/x.ics", ""),
+            path
+        );
+        assert_eq!(
+            to_path("http://10.0.0.5:5232/dav/cal/work/x.ics", "/other/"),
+            path
+        );
+        assert_eq!(to_path(path, "/dav/cal/work/"), path);
+        assert_eq!(to_path("x.ics", "/dav/cal/work/"), path);
+        assert_eq!(
+            to_path("x.ics", "https://dav.example.com/dav/cal/work"),
+            path
+        );
+        assert_eq!(to_path("x.ics", ""), "x.ics");
+        assert_eq!(to_path("https://dav.example.com", ""), "/");
+        assert_eq!(
+            to_path("https://h/cal/Meine Aufgaben/", ""),
+            "/cal/Meine%20Aufgaben/"
+        );
+    }
+}
@@ -0,0 +1,101 @@
+// File: ./src/model/inbox.rs
+// The inbox: the calendar quick-added tasks land in (`inbox_calendar`), and the triage that
+// later files each of them into another calendar with its tags and dates.
+use crate::model::{CalendarListEntry, LOCAL_CALENDAR_HREF, Task};
+use std::collections::HashSet;
+
+/// The href of the inbox named (or given by href) in the config, when that calendar exists.
+pub fn inbox_href(inbox: Option<&str>, calendars: &[CalendarListEntry]) -> Option<String> {
+    let wanted = inbox?;
+    calendars
+        .iter()
+        .find(|c| c.name == wanted || c.href == wanted)
+        .map(|c| c.href.clone())
+        .or_else(|| (wanted == LOCAL_CALENDAR_HREF).then(|| wanted.to_string()))
+}
+
+/// UIDs for the triage: the open tasks of the inbox, oldest first. Subtasks are filed along
+/// with their parent, so only those whose parent is elsewhere are queued.
+pub fn triage_queue(inbox: &[Task]) -> Vec<String> {
+    let uids: HashSet<&str> = inbox.iter().map(|t| t.uid.as_str()).collect();
+    let mut open: Vec<&Task> = inbox
+        .iter()
+        .filter(|t| !t.status.is_done())
+        .filter(|t| t.parent_uid.as_deref().is_none_or(|p| !uids.contains(p)))
+        .collect();
+    open.sort_by_key(|t| t.created);
+    open.into_iter().map(|t| t.uid.clone()).collect()
+}
+
+/// The calendars inbox tasks can be filed into: the writable, synced ones but the inbox.
+pub fn filing_targets(
+    calendars: &[CalendarListEntry],
+    inbox_href: &str,
+    disabled: &HashSet<String>,
+) -> Vec<CalendarListEntry> {
+    calendars
+        .iter()
+        .filter(|c| c.href != inbox_href && !c.is_read_only && !disabled.contains(&c.href))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::TaskStatus;
+    use chrono::{Duration, Utc};
+    use std::collections::HashMap;
+
+    fn calendar(name: &str, href: &str) -> CalendarListEntry {
+        CalendarListEntry {
+            name: name.to_string(),
+            href: href.to_string(),
+            color: None,
+            is_shared: false,
+            is_read_only: false,
+        }
+    }
+
+    #[test]
+    fn test_triage_queue() {
+        let calendars = vec![
+            calendar("Inbox", "/cal/inbox/"),
+            calendar("Work", "/cal/work/"),
+            CalendarListEntry {
+                is_read_only: true,
+                ..calendar("Holidays", "/cal/holidays/")
+            },
+        ];
+        assert_eq!(
+            inbox_href(Some("Inbox"), &calendars).as_deref(),
+            Some("/cal/inbox/")
+        );
+        assert_eq!(inbox_href(Some("Gone"), &calendars), None);
+        assert_eq!(
+            inbox_href(Some(LOCAL_CALENDAR_HREF), &calendars).as_deref(),
+            Some(LOCAL_CALENDAR_HREF)
+        );
+        let targets = filing_targets(&calendars, "/cal/inbox/", &HashSet::new());
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].name, "Work");
+
+        let now = Utc::now();
+        let task = |uid: &str, age: i64| {
+            let mut t = Task::new(uid, &HashMap::new());
+            t.uid = uid.to_string();
+            t.created = Some(now - Duration::hours(age));
+            t
+        };
+        let newer = task("newer", 1);
+        let older = task("older", 5);
+        let mut child = task("child", 9);
+        child.parent_uid = Some("older".to_string());
+        let mut orphan = task("orphan", 3);
+        orphan.parent_uid = Some("filed-already".to_string());
+        let mut done = task("done", 7);
+        done.status = TaskStatus::Completed;
+        let inbox = vec![newer, older, child, orphan, done];
+        assert_eq!(triage_queue(&inbox), vec!["older", "orphan", "newer"]);
+    }
+}
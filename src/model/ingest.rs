@@ -0,0 +1,136 @@
+// File: ./src/model/ingest.rs
+// Turns iTIP payloads (ICS attached to invitation emails) into task creations and updates
+use crate::model::{Task, TaskStatus};
+
+/// The iTIP METHOD of a payload. Files without METHOD are plain exports and imported as-is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItipMethod {
+    Request,
+    Cancel,
+    Publish,
+}
+
+/// What to do with one incoming VTODO.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IngestOp {
+    Create(Task),
+    Update(Task),
+}
+
+/// Finds the VCALENDAR in `raw` (which may be a whole email) and parses its VTODOs.
+pub fn parse_itip(raw: &str, calendar_href: &str) -> Result<(ItipMethod, Vec<Task>), String> {
+    let start = raw
+        .find("BEGIN:VCALENDAR")
+        .ok_or("No calendar data found")?;
+    let end = raw[start..]
+        .find("END:VCALENDAR")
+        .map(|i| start + i + "END:VCALENDAR".len())
+        .ok_or("Calendar data is truncated")?;
+    let ics = &raw[start..end];
+
+    let method = ics
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("METHOD:"))
+        .map(|m| m.trim().to_uppercase());
+    let method = match method.as_deref() {
+        Some("REQUEST") => ItipMethod::Request,
+        Some("CANCEL") => ItipMethod::Cancel,
+        Some("PUBLISH") | None => ItipMethod::Publish,
+        Some(other) => return Err(format!("Unsupported iTIP method: {}", other)),
+    };
+
+    let tasks = Task::from_ics_all(ics, String::new(), String::new(), calendar_href.to_string())?
+        .into_iter()
+        .map(|mut t| {
+            t.href.clear();
+            // METHOD belongs to the message, not to the stored object
+            t.unmapped_properties.retain(|p| p.key != "METHOD");
+            t
+        })
+        .collect();
+    Ok((method, tasks))
+}
+
+fn sequence(task: &Task) -> i64 {
+    task.unmapped_properties
+        .iter()
+        .find(|p| p.key == "SEQUENCE")
+        .and_then(|p| p.value.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Decides how each incoming task affects the `existing` ones (matched by UID).
+/// - REQUEST/PUBLISH create unknown tasks and update known ones, keeping the local status,
+///   parent and location; outdated revisions (lower SEQUENCE) are skipped.
+/// - CANCEL marks known tasks as cancelled and ignores unknown ones.
+pub fn plan_ingest(method: ItipMethod, incoming: Vec<Task>, existing: &[Task]) -> Vec<IngestOp> {
+    let mut ops = Vec::new();
+    for task in incoming {
+        let known = existing.iter().find(|t| t.uid == task.uid);
+        match (method, known) {
+            (ItipMethod::Cancel, Some(old)) => {
+                if old.status != TaskStatus::Cancelled {
+                    let mut cancelled = old.clone();
+                    cancelled.status = TaskStatus::Cancelled;
+                    ops.push(IngestOp::Update(cancelled));
+                }
+            }
+            (ItipMethod::Cancel, None) => {}
+            (_, Some(old)) => {
+                if sequence(&task) < sequence(old) {
+                    continue;
+                }
+                let mut updated = task;
+                updated.href = old.href.clone();
+                updated.etag = old.etag.clone();
+                updated.calendar_href = old.calendar_href.clone();
+                updated.status = old.status;
+                updated.completed = old.completed;
+                updated.parent_uid = old.parent_uid.clone();
+                ops.push(IngestOp::Update(updated));
+            }
+            (_, None) => ops.push(IngestOp::Create(task)),
+        }
+    }
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INVITE: &str = "Content-Type: text/calendar; method=REQUEST\r
+\r
+BEGIN:VCALENDAR\r
+VERSION:2.0\r
+METHOD:REQUEST\r
+BEGIN:VTODO\r
+UID:invite-1\r
+SEQUENCE:2\r
+SUMMARY:Review budget\r
+END:VTODO\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_request_and_cancel() {
+        let (method, tasks) = parse_itip(INVITE, "/cal/").unwrap();
+        assert_eq!(method, ItipMethod::Request);
+        assert_eq!(tasks[0].summary, "Review budget");
+
+        let ops = plan_ingest(method, tasks.clone(), &[]);
+        assert!(matches!(&ops[0], IngestOp::Create(t) if t.calendar_href == "/cal/"));
+
+        let mut existing = tasks[0].clone();
+        existing.calendar_href = "/work/".to_string();
+        existing.status = TaskStatus::InProcess;
+        let ops = plan_ingest(method, tasks.clone(), std::slice::from_ref(&existing));
+        assert!(matches!(&ops[0], IngestOp::Update(t)
+            if t.calendar_href == "/work/" && t.status == TaskStatus::InProcess));
+
+        let cancel = INVITE.replace("METHOD:REQUEST", "METHOD:CANCEL");
+        let (method, tasks) = parse_itip(&cancel, "/cal/").unwrap();
+        let ops = plan_ingest(method, tasks, &[existing]);
+        assert!(matches!(&ops[0], IngestOp::Update(t) if t.status == TaskStatus::Cancelled));
+    }
+}
@@ -1,5 +1,7 @@
 // File: src/model/item.rs
-use chrono::{DateTime, Utc};
+use crate::model::duration::Duration;
+use crate::model::priority::Priority;
+use chrono::{DateTime, SubsecRound, Utc};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
@@ -10,6 +12,31 @@ pub struct CalendarListEntry {
     pub name: String,
     pub href: String,
     pub color: Option<String>,
+    /// True when the collection is owned by another principal (shared or delegated).
+    #[serde(default)]
+    pub is_shared: bool,
+    /// Set when the server does not grant us write privileges on the collection.
+    #[serde(default)]
+    pub is_read_only: bool,
+}
+
+/// Longest calendar name on the badges of the task rows
+pub const BADGE_NAME_LEN: usize = 12;
+
+/// Characters of a description shown where it is not asked for: the rest is shown on demand,
+/// as laying out pasted notes of hundreds of KiB at every redraw is slow.
+pub const DESCRIPTION_PREVIEW_LEN: usize = 2000;
+
+impl CalendarListEntry {
+    /// The name cut to `max` characters, ending with `ellipsis` when cut.
+    pub fn short_name(&self, max: usize, ellipsis: &str) -> String {
+        if self.name.chars().count() <= max {
+            return self.name.clone();
+        }
+        let mut short: String = self.name.chars().take(max.saturating_sub(1)).collect();
+        short.push_str(ellipsis);
+        short
+    }
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
@@ -26,6 +53,29 @@ impl TaskStatus {
     }
 }
 
+/// What a resource holds: a task (VTODO), or a note (VJOURNAL) that some servers keep in the
+/// same collections. Notes have a title, a description and tags; they are never done or due.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ItemKind {
+    #[default]
+    Todo,
+    Journal,
+}
+
+impl ItemKind {
+    pub fn is_todo(&self) -> bool {
+        *self == Self::Todo
+    }
+
+    /// Name of the iCalendar component
+    pub fn component(&self) -> &'static str {
+        match self {
+            Self::Todo => "VTODO",
+            Self::Journal => "VJOURNAL",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct RawProperty {
     pub key: String,
@@ -33,16 +83,29 @@ pub struct RawProperty {
     pub params: Vec<(String, String)>,
 }
 
+/// A VALARM of the task. The notifier announces it on top of `notify_before_minutes`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Reminder {
+    At(DateTime<Utc>),
+    /// Minutes before the due date (negative: after it)
+    BeforeDue(i64),
+    /// Minutes before the start date, or the due date when there is none
+    BeforeStart(i64),
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Task {
     pub uid: String,
+    /// Tasks written before notes were read have no kind stored
+    #[serde(default, skip_serializing_if = "ItemKind::is_todo")]
+    pub kind: ItemKind,
     pub summary: String,
     pub description: String,
     pub status: TaskStatus,
-    pub estimated_duration: Option<u32>,
+    pub estimated_duration: Option<Duration>,
     pub due: Option<DateTime<Utc>>,
     pub dtstart: Option<DateTime<Utc>>,
-    pub priority: u8,
+    pub priority: Priority,
     pub parent_uid: Option<String>,
     pub dependencies: Vec<String>,
     pub etag: String,
@@ -51,22 +114,58 @@ pub struct Task {
     pub categories: Vec<String>,
     pub depth: usize,
     pub rrule: Option<String>,
+    /// Next occurrence is computed from the completion date instead of the schedule.
+    #[serde(default)]
+    pub recur_from_completion: bool,
+    /// EXDATE: occurrences left out of the series (skipped here or removed by another client).
+    /// Date-only values are kept as midnight and exclude that whole day.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exdates: Vec<DateTime<Utc>>,
+    /// CREATED, LAST-MODIFIED and COMPLETED. Maintained by `touch` whenever the task is saved.
+    #[serde(default)]
+    pub created: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_modified: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub completed: Option<DateTime<Utc>>,
+    /// X-OC-HIDESUBTASKS (Nextcloud Tasks): children are folded away in tree views.
+    #[serde(default)]
+    pub hide_subtasks: bool,
+    /// X-APPLE-SORT-ORDER (Nextcloud Tasks manual ordering). Breaks ties before the summary.
+    #[serde(default)]
+    pub sort_order: Option<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reminders: Vec<Reminder>,
+    /// View helper: number of direct children folded away by `hide_subtasks`.
+    #[serde(skip)]
+    pub hidden_subtasks: usize,
     pub unmapped_properties: Vec<RawProperty>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub raw_components: Vec<String>,
 }
 
+impl Task {
+    /// The first `max` characters of the description, or None when it is not longer.
+    pub fn description_preview(&self, max: usize) -> Option<&str> {
+        self.description
+            .char_indices()
+            .nth(max)
+            .map(|(end, _)| &self.description[..end])
+    }
+}
+
 impl Task {
     pub fn new(input: &str, aliases: &HashMap<String, Vec<String>>) -> Self {
         let mut task = Self {
             uid: Uuid::new_v4().to_string(),
+            kind: ItemKind::Todo,
             summary: String::new(),
             description: String::new(),
             status: TaskStatus::NeedsAction,
             estimated_duration: None,
             due: None,
             dtstart: None,
-            priority: 0,
+            priority: Priority::None,
             parent_uid: None,
             dependencies: Vec::new(),
             etag: String::new(),
@@ -75,6 +174,15 @@ impl Task {
             categories: Vec::new(),
             depth: 0,
             rrule: None,
+            recur_from_completion: false,
+            exdates: Vec::new(),
+            created: None,
+            last_modified: None,
+            completed: None,
+            hide_subtasks: false,
+            sort_order: None,
+            reminders: Vec::new(),
+            hidden_subtasks: 0,
             unmapped_properties: Vec::new(),
             raw_components: Vec::new(),
         };
@@ -82,29 +190,30 @@ impl Task {
         task
     }
 
+    /// A note titled `title` as typed: no smart syntax is read.
+    pub fn new_note(title: &str) -> Self {
+        let mut note = Self::new("", &HashMap::new());
+        note.kind = ItemKind::Journal;
+        note.summary = title.trim().to_string();
+        note
+    }
+
+    pub fn is_note(&self) -> bool {
+        self.kind == ItemKind::Journal
+    }
+
     // --- View Helpers ---
 
     pub fn format_duration_short(&self) -> String {
-        if let Some(mins) = self.estimated_duration {
-            if mins >= 525600 {
-                format!("[~{}y]", mins / 525600)
-            } else if mins >= 43200 {
-                format!("[~{}mo]", mins / 43200)
-            } else if mins >= 10080 {
-                format!("[~{}w]", mins / 10080)
-            } else if mins >= 1440 {
-                format!("[~{}d]", mins / 1440)
-            } else if mins >= 60 {
-                format!("[~{}h]", mins / 60)
-            } else {
-                format!("[~{}m]", mins)
-            }
-        } else {
-            String::new()
-        }
+        self.estimated_duration
+            .map(|d| format!("[~{}]", d))
+            .unwrap_or_default()
     }
 
     pub fn checkbox_symbol(&self) -> &'static str {
+        if self.is_note() {
+            return "[=]";
+        }
         match self.status {
             TaskStatus::Completed => "[x]",
             TaskStatus::Cancelled => "[-]",
@@ -115,6 +224,36 @@ impl Task {
 
     // --- Logic ---
 
+    /// Updates the bookkeeping timestamps right before the task is written out.
+    pub fn touch(&mut self, is_new: bool) {
+        let now = Utc::now().trunc_subsecs(0);
+        if is_new && self.created.is_none() {
+            self.created = Some(now);
+        }
+        self.last_modified = Some(now);
+        match self.status {
+            TaskStatus::Completed => {
+                self.completed.get_or_insert(now);
+            }
+            _ => self.completed = None,
+        }
+    }
+
+    /// When the reminders go off. Relative ones are skipped while the date they follow is unset.
+    pub fn reminder_times(&self) -> Vec<DateTime<Utc>> {
+        self.reminders
+            .iter()
+            .filter_map(|r| match *r {
+                Reminder::At(t) => Some(t),
+                Reminder::BeforeDue(m) => self.due.map(|d| d - chrono::Duration::minutes(m)),
+                Reminder::BeforeStart(m) => self
+                    .dtstart
+                    .or(self.due)
+                    .map(|d| d - chrono::Duration::minutes(m)),
+            })
+            .collect()
+    }
+
     pub fn compare_with_cutoff(&self, other: &Self, cutoff: Option<DateTime<Utc>>) -> Ordering {
         fn status_prio(s: TaskStatus) -> u8 {
             match s {
@@ -163,13 +302,8 @@ impl Task {
             (false, false) => {}
         }
 
-        let p1 = if self.priority == 0 { 5 } else { self.priority };
-        let p2 = if other.priority == 0 {
-            5
-        } else {
-            other.priority
-        };
-
+        let p1 = self.priority.rank();
+        let p2 = other.priority.rank();
         if p1 != p2 {
             return p1.cmp(&p2);
         }
@@ -185,10 +319,23 @@ impl Task {
             _ => {}
         }
 
+        // Manual order set in Nextcloud; unordered tasks go last
+        match (self.sort_order, other.sort_order) {
+            (Some(o1), Some(o2)) if o1 != o2 => return o1.cmp(&o2),
+            (Some(_), None) => return Ordering::Less,
+            (None, Some(_)) => return Ordering::Greater,
+            _ => {}
+        }
+
         self.summary.cmp(&other.summary)
     }
 
-    pub fn organize_hierarchy(mut tasks: Vec<Task>, cutoff: Option<DateTime<Utc>>) -> Vec<Task> {
+    /// Builds the tree order. With `fold`, children of `hide_subtasks` parents are left out.
+    pub fn organize_hierarchy(
+        mut tasks: Vec<Task>,
+        cutoff: Option<DateTime<Utc>>,
+        fold: bool,
+    ) -> Vec<Task> {
         let present_uids: HashSet<String> = tasks.iter().map(|t| t.uid.clone()).collect();
         let mut children_map: HashMap<String, Vec<Task>> = HashMap::new();
         let mut roots: Vec<Task> = Vec::new();
@@ -214,7 +361,7 @@ impl Task {
 
         let mut result = Vec::new();
         for root in roots {
-            Self::append_task_and_children(&root, &mut result, &children_map, 0);
+            Self::append_task_and_children(&root, &mut result, &children_map, 0, fold);
         }
         result
     }
@@ -224,13 +371,20 @@ impl Task {
         result: &mut Vec<Task>,
         map: &HashMap<String, Vec<Task>>,
         depth: usize,
+        fold: bool,
     ) {
         let mut t = task.clone();
         t.depth = depth;
+        let children = map.get(&task.uid);
+        if fold && task.hide_subtasks {
+            t.hidden_subtasks = children.map_or(0, Vec::len);
+            result.push(t);
+            return;
+        }
         result.push(t);
-        if let Some(children) = map.get(&task.uid) {
+        if let Some(children) = children {
             for child in children {
-                Self::append_task_and_children(child, result, map, depth + 1);
+                Self::append_task_and_children(child, result, map, depth + 1, fold);
             }
         }
     }
@@ -1,8 +1,72 @@
 // File: ./src/model/matcher.rs
 // Handles logic for checking if a task matches a search query
+use crate::model::duration::Duration;
 use crate::model::item::{Task, TaskStatus};
 use chrono::Utc;
 
+/// Word operators and the symbols `matches_search_term` uses for them
+const FIELDS: [(&str, &str); 7] = [
+    ("tag", "#"),
+    ("due", "@"),
+    ("start", "^"),
+    ("prio", "!"),
+    ("priority", "!"),
+    ("est", "~"),
+    ("duration", "~"),
+];
+
+/// A search box query. Word operators are rewritten to the symbols (`tag:work` -> `#work`,
+/// `due<2024-07-01` -> `@<2024-07-01`, `prio>=5` -> `!>=5`) and `cal:` terms are kept apart,
+/// as matching them needs the calendar names.
+#[derive(Debug, Default, PartialEq)]
+pub struct SearchQuery {
+    /// The rest of the query, for `Task::matches_search_term`
+    pub text: String,
+    /// Lowercased `cal:` terms; a task matches if its calendar matches any of them
+    pub calendars: Vec<String>,
+}
+
+impl SearchQuery {
+    pub fn parse(term: &str) -> Self {
+        let mut query = SearchQuery::default();
+        let mut words = Vec::new();
+        for word in term.split_whitespace() {
+            let lower = word.to_lowercase();
+            if let Some(name) = lower.strip_prefix("cal:")
+                && !name.is_empty()
+            {
+                query.calendars.push(name.to_string());
+                continue;
+            }
+            let rewritten = FIELDS.iter().find_map(|(field, symbol)| {
+                let rest = lower.strip_prefix(field)?;
+                let value = if rest.starts_with(['<', '>']) {
+                    rest
+                } else {
+                    rest.strip_prefix([':', '='])?
+                };
+                (!value.is_empty()).then(|| format!("{}{}", symbol, value))
+            });
+            words.push(rewritten.unwrap_or(lower));
+        }
+        query.text = words.join(" ");
+        query
+    }
+
+    /// `name` is the display name of the calendar `href`, when known.
+    pub fn matches_calendar(&self, href: &str, name: Option<&str>) -> bool {
+        if self.calendars.is_empty() {
+            return true;
+        }
+        let href = href.to_lowercase();
+        let name = name.map(str::to_lowercase);
+        self.calendars.iter().any(|needle| {
+            name.as_deref().is_some_and(|n| n.contains(needle.as_str()))
+                || href.contains(needle.as_str())
+        })
+    }
+}
+
 impl Task {
     pub fn matches_search_term(&self, term: &str) -> bool {
         if term.is_empty() {
@@ -30,23 +94,7 @@ impl Task {
                 };
 
                 // Parse value
-                let mins = if let Some(n) = val_str.strip_suffix('m') {
-                    n.parse::<u32>().ok()
-                } else if let Some(n) = val_str.strip_suffix('h') {
-                    n.parse::<u32>().ok().map(|h| h * 60)
-                } else if let Some(n) = val_str.strip_suffix('d') {
-                    n.parse::<u32>().ok().map(|d| d * 1440)
-                } else if let Some(n) = val_str.strip_suffix('w') {
-                    n.parse::<u32>().ok().map(|w| w * 10080)
-                } else if let Some(n) = val_str.strip_suffix("mo") {
-                    n.parse::<u32>().ok().map(|m| m * 43200)
-                } else if let Some(n) = val_str.strip_suffix('y') {
-                    n.parse::<u32>().ok().map(|y| y * 525600)
-                } else {
-                    None
-                };
-
-                if let Some(target) = mins {
+                if let Some(target) = Duration::parse(val_str) {
                     match self.estimated_duration {
                         Some(d) => match op {
                             "<" => {
@@ -97,7 +145,7 @@ impl Task {
                 };
 
                 if let Ok(target) = val_str.parse::<u8>() {
-                    let p = self.priority;
+                    let p = self.priority.value();
                     match op {
                         "<" => {
                             if p >= target {
@@ -322,3 +370,30 @@ impl Task {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_search_operators() {
+        let query = SearchQuery::parse("tag:work due<2024-07-01 prio>=5 Report cal:Home est:30m");
+        assert_eq!(query.text, "#work @<2024-07-01 !>=5 report ~30m");
+        assert_eq!(query.calendars, ["home"]);
+        assert!(query.matches_calendar("/cals/a1b2/", Some("Home stuff")));
+        assert!(query.matches_calendar("/cals/home/", None));
+        assert!(!query.matches_calendar("/cals/work/", Some("Work")));
+        // Plain words that merely start like an operator are left alone
+        assert_eq!(SearchQuery::parse("tagline due:").text, "tagline due:");
+
+        let mut task = Task::new("Write report #work !6", &HashMap::new());
+        task.due = chrono::NaiveDate::from_ymd_opt(2024, 6, 15)
+            .and_then(|d| d.and_hms_opt(12, 0, 0))
+            .map(|d| d.and_utc());
+        let matches = |term: &str| task.matches_search_term(&SearchQuery::parse(term).text);
+        assert!(matches("tag:work due<2024-07-01 prio>=5 report"));
+        assert!(!matches("due>2024-07-01"));
+        assert!(!matches("priority<5"));
+    }
+}
@@ -1,8 +1,31 @@
 // File: src/model/mod.rs
 pub mod adapter;
+pub mod agenda;
+pub mod builder;
+pub mod dedupe;
+pub mod draft;
+pub mod due;
+pub mod duration;
+pub mod href;
+pub mod inbox;
+pub mod ingest;
 pub mod item;
 pub mod matcher;
 pub mod parser;
+pub mod priority;
+pub mod recurrence;
+pub mod report;
+pub mod share;
+pub mod syntax;
+pub mod template;
+pub mod validate;
 
-pub use item::{CalendarListEntry, Task, TaskStatus};
-pub use parser::extract_inline_aliases;
+pub use builder::TaskBuilder;
+pub use duration::Duration;
+pub use item::{CalendarListEntry, Reminder, Task, TaskStatus};
+pub use parser::{alias_preview, expand_aliases, extract_inline_aliases};
+pub use priority::Priority;
+
+/// Href of the calendar kept on disk only. Here rather than in `storage` so that the model
+/// (the inbox, for one) can name it in builds without the native modules.
+pub const LOCAL_CALENDAR_HREF: &str = "local://default";
@@ -1,30 +1,51 @@
 // File: src/model/parser.rs
 // Handles smart text input parsing
-use crate::model::item::Task;
-use chrono::{DateTime, Local, NaiveDate, Utc};
+use crate::model::duration::Duration;
+use crate::model::item::{Reminder, Task};
+use crate::model::priority::{self, Priority};
+use crate::model::syntax;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, Utc};
 use std::collections::HashMap;
 
 impl Task {
     pub fn apply_smart_input(&mut self, input: &str, aliases: &HashMap<String, Vec<String>>) {
+        // The title of a note is taken as typed; its other fields are left alone
+        if self.is_note() {
+            self.summary = input.trim().to_string();
+            return;
+        }
         let mut summary_words = Vec::new();
         // Reset fields
-        self.priority = 0;
+        self.priority = Priority::None;
         self.due = None;
         self.dtstart = None;
         self.rrule = None;
+        self.recur_from_completion = false;
         self.estimated_duration = None;
         self.categories.clear();
+        self.reminders.clear();
 
-        let tokens: Vec<&str> = input.split_whitespace().collect();
+        let syntax = syntax::current();
+        let tokens: Vec<String> = input
+            .split_whitespace()
+            .map(|w| syntax.normalize(w))
+            .collect();
         let mut i = 0;
 
         while i < tokens.len() {
-            let word = tokens[i];
+            let word = tokens[i].as_str();
 
-            // 1. Priority (!1 - !9)
+            // 1. Priority (!1 - !9, or a band name: !high)
             if word.starts_with('!')
                 && let Ok(p) = word[1..].parse::<u8>()
                 && (1..=9).contains(&p)
+            {
+                self.priority = Priority::Level(p);
+                i += 1;
+                continue;
+            }
+            if let Some(name) = word.strip_prefix('!')
+                && let Some(p) = priority::value_of(name)
             {
                 self.priority = p;
                 i += 1;
@@ -33,9 +54,9 @@ impl Task {
 
             // 2. Duration (est:30m, ~30m)
             if let Some(val) = word.strip_prefix("est:").or_else(|| word.strip_prefix('~'))
-                && let Some(m) = parse_duration(val)
+                && let Some(d) = Duration::parse(val)
             {
-                self.estimated_duration = Some(m);
+                self.estimated_duration = Some(d);
                 i += 1;
                 continue;
             }
@@ -48,21 +69,9 @@ impl Task {
                         self.categories.push(cat.clone());
                     }
 
-                    // Apply aliases recursively (e.g. #a:b -> check alias for #a:b, then #a)
-                    let mut search = cat.as_str();
-                    loop {
-                        if let Some(expanded_tags) = aliases.get(search) {
-                            for extra_tag in expanded_tags {
-                                if !self.categories.contains(extra_tag) {
-                                    self.categories.push(extra_tag.clone());
-                                }
-                            }
-                        }
-                        // Move up hierarchy
-                        if let Some(idx) = search.rfind(':') {
-                            search = &search[..idx];
-                        } else {
-                            break;
+                    for extra_tag in alias_targets(&cat, aliases) {
+                        if !self.categories.contains(&extra_tag) {
+                            self.categories.push(extra_tag);
                         }
                     }
 
@@ -84,8 +93,8 @@ impl Task {
             // 5. Explicit Recurrence with interval (rec:every 2 days)
             // Or synonym (@every 2 days)
             if (word == "rec:every" || word == "@every") && i + 2 < tokens.len() {
-                let amount_str = tokens[i + 1];
-                let unit_str = tokens[i + 2];
+                let amount_str = &tokens[i + 1];
+                let unit_str = &tokens[i + 2];
                 if let Ok(interval) = amount_str.parse::<u32>() {
                     let freq = parse_freq_unit(unit_str);
                     if !freq.is_empty() {
@@ -96,6 +105,13 @@ impl Task {
                 }
             }
 
+            // 5b. Recur from completion date instead of schedule (rec:fromdone, @fromdone)
+            if word == "rec:fromdone" || word == "@fromdone" {
+                self.recur_from_completion = true;
+                i += 1;
+                continue;
+            }
+
             // 6. Due Date (due:2025-01-01, @2025-01-01)
             if let Some(val) = word.strip_prefix("due:").or_else(|| word.strip_prefix('@'))
                 && let Some(dt) = parse_smart_date(val, true)
@@ -118,6 +134,18 @@ impl Task {
                 continue;
             }
 
+            // 8. Reminders (rem:2d before due, rem:+1h after it, rem:^15m before start,
+            // rem:2025-03-01T09:00 at a fixed time)
+            if let Some(val) = word.strip_prefix("rem:")
+                && let Some(reminder) = parse_reminder(val)
+            {
+                if !self.reminders.contains(&reminder) {
+                    self.reminders.push(reminder);
+                }
+                i += 1;
+                continue;
+            }
+
             // Fallback: Add to summary
             summary_words.push(word);
             i += 1;
@@ -127,60 +155,68 @@ impl Task {
 
     pub fn to_smart_string(&self) -> String {
         let mut s = self.summary.clone();
+        if self.is_note() {
+            return s;
+        }
+        // Symbols, words and dates as configured in [smart_syntax], which the parser reads back
+        let syntax = syntax::current();
 
         // Priority: !1
-        if self.priority > 0 {
-            s.push_str(&format!(" !{}", self.priority));
+        if self.priority.is_set() {
+            s.push_str(&format!(" {}{}", syntax.output_prefix("!"), self.priority));
         }
 
         // Start: ^YYYY-MM-DD
         if let Some(start) = self.dtstart {
-            s.push_str(&format!(" ^{}", start.format("%Y-%m-%d")));
+            let date = syntax.format_date(start.date_naive());
+            s.push_str(&format!(" {}{}", syntax.output_prefix("^"), date));
         }
 
         // Due: @YYYY-MM-DD
         if let Some(d) = self.due {
-            s.push_str(&format!(" @{}", d.format("%Y-%m-%d")));
+            let date = syntax.format_date(d.date_naive());
+            s.push_str(&format!(" {}{}", syntax.output_prefix("@"), date));
         }
 
         // Duration: ~30m
-        if let Some(mins) = self.estimated_duration {
-            let dur_str = if mins >= 525600 {
-                format!("~{}y", mins / 525600)
-            } else if mins >= 43200 {
-                format!("~{}mo", mins / 43200)
-            } else if mins >= 10080 {
-                format!("~{}w", mins / 10080)
-            } else if mins >= 1440 {
-                format!("~{}d", mins / 1440)
-            } else if mins >= 60 {
-                format!("~{}h", mins / 60)
-            } else {
-                format!("~{}m", mins)
-            };
-            s.push_str(&format!(" {}", dur_str));
+        if let Some(d) = self.estimated_duration {
+            s.push_str(&format!(" {}{}", syntax.output_prefix("~"), d.exact()));
         }
 
         // Recurrence: @weekly or @every ...
         if let Some(r) = &self.rrule {
-            if r == "FREQ=DAILY" {
-                s.push_str(" @daily");
-            } else if r == "FREQ=WEEKLY" {
-                s.push_str(" @weekly");
-            } else if r == "FREQ=MONTHLY" {
-                s.push_str(" @monthly");
-            } else if r == "FREQ=YEARLY" {
-                s.push_str(" @yearly");
+            let keyword = match r.as_str() {
+                "FREQ=DAILY" => Some("daily"),
+                "FREQ=WEEKLY" => Some("weekly"),
+                "FREQ=MONTHLY" => Some("monthly"),
+                "FREQ=YEARLY" => Some("yearly"),
+                _ => None,
+            };
+            if let Some(keyword) = keyword {
+                s.push_str(&format!(" @{}", syntax.output_word(keyword)));
             } else if let Some(simple) = reconstruct_simple_rrule(r) {
                 s.push_str(&format!(" {}", simple));
             } else {
-                s.push_str(" rec:custom"); // Fallback for complex RRULEs
+                s.push_str(&format!(" rec:{}", r)); // Raw RRULE for complex rules
             }
+            if self.recur_from_completion {
+                s.push_str(" @fromdone");
+            }
+        }
+
+        // Reminders: rem:2d
+        for reminder in &self.reminders {
+            let token = match *reminder {
+                Reminder::At(t) => t.format("%Y-%m-%dT%H:%M").to_string(),
+                Reminder::BeforeDue(m) => format_offset(m),
+                Reminder::BeforeStart(m) => format!("^{}", format_offset(m)),
+            };
+            s.push_str(&format!(" rem:{}", token));
         }
 
         // Tags: #tag
         for cat in &self.categories {
-            s.push_str(&format!(" #{}", cat));
+            s.push_str(&format!(" {}{}", syntax.output_prefix("#"), cat));
         }
         s
     }
@@ -222,12 +258,71 @@ pub fn extract_inline_aliases(input: &str) -> (String, HashMap<String, Vec<Strin
     (cleaned_words.join(" "), new_aliases)
 }
 
+/// The tags each alias in `input` would add, in order: `(alias, tags)`. Inline definitions
+/// (#alias=#tag1,#tag2) count too. Used for the live preview under the input boxes.
+pub fn expand_aliases(
+    input: &str,
+    aliases: &HashMap<String, Vec<String>>,
+) -> Vec<(String, Vec<String>)> {
+    let (clean_input, inline) = extract_inline_aliases(input);
+    let mut aliases = aliases.clone();
+    aliases.extend(inline);
+
+    let syntax = syntax::current();
+    let mut expansions: Vec<(String, Vec<String>)> = Vec::new();
+    for word in clean_input.split_whitespace().map(|w| syntax.normalize(w)) {
+        if let Some(tag) = word.strip_prefix('#')
+            && !expansions.iter().any(|(t, _)| t == tag)
+        {
+            let added = alias_targets(tag, &aliases);
+            if !added.is_empty() {
+                expansions.push((tag.to_string(), added));
+            }
+        }
+    }
+    expansions
+}
+
+/// One line describing `expand_aliases`, e.g. "#work → #dev #rust"; None without aliases.
+pub fn alias_preview(input: &str, aliases: &HashMap<String, Vec<String>>) -> Option<String> {
+    let parts: Vec<String> = expand_aliases(input, aliases)
+        .into_iter()
+        .map(|(tag, added)| {
+            let added: Vec<String> = added.iter().map(|t| format!("#{}", t)).collect();
+            format!("#{} → {}", tag, added.join(" "))
+        })
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
+
 // --- Helpers ---
 
+/// Tags the aliases add to `tag`, walking up its hierarchy (#a:b uses the aliases of a:b and a).
+fn alias_targets(tag: &str, aliases: &HashMap<String, Vec<String>>) -> Vec<String> {
+    let mut targets: Vec<String> = Vec::new();
+    let mut search = tag;
+    loop {
+        for extra_tag in aliases.get(search).into_iter().flatten() {
+            if extra_tag != tag && !targets.contains(extra_tag) {
+                targets.push(extra_tag.clone());
+            }
+        }
+        match search.rfind(':') {
+            Some(idx) => search = &search[..idx],
+            None => break,
+        }
+    }
+    targets
+}
+
 fn reconstruct_simple_rrule(rrule: &str) -> Option<String> {
     // Basic parser to handle FREQ=X;INTERVAL=Y -> @every Y X(s)
     let parts: HashMap<&str, &str> = rrule.split(';').filter_map(|s| s.split_once('=')).collect();
 
+    if parts.keys().any(|k| *k != "FREQ" && *k != "INTERVAL") {
+        return None;
+    }
+
     let freq = parts.get("FREQ")?;
     let interval = parts.get("INTERVAL").unwrap_or(&"1");
 
@@ -242,25 +337,39 @@ fn reconstruct_simple_rrule(rrule: &str) -> Option<String> {
     Some(format!("@every {} {}", interval, unit))
 }
 
-fn parse_duration(val: &str) -> Option<u32> {
-    let lower = val.to_lowercase();
-    if let Some(n) = lower.strip_suffix("min") {
-        return n.parse::<u32>().ok();
+/// "2d" before the due date, "+1h" after it, "^15m" before the start, or a UTC date and time.
+fn parse_reminder(val: &str) -> Option<Reminder> {
+    if let Ok(at) = NaiveDateTime::parse_from_str(val, "%Y-%m-%dT%H:%M") {
+        return Some(Reminder::At(at.and_utc()));
     }
-    if let Some(n) = lower.strip_suffix('m') {
-        return n.parse::<u32>().ok();
-    } else if let Some(n) = lower.strip_suffix('h') {
-        return n.parse::<u32>().ok().map(|h| h * 60);
-    } else if let Some(n) = lower.strip_suffix('d') {
-        return n.parse::<u32>().ok().map(|d| d * 24 * 60);
-    } else if let Some(n) = lower.strip_suffix('w') {
-        return n.parse::<u32>().ok().map(|w| w * 7 * 24 * 60);
-    } else if let Some(n) = lower.strip_suffix("mo") {
-        return n.parse::<u32>().ok().map(|mo| mo * 30 * 24 * 60);
-    } else if let Some(n) = lower.strip_suffix('y') {
-        return n.parse::<u32>().ok().map(|y| y * 365 * 24 * 60);
+    let (before_start, val) = match val.strip_prefix('^') {
+        Some(rest) => (true, rest),
+        None => (false, val),
+    };
+    let (after, val) = match val.strip_prefix('+') {
+        Some(rest) => (true, rest),
+        None => (false, val),
+    };
+    let mins = Duration::parse(val)?.minutes() as i64;
+    let mins = if after { -mins } else { mins };
+    Some(if before_start {
+        Reminder::BeforeStart(mins)
+    } else {
+        Reminder::BeforeDue(mins)
+    })
+}
+
+/// Reminder offsets are written exactly, in the largest unit that divides them ("+" = after).
+fn format_offset(mins: i64) -> String {
+    let sign = if mins < 0 { "+" } else { "" };
+    let mins = mins.unsigned_abs();
+    match [(10080, "w"), (1440, "d"), (60, "h")]
+        .into_iter()
+        .find(|(n, _)| mins > 0 && mins.is_multiple_of(*n))
+    {
+        Some((n, unit)) => format!("{}{}{}", sign, mins / n, unit),
+        None => format!("{}{}m", sign, mins),
     }
-    None
 }
 
 fn parse_recurrence(val: &str) -> Option<String> {
@@ -269,6 +378,8 @@ fn parse_recurrence(val: &str) -> Option<String> {
         "weekly" => Some("FREQ=WEEKLY".to_string()),
         "monthly" => Some("FREQ=MONTHLY".to_string()),
         "yearly" => Some("FREQ=YEARLY".to_string()),
+        // Raw RRULE, as written by the recurrence builders (rec:FREQ=WEEKLY;BYDAY=MO,WE)
+        raw if raw.starts_with("FREQ=") => Some(raw.to_string()),
         _ => None,
     }
 }
@@ -288,7 +399,7 @@ fn parse_freq_unit(unit: &str) -> &'static str {
     }
 }
 
-fn parse_smart_date(val: &str, end_of_day: bool) -> Option<DateTime<Utc>> {
+pub fn parse_smart_date(val: &str, end_of_day: bool) -> Option<DateTime<Utc>> {
     // 1. Specific Date YYYY-MM-DD
     if let Ok(date) = NaiveDate::parse_from_str(val, "%Y-%m-%d") {
         return finalize_date(date, end_of_day);
@@ -329,3 +440,69 @@ fn finalize_date(d: NaiveDate, end_of_day: bool) -> Option<DateTime<Utc>> {
     };
     Some(t.and_utc())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_aliases_preview() {
+        let aliases = HashMap::from([
+            (
+                "cfait".to_string(),
+                vec!["dev".to_string(), "rust".to_string()],
+            ),
+            ("home".to_string(), vec!["personal".to_string()]),
+        ]);
+        // Parent aliases apply to sub-tags; plain tags and repeats add nothing
+        let expansions = expand_aliases("Fix sync #cfait:gui #misc #cfait:gui", &aliases);
+        assert_eq!(
+            expansions,
+            vec![(
+                "cfait:gui".to_string(),
+                vec!["dev".to_string(), "rust".to_string()]
+            )]
+        );
+        // Matches what the parser adds
+        let task = Task::new("Fix sync #cfait:gui", &aliases);
+        assert_eq!(task.categories, ["cfait:gui", "dev", "rust"]);
+
+        assert_eq!(
+            alias_preview("Mow #home #garden=#outside", &aliases).as_deref(),
+            Some("#home → #personal, #garden → #outside")
+        );
+        assert_eq!(alias_preview("Plain task", &aliases), None);
+    }
+
+    #[test]
+    fn test_reminder_tokens_roundtrip() {
+        let input = "Pay rent @2025-03-01 rem:2d rem:^90m rem:+1h rem:2025-02-20T08:30";
+        let task = Task::new(input, &HashMap::new());
+        assert_eq!(task.summary, "Pay rent");
+        assert_eq!(
+            task.reminders,
+            vec![
+                Reminder::BeforeDue(2880),
+                Reminder::BeforeStart(90),
+                Reminder::BeforeDue(-60),
+                Reminder::At(
+                    NaiveDateTime::parse_from_str("2025-02-20T08:30", "%Y-%m-%dT%H:%M")
+                        .unwrap()
+                        .and_utc()
+                ),
+            ]
+        );
+        let smart = task.to_smart_string();
+        assert!(smart.contains("rem:2d rem:^90m rem:+1h rem:2025-02-20T08:30"));
+        assert_eq!(Task::new(&smart, &HashMap::new()).reminders, task.reminders);
+
+        // Without a start date, start-relative reminders follow the due date
+        let due = task.due.unwrap();
+        let times = task.reminder_times();
+        assert_eq!(times[0], due - chrono::Duration::days(2));
+        assert_eq!(times[1], due - chrono::Duration::minutes(90));
+        assert_eq!(times[2], due + chrono::Duration::hours(1));
+        // Not a reminder: kept in the summary
+        assert_eq!(Task::new("rem:soon", &HashMap::new()).summary, "rem:soon");
+    }
+}
@@ -0,0 +1,253 @@
+// File: ./src/model/priority.rs
+// Task priorities, and their named bands (RFC 5545: 1-4 high, 5 medium, 6-9 low),
+// configurable through `priority_bands` and shared by the UIs and the `!high` smart syntax
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::RwLock;
+
+/// RFC 5545 PRIORITY, stored as its number (0 for none) in the ICS and the caches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(from = "u8", into = "u8")]
+pub enum Priority {
+    #[default]
+    None,
+    /// 1 (most urgent) to 9. Larger values read from elsewhere are kept for `validate`.
+    Level(u8),
+}
+
+impl Priority {
+    pub const HIGH: Priority = Priority::Level(1);
+    pub const MEDIUM: Priority = Priority::Level(5);
+    pub const LOW: Priority = Priority::Level(9);
+
+    pub const fn value(self) -> u8 {
+        match self {
+            Priority::None => 0,
+            Priority::Level(p) => p,
+        }
+    }
+
+    pub const fn is_set(self) -> bool {
+        !matches!(self, Priority::None)
+    }
+
+    /// For sorting, most urgent first: no priority ranks as medium.
+    pub const fn rank(self) -> u8 {
+        match self {
+            Priority::None => 5,
+            Priority::Level(p) => p,
+        }
+    }
+
+    /// The quick +/- of the lists: none, low, medium, high.
+    pub fn cycled(self, up: bool) -> Self {
+        match (self, up) {
+            (Priority::None, true) => Priority::LOW,
+            (Priority::LOW, true) => Priority::MEDIUM,
+            (Priority::MEDIUM | Priority::HIGH, true) => Priority::HIGH,
+            (_, true) => Priority::MEDIUM,
+            (Priority::HIGH, false) => Priority::MEDIUM,
+            (Priority::MEDIUM, false) => Priority::LOW,
+            (_, false) => Priority::None,
+        }
+    }
+
+    /// One value at a time, up being more urgent: none, 9, …, 1.
+    pub fn stepped(self, up: bool) -> Self {
+        match (self, up) {
+            (Priority::None, true) => Priority::LOW,
+            (Priority::Level(p), true) => Priority::Level(p.saturating_sub(1).max(1)),
+            (Priority::None, false) => Priority::None,
+            (Priority::Level(p), false) if p >= 9 => Priority::None,
+            (Priority::Level(p), false) => Priority::Level(p + 1),
+        }
+    }
+}
+
+impl From<u8> for Priority {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Priority::None,
+            p => Priority::Level(p),
+        }
+    }
+}
+
+impl From<Priority> for u8 {
+    fn from(priority: Priority) -> Self {
+        priority.value()
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value())
+    }
+}
+
+/// Bands from the config; empty = the RFC 5545 defaults
+static BANDS: RwLock<Vec<PriorityBand>> = RwLock::new(Vec::new());
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriorityBand {
+    pub name: String,
+    /// Inclusive range of priorities, 1 being the most urgent
+    pub min: u8,
+    pub max: u8,
+    /// "#rrggbb" for every priority of the band; None keeps the per-value gradient
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+impl PriorityBand {
+    fn new(name: &str, min: u8, max: u8) -> Self {
+        Self {
+            name: name.to_string(),
+            min,
+            max,
+            color: None,
+        }
+    }
+
+    pub fn contains(&self, priority: u8) -> bool {
+        (self.min..=self.max).contains(&priority)
+    }
+}
+
+pub fn default_bands() -> Vec<PriorityBand> {
+    vec![
+        PriorityBand::new("High", 1, 4),
+        PriorityBand::new("Medium", 5, 5),
+        PriorityBand::new("Low", 6, 9),
+    ]
+}
+
+/// Installs the configured bands (done on every config load).
+pub fn set_bands(bands: Vec<PriorityBand>) {
+    if let Ok(mut current) = BANDS.write() {
+        *current = bands;
+    }
+}
+
+pub fn bands() -> Vec<PriorityBand> {
+    match BANDS.read() {
+        Ok(bands) if !bands.is_empty() => bands.clone(),
+        _ => default_bands(),
+    }
+}
+
+/// The band of a priority; None for no priority or values no band covers.
+pub fn band_of(priority: Priority) -> Option<PriorityBand> {
+    let Priority::Level(p) = priority else {
+        return None;
+    };
+    bands().into_iter().find(|b| b.contains(p))
+}
+
+/// "High", "Medium", … or "None"
+pub fn label(priority: Priority) -> String {
+    band_of(priority).map_or("None".to_string(), |b| b.name)
+}
+
+/// In the most urgent band (the one holding priority 1)
+pub fn is_urgent(priority: Priority) -> bool {
+    priority.is_set() && band_of(priority) == band_of(Priority::HIGH)
+}
+
+/// The configured color of the band of `priority`, if any
+pub fn band_color(priority: Priority) -> Option<String> {
+    band_of(priority).and_then(|b| b.color)
+}
+
+/// Priority set by `!name`: the most urgent value of the band (e.g. `!low` = 6).
+pub fn value_of(name: &str) -> Option<Priority> {
+    bands()
+        .into_iter()
+        .find(|b| b.name.eq_ignore_ascii_case(name))
+        .map(|b| Priority::Level(b.min))
+}
+
+/// Bands must stay within 1-9 and have distinct single-word names.
+pub fn validate_bands(bands: &[PriorityBand]) -> Result<(), String> {
+    for (i, band) in bands.iter().enumerate() {
+        if band.name.is_empty() || band.name.contains(char::is_whitespace) {
+            return Err(format!(
+                "Invalid priority band name '{}': use a single word",
+                band.name
+            ));
+        }
+        if band.min == 0 || band.max > 9 || band.min > band.max {
+            return Err(format!(
+                "Priority band '{}' must cover a range within 1-9",
+                band.name
+            ));
+        }
+        if bands[..i]
+            .iter()
+            .any(|b| b.name.eq_ignore_ascii_case(&band.name))
+        {
+            return Err(format!("Priority band '{}' already exists", band.name));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_bands_follow_rfc5545() {
+        let defaults = default_bands();
+        let find = |p: u8| {
+            defaults
+                .iter()
+                .find(|b| b.contains(p))
+                .map(|b| b.name.as_str())
+        };
+        assert_eq!(find(1), Some("High"));
+        assert_eq!(find(4), Some("High"));
+        assert_eq!(find(5), Some("Medium"));
+        assert_eq!(find(9), Some("Low"));
+        assert_eq!(find(0), None);
+
+        assert!(validate_bands(&defaults).is_ok());
+        let out_of_range = vec![PriorityBand::new("Urgent", 0, 2)];
+        assert!(validate_bands(&out_of_range).is_err());
+        let duplicate = vec![
+            PriorityBand::new("Top", 1, 2),
+            PriorityBand::new("top", 3, 4),
+        ];
+        assert!(validate_bands(&duplicate).is_err());
+    }
+
+    #[test]
+    fn test_band_names_in_smart_input() {
+        let task = crate::model::Task::new("Call the bank !High", &Default::default());
+        assert_eq!(task.summary, "Call the bank");
+        assert_eq!(task.priority, Priority::HIGH);
+        assert_eq!(value_of("low"), Some(Priority::Level(6)));
+        assert_eq!(value_of("urgent"), None);
+        assert_eq!(label(Priority::MEDIUM), "Medium");
+        assert_eq!(label(Priority::None), "None");
+    }
+
+    #[test]
+    fn test_priority_steps() {
+        assert_eq!(Priority::from(0), Priority::None);
+        assert_eq!(u8::from(Priority::Level(3)), 3);
+        assert_eq!(Priority::None.rank(), Priority::MEDIUM.rank());
+        // The lists' ladder and the editor's single steps
+        assert_eq!(Priority::None.cycled(true), Priority::LOW);
+        assert_eq!(Priority::MEDIUM.cycled(true), Priority::HIGH);
+        assert_eq!(Priority::Level(3).cycled(true), Priority::MEDIUM);
+        assert_eq!(Priority::Level(3).cycled(false), Priority::None);
+        assert_eq!(Priority::Level(3).stepped(true), Priority::Level(2));
+        assert_eq!(Priority::HIGH.stepped(true), Priority::HIGH);
+        assert_eq!(Priority::LOW.stepped(false), Priority::None);
+        let json = serde_json::to_string(&Priority::MEDIUM).unwrap();
+        assert_eq!(json, "5");
+        let unset: Priority = serde_json::from_str("0").unwrap();
+        assert_eq!(unset, Priority::None);
+    }
+}
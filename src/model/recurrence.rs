@@ -0,0 +1,284 @@
+// File: src/model/recurrence.rs
+// Structured view over the subset of RRULE that the UIs can build and describe
+use crate::i18n::{tr, tr_args};
+use chrono::Weekday;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Frequency {
+    #[default]
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    pub const ALL: [Frequency; 4] = [
+        Frequency::Daily,
+        Frequency::Weekly,
+        Frequency::Monthly,
+        Frequency::Yearly,
+    ];
+
+    fn as_rrule(&self) -> &'static str {
+        match self {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        }
+    }
+
+    fn from_rrule(val: &str) -> Option<Self> {
+        match val {
+            "DAILY" => Some(Frequency::Daily),
+            "WEEKLY" => Some(Frequency::Weekly),
+            "MONTHLY" => Some(Frequency::Monthly),
+            "YEARLY" => Some(Frequency::Yearly),
+            _ => None,
+        }
+    }
+
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Frequency::Daily => "days",
+            Frequency::Weekly => "weeks",
+            Frequency::Monthly => "months",
+            Frequency::Yearly => "years",
+        }
+    }
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Frequency::Daily => "Daily",
+            Frequency::Weekly => "Weekly",
+            Frequency::Monthly => "Monthly",
+            Frequency::Yearly => "Yearly",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Short weekday name in the current language, for descriptions.
+fn weekday_name(day: Weekday) -> &'static str {
+    tr(match day {
+        Weekday::Mon => "recurrence.mon",
+        Weekday::Tue => "recurrence.tue",
+        Weekday::Wed => "recurrence.wed",
+        Weekday::Thu => "recurrence.thu",
+        Weekday::Fri => "recurrence.fri",
+        Weekday::Sat => "recurrence.sat",
+        Weekday::Sun => "recurrence.sun",
+    })
+}
+
+pub const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+fn weekday_code(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn weekday_from_code(code: &str) -> Option<Weekday> {
+    WEEKDAYS.into_iter().find(|d| weekday_code(*d) == code)
+}
+
+/// A recurrence the builders can edit: every N days/weeks (on given weekdays), months (on a given day) or years.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    pub freq: Frequency,
+    pub interval: u32,
+    pub by_day: Vec<Weekday>,
+    pub by_month_day: Option<u32>,
+}
+
+impl Default for Recurrence {
+    fn default() -> Self {
+        Self {
+            freq: Frequency::Daily,
+            interval: 1,
+            by_day: Vec::new(),
+            by_month_day: None,
+        }
+    }
+}
+
+impl Recurrence {
+    /// Returns None for rules using parts the builder does not model (COUNT, BYSETPOS, ...).
+    pub fn from_rrule(rrule: &str) -> Option<Self> {
+        let mut rec = Recurrence::default();
+        let mut has_freq = false;
+
+        for part in rrule.split(';').filter(|p| !p.is_empty()) {
+            let (key, val) = part.split_once('=')?;
+            match key {
+                "FREQ" => {
+                    rec.freq = Frequency::from_rrule(val)?;
+                    has_freq = true;
+                }
+                "INTERVAL" => rec.interval = val.parse().ok().filter(|n| *n > 0)?,
+                "BYDAY" => {
+                    rec.by_day = val
+                        .split(',')
+                        .map(weekday_from_code)
+                        .collect::<Option<Vec<_>>>()?;
+                }
+                "BYMONTHDAY" => {
+                    rec.by_month_day = Some(val.parse().ok().filter(|d| (1..=31).contains(d))?)
+                }
+                _ => return None,
+            }
+        }
+
+        let by_day_ok = rec.by_day.is_empty() || rec.freq == Frequency::Weekly;
+        let by_month_day_ok = rec.by_month_day.is_none() || rec.freq == Frequency::Monthly;
+        (has_freq && by_day_ok && by_month_day_ok).then_some(rec)
+    }
+
+    pub fn to_rrule(&self) -> String {
+        let mut s = format!("FREQ={}", self.freq.as_rrule());
+        if self.interval > 1 {
+            s.push_str(&format!(";INTERVAL={}", self.interval));
+        }
+        if self.freq == Frequency::Weekly && !self.by_day.is_empty() {
+            let days: Vec<&str> = self.by_day.iter().map(|d| weekday_code(*d)).collect();
+            s.push_str(&format!(";BYDAY={}", days.join(",")));
+        }
+        if self.freq == Frequency::Monthly
+            && let Some(day) = self.by_month_day
+        {
+            s.push_str(&format!(";BYMONTHDAY={}", day));
+        }
+        s
+    }
+
+    /// Adds or removes a weekday, keeping Monday-first order.
+    pub fn toggle_day(&mut self, day: Weekday) {
+        if let Some(pos) = self.by_day.iter().position(|d| *d == day) {
+            self.by_day.remove(pos);
+        } else {
+            self.by_day.push(day);
+            self.by_day.sort_by_key(|d| d.num_days_from_monday());
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let mut s = if self.interval > 1 {
+            let key = match self.freq {
+                Frequency::Daily => "recurrence.every_days",
+                Frequency::Weekly => "recurrence.every_weeks",
+                Frequency::Monthly => "recurrence.every_months",
+                Frequency::Yearly => "recurrence.every_years",
+            };
+            tr_args(key, &[("n", &self.interval)])
+        } else {
+            tr(match self.freq {
+                Frequency::Daily => "recurrence.daily",
+                Frequency::Weekly => "recurrence.weekly",
+                Frequency::Monthly => "recurrence.monthly",
+                Frequency::Yearly => "recurrence.yearly",
+            })
+            .to_string()
+        };
+        if self.freq == Frequency::Weekly && !self.by_day.is_empty() {
+            let days: Vec<&str> = self.by_day.iter().map(|d| weekday_name(*d)).collect();
+            let days = days.join(", ");
+            s.push(' ');
+            s.push_str(&tr_args("recurrence.on_weekdays", &[("days", &days)]));
+        }
+        if self.freq == Frequency::Monthly
+            && let Some(day) = self.by_month_day
+        {
+            s.push(' ');
+            s.push_str(&tr_args("recurrence.on_month_day", &[("day", &day)]));
+        }
+        s
+    }
+}
+
+/// Human-readable description of any RRULE, falling back to the raw rule when it can't be modeled.
+pub fn describe_rrule(rrule: &str) -> String {
+    let parts: HashMap<&str, &str> = rrule.split(';').filter_map(|p| p.split_once('=')).collect();
+    let core: Vec<&str> = rrule
+        .split(';')
+        .filter(|p| !p.starts_with("COUNT=") && !p.starts_with("UNTIL="))
+        .collect();
+
+    let Some(rec) = Recurrence::from_rrule(&core.join(";")) else {
+        return rrule.to_string();
+    };
+
+    let mut s = rec.describe();
+    if let Some(count) = parts.get("COUNT") {
+        s.push_str(", ");
+        s.push_str(&tr_args("recurrence.times", &[("n", count)]));
+    }
+    if let Some(until) = parts.get("UNTIL")
+        && let Some(date) = until.get(..8)
+        && let Ok(d) = chrono::NaiveDate::parse_from_str(date, "%Y%m%d")
+    {
+        let date = d.format("%Y-%m-%d");
+        s.push_str(", ");
+        s.push_str(&tr_args("recurrence.until", &[("date", &date)]));
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_roundtrip_and_description() {
+        crate::i18n::set_language(Some("en"));
+        let mut rec = Recurrence {
+            freq: Frequency::Weekly,
+            interval: 2,
+            ..Default::default()
+        };
+        rec.toggle_day(Weekday::Wed);
+        rec.toggle_day(Weekday::Mon);
+        assert_eq!(rec.to_rrule(), "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE");
+        assert_eq!(Recurrence::from_rrule(&rec.to_rrule()), Some(rec.clone()));
+        assert_eq!(rec.describe(), "every 2 weeks on Mon, Wed");
+
+        assert_eq!(
+            describe_rrule("FREQ=MONTHLY;BYMONTHDAY=15"),
+            "monthly on day 15"
+        );
+        assert_eq!(describe_rrule("FREQ=DAILY;COUNT=5"), "daily, 5 times");
+        assert_eq!(
+            describe_rrule("FREQ=YEARLY;UNTIL=20300101T000000Z"),
+            "yearly, until 2030-01-01"
+        );
+        // Unmodeled rules are shown verbatim
+        assert_eq!(
+            describe_rrule("FREQ=MONTHLY;BYDAY=1MO"),
+            "FREQ=MONTHLY;BYDAY=1MO"
+        );
+
+        // Builder output survives the smart-input round trip used by the edit field
+        let t = crate::model::Task::new("Gym rec:FREQ=WEEKLY;BYDAY=MO,WE", &HashMap::new());
+        assert_eq!(t.rrule.as_deref(), Some("FREQ=WEEKLY;BYDAY=MO,WE"));
+        assert_eq!(t.to_smart_string(), "Gym rec:FREQ=WEEKLY;BYDAY=MO,WE");
+    }
+}
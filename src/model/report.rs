@@ -0,0 +1,87 @@
+// File: ./src/model/report.rs
+// CSV export of completed tasks, for time reporting and invoicing
+use crate::model::{Task, TaskStatus};
+use chrono::{Datelike, Local, NaiveDate};
+use std::collections::HashMap;
+
+pub const CSV_HEADER: &str = "summary,calendar,tags,completed,estimated_minutes";
+
+/// Quotes a field when it holds a separator, a quote or a line break (RFC 4180).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// First day of the current month to today, the default report range.
+pub fn this_month() -> (NaiveDate, NaiveDate) {
+    let today = Local::now().date_naive();
+    (today.with_day(1).unwrap_or(today), today)
+}
+
+/// Tasks completed between `from` and `to` (inclusive, local dates), oldest first.
+/// `calendar_names` maps calendar hrefs to the names shown in the report.
+pub fn completed_tasks_csv<'a>(
+    tasks: impl IntoIterator<Item = &'a Task>,
+    calendar_names: &HashMap<String, String>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> String {
+    let mut done: Vec<(&Task, chrono::DateTime<Local>)> = tasks
+        .into_iter()
+        .filter(|t| t.status == TaskStatus::Completed)
+        .filter_map(|t| Some((t, t.completed?.with_timezone(&Local))))
+        .filter(|(_, at)| (from..=to).contains(&at.date_naive()))
+        .collect();
+    done.sort_by_key(|(_, at)| *at);
+
+    let mut lines = vec![CSV_HEADER.to_string()];
+    for (task, at) in done {
+        let calendar = calendar_names
+            .get(&task.calendar_href)
+            .unwrap_or(&task.calendar_href);
+        let fields = [
+            csv_field(&task.summary),
+            csv_field(calendar),
+            csv_field(&task.categories.join(" ")),
+            at.format("%Y-%m-%d %H:%M").to_string(),
+            task.estimated_duration
+                .map(|d| d.minutes().to_string())
+                .unwrap_or_default(),
+        ];
+        lines.push(fields.join(","));
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_completed_tasks_csv() {
+        let day = |d| Local.with_ymd_and_hms(2025, 3, d, 12, 0, 0).unwrap();
+        let mut invoiced = Task::new("Fix login, again ~90m #acme", &HashMap::new());
+        invoiced.calendar_href = "/cal/work/".to_string();
+        invoiced.status = TaskStatus::Completed;
+        invoiced.completed = Some(day(10).with_timezone(&Utc));
+        let mut too_late = invoiced.clone();
+        too_late.completed = Some(day(20).with_timezone(&Utc));
+        let open = Task::new("Say \"hi\"", &HashMap::new());
+
+        let names = HashMap::from([("/cal/work/".to_string(), "Work".to_string())]);
+        let from = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+        let csv = completed_tasks_csv([&invoiced, &too_late, &open], &names, from, to);
+        assert_eq!(
+            csv,
+            "summary,calendar,tags,completed,estimated_minutes\n\
+             \"Fix login, again\",Work,acme,2025-03-10 12:00,90\n"
+        );
+        assert_eq!(csv_field("Say \"hi\""), "\"Say \"\"hi\"\"\"");
+    }
+}
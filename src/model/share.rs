@@ -0,0 +1,206 @@
+// File: ./src/model/share.rs
+// Single-task export for sharing: readable text, a standalone .ics file or a deep link
+use crate::model::recurrence::describe_rrule;
+use crate::model::{Task, TaskStatus};
+use chrono::Local;
+
+/// URI scheme of links that open a task (`rustache://task/<uid>`).
+pub const DEEP_LINK_SCHEME: &str = "rustache";
+
+/// What a `rustache://` link asks the app to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLink {
+    /// `rustache://task/<uid>`
+    OpenTask(String),
+    /// `rustache://add?text=<smart input>`, pre-fills quick-add without saving
+    Add(String),
+}
+
+impl DeepLink {
+    pub fn parse(uri: &str) -> Option<Self> {
+        let rest = uri
+            .trim()
+            .strip_prefix(DEEP_LINK_SCHEME)?
+            .strip_prefix(':')?
+            .trim_start_matches('/');
+        let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let path = path.trim_end_matches('/');
+
+        if let Some(uid) = path.strip_prefix("task/") {
+            let uid = decode_uri_component(uid);
+            return (!uid.is_empty()).then_some(DeepLink::OpenTask(uid));
+        }
+        if path == "add" {
+            let text = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("text="))
+                .map(|v| decode_uri_component(&v.replace('+', " ")))
+                .unwrap_or_default();
+            return Some(DeepLink::Add(text));
+        }
+        None
+    }
+
+    pub fn to_uri(&self) -> String {
+        match self {
+            DeepLink::OpenTask(uid) => {
+                format!("{}://task/{}", DEEP_LINK_SCHEME, encode_uri_component(uid))
+            }
+            DeepLink::Add(text) => {
+                format!(
+                    "{}://add?text={}",
+                    DEEP_LINK_SCHEME,
+                    encode_uri_component(text)
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareFormat {
+    Text,
+    Ics,
+    Link,
+}
+
+/// Percent-encodes everything but RFC 3986 unreserved characters.
+pub fn encode_uri_component(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Reverses percent-encoding; invalid escapes are kept as-is.
+pub fn decode_uri_component(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(hex) = s.get(i + 1..i + 3)
+            && let Ok(b) = u8::from_str_radix(hex, 16)
+        {
+            out.push(b);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+impl Task {
+    /// Title, dates, priority, tags and description, meant for messages and notes.
+    pub fn to_share_text(&self) -> String {
+        let mut lines = vec![self.summary.clone()];
+        match self.status {
+            TaskStatus::Completed => lines.push("Status: Done".to_string()),
+            TaskStatus::InProcess => lines.push("Status: In process".to_string()),
+            TaskStatus::Cancelled => lines.push("Status: Cancelled".to_string()),
+            TaskStatus::NeedsAction => {}
+        }
+        let fmt = |d: chrono::DateTime<chrono::Utc>| {
+            d.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string()
+        };
+        if let Some(start) = self.dtstart {
+            lines.push(format!("Start: {}", fmt(start)));
+        }
+        if let Some(due) = self.due {
+            lines.push(format!("Due: {}", fmt(due)));
+        }
+        if let Some(rrule) = &self.rrule {
+            lines.push(format!("Repeats: {}", describe_rrule(rrule)));
+        }
+        if self.priority.is_set() {
+            lines.push(format!("Priority: {}", self.priority));
+        }
+        if let Some(d) = self.estimated_duration {
+            lines.push(format!("Estimate: {} min", d.minutes()));
+        }
+        if !self.categories.is_empty() {
+            let tags: Vec<String> = self.categories.iter().map(|c| format!("#{}", c)).collect();
+            lines.push(format!("Tags: {}", tags.join(" ")));
+        }
+        if !self.description.trim().is_empty() {
+            lines.push(String::new());
+            lines.push(self.description.trim().to_string());
+        }
+        lines.join("\n")
+    }
+
+    /// This task alone as a calendar file, without the other VTODOs of its resource.
+    pub fn to_share_ics(&self) -> String {
+        self.without_siblings().to_ics()
+    }
+
+    pub fn deep_link(&self) -> String {
+        DeepLink::OpenTask(self.uid.clone()).to_uri()
+    }
+
+    pub fn share(&self, format: ShareFormat) -> String {
+        match format {
+            ShareFormat::Text => self.to_share_text(),
+            ShareFormat::Ics => self.to_share_ics(),
+            ShareFormat::Link => self.deep_link(),
+        }
+    }
+
+    /// Name for the exported .ics file, derived from the title.
+    pub fn share_file_name(&self) -> String {
+        let words: Vec<&str> = self
+            .summary
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect();
+        if words.is_empty() {
+            "task.ics".to_string()
+        } else {
+            format!("{}.ics", words.join("-").to_lowercase())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_share_formats() {
+        let mut task = Task::new("Buy cat food !1 #shopping", &HashMap::new());
+        task.uid = "abc 1@host".to_string();
+        task.description = "The grain-free one".to_string();
+
+        let text = task.to_share_text();
+        assert!(text.starts_with("Buy cat food\n"));
+        assert!(text.contains("Priority: 1") && text.contains("Tags: #shopping"));
+        assert!(text.ends_with("\n\nThe grain-free one"));
+
+        assert_eq!(task.deep_link(), "rustache://task/abc%201%40host");
+        assert_eq!(task.share_file_name(), "buy-cat-food.ics");
+        assert!(task.share(ShareFormat::Ics).contains("UID:abc 1@host"));
+    }
+
+    #[test]
+    fn test_parse_deep_links() {
+        assert_eq!(
+            DeepLink::parse("rustache://task/abc%201%40host"),
+            Some(DeepLink::OpenTask("abc 1@host".to_string()))
+        );
+        assert_eq!(
+            DeepLink::parse("rustache://add?text=Buy+milk%20%40tomorrow"),
+            Some(DeepLink::Add("Buy milk @tomorrow".to_string()))
+        );
+        let link = DeepLink::Add("Call Bob !1 #work".to_string());
+        assert_eq!(DeepLink::parse(&link.to_uri()), Some(link));
+        assert_eq!(DeepLink::parse("https://task/abc"), None);
+        assert_eq!(DeepLink::parse("rustache://task/"), None);
+    }
+}
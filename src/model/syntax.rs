@@ -0,0 +1,333 @@
+// File: ./src/model/syntax.rs
+// Extra prefixes and local words for the smart input ([smart_syntax] in the config). They are
+// rewritten to the built-in syntax before parsing, which keeps working alongside them. The
+// same settings can shape what `Task::to_smart_string` writes back, e.g. for the Android app.
+use chrono::NaiveDate;
+use chrono::format::{Item, StrftimeItems};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::RwLock;
+
+static SYNTAX: RwLock<Option<SmartSyntax>> = RwLock::new(None);
+
+/// Words the date and recurrence tokens understand, and that `words` can translate to
+pub const KEYWORDS: [&str; 6] = ["today", "tomorrow", "daily", "weekly", "monthly", "yearly"];
+
+/// Dates of the built-in syntax
+pub const ISO_DATE: &str = "%Y-%m-%d";
+
+const LOCALES: [(&str, [&str; 6]); 5] = [
+    (
+        "de",
+        [
+            "heute",
+            "morgen",
+            "täglich",
+            "wöchentlich",
+            "monatlich",
+            "jährlich",
+        ],
+    ),
+    (
+        "es",
+        ["hoy", "mañana", "diario", "semanal", "mensual", "anual"],
+    ),
+    (
+        "fr",
+        [
+            "aujourdhui",
+            "demain",
+            "quotidien",
+            "hebdomadaire",
+            "mensuel",
+            "annuel",
+        ],
+    ),
+    (
+        "it",
+        [
+            "oggi",
+            "domani",
+            "giornaliero",
+            "settimanale",
+            "mensile",
+            "annuale",
+        ],
+    ),
+    (
+        "nl",
+        [
+            "vandaag",
+            "morgen",
+            "dagelijks",
+            "wekelijks",
+            "maandelijks",
+            "jaarlijks",
+        ],
+    ),
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SmartSyntax {
+    /// Built-in translations of `KEYWORDS`: "de", "es", "fr", "it" or "nl"
+    pub locale: Option<String>,
+    /// Prefixes accepted on top of `!`, `~`/`est:`, `#`, `@`/`due:` and `^`/`start:`
+    pub priority_prefixes: Vec<String>,
+    pub duration_prefixes: Vec<String>,
+    pub tag_prefixes: Vec<String>,
+    pub due_prefixes: Vec<String>,
+    pub start_prefixes: Vec<String>,
+    /// Local word -> keyword, e.g. "morgen" = "tomorrow" (on top of the locale's)
+    pub words: HashMap<String, String>,
+    /// Dates written in the smart string, e.g. "%d.%m.%Y" (one word, strftime). Dates typed
+    /// this way are read too; ISO dates always are.
+    pub date_format: Option<String>,
+    /// The smart string is written with the first extra prefix of each kind and the local
+    /// words, instead of the built-in symbols and English keywords.
+    pub localized_output: bool,
+}
+
+/// Installs the config's syntax (done on every config load).
+pub fn set(syntax: SmartSyntax) {
+    if let Ok(mut current) = SYNTAX.write() {
+        *current = Some(syntax);
+    }
+}
+
+pub fn current() -> SmartSyntax {
+    SYNTAX
+        .read()
+        .ok()
+        .and_then(|s| s.clone())
+        .unwrap_or_default()
+}
+
+fn locale_words(locale: &str) -> Option<&'static [&'static str; 6]> {
+    LOCALES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(locale))
+        .map(|(_, words)| words)
+}
+
+impl SmartSyntax {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(locale) = &self.locale
+            && locale_words(locale).is_none()
+        {
+            return Err(format!(
+                "Unknown smart syntax locale '{}' (available: de, es, fr, it, nl)",
+                locale
+            ));
+        }
+        let prefixes = [
+            &self.priority_prefixes,
+            &self.duration_prefixes,
+            &self.tag_prefixes,
+            &self.due_prefixes,
+            &self.start_prefixes,
+        ];
+        for prefix in prefixes.into_iter().flatten() {
+            if prefix.is_empty() || prefix.contains(char::is_whitespace) {
+                return Err(format!("Invalid smart syntax prefix '{}'", prefix));
+            }
+        }
+        for (word, keyword) in &self.words {
+            if !KEYWORDS.contains(&keyword.as_str()) {
+                return Err(format!(
+                    "'{}' must translate to one of: {}",
+                    word,
+                    KEYWORDS.join(", ")
+                ));
+            }
+        }
+        if let Some(format) = &self.date_format {
+            // Written dates must be one word that reads back as the same date
+            let sample = NaiveDate::from_ymd_opt(2025, 12, 31).unwrap_or_default();
+            let valid = !StrftimeItems::new(format).any(|i| matches!(i, Item::Error)) && {
+                let text = sample.format(format).to_string();
+                !text.contains(char::is_whitespace)
+                    && NaiveDate::parse_from_str(&text, format) == Ok(sample)
+            };
+            if !valid {
+                return Err(format!(
+                    "Invalid smart syntax date format '{}' (e.g. \"%d.%m.%Y\")",
+                    format
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// What the smart string starts a token with for the built-in `symbol` (!, ~, #, @ or ^).
+    pub fn output_prefix<'a>(&'a self, symbol: &'a str) -> &'a str {
+        if !self.localized_output {
+            return symbol;
+        }
+        let prefixes = match symbol {
+            "!" => &self.priority_prefixes,
+            "~" => &self.duration_prefixes,
+            "#" => &self.tag_prefixes,
+            "@" => &self.due_prefixes,
+            "^" => &self.start_prefixes,
+            _ => return symbol,
+        };
+        prefixes.first().map_or(symbol, String::as_str)
+    }
+
+    /// What the smart string says for one of `KEYWORDS`: the locale's word, else the user's own.
+    pub fn output_word(&self, keyword: &str) -> String {
+        if !self.localized_output {
+            return keyword.to_string();
+        }
+        if let Some(words) = self.locale.as_deref().and_then(locale_words)
+            && let Some(i) = KEYWORDS.iter().position(|k| *k == keyword)
+        {
+            return words[i].to_string();
+        }
+        // The first in order, so that the output doesn't change from one run to the next
+        self.words
+            .iter()
+            .filter(|(_, k)| *k == keyword)
+            .map(|(w, _)| w)
+            .min()
+            .cloned()
+            .unwrap_or_else(|| keyword.to_string())
+    }
+
+    /// A date as the smart string writes it (ISO without a usable `date_format`).
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        let mut out = String::new();
+        if let Some(format) = &self.date_format
+            && write!(out, "{}", date.format(format)).is_ok()
+        {
+            return out;
+        }
+        date.format(ISO_DATE).to_string()
+    }
+
+    /// The keyword for a local word, if it is one
+    fn translate(&self, word: &str) -> Option<String> {
+        let lower = word.to_lowercase();
+        if let Some((_, keyword)) = self.words.iter().find(|(w, _)| w.to_lowercase() == lower) {
+            return Some(keyword.clone());
+        }
+        let words = locale_words(self.locale.as_deref()?)?;
+        let i = words.iter().position(|w| *w == lower)?;
+        Some(KEYWORDS[i].to_string())
+    }
+
+    /// Rewrites one input word into the built-in syntax ("p:3" -> "!3", "@morgen" -> "@tomorrow").
+    pub fn normalize(&self, word: &str) -> String {
+        let custom = [
+            (&self.priority_prefixes, "!"),
+            (&self.duration_prefixes, "~"),
+            (&self.tag_prefixes, "#"),
+            (&self.due_prefixes, "@"),
+            (&self.start_prefixes, "^"),
+        ];
+        let mut rewritten = None;
+        for (prefixes, builtin) in custom {
+            if let Some(rest) = prefixes
+                .iter()
+                .filter_map(|p| word.strip_prefix(p.as_str()))
+                .find(|rest| !rest.is_empty())
+            {
+                rewritten = Some(format!("{}{}", builtin, rest));
+                break;
+            }
+        }
+        let word = rewritten.unwrap_or_else(|| word.to_string());
+
+        // Dates in the configured format
+        if let Some(format) = &self.date_format {
+            for prefix in ["@", "^", "due:", "start:"] {
+                if let Some(rest) = word.strip_prefix(prefix)
+                    && let Ok(date) = NaiveDate::parse_from_str(rest, format)
+                {
+                    return format!("{}{}", prefix, date.format(ISO_DATE));
+                }
+            }
+        }
+
+        // Local date and recurrence words, after a date prefix
+        for prefix in ["@", "^", "due:", "start:", "rec:"] {
+            if let Some(rest) = word.strip_prefix(prefix)
+                && let Some(keyword) = self.translate(rest)
+            {
+                return format!("{}{}", prefix, keyword);
+            }
+        }
+        word
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_prefixes_and_words() {
+        let syntax = SmartSyntax {
+            locale: Some("de".to_string()),
+            priority_prefixes: vec!["p:".to_string()],
+            due_prefixes: vec!["bis:".to_string()],
+            words: HashMap::from([("Demain".to_string(), "tomorrow".to_string())]),
+            ..Default::default()
+        };
+        assert!(syntax.validate().is_ok());
+        assert_eq!(syntax.normalize("p:3"), "!3");
+        assert_eq!(syntax.normalize("bis:Morgen"), "@tomorrow");
+        assert_eq!(syntax.normalize("@wöchentlich"), "@weekly");
+        assert_eq!(syntax.normalize("^demain"), "^tomorrow");
+        // Built-in syntax and plain words are left alone
+        assert_eq!(syntax.normalize("!2"), "!2");
+        assert_eq!(syntax.normalize("morgen"), "morgen");
+        assert_eq!(syntax.normalize("p:"), "p:");
+
+        let bad = SmartSyntax {
+            words: HashMap::from([("demain".to_string(), "later".to_string())]),
+            ..Default::default()
+        };
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_localized_output() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 4).unwrap();
+        let mut syntax = SmartSyntax {
+            locale: Some("de".to_string()),
+            priority_prefixes: vec!["p:".to_string()],
+            due_prefixes: vec!["bis:".to_string(), "fällig:".to_string()],
+            words: HashMap::from([("jede-woche".to_string(), "weekly".to_string())]),
+            date_format: Some("%d.%m.%Y".to_string()),
+            ..Default::default()
+        };
+        assert!(syntax.validate().is_ok());
+        // Dates are configurable on their own, the symbols and words only when asked for
+        assert_eq!(syntax.format_date(date), "04.03.2025");
+        assert_eq!(syntax.output_prefix("!"), "!");
+        assert_eq!(syntax.output_word("weekly"), "weekly");
+
+        syntax.localized_output = true;
+        assert_eq!(syntax.output_prefix("!"), "p:");
+        assert_eq!(syntax.output_prefix("@"), "bis:");
+        assert_eq!(syntax.output_prefix("~"), "~");
+        assert_eq!(syntax.output_word("weekly"), "wöchentlich");
+        syntax.locale = None;
+        assert_eq!(syntax.output_word("weekly"), "jede-woche");
+        assert_eq!(syntax.output_word("daily"), "daily");
+
+        // What is written reads back
+        assert_eq!(syntax.normalize("bis:04.03.2025"), "@2025-03-04");
+        assert_eq!(syntax.normalize("^04.03.2025"), "^2025-03-04");
+        assert_eq!(syntax.normalize("@2025-03-04"), "@2025-03-04");
+        assert_eq!(syntax.normalize("04.03.2025"), "04.03.2025");
+
+        for bad in ["%d %m %Y", "%d.%m", "%Q"] {
+            syntax.date_format = Some(bad.to_string());
+            assert!(syntax.validate().is_err(), "{}", bad);
+        }
+    }
+}
@@ -0,0 +1,144 @@
+// File: src/model/template.rs
+// Reusable task trees (checklists) stored in the config and instantiated on demand
+use crate::model::Task;
+use crate::model::parser::parse_smart_date;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Prefix that turns the task input into a template instantiation: `tpl:<name> [title]`.
+pub const TEMPLATE_PREFIX: &str = "tpl:";
+
+/// A task (and its subtasks) to create in one go.
+/// `title` is smart syntax; `{title}` and `{date}` are replaced when instantiating.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TaskTemplate {
+    /// Only used to look up top-level templates
+    #[serde(default)]
+    pub name: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Due this many days after the instantiation date
+    #[serde(default)]
+    pub due_offset_days: Option<i64>,
+    #[serde(default)]
+    pub subtasks: Vec<TaskTemplate>,
+}
+
+impl TaskTemplate {
+    /// Builds the whole tree, parents before their children, ready to be passed to `create_task`.
+    pub fn instantiate(
+        &self,
+        title: &str,
+        calendar_href: &str,
+        aliases: &HashMap<String, Vec<String>>,
+    ) -> Vec<Task> {
+        let mut tasks = Vec::new();
+        self.instantiate_into(title, calendar_href, aliases, None, &mut tasks);
+        tasks
+    }
+
+    fn instantiate_into(
+        &self,
+        title: &str,
+        calendar_href: &str,
+        aliases: &HashMap<String, Vec<String>>,
+        parent_uid: Option<String>,
+        out: &mut Vec<Task>,
+    ) {
+        let today = Local::now().date_naive().format("%Y-%m-%d").to_string();
+        let input = self
+            .title
+            .replace("{title}", title)
+            .replace("{date}", &today);
+
+        let mut task = Task::new(&input, aliases);
+        task.description = self.description.clone();
+        for tag in &self.tags {
+            if !task.categories.contains(tag) {
+                task.categories.push(tag.clone());
+            }
+        }
+        if task.due.is_none()
+            && let Some(days) = self.due_offset_days
+        {
+            task.due = parse_smart_date(&format!("{}d", days), true);
+        }
+        task.calendar_href = calendar_href.to_string();
+        task.parent_uid = parent_uid;
+
+        let uid = task.uid.clone();
+        out.push(task);
+        for sub in &self.subtasks {
+            sub.instantiate_into(title, calendar_href, aliases, Some(uid.clone()), out);
+        }
+    }
+}
+
+/// Splits `tpl:<name> [title]` into the template name and the title to substitute.
+pub fn parse_template_command(input: &str) -> Option<(&str, &str)> {
+    let rest = input.trim().strip_prefix(TEMPLATE_PREFIX)?;
+    let (name, title) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    (!name.is_empty()).then_some((name, title.trim()))
+}
+
+pub fn find_template<'a>(templates: &'a [TaskTemplate], name: &str) -> Option<&'a TaskTemplate> {
+    templates.iter().find(|t| t.name.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Priority;
+
+    #[test]
+    fn test_instantiate_release_checklist() {
+        let template = TaskTemplate {
+            name: "release".to_string(),
+            title: "Release {title} !2".to_string(),
+            tags: vec!["release".to_string()],
+            due_offset_days: Some(7),
+            subtasks: vec![
+                TaskTemplate {
+                    title: "Bump version to {title}".to_string(),
+                    due_offset_days: Some(1),
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    title: "Publish #announce".to_string(),
+                    description: "Post on the forum".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let templates = vec![template];
+
+        let (name, title) = parse_template_command("tpl:Release v1.2.0").unwrap();
+        let tasks = find_template(&templates, name).unwrap().instantiate(
+            title,
+            "/cal/work/",
+            &HashMap::new(),
+        );
+
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[0].summary, "Release v1.2.0");
+        assert_eq!(tasks[0].priority, Priority::Level(2));
+        assert_eq!(tasks[0].categories, vec!["release".to_string()]);
+        assert_eq!(tasks[0].due, parse_smart_date("7d", true));
+        assert_eq!(tasks[1].summary, "Bump version to v1.2.0");
+        assert_eq!(tasks[1].due, parse_smart_date("1d", true));
+        assert_eq!(tasks[2].categories, vec!["announce".to_string()]);
+        assert_eq!(tasks[2].description, "Post on the forum");
+        assert!(
+            tasks[1..]
+                .iter()
+                .all(|t| t.parent_uid.as_ref() == Some(&tasks[0].uid))
+        );
+        assert!(tasks.iter().all(|t| t.calendar_href == "/cal/work/"));
+        assert_eq!(parse_template_command("tpl:"), None);
+    }
+}
@@ -0,0 +1,160 @@
+// File: src/model/validate.rs
+// Sanity checks run before a task is written to a server or local storage
+use crate::model::item::Task;
+use crate::model::priority::Priority;
+use chrono::Utc;
+use rrule::RRuleSet;
+use std::fmt;
+use std::str::FromStr;
+
+/// Descriptions above this many bytes get a warning when saved
+pub const LARGE_DESCRIPTION: usize = 64 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationWarning {
+    EmptySummary,
+    InvalidRrule(String),
+    DueBeforeStart,
+    SelfParent,
+    SelfDependency,
+    InvalidPriority(u8),
+    /// In KiB
+    LargeDescription(usize),
+}
+
+impl ValidationWarning {
+    /// Blocking problems would produce ICS that other clients reject or misread.
+    pub fn is_blocking(&self) -> bool {
+        matches!(
+            self,
+            ValidationWarning::InvalidRrule(_)
+                | ValidationWarning::SelfParent
+                | ValidationWarning::SelfDependency
+                | ValidationWarning::InvalidPriority(_)
+        )
+    }
+}
+
+impl fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationWarning::EmptySummary => write!(f, "Task has no title"),
+            ValidationWarning::InvalidRrule(e) => write!(f, "Invalid recurrence rule: {}", e),
+            ValidationWarning::DueBeforeStart => write!(f, "Due date is before start date"),
+            ValidationWarning::SelfParent => write!(f, "Task cannot be its own parent"),
+            ValidationWarning::SelfDependency => write!(f, "Task cannot depend on itself"),
+            ValidationWarning::InvalidPriority(p) => {
+                write!(f, "Priority {} is out of range (0-9)", p)
+            }
+            ValidationWarning::LargeDescription(kib) => write!(
+                f,
+                "Description is {} KiB: the lists only show its beginning, and syncing it is slow",
+                kib
+            ),
+        }
+    }
+}
+
+impl Task {
+    pub fn validate(&self) -> Vec<ValidationWarning> {
+        let mut warnings = Vec::new();
+
+        if self.summary.trim().is_empty() {
+            warnings.push(ValidationWarning::EmptySummary);
+        }
+
+        if let Some(rule) = &self.rrule {
+            // Seed with any date: only the rule syntax is checked here
+            let seed = self.dtstart.or(self.due).unwrap_or_else(Utc::now);
+            let rrule_string = format!("DTSTART:{}\nRRULE:{}", seed.format("%Y%m%dT%H%M%SZ"), rule);
+            if let Err(e) = RRuleSet::from_str(&rrule_string) {
+                warnings.push(ValidationWarning::InvalidRrule(e.to_string()));
+            }
+        }
+
+        if let (Some(start), Some(due)) = (self.dtstart, self.due)
+            && due < start
+        {
+            warnings.push(ValidationWarning::DueBeforeStart);
+        }
+
+        if self.parent_uid.as_deref() == Some(self.uid.as_str()) {
+            warnings.push(ValidationWarning::SelfParent);
+        }
+        if self.dependencies.contains(&self.uid) {
+            warnings.push(ValidationWarning::SelfDependency);
+        }
+
+        if let Priority::Level(p) = self.priority
+            && p > 9
+        {
+            warnings.push(ValidationWarning::InvalidPriority(p));
+        }
+        if self.description.len() > LARGE_DESCRIPTION {
+            let kib = self.description.len() / 1024;
+            warnings.push(ValidationWarning::LargeDescription(kib));
+        }
+
+        warnings
+    }
+}
+
+/// Splits warnings into an error (if anything blocks the write) and displayable notes.
+pub fn check_before_save(task: &Task) -> Result<Vec<String>, String> {
+    let warnings = task.validate();
+    let blocking: Vec<String> = warnings
+        .iter()
+        .filter(|w| w.is_blocking())
+        .map(|w| w.to_string())
+        .collect();
+    if !blocking.is_empty() {
+        return Err(format!(
+            "'{}' not saved: {}",
+            task.summary,
+            blocking.join("; ")
+        ));
+    }
+    Ok(warnings.iter().map(|w| w.to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_validate_flags_bad_data() {
+        let mut t = Task::new("Water plants @daily", &HashMap::new());
+        assert!(t.validate().is_empty());
+
+        t.summary = "  ".to_string();
+        t.rrule = Some("FREQ=SOMETIMES".to_string());
+        t.parent_uid = Some(t.uid.clone());
+        t.priority = Priority::Level(12);
+        let warnings = t.validate();
+        assert!(warnings.contains(&ValidationWarning::EmptySummary));
+        assert!(warnings.contains(&ValidationWarning::SelfParent));
+        assert!(warnings.contains(&ValidationWarning::InvalidPriority(12)));
+        assert!(
+            warnings
+                .iter()
+                .any(|w| matches!(w, ValidationWarning::InvalidRrule(_)))
+        );
+        assert!(check_before_save(&t).is_err());
+
+        let mut soft = Task::new("Report ^2025-02-01 @2025-01-01", &HashMap::new());
+        assert_eq!(soft.validate(), vec![ValidationWarning::DueBeforeStart]);
+        soft.due = None;
+        assert_eq!(check_before_save(&soft), Ok(vec![]));
+
+        // Pasted meeting notes are saved, with a note, and previewed in the lists
+        soft.description = "Minutes. ".repeat(20_000);
+        assert_eq!(
+            soft.validate(),
+            vec![ValidationWarning::LargeDescription(175)]
+        );
+        assert_eq!(check_before_save(&soft).map(|notes| notes.len()), Ok(1));
+        assert_eq!(soft.description_preview(9), Some("Minutes. "));
+        assert_eq!(soft.description_preview(200_000), None);
+    }
+}
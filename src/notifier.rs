@@ -0,0 +1,223 @@
+// File: ./src/notifier.rs
+// Desktop reminders for due tasks and for the tasks' own reminders (VALARMs), shared by the
+// GUI and `cfait daemon`
+use crate::model::Task;
+use chrono::{DateTime, Duration, Local, Utc};
+use notify_rust::Notification;
+use std::collections::HashMap;
+
+/// How often the due dates are checked
+pub const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReminderAction {
+    Done,
+    /// Ask again in an hour
+    Snooze,
+    Dismissed,
+}
+
+/// Remembers which reminders were shown, so each is announced once.
+#[derive(Debug, Default)]
+pub struct Reminders {
+    /// uid -> due date (or time of the task's own reminder) already announced
+    announced: HashMap<String, DateTime<Utc>>,
+    /// uid -> end of the snooze
+    snoozed: HashMap<String, DateTime<Utc>>,
+}
+
+/// The latest reminder of `task` that went off by `now`, as the key it is announced under:
+/// the due date when it is within `lead_minutes`, or the time of one of its own reminders.
+fn latest_reminder(
+    task: &Task,
+    lead_minutes: Option<u32>,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let lead = lead_minutes.and_then(|m| {
+        let due = task.due?;
+        Some((due - Duration::minutes(m as i64), due))
+    });
+    let own = task.reminder_times().into_iter().map(|t| (t, t));
+    lead.into_iter()
+        .chain(own)
+        .filter(|(fires, _)| *fires <= now)
+        .max_by_key(|(fires, _)| *fires)
+        .map(|(_, key)| key)
+}
+
+impl Reminders {
+    /// Open tasks that are overdue or due within `lead_minutes` (when set), or whose own
+    /// reminder went off, and were not announced yet.
+    pub fn due<'a>(
+        &mut self,
+        tasks: impl IntoIterator<Item = &'a Task>,
+        lead_minutes: Option<u32>,
+        now: DateTime<Utc>,
+    ) -> Vec<Task> {
+        let mut due = Vec::new();
+        for task in tasks {
+            if task.status.is_done() {
+                continue;
+            }
+            let Some(date) = latest_reminder(task, lead_minutes, now) else {
+                continue;
+            };
+            match self.snoozed.get(&task.uid) {
+                Some(until) if *until > now => continue,
+                Some(_) => {
+                    self.snoozed.remove(&task.uid);
+                }
+                None if self.announced.get(&task.uid) == Some(&date) => continue,
+                None => {}
+            }
+            self.announced.insert(task.uid.clone(), date);
+            due.push(task.clone());
+        }
+        due
+    }
+
+    pub fn snooze(&mut self, uid: &str, now: DateTime<Utc>) {
+        self.snoozed
+            .insert(uid.to_string(), now + Duration::hours(1));
+    }
+}
+
+/// Shows the reminder and blocks until the user answers it. Only XDG notification servers
+/// support buttons; elsewhere the notification is informative and Dismissed is returned.
+pub fn notify_blocking(task: &Task) -> ReminderAction {
+    let local = |d: DateTime<Utc>| d.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string();
+    let (title, body) = match task.due {
+        Some(due) if due <= Utc::now() => (
+            format!("Overdue: {}", task.summary),
+            format!("Due {}", local(due)),
+        ),
+        Some(due) => (
+            format!("Due soon: {}", task.summary),
+            format!("Due {}", local(due)),
+        ),
+        None => (
+            format!("Reminder: {}", task.summary),
+            task.dtstart
+                .map(|start| format!("Starts {}", local(start)))
+                .unwrap_or_default(),
+        ),
+    };
+
+    let mut notification = Notification::new();
+    notification
+        .appname("Cfait")
+        .icon("cfait")
+        .summary(&title)
+        .body(&body);
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        notification
+            .action("done", "Done")
+            .action("snooze", "Snooze 1h");
+        let Ok(handle) = notification.show() else {
+            return ReminderAction::Dismissed;
+        };
+        let mut picked = ReminderAction::Dismissed;
+        handle.wait_for_action(|action| {
+            picked = match action {
+                "done" => ReminderAction::Done,
+                "snooze" => ReminderAction::Snooze,
+                _ => ReminderAction::Dismissed,
+            }
+        });
+        picked
+    }
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    {
+        let _ = notification.show();
+        ReminderAction::Dismissed
+    }
+}
+
+/// Tells that finishing a task freed `tasks`, which waited for it. Informative only.
+pub fn notify_unblocked(tasks: &[Task]) {
+    let (title, body) = match tasks {
+        [] => return,
+        [task] => (
+            format!("Unblocked: {}", task.summary),
+            "Nothing blocks it any more.".to_string(),
+        ),
+        _ => (
+            format!("{} tasks unblocked", tasks.len()),
+            tasks
+                .iter()
+                .map(|t| t.summary.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+    };
+    let _ = Notification::new()
+        .appname("Cfait")
+        .icon("cfait")
+        .summary(&title)
+        .body(&body)
+        .show();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Reminder, TaskStatus};
+
+    #[test]
+    fn test_due_reminders_once_and_after_snooze() {
+        let now = Utc::now();
+        let mut soon = Task::new("Call the dentist", &HashMap::new());
+        soon.due = Some(now + Duration::minutes(10));
+        let mut later = Task::new("File taxes", &HashMap::new());
+        later.due = Some(now + Duration::days(3));
+        let mut done = Task::new("Old chore", &HashMap::new());
+        done.due = Some(now - Duration::days(1));
+        done.status = TaskStatus::Completed;
+        let tasks = vec![soon.clone(), later, done];
+
+        let mut reminders = Reminders::default();
+        let due = reminders.due(&tasks, Some(15), now);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].uid, soon.uid);
+        assert!(reminders.due(&tasks, Some(15), now).is_empty());
+
+        reminders.snooze(&soon.uid, now);
+        assert!(
+            reminders
+                .due(&tasks, Some(15), now + Duration::minutes(30))
+                .is_empty()
+        );
+        assert_eq!(
+            reminders
+                .due(&tasks, Some(15), now + Duration::minutes(61))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_own_reminders_without_lead() {
+        let now = Utc::now();
+        let mut taxes = Task::new("File taxes rem:2d rem:1d", &HashMap::new());
+        taxes.due = Some(now + Duration::days(3));
+        let mut call = Task::new("Call Bob", &HashMap::new());
+        call.reminders = vec![Reminder::At(now + Duration::hours(1))];
+        let tasks = vec![taxes.clone(), call.clone()];
+
+        let mut reminders = Reminders::default();
+        assert!(reminders.due(&tasks, None, now).is_empty());
+        // Each reminder is announced once, the due date itself only with a lead
+        let day = now + Duration::days(1) + Duration::minutes(1);
+        let due = reminders.due(&tasks, None, day);
+        assert_eq!(due.len(), 2);
+        assert!(reminders.due(&tasks, None, day).is_empty());
+        let second = reminders.due(&tasks, None, now + Duration::days(2) + Duration::minutes(1));
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].uid, taxes.uid);
+        let overdue = now + Duration::days(4);
+        assert!(reminders.due(&tasks, None, overdue).is_empty());
+        assert_eq!(reminders.due(&tasks, Some(0), overdue).len(), 1);
+    }
+}
@@ -8,6 +8,16 @@ use std::sync::OnceLock;
 
 // Allow injecting a base path (from Android Context)
 static ANDROID_DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+// Set from the --config command line flag
+static CONFIG_FILE_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+// Set from the --portable command line flag
+static PORTABLE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// A directory with this name next to the executable turns on portable mode by itself.
+pub const PORTABLE_DIR_NAME: &str = "cfait-data";
+
+/// Moves every directory under `$RUSTACHE_HOME/{config,data,cache}` (e.g. one per profile).
+pub const HOME_ENV_VAR: &str = "RUSTACHE_HOME";
 
 pub struct AppPaths;
 
@@ -17,6 +27,52 @@ impl AppPaths {
         let _ = ANDROID_DATA_DIR.set(PathBuf::from(path));
     }
 
+    /// Use this config file instead of the default one. Must be called before the first lookup.
+    pub fn init_config_path(path: PathBuf) {
+        let _ = CONFIG_FILE_OVERRIDE.set(path);
+    }
+
+    /// Keep everything in `dir` (defaults to `cfait-data` next to the executable).
+    /// Must be called before the first lookup.
+    pub fn init_portable(dir: Option<PathBuf>) {
+        if let Some(dir) = dir.or_else(Self::exe_portable_dir) {
+            let _ = PORTABLE_DIR.set(dir);
+        }
+    }
+
+    /// Applies `--config <path>` and `--portable[=<dir>]`.
+    pub fn init_from_args(args: &[String]) {
+        if let Some(path) = Self::config_flag(args) {
+            Self::init_config_path(path);
+        }
+        for arg in args {
+            if arg == "--portable" {
+                Self::init_portable(None);
+            } else if let Some(dir) = arg.strip_prefix("--portable=") {
+                Self::init_portable(Some(PathBuf::from(dir)));
+            }
+        }
+    }
+
+    fn exe_portable_dir() -> Option<PathBuf> {
+        let exe = env::current_exe().ok()?;
+        exe.parent().map(|dir| dir.join(PORTABLE_DIR_NAME))
+    }
+
+    /// Reads `--config <path>` or `--config=<path>` from the command line arguments.
+    pub fn config_flag(args: &[String]) -> Option<PathBuf> {
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if arg == "--config" {
+                return iter.next().map(PathBuf::from);
+            }
+            if let Some(path) = arg.strip_prefix("--config=") {
+                return Some(PathBuf::from(path));
+            }
+        }
+        None
+    }
+
     /// Returns the ProjectDirs struct, common to all path lookups.
     fn get_proj_dirs() -> Option<ProjectDirs> {
         ProjectDirs::from("com", "cfait", "cfait")
@@ -45,7 +101,37 @@ impl AppPaths {
             return Some(PathBuf::from(test_dir));
         }
 
-        // 3. Standard OS location
+        // 3. Portable mode: requested at startup, or a data directory shipped with the binary
+        if let Some(dir) = PORTABLE_DIR.get() {
+            return Some(dir.join(subdir));
+        }
+        if let Some(dir) = Self::exe_portable_dir().filter(|d| d.is_dir()) {
+            return Some(dir.join(subdir));
+        }
+
+        // 4. User Override (all data in one place)
+        if let Some(home) = env::var_os(HOME_ENV_VAR).filter(|v| !v.is_empty()) {
+            return Some(PathBuf::from(home).join(subdir));
+        }
+
+        // 5. XDG variables, also honored where `directories` ignores them (e.g. macOS).
+        // The spec says relative paths are invalid and must be ignored.
+        if cfg!(unix) {
+            let var = match subdir {
+                "data" => "XDG_DATA_HOME",
+                "config" => "XDG_CONFIG_HOME",
+                "cache" => "XDG_CACHE_HOME",
+                _ => return None,
+            };
+            if let Some(dir) = env::var_os(var)
+                .map(PathBuf::from)
+                .filter(|p| p.is_absolute())
+            {
+                return Some(dir.join("cfait"));
+            }
+        }
+
+        // 6. Standard OS location
         let proj = Self::get_proj_dirs()?;
 
         let dir = match subdir {
@@ -77,14 +163,77 @@ impl AppPaths {
     }
 
     pub fn get_config_file_path() -> Result<PathBuf> {
+        if let Some(path) = CONFIG_FILE_OVERRIDE.get() {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                Self::ensure_exists(parent.to_path_buf())?;
+            }
+            return Ok(path.clone());
+        }
         Ok(Self::get_config_dir()?.join("config.toml"))
     }
 
+    /// Where shared .ics files are written: Downloads (or home) on desktop,
+    /// the app's own files on Android and in tests.
+    pub fn get_export_dir() -> Result<PathBuf> {
+        if ANDROID_DATA_DIR.get().is_none()
+            && env::var("CFAIT_TEST_DIR").is_err()
+            && let Some(dirs) = directories::UserDirs::new()
+        {
+            let dir = dirs.download_dir().unwrap_or(dirs.home_dir());
+            return Ok(dir.to_path_buf());
+        }
+        let path = Self::resolve_base("exports")
+            .ok_or_else(|| anyhow::anyhow!("Could not determine export directory"))?;
+        Self::ensure_exists(path)
+    }
+
     pub fn get_journal_path() -> Option<PathBuf> {
         Self::get_data_dir().ok().map(|p| p.join("journal.json"))
     }
 
+    /// `ui` is "tui" or "gui": both can run side by side, each restores its own session
+    pub fn get_session_path(ui: &str) -> Option<PathBuf> {
+        Self::get_data_dir()
+            .ok()
+            .map(|p| p.join(format!("session_{}.json", ui)))
+    }
+
+    /// Next to the other data rather than in the working directory, which is often not
+    /// writable when cfait is started from a shortcut (e.g. System32 on Windows).
+    pub fn get_panic_log_path() -> Option<PathBuf> {
+        Self::get_data_dir().ok().map(|p| p.join("cfait_panic.log"))
+    }
+
+    /// Requests to the server, when `wire_log` is on
+    pub fn get_wire_log_path() -> Option<PathBuf> {
+        Self::get_data_dir().ok().map(|p| p.join("wire.log"))
+    }
+
+    pub fn get_daemon_lock_path() -> Option<PathBuf> {
+        Self::get_data_dir().ok().map(|p| p.join("daemon.lock"))
+    }
+
     pub fn get_local_task_path() -> Option<PathBuf> {
         Self::get_data_dir().ok().map(|p| p.join("local.json"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_flag() {
+        let args = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            AppPaths::config_flag(&args(&["cfait", "--config", "/tmp/work.toml"])),
+            Some(PathBuf::from("/tmp/work.toml"))
+        );
+        assert_eq!(
+            AppPaths::config_flag(&args(&["cfait", "--config=work.toml"])),
+            Some(PathBuf::from("work.toml"))
+        );
+        assert_eq!(AppPaths::config_flag(&args(&["cfait", "--config"])), None);
+        assert_eq!(AppPaths::config_flag(&args(&["cfait"])), None);
+    }
+}
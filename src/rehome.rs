@@ -0,0 +1,214 @@
+// File: ./src/rehome.rs
+// Moving to another server, or to another path on the same one: rewrites the hrefs kept
+// locally (offline queue, mirror, config, sessions) from the old base URL to the new one, so
+// the queued changes reach the new server instead of failing against the old one forever.
+// The queue's ETags are then checked against the new server by `RustyClient::revalidate_journal`.
+use crate::cache::Cache;
+use crate::config::Config;
+use crate::journal::{Action, Journal};
+use crate::model::Task;
+use crate::session::Session;
+use anyhow::{Result, anyhow};
+use http::Uri;
+
+/// Rewrites hrefs under one base path to another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rebase {
+    old: String,
+    new: String,
+}
+
+/// The path of a collection URL, with the trailing slash hrefs are compared with.
+fn base_path(url: &str) -> Result<String> {
+    let uri: Uri = url
+        .trim()
+        .parse()
+        .map_err(|e| anyhow!("Invalid URL '{}': {}", url, e))?;
+    if uri.scheme().is_none() {
+        return Err(anyhow!("'{}' is not a URL (http:// or https://)", url));
+    }
+    let path = uri.path().trim_end_matches('/');
+    Ok(format!("{}/", path))
+}
+
+impl Rebase {
+    pub fn new(old_url: &str, new_url: &str) -> Result<Self> {
+        Ok(Self {
+            old: base_path(old_url)?,
+            new: base_path(new_url)?,
+        })
+    }
+
+    /// The href on the new server, None if `href` is not under the old base (local calendar,
+    /// already moved).
+    pub fn href(&self, href: &str) -> Option<String> {
+        let path = if href.starts_with("http://") || href.starts_with("https://") {
+            href.parse::<Uri>().ok()?.path().to_string()
+        } else if href.starts_with('/') {
+            href.to_string()
+        } else {
+            return None;
+        };
+        // Running twice must not move hrefs again when the new base is below the old one
+        if self.new.starts_with(&self.old) && path.starts_with(&self.new) {
+            return None;
+        }
+        let rest = match path.strip_prefix(&self.old) {
+            Some(rest) => rest,
+            // A calendar href written without its trailing slash
+            None if format!("{}/", path) == self.old => "",
+            None => return None,
+        };
+        let moved = format!("{}{}", self.new, rest);
+        (moved != href).then_some(moved)
+    }
+
+    /// Rebases the task's href and calendar. The ETag is the old server's, so it's dropped
+    /// when the task moved; returns whether it did.
+    pub fn task(&self, task: &mut Task) -> bool {
+        let mut moved = false;
+        if let Some(href) = self.href(&task.href) {
+            task.href = href;
+            moved = true;
+        }
+        if let Some(href) = self.href(&task.calendar_href) {
+            task.calendar_href = href;
+            moved = true;
+        }
+        if moved {
+            task.etag.clear();
+        }
+        moved
+    }
+
+    fn replace(&self, href: &mut String) -> bool {
+        match self.href(href) {
+            Some(moved) => {
+                *href = moved;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RehomeReport {
+    pub queued: usize,
+    pub calendars: usize,
+    pub cached_tasks: usize,
+}
+
+/// Rewrites every href stored locally from `old_url` to `new_url` and makes `new_url` the
+/// server of the config. Sync tokens are dropped: they only mean something to the old server.
+pub fn rehome(old_url: &str, new_url: &str) -> Result<RehomeReport> {
+    let rebase = Rebase::new(old_url, new_url)?;
+    let mut report = RehomeReport::default();
+
+    Journal::modify(|queue| {
+        for action in queue.iter_mut() {
+            let moved = match action {
+                Action::Create(t) | Action::Update(t) | Action::Delete(t) => rebase.task(t),
+                Action::Move(t, target) => rebase.task(t) | rebase.replace(target),
+            };
+            report.queued += moved as usize;
+        }
+    })?;
+
+    let mut calendars = Cache::load_calendars()?;
+    for cal in &mut calendars {
+        let Some(href) = rebase.href(&cal.href) else {
+            continue;
+        };
+        let (mut tasks, _) = Cache::load(&cal.href)?;
+        for task in &mut tasks {
+            report.cached_tasks += rebase.task(task) as usize;
+        }
+        Cache::save(&href, &tasks, None)?;
+        Cache::remove(&cal.href)?;
+        cal.href = href;
+        report.calendars += 1;
+    }
+    Cache::save_calendars(&calendars)?;
+
+    let mut config = Config::load()?;
+    config.url = new_url.trim().to_string();
+    for href in config
+        .hidden_calendars
+        .iter_mut()
+        .chain(config.disabled_calendars.iter_mut())
+    {
+        rebase.replace(href);
+    }
+    config.calendar_defaults = std::mem::take(&mut config.calendar_defaults)
+        .into_iter()
+        .map(|(href, defaults)| (rebase.href(&href).unwrap_or(href), defaults))
+        .collect();
+    // Names are left alone, only hrefs are under the base
+    for calendar in [&mut config.default_calendar, &mut config.inbox_calendar]
+        .into_iter()
+        .flatten()
+    {
+        rebase.replace(calendar);
+    }
+    if let Some(imap) = &mut config.imap
+        && let Some(calendar) = &mut imap.calendar
+    {
+        rebase.replace(calendar);
+    }
+    config.save()?;
+
+    for ui in ["tui", "gui"] {
+        let mut session = Session::load(ui);
+        if let Some(active) = &mut session.active_calendar
+            && rebase.replace(active)
+        {
+            session.save(ui)?;
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_rebase_hrefs() {
+        let rebase = Rebase::new(
+            "https://old.example.com/remote.php/dav/calendars/alice/",
+            "https://new.example.com/dav/calendars/alice",
+        )
+        .unwrap();
+        assert_eq!(
+            rebase.href("/remote.php/dav/calendars/alice/work/x.ics"),
+            Some("/dav/calendars/alice/work/x.ics".to_string())
+        );
+        assert_eq!(
+            rebase.href("https://old.example.com/remote.php/dav/calendars/alice/work/"),
+            Some("/dav/calendars/alice/work/".to_string())
+        );
+        assert_eq!(
+            rebase.href("/remote.php/dav/calendars/alice"),
+            Some("/dav/calendars/alice/".to_string())
+        );
+        assert_eq!(rebase.href("/remote.php/dav/calendars/bob/work/"), None);
+        assert_eq!(rebase.href("local://default"), None);
+
+        let mut task = Task::new("Call Bob", &HashMap::new());
+        task.href = "/remote.php/dav/calendars/alice/work/1.ics".to_string();
+        task.calendar_href = "/remote.php/dav/calendars/alice/work/".to_string();
+        task.etag = "\"old\"".to_string();
+        assert!(rebase.task(&mut task));
+        assert_eq!(task.calendar_href, "/dav/calendars/alice/work/");
+        assert!(task.etag.is_empty());
+
+        // The new base is below the old one: a second run leaves moved hrefs alone
+        let deeper =
+            Rebase::new("https://dav.example.com/", "https://dav.example.com/alice/").unwrap();
+        assert_eq!(deeper.href("/work/"), Some("/alice/work/".to_string()));
+        assert_eq!(deeper.href("/alice/work/"), None);
+        assert!(Rebase::new("dav.example.com", "https://dav.example.com/").is_err());
+    }
+}
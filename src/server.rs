@@ -0,0 +1,451 @@
+// File: ./src/server.rs
+// `cfait serve`: JSON-RPC 2.0 over stdin/stdout, one message per line, for editor plugins and
+// launchers (neovim, Raycast...) that would rather spawn cfait than link the crate. Changes go
+// through `RustyClient` like in the UIs, so they are journaled while offline.
+use crate::client::RustyClient;
+use crate::config::Config;
+use crate::i18n::tr_args;
+use crate::model::{CalendarListEntry, Task};
+use crate::storage::{LOCAL_CALENDAR_HREF, LOCAL_CALENDAR_NAME};
+use crate::store::{FilterOptions, TaskStore};
+use anyhow::{Result, anyhow};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+pub const USAGE: &str = "cfait serve   (JSON-RPC 2.0 on stdin/stdout, one message per line)";
+
+// Standard JSON-RPC error codes
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+/// Anything the operation itself refused (unknown task, validation, network...)
+pub const SERVER_ERROR: i64 = -32000;
+
+#[derive(Debug, Deserialize)]
+pub struct RpcRequest {
+    pub jsonrpc: String,
+    /// None for notifications, which get no response
+    #[serde(default)]
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<String> for RpcError {
+    fn from(message: String) -> Self {
+        Self::new(SERVER_ERROR, message)
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TasksParams {
+    /// Name or href; all calendars when unset
+    pub calendar: Option<String>,
+    /// Same syntax as the search box of the UIs (words, #tag, is:done, cal:...)
+    pub query: String,
+    pub include_done: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateParams {
+    /// Smart syntax, e.g. "Call Bob @tomorrow !1 #work"
+    pub text: String,
+    /// Name or href; the default calendar when unset
+    #[serde(default)]
+    pub calendar: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct UidParams {
+    uid: String,
+}
+
+#[derive(Deserialize)]
+struct UpdateParams {
+    task: Box<Task>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct SyncParams {
+    calendar: Option<String>,
+}
+
+pub enum Call {
+    Calendars,
+    Tasks(TasksParams),
+    Create(CreateParams),
+    /// The whole task, as returned by `tasks`, with its changes
+    Update(Box<Task>),
+    Delete(String),
+    Toggle(String),
+    Sync(Option<String>),
+}
+
+fn params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params)
+        .map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid params: {}", e)))
+}
+
+impl Call {
+    pub fn parse(method: &str, raw: Value) -> Result<Self, RpcError> {
+        // Omitted params count as no params
+        let raw = if raw.is_null() { json!({}) } else { raw };
+        Ok(match method {
+            "calendars" => Call::Calendars,
+            "tasks" => Call::Tasks(params(raw)?),
+            "create" => Call::Create(params(raw)?),
+            "update" => Call::Update(params::<UpdateParams>(raw)?.task),
+            "delete" => Call::Delete(params::<UidParams>(raw)?.uid),
+            "toggle" => Call::Toggle(params::<UidParams>(raw)?.uid),
+            "sync" => Call::Sync(params::<SyncParams>(raw)?.calendar),
+            other => {
+                return Err(RpcError::new(
+                    METHOD_NOT_FOUND,
+                    format!("Unknown method: {}", other),
+                ));
+            }
+        })
+    }
+}
+
+pub struct Server {
+    client: RustyClient,
+    calendars: Vec<CalendarListEntry>,
+    store: TaskStore,
+    config: Config,
+}
+
+impl Server {
+    /// `calendars` are the server's; the Local calendar is added here.
+    pub fn new(client: RustyClient, mut calendars: Vec<CalendarListEntry>, config: Config) -> Self {
+        if !calendars.iter().any(|c| c.href == LOCAL_CALENDAR_HREF) {
+            calendars.push(CalendarListEntry {
+                name: LOCAL_CALENDAR_NAME.to_string(),
+                href: LOCAL_CALENDAR_HREF.to_string(),
+                color: None,
+                is_shared: false,
+                is_read_only: false,
+            });
+        }
+        Self {
+            client,
+            calendars,
+            store: TaskStore::new(),
+            config,
+        }
+    }
+
+    fn find_calendar(&self, wanted: &str) -> Result<String, RpcError> {
+        self.calendars
+            .iter()
+            .find(|c| c.name == wanted || c.href == wanted)
+            .map(|c| c.href.clone())
+            .ok_or_else(|| RpcError::from(format!("Unknown calendar '{}'", wanted)))
+    }
+
+    fn find_task(&self, uid: &str) -> Result<Task, RpcError> {
+        self.store
+            .get_task(uid)
+            .cloned()
+            .ok_or_else(|| RpcError::from(format!("Unknown task '{}'", uid)))
+    }
+
+    /// Refuses changes to calendars the server marked read-only, which it would answer with a
+    /// 403 once the queued change is pushed.
+    fn check_writable(&self, href: &str) -> Result<(), RpcError> {
+        let read_only = self
+            .calendars
+            .iter()
+            .find(|c| c.href == href && c.is_read_only);
+        match read_only {
+            Some(cal) => Err(tr_args("error.read_only", &[("name", &cal.name)]).into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Fetches one calendar (all of them for `None`) into the store.
+    pub async fn sync(&mut self, href: Option<&str>) -> Result<(), String> {
+        match href {
+            Some(href) => {
+                let tasks = self.client.get_tasks(href).await?;
+                self.store.insert(href.to_string(), tasks);
+            }
+            None => {
+                self.store.clear();
+                let report = self.client.get_all_tasks(&self.calendars).await?;
+                // Stdout carries the protocol
+                for line in report.failure_lines(&self.calendars) {
+                    eprintln!("Could not fetch {}", line);
+                }
+                for (href, tasks) in report.tasks {
+                    self.store.insert(href, tasks);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn call(&mut self, call: Call) -> Result<Value, RpcError> {
+        match call {
+            Call::Calendars => Ok(json!(self.calendars)),
+            Call::Tasks(p) => {
+                let href = p.calendar.map(|c| self.find_calendar(&c)).transpose()?;
+                let names: HashMap<String, String> = self
+                    .calendars
+                    .iter()
+                    .map(|c| (c.href.clone(), c.name.clone()))
+                    .collect();
+                let none = HashSet::new();
+                let tasks = self.store.filter(FilterOptions {
+                    active_cal_href: href.as_deref(),
+                    hidden_calendars: &none,
+                    selected_categories: &none,
+                    match_all_categories: false,
+                    search_term: &p.query,
+                    calendar_names: &names,
+                    hide_completed_global: !p.include_done,
+                    cutoff_date: None,
+                    min_duration: None,
+                    max_duration: None,
+                    include_unset_duration: true,
+                    completed_cutoff: None,
+                    fold_subtasks: false,
+                    quick_filters: &[],
+                    hide_not_started: false,
+                });
+                Ok(json!(tasks))
+            }
+            Call::Create(p) => {
+                // Captured tasks wait in the inbox, when there is one
+                let wanted = p
+                    .calendar
+                    .or(self.config.inbox_calendar.clone())
+                    .or(self.config.default_calendar.clone());
+                let href = match wanted {
+                    Some(wanted) => self.find_calendar(&wanted)?,
+                    None => LOCAL_CALENDAR_HREF.to_string(),
+                };
+                let mut task = Task::new(&p.text, &self.config.tag_aliases);
+                if let Some(defaults) = self.config.calendar_defaults.get(&href) {
+                    defaults.apply(&mut task);
+                }
+                self.check_writable(&href)?;
+                task.calendar_href = href;
+                self.store
+                    .place_new_task(&mut task, self.config.new_task_placement, None);
+                self.client.create_task(&mut task).await?;
+                self.store.add_task(task.clone());
+                Ok(json!(task))
+            }
+            Call::Update(mut task) => {
+                let current = self.find_task(&task.uid)?;
+                self.check_writable(&current.calendar_href)?;
+                self.check_writable(&task.calendar_href)?;
+                self.client.update_task(&mut task).await?;
+                self.store.update_or_add_task((*task).clone());
+                Ok(json!(task))
+            }
+            Call::Delete(uid) => {
+                let task = self.find_task(&uid)?;
+                self.check_writable(&task.calendar_href)?;
+                self.client.delete_task(&task).await?;
+                self.store.delete_task(&uid);
+                Ok(json!(true))
+            }
+            Call::Toggle(uid) => {
+                self.check_writable(&self.find_task(&uid)?.calendar_href)?;
+                let mut task = self.store.toggle_task(&uid).ok_or_else(|| {
+                    RpcError::from(format!("'{}' is a note and cannot be done", uid))
+                })?;
+                let (task, next, _) = self.client.toggle_task(&mut task).await?;
+                // The saved copy carries the new ETag, sequence and timestamps
+                self.store.update_or_add_task(task.clone());
+                if let Some(next) = next {
+                    self.store.add_task(next);
+                }
+                Ok(json!(task))
+            }
+            Call::Sync(calendar) => {
+                let href = calendar.map(|c| self.find_calendar(&c)).transpose()?;
+                self.sync(href.as_deref()).await?;
+                Ok(json!(true))
+            }
+        }
+    }
+}
+
+impl Responder for Server {
+    async fn respond(&mut self, line: &str) -> Option<Value> {
+        let request = match parse_request(line) {
+            Ok(request) => request,
+            Err(error) => return Some(error),
+        };
+        let result = match Call::parse(&request.method, request.params) {
+            Ok(call) => self.call(call).await,
+            Err(e) => Err(e),
+        };
+        response(request.id, result)
+    }
+}
+
+/// A request, or the error response to send back when it is not one.
+pub fn parse_request(line: &str) -> Result<RpcRequest, Value> {
+    let value: Value = serde_json::from_str(line)
+        .map_err(|e| error_response(Value::Null, PARSE_ERROR, e.to_string()))?;
+    let id = value.get("id").cloned().unwrap_or(Value::Null);
+    let request: RpcRequest = serde_json::from_value(value)
+        .map_err(|e| error_response(id.clone(), INVALID_REQUEST, e.to_string()))?;
+    if request.jsonrpc != "2.0" {
+        return Err(error_response(
+            id,
+            INVALID_REQUEST,
+            "jsonrpc must be \"2.0\"",
+        ));
+    }
+    Ok(request)
+}
+
+/// The response to request `id`; None for notifications, which have no id.
+pub fn response(id: Option<Value>, result: Result<Value, RpcError>) -> Option<Value> {
+    let id = id?;
+    Some(match result {
+        Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+        Err(e) => json!({"jsonrpc": "2.0", "id": id, "error": e}),
+    })
+}
+
+fn error_response(id: Value, code: i64, message: impl Into<String>) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "error": RpcError::new(code, message)})
+}
+
+/// Answers JSON-RPC messages, one per line: `Server` and the MCP server (`crate::mcp`).
+pub trait Responder {
+    /// The response to one line of input; None for notifications.
+    fn respond(&mut self, line: &str) -> impl Future<Output = Option<Value>>;
+}
+
+/// Answers requests until `input` ends.
+pub async fn serve<H, R, W>(handler: &mut H, input: R, mut output: W) -> Result<()>
+where
+    H: Responder,
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut lines = BufReader::new(input).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handler.respond(&line).await {
+            let mut out = response.to_string();
+            out.push('\n');
+            output.write_all(out.as_bytes()).await?;
+            output.flush().await?;
+        }
+    }
+    Ok(())
+}
+
+/// The server with every calendar loaded, connected like the UIs (the local replica when
+/// offline). Messages for humans go to stderr, stdout is the protocol's.
+pub async fn connect() -> Result<Server> {
+    let config = Config::load()?;
+    let (client, calendars, _, _, warning) = RustyClient::connect_with_fallback(config.clone())
+        .await
+        .map_err(|e| anyhow!(e))?;
+    if let Some(w) = warning {
+        eprintln!("{}: changes are queued until the next sync.", w);
+    }
+    let mut server = Server::new(client, calendars, config);
+    server.sync(None).await.map_err(|e| anyhow!(e))?;
+    Ok(server)
+}
+
+pub async fn run(_args: &[String]) -> Result<()> {
+    let mut server = connect().await?;
+    serve(&mut server, tokio::io::stdin(), tokio::io::stdout()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::TaskStatus;
+
+    #[test]
+    fn test_parse_calls() {
+        assert!(matches!(
+            Call::parse("calendars", Value::Null),
+            Ok(Call::Calendars)
+        ));
+        match Call::parse("tasks", json!({"query": "#work"})) {
+            Ok(Call::Tasks(p)) => {
+                assert_eq!(p.query, "#work");
+                assert!(!p.include_done && p.calendar.is_none());
+            }
+            _ => panic!("expected tasks"),
+        }
+        assert!(matches!(
+            Call::parse("toggle", json!({"uid": "abc"})),
+            Ok(Call::Toggle(uid)) if uid == "abc"
+        ));
+        let err = Call::parse("create", json!({})).err().unwrap();
+        assert_eq!(err.code, INVALID_PARAMS);
+        let err = Call::parse("explode", Value::Null).err().unwrap();
+        assert_eq!(err.code, METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_read_only_calendar_refuses_changes() {
+        let shared = CalendarListEntry {
+            name: "Team".to_string(),
+            href: "/cal/team/".to_string(),
+            color: None,
+            is_shared: true,
+            is_read_only: true,
+        };
+        let client = RustyClient::new("", "", "", false).unwrap();
+        let mut server = Server::new(client, vec![shared], Config::default());
+        let href = "/cal/team/".to_string();
+        let mut task = Task::new("Review budget", &Default::default());
+        task.calendar_href = href.clone();
+        let uid = task.uid.clone();
+        server.store.insert(href, vec![task.clone()]);
+
+        let create = Call::parse("create", json!({"text": "New", "calendar": "Team"})).unwrap();
+        let err = server.call(create).await.unwrap_err();
+        assert_eq!(err.code, SERVER_ERROR);
+        assert!(err.message.contains("Team"));
+
+        let err = server.call(Call::Toggle(uid.clone())).await.unwrap_err();
+        assert_eq!(err.code, SERVER_ERROR);
+        let stored = server.store.get_task(&uid).unwrap();
+        assert_eq!(stored.status, TaskStatus::NeedsAction);
+
+        assert!(server.call(Call::Delete(uid.clone())).await.is_err());
+        assert!(server.call(Call::Update(Box::new(task))).await.is_err());
+        assert!(server.store.get_task(&uid).is_some());
+    }
+}
@@ -0,0 +1,72 @@
+// File: ./src/session.rs
+// Where the user left the UI (calendar, selection, sidebar, tags, grouping, scroll position),
+// restored at the next start. Kept out of the config, which is meant to be edited by hand.
+use crate::paths::AppPaths;
+use crate::storage::LocalStorage;
+use crate::store::GroupBy;
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SidebarView {
+    #[default]
+    Calendars,
+    Tags,
+    /// TUI only; the GUI falls back to the calendars
+    Agenda,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub active_calendar: Option<String>,
+    pub selected_task: Option<String>,
+    pub sidebar: SidebarView,
+    pub selected_tags: Vec<String>,
+    pub match_all_tags: bool,
+    pub group_by: GroupBy,
+    pub collapsed_groups: Vec<String>,
+    /// First visible line of the task list (TUI)
+    pub list_offset: usize,
+    /// Selected line of the sidebar (TUI)
+    pub sidebar_selected: usize,
+    /// Relative scroll position of the task list, 0-1 (GUI)
+    pub scroll_y: f32,
+}
+
+impl Session {
+    /// The session `ui` ("tui" or "gui") saved last; the defaults if there is none or it is
+    /// unreadable, which must never keep the app from starting.
+    pub fn load(ui: &str) -> Self {
+        AppPaths::get_session_path(ui)
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, ui: &str) -> Result<()> {
+        let path = AppPaths::get_session_path(ui)
+            .ok_or_else(|| anyhow!("No data directory for the session"))?;
+        LocalStorage::atomic_write(path, serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_session_keeps_defaults() {
+        let session: Session =
+            serde_json::from_str(r#"{"sidebar": "agenda", "group_by": "Tag"}"#).unwrap();
+        assert_eq!(session.sidebar, SidebarView::Agenda);
+        assert_eq!(session.group_by, GroupBy::Tag);
+        assert_eq!(session.active_calendar, None);
+        assert!(session.selected_tags.is_empty());
+
+        let json = serde_json::to_string(&session).unwrap();
+        assert_eq!(serde_json::from_str::<Session>(&json).unwrap(), session);
+    }
+}
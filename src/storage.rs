@@ -2,35 +2,101 @@
 use crate::model::Task;
 use crate::paths::AppPaths;
 use anyhow::Result;
+use chrono::{Duration, Utc};
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 #[cfg(not(target_os = "android"))]
 use fs2::FileExt;
 
 // Constants for identification
-pub const LOCAL_CALENDAR_HREF: &str = "local://default";
+pub use crate::model::LOCAL_CALENDAR_HREF;
 pub const LOCAL_CALENDAR_NAME: &str = "Local";
 
 pub struct LocalStorage;
 
+/// Held by `cfait daemon` while it runs. The UIs then leave syncing and reminders to it
+/// and read the local replica it keeps up to date.
+#[cfg(not(target_os = "android"))]
+pub struct DaemonLock {
+    _file: fs::File,
+}
+
+#[cfg(not(target_os = "android"))]
+impl DaemonLock {
+    fn open() -> Result<fs::File> {
+        let path = AppPaths::get_daemon_lock_path()
+            .ok_or_else(|| anyhow::anyhow!("Could not determine data directory"))?;
+        Ok(fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?)
+    }
+
+    /// Released when dropped (or when the process dies).
+    pub fn acquire() -> Result<Self> {
+        let file = Self::open()?;
+        FileExt::try_lock_exclusive(&file)
+            .map_err(|_| anyhow::anyhow!("Another cfait daemon is already running"))?;
+        Ok(Self { _file: file })
+    }
+
+    pub fn is_held() -> bool {
+        Self::open().is_ok_and(|file| FileExt::try_lock_shared(&file).is_err())
+    }
+}
+
+/// Modification times of the files shared by all cfait processes (journal, local tasks and
+/// cache), to notice when another instance wrote them.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DataStamp(Vec<Option<SystemTime>>);
+
+impl DataStamp {
+    pub fn current() -> Self {
+        let mtime = |path: Option<PathBuf>| {
+            path.and_then(|p| fs::metadata(p).ok())
+                .and_then(|m| m.modified().ok())
+        };
+        // Cache files are replaced by a rename, which touches their directory
+        Self(vec![
+            mtime(AppPaths::get_journal_path()),
+            mtime(LocalStorage::get_path()),
+            mtime(AppPaths::get_cache_dir().ok()),
+        ])
+    }
+}
+
 impl LocalStorage {
     pub fn get_path() -> Option<PathBuf> {
         AppPaths::get_local_task_path()
     }
 
-    /// Helper to get a sidecar lock file path (e.g., "local.json.lock")
-    #[cfg(not(target_os = "android"))]
-    fn get_lock_path(file_path: &Path) -> PathBuf {
-        let mut lock_path = file_path.to_path_buf();
-        if let Some(ext) = lock_path.extension() {
+    /// Helper to get a sidecar file path (e.g., "local.json.lock")
+    fn get_sidecar_path(file_path: &Path, suffix: &str) -> PathBuf {
+        let mut sidecar = file_path.to_path_buf();
+        if let Some(ext) = sidecar.extension() {
             let mut new_ext = ext.to_os_string();
-            new_ext.push(".lock");
-            lock_path.set_extension(new_ext);
+            new_ext.push(".");
+            new_ext.push(suffix);
+            sidecar.set_extension(new_ext);
         } else {
-            lock_path.set_extension("lock");
+            sidecar.set_extension(suffix);
         }
-        lock_path
+        sidecar
+    }
+
+    #[cfg(not(target_os = "android"))]
+    fn get_lock_path(file_path: &Path) -> PathBuf {
+        Self::get_sidecar_path(file_path, "lock")
+    }
+
+    /// The previous version of the local tasks, restored if the file gets corrupted
+    fn get_backup_path(file_path: &Path) -> PathBuf {
+        Self::get_sidecar_path(file_path, "bak")
     }
 
     /// Execute a closure while holding an exclusive lock on the sidecar file.
@@ -62,38 +128,146 @@ impl LocalStorage {
         }
     }
 
-    /// Atomic write: Write to .tmp file then rename
+    /// Atomic write: Write to .tmp file, flush it to disk, then rename. A crash leaves either
+    /// the old or the new contents, never a truncated file.
     pub fn atomic_write<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> Result<()> {
         let path = path.as_ref();
         let tmp_path = path.with_extension("tmp");
-        fs::write(&tmp_path, contents)?;
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(contents.as_ref())?;
+        file.sync_all()?;
+        drop(file);
         fs::rename(tmp_path, path)?;
+
+        // Persist the rename itself
+        #[cfg(unix)]
+        if let Some(dir) = path.parent()
+            && let Ok(dir) = fs::File::open(dir)
+        {
+            let _ = dir.sync_all();
+        }
         Ok(())
     }
 
-    pub fn save(tasks: &[Task]) -> Result<()> {
+    /// Writes a task as a standalone .ics file for sharing and returns its path.
+    pub fn export_ics(task: &Task) -> Result<PathBuf> {
+        let path = AppPaths::get_export_dir()?.join(task.share_file_name());
+        Self::atomic_write(&path, task.to_share_ics())?;
+        Ok(path)
+    }
+
+    /// Writes a printable page (see `model::agenda`) to the export folder and returns its path.
+    pub fn export_page(name: &str, html: &str) -> Result<PathBuf> {
+        let path = AppPaths::get_export_dir()?.join(format!("{}.html", name));
+        Self::atomic_write(&path, html)?;
+        Ok(path)
+    }
+
+    /// Internal load helper (no locking). Falls back to the backup if the file is unreadable.
+    fn load_internal(path: &Path) -> Result<Vec<Task>> {
+        if !path.exists() {
+            return Ok(vec![]);
+        }
+        let json = fs::read_to_string(path)?;
+        match serde_json::from_str::<Vec<Task>>(&json) {
+            Ok(tasks) => Ok(tasks),
+            Err(e) => fs::read_to_string(Self::get_backup_path(path))
+                .ok()
+                .and_then(|backup| serde_json::from_str::<Vec<Task>>(&backup).ok())
+                .ok_or_else(|| e.into()),
+        }
+    }
+
+    /// Internal save helper (no locking): rotates the current file, if sound, into the backup.
+    fn save_internal(path: &Path, tasks: &[Task]) -> Result<()> {
+        if let Ok(current) = fs::read_to_string(path)
+            && serde_json::from_str::<Vec<Task>>(&current).is_ok()
+        {
+            Self::atomic_write(Self::get_backup_path(path), current)?;
+        }
+        let json = serde_json::to_string_pretty(tasks)?;
+        Self::atomic_write(path, json)
+    }
+
+    /// Transactional modification of the local tasks, so that two instances editing at the
+    /// same time don't overwrite each other's changes.
+    pub fn modify<F>(f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Vec<Task>),
+    {
         if let Some(path) = Self::get_path() {
             Self::with_lock(&path, || {
-                let json = serde_json::to_string_pretty(tasks)?;
-                Self::atomic_write(&path, json)?;
-                Ok(())
+                let mut tasks = Self::load_internal(&path)?;
+                f(&mut tasks);
+                Self::save_internal(&path, &tasks)
             })?;
         }
         Ok(())
     }
 
+    /// Drops the done tasks completed more than `days` ago (see `cache::prune_completed`);
+    /// returns how many went. The file is only rewritten when some do.
+    pub fn prune(days: u32) -> Result<usize> {
+        let cutoff = Utc::now() - Duration::days(days as i64);
+        let (_, old) = crate::cache::prune_completed(Self::load()?, cutoff);
+        if old.is_empty() {
+            return Ok(0);
+        }
+        let mut count = 0;
+        Self::modify(|tasks| {
+            let (kept, pruned) = crate::cache::prune_completed(std::mem::take(tasks), cutoff);
+            count = pruned.len();
+            *tasks = kept;
+        })?;
+        Ok(count)
+    }
+
+    pub fn save(tasks: &[Task]) -> Result<()> {
+        if let Some(path) = Self::get_path() {
+            Self::with_lock(&path, || Self::save_internal(&path, tasks))?;
+        }
+        Ok(())
+    }
+
     pub fn load() -> Result<Vec<Task>> {
         if let Some(path) = Self::get_path() {
             if !path.exists() {
                 return Ok(vec![]);
             }
-            return Self::with_lock(&path, || {
-                let json = fs::read_to_string(&path)?;
-                // CHANGE: Propagate error instead of checking `if let Ok`
-                let tasks = serde_json::from_str::<Vec<Task>>(&json)?;
-                Ok(tasks)
-            });
+            // Propagate parse errors instead of treating the file as empty
+            return Self::with_lock(&path, || Self::load_internal(&path));
         }
         Ok(vec![])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_corrupted_file_falls_back_to_backup() {
+        let dir = std::env::temp_dir().join(format!("cfait-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("local.json");
+        let first = vec![Task::new("First", &HashMap::new())];
+        let second = vec![Task::new("Second", &HashMap::new())];
+
+        LocalStorage::save_internal(&path, &first).unwrap();
+        LocalStorage::save_internal(&path, &second).unwrap();
+        assert_eq!(
+            LocalStorage::load_internal(&path).unwrap()[0].summary,
+            "Second"
+        );
+        assert!(!path.with_extension("tmp").exists());
+
+        // Torn write from before atomic saves: the previous version is used
+        fs::write(&path, "[{\"uid\": \"trunc").unwrap();
+        assert_eq!(
+            LocalStorage::load_internal(&path).unwrap()[0].summary,
+            "First"
+        );
+        let _ = fs::remove_dir_all(dir);
+    }
+}
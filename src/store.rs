@@ -1,29 +1,474 @@
 // File: src/store.rs
+#[cfg(feature = "native")]
 use crate::cache::Cache;
-use crate::model::{Task, TaskStatus};
-use chrono::{DateTime, Utc};
+use crate::model::matcher::SearchQuery;
+use crate::model::{Duration, Task, TaskStatus, priority};
+use chrono::{DateTime, Days, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, mpsc};
+use uuid::Uuid;
 
 pub const UNCATEGORIZED_ID: &str = ":::uncategorized:::";
 
+/// One section of the agenda; `date` is None for the overdue tasks.
+#[derive(Debug, Clone)]
+pub struct AgendaDay {
+    pub date: Option<NaiveDate>,
+    pub tasks: Vec<Task>,
+}
+
+impl AgendaDay {
+    pub fn label(&self, today: NaiveDate) -> String {
+        match self.date {
+            None => "Overdue".to_string(),
+            Some(d) if d == today => "Today".to_string(),
+            Some(d) if today.succ_opt() == Some(d) => "Tomorrow".to_string(),
+            Some(d) => d.format("%a %d %b").to_string(),
+        }
+    }
+}
+
+/// Open tasks of a calendar and their summed estimate, for the sidebar.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CalendarStats {
+    pub open: usize,
+    pub estimated_minutes: u32,
+}
+
+impl CalendarStats {
+    /// "5" or "5 · ~2h"; empty without open tasks
+    pub fn label(&self) -> String {
+        match (self.open, self.estimated_minutes) {
+            (0, _) => String::new(),
+            (n, 0) => n.to_string(),
+            (n, mins) => format!("{} · ~{}", n, Duration::from_minutes(mins)),
+        }
+    }
+}
+
+/// Where new tasks go among the tasks of the same priority and due date, through their
+/// manual order (X-APPLE-SORT-ORDER).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NewTaskPlacement {
+    /// Wherever the sort puts them (no manual order)
+    #[default]
+    Sorted,
+    Top,
+    /// After the manually ordered tasks
+    Bottom,
+    /// Next to the selected task, under the same parent
+    AfterSelection,
+}
+
+/// What the lists do with open tasks whose start date has not come yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotStarted {
+    #[default]
+    Show,
+    /// Greyed out
+    Dim,
+    /// Left out of the lists until their start date
+    Hide,
+}
+
+impl NotStarted {
+    /// The next option, for the quick toggles
+    pub fn next(self) -> Self {
+        match self {
+            NotStarted::Show => NotStarted::Dim,
+            NotStarted::Dim => NotStarted::Hide,
+            NotStarted::Hide => NotStarted::Show,
+        }
+    }
+}
+
+impl std::fmt::Display for NotStarted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            NotStarted::Show => "shown",
+            NotStarted::Dim => "dimmed",
+            NotStarted::Hide => "hidden",
+        })
+    }
+}
+
+/// One operation on several selected tasks at once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkAction {
+    Complete,
+    Delete,
+    /// To the calendar of this href
+    Move(String),
+    AddTag(String),
+}
+
+/// What a key does in the task list, the same in the TUI and the GUI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListKey {
+    Next,
+    Previous,
+    PageDown,
+    PageUp,
+    /// Done / not done
+    Toggle,
+    /// The task's details (groups fold with it in the TUI)
+    Expand,
+    /// Into the input, as a smart string
+    Edit,
+    Delete,
+}
+
+impl ListKey {
+    /// Rows PageDown and PageUp skip
+    pub const PAGE: usize = 10;
+
+    /// A character, or a named key: "Down", "Up", "PageDown", "PageUp", "Enter", "Delete".
+    pub fn from_key(key: &str) -> Option<Self> {
+        Some(match key {
+            "Down" | "j" => ListKey::Next,
+            "Up" | "k" => ListKey::Previous,
+            "PageDown" => ListKey::PageDown,
+            "PageUp" => ListKey::PageUp,
+            " " => ListKey::Toggle,
+            "Enter" => ListKey::Expand,
+            "e" => ListKey::Edit,
+            "Delete" | "d" => ListKey::Delete,
+            _ => return None,
+        })
+    }
+}
+
+/// How list views split their tasks into collapsible sections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GroupBy {
+    #[default]
+    None,
+    Calendar,
+    Tag,
+    Priority,
+    Due,
+}
+
+impl GroupBy {
+    pub const ALL: [GroupBy; 5] = [
+        GroupBy::None,
+        GroupBy::Calendar,
+        GroupBy::Tag,
+        GroupBy::Priority,
+        GroupBy::Due,
+    ];
+
+    /// The next option, for UIs that cycle through them with one key
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|g| *g == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+impl std::fmt::Display for GroupBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            GroupBy::None => "No grouping",
+            GroupBy::Calendar => "Calendar",
+            GroupBy::Tag => "Tag",
+            GroupBy::Priority => "Priority",
+            GroupBy::Due => "Due date",
+        })
+    }
+}
+
+/// One section of a grouped list. `key` is stable across refreshes (for collapsed state).
+#[derive(Debug, Clone)]
+pub struct TaskGroup {
+    pub key: String,
+    pub label: String,
+    pub tasks: Vec<Task>,
+}
+
+/// Splits the output of `TaskStore::filter` into groups, keeping the task order inside each
+/// one. Tasks with several tags appear under each of them; empty groups are left out.
+pub fn group_tasks(
+    tasks: Vec<Task>,
+    by: GroupBy,
+    calendar_names: &HashMap<String, String>,
+    today: NaiveDate,
+) -> Vec<TaskGroup> {
+    // (sort key, group key, label) for every group a task belongs to
+    let keys_of = |t: &Task| -> Vec<(String, String, String)> {
+        match by {
+            GroupBy::None => vec![(String::new(), String::new(), String::new())],
+            GroupBy::Calendar => {
+                let name = calendar_names
+                    .get(&t.calendar_href)
+                    .cloned()
+                    .unwrap_or_else(|| t.calendar_href.clone());
+                vec![(name.to_lowercase(), t.calendar_href.clone(), name)]
+            }
+            GroupBy::Tag if t.categories.is_empty() => vec![(
+                "\u{10ffff}".to_string(),
+                UNCATEGORIZED_ID.to_string(),
+                "No tags".to_string(),
+            )],
+            GroupBy::Tag => t
+                .categories
+                .iter()
+                .map(|c| (c.to_lowercase(), c.clone(), format!("#{}", c)))
+                .collect(),
+            GroupBy::Priority => {
+                let (rank, key, label) = match t.priority.value() {
+                    1..=4 => ("0", "high", "High priority"),
+                    5 => ("1", "medium", "Medium priority"),
+                    6..=9 => ("2", "low", "Low priority"),
+                    _ => ("3", "none", "No priority"),
+                };
+                vec![(rank.to_string(), key.to_string(), label.to_string())]
+            }
+            GroupBy::Due => {
+                let day = t.due.map(|d| d.with_timezone(&Local).date_naive());
+                let (rank, key, label) = match day {
+                    Some(d) if d < today => ("0", "overdue", "Overdue"),
+                    Some(d) if d == today => ("1", "today", "Today"),
+                    Some(d) if d <= today + Days::new(7) => ("2", "week", "Next 7 days"),
+                    Some(_) => ("3", "later", "Later"),
+                    None => ("4", "none", "No due date"),
+                };
+                vec![(rank.to_string(), key.to_string(), label.to_string())]
+            }
+        }
+    };
+
+    let mut groups: Vec<(String, TaskGroup)> = Vec::new();
+    for mut task in tasks {
+        // Parents and children only stay together when grouped by calendar
+        if !matches!(by, GroupBy::None | GroupBy::Calendar) {
+            task.depth = 0;
+        }
+        for (sort, key, label) in keys_of(&task) {
+            match groups.iter_mut().find(|(_, g)| g.key == key) {
+                Some((_, group)) => group.tasks.push(task.clone()),
+                None => groups.push((
+                    sort,
+                    TaskGroup {
+                        key,
+                        label,
+                        tasks: vec![task.clone()],
+                    },
+                )),
+            }
+        }
+    }
+    groups.sort_by(|a, b| a.0.cmp(&b.0));
+    groups.into_iter().map(|(_, g)| g).collect()
+}
+
+/// A line of a grouped task list
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListRow {
+    Group {
+        key: String,
+        label: String,
+        count: usize,
+        collapsed: bool,
+    },
+    /// Index into the task list returned alongside the rows
+    Task(usize),
+}
+
+/// Lays groups out for a list view: the tasks left visible by `collapsed` (group keys), and
+/// the rows showing them under their headers.
+pub fn flatten_groups(
+    groups: Vec<TaskGroup>,
+    collapsed: &HashSet<String>,
+) -> (Vec<Task>, Vec<ListRow>) {
+    let mut tasks = Vec::new();
+    let mut rows = Vec::new();
+    for group in groups {
+        let is_collapsed = collapsed.contains(&group.key);
+        rows.push(ListRow::Group {
+            key: group.key,
+            label: group.label,
+            count: group.tasks.len(),
+            collapsed: is_collapsed,
+        });
+        if !is_collapsed {
+            for task in group.tasks {
+                rows.push(ListRow::Task(tasks.len()));
+                tasks.push(task);
+            }
+        }
+    }
+    (tasks, rows)
+}
+
+/// Writes a calendar's tasks to the disk cache, keeping its sync token. The browser build has
+/// no disk: its frontend saves the store through `web::IdbStorage` instead.
+fn persist(href: &str, tasks: &[Task]) {
+    #[cfg(feature = "native")]
+    {
+        let (_, token) = Cache::load(href).unwrap_or((vec![], None));
+        let _ = Cache::save(href, tasks, token);
+    }
+
+    #[cfg(not(feature = "native"))]
+    let _ = (href, tasks);
+}
+
+/// What changed in a `TaskStore`, by UID (or calendar href): the frontends read the tasks
+/// back from the store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoreEvent {
+    TaskAdded(String),
+    TaskUpdated(String),
+    TaskRemoved(String),
+    /// All the tasks of the calendar were replaced (after a sync, a reload or a clear)
+    CalendarLoaded(String),
+}
+
+/// The subscribers of a store: each gets every event. Shared by the clones of the store, and
+/// equal (and hashed) by identity, so the GUI can key a subscription on it.
+#[derive(Debug, Clone, Default)]
+pub struct ChangeFeed(Arc<Mutex<Vec<mpsc::Sender<StoreEvent>>>>);
+
+impl ChangeFeed {
+    /// Events from now on, until the receiver is dropped.
+    pub fn subscribe(&self) -> mpsc::Receiver<StoreEvent> {
+        let (tx, rx) = mpsc::channel();
+        if let Ok(mut subscribers) = self.0.lock() {
+            subscribers.push(tx);
+        }
+        rx
+    }
+
+    fn emit(&self, event: StoreEvent) {
+        if let Ok(mut subscribers) = self.0.lock() {
+            subscribers.retain(|tx| tx.send(event.clone()).is_ok());
+        }
+    }
+}
+
+impl PartialEq for ChangeFeed {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for ChangeFeed {}
+
+impl Hash for ChangeFeed {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state);
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct TaskStore {
     pub calendars: HashMap<String, Vec<Task>>,
     /// Reverse index: Maps Task UID -> Calendar HREF for O(1) lookups
     pub index: HashMap<String, String>,
+    /// Mutations go through the methods below, which announce them here
+    changes: ChangeFeed,
 }
 
+/// Chips of the GUI's quick filters bar, applied on top of the search and the sidebar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuickFilter {
+    /// Open tasks due today (with Overdue: due by the end of today)
+    DueToday,
+    /// Open tasks past their due date
+    Overdue,
+    /// Tasks in the most urgent priority band
+    Urgent,
+}
+
+impl QuickFilter {
+    pub const ALL: [QuickFilter; 3] = [Self::DueToday, Self::Overdue, Self::Urgent];
+}
+
+/// The due chips widen each other, the others narrow the list.
+fn matches_quick_filters(task: &Task, filters: &[QuickFilter], now: DateTime<Utc>) -> bool {
+    if filters.contains(&QuickFilter::Urgent) && !priority::is_urgent(task.priority) {
+        return false;
+    }
+    let today = filters.contains(&QuickFilter::DueToday);
+    let overdue = filters.contains(&QuickFilter::Overdue);
+    if !today && !overdue {
+        return true;
+    }
+    let Some(due) = task.due.filter(|_| !task.status.is_done()) else {
+        return false;
+    };
+    let is_today = due.with_timezone(&Local).date_naive() == now.with_timezone(&Local).date_naive();
+    (today && is_today) || (overdue && due < now)
+}
+
+/// How pressing a due date is, from least to most; done and undated tasks have none.
+/// Counted in days, the due date being the UTC day it ends (see `model::due::full`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Urgency {
+    None,
+    /// Due within the next `due_soon_days` days
+    Soon,
+    Today,
+    /// Due before today
+    Overdue,
+}
+
+pub fn urgency(task: &Task, now: DateTime<Utc>, soon_days: u32) -> Urgency {
+    let Some(due) = task.due.filter(|_| !task.status.is_done()) else {
+        return Urgency::None;
+    };
+    let days = (due.date_naive() - now.with_timezone(&Local).date_naive()).num_days();
+    if days < 0 {
+        Urgency::Overdue
+    } else if days == 0 {
+        Urgency::Today
+    } else if days <= soon_days as i64 {
+        Urgency::Soon
+    } else {
+        Urgency::None
+    }
+}
+
+/// Whether a list holds tasks of more than one calendar, which the rows then tell apart.
+pub fn mixes_calendars(tasks: &[Task]) -> bool {
+    tasks
+        .windows(2)
+        .any(|w| w[0].calendar_href != w[1].calendar_href)
+}
+
+/// Open tasks starting on a later day than today (the start date's UTC day, like due dates).
+pub fn not_started(task: &Task, now: DateTime<Utc>) -> bool {
+    !task.status.is_done()
+        && task
+            .dtstart
+            .is_some_and(|start| start.date_naive() > now.with_timezone(&Local).date_naive())
+}
+
+#[derive(Clone, Copy)]
 pub struct FilterOptions<'a> {
     pub active_cal_href: Option<&'a str>,
     pub hidden_calendars: &'a std::collections::HashSet<String>,
     pub selected_categories: &'a HashSet<String>,
     pub match_all_categories: bool,
     pub search_term: &'a str,
+    /// Display names by href, for the `cal:` search operator
+    pub calendar_names: &'a HashMap<String, String>,
     pub hide_completed_global: bool,
     pub cutoff_date: Option<DateTime<Utc>>,
     pub min_duration: Option<u32>,
     pub max_duration: Option<u32>,
     pub include_unset_duration: bool,
+    /// Done tasks completed before this are left out (retention window)
+    pub completed_cutoff: Option<DateTime<Utc>>,
+    /// Leave out the children of tasks with `hide_subtasks` (only while the tree is shown)
+    pub fold_subtasks: bool,
+    pub quick_filters: &'a [QuickFilter],
+    /// Leave out open tasks that start on a later day (`NotStarted::Hide`)
+    pub hide_not_started: bool,
 }
 
 impl TaskStore {
@@ -31,17 +476,35 @@ impl TaskStore {
         Self::default()
     }
 
+    /// The feed of the changes, for `ChangeFeed::subscribe`.
+    pub fn changes(&self) -> ChangeFeed {
+        self.changes.clone()
+    }
+
+    pub fn subscribe(&self) -> mpsc::Receiver<StoreEvent> {
+        self.changes.subscribe()
+    }
+
+    /// Tells the subscribers that `task` changed, and hands it back.
+    fn updated(&self, task: Task) -> Option<Task> {
+        self.changes.emit(StoreEvent::TaskUpdated(task.uid.clone()));
+        Some(task)
+    }
+
     pub fn insert(&mut self, calendar_href: String, tasks: Vec<Task>) {
         for task in &tasks {
             self.index.insert(task.uid.clone(), calendar_href.clone());
         }
-        self.calendars.insert(calendar_href, tasks);
+        self.calendars.insert(calendar_href.clone(), tasks);
+        self.changes.emit(StoreEvent::CalendarLoaded(calendar_href));
     }
 
     pub fn add_task(&mut self, task: Task) {
         let href = task.calendar_href.clone();
-        self.index.insert(task.uid.clone(), href.clone());
+        let uid = task.uid.clone();
+        self.index.insert(uid.clone(), href.clone());
         self.calendars.entry(href).or_default().push(task);
+        self.changes.emit(StoreEvent::TaskAdded(uid));
     }
 
     /// Updates an existing task or adds it if missing.
@@ -54,24 +517,34 @@ impl TaskStore {
 
         let list = self.calendars.entry(href.clone()).or_default();
 
-        if let Some(idx) = list.iter().position(|t| t.uid == task.uid) {
+        let uid = task.uid.clone();
+        let event = if let Some(idx) = list.iter().position(|t| t.uid == task.uid) {
             list[idx] = task;
+            StoreEvent::TaskUpdated(uid)
         } else {
             list.push(task);
-        }
+            StoreEvent::TaskAdded(uid)
+        };
 
         // Persist
-        let (_, token) = Cache::load(&href).unwrap_or((vec![], None));
-        let _ = Cache::save(&href, list, token);
+        persist(&href, list);
+        self.changes.emit(event);
     }
 
     pub fn clear(&mut self) {
-        self.calendars.clear();
         self.index.clear();
+        for (href, _) in self.calendars.drain() {
+            self.changes.emit(StoreEvent::CalendarLoaded(href));
+        }
     }
 
     // --- Core Logic Helpers ---
 
+    pub fn get_task(&self, uid: &str) -> Option<&Task> {
+        let href = self.index.get(uid)?;
+        self.calendars.get(href)?.iter().find(|t| t.uid == uid)
+    }
+
     pub fn get_task_mut(&mut self, uid: &str) -> Option<(&mut Task, String)> {
         let href = self.index.get(uid)?.clone();
 
@@ -85,52 +558,101 @@ impl TaskStore {
         None
     }
 
+    /// None for a note, which is never done.
     pub fn toggle_task(&mut self, uid: &str) -> Option<Task> {
-        if let Some((task, _)) = self.get_task_mut(uid) {
-            task.status = if task.status == TaskStatus::Completed {
-                TaskStatus::NeedsAction
-            } else {
-                TaskStatus::Completed
-            };
-            return Some(task.clone());
+        let (task, _) = self.get_task_mut(uid)?;
+        if task.is_note() {
+            return None;
         }
-        None
+        task.status = if task.status == TaskStatus::Completed {
+            TaskStatus::NeedsAction
+        } else {
+            TaskStatus::Completed
+        };
+        let task = task.clone();
+        self.updated(task)
     }
 
     pub fn set_status(&mut self, uid: &str, status: TaskStatus) -> Option<Task> {
-        if let Some((task, _)) = self.get_task_mut(uid) {
-            if task.status == status {
-                task.status = TaskStatus::NeedsAction;
-            } else {
-                task.status = status;
-            }
-            return Some(task.clone());
+        let (task, _) = self.get_task_mut(uid)?;
+        if task.is_note() {
+            return None;
         }
-        None
+        if task.status == status {
+            task.status = TaskStatus::NeedsAction;
+        } else {
+            task.status = status;
+        }
+        let task = task.clone();
+        self.updated(task)
     }
 
     pub fn change_priority(&mut self, uid: &str, delta: i8) -> Option<Task> {
-        if let Some((task, _)) = self.get_task_mut(uid) {
-            task.priority = if delta > 0 {
-                match task.priority {
-                    0 => 9,
-                    9 => 5,
-                    5 => 1,
-                    1 => 1,
-                    _ => 5,
-                }
-            } else {
-                match task.priority {
-                    1 => 5,
-                    5 => 9,
-                    9 => 0,
-                    0 => 0,
-                    _ => 0,
-                }
-            };
-            return Some(task.clone());
+        let (task, _) = self.get_task_mut(uid)?;
+        if task.is_note() {
+            return None;
         }
-        None
+        task.priority = task.priority.cycled(delta > 0);
+        let task = task.clone();
+        self.updated(task)
+    }
+
+    pub fn set_recurrence(&mut self, uid: &str, rrule: Option<String>) -> Option<Task> {
+        let (task, _) = self.get_task_mut(uid)?;
+        if rrule.is_none() {
+            task.recur_from_completion = false;
+        }
+        task.rrule = rrule;
+        let task = task.clone();
+        self.updated(task)
+    }
+
+    /// Folds or unfolds the subtasks of a task (synced as X-OC-HIDESUBTASKS).
+    pub fn toggle_subtasks_hidden(&mut self, uid: &str) -> Option<Task> {
+        let (task, _) = self.get_task_mut(uid)?;
+        task.hide_subtasks = !task.hide_subtasks;
+        let task = task.clone();
+        self.updated(task)
+    }
+
+    /// Folds or unfolds the children of `uid`. None when it has no children or is already so.
+    pub fn set_subtasks_hidden(&mut self, uid: &str, hidden: bool) -> Option<Task> {
+        let has_children = self
+            .calendars
+            .values()
+            .flatten()
+            .any(|t| t.parent_uid.as_deref() == Some(uid));
+        let (task, _) = self.get_task_mut(uid)?;
+        if !has_children || task.hide_subtasks == hidden {
+            return None;
+        }
+        task.hide_subtasks = hidden;
+        let task = task.clone();
+        self.updated(task)
+    }
+
+    pub fn skip_occurrence(&mut self, uid: &str) -> Option<Task> {
+        let (task, _) = self.get_task_mut(uid)?;
+        if !task.skip_occurrence() {
+            return None;
+        }
+        let task = task.clone();
+        self.updated(task)
+    }
+
+    /// Returns the detached occurrence (to update) and the continuation of the series (to create).
+    pub fn reschedule_occurrence(
+        &mut self,
+        uid: &str,
+        new_due: DateTime<Utc>,
+    ) -> Option<(Task, Task)> {
+        let (task, _) = self.get_task_mut(uid)?;
+        let next = task.reschedule_occurrence(new_due)?;
+        let updated = task.clone();
+        self.changes
+            .emit(StoreEvent::TaskUpdated(updated.uid.clone()));
+        self.add_task(next.clone());
+        Some((updated, next))
     }
 
     pub fn delete_task(&mut self, uid: &str) -> Option<Task> {
@@ -141,39 +663,131 @@ impl TaskStore {
         {
             let task = tasks.remove(idx);
             self.index.remove(uid);
-            let (_, token) = Cache::load(&href).unwrap_or((vec![], None));
-            let _ = Cache::save(&href, tasks, token);
+            persist(&href, tasks);
+            self.changes.emit(StoreEvent::TaskRemoved(task.uid.clone()));
             return Some(task);
         }
         None
     }
 
     pub fn set_parent(&mut self, child_uid: &str, parent_uid: Option<String>) -> Option<Task> {
-        if let Some((task, _)) = self.get_task_mut(child_uid) {
-            task.parent_uid = parent_uid;
-            return Some(task.clone());
-        }
-        None
+        let (task, _) = self.get_task_mut(child_uid)?;
+        task.parent_uid = parent_uid;
+        let task = task.clone();
+        self.updated(task)
     }
 
     pub fn add_dependency(&mut self, task_uid: &str, dep_uid: String) -> Option<Task> {
-        if let Some((task, _)) = self.get_task_mut(task_uid)
-            && !task.dependencies.contains(&dep_uid)
-        {
-            task.dependencies.push(dep_uid);
-            return Some(task.clone());
+        let (task, _) = self.get_task_mut(task_uid)?;
+        if task.dependencies.contains(&dep_uid) {
+            return None;
         }
-        None
+        task.dependencies.push(dep_uid);
+        let task = task.clone();
+        self.updated(task)
     }
 
     pub fn remove_dependency(&mut self, task_uid: &str, dep_uid: &str) -> Option<Task> {
-        if let Some((task, _)) = self.get_task_mut(task_uid)
-            && let Some(pos) = task.dependencies.iter().position(|d| d == dep_uid)
-        {
-            task.dependencies.remove(pos);
-            return Some(task.clone());
+        let (task, _) = self.get_task_mut(task_uid)?;
+        let pos = task.dependencies.iter().position(|d| d == dep_uid)?;
+        task.dependencies.remove(pos);
+        let task = task.clone();
+        self.updated(task)
+    }
+
+    /// The direct subtasks of a task, across calendars.
+    pub fn children(&self, uid: &str) -> Vec<&Task> {
+        self.calendars
+            .values()
+            .flatten()
+            .filter(|t| t.parent_uid.as_deref() == Some(uid))
+            .collect()
+    }
+
+    /// A task followed by all its descendants (parents before children), across calendars.
+    pub fn subtree(&self, uid: &str) -> Vec<Task> {
+        let Some(root) = self
+            .index
+            .get(uid)
+            .and_then(|href| self.calendars.get(href))
+            .and_then(|list| list.iter().find(|t| t.uid == uid))
+        else {
+            return Vec::new();
+        };
+
+        let mut seen: HashSet<String> = HashSet::from([root.uid.clone()]);
+        let mut result = vec![root.clone()];
+        let mut i = 0;
+        while i < result.len() {
+            let parent_uid = result[i].uid.clone();
+            let children: Vec<Task> = self
+                .calendars
+                .values()
+                .flatten()
+                .filter(|t| t.parent_uid.as_ref() == Some(&parent_uid))
+                .filter(|t| seen.insert(t.uid.clone()))
+                .cloned()
+                .collect();
+            result.extend(children);
+            i += 1;
         }
-        None
+        result
+    }
+
+    /// Copies a task, optionally with all its descendants, into `target_href` under fresh UIDs.
+    /// Links inside the copied subtree are remapped; links to other tasks are kept.
+    /// Returns the copies (parents first), already added to the store.
+    pub fn duplicate_task(
+        &mut self,
+        uid: &str,
+        with_subtree: bool,
+        target_href: &str,
+    ) -> Vec<Task> {
+        let originals = if with_subtree {
+            self.subtree(uid)
+        } else {
+            self.get_task_mut(uid)
+                .map(|(t, _)| t.clone())
+                .into_iter()
+                .collect()
+        };
+
+        let new_uids: HashMap<String, String> = originals
+            .iter()
+            .map(|t| (t.uid.clone(), Uuid::new_v4().to_string()))
+            .collect();
+        let remap = |u: &String| new_uids.get(u).cloned().unwrap_or_else(|| u.clone());
+
+        let copies: Vec<Task> = originals
+            .into_iter()
+            .map(|mut t| {
+                t.uid = remap(&t.uid);
+                t.parent_uid = t.parent_uid.as_ref().map(remap);
+                t.dependencies = t.dependencies.iter().map(remap).collect();
+                t.calendar_href = target_href.to_string();
+                t.etag.clear();
+                t.href.clear();
+                // Exceptions and sibling VTODOs belong to the original resource
+                t.raw_components.clear();
+                t.created = None;
+                t.last_modified = None;
+                t
+            })
+            .collect();
+
+        for copy in &copies {
+            self.add_task(copy.clone());
+        }
+        copies
+    }
+
+    /// Moves a task and all its descendants so the tree stays together in `target_href`.
+    /// UIDs are kept, so parent links stay valid. Returns the moved tasks (parents first).
+    pub fn move_subtree(&mut self, uid: &str, target_href: &str) -> Vec<Task> {
+        self.subtree(uid)
+            .iter()
+            .filter_map(|t| self.move_task(&t.uid, target_href.to_string()))
+            .collect()
     }
 
     pub fn move_task(&mut self, uid: &str, target_href: String) -> Option<Task> {
@@ -189,8 +803,7 @@ impl TaskStore {
             self.add_task(task.clone());
 
             if let Some(target_list) = self.calendars.get(&target_href) {
-                let (_, token) = Cache::load(&target_href).unwrap_or((vec![], None));
-                let _ = Cache::save(&target_href, target_list, token);
+                persist(&target_href, target_list);
             }
 
             return Some(task);
@@ -198,6 +811,37 @@ impl TaskStore {
         None
     }
 
+    /// Applies `action` to the tasks of `uids` it changes: open ones for `Complete` (never
+    /// notes), those without the tag for `AddTag`. Returns them for `RustyClient::apply_bulk`,
+    /// moved ones as they were before.
+    pub fn apply_bulk(&mut self, uids: &[String], action: &BulkAction) -> Vec<Task> {
+        uids.iter()
+            .filter_map(|uid| match action {
+                BulkAction::Complete => {
+                    if !self.get_task(uid).is_some_and(|t| !t.status.is_done()) {
+                        return None;
+                    }
+                    self.set_status(uid, TaskStatus::Completed)
+                }
+                BulkAction::Delete => self.delete_task(uid),
+                BulkAction::Move(href) => {
+                    let before = self.get_task(uid).cloned();
+                    self.move_task(uid, href.clone()).and(before)
+                }
+                BulkAction::AddTag(tag) => {
+                    let (task, _) = self.get_task_mut(uid)?;
+                    if task.categories.contains(tag) {
+                        return None;
+                    }
+                    task.categories.push(tag.clone());
+                    task.categories.sort();
+                    let task = task.clone();
+                    self.updated(task)
+                }
+            })
+            .collect()
+    }
+
     // --- NEW SHARED LOGIC ---
 
     /// Finds all tasks tagged with `alias_key` that are missing one or more of `target_tags`.
@@ -239,6 +883,7 @@ impl TaskStore {
 
                 // Track for return
                 modified_tasks.push(task.clone());
+                self.changes.emit(StoreEvent::TaskUpdated(uid));
             }
         }
 
@@ -366,19 +1011,42 @@ impl TaskStore {
             }
         }
 
+        let query = SearchQuery::parse(options.search_term);
+        let now = Utc::now();
+        let has_status_filter = query.text.contains("is:done")
+            || query.text.contains("is:active")
+            || query.text.contains("is:ongoing");
+
         let filtered: Vec<Task> = raw_tasks
             .into_iter()
             .filter(|t| {
-                let search_lower = options.search_term.to_lowercase();
-                let has_status_filter = search_lower.contains("is:done")
-                    || search_lower.contains("is:active")
-                    || search_lower.contains("is:ongoing");
+                let cal_name = options.calendar_names.get(&t.calendar_href);
+                if !query.matches_calendar(&t.calendar_href, cal_name.map(String::as_str)) {
+                    return false;
+                }
 
                 if !has_status_filter && t.status.is_done() && options.hide_completed_global {
                     return false;
                 }
 
-                match t.estimated_duration {
+                if !matches_quick_filters(t, options.quick_filters, now) {
+                    return false;
+                }
+
+                if options.hide_not_started && not_started(t, now) {
+                    return false;
+                }
+
+                // Tasks without any timestamp are kept: their age is unknown
+                if !has_status_filter
+                    && t.status.is_done()
+                    && let Some(limit) = options.completed_cutoff
+                    && t.completed.or(t.last_modified).is_some_and(|d| d < limit)
+                {
+                    return false;
+                }
+
+                match t.estimated_duration.map(Duration::minutes) {
                     Some(mins) => {
                         if let Some(min) = options.min_duration
                             && mins < min
@@ -456,14 +1124,182 @@ impl TaskStore {
                     }
                 }
 
-                if !options.search_term.is_empty() {
-                    return t.matches_search_term(options.search_term);
+                if !query.text.is_empty() {
+                    return t.matches_search_term(&query.text);
                 }
                 true
             })
             .collect();
 
-        Task::organize_hierarchy(filtered, options.cutoff_date)
+        Task::organize_hierarchy(filtered, options.cutoff_date, options.fold_subtasks)
+    }
+
+    /// Open tasks matching `options`, totalled per calendar. Hidden calendars and folded
+    /// subtasks are counted too, so the sidebar can show what a calendar holds.
+    pub fn calendar_stats(&self, options: FilterOptions) -> HashMap<String, CalendarStats> {
+        let no_hidden = HashSet::new();
+        let tasks = self.filter(FilterOptions {
+            active_cal_href: None,
+            hidden_calendars: &no_hidden,
+            fold_subtasks: false,
+            ..options
+        });
+        let mut stats: HashMap<String, CalendarStats> = HashMap::new();
+        for task in tasks.iter().filter(|t| !t.status.is_done() && !t.is_note()) {
+            let entry = stats.entry(task.calendar_href.clone()).or_default();
+            entry.open += 1;
+            entry.estimated_minutes = entry
+                .estimated_minutes
+                .saturating_add(task.estimated_duration.map_or(0, Duration::minutes));
+        }
+        stats
+    }
+
+    /// Open tasks of `filter` grouped for the agenda: overdue first, then one section for
+    /// each of the `days` days starting `today` (sections may be empty). Sorted by due time.
+    pub fn agenda(&self, options: FilterOptions, today: NaiveDate, days: u32) -> Vec<AgendaDay> {
+        let mut sections: Vec<AgendaDay> = std::iter::once(None)
+            .chain((0..days).map(|d| today.checked_add_days(Days::new(d as u64))))
+            .map(|date| AgendaDay {
+                date,
+                tasks: Vec::new(),
+            })
+            .collect();
+
+        let mut open: Vec<Task> = self
+            .filter(options)
+            .into_iter()
+            .filter(|t| !t.status.is_done() && t.due.is_some())
+            .collect();
+        open.sort_by_key(|t| t.due);
+        for mut task in open {
+            let Some(due) = task.due.map(|d| d.with_timezone(&Local).date_naive()) else {
+                continue;
+            };
+            // Flat lists: the hierarchy of the regular view does not apply here
+            task.depth = 0;
+            if due < today {
+                sections[0].tasks.push(task);
+            } else if let Some(section) = sections.iter_mut().find(|s| s.date == Some(due)) {
+                section.tasks.push(task);
+            }
+        }
+        sections
+    }
+
+    /// Open tasks of `filter` not modified since `cutoff`, oldest first, for the review.
+    /// Tasks without any timestamp (never saved by cfait) count as stale.
+    pub fn stale_tasks(&self, options: FilterOptions, cutoff: DateTime<Utc>) -> Vec<Task> {
+        let mut stale: Vec<Task> = self
+            .filter(FilterOptions {
+                fold_subtasks: false,
+                ..options
+            })
+            .into_iter()
+            .filter(|t| !t.status.is_done() && !t.is_note())
+            .filter(|t| t.last_modified.or(t.created).is_none_or(|d| d < cutoff))
+            .collect();
+        stale.sort_by_key(|t| t.last_modified.or(t.created));
+        for task in &mut stale {
+            task.depth = 0;
+        }
+        stale
+    }
+
+    /// The next actions among the tasks of `options`: open ones neither waiting for an open
+    /// dependency nor starting on a later day, as a flat list by priority, then due date.
+    pub fn next_actions(&self, options: FilterOptions, now: DateTime<Utc>) -> Vec<Task> {
+        let mut next: Vec<Task> = self
+            .filter(FilterOptions {
+                fold_subtasks: false,
+                ..options
+            })
+            .into_iter()
+            .filter(|t| !t.status.is_done() && !t.is_note())
+            .filter(|t| !self.is_blocked(t) && !not_started(t, now))
+            .collect();
+        // Unprioritized tasks rank as medium, as in the regular sort
+        next.sort_by(|a, b| {
+            a.priority
+                .rank()
+                .cmp(&b.priority.rank())
+                .then_with(|| a.due.is_none().cmp(&b.due.is_none()))
+                .then_with(|| a.due.cmp(&b.due))
+        });
+        for task in &mut next {
+            task.depth = 0;
+        }
+        next
+    }
+
+    /// Open overdue tasks outside the `excluded` calendars, for the badges of the UIs.
+    pub fn overdue_count(&self, excluded: &HashSet<String>, now: DateTime<Utc>) -> usize {
+        self.calendars
+            .iter()
+            .filter(|(href, _)| !excluded.contains(*href))
+            .flat_map(|(_, tasks)| tasks)
+            .filter(|t| urgency(t, now, 0) == Urgency::Overdue)
+            .count()
+    }
+
+    /// UIDs for the review: stale open tasks outside the `excluded` calendars, whatever the
+    /// tag and search filters of the list.
+    pub fn review_queue(&self, excluded: &HashSet<String>, weeks: u32) -> Vec<String> {
+        let no_tags = HashSet::new();
+        let no_names = HashMap::new();
+        let options = FilterOptions {
+            active_cal_href: None,
+            hidden_calendars: excluded,
+            selected_categories: &no_tags,
+            match_all_categories: false,
+            search_term: "",
+            calendar_names: &no_names,
+            hide_completed_global: true,
+            cutoff_date: None,
+            min_duration: None,
+            max_duration: None,
+            include_unset_duration: true,
+            completed_cutoff: None,
+            fold_subtasks: false,
+            quick_filters: &[],
+            hide_not_started: false,
+        };
+        let cutoff = Utc::now() - chrono::Duration::weeks(weeks as i64);
+        self.stale_tasks(options, cutoff)
+            .into_iter()
+            .map(|t| t.uid)
+            .collect()
+    }
+
+    /// Records that the task was looked at, which takes it out of the review for a while.
+    pub fn mark_reviewed(&mut self, uid: &str) -> Option<Task> {
+        let (task, _) = self.get_task_mut(uid)?;
+        task.touch(false);
+        let task = task.clone();
+        self.updated(task)
+    }
+
+    /// Copies the timestamps a save set (`Task::touch`) onto the stored task, keeping any
+    /// edit made to it since. The review and the retention read them from the store.
+    pub fn stamp(&mut self, saved: &Task) {
+        let Some((task, _)) = self.get_task_mut(&saved.uid) else {
+            return;
+        };
+        task.created = saved.created;
+        task.last_modified = saved.last_modified;
+        if task.status == saved.status {
+            task.completed = saved.completed;
+        }
+        let task = task.clone();
+        self.updated(task);
+    }
+
+    pub fn set_due(&mut self, uid: &str, due: Option<DateTime<Utc>>) -> Option<Task> {
+        let (task, _) = self.get_task_mut(uid)?;
+        task.due = due;
+        task.touch(false);
+        let task = task.clone();
+        self.updated(task)
     }
 
     pub fn is_task_done(&self, uid: &str) -> Option<bool> {
@@ -494,6 +1330,64 @@ impl TaskStore {
         false
     }
 
+    /// Open tasks that were waiting for `uid` and that nothing blocks any more: those that
+    /// completing (or cancelling) it freed. Empty while `uid` is not done.
+    pub fn unblocked_by(&self, uid: &str) -> Vec<Task> {
+        if self.is_task_done(uid) != Some(true) {
+            return Vec::new();
+        }
+        self.calendars
+            .values()
+            .flatten()
+            .filter(|t| !t.status.is_done() && t.dependencies.iter().any(|d| d == uid))
+            .filter(|t| !self.is_blocked(t))
+            .cloned()
+            .collect()
+    }
+
+    /// Gives the new `task` the manual order `placement` asks for among its siblings (same
+    /// calendar and parent). `selected` is the task under the cursor: with `AfterSelection`,
+    /// a task of the same calendar without a parent yet becomes its sibling.
+    pub fn place_new_task(
+        &self,
+        task: &mut Task,
+        placement: NewTaskPlacement,
+        selected: Option<&str>,
+    ) {
+        let Some(tasks) = self.calendars.get(&task.calendar_href) else {
+            return;
+        };
+        let anchor = selected
+            .filter(|_| placement == NewTaskPlacement::AfterSelection)
+            .and_then(|uid| tasks.iter().find(|t| t.uid == uid));
+        if let Some(anchor) = anchor
+            && task.parent_uid.is_none()
+        {
+            task.parent_uid = anchor.parent_uid.clone();
+        }
+        let orders = tasks
+            .iter()
+            .filter(|t| t.uid != task.uid && t.parent_uid == task.parent_uid)
+            .filter_map(|t| t.sort_order);
+        task.sort_order = match placement {
+            NewTaskPlacement::Sorted => task.sort_order,
+            NewTaskPlacement::Top => Some(orders.min().map_or(0, |o| o - 1)),
+            NewTaskPlacement::Bottom => Some(orders.max().map_or(0, |o| o + 1)),
+            NewTaskPlacement::AfterSelection => match anchor {
+                // Halfway to the next sibling, so the order of the others stays as it is
+                Some(anchor) if anchor.parent_uid == task.parent_uid => {
+                    anchor
+                        .sort_order
+                        .map(|o| match orders.filter(|n| *n > o).min() {
+                            Some(next) if next - o > 1 => o + (next - o) / 2,
+                            _ => o + 1,
+                        })
+                }
+                _ => task.sort_order,
+            },
+        };
+    }
+
     pub fn get_summary(&self, uid: &str) -> Option<String> {
         if let Some(href) = self.index.get(uid)
             && let Some(tasks) = self.calendars.get(href)
@@ -504,3 +1398,705 @@ impl TaskStore {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Priority;
+
+    #[test]
+    fn test_duplicate_subtree_remaps_links() {
+        let mut store = TaskStore::new();
+        let mut parent = Task::new("Parent", &HashMap::new());
+        parent.calendar_href = "/cal/a/".to_string();
+        parent.etag = "\"1\"".to_string();
+        let mut first = Task::new("First", &HashMap::new());
+        first.calendar_href = "/cal/a/".to_string();
+        first.parent_uid = Some(parent.uid.clone());
+        let mut second = Task::new("Second", &HashMap::new());
+        second.calendar_href = "/cal/a/".to_string();
+        second.parent_uid = Some(parent.uid.clone());
+        second.dependencies = vec![first.uid.clone(), "external".to_string()];
+        for t in [&parent, &first, &second] {
+            store.add_task(t.clone());
+        }
+
+        let alone = store.duplicate_task(&parent.uid, false, "/cal/a/");
+        assert_eq!(alone.len(), 1);
+        assert_ne!(alone[0].uid, parent.uid);
+        assert!(alone[0].etag.is_empty());
+
+        let copies = store.duplicate_task(&parent.uid, true, "/cal/b/");
+        assert_eq!(copies.len(), 3);
+        assert!(copies.iter().all(|t| t.calendar_href == "/cal/b/"));
+        let new_root = &copies[0].uid;
+        assert!(
+            copies[1..]
+                .iter()
+                .all(|t| t.parent_uid.as_ref() == Some(new_root))
+        );
+        let new_first = copies.iter().find(|t| t.summary == "First").unwrap();
+        let new_second = copies.iter().find(|t| t.summary == "Second").unwrap();
+        assert_eq!(
+            new_second.dependencies,
+            vec![new_first.uid.clone(), "external".to_string()]
+        );
+        // Originals are untouched
+        assert_eq!(store.calendars["/cal/a/"].len(), 4);
+    }
+
+    #[test]
+    fn test_completed_retention_cutoff() {
+        let mut store = TaskStore::new();
+        let now = Utc::now();
+        for (name, completed) in [
+            ("Recent", Some(now - chrono::Duration::days(2))),
+            ("Old", Some(now - chrono::Duration::days(30))),
+            ("Unknown", None),
+        ] {
+            let mut t = Task::new(name, &HashMap::new());
+            t.calendar_href = "/cal/a/".to_string();
+            t.status = TaskStatus::Completed;
+            t.completed = completed;
+            store.add_task(t);
+        }
+
+        let empty = HashSet::new();
+        let no_names = HashMap::new();
+        let visible = |search: &str| {
+            let mut names: Vec<String> = store
+                .filter(FilterOptions {
+                    active_cal_href: None,
+                    hidden_calendars: &empty,
+                    selected_categories: &empty,
+                    match_all_categories: false,
+                    search_term: search,
+                    calendar_names: &no_names,
+                    hide_completed_global: false,
+                    cutoff_date: None,
+                    min_duration: None,
+                    max_duration: None,
+                    include_unset_duration: true,
+                    completed_cutoff: Some(now - chrono::Duration::days(7)),
+                    fold_subtasks: false,
+                    quick_filters: &[],
+                    hide_not_started: false,
+                })
+                .into_iter()
+                .map(|t| t.summary)
+                .collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(visible(""), vec!["Recent", "Unknown"]);
+        // An explicit status filter shows the whole history
+        assert_eq!(visible("is:done").len(), 3);
+    }
+
+    #[test]
+    fn test_agenda_sections() {
+        let mut store = TaskStore::new();
+        let today = Local::now().date_naive();
+        let at = |days: i64| {
+            (today + chrono::Duration::days(days))
+                .and_hms_opt(9, 0, 0)
+                .and_then(|d| d.and_local_timezone(Local).single())
+                .map(|d| d.with_timezone(&Utc))
+        };
+        for (name, due, done) in [
+            ("Late", at(-3), false),
+            ("Now", at(0), false),
+            ("Finished", at(0), true),
+            ("Next week", at(10), false),
+            ("Someday", None, false),
+        ] {
+            let mut t = Task::new(name, &HashMap::new());
+            t.calendar_href = "/cal/a/".to_string();
+            t.due = due;
+            if done {
+                t.status = TaskStatus::Completed;
+            }
+            store.add_task(t);
+        }
+
+        let empty = HashSet::new();
+        let no_names = HashMap::new();
+        let sections = store.agenda(
+            FilterOptions {
+                active_cal_href: None,
+                hidden_calendars: &empty,
+                selected_categories: &empty,
+                match_all_categories: false,
+                search_term: "",
+                calendar_names: &no_names,
+                hide_completed_global: false,
+                cutoff_date: None,
+                min_duration: None,
+                max_duration: None,
+                include_unset_duration: true,
+                completed_cutoff: None,
+                fold_subtasks: false,
+                quick_filters: &[],
+                hide_not_started: false,
+            },
+            today,
+            7,
+        );
+        assert_eq!(sections.len(), 8);
+        assert_eq!(sections[0].label(today), "Overdue");
+        assert_eq!(sections[0].tasks[0].summary, "Late");
+        assert_eq!(sections[1].label(today), "Today");
+        assert_eq!(sections[1].tasks.len(), 1);
+        assert_eq!(sections[1].tasks[0].summary, "Now");
+        assert_eq!(sections[2].label(today), "Tomorrow");
+        assert!(sections[2..].iter().all(|s| s.tasks.is_empty()));
+    }
+
+    #[test]
+    fn test_set_subtasks_hidden_needs_children() {
+        let mut store = TaskStore::new();
+        let mut parent = Task::new("Parent", &HashMap::new());
+        parent.calendar_href = "/cal/a/".to_string();
+        let mut child = Task::new("Child", &HashMap::new());
+        child.calendar_href = "/cal/a/".to_string();
+        child.parent_uid = Some(parent.uid.clone());
+        let (parent_uid, child_uid) = (parent.uid.clone(), child.uid.clone());
+        store.add_task(parent);
+        store.add_task(child);
+
+        assert!(store.set_subtasks_hidden(&child_uid, true).is_none());
+        let folded = store.set_subtasks_hidden(&parent_uid, true).unwrap();
+        assert!(folded.hide_subtasks);
+        // Already folded: nothing to save
+        assert!(store.set_subtasks_hidden(&parent_uid, true).is_none());
+        let unfolded = store.set_subtasks_hidden(&parent_uid, false).unwrap();
+        assert!(!unfolded.hide_subtasks);
+    }
+
+    #[test]
+    fn test_list_keys() {
+        assert_eq!(ListKey::from_key("j"), Some(ListKey::Next));
+        assert_eq!(ListKey::from_key("Up"), Some(ListKey::Previous));
+        assert_eq!(ListKey::from_key(" "), Some(ListKey::Toggle));
+        assert_eq!(ListKey::from_key("Delete"), ListKey::from_key("d"));
+        assert_eq!(ListKey::from_key("D"), None);
+    }
+
+    #[test]
+    fn test_apply_bulk() {
+        let mut store = TaskStore::new();
+        let open = Task::new("Open #home", &HashMap::new());
+        let mut done = Task::new("Done", &HashMap::new());
+        done.status = TaskStatus::Completed;
+        let note = Task::new_note("Note");
+        let uids: Vec<String> = [&open, &done, &note]
+            .iter()
+            .map(|t| t.uid.clone())
+            .collect();
+        for task in [open, done, note] {
+            store.add_task(task);
+        }
+
+        // Only what changes comes back, to be saved
+        let completed = store.apply_bulk(&uids, &BulkAction::Complete);
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].uid, uids[0]);
+        assert_eq!(
+            store.get_task(&uids[1]).unwrap().status,
+            TaskStatus::Completed
+        );
+        assert!(store.apply_bulk(&uids, &BulkAction::Complete).is_empty());
+
+        let tagged = store.apply_bulk(&uids, &BulkAction::AddTag("home".to_string()));
+        assert_eq!(tagged.len(), 2);
+        assert_eq!(store.get_task(&uids[2]).unwrap().categories, ["home"]);
+
+        // Moved tasks come back as they were, to be found in their former calendar
+        let target = BulkAction::Move("/cal/other/".to_string());
+        let moved = store.apply_bulk(&uids[..2], &target);
+        assert_eq!(moved.len(), 2);
+        assert!(moved.iter().all(|t| t.calendar_href != "/cal/other/"));
+        let in_target = |store: &TaskStore, uid: &str| {
+            store.get_task(uid).unwrap().calendar_href == "/cal/other/"
+        };
+        assert!(in_target(&store, &uids[0]) && in_target(&store, &uids[1]));
+        assert!(!in_target(&store, &uids[2]));
+        assert!(store.apply_bulk(&uids[..2], &target).is_empty());
+
+        let deleted = store.apply_bulk(&uids, &BulkAction::Delete);
+        assert_eq!(deleted.len(), 3);
+        assert!(uids.iter().all(|uid| store.get_task(uid).is_none()));
+        assert!(store.apply_bulk(&uids, &BulkAction::Delete).is_empty());
+    }
+
+    #[test]
+    fn test_stamp_keeps_later_edits() {
+        let mut store = TaskStore::new();
+        let task = Task::new("Call the plumber", &HashMap::new());
+        let uid = task.uid.clone();
+        store.add_task(task.clone());
+
+        // Saved by the client while the task was edited again in the store
+        let mut saved = task;
+        saved.touch(true);
+        store.get_task_mut(&uid).unwrap().0.summary = "Call the plumber today".to_string();
+        store.stamp(&saved);
+
+        let stored = store.get_task(&uid).unwrap();
+        assert_eq!(stored.summary, "Call the plumber today");
+        assert_eq!(stored.last_modified, saved.last_modified);
+        assert_eq!(stored.created, saved.created);
+    }
+
+    #[test]
+    fn test_calendar_stats_follow_filters() {
+        let mut store = TaskStore::new();
+        for (name, href, mins, done) in [
+            ("Write #report", "/cal/work/", Some(90), false),
+            ("Call #client", "/cal/work/", Some(30), false),
+            ("Old #report", "/cal/work/", Some(60), true),
+            ("Laundry", "/cal/home/", None, false),
+        ] {
+            let mut t = Task::new(name, &HashMap::new());
+            t.calendar_href = href.to_string();
+            t.estimated_duration = mins.map(Duration::from_minutes);
+            if done {
+                t.status = TaskStatus::Completed;
+            }
+            store.add_task(t);
+        }
+        // The home calendar is hidden but still counted
+        let hidden = HashSet::from(["/cal/home/".to_string()]);
+        let no_tags = HashSet::new();
+        let names = HashMap::from([("/cal/home/".to_string(), "Household".to_string())]);
+        let options = FilterOptions {
+            active_cal_href: None,
+            hidden_calendars: &hidden,
+            selected_categories: &no_tags,
+            match_all_categories: false,
+            search_term: "",
+            calendar_names: &names,
+            hide_completed_global: false,
+            cutoff_date: None,
+            min_duration: None,
+            max_duration: None,
+            include_unset_duration: true,
+            completed_cutoff: None,
+            fold_subtasks: true,
+            quick_filters: &[],
+            hide_not_started: false,
+        };
+        let stats = store.calendar_stats(options);
+        assert_eq!(stats["/cal/work/"].label(), "2 · ~2h");
+        assert_eq!(stats["/cal/home/"].label(), "1");
+
+        let stats = store.calendar_stats(FilterOptions {
+            search_term: "#report",
+            ..options
+        });
+        assert_eq!(
+            stats["/cal/work/"],
+            CalendarStats {
+                open: 1,
+                estimated_minutes: 90
+            }
+        );
+        assert!(!stats.contains_key("/cal/home/"));
+
+        // `cal:` also matches calendar names
+        let stats = store.calendar_stats(FilterOptions {
+            search_term: "cal:household",
+            ..options
+        });
+        assert_eq!(stats.keys().collect::<Vec<_>>(), ["/cal/home/"]);
+    }
+
+    #[test]
+    fn test_quick_filters() {
+        let noon = Local::now().date_naive().and_hms_opt(12, 0, 0).unwrap();
+        let now = noon.and_local_timezone(Local).unwrap().with_timezone(&Utc);
+        let due = |name: &str, offset: chrono::Duration| {
+            let mut t = Task::new(name, &HashMap::new());
+            t.due = Some(now + offset);
+            t
+        };
+        let today = due("Pay rent !1", chrono::Duration::hours(3));
+        let overdue = due("Call Bob", chrono::Duration::days(-2));
+        let mut done = overdue.clone();
+        done.status = TaskStatus::Completed;
+        let later = due("Renew passport !2", chrono::Duration::days(3));
+        let undated = Task::new("Read a book", &HashMap::new());
+
+        let shown = |filters: &[QuickFilter]| {
+            [&today, &overdue, &done, &later, &undated]
+                .into_iter()
+                .filter(|t| matches_quick_filters(t, filters, now))
+                .map(|t| t.summary.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(shown(&[]).len(), 5);
+        assert_eq!(shown(&[QuickFilter::DueToday]), ["Pay rent"]);
+        assert_eq!(shown(&[QuickFilter::Overdue]), ["Call Bob"]);
+        assert_eq!(
+            shown(&[QuickFilter::DueToday, QuickFilter::Overdue]),
+            ["Pay rent", "Call Bob"]
+        );
+        assert_eq!(
+            shown(&[QuickFilter::Urgent]),
+            ["Pay rent", "Renew passport"]
+        );
+        assert_eq!(
+            shown(&[QuickFilter::Urgent, QuickFilter::DueToday]),
+            ["Pay rent"]
+        );
+    }
+
+    #[test]
+    fn test_hide_not_started() {
+        let now = Utc::now();
+        // As the smart input writes them: the start of the day, in UTC
+        let day = |n: i64| {
+            (Local::now().date_naive() + chrono::Duration::days(n))
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+        let mut store = TaskStore::new();
+        for (name, start) in [("Started", -1), ("Starts today", 0), ("Starts later", 2)] {
+            let mut t = Task::new(name, &HashMap::new());
+            t.calendar_href = "/cal/a/".to_string();
+            t.dtstart = Some(day(start));
+            store.add_task(t);
+        }
+        let mut done = Task::new("Done early", &HashMap::new());
+        done.calendar_href = "/cal/a/".to_string();
+        done.dtstart = Some(day(5));
+        done.status = TaskStatus::Completed;
+        assert!(!not_started(&done, now));
+        store.add_task(done);
+
+        let none = HashSet::new();
+        let names = HashMap::new();
+        let options = FilterOptions {
+            active_cal_href: None,
+            hidden_calendars: &none,
+            selected_categories: &none,
+            match_all_categories: false,
+            search_term: "",
+            calendar_names: &names,
+            hide_completed_global: false,
+            cutoff_date: None,
+            min_duration: None,
+            max_duration: None,
+            include_unset_duration: true,
+            completed_cutoff: None,
+            fold_subtasks: true,
+            quick_filters: &[],
+            hide_not_started: false,
+        };
+        assert_eq!(store.filter(options).len(), 4);
+        let shown = store.filter(FilterOptions {
+            hide_not_started: true,
+            ..options
+        });
+        assert_eq!(shown.len(), 3);
+        assert!(shown.iter().all(|t| t.summary != "Starts later"));
+    }
+
+    #[test]
+    fn test_next_actions() {
+        let now = Utc::now();
+        let mut store = TaskStore::new();
+        let mut add = |text: &str, uid: &str| {
+            let mut t = Task::new(text, &HashMap::new());
+            t.uid = uid.to_string();
+            t.calendar_href = "/cal/a/".to_string();
+            store.add_task(t.clone());
+            t
+        };
+        add("Write report !3", "report");
+        add("Send report !1", "send");
+        add("Water plants", "plants");
+        add("Book flights !3 @tomorrow", "flights");
+        add("Renew passport !2 ^2099-01-01", "passport");
+        add("Pack bags", "pack");
+        let mut done = add("Old chore !1", "done");
+        done.status = TaskStatus::Completed;
+        store.update_or_add_task(done);
+        store.add_dependency("send", "report".to_string());
+        store.add_dependency("pack", "report".to_string());
+        store.add_dependency("pack", "flights".to_string());
+
+        let none = HashSet::new();
+        let names = HashMap::new();
+        let options = FilterOptions {
+            active_cal_href: None,
+            hidden_calendars: &none,
+            selected_categories: &none,
+            match_all_categories: false,
+            search_term: "",
+            calendar_names: &names,
+            hide_completed_global: false,
+            cutoff_date: None,
+            min_duration: None,
+            max_duration: None,
+            include_unset_duration: true,
+            completed_cutoff: None,
+            fold_subtasks: true,
+            quick_filters: &[],
+            hide_not_started: false,
+        };
+        let next: Vec<String> = store
+            .next_actions(options, now)
+            .into_iter()
+            .map(|t| t.uid)
+            .collect();
+        // Blocked, not started and done tasks are left out; dated before undated
+        assert_eq!(next, ["flights", "report", "plants"]);
+
+        // Only the task whose last blocker gets done is announced as unblocked
+        assert!(store.unblocked_by("report").is_empty());
+        store.toggle_task("report");
+        let freed: Vec<String> = store
+            .unblocked_by("report")
+            .into_iter()
+            .map(|t| t.uid)
+            .collect();
+        assert_eq!(freed, ["send"]);
+    }
+
+    #[test]
+    fn test_calendar_badges() {
+        let task = |href: &str| {
+            let mut t = Task::new("Task", &HashMap::new());
+            t.calendar_href = href.to_string();
+            t
+        };
+        assert!(!mixes_calendars(&[task("/a/"), task("/a/")]));
+        assert!(mixes_calendars(&[task("/a/"), task("/a/"), task("/b/")]));
+
+        let cal = crate::model::CalendarListEntry {
+            name: "Personal projects".to_string(),
+            href: "/a/".to_string(),
+            color: None,
+            is_shared: false,
+            is_read_only: false,
+        };
+        assert_eq!(cal.short_name(9, "…"), "Personal…");
+        assert_eq!(cal.short_name(20, "…"), "Personal projects");
+    }
+
+    #[test]
+    fn test_urgency() {
+        let now = Utc::now();
+        // As the smart input writes them: the end of the day, in UTC
+        let end_of_today = Local::now().date_naive().and_hms_opt(23, 59, 59).unwrap();
+        let due = |days: i64| {
+            let mut t = Task::new("Task", &HashMap::new());
+            t.due = Some(end_of_today.and_utc() + chrono::Duration::days(days));
+            t.calendar_href = "/cal/home/".to_string();
+            t
+        };
+        let overdue = due(-1);
+        let mut done = overdue.clone();
+        done.status = TaskStatus::Completed;
+        assert_eq!(urgency(&overdue, now, 3), Urgency::Overdue);
+        assert_eq!(urgency(&done, now, 3), Urgency::None);
+        assert_eq!(urgency(&due(0), now, 3), Urgency::Today);
+        assert_eq!(urgency(&due(2), now, 3), Urgency::Soon);
+        assert_eq!(urgency(&due(2), now, 0), Urgency::None);
+        assert_eq!(urgency(&due(5), now, 3), Urgency::None);
+        assert!(Urgency::Overdue > Urgency::Today && Urgency::Today > Urgency::Soon);
+
+        let mut store = TaskStore::new();
+        store.insert("/cal/home/".to_string(), vec![overdue.clone(), done]);
+        store.insert("/cal/old/".to_string(), vec![overdue]);
+        let excluded: HashSet<String> = ["/cal/old/".to_string()].into();
+        assert_eq!(store.overdue_count(&excluded, now), 1);
+        assert_eq!(store.overdue_count(&HashSet::new(), now), 2);
+    }
+
+    #[test]
+    fn test_group_tasks() {
+        let today = Local::now().date_naive();
+        let task = |name: &str, href: &str, tags: &[&str], priority: Priority| {
+            let mut t = Task::new(name, &HashMap::new());
+            t.calendar_href = href.to_string();
+            t.categories = tags.iter().map(|s| s.to_string()).collect();
+            t.priority = priority;
+            t
+        };
+        let tasks = vec![
+            task(
+                "Report",
+                "/cal/work/",
+                &["office", "urgent"],
+                Priority::HIGH,
+            ),
+            task("Groceries", "/cal/home/", &[], Priority::None),
+            task("Plants", "/cal/home/", &["garden"], Priority::Level(7)),
+        ];
+        let names = HashMap::from([
+            ("/cal/work/".to_string(), "Work".to_string()),
+            ("/cal/home/".to_string(), "Home".to_string()),
+        ]);
+        let labels = |groups: &[TaskGroup]| -> Vec<(String, usize)> {
+            groups
+                .iter()
+                .map(|g| (g.label.clone(), g.tasks.len()))
+                .collect()
+        };
+
+        let by_cal = group_tasks(tasks.clone(), GroupBy::Calendar, &names, today);
+        assert_eq!(
+            labels(&by_cal),
+            [("Home".to_string(), 2), ("Work".to_string(), 1)]
+        );
+        assert_eq!(by_cal[0].tasks[0].summary, "Groceries");
+
+        // A task with two tags is listed under both, untagged tasks come last
+        let by_tag = group_tasks(tasks.clone(), GroupBy::Tag, &names, today);
+        assert_eq!(
+            labels(&by_tag),
+            [
+                ("#garden".to_string(), 1),
+                ("#office".to_string(), 1),
+                ("#urgent".to_string(), 1),
+                ("No tags".to_string(), 1)
+            ]
+        );
+        assert_eq!(by_tag[3].key, UNCATEGORIZED_ID);
+
+        let by_prio = group_tasks(tasks.clone(), GroupBy::Priority, &names, today);
+        let keys: Vec<&str> = by_prio.iter().map(|g| g.key.as_str()).collect();
+        assert_eq!(keys, ["high", "low", "none"]);
+
+        let by_due = group_tasks(tasks, GroupBy::Due, &names, today);
+        assert_eq!(labels(&by_due), [("No due date".to_string(), 3)]);
+        assert_eq!(GroupBy::Due.next(), GroupBy::None);
+    }
+
+    #[test]
+    fn test_stale_tasks_oldest_first() {
+        let now = Utc::now();
+        let mut store = TaskStore::new();
+        for (name, weeks_ago, done) in [
+            ("Recent", Some(1), false),
+            ("Old", Some(10), false),
+            ("Older", Some(20), false),
+            ("Old but done", Some(30), true),
+            ("Imported", None, false),
+        ] {
+            let mut t = Task::new(name, &HashMap::new());
+            t.calendar_href = "/cal/a/".to_string();
+            t.last_modified = weeks_ago.map(|w| now - chrono::Duration::weeks(w));
+            if done {
+                t.status = TaskStatus::Completed;
+            }
+            store.add_task(t);
+        }
+        let no_cals = HashSet::new();
+        let no_tags = HashSet::new();
+        let no_names = HashMap::new();
+        let options = FilterOptions {
+            active_cal_href: None,
+            hidden_calendars: &no_cals,
+            selected_categories: &no_tags,
+            match_all_categories: false,
+            search_term: "",
+            calendar_names: &no_names,
+            hide_completed_global: false,
+            cutoff_date: None,
+            min_duration: None,
+            max_duration: None,
+            include_unset_duration: true,
+            completed_cutoff: None,
+            fold_subtasks: true,
+            quick_filters: &[],
+            hide_not_started: false,
+        };
+        let stale = store.stale_tasks(options, now - chrono::Duration::weeks(4));
+        let names: Vec<&str> = stale.iter().map(|t| t.summary.as_str()).collect();
+        assert_eq!(names, ["Imported", "Older", "Old"]);
+
+        // Keeping a task resets its age
+        let uid = stale[1].uid.clone();
+        assert!(store.mark_reviewed(&uid).is_some());
+        let stale = store.stale_tasks(options, now - chrono::Duration::weeks(4));
+        assert_eq!(stale.len(), 2);
+    }
+
+    #[test]
+    fn test_place_new_task() {
+        let mut store = TaskStore::new();
+        let task = |name: &str, order: Option<i64>| {
+            let mut t = Task::new(name, &HashMap::new());
+            t.calendar_href = "/cal/".to_string();
+            t.sort_order = order;
+            t
+        };
+        let first = task("First", Some(10));
+        let second = task("Second", Some(20));
+        let mut child = task("Child", None);
+        child.parent_uid = Some(second.uid.clone());
+        let (first_uid, second_uid) = (first.uid.clone(), second.uid.clone());
+        for t in [first, second, child.clone(), task("Unordered", None)] {
+            store.add_task(t);
+        }
+
+        let place = |placement, selected: Option<&str>| {
+            let mut new = task("New", None);
+            store.place_new_task(&mut new, placement, selected);
+            (new.sort_order, new.parent_uid)
+        };
+        assert_eq!(place(NewTaskPlacement::Sorted, None), (None, None));
+        assert_eq!(place(NewTaskPlacement::Top, None), (Some(9), None));
+        assert_eq!(place(NewTaskPlacement::Bottom, None), (Some(21), None));
+        assert_eq!(
+            place(NewTaskPlacement::AfterSelection, Some(&first_uid)),
+            (Some(15), None)
+        );
+        assert_eq!(
+            place(NewTaskPlacement::AfterSelection, Some(&second_uid)),
+            (Some(21), None)
+        );
+        // Next to a subtask: under the same parent
+        assert_eq!(
+            place(NewTaskPlacement::AfterSelection, Some(&child.uid)),
+            (None, Some(second_uid.clone()))
+        );
+        assert_eq!(
+            place(NewTaskPlacement::Top, Some(&child.uid)),
+            (Some(9), None)
+        );
+    }
+
+    #[test]
+    fn test_change_events() {
+        let mut store = TaskStore::new();
+        let events = store.subscribe();
+        let copy = store.clone();
+        let task = Task::new("Water the plants", &HashMap::new());
+        let uid = task.uid.clone();
+        store.add_task(task);
+        store.toggle_task(&uid);
+        assert!(store.add_dependency(&uid, uid.clone()).is_some());
+        assert!(store.add_dependency(&uid, uid.clone()).is_none());
+        copy.changes
+            .emit(StoreEvent::TaskRemoved("gone".to_string()));
+        store.insert("/cal/home/".to_string(), Vec::new());
+        assert_eq!(
+            events.try_iter().collect::<Vec<_>>(),
+            [
+                StoreEvent::TaskAdded(uid.clone()),
+                StoreEvent::TaskUpdated(uid.clone()),
+                StoreEvent::TaskUpdated(uid),
+                StoreEvent::TaskRemoved("gone".to_string()),
+                StoreEvent::CalendarLoaded("/cal/home/".to_string()),
+            ]
+        );
+        assert_eq!(store.changes(), copy.changes());
+        assert_ne!(store.changes(), TaskStore::new().changes());
+    }
+}
@@ -1,9 +1,12 @@
+use crate::config::Config;
 use crate::model::{CalendarListEntry, Task};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SidebarMode {
     Calendars,
     Categories,
+    /// Overdue tasks and the next days; the sidebar picks the day shown
+    Agenda,
 }
 
 #[derive(Debug)]
@@ -18,12 +21,18 @@ pub enum Action {
     MarkCancelled(Task),
     DeleteTask(Task),
     Refresh,
+    /// Re-download from scratch: one calendar, None = all of them
+    ForceRefresh(Option<String>),
     Quit,
-    MoveTask(Task, String),   // Task, New Calendar Href
-    StartCreateChild(String), // Parent Task UID
-    MigrateLocal(String),     // target_href
+    MoveTasks(Vec<Task>, String), // Tasks (parents first), New Calendar Href
+    FileTask(Task, String),       // Triaged inbox task (with its changes), New Calendar Href
+    StartCreateChild(String),     // Parent Task UID
+    MigrateLocal(String),         // target_href
     ToggleCalendarVisibility(String),
     IsolateCalendar(String),
+    SetDisabledCalendars(Vec<String>), // Calendars left out of sync
+    RemoteChanged(Option<String>),     // Push notification: calendar href, None = all
+    ReloadReplica,                     // `cfait daemon` synced: re-read the local replica
 }
 
 #[derive(Debug)]
@@ -32,4 +41,10 @@ pub enum AppEvent {
     TasksLoaded(Vec<(String, Vec<Task>)>),
     Error(String),
     Status(String),
+    /// A task as it was saved, with the timestamps the save set
+    TaskSaved(Task),
+    /// Calendars the last sync could not fetch, "Name: error" (empty once they all succeed)
+    FetchFailed(Vec<String>),
+    /// The config file was edited by another program (boxed: Config is large)
+    ConfigReloaded(Result<Box<Config>, String>),
 }
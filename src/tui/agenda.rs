@@ -0,0 +1,68 @@
+// File: ./src/tui/agenda.rs
+// `cfait agenda`: the week's open tasks as a printable HTML page
+use crate::client::RustyClient;
+use crate::config::Config;
+use crate::model::agenda::{week_html, week_start};
+use crate::storage::{LOCAL_CALENDAR_HREF, LOCAL_CALENDAR_NAME, LocalStorage};
+use anyhow::{Result, anyhow};
+use chrono::{Local, NaiveDate};
+use std::collections::HashMap;
+
+pub const USAGE: &str = "cfait agenda [--week YYYY-MM-DD] [-o <FILE.html>]";
+
+pub async fn run(args: &[String]) -> Result<()> {
+    let mut day = Local::now().date_naive();
+    let mut output = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--week" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| anyhow!("Missing date\nUsage: {}", USAGE))?;
+                day = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .map_err(|_| anyhow!("Invalid date '{}', expected YYYY-MM-DD", value))?;
+            }
+            "-o" | "--output" => output = iter.next().cloned(),
+            "--config" => {
+                iter.next(); // Already applied by AppPaths::init_from_args
+            }
+            other => return Err(anyhow!("Unknown argument: {}\nUsage: {}", other, USAGE)),
+        }
+    }
+
+    let config = Config::load()?;
+    let (client, calendars, _, _, warning) = RustyClient::connect_with_fallback(config)
+        .await
+        .map_err(|e| anyhow!(e))?;
+    if let Some(w) = warning {
+        eprintln!("{}: using the local copy.", w);
+    }
+
+    let mut names: HashMap<String, String> = calendars
+        .iter()
+        .map(|c| (c.href.clone(), c.name.clone()))
+        .collect();
+    names.insert(
+        LOCAL_CALENDAR_HREF.to_string(),
+        LOCAL_CALENDAR_NAME.to_string(),
+    );
+    let mut tasks = LocalStorage::load().unwrap_or_default();
+    let report = client.get_all_tasks(&calendars).await.unwrap_or_default();
+    for line in report.failure_lines(&calendars) {
+        eprintln!("Could not fetch {}, using the local copy.", line);
+    }
+    for (_, fetched) in report.tasks {
+        tasks.extend(fetched);
+    }
+
+    let html = week_html(&tasks, &names, week_start(day));
+    match output {
+        Some(path) => {
+            LocalStorage::atomic_write(&path, html)?;
+            eprintln!("Wrote {}", path);
+        }
+        None => print!("{}", html),
+    }
+    Ok(())
+}
@@ -0,0 +1,40 @@
+// File: ./src/tui/backup.rs
+// `cfait backup`: takes, lists and restores snapshots of the local data without the UI
+use crate::backup;
+use anyhow::{Result, anyhow};
+
+pub const USAGE: &str = "cfait backup [list | restore <NAME>]";
+
+pub fn run(args: &[String]) -> Result<()> {
+    let mut words = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--config" {
+            iter.next(); // Already applied by AppPaths::init_from_args
+        } else if !arg.starts_with("--") {
+            words.push(arg.as_str());
+        }
+    }
+
+    match words.as_slice() {
+        [] => println!("Backed up as {}.", backup::snapshot(None)?),
+        ["list"] => {
+            let names = backup::list()?;
+            if names.is_empty() {
+                println!("No backups yet.");
+            }
+            for name in names {
+                println!("{}", name);
+            }
+        }
+        ["restore", name] => {
+            backup::restore(name)?;
+            println!(
+                "Restored {} (the previous state was backed up first).",
+                name
+            );
+        }
+        _ => return Err(anyhow!("Usage: {}", USAGE)),
+    }
+    Ok(())
+}
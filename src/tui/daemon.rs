@@ -0,0 +1,413 @@
+// File: ./src/tui/daemon.rs
+// `cfait daemon`: keeps the local replica in sync and posts reminders without any UI
+// (e.g. as a systemd user service). Running TUIs and GUIs then read its replica and talk to
+// it over `crate::ipc`.
+use crate::backup;
+use crate::cache::SYNC_INTERVAL;
+#[cfg(feature = "imap")]
+use crate::client::imap;
+use crate::client::{PushTarget, RustyClient, push, throttle};
+use crate::config::Config;
+use crate::ipc::{self, Reply, Request};
+use crate::journal::Journal;
+use crate::model::CalendarListEntry;
+use crate::notifier::{CHECK_INTERVAL, ReminderAction, Reminders, notify_blocking};
+use crate::storage::{DaemonLock, LOCAL_CALENDAR_HREF};
+use crate::store::TaskStore;
+use anyhow::{Result, anyhow};
+use chrono::Utc;
+#[cfg(feature = "imap")]
+use std::collections::HashSet;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, oneshot};
+
+pub const USAGE: &str = "cfait daemon [status | sync [--calendar <href>] [--dry-run | --timings]]";
+
+type SyncRequest = (Option<String>, oneshot::Sender<Reply>);
+
+/// `args` are the arguments after `daemon`: none runs the daemon, anything else talks to it.
+pub async fn run(args: &[String]) -> Result<()> {
+    match args.first().map(String::as_str) {
+        None => serve().await,
+        Some("status") => match ipc::request(&Request::Status).await? {
+            Reply::Status { pending, last_sync } => {
+                let last = last_sync.map_or("never".to_string(), |t| {
+                    t.with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d %H:%M")
+                        .to_string()
+                });
+                println!(
+                    "Daemon running. Last sync: {}, {} change(s) pending.",
+                    last, pending
+                );
+                Ok(())
+            }
+            other => Err(anyhow!("Unexpected reply: {:?}", other)),
+        },
+        Some("sync") => {
+            let mut calendar = None;
+            let mut iter = args[1..].iter();
+            while let Some(arg) = iter.next() {
+                match arg.as_str() {
+                    "--calendar" => {
+                        calendar = Some(
+                            iter.next()
+                                .cloned()
+                                .ok_or_else(|| anyhow!("--calendar needs a calendar href"))?,
+                        )
+                    }
+                    "--dry-run" => return dry_run(),
+                    "--timings" => return timings().await,
+                    other => return Err(anyhow!("Unknown argument: {}", other)),
+                }
+            }
+            match ipc::request(&Request::Sync { calendar }).await? {
+                Reply::Synced => {
+                    println!("Synced.");
+                    Ok(())
+                }
+                Reply::Error { message } => Err(anyhow!(message)),
+                other => Err(anyhow!("Unexpected reply: {:?}", other)),
+            }
+        }
+        Some(other) => Err(anyhow!(
+            "Unknown daemon command: {}\nUsage: {}",
+            other,
+            USAGE
+        )),
+    }
+}
+
+/// Prints the requests the next sync would send for the queued changes. Nothing is sent.
+fn dry_run() -> Result<()> {
+    let config = Config::load()?;
+    let client = RustyClient::new(
+        &config.url,
+        &config.username,
+        &config.password,
+        config.allow_insecure_certs,
+    )
+    .map_err(|e| anyhow!(e))?;
+    let plan = client.sync_journal_dry_run();
+    if plan.is_empty() {
+        println!("Nothing to push.");
+    }
+    for request in plan {
+        println!("{}", request);
+    }
+    let held = Journal::load().held.len();
+    if held > 0 {
+        println!("{} task(s) held back.", held);
+    }
+    Ok(())
+}
+
+/// Fetches every calendar here (not in the daemon) and prints how long each took, slowest
+/// first, to tune `[request_limits]`.
+async fn timings() -> Result<()> {
+    let config = Config::load()?;
+    let configured = config.request_limits.concurrent;
+    let (client, calendars, _, _, warning) = RustyClient::connect_with_fallback(config)
+        .await
+        .map_err(|e| anyhow!(e))?;
+    if let Some(w) = warning {
+        return Err(anyhow!(w));
+    }
+    let started = std::time::Instant::now();
+    let mut report = client
+        .get_all_tasks(&calendars)
+        .await
+        .map_err(|e| anyhow!(e))?;
+    let total = started.elapsed();
+
+    report.timings.sort_by(|a, b| b.1.cmp(&a.1));
+    for (href, elapsed) in &report.timings {
+        let name = calendars
+            .iter()
+            .find(|c| &c.href == href)
+            .map_or(href.as_str(), |c| c.name.as_str());
+        let failed = if report.failures.iter().any(|(h, _)| h == href) {
+            "  (failed)"
+        } else {
+            ""
+        };
+        println!("{:>8.2}s  {}{}", elapsed.as_secs_f64(), name, failed);
+    }
+    println!("{:>8.2}s  total", total.as_secs_f64());
+    let concurrency = throttle::concurrency();
+    if concurrency < configured {
+        println!(
+            "The server asked to slow down: {} requests at a time instead of {}.",
+            concurrency, configured
+        );
+    }
+    Ok(())
+}
+
+async fn serve() -> Result<()> {
+    let _lock = DaemonLock::acquire()?;
+    let config = Config::load()?;
+    let lead = config.notify_before_minutes;
+    let push_target = PushTarget::from_config(&config);
+    #[cfg(feature = "imap")]
+    let mail_config = config.clone();
+    let (client, mut calendars, _, _, warning) = RustyClient::connect_with_fallback(config)
+        .await
+        .map_err(|e| anyhow!(e))?;
+    if let Some(w) = warning {
+        eprintln!("{}: retrying at the next sync.", w);
+    }
+
+    let (remote_tx, mut remote_changes) = mpsc::channel::<Option<String>>(10);
+    if let Some(target) = push_target {
+        tokio::spawn(async move {
+            let res = push::listen(target, move |hint| {
+                let _ = remote_tx.try_send(hint);
+                !remote_tx.is_closed()
+            })
+            .await;
+            if let Err(e) = res {
+                eprintln!("Push sync off: {}", e);
+            }
+        });
+    }
+
+    // Subscribers get the hrefs refreshed by every sync (empty = all calendars)
+    let (changes, _) = broadcast::channel::<Vec<String>>(16);
+    let (sync_tx, mut sync_requests) = mpsc::channel::<SyncRequest>(10);
+    let handler = move |request: Request| {
+        let sync_tx = sync_tx.clone();
+        async move {
+            match request {
+                Request::Status => {
+                    let status = Journal::status();
+                    Reply::Status {
+                        pending: status.pending,
+                        last_sync: status.last_sync,
+                    }
+                }
+                Request::Sync { calendar } => {
+                    let (tx, rx) = oneshot::channel();
+                    if sync_tx.send((calendar, tx)).await.is_err() {
+                        return Reply::Error {
+                            message: "The daemon is stopping".to_string(),
+                        };
+                    }
+                    rx.await.unwrap_or(Reply::Error {
+                        message: "The daemon is stopping".to_string(),
+                    })
+                }
+                Request::Subscribe => Reply::Error {
+                    message: "Already subscribed".to_string(),
+                },
+            }
+        }
+    };
+    #[cfg(feature = "imap")]
+    if mail_config.imap.is_some() {
+        let client = client.clone();
+        let calendars = calendars.clone();
+        let changes = changes.clone();
+        tokio::spawn(poll_mail(client, calendars, mail_config, changes));
+    }
+    let ipc_changes = changes.clone();
+    tokio::spawn(async move {
+        if let Err(e) = ipc::serve(ipc_changes, handler).await {
+            eprintln!("IPC off: {}", e);
+        }
+    });
+
+    match lead {
+        Some(m) => println!(
+            "Syncing, reminders {} min before due dates and at the tasks' own. Ctrl+C to stop.",
+            m
+        ),
+        None => println!("Syncing, reminders only at the tasks' own. Ctrl+C to stop."),
+    }
+
+    let mut reminders = Reminders::default();
+    let mut store = TaskStore::new();
+    let (answer_tx, mut answers) = mpsc::unbounded_channel::<(String, ReminderAction)>();
+    let mut check = tokio::time::interval(CHECK_INTERVAL);
+    // Full sync (which also flushes the journal); push notifications come in between
+    let mut sync = tokio::time::interval(SYNC_INTERVAL);
+
+    loop {
+        tokio::select! {
+            _ = sync.tick() => {
+                if let Err(e) = backup::snapshot_if_due() {
+                    eprintln!("Backup failed: {}", e);
+                }
+                if let Ok(cals) = client.get_calendars().await {
+                    calendars = cals;
+                }
+                if let Err(e) = sync_calendars(&client, &calendars, None, &changes).await {
+                    eprintln!("Sync failed: {}", e);
+                }
+            }
+            Some(hint) = remote_changes.recv() => {
+                if let Err(e) = sync_calendars(&client, &calendars, hint, &changes).await {
+                    eprintln!("Sync failed: {}", e);
+                }
+            }
+            Some((calendar, reply)) = sync_requests.recv() => {
+                let res = sync_calendars(&client, &calendars, calendar, &changes).await;
+                let _ = reply.send(match res {
+                    Ok(()) => Reply::Synced,
+                    Err(message) => Reply::Error { message },
+                });
+            }
+            _ = check.tick() => {
+                reload_replica(&client, &calendars, &mut store);
+                let due = reminders.due(store.calendars.values().flatten(), lead, Utc::now());
+                for task in due {
+                    let tx = answer_tx.clone();
+                    tokio::task::spawn_blocking(move || {
+                        let _ = tx.send((task.uid.clone(), notify_blocking(&task)));
+                    });
+                }
+            }
+            Some((uid, action)) = answers.recv() => match action {
+                ReminderAction::Snooze => reminders.snooze(&uid, Utc::now()),
+                ReminderAction::Done => {
+                    if let Err(e) = complete(&client, &mut store, &uid).await {
+                        eprintln!("Could not complete task: {}", e);
+                    }
+                }
+                ReminderAction::Dismissed => {}
+            },
+            _ = tokio::signal::ctrl_c() => {
+                // Last chance to push changes queued while offline
+                let _ = client.sync_journal().await;
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Fetches one calendar (or all when unknown / `None`) and tells the subscribers.
+async fn sync_calendars(
+    client: &RustyClient,
+    calendars: &[CalendarListEntry],
+    href: Option<String>,
+    changes: &broadcast::Sender<Vec<String>>,
+) -> Result<(), String> {
+    let changed = match href {
+        Some(href) if calendars.iter().any(|c| c.href == href) => {
+            client.get_tasks(&href).await?;
+            vec![href]
+        }
+        _ => {
+            let report = client.get_all_tasks(calendars).await?;
+            for line in report.failure_lines(calendars) {
+                eprintln!("Could not fetch {}", line);
+            }
+            Vec::new()
+        }
+    };
+    // No receivers is fine
+    let _ = changes.send(changed);
+    Ok(())
+}
+
+fn reload_replica(client: &RustyClient, calendars: &[CalendarListEntry], store: &mut TaskStore) {
+    store.clear();
+    store.insert(
+        LOCAL_CALENDAR_HREF.to_string(),
+        RustyClient::local_tasks(LOCAL_CALENDAR_HREF),
+    );
+    for cal in calendars {
+        if cal.href != LOCAL_CALENDAR_HREF && !client.is_sync_disabled(&cal.href) {
+            store.insert(cal.href.clone(), RustyClient::local_tasks(&cal.href));
+        }
+    }
+}
+
+/// Turns mails into tasks every `interval_minutes`, see `client::imap`.
+#[cfg(feature = "imap")]
+async fn poll_mail(
+    client: RustyClient,
+    calendars: Vec<CalendarListEntry>,
+    config: Config,
+    changes: broadcast::Sender<Vec<String>>,
+) {
+    let Some(mail) = config.imap else { return };
+    let wanted = mail
+        .calendar
+        .clone()
+        .or(config.inbox_calendar)
+        .or(config.default_calendar);
+    let href = match wanted {
+        Some(wanted) => match calendars
+            .iter()
+            .find(|c| c.name == wanted || c.href == wanted)
+        {
+            Some(cal) => cal.href.clone(),
+            None => {
+                eprintln!("Mail import off: unknown calendar '{}'", wanted);
+                return;
+            }
+        },
+        None => LOCAL_CALENDAR_HREF.to_string(),
+    };
+    println!(
+        "Importing mails from {} every {} min.",
+        mail.folder, mail.interval_minutes
+    );
+
+    let mut tick =
+        tokio::time::interval(Duration::from_secs(u64::from(mail.interval_minutes) * 60));
+    let mut store = TaskStore::new();
+    loop {
+        tick.tick().await;
+        // Tasks made from mails before, even if done since
+        reload_replica(&client, &calendars, &mut store);
+        let known: HashSet<String> = store
+            .calendars
+            .values()
+            .flatten()
+            .filter_map(imap::message_id)
+            .map(str::to_string)
+            .collect();
+        let tasks = match imap::fetch_tasks(&mail, &href, &known).await {
+            Ok((tasks, warnings)) => {
+                for w in warnings {
+                    eprintln!("{}", w);
+                }
+                tasks
+            }
+            Err(e) => {
+                eprintln!("Mail import failed: {}", e);
+                continue;
+            }
+        };
+        if tasks.is_empty() {
+            continue;
+        }
+        for mut task in tasks {
+            if let Some(defaults) = config.calendar_defaults.get(&href) {
+                defaults.apply(&mut task);
+            }
+            match client.create_task(&mut task).await {
+                Ok(_) => println!("From mail: {}", task.summary),
+                Err(e) => eprintln!("Could not add '{}': {}", task.summary, e),
+            }
+        }
+        let _ = changes.send(vec![href.clone()]);
+    }
+}
+
+async fn complete(client: &RustyClient, store: &mut TaskStore, uid: &str) -> Result<()> {
+    if store.is_task_done(uid) != Some(false) {
+        return Ok(());
+    }
+    let mut task = store
+        .toggle_task(uid)
+        .ok_or_else(|| anyhow!("Task not found"))?;
+    client
+        .toggle_task(&mut task)
+        .await
+        .map_err(|e| anyhow!(e))?;
+    println!("Done: {}", task.summary);
+    Ok(())
+}
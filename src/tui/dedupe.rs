@@ -0,0 +1,145 @@
+// File: ./src/tui/dedupe.rs
+// `cfait dedupe`: lists probable duplicate tasks and merges each group the user confirms
+use crate::client::RustyClient;
+use crate::config::Config;
+use crate::model::Task;
+use crate::model::dedupe::{DuplicateGroup, DuplicateKind, find_duplicates, plan_merge};
+use crate::storage::{LOCAL_CALENDAR_HREF, LOCAL_CALENDAR_NAME, LocalStorage};
+use anyhow::{Result, anyhow};
+use chrono::Duration;
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+pub const USAGE: &str = "cfait dedupe [--days <N>] [--yes]   (merge duplicate tasks)";
+
+fn describe(task: &Task, names: &HashMap<String, String>) -> String {
+    let calendar = names
+        .get(&task.calendar_href)
+        .map_or(task.calendar_href.as_str(), String::as_str);
+    let due = task
+        .due
+        .map(|d| format!(", due {}", d.format("%Y-%m-%d %H:%M")))
+        .unwrap_or_default();
+    format!("{}  [{}{}]", task.summary, calendar, due)
+}
+
+/// The same copy of a task: UIDs repeat across calendars after a bad migration.
+fn same(a: &Task, b: &Task) -> bool {
+    a.uid == b.uid && a.calendar_href == b.calendar_href
+}
+
+fn confirm() -> Result<bool> {
+    print!("Merge into the first one? [y/N] ");
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+pub async fn run(args: &[String]) -> Result<()> {
+    let mut days = 1;
+    let mut assume_yes = false;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--days" => {
+                days = iter
+                    .next()
+                    .and_then(|v| v.parse().ok())
+                    .ok_or_else(|| anyhow!("--days needs a number\nUsage: {}", USAGE))?;
+            }
+            "-y" | "--yes" => assume_yes = true,
+            "--config" => {
+                iter.next(); // Already applied by AppPaths::init_from_args
+            }
+            _ => {}
+        }
+    }
+
+    let config = Config::load()?;
+    let (client, calendars, _, _, warning) = RustyClient::connect_with_fallback(config)
+        .await
+        .map_err(|e| anyhow!(e))?;
+    if let Some(w) = warning {
+        eprintln!("{}: changes are queued until the next sync.", w);
+    }
+
+    let mut names: HashMap<String, String> = calendars
+        .iter()
+        .map(|c| (c.href.clone(), c.name.clone()))
+        .collect();
+    names.insert(
+        LOCAL_CALENDAR_HREF.to_string(),
+        LOCAL_CALENDAR_NAME.to_string(),
+    );
+    let mut tasks = LocalStorage::load().unwrap_or_default();
+    let report = client.get_all_tasks(&calendars).await.unwrap_or_default();
+    for line in report.failure_lines(&calendars) {
+        eprintln!("Could not fetch {}, using the local copy.", line);
+    }
+    for (href, fetched) in report.tasks {
+        if href != LOCAL_CALENDAR_HREF {
+            tasks.extend(fetched);
+        }
+    }
+
+    let groups = find_duplicates(&tasks, Duration::days(days));
+    if groups.is_empty() {
+        println!("No duplicates found.");
+        return Ok(());
+    }
+    let mut merged = 0;
+    for group in &groups {
+        println!();
+        match group.kind {
+            DuplicateKind::SameUid => println!("Same task in several calendars:"),
+            DuplicateKind::SameSummary => println!("Same title:"),
+        }
+        for (i, task) in group.tasks.iter().enumerate() {
+            let marker = if i == 0 { "*" } else { " " };
+            println!("  {} {}", marker, describe(task, &names));
+        }
+        if !assume_yes && !confirm()? {
+            continue;
+        }
+
+        // Earlier merges may have relinked tasks of this group: plan with their current state
+        let current = DuplicateGroup {
+            kind: group.kind,
+            tasks: group
+                .tasks
+                .iter()
+                .filter_map(|g| tasks.iter().find(|t| same(t, g)).cloned())
+                .collect(),
+        };
+        let plan = plan_merge(&current, &tasks);
+        let mut failed = false;
+        for mut task in plan.relink.into_iter().chain(std::iter::once(plan.keep)) {
+            match client.update_task(&mut task).await {
+                Ok(warnings) => warnings.iter().for_each(|w| eprintln!("  {}", w)),
+                Err(e) => {
+                    eprintln!("  Failed to update {}: {}", task.summary, e);
+                    failed = true;
+                }
+            }
+            if let Some(t) = tasks.iter_mut().find(|t| same(t, &task)) {
+                *t = task;
+            }
+        }
+        // Extras are only deleted once everything pointing to them points to the kept task
+        if failed {
+            eprintln!("  Kept the duplicates.");
+            continue;
+        }
+        for extra in &plan.delete {
+            match client.delete_task(extra).await {
+                Ok(_) => tasks.retain(|t| !same(t, extra)),
+                Err(e) => eprintln!("  Failed to delete {}: {}", describe(extra, &names), e),
+            }
+        }
+        merged += 1;
+    }
+    println!();
+    println!("Merged {} of {} groups.", merged, groups.len());
+    Ok(())
+}
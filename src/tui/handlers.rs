@@ -1,22 +1,51 @@
 // File: src/tui/handlers.rs
-use crate::config::Config;
-use crate::model::{Task, TaskStatus, extract_inline_aliases};
-use crate::storage::LOCAL_CALENDAR_HREF;
+use crate::client::wirelog;
+use crate::config::{CalendarDefaults, Config, validate_alias};
+use crate::i18n::{tr, tr_args};
+use crate::journal::Journal;
+use crate::model::agenda;
+use crate::model::due::DueDisplay;
+use crate::model::inbox;
+use crate::model::parser::parse_smart_date;
+use crate::model::recurrence::{Frequency, Recurrence, describe_rrule};
+use crate::model::share::ShareFormat;
+use crate::model::template::{find_template, parse_template_command};
+use crate::model::validate::check_before_save;
+use crate::model::{CalendarListEntry, Task, TaskStatus, extract_inline_aliases};
+use crate::storage::{LOCAL_CALENDAR_HREF, LocalStorage};
+use crate::store::ListKey;
 use crate::tui::action::{Action, AppEvent, SidebarMode};
-use crate::tui::state::{AppState, Focus, InputMode};
+use crate::tui::state::{AppState, Focus, InputMode, RecurrenceStep};
+use crate::tui::term;
+use chrono::{Local, Weekday};
+use crossterm::clipboard::CopyToClipboard;
 use crossterm::event::{KeyCode, KeyEvent};
+use std::collections::HashMap;
 use tokio::sync::mpsc::Sender;
 
 pub fn handle_app_event(state: &mut AppState, event: AppEvent, default_cal: &Option<String>) {
     match event {
         AppEvent::Status(s) => state.message = s,
+        AppEvent::TaskSaved(task) => state.store.stamp(&task),
+        AppEvent::FetchFailed(lines) => {
+            if lines != state.fetch_failures {
+                state.fetch_failures = lines;
+                state.fetch_failures_dismissed = false;
+            }
+        }
         AppEvent::Error(s) => {
-            state.message = format!("Error: {}", s);
+            state.message = tr_args("tui.error", &[("error", &s)]);
             state.loading = false;
         }
         AppEvent::CalendarsLoaded(cals) => {
             state.calendars = cals;
 
+            // The calendar of the restored session, as long as it still exists
+            let kept = state
+                .active_cal_href
+                .take()
+                .filter(|href| state.calendars.iter().any(|c| c.href == *href));
+
             // Unhide default calendar on load
             if let Some(def) = default_cal
                 && let Some(found) = state
@@ -29,6 +58,10 @@ pub fn handle_app_event(state: &mut AppState, event: AppEvent, default_cal: &Opt
                 }
                 state.active_cal_href = Some(found.href.clone());
             }
+            if let Some(href) = kept {
+                state.hidden_calendars.remove(&href);
+                state.active_cal_href = Some(href);
+            }
 
             if state.active_cal_href.is_none() {
                 state.active_cal_href = Some(LOCAL_CALENDAR_HREF.to_string());
@@ -40,8 +73,38 @@ pub fn handle_app_event(state: &mut AppState, event: AppEvent, default_cal: &Opt
                 state.store.insert(href, tasks);
             }
             state.refresh_filtered_view();
+            state.apply_restored_selection();
             state.loading = false;
         }
+        AppEvent::ConfigReloaded(Ok(cfg)) => {
+            state.hide_completed = cfg.hide_completed;
+            state.hide_fully_completed_tags = cfg.hide_fully_completed_tags;
+            state.tag_aliases = cfg.tag_aliases;
+            state.calendar_defaults = cfg.calendar_defaults;
+            state.templates = cfg.templates;
+            state.move_subtree = cfg.move_subtree;
+            state.new_task_placement = cfg.new_task_placement;
+            state.due_display = cfg.due_display;
+            state.due_soon_days = cfg.due_soon_days;
+            state.not_started = cfg.not_started;
+            state.tui_columns = cfg.tui_columns;
+            state.sort_cutoff_months = cfg.sort_cutoff_months;
+            state.completed_retention_days = cfg.completed_retention_days;
+            state.review_after_weeks = cfg.review_after_weeks;
+            state.inbox_calendar = cfg.inbox_calendar;
+            state.hidden_calendars = cfg.hidden_calendars.into_iter().collect();
+            state.disabled_calendars = cfg.disabled_calendars.into_iter().collect();
+            // Never hide the calendar new tasks go to
+            if let Some(active) = &state.active_cal_href {
+                state.hidden_calendars.remove(active);
+            }
+            state.refresh_filtered_view();
+            // The network actor keeps its connection; server settings need a restart
+            state.message = tr("tui.config_reloaded").to_string();
+        }
+        AppEvent::ConfigReloaded(Err(e)) => {
+            state.message = tr_args("tui.config_not_reloaded", &[("error", &e)]);
+        }
     }
 }
 
@@ -50,8 +113,34 @@ pub async fn handle_key_event(
     state: &mut AppState,
     action_tx: &Sender<Action>,
 ) -> Option<Action> {
+    if !term::is_input(&key) {
+        return None;
+    }
+    if state.mode == InputMode::Normal && state.shows_fetch_failures() {
+        if matches!(key.code, KeyCode::Esc | KeyCode::Enter) {
+            state.fetch_failures_dismissed = true;
+        }
+        return None;
+    }
     match state.mode {
         InputMode::Creating => match key.code {
+            KeyCode::Enter if state.creating_note => {
+                state.mode = InputMode::Normal;
+                state.creating_note = false;
+                let target_href = state
+                    .active_cal_href
+                    .clone()
+                    .or_else(|| state.calendars.first().map(|c| c.href.clone()));
+                let mut note = Task::new_note(&state.input_buffer);
+                state.reset_input();
+                if note.summary.is_empty() {
+                    return None;
+                }
+                note.calendar_href = target_href?;
+                state.store.add_task(note.clone());
+                state.message = tr("tui.note_added").to_string();
+                return Some(Action::CreateTask(note));
+            }
             KeyCode::Enter if !state.input_buffer.is_empty() => {
                 // --- 1. Extract Inline Aliases ---
                 let (clean_input, new_aliases) = extract_inline_aliases(&state.input_buffer);
@@ -97,7 +186,7 @@ pub async fn handle_key_event(
                     } else {
                         state.mode = InputMode::Normal;
                         state.reset_input();
-                        state.message = "Alias updated.".to_string();
+                        state.message = tr("tui.alias_updated").to_string();
                         return None;
                     }
                 }
@@ -107,13 +196,73 @@ pub async fn handle_key_event(
                     .clone()
                     .or_else(|| state.calendars.first().map(|c| c.href.clone()));
 
-                if let Some(href) = target_href {
+                // --- 3. Template instantiation (tpl:name title) ---
+                if let Some((name, title)) = parse_template_command(&clean_input)
+                    && let Some(href) = &target_href
+                {
+                    let Some(mut tasks) = find_template(&state.templates, name)
+                        .map(|tpl| tpl.instantiate(title, href, &state.tag_aliases))
+                    else {
+                        state.message = tr_args("tui.no_template", &[("name", &name)]);
+                        return None;
+                    };
+                    if let Some(root) = tasks.first_mut() {
+                        root.parent_uid = state.creating_child_of.clone();
+                    }
+                    for task in &mut tasks {
+                        if let Some(defaults) = state.calendar_defaults.get(href) {
+                            defaults.apply(task);
+                        }
+                        if let Err(e) = check_before_save(task) {
+                            state.message = e;
+                            return None;
+                        }
+                    }
+
+                    state.message = tr_args(
+                        "tui.created_from_template",
+                        &[("n", &tasks.len()), ("name", &name)],
+                    );
+                    for task in &tasks {
+                        state.store.add_task(task.clone());
+                    }
+                    state.mode = InputMode::Normal;
+                    state.reset_input();
+                    state.creating_child_of = None;
+
+                    let last = tasks.pop();
+                    for task in tasks {
+                        let _ = action_tx.send(Action::CreateTask(task)).await;
+                    }
+                    return last.map(Action::CreateTask);
+                }
+
+                // Quick-added tasks wait in the inbox for the triage ('I')
+                let inbox = state
+                    .inbox_href()
+                    .filter(|_| state.creating_child_of.is_none());
+                if let Some(href) = inbox.clone().or(target_href) {
                     let mut task = Task::new(&clean_input, &state.tag_aliases);
+                    if let Some(defaults) = state.calendar_defaults.get(&href) {
+                        defaults.apply(&mut task);
+                    }
                     task.calendar_href = href.clone();
                     task.parent_uid = state.creating_child_of.clone();
+                    let selected = state.get_selected_task().map(|t| t.uid.clone());
+                    state.store.place_new_task(
+                        &mut task,
+                        state.new_task_placement,
+                        selected.as_deref(),
+                    );
+                    if let Err(e) = check_before_save(&task) {
+                        state.message = e;
+                        return None;
+                    }
 
                     state.store.add_task(task.clone());
-                    state.refresh_filtered_view();
+                    if inbox.is_some_and(|h| state.active_cal_href.as_ref() != Some(&h)) {
+                        state.message = tr("tui.added_to_inbox").to_string();
+                    }
 
                     state.mode = InputMode::Normal;
                     state.reset_input();
@@ -125,6 +274,7 @@ pub async fn handle_key_event(
             }
             KeyCode::Esc => {
                 state.mode = InputMode::Normal;
+                state.creating_note = false;
                 state.reset_input();
             }
             KeyCode::Char(c) => state.enter_char(c),
@@ -157,7 +307,13 @@ pub async fn handle_key_event(
                 if let Some(uid) = target_uid
                     && let Some((t, _)) = state.store.get_task_mut(&uid)
                 {
-                    t.apply_smart_input(&clean_input, &state.tag_aliases);
+                    let mut candidate = t.clone();
+                    candidate.apply_smart_input(&clean_input, &state.tag_aliases);
+                    if let Err(e) = check_before_save(&candidate) {
+                        state.message = e;
+                        return None;
+                    }
+                    *t = candidate;
                     let clone = t.clone();
                     state.refresh_filtered_view();
                     state.mode = InputMode::Normal;
@@ -177,31 +333,35 @@ pub async fn handle_key_event(
             _ => {}
         },
         InputMode::EditingDescription => match key.code {
+            _ if term::is_newline(&key) => state.enter_char('\n'),
             KeyCode::Enter => {
-                if key.modifiers.contains(crossterm::event::KeyModifiers::ALT)
-                    || key
-                        .modifiers
-                        .contains(crossterm::event::KeyModifiers::SHIFT)
-                {
-                    state.enter_char('\n');
-                } else {
-                    let target_uid = state
-                        .editing_index
-                        .and_then(|idx| state.tasks.get(idx).map(|t| t.uid.clone()));
+                let target_uid = state
+                    .editing_index
+                    .and_then(|idx| state.tasks.get(idx).map(|t| t.uid.clone()));
 
-                    if let Some(uid) = target_uid
-                        && let Some((t, _)) = state.store.get_task_mut(&uid)
-                    {
-                        t.description = state.input_buffer.clone();
-                        let clone = t.clone();
-                        state.refresh_filtered_view();
-                        state.mode = InputMode::Normal;
-                        state.reset_input();
-                        return Some(Action::UpdateTask(clone));
+                if let Some(uid) = target_uid
+                    && let Some((t, _)) = state.store.get_task_mut(&uid)
+                {
+                    let mut candidate = t.clone();
+                    candidate.description = state.input_buffer.clone();
+                    match check_before_save(&candidate) {
+                        Err(e) => {
+                            state.message = e;
+                            return None;
+                        }
+                        // E.g. a description over LARGE_DESCRIPTION: saved, with a warning
+                        Ok(notes) if !notes.is_empty() => state.message = notes.join("; "),
+                        Ok(_) => {}
                     }
+                    *t = candidate;
+                    let clone = t.clone();
+                    state.refresh_filtered_view();
                     state.mode = InputMode::Normal;
                     state.reset_input();
+                    return Some(Action::UpdateTask(clone));
                 }
+                state.mode = InputMode::Normal;
+                state.reset_input();
             }
             KeyCode::Esc => {
                 state.mode = InputMode::Normal;
@@ -220,10 +380,12 @@ pub async fn handle_key_event(
 
             KeyCode::Char(' ') => {
                 if state.active_focus == Focus::Main {
-                    if let Some(uid) = state.get_selected_task().map(|t| t.uid.clone())
+                    if state.get_selected_task().is_some_and(|t| t.is_note()) {
+                        state.message = tr("tui.notes_never_done").to_string();
+                    } else if let Some(uid) = state.writable_selected_uid()
                         && let Some(updated) = state.store.toggle_task(&uid)
                     {
-                        state.refresh_filtered_view();
+                        state.announce_unblocked(&uid);
                         return Some(Action::ToggleTask(updated));
                     }
                 } else if state.active_focus == Focus::Sidebar
@@ -231,7 +393,10 @@ pub async fn handle_key_event(
                 {
                     let target_cal = if let Some(idx) = state.cal_state.selected() {
                         let filtered = state.get_filtered_calendars();
-                        filtered.get(idx).map(|c| c.href.clone())
+                        filtered
+                            .get(idx)
+                            .filter(|c| !state.disabled_calendars.contains(&c.href))
+                            .map(|c| c.href.clone())
                     } else {
                         None
                     };
@@ -250,68 +415,64 @@ pub async fn handle_key_event(
                 }
             }
             KeyCode::Char('s') => {
-                if let Some(uid) = state.get_selected_task().map(|t| t.uid.clone())
+                if let Some(uid) = state.writable_selected_uid()
                     && let Some(updated) = state.store.set_status(&uid, TaskStatus::InProcess)
                 {
-                    state.refresh_filtered_view();
                     return Some(Action::MarkInProcess(updated));
                 }
             }
             KeyCode::Char('x') => {
-                if let Some(uid) = state.get_selected_task().map(|t| t.uid.clone())
+                if let Some(uid) = state.writable_selected_uid()
                     && let Some(updated) = state.store.set_status(&uid, TaskStatus::Cancelled)
                 {
-                    state.refresh_filtered_view();
+                    state.announce_unblocked(&uid);
                     return Some(Action::MarkCancelled(updated));
                 }
             }
             KeyCode::Char('+') => {
-                if let Some(uid) = state.get_selected_task().map(|t| t.uid.clone())
+                if let Some(uid) = state.writable_selected_uid()
                     && let Some(updated) = state.store.change_priority(&uid, 1)
                 {
-                    state.refresh_filtered_view();
                     return Some(Action::UpdateTask(updated));
                 }
             }
             KeyCode::Char('-') => {
-                if let Some(uid) = state.get_selected_task().map(|t| t.uid.clone())
+                if let Some(uid) = state.writable_selected_uid()
                     && let Some(updated) = state.store.change_priority(&uid, -1)
                 {
-                    state.refresh_filtered_view();
                     return Some(Action::UpdateTask(updated));
                 }
             }
             KeyCode::Char('d') => {
-                if let Some(uid) = state.get_selected_task().map(|t| t.uid.clone())
+                if let Some(uid) = state.writable_selected_uid()
                     && let Some(deleted) = state.store.delete_task(&uid)
                 {
-                    state.refresh_filtered_view();
                     return Some(Action::DeleteTask(deleted));
                 }
             }
             KeyCode::Char('c') => {
-                let data = if let Some(parent_uid) = &state.yanked_uid
-                    && let Some(view_task) = state.get_selected_task()
+                let data = if let Some(parent_uid) = state.yanked_uid.clone()
+                    && let Some(child_uid) = state.writable_selected_uid()
                 {
-                    Some((view_task.uid.clone(), parent_uid.clone()))
+                    Some((child_uid, parent_uid))
                 } else {
                     None
                 };
 
                 if let Some((child_uid, parent_uid)) = data {
                     if child_uid == parent_uid {
-                        state.message = "Cannot be child of self!".to_string();
+                        state.message = tr("tui.no_self_child").to_string();
                     } else if let Some(updated) =
                         state.store.set_parent(&child_uid, Some(parent_uid))
                     {
                         state.yanked_uid = None; // Auto-unlink after action
-                        state.refresh_filtered_view();
                         return Some(Action::UpdateTask(updated));
                     }
                 }
             }
             KeyCode::Char('C') => {
                 if state.active_focus == Focus::Main
+                    && state.writable_selected_uid().is_some()
                     && let Some(task) = state.get_selected_task()
                 {
                     let uid = task.uid.clone();
@@ -328,7 +489,152 @@ pub async fn handle_key_event(
 
                     state.mode = InputMode::Creating;
                     state.creating_child_of = Some(uid);
-                    state.message = format!("New Child of '{}'...", summary);
+                    state.message = tr_args("tui.new_child_of", &[("summary", &summary)]);
+                }
+            }
+            KeyCode::Char('n') => {
+                if let Some(uid) = state.writable_selected_uid()
+                    && let Some(updated) = state.store.skip_occurrence(&uid)
+                {
+                    state.message = tr("tui.skipped_occurrence").to_string();
+                    return Some(Action::UpdateTask(updated));
+                }
+            }
+            KeyCode::Char('z') => {
+                if state.active_focus == Focus::Main
+                    && let Some(uid) = state.writable_selected_uid()
+                    && let Some(updated) = state.store.toggle_subtasks_hidden(&uid)
+                {
+                    state.message = if updated.hide_subtasks {
+                        tr("tui.subtasks_folded").to_string()
+                    } else {
+                        tr("tui.subtasks_unfolded").to_string()
+                    };
+                    return Some(Action::UpdateTask(updated));
+                }
+            }
+            KeyCode::Char('h') | KeyCode::Char('l') => {
+                let fold = key.code == KeyCode::Char('h');
+                if state.active_focus == Focus::Main
+                    && let Some(t) = state.get_selected_task()
+                {
+                    let uid = t.uid.clone();
+                    let parent = t.parent_uid.clone();
+                    let href = t.calendar_href.clone();
+                    let folds = t.hide_subtasks != fold && !state.store.children(&uid).is_empty();
+                    if !folds {
+                        // Nothing to fold here: go up to the parent, as in a file tree
+                        if fold && let Some(parent) = parent {
+                            state.select_task(&parent);
+                        }
+                    } else if state.check_writable(&href)
+                        && let Some(updated) = state.store.set_subtasks_hidden(&uid, fold)
+                    {
+                        state.message = if fold {
+                            tr("tui.subtasks_folded").to_string()
+                        } else {
+                            tr("tui.subtasks_unfolded").to_string()
+                        };
+                        return Some(Action::UpdateTask(updated));
+                    }
+                }
+            }
+            KeyCode::Char('D') => {
+                if let Some(href) = state.active_cal_href.clone() {
+                    let current = state
+                        .calendar_defaults
+                        .get(&href)
+                        .map(|d| d.to_smart_string())
+                        .unwrap_or_default();
+                    state.set_input(current);
+                    state.mode = InputMode::EditingDefaults;
+                    state.message = tr("tui.defaults_prompt").to_string();
+                } else {
+                    state.message = tr("tui.defaults_no_calendar").to_string();
+                }
+            }
+            KeyCode::Char('Z') => {
+                // The calendar under the cursor in the sidebar, else all of them
+                let href = if state.active_focus == Focus::Sidebar
+                    && state.sidebar_mode == SidebarMode::Calendars
+                    && let Some(idx) = state.cal_state.selected()
+                {
+                    state
+                        .get_filtered_calendars()
+                        .get(idx)
+                        .map(|c| c.href.clone())
+                } else {
+                    None
+                };
+                if href.as_deref() == Some(LOCAL_CALENDAR_HREF) {
+                    state.message = tr("tui.local_nothing_to_download").to_string();
+                    return None;
+                }
+                return Some(Action::ForceRefresh(href));
+            }
+            KeyCode::Char('S') => {
+                if state.active_focus == Focus::Sidebar
+                    && state.sidebar_mode == SidebarMode::Calendars
+                    && let Some(idx) = state.cal_state.selected()
+                    && let Some(href) = state
+                        .get_filtered_calendars()
+                        .get(idx)
+                        .map(|c| c.href.clone())
+                {
+                    if href == LOCAL_CALENDAR_HREF {
+                        state.message = tr("tui.local_never_synced").to_string();
+                        return None;
+                    }
+                    if state.disabled_calendars.remove(&href) {
+                        state.message = tr("tui.sync_resumed").to_string();
+                    } else {
+                        state.disabled_calendars.insert(href.clone());
+                        if state.active_cal_href.as_ref() == Some(&href) {
+                            state.active_cal_href = None;
+                        }
+                        state.message = tr("tui.sync_turned_off").to_string();
+                    }
+                    state.refresh_filtered_view();
+                    let disabled: Vec<String> = state.disabled_calendars.iter().cloned().collect();
+                    if let Ok(mut cfg) = Config::load() {
+                        cfg.disabled_calendars = disabled.clone();
+                        let _ = cfg.save();
+                    }
+                    return Some(Action::SetDisabledCalendars(disabled));
+                }
+            }
+            KeyCode::Char('R') => {
+                if state.active_focus == Focus::Main
+                    && state.get_selected_task().is_some_and(|t| t.rrule.is_some())
+                    && state.writable_selected_uid().is_some()
+                {
+                    state.editing_index = state.selected_task_index();
+                    state.reset_input();
+                    state.mode = InputMode::Rescheduling;
+                    state.message = tr("tui.reschedule_prompt").to_string();
+                }
+            }
+            KeyCode::Char('o') => {
+                if state.active_focus == Focus::Main
+                    && state.writable_selected_uid().is_some()
+                    && let Some(t) = state.get_selected_task()
+                {
+                    let current = t.rrule.clone();
+                    let draft = current.as_deref().and_then(Recurrence::from_rrule);
+                    let prefill = draft
+                        .as_ref()
+                        .map(|r| r.freq.to_string()[..1].to_lowercase())
+                        .unwrap_or_default();
+
+                    state.message = match (&current, &draft) {
+                        (Some(raw), None) => tr_args("tui.rule_replaced", &[("rule", raw)]),
+                        _ => tr("tui.recurrence_prompt").to_string(),
+                    };
+                    state.recurrence_draft = draft.unwrap_or_default();
+                    state.recurrence_step = RecurrenceStep::Frequency;
+                    state.editing_index = state.selected_task_index();
+                    state.set_input(prefill);
+                    state.mode = InputMode::EditingRecurrence;
                 }
             }
             KeyCode::Char('y') => {
@@ -336,51 +642,49 @@ pub async fn handle_key_event(
                     let uid = t.uid.clone();
                     let summary = t.summary.clone();
                     state.yanked_uid = Some(uid);
-                    state.message = format!("Yanked: {}", summary);
+                    state.message = tr_args("tui.yanked", &[("summary", &summary)]);
                 }
             }
             KeyCode::Char('b') => {
-                let data = if let Some(yanked) = &state.yanked_uid
-                    && let Some(current) = state.get_selected_task()
+                let data = if let Some(yanked) = state.yanked_uid.clone()
+                    && let Some(current) = state.writable_selected_uid()
                 {
-                    Some((current.uid.clone(), yanked.clone()))
+                    Some((current, yanked))
                 } else {
                     None
                 };
 
                 if let Some((curr_uid, yanked_uid)) = data {
                     if curr_uid == yanked_uid {
-                        state.message = "Cannot depend on self!".to_string();
+                        state.message = tr("tui.no_self_dependency").to_string();
                     } else if let Some(updated) = state.store.add_dependency(&curr_uid, yanked_uid)
                     {
                         state.yanked_uid = None; // Auto-unlink after action
-                        state.refresh_filtered_view();
                         return Some(Action::UpdateTask(updated));
                     }
                 }
             }
             KeyCode::Char('.') | KeyCode::Char('>') => {
                 if state.active_focus == Focus::Main
-                    && let Some(idx) = state.list_state.selected()
+                    && let Some(idx) = state.selected_task_index()
                     && idx > 0
                     && idx < state.tasks.len()
+                    && let Some(current_uid) = state.writable_selected_uid()
                 {
                     let parent_uid = state.tasks[idx - 1].uid.clone();
-                    let current_uid = state.tasks[idx].uid.clone();
                     if let Some(updated) = state.store.set_parent(&current_uid, Some(parent_uid)) {
-                        state.refresh_filtered_view();
                         return Some(Action::UpdateTask(updated));
                     }
                 }
             }
             KeyCode::Char(',') | KeyCode::Char('<') => {
                 if state.active_focus == Focus::Main
-                    && let Some(view_task) = state.get_selected_task()
-                    && view_task.parent_uid.is_some()
+                    && state
+                        .get_selected_task()
+                        .is_some_and(|t| t.parent_uid.is_some())
+                    && let Some(uid) = state.writable_selected_uid()
                 {
-                    let uid = view_task.uid.clone();
                     if let Some(updated) = state.store.set_parent(&uid, None) {
-                        state.refresh_filtered_view();
                         return Some(Action::UpdateTask(updated));
                     }
                 }
@@ -392,6 +696,7 @@ pub async fn handle_key_event(
                         .iter()
                         .filter(|c| {
                             c.href != LOCAL_CALENDAR_HREF
+                                && !c.is_read_only
                                 && !state.disabled_calendars.contains(&c.href)
                         })
                         .cloned()
@@ -402,29 +707,57 @@ pub async fn handle_key_event(
                     }
                 }
             }
+            KeyCode::Char('p') | KeyCode::Char('P') => {
+                if state.active_focus == Focus::Main
+                    && let Some(task) = state.get_selected_task()
+                {
+                    // Current calendar first, so Enter duplicates in place
+                    let current_href = task.calendar_href.clone();
+                    let mut targets: Vec<CalendarListEntry> = state
+                        .calendars
+                        .iter()
+                        .filter(|c| !c.is_read_only && !state.disabled_calendars.contains(&c.href))
+                        .cloned()
+                        .collect();
+                    targets.sort_by_key(|c| c.href != current_href);
+                    state.move_targets = targets;
+                    if !state.move_targets.is_empty() {
+                        state.duplicate_with_subtree = key.code == KeyCode::Char('P');
+                        state.move_selection_state.select(Some(0));
+                        state.mode = InputMode::Duplicating;
+                        state.message = tr("tui.select_calendar").to_string();
+                    }
+                }
+            }
             KeyCode::Char('M') => {
-                if let Some(task) = state.get_selected_task() {
+                if state.writable_selected_uid().is_some()
+                    && let Some(task) = state.get_selected_task()
+                {
                     let current_href = task.calendar_href.clone();
                     state.move_targets = state
                         .calendars
                         .iter()
                         .filter(|c| {
-                            c.href != current_href && !state.disabled_calendars.contains(&c.href)
+                            c.href != current_href
+                                && !c.is_read_only
+                                && !state.disabled_calendars.contains(&c.href)
                         })
                         .cloned()
                         .collect();
                     if !state.move_targets.is_empty() {
                         state.move_selection_state.select(Some(0));
                         state.mode = InputMode::Moving;
-                        state.message = "Select a calendar and press Enter.".to_string();
+                        state.message = tr("tui.select_calendar").to_string();
                     }
                 }
             }
             KeyCode::Down | KeyCode::Char('j') => state.next(),
             KeyCode::Up | KeyCode::Char('k') => state.previous(),
-            KeyCode::PageDown => state.jump_forward(10),
-            KeyCode::PageUp => state.jump_backward(10),
+            KeyCode::PageDown => state.jump_forward(ListKey::PAGE),
+            KeyCode::PageUp => state.jump_backward(ListKey::PAGE),
             KeyCode::Tab => state.toggle_focus(),
+            KeyCode::Char('w') => state.toggle_details_focus(),
+            KeyCode::Esc if state.active_focus == Focus::Details => state.toggle_details_focus(),
             KeyCode::Char('1') => {
                 state.sidebar_mode = SidebarMode::Calendars;
                 state.refresh_filtered_view();
@@ -433,6 +766,21 @@ pub async fn handle_key_event(
                 state.sidebar_mode = SidebarMode::Categories;
                 state.refresh_filtered_view();
             }
+            KeyCode::Char('g') => {
+                state.group_by = state.group_by.next();
+                state.message = tr_args("tui.group_by", &[("group", &state.group_by)]);
+                state.refresh_filtered_view();
+            }
+            KeyCode::Char('3') => {
+                // Start on the overdue tasks, or today when there are none
+                state.sidebar_mode = SidebarMode::Agenda;
+                state.cal_state.select(Some(0));
+                state.refresh_filtered_view();
+                if state.tasks.is_empty() {
+                    state.cal_state.select(Some(1));
+                    state.refresh_filtered_view();
+                }
+            }
             KeyCode::Char('m') => {
                 state.match_all_categories = !state.match_all_categories;
                 state.refresh_filtered_view();
@@ -441,6 +789,19 @@ pub async fn handle_key_event(
                 state.hide_completed = !state.hide_completed;
                 state.refresh_filtered_view();
             }
+            KeyCode::Char('L') => {
+                if let Some(days) = state.completed_retention_days {
+                    state.show_all_completed = !state.show_all_completed;
+                    state.message = if state.show_all_completed {
+                        tr("tui.showing_all_completed").to_string()
+                    } else {
+                        tr_args("tui.completed_last_days", &[("days", &days)])
+                    };
+                    state.refresh_filtered_view();
+                } else {
+                    state.message = tr("tui.no_retention").to_string();
+                }
+            }
             KeyCode::Char('*') => {
                 if state.active_focus == Focus::Sidebar {
                     match state.sidebar_mode {
@@ -472,6 +833,7 @@ pub async fn handle_key_event(
                         SidebarMode::Categories => {
                             state.selected_categories.clear();
                         }
+                        SidebarMode::Agenda => {}
                     }
                     state.refresh_filtered_view();
                 }
@@ -482,7 +844,10 @@ pub async fn handle_key_event(
                 {
                     let target_href = if let Some(idx) = state.cal_state.selected() {
                         let filtered = state.get_filtered_calendars();
-                        filtered.get(idx).map(|c| c.href.clone())
+                        filtered
+                            .get(idx)
+                            .filter(|c| !state.disabled_calendars.contains(&c.href))
+                            .map(|c| c.href.clone())
                     } else {
                         None
                     };
@@ -510,7 +875,10 @@ pub async fn handle_key_event(
                         SidebarMode::Calendars => {
                             let target_href = if let Some(idx) = state.cal_state.selected() {
                                 let filtered = state.get_filtered_calendars();
-                                filtered.get(idx).map(|c| c.href.clone())
+                                filtered
+                                    .get(idx)
+                                    .filter(|c| !state.disabled_calendars.contains(&c.href))
+                                    .map(|c| c.href.clone())
                             } else {
                                 None
                             };
@@ -543,7 +911,10 @@ pub async fn handle_key_event(
                                 state.refresh_filtered_view();
                             }
                         }
+                        SidebarMode::Agenda => state.active_focus = Focus::Main,
                     }
+                } else {
+                    state.toggle_selected_group();
                 }
             }
             KeyCode::Char('/') => {
@@ -551,30 +922,304 @@ pub async fn handle_key_event(
                 state.reset_input();
             }
             KeyCode::Char('a') => {
+                if let Some(href) = state.active_cal_href.clone()
+                    && !state.check_writable(&href)
+                {
+                    return None;
+                }
                 state.mode = InputMode::Creating;
                 state.reset_input();
-                state.message = "New Task...".to_string();
+                state.message = tr("tui.new_task").to_string();
+            }
+            KeyCode::Char('J') => {
+                if let Some(href) = state.active_cal_href.clone()
+                    && !state.check_writable(&href)
+                {
+                    return None;
+                }
+                state.mode = InputMode::Creating;
+                state.creating_note = true;
+                state.creating_child_of = None;
+                state.reset_input();
+                state.message = tr("tui.new_note").to_string();
             }
             KeyCode::Char('e') => {
-                if let Some(t) = state.get_selected_task() {
+                if state.writable_selected_uid().is_some()
+                    && let Some(t) = state.get_selected_task()
+                {
                     state.input_buffer = t.to_smart_string();
                     state.cursor_position = state.input_buffer.len();
-                    state.editing_index = state.list_state.selected();
+                    state.editing_index = state.selected_task_index();
                     state.mode = InputMode::Editing;
                 }
             }
+            KeyCode::Char('v') => {
+                let count = state.start_review();
+                if count == 0 {
+                    state.message = tr_args(
+                        "tui.nothing_to_review",
+                        &[("weeks", &state.review_after_weeks)],
+                    );
+                } else {
+                    state.mode = InputMode::Reviewing;
+                    state.message = tr("tui.review_help").to_string();
+                }
+            }
+            KeyCode::Char('I') => {
+                if state.inbox_href().is_none() {
+                    state.message = tr("tui.no_inbox").to_string();
+                } else if state.start_triage() == 0 {
+                    state.message = tr("tui.inbox_empty").to_string();
+                } else {
+                    state.mode = InputMode::Triaging;
+                    state.message = tr("tui.triage_help").to_string();
+                }
+            }
+            KeyCode::Char('T') => {
+                // For this session; `due_display` in the config sets the default
+                state.due_display = state.due_display.toggled();
+                state.message = tr(match state.due_display {
+                    DueDisplay::Relative => "tui.due_relative",
+                    DueDisplay::Absolute => "tui.due_absolute",
+                })
+                .to_string();
+            }
+            KeyCode::Char('N') => {
+                state.next_actions = !state.next_actions;
+                if state.sidebar_mode == SidebarMode::Agenda {
+                    state.sidebar_mode = SidebarMode::Calendars;
+                }
+                state.message = if state.next_actions {
+                    tr("tui.next_actions").to_string()
+                } else {
+                    tr("tui.all_tasks").to_string()
+                };
+                state.refresh_filtered_view();
+            }
+            KeyCode::Char('F') => {
+                // For this session; `not_started` in the config sets the default
+                state.not_started = state.not_started.next();
+                state.message = tr_args("tui.not_started", &[("mode", &state.not_started)]);
+                state.refresh_filtered_view();
+            }
+            KeyCode::Char('U') => {
+                if state.load_pending_changes() == 0 {
+                    state.message = tr("tui.nothing_pending").to_string();
+                } else {
+                    state.mode = InputMode::PreviewingSync;
+                    state.message = tr("tui.preview_help").to_string();
+                }
+            }
+            KeyCode::Char('W') => {
+                if !wirelog::is_enabled() {
+                    state.message = tr("tui.wire_log_off").to_string();
+                } else if state.load_wire_entries() == 0 {
+                    state.message = tr("tui.no_requests").to_string();
+                } else {
+                    state.mode = InputMode::ViewingWireLog;
+                    state.message = tr("tui.wire_log_help").to_string();
+                }
+            }
+            KeyCode::Char('A') => {
+                state.mode = InputMode::ManagingAliases;
+                state.step_alias_selection(0);
+                state.message = tr("tui.alias_help").to_string();
+            }
+            KeyCode::Char('Y') => {
+                if state.active_focus == Focus::Main && state.get_selected_task().is_some() {
+                    state.mode = InputMode::Sharing;
+                    state.message = tr("tui.share_prompt").to_string();
+                }
+            }
+            KeyCode::Char('O') => {
+                state.mode = InputMode::Printing;
+                state.message = tr("tui.print_help").to_string();
+            }
             KeyCode::Char('E') => {
                 if state.active_focus == Focus::Main
+                    && state.writable_selected_uid().is_some()
                     && let Some(t) = state.get_selected_task()
                 {
                     state.input_buffer = t.description.clone();
                     state.cursor_position = state.input_buffer.len();
-                    state.editing_index = state.list_state.selected();
+                    state.editing_index = state.selected_task_index();
                     state.mode = InputMode::EditingDescription;
                 }
             }
             _ => {}
         },
+        InputMode::EditingDefaults => match key.code {
+            KeyCode::Enter => {
+                if let Some(href) = state.active_cal_href.clone() {
+                    let defaults = CalendarDefaults::from_smart_string(&state.input_buffer);
+                    if defaults.is_empty() {
+                        state.calendar_defaults.remove(&href);
+                    } else {
+                        state.calendar_defaults.insert(href, defaults);
+                    }
+                    if let Ok(mut cfg) = Config::load() {
+                        cfg.calendar_defaults = state.calendar_defaults.clone();
+                        let _ = cfg.save();
+                    }
+                    state.message = tr("tui.defaults_saved").to_string();
+                }
+                state.mode = InputMode::Normal;
+                state.reset_input();
+            }
+            KeyCode::Esc => {
+                state.mode = InputMode::Normal;
+                state.reset_input();
+                state.message = String::new();
+            }
+            KeyCode::Char(c) => state.enter_char(c),
+            KeyCode::Backspace => state.delete_char(),
+            KeyCode::Left => state.move_cursor_left(),
+            KeyCode::Right => state.move_cursor_right(),
+            _ => {}
+        },
+        InputMode::Rescheduling => match key.code {
+            KeyCode::Enter => {
+                let target_uid = state
+                    .editing_index
+                    .and_then(|idx| state.tasks.get(idx).map(|t| t.uid.clone()));
+
+                match parse_smart_date(state.input_buffer.trim(), true) {
+                    Some(new_due) => {
+                        if let Some(uid) = target_uid
+                            && let Some((updated, next)) =
+                                state.store.reschedule_occurrence(&uid, new_due)
+                        {
+                            state.mode = InputMode::Normal;
+                            state.reset_input();
+                            state.message = tr("tui.occurrence_rescheduled").to_string();
+                            let _ = action_tx.send(Action::CreateTask(next)).await;
+                            return Some(Action::UpdateTask(updated));
+                        }
+                        state.mode = InputMode::Normal;
+                        state.reset_input();
+                    }
+                    None => {
+                        state.message =
+                            tr_args("tui.invalid_date", &[("input", &state.input_buffer)]);
+                    }
+                }
+            }
+            KeyCode::Esc => {
+                state.mode = InputMode::Normal;
+                state.reset_input();
+                state.message = String::new();
+            }
+            KeyCode::Char(c) => state.enter_char(c),
+            KeyCode::Backspace => state.delete_char(),
+            KeyCode::Left => state.move_cursor_left(),
+            KeyCode::Right => state.move_cursor_right(),
+            _ => {}
+        },
+        InputMode::EditingRecurrence => match key.code {
+            KeyCode::Enter => {
+                let input = state.input_buffer.trim().to_lowercase();
+                match state.recurrence_step {
+                    RecurrenceStep::Frequency => {
+                        state.recurrence_draft.freq = match input.chars().next() {
+                            Some('d') => Frequency::Daily,
+                            Some('w') => Frequency::Weekly,
+                            Some('m') => Frequency::Monthly,
+                            Some('y') => Frequency::Yearly,
+                            None | Some('n') => return finish_recurrence(state, None),
+                            _ => {
+                                state.message =
+                                    tr_args("tui.unknown_frequency", &[("input", &input)]);
+                                return None;
+                            }
+                        };
+                        state.recurrence_step = RecurrenceStep::Interval;
+                        state.set_input(state.recurrence_draft.interval.to_string());
+                    }
+                    RecurrenceStep::Interval => {
+                        state.recurrence_draft.interval = match input.parse::<u32>() {
+                            Ok(n) if n > 0 => n,
+                            _ if input.is_empty() => 1,
+                            _ => {
+                                state.message =
+                                    tr_args("tui.invalid_interval", &[("input", &input)]);
+                                return None;
+                            }
+                        };
+                        let draft = &state.recurrence_draft;
+                        let prefill = match draft.freq {
+                            Frequency::Weekly => Some(
+                                draft
+                                    .by_day
+                                    .iter()
+                                    .map(|d| d.to_string().to_lowercase())
+                                    .collect::<Vec<_>>()
+                                    .join(","),
+                            ),
+                            Frequency::Monthly => Some(
+                                draft
+                                    .by_month_day
+                                    .map(|d| d.to_string())
+                                    .unwrap_or_default(),
+                            ),
+                            _ => None,
+                        };
+                        match prefill {
+                            Some(p) => {
+                                state.recurrence_step = RecurrenceStep::Days;
+                                state.set_input(p);
+                            }
+                            None => {
+                                let rrule = state.recurrence_draft.to_rrule();
+                                return finish_recurrence(state, Some(rrule));
+                            }
+                        }
+                    }
+                    RecurrenceStep::Days => {
+                        if state.recurrence_draft.freq == Frequency::Weekly {
+                            let days: Result<Vec<Weekday>, _> = input
+                                .split(',')
+                                .map(str::trim)
+                                .filter(|d| !d.is_empty())
+                                .map(|d| d.parse::<Weekday>())
+                                .collect();
+                            let Ok(days) = days else {
+                                state.message =
+                                    tr_args("tui.invalid_weekdays", &[("input", &input)]);
+                                return None;
+                            };
+                            state.recurrence_draft.by_day.clear();
+                            for day in days {
+                                if !state.recurrence_draft.by_day.contains(&day) {
+                                    state.recurrence_draft.toggle_day(day);
+                                }
+                            }
+                        } else {
+                            state.recurrence_draft.by_month_day = match input.parse::<u32>() {
+                                Ok(d) if (1..=31).contains(&d) => Some(d),
+                                _ if input.is_empty() => None,
+                                _ => {
+                                    state.message =
+                                        tr_args("tui.invalid_month_day", &[("input", &input)]);
+                                    return None;
+                                }
+                            };
+                        }
+                        let rrule = state.recurrence_draft.to_rrule();
+                        return finish_recurrence(state, Some(rrule));
+                    }
+                }
+            }
+            KeyCode::Esc => {
+                state.mode = InputMode::Normal;
+                state.reset_input();
+                state.message = String::new();
+            }
+            KeyCode::Char(c) => state.enter_char(c),
+            KeyCode::Backspace => state.delete_char(),
+            KeyCode::Left => state.move_cursor_left(),
+            KeyCode::Right => state.move_cursor_right(),
+            _ => {}
+        },
         InputMode::Moving => match key.code {
             KeyCode::Esc => {
                 state.mode = InputMode::Normal;
@@ -592,18 +1237,60 @@ pub async fn handle_key_event(
                     None
                 };
 
-                if let Some((uid, target_href)) = data
-                    && let Some(updated) = state.store.move_task(&uid, target_href.clone())
-                {
-                    state.refresh_filtered_view();
-                    state.message = "Moving task...".to_string();
-                    state.mode = InputMode::Normal;
-                    return Some(Action::MoveTask(updated, target_href));
+                if let Some((uid, target_href)) = data {
+                    let moved: Vec<Task> = if state.move_subtree {
+                        state.store.move_subtree(&uid, &target_href)
+                    } else {
+                        state
+                            .store
+                            .move_task(&uid, target_href.clone())
+                            .into_iter()
+                            .collect()
+                    };
+                    if !moved.is_empty() {
+                        state.message = tr_args("tui.moving", &[("n", &moved.len())]);
+                        state.mode = InputMode::Normal;
+                        return Some(Action::MoveTasks(moved, target_href));
+                    }
                 }
                 state.mode = InputMode::Normal;
             }
             _ => {}
         },
+        InputMode::Duplicating => match key.code {
+            KeyCode::Esc => {
+                state.mode = InputMode::Normal;
+                state.message = String::new();
+            }
+            KeyCode::Down | KeyCode::Char('j') => state.next_move_target(),
+            KeyCode::Up | KeyCode::Char('k') => state.previous_move_target(),
+            KeyCode::Enter => {
+                state.mode = InputMode::Normal;
+                let data = if let Some(task) = state.get_selected_task()
+                    && let Some(idx) = state.move_selection_state.selected()
+                    && let Some(target_cal) = state.move_targets.get(idx)
+                {
+                    Some((task.uid.clone(), target_cal.href.clone()))
+                } else {
+                    None
+                };
+
+                if let Some((uid, target_href)) = data {
+                    let mut copies = state.store.duplicate_task(
+                        &uid,
+                        state.duplicate_with_subtree,
+                        &target_href,
+                    );
+                    state.message = tr_args("tui.duplicated", &[("n", &copies.len())]);
+                    let last = copies.pop();
+                    for copy in copies {
+                        let _ = action_tx.send(Action::CreateTask(copy)).await;
+                    }
+                    return last.map(Action::CreateTask);
+                }
+            }
+            _ => {}
+        },
         InputMode::Exporting => match key.code {
             KeyCode::Esc => {
                 state.mode = InputMode::Normal;
@@ -622,7 +1309,447 @@ pub async fn handle_key_event(
             }
             _ => {}
         },
+        InputMode::ManagingAliases => match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                state.mode = InputMode::Normal;
+                state.message = String::new();
+            }
+            KeyCode::Down | KeyCode::Char('j') => state.step_alias_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => state.step_alias_selection(-1),
+            KeyCode::Char('a') => {
+                state.alias_editing = None;
+                state.set_input("#".to_string());
+                state.mode = InputMode::EditingAlias;
+            }
+            KeyCode::Char('e') | KeyCode::Enter => {
+                if let Some(key) = state.selected_alias() {
+                    let tags = state.tag_aliases[&key].join(",#");
+                    state.set_input(format!("#{}=#{}", key, tags));
+                    state.alias_editing = Some(key);
+                    state.mode = InputMode::EditingAlias;
+                }
+            }
+            KeyCode::Char('d') => {
+                if let Some(key) = state.selected_alias() {
+                    state.tag_aliases.remove(&key);
+                    save_aliases(state);
+                    state.step_alias_selection(0);
+                    state.message = tr_args("tui.alias_deleted", &[("name", &key)]);
+                }
+            }
+            _ => {}
+        },
+        InputMode::EditingAlias => match key.code {
+            KeyCode::Enter => {
+                // Same syntax as inline definitions; spaces around commas are fine
+                let mut input: String = state.input_buffer.split_whitespace().collect();
+                if !input.starts_with('#') {
+                    input.insert(0, '#');
+                }
+                let (_, parsed) = extract_inline_aliases(&input);
+                let Some((key, tags)) = parsed.into_iter().next() else {
+                    state.message = tr("tui.alias_format").to_string();
+                    return None;
+                };
+                if let Err(e) = validate_alias(&key, &tags) {
+                    state.message = e;
+                    return None;
+                }
+                if let Some(old) = state.alias_editing.take()
+                    && old != key
+                {
+                    state.tag_aliases.remove(&old);
+                }
+                state.tag_aliases.insert(key.clone(), tags.clone());
+                save_aliases(state);
+
+                let modified = state.store.apply_alias_retroactively(&key, &tags);
+                state.mode = InputMode::ManagingAliases;
+                state.reset_input();
+                let idx = state.alias_keys().iter().position(|k| *k == key);
+                state.alias_selection_state.select(idx);
+                state.message = tr_args(
+                    "tui.alias_saved",
+                    &[("name", &key), ("help", &tr("tui.alias_help"))],
+                );
+                for t in modified {
+                    let _ = action_tx.send(Action::UpdateTask(t)).await;
+                }
+            }
+            KeyCode::Esc => {
+                state.mode = InputMode::ManagingAliases;
+                state.reset_input();
+                state.message = tr("tui.alias_help").to_string();
+            }
+            KeyCode::Char(c) => state.enter_char(c),
+            KeyCode::Backspace => state.delete_char(),
+            KeyCode::Left => state.move_cursor_left(),
+            KeyCode::Right => state.move_cursor_right(),
+            _ => {}
+        },
+        InputMode::Reviewing => {
+            let Some(uid) = state.review_task().map(|t| t.uid.clone()) else {
+                return finish_review(state);
+            };
+            let action = match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return finish_review(state),
+                KeyCode::Char('k') | KeyCode::Enter => {
+                    state.store.mark_reviewed(&uid).map(Action::UpdateTask)
+                }
+                KeyCode::Char('c') | KeyCode::Char(' ') => {
+                    state.store.toggle_task(&uid).map(Action::ToggleTask)
+                }
+                KeyCode::Char('d') => state.store.delete_task(&uid).map(Action::DeleteTask),
+                KeyCode::Char('r') => {
+                    state.reset_input();
+                    state.mode = InputMode::ReviewRescheduling;
+                    state.message = tr("tui.review_due_prompt").to_string();
+                    return None;
+                }
+                KeyCode::Char('s') | KeyCode::Right => None,
+                _ => return None,
+            };
+            state.review_queue.remove(0);
+            if state.review_task().is_none() {
+                finish_review(state);
+            }
+            return action;
+        }
+        InputMode::ReviewRescheduling => match key.code {
+            KeyCode::Enter => {
+                let input = state.input_buffer.trim().to_string();
+                let due = if input.is_empty() {
+                    None
+                } else if let Some(due) = parse_smart_date(&input, true) {
+                    Some(due)
+                } else {
+                    state.message = tr_args("tui.invalid_date", &[("input", &input)]);
+                    return None;
+                };
+                let updated = state
+                    .review_task()
+                    .map(|t| t.uid.clone())
+                    .and_then(|uid| state.store.set_due(&uid, due));
+                state.reset_input();
+                state.mode = InputMode::Reviewing;
+                state.message = tr("tui.review_help").to_string();
+                if !state.review_queue.is_empty() {
+                    state.review_queue.remove(0);
+                }
+                if state.review_task().is_none() {
+                    finish_review(state);
+                }
+                return updated.map(Action::UpdateTask);
+            }
+            KeyCode::Esc => {
+                state.reset_input();
+                state.mode = InputMode::Reviewing;
+                state.message = tr("tui.review_help").to_string();
+            }
+            KeyCode::Char(c) => state.enter_char(c),
+            KeyCode::Backspace => state.delete_char(),
+            KeyCode::Left => state.move_cursor_left(),
+            KeyCode::Right => state.move_cursor_right(),
+            _ => {}
+        },
+        InputMode::Triaging => {
+            let Some(uid) = state.triage_task().map(|t| t.uid.clone()) else {
+                return finish_triage(state);
+            };
+            let action = match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return finish_triage(state),
+                KeyCode::Char('f') | KeyCode::Enter => {
+                    state.input_buffer = state
+                        .triage_task()
+                        .map(|t| t.to_smart_string())
+                        .unwrap_or_default();
+                    state.cursor_position = state.input_buffer.len();
+                    state.mode = InputMode::TriageEditing;
+                    state.message = tr("tui.triage_edit_help").to_string();
+                    return None;
+                }
+                KeyCode::Char('c') | KeyCode::Char(' ') => {
+                    state.store.toggle_task(&uid).map(Action::ToggleTask)
+                }
+                KeyCode::Char('d') => state.store.delete_task(&uid).map(Action::DeleteTask),
+                KeyCode::Char('s') | KeyCode::Right => None,
+                _ => return None,
+            };
+            state.triage_queue.remove(0);
+            if state.triage_task().is_none() {
+                finish_triage(state);
+            }
+            return action;
+        }
+        InputMode::TriageEditing => match key.code {
+            KeyCode::Enter => {
+                let Some(mut candidate) = state.triage_task().cloned() else {
+                    return finish_triage(state);
+                };
+                candidate.apply_smart_input(state.input_buffer.trim(), &state.tag_aliases);
+                if let Err(e) = check_before_save(&candidate) {
+                    state.message = e;
+                    return None;
+                }
+                state.move_targets = inbox::filing_targets(
+                    &state.calendars,
+                    &candidate.calendar_href,
+                    &state.disabled_calendars,
+                );
+                if state.move_targets.is_empty() {
+                    state.message = tr("tui.no_file_target").to_string();
+                    return None;
+                }
+                state.triage_edit = Some(candidate);
+                state.move_selection_state.select(Some(0));
+                state.mode = InputMode::TriageFiling;
+                state.message = tr("tui.file_prompt").to_string();
+            }
+            KeyCode::Esc => {
+                state.reset_input();
+                state.mode = InputMode::Triaging;
+                state.message = tr("tui.triage_help").to_string();
+            }
+            KeyCode::Char(c) => state.enter_char(c),
+            KeyCode::Backspace => state.delete_char(),
+            KeyCode::Left => state.move_cursor_left(),
+            KeyCode::Right => state.move_cursor_right(),
+            _ => {}
+        },
+        InputMode::TriageFiling => match key.code {
+            KeyCode::Esc => {
+                // Back to the tags and dates, as typed
+                state.triage_edit = None;
+                state.mode = InputMode::TriageEditing;
+                state.message = tr("tui.triage_edit_help").to_string();
+            }
+            KeyCode::Down | KeyCode::Char('j') => state.next_move_target(),
+            KeyCode::Up | KeyCode::Char('k') => state.previous_move_target(),
+            KeyCode::Enter => {
+                let target = state
+                    .move_selection_state
+                    .selected()
+                    .and_then(|i| state.move_targets.get(i))
+                    .map(|c| c.href.clone());
+                let (Some(task), Some(target)) = (state.triage_edit.take(), target) else {
+                    return None;
+                };
+                if let Some((t, _)) = state.store.get_task_mut(&task.uid) {
+                    *t = task.clone();
+                }
+                let mut moved: Vec<Task> = if state.move_subtree {
+                    state.store.move_subtree(&task.uid, &target)
+                } else {
+                    state
+                        .store
+                        .move_task(&task.uid, target.clone())
+                        .into_iter()
+                        .collect()
+                };
+                // The task goes with its changes, its subtasks right after
+                moved.retain(|t| t.uid != task.uid);
+                state.reset_input();
+                state.mode = InputMode::Triaging;
+                state.message = tr("tui.triage_help").to_string();
+                state.triage_filed += 1;
+                state.triage_queue.remove(0);
+                if state.triage_task().is_none() {
+                    finish_triage(state);
+                }
+                let _ = action_tx.send(Action::FileTask(task, target.clone())).await;
+                if !moved.is_empty() {
+                    return Some(Action::MoveTasks(moved, target));
+                }
+            }
+            _ => {}
+        },
+        InputMode::PreviewingSync => match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                state.mode = InputMode::Normal;
+                state.message = String::new();
+            }
+            KeyCode::Down | KeyCode::Char('j') => state.step_pending_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => state.step_pending_selection(-1),
+            KeyCode::Char(' ') | KeyCode::Char('x') => {
+                if let Some(change) = state
+                    .pending_state
+                    .selected()
+                    .and_then(|i| state.pending_changes.get(i))
+                {
+                    let (uid, held) = (change.uid.clone(), !change.held);
+                    state.message = match Journal::hold(&uid, held) {
+                        Ok(()) => tr("tui.preview_help").to_string(),
+                        Err(e) => tr_args("tui.queue_not_updated", &[("error", &e)]),
+                    };
+                    state.load_pending_changes();
+                }
+            }
+            KeyCode::Enter | KeyCode::Char('r') => {
+                state.mode = InputMode::Normal;
+                let held = state.pending_changes.iter().filter(|c| c.held).count();
+                state.message = if held > 0 {
+                    tr_args("tui.syncing_held", &[("n", &held)])
+                } else {
+                    tr("tui.syncing").to_string()
+                };
+                return Some(Action::Refresh);
+            }
+            _ => {}
+        },
+        InputMode::ViewingWireLog => match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                state.mode = InputMode::Normal;
+                state.message = String::new();
+            }
+            KeyCode::Down | KeyCode::Char('j') => state.step_wire_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => state.step_wire_selection(-1),
+            KeyCode::Char('r') => {
+                state.load_wire_entries();
+            }
+            _ => {}
+        },
+        InputMode::Sharing => {
+            let format = match key.code {
+                KeyCode::Char('t') => Some(ShareFormat::Text),
+                KeyCode::Char('i') => Some(ShareFormat::Ics),
+                KeyCode::Char('l') => Some(ShareFormat::Link),
+                _ => None,
+            };
+            state.mode = InputMode::Normal;
+            state.message = match (format, state.get_selected_task()) {
+                (Some(format), Some(task)) => share_task(task, format),
+                _ => String::new(),
+            };
+        }
+        InputMode::Printing => {
+            state.mode = InputMode::Normal;
+            state.message = match key.code {
+                KeyCode::Char('l') => print_page(state, false),
+                KeyCode::Char('w') => print_page(state, true),
+                _ => String::new(),
+            };
+        }
         _ => {}
     }
     None
 }
+
+/// Leaves the review, saying how far it got.
+fn finish_review(state: &mut AppState) -> Option<Action> {
+    let reviewed = state.review_total - state.review_queue.len();
+    state.message = if state.review_queue.is_empty() {
+        tr_args("tui.review_done", &[("n", &reviewed)])
+    } else {
+        tr_args(
+            "tui.review_stopped",
+            &[("n", &reviewed), ("total", &state.review_total)],
+        )
+    };
+    state.review_queue.clear();
+    state.mode = InputMode::Normal;
+    state.refresh_filtered_view();
+    None
+}
+
+/// Leaves the triage, saying how far it got.
+fn finish_triage(state: &mut AppState) -> Option<Action> {
+    let seen = state.triage_total - state.triage_queue.len();
+    state.message = if state.triage_queue.is_empty() {
+        tr_args(
+            "tui.triage_done",
+            &[
+                ("filed", &state.triage_filed),
+                ("total", &state.triage_total),
+            ],
+        )
+    } else {
+        tr_args(
+            "tui.triage_stopped",
+            &[
+                ("n", &seen),
+                ("total", &state.triage_total),
+                ("filed", &state.triage_filed),
+            ],
+        )
+    };
+    state.triage_queue.clear();
+    state.triage_edit = None;
+    state.reset_input();
+    state.mode = InputMode::Normal;
+    state.refresh_filtered_view();
+    None
+}
+
+fn save_aliases(state: &AppState) {
+    if let Ok(mut cfg) = Config::load() {
+        cfg.tag_aliases = state.tag_aliases.clone();
+        let _ = cfg.save();
+    }
+}
+
+/// Copies the task to the terminal's clipboard (OSC 52) or saves it as an .ics file.
+fn share_task(task: &Task, format: ShareFormat) -> String {
+    if format == ShareFormat::Ics {
+        return match LocalStorage::export_ics(task) {
+            Ok(path) => tr_args("tui.saved_to", &[("path", &path.display())]),
+            Err(e) => tr_args("tui.error", &[("error", &e)]),
+        };
+    }
+    let copy = CopyToClipboard::to_clipboard_from(task.share(format));
+    match crossterm::execute!(std::io::stdout(), copy) {
+        Ok(_) => tr("tui.copied").to_string(),
+        Err(e) => tr_args("tui.error", &[("error", &e)]),
+    }
+}
+
+/// Saves the list shown, or this week's agenda, as a printable page in the export folder.
+fn print_page(state: &AppState, week: bool) -> String {
+    let names: HashMap<String, String> = state
+        .calendars
+        .iter()
+        .map(|c| (c.href.clone(), c.name.clone()))
+        .collect();
+    let (name, html) = if week {
+        let start = agenda::week_start(Local::now().date_naive());
+        let tasks = state
+            .store
+            .calendars
+            .iter()
+            .filter(|(href, _)| {
+                !state.hidden_calendars.contains(*href) && !state.disabled_calendars.contains(*href)
+            })
+            .flat_map(|(_, tasks)| tasks);
+        let name = format!("cfait_week_{}", start.format("%Y-%m-%d"));
+        (name, agenda::week_html(tasks, &names, start))
+    } else {
+        let title = state
+            .active_cal_href
+            .as_ref()
+            .and_then(|href| names.get(href))
+            .map_or(tr("tui.tasks"), |n| n.as_str());
+        (
+            "cfait_list".to_string(),
+            agenda::list_html(title, &state.tasks, &names),
+        )
+    };
+    match LocalStorage::export_page(&name, &html) {
+        Ok(path) => tr_args("tui.saved_to", &[("path", &path.display())]),
+        Err(e) => tr_args("tui.error", &[("error", &e)]),
+    }
+}
+
+/// Applies the rule built by the guided recurrence prompt to the task being edited.
+fn finish_recurrence(state: &mut AppState, rrule: Option<String>) -> Option<Action> {
+    let uid = state
+        .editing_index
+        .and_then(|idx| state.tasks.get(idx).map(|t| t.uid.clone()));
+    state.mode = InputMode::Normal;
+    state.reset_input();
+
+    let updated = state.store.set_recurrence(&uid?, rrule)?;
+    state.message = match &updated.rrule {
+        Some(r) => tr_args("tui.repeats", &[("rule", &describe_rrule(r))]),
+        None => tr("tui.recurrence_cleared").to_string(),
+    };
+    Some(Action::UpdateTask(updated))
+}
@@ -0,0 +1,97 @@
+// File: ./src/tui/ingest.rs
+// `cfait ingest`: imports ICS invitations (e.g. piped from a mail client) without the UI
+use crate::client::RustyClient;
+use crate::config::Config;
+use crate::model::ingest::{IngestOp, ItipMethod, parse_itip, plan_ingest};
+use crate::storage::{LOCAL_CALENDAR_HREF, LocalStorage};
+use anyhow::{Result, anyhow};
+use std::io::Read;
+
+pub const USAGE: &str = "cfait ingest [--calendar <NAME|HREF>] [<FILE.ics>|-]";
+
+pub async fn run(args: &[String]) -> Result<()> {
+    let mut calendar_arg = None;
+    let mut path = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--calendar" {
+            calendar_arg = iter.next().cloned();
+        } else if let Some(v) = arg.strip_prefix("--calendar=") {
+            calendar_arg = Some(v.to_string());
+        } else if arg == "--config" {
+            iter.next(); // Already applied by AppPaths::init_from_args
+        } else if !arg.starts_with("--") {
+            path = Some(arg.clone());
+        }
+    }
+
+    let raw = match path.as_deref() {
+        Some("-") | None => {
+            let mut buf = String::new();
+            std::io::stdin().read_to_string(&mut buf)?;
+            buf
+        }
+        Some(p) => std::fs::read_to_string(p)?,
+    };
+
+    let config = Config::load()?;
+    let (client, calendars, _, active, warning) = RustyClient::connect_with_fallback(config)
+        .await
+        .map_err(|e| anyhow!(e))?;
+    if let Some(w) = warning {
+        eprintln!("{}: changes are queued until the next sync.", w);
+    }
+
+    let target = match &calendar_arg {
+        Some(wanted) => calendars
+            .iter()
+            .find(|c| c.name == *wanted || c.href == *wanted)
+            .map(|c| c.href.clone())
+            .ok_or_else(|| anyhow!("Unknown calendar '{}'", wanted))?,
+        None => active.unwrap_or_else(|| LOCAL_CALENDAR_HREF.to_string()),
+    };
+
+    let (method, incoming) = parse_itip(&raw, &target).map_err(|e| anyhow!(e))?;
+
+    let mut existing = LocalStorage::load().unwrap_or_default();
+    let report = client.get_all_tasks(&calendars).await.unwrap_or_default();
+    for line in report.failure_lines(&calendars) {
+        eprintln!("Could not fetch {}, using the local copy.", line);
+    }
+    for (_, tasks) in report.tasks {
+        existing.extend(tasks);
+    }
+
+    let ops = plan_ingest(method, incoming, &existing);
+    if ops.is_empty() {
+        println!("Nothing to do.");
+        return Ok(());
+    }
+    for op in ops {
+        let (verb, task, res) = match op {
+            IngestOp::Create(mut task) => {
+                let res = client.create_task(&mut task).await;
+                ("Created", task, res)
+            }
+            IngestOp::Update(mut task) => {
+                let res = client.update_task(&mut task).await;
+                let verb = if method == ItipMethod::Cancel {
+                    "Cancelled"
+                } else {
+                    "Updated"
+                };
+                (verb, task, res)
+            }
+        };
+        match res {
+            Ok(warnings) => {
+                println!("{}: {}", verb, task.summary);
+                for w in warnings {
+                    eprintln!("  {}", w);
+                }
+            }
+            Err(e) => eprintln!("Failed: {} ({})", task.summary, e),
+        }
+    }
+    Ok(())
+}
@@ -1,40 +1,132 @@
 // File: ./src/tui/mod.rs
 pub mod action;
+pub mod agenda;
+pub mod backup;
+pub mod daemon;
+pub mod dedupe;
 pub mod handlers;
+pub mod ingest;
 pub mod network;
+pub mod quota;
+pub mod rehome;
+pub mod report;
 pub mod state;
+pub mod term;
+pub mod vacuum;
+pub mod verify;
 pub mod view;
 
+use crate::cache::Cache;
+use crate::client::{PushTarget, push};
 use crate::config;
+use crate::demo;
+use crate::i18n::tr_args;
+use crate::ipc;
+use crate::journal::Journal;
+use crate::paths::AppPaths;
+use crate::session::Session;
+use crate::storage::{DaemonLock, DataStamp};
+use crate::tui::action::AppEvent;
 use crate::tui::state::{AppState, InputMode};
 use crate::tui::view::draw;
 
 use anyhow::Result;
-use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, MouseEventKind},
-    execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
-};
-use ratatui::{Terminal, backend::CrosstermBackend};
-use std::{env, io, time::Duration};
+use crossterm::event::{self, Event, MouseEventKind};
+use std::{env, time::Duration};
 use tokio::sync::mpsc;
 
+/// How often to look for writes by other cfait processes
+const STAMP_POLL: Duration = Duration::from_secs(2);
+
 pub async fn run() -> Result<()> {
     // --- 1. PREAMBLE & CONFIG ---
     let args: Vec<String> = env::args().collect();
     if args.len() > 1 && (args[1] == "--help" || args[1] == "-h") {
         println!("Usage: cfait [OPTIONS]");
+        println!("       {}", ingest::USAGE);
+        println!("       {}", daemon::USAGE);
+        println!("       {}", backup::USAGE);
+        println!("       {}", report::USAGE);
+        println!("       {}", agenda::USAGE);
+        println!("       {}", dedupe::USAGE);
+        println!("       {}", rehome::USAGE);
+        println!("       {}", verify::USAGE);
+        println!("       {}", quota::USAGE);
+        println!("       {}", vacuum::USAGE);
+        println!("       {}", crate::server::USAGE);
+        #[cfg(feature = "mcp")]
+        println!("       {}", crate::mcp::USAGE);
+        println!();
+        println!("Options:");
+        println!("  --config <FILE>     Use this config file instead of the default one");
+        println!("  --portable[=<DIR>]  Keep all data in DIR (default: cfait-data next to cfait)");
+        println!("  --demo              Try cfait on sample tasks, none of your data is used");
+        println!("  -h, --help          Print help");
+        println!();
+        println!("Set RUSTACHE_HOME to keep config, data and cache in one directory.");
         return Ok(());
     }
+    // The demo keeps everything in a directory of its own, whatever the other flags say
+    let demo_dir = if demo::requested(&args) {
+        Some(demo::start()?)
+    } else {
+        AppPaths::init_from_args(&args);
+        None
+    };
 
-    // Panic Hook
+    // Headless import of invitations, e.g. from a mail client's "pipe to command"
+    if let Some(pos) = (1..args.len()).find(|&i| args[i] == "ingest" && args[i - 1] != "--config") {
+        return ingest::run(&args[pos + 1..]).await;
+    }
+    if let Some(pos) = (1..args.len()).find(|&i| args[i] == "daemon" && args[i - 1] != "--config") {
+        return daemon::run(&args[pos + 1..]).await;
+    }
+    if let Some(pos) = (1..args.len()).find(|&i| args[i] == "backup" && args[i - 1] != "--config") {
+        return backup::run(&args[pos + 1..]);
+    }
+    if let Some(pos) = (1..args.len()).find(|&i| args[i] == "report" && args[i - 1] != "--config") {
+        return report::run(&args[pos + 1..]).await;
+    }
+    if let Some(pos) = (1..args.len()).find(|&i| args[i] == "agenda" && args[i - 1] != "--config") {
+        return agenda::run(&args[pos + 1..]).await;
+    }
+    if let Some(pos) = (1..args.len()).find(|&i| args[i] == "dedupe" && args[i - 1] != "--config") {
+        return dedupe::run(&args[pos + 1..]).await;
+    }
+    if let Some(pos) = (1..args.len()).find(|&i| args[i] == "rehome" && args[i - 1] != "--config") {
+        return rehome::run(&args[pos + 1..]).await;
+    }
+    if let Some(pos) = (1..args.len()).find(|&i| args[i] == "verify" && args[i - 1] != "--config") {
+        return verify::run(&args[pos + 1..]).await;
+    }
+    if let Some(pos) = (1..args.len()).find(|&i| args[i] == "quota" && args[i - 1] != "--config") {
+        return quota::run(&args[pos + 1..]).await;
+    }
+    if let Some(pos) = (1..args.len()).find(|&i| args[i] == "vacuum" && args[i - 1] != "--config") {
+        return vacuum::run(&args[pos + 1..]);
+    }
+    if let Some(pos) = (1..args.len()).find(|&i| args[i] == "serve" && args[i - 1] != "--config") {
+        return crate::server::run(&args[pos + 1..]).await;
+    }
+    #[cfg(feature = "mcp")]
+    if let Some(pos) = (1..args.len()).find(|&i| args[i] == "mcp" && args[i - 1] != "--config") {
+        return crate::mcp::run(&args[pos + 1..]).await;
+    }
+    if let Err(e) = crate::backup::snapshot_if_due() {
+        eprintln!("Backup failed: {}", e);
+    }
+
+    // Panic Hook: a terminal left in raw mode would hide the message (and, on Windows, keep the
+    // console unusable after exit)
     let default_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |info| {
         use std::io::Write;
+        let _ = term::restore();
+        let log_path = AppPaths::get_panic_log_path().unwrap_or("cfait_panic.log".into());
         if let Ok(mut file) = std::fs::OpenOptions::new()
             .create(true)
             .append(true)
-            .open("cfait_panic.log")
+            .open(log_path)
         {
             let _ = writeln!(file, "PANIC: {:?}", info);
         }
@@ -43,6 +135,7 @@ pub async fn run() -> Result<()> {
 
     let config_result = config::Config::load();
     let (
+        push_target,
         url,
         user,
         pass,
@@ -50,12 +143,24 @@ pub async fn run() -> Result<()> {
         hide_completed,
         hide_fully_completed_tags,
         tag_aliases,
+        calendar_defaults,
+        templates,
+        move_subtree,
+        new_task_placement,
+        due_display,
+        due_soon_days,
+        not_started,
+        tui_columns,
         sort_cutoff,
+        retention_days,
+        review_weeks,
+        inbox_calendar,
         allow_insecure,
         hidden_calendars,
         disabled_calendars,
     ) = match config_result {
         Ok(cfg) => (
+            PushTarget::from_config(&cfg),
             cfg.url,
             cfg.username,
             cfg.password,
@@ -63,7 +168,18 @@ pub async fn run() -> Result<()> {
             cfg.hide_completed,
             cfg.hide_fully_completed_tags,
             cfg.tag_aliases,
+            cfg.calendar_defaults,
+            cfg.templates,
+            cfg.move_subtree,
+            cfg.new_task_placement,
+            cfg.due_display,
+            cfg.due_soon_days,
+            cfg.not_started,
+            cfg.tui_columns,
             cfg.sort_cutoff_months,
+            cfg.completed_retention_days,
+            cfg.review_after_weeks,
+            cfg.inbox_calendar,
             cfg.allow_insecure_certs,
             cfg.hidden_calendars,
             cfg.disabled_calendars,
@@ -71,26 +187,39 @@ pub async fn run() -> Result<()> {
         Err(_) => {
             let path_str =
                 config::Config::get_path_string().unwrap_or("[path unknown]".to_string());
-            eprintln!("Config file not found: {}", path_str);
+            eprintln!(
+                "{}",
+                tr_args("error.config_not_found", &[("path", &path_str)])
+            );
             return Ok(());
         }
     };
 
     // --- 2. TERMINAL SETUP ---
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    let mut terminal = term::setup()?;
 
     // --- 3. STATE INIT ---
     let mut app_state = AppState::new();
     app_state.hide_completed = hide_completed;
     app_state.hide_fully_completed_tags = hide_fully_completed_tags;
     app_state.tag_aliases = tag_aliases;
+    app_state.calendar_defaults = calendar_defaults;
+    app_state.templates = templates;
+    app_state.move_subtree = move_subtree;
+    app_state.new_task_placement = new_task_placement;
+    app_state.due_display = due_display;
+    app_state.due_soon_days = due_soon_days;
+    app_state.not_started = not_started;
+    app_state.tui_columns = tui_columns;
     app_state.sort_cutoff_months = sort_cutoff;
+    app_state.completed_retention_days = retention_days;
+    app_state.review_after_weeks = review_weeks;
+    app_state.inbox_calendar = inbox_calendar;
     app_state.hidden_calendars = hidden_calendars.into_iter().collect();
-    app_state.disabled_calendars = disabled_calendars.into_iter().collect();
+    app_state.disabled_calendars = disabled_calendars.iter().cloned().collect();
+    app_state.sync_status = Journal::status();
+    app_state.sync_times = Cache::sync_times();
+    app_state.restore_session(Session::load("tui"));
 
     let (action_tx, action_rx) = mpsc::channel(10);
     let (event_tx, mut event_rx) = mpsc::channel(10);
@@ -102,17 +231,82 @@ pub async fn run() -> Result<()> {
         pass,
         allow_insecure,
         default_cal.clone(), // Clone for the thread
+        disabled_calendars,
         action_rx,
-        event_tx,
+        event_tx.clone(),
     ));
 
+    // A running `cfait daemon` syncs for everyone: follow its replica instead of the server
+    let daemon_running = DaemonLock::is_held();
+    if daemon_running {
+        let feed_tx = action_tx.clone();
+        tokio::spawn(ipc::subscribe(move |_| {
+            let _ = feed_tx.try_send(action::Action::ReloadReplica);
+            !feed_tx.is_closed()
+        }));
+    }
+
+    // Another instance (or a daemon without change feed) wrote the journal, local tasks or cache
+    let stamp_tx = action_tx.clone();
+    tokio::spawn(async move {
+        let mut seen = DataStamp::current();
+        let mut poll = tokio::time::interval(STAMP_POLL);
+        loop {
+            poll.tick().await;
+            let stamp = DataStamp::current();
+            if stamp != seen {
+                seen = stamp;
+                if stamp_tx.send(action::Action::ReloadReplica).await.is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    // Syncs as soon as the server reports a change, instead of waiting for 'r'
+    if let Some(target) = push_target.filter(|_| !daemon_running) {
+        let push_tx = action_tx.clone();
+        let status_tx = event_tx.clone();
+        tokio::spawn(async move {
+            let res = push::listen(target, move |hint| {
+                let _ = push_tx.try_send(action::Action::RemoteChanged(hint));
+                !push_tx.is_closed()
+            })
+            .await;
+            if let Err(e) = res {
+                let _ = status_tx
+                    .send(AppEvent::Status(format!("Push sync off: {}", e)))
+                    .await;
+            }
+        });
+    }
+
+    // Kept alive for the whole session; reloading is best-effort
+    let _config_watcher = config::Config::watch(move |res| {
+        let _ = event_tx.blocking_send(AppEvent::ConfigReloaded(res.map(Box::new)));
+    })
+    .ok();
+
+    // Whatever changes the tasks (keys, sync results, reloads) shows up here
+    let store_events = app_state.store.subscribe();
+
     // --- 5. UI LOOP ---
     loop {
         terminal.draw(|f| draw(f, &mut app_state))?;
 
         // A. Network Events
         if let Ok(event) = event_rx.try_recv() {
+            let disabled_before = app_state.disabled_calendars.clone();
             handlers::handle_app_event(&mut app_state, event, &default_cal);
+            app_state.sync_status = Journal::status();
+            app_state.sync_times = Cache::sync_times();
+            // A reloaded config may turn sync on or off for some calendars
+            if app_state.disabled_calendars != disabled_before {
+                let hrefs = app_state.disabled_calendars.iter().cloned().collect();
+                let _ = action_tx
+                    .send(action::Action::SetDisabledCalendars(hrefs))
+                    .await;
+            }
         }
 
         // B. Input Events
@@ -147,15 +341,20 @@ pub async fn run() -> Result<()> {
                 _ => {}
             }
         }
+
+        // C. Store Changes (once for all those since the last frame)
+        if store_events.try_iter().count() > 0 {
+            app_state.refresh_filtered_view();
+        }
     }
 
     // --- 6. CLEANUP ---
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    term::restore()?;
+    if let Err(e) = app_state.session().save("tui") {
+        eprintln!("Could not save the session: {}", e);
+    }
+    if let Some(dir) = demo_dir {
+        demo::finish(&dir);
+    }
     Ok(())
 }
@@ -2,8 +2,9 @@
 // New file: Encapsulates the network actor logic
 use crate::cache::Cache;
 use crate::client::RustyClient;
+use crate::ipc::{self, Reply, Request};
 use crate::model::CalendarListEntry;
-use crate::storage::{LOCAL_CALENDAR_HREF, LOCAL_CALENDAR_NAME, LocalStorage};
+use crate::storage::{DaemonLock, LOCAL_CALENDAR_HREF, LOCAL_CALENDAR_NAME, LocalStorage};
 use crate::tui::action::{Action, AppEvent};
 use tokio::sync::mpsc::{Receiver, Sender};
 
@@ -13,24 +14,23 @@ pub async fn run_network_actor(
     pass: String,
     allow_insecure: bool,
     _default_cal: Option<String>,
+    disabled_calendars: Vec<String>,
     mut action_rx: Receiver<Action>,
     event_tx: Sender<AppEvent>,
 ) {
     // ------------------------------------------------------------------
     // 0. LOAD CACHE IMMEDIATELY
     // ------------------------------------------------------------------
+    // Until the server answers, the cached calendars are the ones refreshed and synced
+    let mut calendars = vec![local_calendar()];
     if let Ok(mut cached_cals) = Cache::load_calendars() {
-        let local_cal = CalendarListEntry {
-            name: LOCAL_CALENDAR_NAME.to_string(),
-            href: LOCAL_CALENDAR_HREF.to_string(),
-            color: None,
-        };
         if !cached_cals.iter().any(|c| c.href == LOCAL_CALENDAR_HREF) {
-            cached_cals.push(local_cal);
+            cached_cals.push(local_calendar());
         }
+        calendars = cached_cals;
 
         let _ = event_tx
-            .send(AppEvent::CalendarsLoaded(cached_cals.clone()))
+            .send(AppEvent::CalendarsLoaded(calendars.clone()))
             .await;
 
         let mut cached_tasks = Vec::new();
@@ -38,11 +38,9 @@ pub async fn run_network_actor(
             cached_tasks.push((LOCAL_CALENDAR_HREF.to_string(), local_t));
         }
 
-        for cal in &cached_cals {
-            if cal.href != LOCAL_CALENDAR_HREF
-                && let Ok((tasks, _)) = Cache::load(&cal.href)
-            {
-                cached_tasks.push((cal.href.clone(), tasks));
+        for cal in &calendars {
+            if cal.href != LOCAL_CALENDAR_HREF {
+                cached_tasks.push((cal.href.clone(), RustyClient::local_tasks(&cal.href)));
             }
         }
 
@@ -52,7 +50,7 @@ pub async fn run_network_actor(
     }
 
     // ------------------------------------------------------------------
-    // 1. CONNECT & SYNC
+    // 1. CONNECT & SYNC, while the cached tasks can already be edited
     // ------------------------------------------------------------------
     let client = match RustyClient::new(&url, &user, &pass, allow_insecure) {
         Ok(c) => c,
@@ -61,84 +59,26 @@ pub async fn run_network_actor(
             return;
         }
     };
-    let _ = event_tx
-        .send(AppEvent::Status("Connecting...".to_string()))
-        .await;
-
-    let mut calendars = match client.get_calendars().await {
-        Ok(cals) => cals,
-        Err(e) => {
-            let err_str = e.to_string();
-            if err_str.contains("InvalidCertificate") {
-                let mut helpful_msg =
-                    "Connection failed: The server presented an invalid TLS/SSL certificate."
-                        .to_string();
-                let config_advice = format!(
-                    "\n\nTo fix this, please edit your config file:\n  {}",
-                    crate::config::Config::get_path_string()
-                        .unwrap_or_else(|_| "path unknown".to_string())
-                );
-                if !allow_insecure {
-                    helpful_msg.push_str(
-                        "\nIf this is a self-hosted server, set 'allow_insecure_certs = true'.",
-                    );
-                }
-                helpful_msg.push_str(&config_advice);
-                let _ = event_tx.send(AppEvent::Error(helpful_msg)).await;
-                return;
-            } else {
-                let _ = event_tx
-                    .send(AppEvent::Status(format!("Sync warning: {}", err_str)))
-                    .await;
-                vec![]
-            }
-        }
-    };
-
-    let local_cal = CalendarListEntry {
-        name: LOCAL_CALENDAR_NAME.to_string(),
-        href: LOCAL_CALENDAR_HREF.to_string(),
-        color: None,
-    };
-    calendars.push(local_cal);
-
-    let _ = event_tx
-        .send(AppEvent::CalendarsLoaded(calendars.clone()))
-        .await;
-
-    let _ = event_tx
-        .send(AppEvent::Status("Syncing...".to_string()))
-        .await;
-
-    // Load tasks again with validated calendars list
-    let mut cached_results = Vec::new();
-    for cal in &calendars {
-        if cal.href != LOCAL_CALENDAR_HREF
-            && let Ok((tasks, _)) = Cache::load(&cal.href)
-        {
-            cached_results.push((cal.href.clone(), tasks));
-        }
-    }
-    if !cached_results.is_empty() {
-        let _ = event_tx.send(AppEvent::TasksLoaded(cached_results)).await;
-    }
-
-    match client.get_all_tasks(&calendars).await {
-        Ok(results) => {
-            let _ = event_tx.send(AppEvent::TasksLoaded(results)).await;
-            let _ = event_tx.send(AppEvent::Status("Ready.".to_string())).await;
-        }
-        Err(e) => {
-            let _ = event_tx
-                .send(AppEvent::Status(format!("Sync warning: {}", e)))
-                .await;
-        }
-    }
+    client.set_disabled_calendars(disabled_calendars);
+    let startup = connect_and_sync(client.clone(), allow_insecure, event_tx.clone());
+    tokio::pin!(startup);
+    let mut connecting = true;
 
     // ------------------------------------------------------------------
     // 2. ACTION LOOP
     // ------------------------------------------------------------------
-    while let Some(action) = action_rx.recv().await {
+    loop {
+        let action = tokio::select! {
+            listed = &mut startup, if connecting => {
+                connecting = false;
+                if let Some(listed) = listed {
+                    calendars = listed;
+                }
+                continue;
+            }
+            action = action_rx.recv() => action,
+        };
+        let Some(action) = action else { break };
         match action {
             Action::Quit => break,
             Action::SwitchCalendar(href) => match client.get_tasks(&href).await {
@@ -167,10 +107,42 @@ pub async fn run_network_actor(
                         .await;
                 }
             },
+            Action::SetDisabledCalendars(hrefs) => {
+                for href in client.set_disabled_calendars(hrefs) {
+                    if let Ok(t) = client.get_tasks(&href).await {
+                        let _ = event_tx.send(AppEvent::TasksLoaded(vec![(href, t)])).await;
+                    }
+                }
+            }
+            Action::RemoteChanged(Some(href)) if calendars.iter().any(|c| c.href == href) => {
+                if !client.is_sync_disabled(&href)
+                    && let Ok(t) = client.get_tasks(&href).await
+                {
+                    let _ = event_tx.send(AppEvent::TasksLoaded(vec![(href, t)])).await;
+                }
+            }
+            Action::RemoteChanged(_) => {
+                if let Ok(report) = client.reconcile(&calendars).await {
+                    let failures = report.failure_lines(&calendars);
+                    if !report.tasks.is_empty() {
+                        let _ = event_tx.send(AppEvent::TasksLoaded(report.tasks)).await;
+                    }
+                    let _ = event_tx.send(AppEvent::FetchFailed(failures)).await;
+                }
+            }
+            Action::ReloadReplica => {
+                let results = calendars
+                    .iter()
+                    .filter(|c| !client.is_sync_disabled(&c.href))
+                    .map(|c| (c.href.clone(), RustyClient::local_tasks(&c.href)))
+                    .collect();
+                let _ = event_tx.send(AppEvent::TasksLoaded(results)).await;
+            }
             Action::CreateTask(mut new_task) => {
                 let href = new_task.calendar_href.clone();
                 match client.create_task(&mut new_task).await {
                     Ok(msgs) => {
+                        let _ = event_tx.send(AppEvent::TaskSaved(new_task)).await;
                         if let Ok(t) = client.get_tasks(&href).await {
                             let _ = event_tx.send(AppEvent::TasksLoaded(vec![(href, t)])).await;
                         }
@@ -190,6 +162,7 @@ pub async fn run_network_actor(
                 let href = task.calendar_href.clone();
                 match client.update_task(&mut task).await {
                     Ok(msgs) => {
+                        let _ = event_tx.send(AppEvent::TaskSaved(task)).await;
                         let s = if msgs.is_empty() {
                             "Saved.".to_string()
                         } else {
@@ -216,6 +189,7 @@ pub async fn run_network_actor(
 
                 match client.toggle_task(&mut task).await {
                     Ok((_, _, msgs)) => {
+                        let _ = event_tx.send(AppEvent::TaskSaved(task)).await;
                         let s = if msgs.is_empty() {
                             "Synced.".to_string()
                         } else {
@@ -253,11 +227,43 @@ pub async fn run_network_actor(
                     }
                 }
             }
-            Action::Refresh => {
+            action @ (Action::Refresh | Action::ForceRefresh(_)) => {
+                if let Action::ForceRefresh(href) = action {
+                    if client.client.is_none() {
+                        let msg = "Offline: the local copy is kept.".to_string();
+                        let _ = event_tx.send(AppEvent::Error(msg)).await;
+                        continue;
+                    }
+                    // Local tasks and the offline queue are kept, only the mirrors go
+                    let res = match href {
+                        Some(href) => client.invalidate_calendar(&href),
+                        None => client.invalidate_all().map(|_| ()),
+                    };
+                    if let Err(e) = res {
+                        let _ = event_tx.send(AppEvent::Error(e)).await;
+                        continue;
+                    }
+                }
                 let _ = event_tx
                     .send(AppEvent::Status("Refreshing...".to_string()))
                     .await;
 
+                // The daemon syncs for us; its change feed then reloads the replica
+                if DaemonLock::is_held() {
+                    match ipc::request(&Request::Sync { calendar: None }).await {
+                        Ok(Reply::Synced) => {
+                            let _ = event_tx.send(AppEvent::Status("Synced.".to_string())).await;
+                            continue;
+                        }
+                        Ok(Reply::Error { message }) => {
+                            let _ = event_tx.send(AppEvent::Error(message)).await;
+                            continue;
+                        }
+                        // Unreachable daemon: sync ourselves
+                        _ => {}
+                    }
+                }
+
                 let mut calendars = match client.get_calendars().await {
                     Ok(c) => c,
                     Err(e) => {
@@ -266,20 +272,19 @@ pub async fn run_network_actor(
                     }
                 };
 
-                let local_cal = CalendarListEntry {
-                    name: LOCAL_CALENDAR_NAME.to_string(),
-                    href: LOCAL_CALENDAR_HREF.to_string(),
-                    color: None,
-                };
-                calendars.push(local_cal);
+                calendars.push(local_calendar());
 
                 let _ = event_tx
                     .send(AppEvent::CalendarsLoaded(calendars.clone()))
                     .await;
 
-                match client.get_all_tasks(&calendars).await {
-                    Ok(results) => {
-                        let _ = event_tx.send(AppEvent::TasksLoaded(results)).await;
+                match client.reconcile(&calendars).await {
+                    Ok(report) => {
+                        let failures = report.failure_lines(&calendars);
+                        if !report.tasks.is_empty() {
+                            let _ = event_tx.send(AppEvent::TasksLoaded(report.tasks)).await;
+                        }
+                        let _ = event_tx.send(AppEvent::FetchFailed(failures)).await;
                         let _ = event_tx
                             .send(AppEvent::Status("Refreshed.".to_string()))
                             .await;
@@ -297,6 +302,7 @@ pub async fn run_network_actor(
                 }
                 match client.update_task(&mut task).await {
                     Ok(msgs) => {
+                        let _ = event_tx.send(AppEvent::TaskSaved(task)).await;
                         let s = if msgs.is_empty() {
                             "Saved.".to_string()
                         } else {
@@ -317,6 +323,7 @@ pub async fn run_network_actor(
                 }
                 match client.update_task(&mut task).await {
                     Ok(msgs) => {
+                        let _ = event_tx.send(AppEvent::TaskSaved(task)).await;
                         let s = if msgs.is_empty() {
                             "Saved.".to_string()
                         } else {
@@ -329,33 +336,73 @@ pub async fn run_network_actor(
                     }
                 }
             }
-            Action::MoveTask(task, new_href) => {
+            Action::MoveTasks(tasks, new_href) => {
+                let total = tasks.len();
+                let mut old_hrefs: Vec<String> = Vec::new();
+                let mut msgs = Vec::new();
+                let mut failed = None;
+                for (i, task) in tasks.iter().enumerate() {
+                    if total > 1 {
+                        let _ = event_tx
+                            .send(AppEvent::Status(format!("Moving {}/{}...", i + 1, total)))
+                            .await;
+                    }
+                    if !old_hrefs.contains(&task.calendar_href) {
+                        old_hrefs.push(task.calendar_href.clone());
+                    }
+                    match client.move_task(task, &new_href).await {
+                        Ok((_, m)) => msgs.extend(m),
+                        Err(e) => {
+                            failed = Some(e);
+                            break;
+                        }
+                    }
+                }
+
+                if let Some(e) = failed {
+                    let _ = event_tx
+                        .send(AppEvent::Error(format!("Move failed: {}", e)))
+                        .await;
+                } else {
+                    let s = if msgs.is_empty() {
+                        "Moved.".to_string()
+                    } else {
+                        msgs.join("; ")
+                    };
+                    let _ = event_tx.send(AppEvent::Status(s)).await;
+                }
+
+                if !old_hrefs.contains(&new_href) {
+                    old_hrefs.push(new_href);
+                }
+                for href in old_hrefs {
+                    if let Ok(t) = client.get_tasks(&href).await {
+                        let _ = event_tx.send(AppEvent::TasksLoaded(vec![(href, t)])).await;
+                    }
+                }
+            }
+            Action::FileTask(mut task, new_href) => {
                 let old_href = task.calendar_href.clone();
-                match client.move_task(&task, &new_href).await {
+                match client.file_task(&mut task, &new_href).await {
                     Ok((_, msgs)) => {
                         let s = if msgs.is_empty() {
-                            "Moved.".to_string()
+                            "Filed.".to_string()
                         } else {
                             msgs.join("; ")
                         };
                         let _ = event_tx.send(AppEvent::Status(s)).await;
-                        if let Ok(t1) = client.get_tasks(&old_href).await {
-                            let _ = event_tx
-                                .send(AppEvent::TasksLoaded(vec![(old_href, t1)]))
-                                .await;
-                        }
-                        if let Ok(t2) = client.get_tasks(&new_href).await {
-                            let _ = event_tx
-                                .send(AppEvent::TasksLoaded(vec![(new_href, t2)]))
-                                .await;
-                        }
                     }
                     Err(e) => {
                         let _ = event_tx
-                            .send(AppEvent::Error(format!("Move failed: {}", e)))
+                            .send(AppEvent::Error(format!("Filing failed: {}", e)))
                             .await;
                     }
                 }
+                for href in [old_href, new_href] {
+                    if let Ok(t) = client.get_tasks(&href).await {
+                        let _ = event_tx.send(AppEvent::TasksLoaded(vec![(href, t)])).await;
+                    }
+                }
             }
             Action::MigrateLocal(target_href) => {
                 if let Ok(local_tasks) = LocalStorage::load() {
@@ -398,3 +445,94 @@ pub async fn run_network_actor(
         }
     }
 }
+
+/// The local calendar, listed with the server's.
+fn local_calendar() -> CalendarListEntry {
+    CalendarListEntry {
+        name: LOCAL_CALENDAR_NAME.to_string(),
+        href: LOCAL_CALENDAR_HREF.to_string(),
+        color: None,
+        is_shared: false,
+        is_read_only: false,
+    }
+}
+
+/// Lists the calendars and syncs them (the offline queue first), reporting as it goes. None
+/// when the server can't be reached: the cached calendars stay, changes wait in the queue.
+async fn connect_and_sync(
+    client: RustyClient,
+    allow_insecure: bool,
+    event_tx: Sender<AppEvent>,
+) -> Option<Vec<CalendarListEntry>> {
+    let _ = event_tx
+        .send(AppEvent::Status("Connecting...".to_string()))
+        .await;
+
+    let mut calendars = match client.get_calendars().await {
+        Ok(cals) => cals,
+        Err(e) => {
+            let err_str = e.to_string();
+            if err_str.contains("InvalidCertificate") {
+                let mut helpful_msg =
+                    "Connection failed: The server presented an invalid TLS/SSL certificate."
+                        .to_string();
+                let config_advice = format!(
+                    "\n\nTo fix this, please edit your config file:\n  {}",
+                    crate::config::Config::get_path_string()
+                        .unwrap_or_else(|_| "path unknown".to_string())
+                );
+                if !allow_insecure {
+                    helpful_msg.push_str(
+                        "\nIf this is a self-hosted server, set 'allow_insecure_certs = true'.",
+                    );
+                }
+                helpful_msg.push_str(&config_advice);
+                let _ = event_tx.send(AppEvent::Error(helpful_msg)).await;
+                return None;
+            } else {
+                let _ = event_tx
+                    .send(AppEvent::Status(format!("Sync warning: {}", err_str)))
+                    .await;
+                return None;
+            }
+        }
+    };
+
+    calendars.push(local_calendar());
+
+    let _ = event_tx
+        .send(AppEvent::CalendarsLoaded(calendars.clone()))
+        .await;
+
+    let _ = event_tx
+        .send(AppEvent::Status("Syncing...".to_string()))
+        .await;
+
+    // Load tasks again with validated calendars list
+    let cached_results: Vec<_> = calendars
+        .iter()
+        .filter(|cal| cal.href != LOCAL_CALENDAR_HREF)
+        .map(|cal| (cal.href.clone(), RustyClient::local_tasks(&cal.href)))
+        .collect();
+    if !cached_results.is_empty() {
+        let _ = event_tx.send(AppEvent::TasksLoaded(cached_results)).await;
+    }
+
+    // Only the calendars that changed on either side are sent again
+    match client.reconcile(&calendars).await {
+        Ok(report) => {
+            let failures = report.failure_lines(&calendars);
+            if !report.tasks.is_empty() {
+                let _ = event_tx.send(AppEvent::TasksLoaded(report.tasks)).await;
+            }
+            let _ = event_tx.send(AppEvent::FetchFailed(failures)).await;
+            let _ = event_tx.send(AppEvent::Status("Ready.".to_string())).await;
+        }
+        Err(e) => {
+            let _ = event_tx
+                .send(AppEvent::Status(format!("Sync warning: {}", e)))
+                .await;
+        }
+    }
+    Some(calendars)
+}
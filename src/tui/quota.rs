@@ -0,0 +1,42 @@
+// File: ./src/tui/quota.rs
+// `cfait quota`: the storage limits the server reports for each calendar
+use crate::client::RustyClient;
+use crate::client::quota::NEAR_FULL_PERCENT;
+use crate::config::Config;
+use crate::storage::LOCAL_CALENDAR_HREF;
+use anyhow::{Result, anyhow};
+
+pub const USAGE: &str = "cfait quota   (space used and left on the server, per calendar)";
+
+pub async fn run(args: &[String]) -> Result<()> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--config" => {
+                iter.next(); // Already applied by AppPaths::init_from_args
+            }
+            other => return Err(anyhow!("Unknown argument: {}\nUsage: {}", other, USAGE)),
+        }
+    }
+
+    let config = Config::load()?;
+    let (client, calendars, _, _, warning) = RustyClient::connect_with_fallback(config)
+        .await
+        .map_err(|e| anyhow!(e))?;
+    if let Some(w) = warning {
+        return Err(anyhow!("{}: the limits are the server's to tell.", w));
+    }
+    for cal in calendars.iter().filter(|c| c.href != LOCAL_CALENDAR_HREF) {
+        match client.storage_limits(&cal.href).await {
+            Ok(limits) if limits.is_near_full() => println!(
+                "{}: {} (over {}%, nearly full)",
+                cal.name,
+                limits.describe(),
+                NEAR_FULL_PERCENT
+            ),
+            Ok(limits) => println!("{}: {}", cal.name, limits.describe()),
+            Err(e) => eprintln!("{}: could not ask ({})", cal.name, e),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,49 @@
+// File: ./src/tui/rehome.rs
+// `cfait rehome`: points the local data at a new server after a migration, without the UI
+use crate::backup;
+use crate::client::RustyClient;
+use crate::config::Config;
+use crate::rehome;
+use anyhow::{Result, anyhow};
+
+pub const USAGE: &str = "cfait rehome <OLD_URL> <NEW_URL>   (after moving to another server)";
+
+pub async fn run(args: &[String]) -> Result<()> {
+    let mut words = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--config" {
+            iter.next(); // Already applied by AppPaths::init_from_args
+        } else if !arg.starts_with("--") {
+            words.push(arg.as_str());
+        }
+    }
+    let [old_url, new_url] = words.as_slice() else {
+        return Err(anyhow!("Usage: {}", USAGE));
+    };
+
+    let name = backup::snapshot(Some("rehome"))?;
+    let report = rehome::rehome(old_url, new_url)?;
+    println!(
+        "Moved {} queued changes and {} calendars ({} tasks) to {} (backed up as {}).",
+        report.queued, report.calendars, report.cached_tasks, new_url, name
+    );
+
+    let config = Config::load()?;
+    let client = RustyClient::new(
+        &config.url,
+        &config.username,
+        &config.password,
+        config.allow_insecure_certs,
+    )
+    .map_err(|e| anyhow!(e))?;
+    match client.revalidate_journal().await {
+        Ok(revised) => println!("Checked {} queued changes against the new server.", revised),
+        Err(e) => eprintln!(
+            "Could not check the queued changes against the new server ({}).\n\
+             Run the same command again once it can be reached.",
+            e
+        ),
+    }
+    Ok(())
+}
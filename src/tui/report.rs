@@ -0,0 +1,69 @@
+// File: ./src/tui/report.rs
+// `cfait report`: CSV of the tasks completed over a date range, for invoicing
+use crate::client::RustyClient;
+use crate::config::Config;
+use crate::model::report::{completed_tasks_csv, this_month};
+use crate::storage::{LOCAL_CALENDAR_HREF, LOCAL_CALENDAR_NAME, LocalStorage};
+use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+pub const USAGE: &str = "cfait report [--from YYYY-MM-DD] [--to YYYY-MM-DD] [-o <FILE.csv>]";
+
+fn parse_date(value: Option<&String>) -> Result<NaiveDate> {
+    let value = value.ok_or_else(|| anyhow!("Missing date\nUsage: {}", USAGE))?;
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| anyhow!("Invalid date '{}', expected YYYY-MM-DD", value))
+}
+
+pub async fn run(args: &[String]) -> Result<()> {
+    let (mut from, mut to) = this_month();
+    let mut output = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--from" => from = parse_date(iter.next())?,
+            "--to" => to = parse_date(iter.next())?,
+            "-o" | "--output" => output = iter.next().cloned(),
+            "--config" => {
+                iter.next(); // Already applied by AppPaths::init_from_args
+            }
+            _ => {}
+        }
+    }
+
+    let config = Config::load()?;
+    let (client, calendars, _, _, warning) = RustyClient::connect_with_fallback(config)
+        .await
+        .map_err(|e| anyhow!(e))?;
+    if let Some(w) = warning {
+        eprintln!("{}: using the local copy.", w);
+    }
+
+    let mut names: HashMap<String, String> = calendars
+        .iter()
+        .map(|c| (c.href.clone(), c.name.clone()))
+        .collect();
+    names.insert(
+        LOCAL_CALENDAR_HREF.to_string(),
+        LOCAL_CALENDAR_NAME.to_string(),
+    );
+    let mut tasks = LocalStorage::load().unwrap_or_default();
+    let report = client.get_all_tasks(&calendars).await.unwrap_or_default();
+    for line in report.failure_lines(&calendars) {
+        eprintln!("Could not fetch {}, using the local copy.", line);
+    }
+    for (_, fetched) in report.tasks {
+        tasks.extend(fetched);
+    }
+
+    let csv = completed_tasks_csv(&tasks, &names, from, to);
+    match output {
+        Some(path) => {
+            LocalStorage::atomic_write(&path, csv)?;
+            eprintln!("Wrote {}", path);
+        }
+        None => print!("{}", csv),
+    }
+    Ok(())
+}
@@ -1,14 +1,33 @@
 // File: ./src/tui/state.rs
+use crate::client::wirelog::{self, WireEntry};
+use crate::config::{CalendarDefaults, DEFAULT_TUI_COLUMNS, TuiColumn};
+use crate::i18n::tr_args;
+use crate::journal::{Journal, PendingChange, SyncStatus};
+use crate::model::due::DueDisplay;
+use crate::model::inbox;
+use crate::model::recurrence::Recurrence;
+use crate::model::template::TaskTemplate;
 use crate::model::{CalendarListEntry, Task};
-use crate::store::{FilterOptions, TaskStore};
+use crate::session::{Session, SidebarView};
+use crate::store::{
+    AgendaDay, CalendarStats, FilterOptions, GroupBy, ListRow, NewTaskPlacement, NotStarted,
+    TaskStore, flatten_groups, group_tasks,
+};
 use crate::tui::action::SidebarMode;
+use crate::tui::term::Glyphs;
+use chrono::{DateTime, Local, Utc};
 use ratatui::widgets::ListState;
 use std::collections::{HashMap, HashSet};
 
+/// Days shown by the agenda, today included
+const AGENDA_DAYS: u32 = 7;
+
 #[derive(PartialEq, Clone, Copy)]
 pub enum Focus {
     Sidebar,
     Main,
+    /// The details pane, scrolled with j/k
+    Details,
 }
 
 #[derive(PartialEq, Clone, Copy)]
@@ -20,6 +39,33 @@ pub enum InputMode {
     EditingDescription,
     Moving,
     Exporting,
+    Rescheduling,
+    EditingRecurrence,
+    EditingDefaults,
+    Duplicating,
+    Sharing,
+    /// Choosing what to print: the list as shown or the week's agenda
+    Printing,
+    ManagingAliases,
+    EditingAlias,
+    Reviewing,
+    ReviewRescheduling,
+    /// Walking through the inbox; then typing tags and dates, then picking the calendar
+    Triaging,
+    TriageEditing,
+    TriageFiling,
+    /// The offline queue, before it is pushed
+    PreviewingSync,
+    /// The last requests to the server (`wire_log`)
+    ViewingWireLog,
+}
+
+/// Steps of the guided recurrence prompt.
+#[derive(PartialEq, Clone, Copy)]
+pub enum RecurrenceStep {
+    Frequency,
+    Interval,
+    Days,
 }
 
 pub struct AppState {
@@ -32,6 +78,8 @@ pub struct AppState {
     pub list_state: ListState,
     pub cal_state: ListState,
     pub active_focus: Focus,
+    /// Lines scrolled in the details pane, kept while it has the focus
+    pub details_scroll: u16,
     pub mode: InputMode,
     pub message: String,
     pub loading: bool,
@@ -46,6 +94,37 @@ pub struct AppState {
     pub hide_completed: bool,
     pub hide_fully_completed_tags: bool,
     pub sort_cutoff_months: Option<u32>,
+    pub completed_retention_days: Option<u32>,
+    pub review_after_weeks: u32,
+    /// Tasks left in the review (Reviewing mode), current one first
+    pub review_queue: Vec<String>,
+    pub review_total: usize,
+    /// Calendar name or href quick-added tasks go to (`inbox_calendar`)
+    pub inbox_calendar: Option<String>,
+    /// Inbox tasks left in the triage (Triaging modes), current one first
+    pub triage_queue: Vec<String>,
+    pub triage_total: usize,
+    pub triage_filed: usize,
+    /// The task being triaged with the tags and dates typed, until its calendar is picked
+    pub triage_edit: Option<Task>,
+    /// Queued actions shown in PreviewingSync mode
+    pub pending_changes: Vec<PendingChange>,
+    pub pending_state: ListState,
+    /// Requests shown in ViewingWireLog mode, newest first
+    pub wire_entries: Vec<WireEntry>,
+    pub wire_state: ListState,
+    /// Session-only override of the retention window ('L')
+    pub show_all_completed: bool,
+    /// Sections of the agenda view ('3'), rebuilt with the task list
+    pub agenda: Vec<AgendaDay>,
+    /// Open tasks and estimates per calendar, for the sidebar
+    pub calendar_stats: HashMap<String, CalendarStats>,
+    /// Grouping of the task list ('g'); `rows` stays empty when there is none
+    pub group_by: GroupBy,
+    pub collapsed_groups: HashSet<String>,
+    pub rows: Vec<ListRow>,
+    /// Task and list offset of the restored session, applied once the tasks are loaded
+    pub restore_selection: Option<(String, usize)>,
 
     // Input Buffers
     pub input_buffer: String,
@@ -53,16 +132,43 @@ pub struct AppState {
     pub editing_index: Option<usize>,
     pub move_selection_state: ListState,
     pub move_targets: Vec<CalendarListEntry>,
+    /// Whether the pending duplicate (Duplicating mode) includes the subtasks.
+    pub duplicate_with_subtree: bool,
+    pub move_subtree: bool,
+    pub new_task_placement: NewTaskPlacement,
+    pub due_display: DueDisplay,
+    pub due_soon_days: u32,
+    pub not_started: NotStarted,
+    /// The list shows the next actions instead of the tree (not in the agenda)
+    pub next_actions: bool,
+    pub tui_columns: Vec<TuiColumn>,
     pub export_selection_state: ListState,
     pub export_targets: Vec<CalendarListEntry>,
+    pub recurrence_step: RecurrenceStep,
+    pub recurrence_draft: Recurrence,
 
     pub yanked_uid: Option<String>,
     pub creating_child_of: Option<String>,
+    /// The text typed in `Creating` is the title of a new note (VJOURNAL)
+    pub creating_note: bool,
     pub show_full_help: bool,
     pub tag_aliases: HashMap<String, Vec<String>>,
+    pub alias_selection_state: ListState,
+    /// Alias being edited in EditingAlias mode (None = adding a new one)
+    pub alias_editing: Option<String>,
+    pub calendar_defaults: HashMap<String, CalendarDefaults>,
+    pub templates: Vec<TaskTemplate>,
 
     // Track unsynced status
-    pub unsynced_changes: bool,
+    pub sync_status: SyncStatus,
+    /// Last successful sync of each calendar, by href
+    pub sync_times: HashMap<String, DateTime<Utc>>,
+    /// Calendars the last sync could not fetch, "Name: error"
+    pub fetch_failures: Vec<String>,
+    /// Closed by the user, shown again when the failures change
+    pub fetch_failures_dismissed: bool,
+    /// Symbols the terminal can display
+    pub glyphs: &'static Glyphs,
 }
 
 impl Default for AppState {
@@ -85,6 +191,7 @@ impl AppState {
             list_state: l_state,
             cal_state: c_state,
             active_focus: Focus::Main,
+            details_scroll: 0,
             mode: InputMode::Normal,
             message: "Loading...".to_string(),
             loading: true,
@@ -98,29 +205,103 @@ impl AppState {
             hide_completed: false,
             hide_fully_completed_tags: false,
             sort_cutoff_months: Some(6),
+            completed_retention_days: None,
+            review_after_weeks: 4,
+            review_queue: Vec::new(),
+            review_total: 0,
+            inbox_calendar: None,
+            triage_queue: Vec::new(),
+            triage_total: 0,
+            triage_filed: 0,
+            triage_edit: None,
+            pending_changes: Vec::new(),
+            pending_state: ListState::default(),
+            wire_entries: Vec::new(),
+            wire_state: ListState::default(),
+            show_all_completed: false,
+            agenda: Vec::new(),
+            calendar_stats: HashMap::new(),
+            group_by: GroupBy::None,
+            collapsed_groups: HashSet::new(),
+            rows: Vec::new(),
+            restore_selection: None,
 
             input_buffer: String::new(),
             cursor_position: 0,
             editing_index: None,
             move_selection_state: ListState::default(),
             move_targets: Vec::new(),
+            duplicate_with_subtree: false,
+            move_subtree: true,
+            new_task_placement: NewTaskPlacement::Sorted,
+            due_display: DueDisplay::Relative,
+            due_soon_days: 3,
+            not_started: NotStarted::Show,
+            next_actions: false,
+            tui_columns: DEFAULT_TUI_COLUMNS.to_vec(),
             yanked_uid: None,
             creating_child_of: None,
+            creating_note: false,
             show_full_help: false,
 
             tag_aliases: HashMap::new(),
+            alias_selection_state: ListState::default(),
+            alias_editing: None,
+            calendar_defaults: HashMap::new(),
+            templates: Vec::new(),
             export_selection_state: ListState::default(),
             export_targets: Vec::new(),
+            recurrence_step: RecurrenceStep::Frequency,
+            recurrence_draft: Recurrence::default(),
 
-            unsynced_changes: false, // Default false
+            sync_status: SyncStatus::default(),
+            sync_times: HashMap::new(),
+            fetch_failures: Vec::new(),
+            fetch_failures_dismissed: false,
+            glyphs: Glyphs::detect(),
         }
     }
 
+    /// Calendars in sidebar order: synced ones first, then those with sync turned off.
     pub fn get_filtered_calendars(&self) -> Vec<&CalendarListEntry> {
+        let (mut synced, disabled): (Vec<_>, Vec<_>) = self
+            .calendars
+            .iter()
+            .partition(|c| !self.disabled_calendars.contains(&c.href));
+        synced.extend(disabled);
+        synced
+    }
+
+    /// Name of the calendar if it is read-only, so callers can explain why an edit was refused.
+    pub fn read_only_calendar_name(&self, href: &str) -> Option<String> {
         self.calendars
             .iter()
-            .filter(|c| !self.disabled_calendars.contains(&c.href))
-            .collect()
+            .find(|c| c.href == href && c.is_read_only)
+            .map(|c| c.name.clone())
+    }
+
+    /// The popup listing the calendars the last sync could not fetch is open.
+    pub fn shows_fetch_failures(&self) -> bool {
+        !self.fetch_failures.is_empty() && !self.fetch_failures_dismissed
+    }
+
+    /// Whether tasks of `href` can be changed; if not, the status line says why. Checked
+    /// where a key would change the store, before it does.
+    pub fn check_writable(&mut self, href: &str) -> bool {
+        match self.read_only_calendar_name(href) {
+            Some(name) => {
+                self.message = tr_args("error.read_only", &[("name", &name)]);
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// UID of the selected task, when its calendar can be changed (see `check_writable`).
+    pub fn writable_selected_uid(&mut self) -> Option<String> {
+        let task = self.get_selected_task()?;
+        let (uid, href) = (task.uid.clone(), task.calendar_href.clone());
+        self.check_writable(&href).then_some(uid)
     }
 
     pub fn refresh_filtered_view(&mut self) {
@@ -140,24 +321,66 @@ impl AppState {
             None
         };
 
+        let completed_cutoff = self
+            .completed_retention_days
+            .filter(|_| !self.show_all_completed)
+            .map(|days| chrono::Utc::now() - chrono::Duration::days(days as i64));
+
         let mut effective_hidden = self.hidden_calendars.clone();
         effective_hidden.extend(self.disabled_calendars.clone());
+        let names: HashMap<String, String> = self
+            .calendars
+            .iter()
+            .map(|c| (c.href.clone(), c.name.clone()))
+            .collect();
 
-        self.tasks = self.store.filter(FilterOptions {
+        let options = FilterOptions {
             active_cal_href: cal_filter,
             selected_categories: &self.selected_categories,
             match_all_categories: self.match_all_categories,
             hidden_calendars: &effective_hidden,
             search_term,
+            calendar_names: &names,
             hide_completed_global: self.hide_completed,
             cutoff_date,
             // TUI currently doesn't implement duration filtering UI, so we disable it
             min_duration: None,
             max_duration: None,
             include_unset_duration: true,
-        });
+            completed_cutoff,
+            fold_subtasks: search_term.is_empty(),
+            quick_filters: &[],
+            hide_not_started: self.not_started == NotStarted::Hide,
+        };
+        self.calendar_stats = self.store.calendar_stats(options);
+        self.tasks = if self.sidebar_mode == SidebarMode::Agenda {
+            self.agenda = self
+                .store
+                .agenda(options, Local::now().date_naive(), AGENDA_DAYS);
+            let day = self.cal_state.selected().unwrap_or(0);
+            self.agenda
+                .get(day)
+                .map(|d| d.tasks.clone())
+                .unwrap_or_default()
+        } else if self.next_actions {
+            self.store.next_actions(options, chrono::Utc::now())
+        } else {
+            self.store.filter(options)
+        };
+
+        // The agenda is already split by day
+        self.rows.clear();
+        if self.group_by != GroupBy::None && self.sidebar_mode != SidebarMode::Agenda {
+            let groups = group_tasks(
+                std::mem::take(&mut self.tasks),
+                self.group_by,
+                &names,
+                Local::now().date_naive(),
+            );
+            (self.tasks, self.rows) = flatten_groups(groups, &self.collapsed_groups);
+        }
 
-        let len = self.tasks.len();
+        let len = self.list_len();
         if len == 0 {
             self.list_state.select(None);
         } else {
@@ -171,13 +394,59 @@ impl AppState {
     }
 
     pub fn get_selected_task(&self) -> Option<&Task> {
-        if let Some(idx) = self.list_state.selected() {
-            self.tasks.get(idx)
+        self.selected_task_index()
+            .and_then(|idx| self.tasks.get(idx))
+    }
+
+    /// Moves the selection to the task `uid` if it is listed.
+    pub fn select_task(&mut self, uid: &str) -> bool {
+        let Some(idx) = self.tasks.iter().position(|t| t.uid == uid) else {
+            return false;
+        };
+        let line = if self.rows.is_empty() {
+            Some(idx)
         } else {
-            None
+            self.rows.iter().position(|r| *r == ListRow::Task(idx))
+        };
+        self.list_state.select(line.or(self.list_state.selected()));
+        line.is_some()
+    }
+
+    /// Lines in the task list: tasks, plus the group headers when grouped.
+    pub fn list_len(&self) -> usize {
+        if self.rows.is_empty() {
+            self.tasks.len()
+        } else {
+            self.rows.len()
         }
     }
 
+    /// Index into `tasks` of the selected line; None on a group header.
+    pub fn selected_task_index(&self) -> Option<usize> {
+        let idx = self.list_state.selected()?;
+        if self.rows.is_empty() {
+            return Some(idx);
+        }
+        match self.rows.get(idx)? {
+            ListRow::Task(i) => Some(*i),
+            ListRow::Group { .. } => None,
+        }
+    }
+
+    /// Collapses or expands the group whose header is selected. Returns false elsewhere.
+    pub fn toggle_selected_group(&mut self) -> bool {
+        let Some(ListRow::Group { key, .. }) =
+            self.list_state.selected().and_then(|i| self.rows.get(i))
+        else {
+            return false;
+        };
+        if !self.collapsed_groups.remove(key) {
+            self.collapsed_groups.insert(key.clone());
+        }
+        self.refresh_filtered_view();
+        true
+    }
+
     // --- INPUT HELPERS ---
     pub fn move_cursor_left(&mut self) {
         let cursor_moved_left = self.cursor_position.saturating_sub(1);
@@ -200,6 +469,10 @@ impl AppState {
             self.move_cursor_left();
         }
     }
+    pub fn set_input(&mut self, value: String) {
+        self.cursor_position = value.chars().count();
+        self.input_buffer = value;
+    }
     pub fn reset_input(&mut self) {
         self.input_buffer.clear();
         self.cursor_position = 0;
@@ -211,11 +484,7 @@ impl AppState {
     // --- HELPER FOR SIDEBAR LENGTH ---
     fn get_sidebar_len(&self) -> usize {
         match self.sidebar_mode {
-            SidebarMode::Calendars => self
-                .calendars
-                .iter()
-                .filter(|c| !self.disabled_calendars.contains(&c.href))
-                .count(),
+            SidebarMode::Calendars => self.calendars.len(),
             SidebarMode::Categories => self
                 .store
                 .get_all_categories(
@@ -225,6 +494,14 @@ impl AppState {
                     &self.hidden_calendars,
                 )
                 .len(),
+            SidebarMode::Agenda => self.agenda.len(),
+        }
+    }
+
+    /// In the agenda, moving through the sidebar switches the day shown.
+    fn sidebar_moved(&mut self) {
+        if self.sidebar_mode == SidebarMode::Agenda {
+            self.refresh_filtered_view();
         }
     }
 
@@ -232,12 +509,13 @@ impl AppState {
     pub fn next(&mut self) {
         match self.active_focus {
             Focus::Main => {
-                if self.tasks.is_empty() {
+                let len = self.list_len();
+                if len == 0 {
                     return;
                 }
                 let i = match self.list_state.selected() {
                     Some(i) => {
-                        if i >= self.tasks.len() - 1 {
+                        if i >= len - 1 {
                             0
                         } else {
                             i + 1
@@ -263,19 +541,22 @@ impl AppState {
                     None => 0,
                 };
                 self.cal_state.select(Some(i));
+                self.sidebar_moved();
             }
+            Focus::Details => self.details_scroll = self.details_scroll.saturating_add(1),
         }
     }
     pub fn previous(&mut self) {
         match self.active_focus {
             Focus::Main => {
-                if self.tasks.is_empty() {
+                let len = self.list_len();
+                if len == 0 {
                     return;
                 }
                 let i = match self.list_state.selected() {
                     Some(i) => {
                         if i == 0 {
-                            self.tasks.len() - 1
+                            len - 1
                         } else {
                             i - 1
                         }
@@ -300,16 +581,18 @@ impl AppState {
                     None => 0,
                 };
                 self.cal_state.select(Some(i));
+                self.sidebar_moved();
             }
+            Focus::Details => self.details_scroll = self.details_scroll.saturating_sub(1),
         }
     }
     pub fn jump_forward(&mut self, step: usize) {
         match self.active_focus {
             Focus::Main => {
-                if !self.tasks.is_empty() {
+                let len = self.list_len();
+                if len > 0 {
                     let current = self.list_state.selected().unwrap_or(0);
-                    self.list_state
-                        .select(Some((current + step).min(self.tasks.len() - 1)));
+                    self.list_state.select(Some((current + step).min(len - 1)));
                 }
             }
             Focus::Sidebar => {
@@ -317,14 +600,18 @@ impl AppState {
                 if len > 0 {
                     let current = self.cal_state.selected().unwrap_or(0);
                     self.cal_state.select(Some((current + step).min(len - 1)));
+                    self.sidebar_moved();
                 }
             }
+            Focus::Details => {
+                self.details_scroll = self.details_scroll.saturating_add(step as u16);
+            }
         }
     }
     pub fn jump_backward(&mut self, step: usize) {
         match self.active_focus {
             Focus::Main => {
-                if !self.tasks.is_empty() {
+                if self.list_len() > 0 {
                     let current = self.list_state.selected().unwrap_or(0);
                     self.list_state.select(Some(current.saturating_sub(step)));
                 }
@@ -334,16 +621,42 @@ impl AppState {
                 if len > 0 {
                     let current = self.cal_state.selected().unwrap_or(0);
                     self.cal_state.select(Some(current.saturating_sub(step)));
+                    self.sidebar_moved();
                 }
             }
+            Focus::Details => {
+                self.details_scroll = self.details_scroll.saturating_sub(step as u16);
+            }
         }
     }
     pub fn toggle_focus(&mut self) {
         self.active_focus = match self.active_focus {
             Focus::Main => Focus::Sidebar,
-            Focus::Sidebar => Focus::Main,
+            Focus::Sidebar | Focus::Details => Focus::Main,
+        };
+        self.details_scroll = 0;
+    }
+    /// Names in the status bar the tasks that finishing `uid` unblocked, if any.
+    pub fn announce_unblocked(&mut self, uid: &str) {
+        let freed: Vec<String> = self
+            .store
+            .unblocked_by(uid)
+            .into_iter()
+            .map(|t| t.summary)
+            .collect();
+        if !freed.is_empty() {
+            self.message = format!("Unblocked: {}", freed.join(", "));
         }
     }
+    /// Moves the focus to the details pane of the selected task, or back to the list.
+    pub fn toggle_details_focus(&mut self) {
+        self.active_focus = if self.active_focus == Focus::Details {
+            Focus::Main
+        } else {
+            Focus::Details
+        };
+        self.details_scroll = 0;
+    }
     pub fn next_move_target(&mut self) {
         if self.move_targets.is_empty() {
             return;
@@ -410,11 +723,181 @@ impl AppState {
         };
         self.export_selection_state.select(Some(i));
     }
+
+    /// Queues the open tasks not modified for `review_after_weeks` in the visible, writable
+    /// calendars. Returns how many there are.
+    pub fn start_review(&mut self) -> usize {
+        let mut excluded = self.hidden_calendars.clone();
+        excluded.extend(self.disabled_calendars.iter().cloned());
+        excluded.extend(
+            self.calendars
+                .iter()
+                .filter(|c| c.is_read_only)
+                .map(|c| c.href.clone()),
+        );
+        self.review_queue = self.store.review_queue(&excluded, self.review_after_weeks);
+        self.review_total = self.review_queue.len();
+        self.review_total
+    }
+
+    /// The task under review; tasks deleted meanwhile (e.g. by a sync) are skipped.
+    pub fn review_task(&mut self) -> Option<&Task> {
+        while let Some(uid) = self.review_queue.first() {
+            if self.store.get_task(uid).is_some() {
+                break;
+            }
+            self.review_queue.remove(0);
+        }
+        self.store.get_task(self.review_queue.first()?)
+    }
+
+    /// The calendar new top-level tasks go to, when an existing inbox is set.
+    pub fn inbox_href(&self) -> Option<String> {
+        inbox::inbox_href(self.inbox_calendar.as_deref(), &self.calendars)
+    }
+
+    /// Queues the open tasks of the inbox. Returns how many there are.
+    pub fn start_triage(&mut self) -> usize {
+        self.triage_queue = self
+            .inbox_href()
+            .and_then(|href| self.store.calendars.get(&href))
+            .map(|tasks| inbox::triage_queue(tasks))
+            .unwrap_or_default();
+        self.triage_total = self.triage_queue.len();
+        self.triage_filed = 0;
+        self.triage_edit = None;
+        self.triage_total
+    }
+
+    /// The task being triaged; tasks gone from the inbox meanwhile (e.g. by a sync) are
+    /// skipped.
+    pub fn triage_task(&mut self) -> Option<&Task> {
+        let inbox = self.inbox_href();
+        while let Some(uid) = self.triage_queue.first() {
+            if self
+                .store
+                .get_task(uid)
+                .is_some_and(|t| inbox.as_ref() == Some(&t.calendar_href))
+            {
+                break;
+            }
+            self.triage_queue.remove(0);
+        }
+        self.store.get_task(self.triage_queue.first()?)
+    }
+
+    // --- SESSION ---
+    pub fn session(&self) -> Session {
+        Session {
+            active_calendar: self.active_cal_href.clone(),
+            selected_task: self.get_selected_task().map(|t| t.uid.clone()),
+            sidebar: match self.sidebar_mode {
+                SidebarMode::Calendars => SidebarView::Calendars,
+                SidebarMode::Categories => SidebarView::Tags,
+                SidebarMode::Agenda => SidebarView::Agenda,
+            },
+            selected_tags: self.selected_categories.iter().cloned().collect(),
+            match_all_tags: self.match_all_categories,
+            group_by: self.group_by,
+            collapsed_groups: self.collapsed_groups.iter().cloned().collect(),
+            list_offset: self.list_state.offset(),
+            sidebar_selected: self.cal_state.selected().unwrap_or(0),
+            ..Default::default()
+        }
+    }
+
+    /// Puts back the filters of a saved session. The active calendar is checked once the
+    /// calendars are known, the selection once the tasks are (`apply_restored_selection`).
+    pub fn restore_session(&mut self, session: Session) {
+        self.active_cal_href = session.active_calendar;
+        self.sidebar_mode = match session.sidebar {
+            SidebarView::Calendars => SidebarMode::Calendars,
+            SidebarView::Tags => SidebarMode::Categories,
+            SidebarView::Agenda => SidebarMode::Agenda,
+        };
+        self.cal_state.select(Some(session.sidebar_selected));
+        self.selected_categories = session.selected_tags.into_iter().collect();
+        self.match_all_categories = session.match_all_tags;
+        self.group_by = session.group_by;
+        self.collapsed_groups = session.collapsed_groups.into_iter().collect();
+        self.restore_selection = session.selected_task.map(|uid| (uid, session.list_offset));
+    }
+
+    pub fn apply_restored_selection(&mut self) {
+        if let Some((uid, offset)) = self.restore_selection.take()
+            && self.select_task(&uid)
+        {
+            *self.list_state.offset_mut() = offset;
+        }
+    }
+
+    /// Alias names in the order of the alias popup.
+    pub fn alias_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.tag_aliases.keys().cloned().collect();
+        keys.sort_unstable_by_key(|k| k.to_lowercase());
+        keys
+    }
+
+    pub fn selected_alias(&self) -> Option<String> {
+        let idx = self.alias_selection_state.selected()?;
+        self.alias_keys().into_iter().nth(idx)
+    }
+
+    /// Re-reads the offline queue for the sync preview, keeping the selection in range.
+    pub fn load_pending_changes(&mut self) -> usize {
+        self.pending_changes = Journal::load().preview();
+        let len = self.pending_changes.len();
+        let selected = self.pending_state.selected().unwrap_or(0);
+        self.pending_state
+            .select((len > 0).then(|| selected.min(len - 1)));
+        len
+    }
+
+    pub fn step_pending_selection(&mut self, delta: isize) {
+        let len = self.pending_changes.len();
+        if len == 0 {
+            return;
+        }
+        let i = self.pending_state.selected().unwrap_or(0) as isize + delta;
+        self.pending_state
+            .select(Some(i.rem_euclid(len as isize) as usize));
+    }
+
+    /// Re-reads the wire log, newest first, and selects the newest request.
+    pub fn load_wire_entries(&mut self) -> usize {
+        self.wire_entries = wirelog::recent().into_iter().rev().collect();
+        let len = self.wire_entries.len();
+        self.wire_state.select((len > 0).then_some(0));
+        len
+    }
+
+    pub fn step_wire_selection(&mut self, delta: isize) {
+        let len = self.wire_entries.len();
+        if len == 0 {
+            return;
+        }
+        let i = self.wire_state.selected().unwrap_or(0) as isize + delta;
+        self.wire_state
+            .select(Some(i.rem_euclid(len as isize) as usize));
+    }
+
+    /// Moves the alias popup selection by `delta`, wrapping around.
+    pub fn step_alias_selection(&mut self, delta: isize) {
+        let len = self.tag_aliases.len();
+        if len == 0 {
+            self.alias_selection_state.select(None);
+            return;
+        }
+        let i = self.alias_selection_state.selected().unwrap_or(0) as isize + delta;
+        self.alias_selection_state
+            .select(Some(i.rem_euclid(len as isize) as usize));
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::Priority;
     use std::collections::HashMap;
 
     fn dummy_task() -> Task {
@@ -466,6 +949,110 @@ mod tests {
         // Selection should stay None or safe default, but definitely no panic
     }
 
+    #[test]
+    fn test_details_focus_scrolls() {
+        let mut state = AppState::new();
+        state.tasks = vec![dummy_task(), dummy_task()];
+        state.list_state.select(Some(0));
+
+        state.toggle_details_focus();
+        state.next();
+        state.jump_forward(10);
+        state.previous();
+        // The pane scrolls while the selection stays put
+        assert_eq!(state.details_scroll, 10);
+        assert_eq!(state.list_state.selected(), Some(0));
+
+        state.toggle_details_focus();
+        assert!(state.active_focus == Focus::Main);
+        assert_eq!(state.details_scroll, 0);
+    }
+
+    #[test]
+    fn test_read_only_calendar_is_not_writable() {
+        let mut state = AppState::new();
+        state.calendars = vec![CalendarListEntry {
+            name: "Team".to_string(),
+            href: "/cal/team/".to_string(),
+            color: None,
+            is_shared: true,
+            is_read_only: true,
+        }];
+        let mut task = dummy_task();
+        task.calendar_href = "/cal/team/".to_string();
+        state.tasks = vec![task, dummy_task()];
+
+        state.list_state.select(Some(0));
+        assert_eq!(state.writable_selected_uid(), None);
+        assert!(state.message.contains("Team"));
+
+        state.list_state.select(Some(1));
+        assert_eq!(
+            state.writable_selected_uid(),
+            Some(state.tasks[1].uid.clone())
+        );
+    }
+
+    #[test]
+    fn test_grouped_list_headers_and_collapse() {
+        let mut state = AppState::new();
+        for (name, prio) in [("Urgent", 1), ("Whenever", 9), ("Also urgent", 2)] {
+            let mut t = Task::new(name, &HashMap::new());
+            t.calendar_href = "/cal/a/".to_string();
+            t.priority = Priority::from(prio);
+            state.store.add_task(t);
+        }
+        state.group_by = GroupBy::Priority;
+        state.refresh_filtered_view();
+        // High header, 2 tasks, Low header, 1 task
+        assert_eq!(state.list_len(), 5);
+        state.list_state.select(Some(0));
+        assert!(state.get_selected_task().is_none());
+
+        state.next();
+        assert_eq!(
+            state.get_selected_task().map(|t| t.priority),
+            Some(Priority::HIGH)
+        );
+
+        state.list_state.select(Some(0));
+        assert!(state.toggle_selected_group());
+        assert_eq!(state.list_len(), 3);
+        assert_eq!(state.tasks.len(), 1);
+        state.jump_forward(2);
+        assert!(!state.toggle_selected_group());
+    }
+
+    #[test]
+    fn test_session_restores_grouping_and_selection() {
+        let mut state = AppState::new();
+        for (name, prio) in [("Urgent", 1), ("Whenever", 9), ("Later", 9)] {
+            let mut t = Task::new(name, &HashMap::new());
+            t.calendar_href = "/cal/a/".to_string();
+            t.priority = Priority::from(prio);
+            state.store.add_task(t);
+        }
+        state.group_by = GroupBy::Priority;
+        state.collapsed_groups.insert("high".to_string());
+        state.refresh_filtered_view();
+        state.list_state.select(Some(3));
+        let saved = state.session();
+        assert_eq!(
+            saved.selected_task.as_deref(),
+            Some(state.tasks[1].uid.as_str())
+        );
+
+        let mut restored = AppState::new();
+        restored.store = std::mem::take(&mut state.store);
+        restored.restore_session(saved);
+        restored.refresh_filtered_view();
+        restored.apply_restored_selection();
+        assert_eq!(restored.group_by, GroupBy::Priority);
+        assert_eq!(restored.list_len(), 4);
+        assert_eq!(restored.list_state.selected(), Some(3));
+        assert!(restored.restore_selection.is_none());
+    }
+
     #[test]
     fn test_cursor_clamping() {
         let mut state = AppState::new();
@@ -486,4 +1073,22 @@ mod tests {
 
         assert_eq!(state.cursor_position, 0);
     }
+
+    #[test]
+    fn test_alias_selection_is_sorted_and_wraps() {
+        let mut state = AppState::new();
+        state.step_alias_selection(1);
+        assert_eq!(state.selected_alias(), None);
+
+        state.tag_aliases = HashMap::from([
+            ("work".to_string(), vec!["job".to_string()]),
+            ("Home".to_string(), vec!["personal".to_string()]),
+        ]);
+        state.step_alias_selection(0);
+        assert_eq!(state.selected_alias().as_deref(), Some("Home"));
+        state.step_alias_selection(-1);
+        assert_eq!(state.selected_alias().as_deref(), Some("work"));
+        state.step_alias_selection(1);
+        assert_eq!(state.selected_alias().as_deref(), Some("Home"));
+    }
 }
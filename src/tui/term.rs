@@ -0,0 +1,207 @@
+// File: ./src/tui/term.rs
+// What differs between terminals: setup and teardown, the symbols drawn and how keys arrive.
+// `view::draw` only needs a ratatui `Frame`, so tests render it into a `TestBackend`.
+use anyhow::Result;
+use crossterm::{
+    cursor::Show,
+    event::{
+        DisableMouseCapture, EnableMouseCapture, KeyCode, KeyEvent, KeyEventKind, KeyModifiers,
+    },
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use ratatui::{
+    Terminal,
+    backend::CrosstermBackend,
+    symbols::{border, scrollbar},
+};
+use std::env;
+use std::io::{self, Stdout};
+
+/// "1" draws with ASCII only, "0" with Unicode, whatever the terminal looks like.
+pub const ASCII_ENV_VAR: &str = "CFAIT_ASCII";
+
+pub type Tui = Terminal<CrosstermBackend<Stdout>>;
+
+pub struct Glyphs {
+    pub border: border::Set,
+    pub collapsed: &'static str,
+    pub expanded: &'static str,
+    pub description: &'static str,
+    pub defaults: &'static str,
+    pub recurrence: &'static str,
+    pub arrow: &'static str,
+    pub ellipsis: &'static str,
+    pub scrollbar: scrollbar::Set,
+}
+
+pub const UNICODE: Glyphs = Glyphs {
+    border: border::PLAIN,
+    collapsed: "▸",
+    expanded: "▾",
+    description: "📝 ",
+    defaults: "⚙ ",
+    recurrence: "↻ ",
+    arrow: "→",
+    ellipsis: "…",
+    scrollbar: scrollbar::VERTICAL,
+};
+
+pub const ASCII: Glyphs = Glyphs {
+    border: border::Set {
+        top_left: "+",
+        top_right: "+",
+        bottom_left: "+",
+        bottom_right: "+",
+        vertical_left: "|",
+        vertical_right: "|",
+        horizontal_top: "-",
+        horizontal_bottom: "-",
+    },
+    collapsed: ">",
+    expanded: "v",
+    description: "= ",
+    defaults: "* ",
+    recurrence: "o ",
+    arrow: "->",
+    ellipsis: ".",
+    scrollbar: scrollbar::Set {
+        track: "|",
+        thumb: "#",
+        begin: "^",
+        end: "v",
+    },
+};
+
+impl Glyphs {
+    /// ASCII in the legacy Windows console, whose fonts lack emoji and most symbols; Unicode in
+    /// Windows Terminal, VS Code, ConEmu and on every other platform.
+    pub fn detect() -> &'static Glyphs {
+        match env::var(ASCII_ENV_VAR).as_deref() {
+            Ok("1") => return &ASCII,
+            Ok("0") => return &UNICODE,
+            _ => {}
+        }
+        let modern_console = ["WT_SESSION", "TERM_PROGRAM", "ConEmuANSI"]
+            .iter()
+            .any(|var| env::var_os(var).is_some());
+        if cfg!(windows) && !modern_console {
+            &ASCII
+        } else {
+            &UNICODE
+        }
+    }
+}
+
+pub fn setup() -> Result<Tui> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    Ok(Terminal::new(CrosstermBackend::new(stdout))?)
+}
+
+/// Gives the terminal back to the shell. Needs no `Tui`, so the panic hook can call it too.
+pub fn restore() -> Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        Show
+    )?;
+    Ok(())
+}
+
+/// The Windows console reports key releases as well as presses; only presses (and their
+/// repeats) are input, or every key would act twice.
+pub fn is_input(key: &KeyEvent) -> bool {
+    key.kind != KeyEventKind::Release
+}
+
+/// A line break in multi-line input. Alt+Enter is taken by the Windows console (full screen)
+/// and Shift+Enter often arrives as a plain Enter there, so Ctrl+Enter works as well; Unix
+/// terminals send that one as Ctrl+J.
+pub fn is_newline(key: &KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Enter => key
+            .modifiers
+            .intersects(KeyModifiers::ALT | KeyModifiers::SHIFT | KeyModifiers::CONTROL),
+        KeyCode::Char('j') => key.modifiers.contains(KeyModifiers::CONTROL),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n;
+    use crate::model::Task;
+    use crate::store::GroupBy;
+    use crate::tui::state::AppState;
+    use crate::tui::view::draw;
+    use crossterm::event::KeyEventState;
+    use ratatui::backend::TestBackend;
+    use std::collections::HashMap;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers, kind: KeyEventKind) -> KeyEvent {
+        KeyEvent {
+            code,
+            modifiers,
+            kind,
+            state: KeyEventState::NONE,
+        }
+    }
+
+    #[test]
+    fn test_windows_key_events() {
+        let press = key(KeyCode::Char('a'), KeyModifiers::NONE, KeyEventKind::Press);
+        let release = key(
+            KeyCode::Char('a'),
+            KeyModifiers::NONE,
+            KeyEventKind::Release,
+        );
+        assert!(is_input(&press));
+        assert!(!is_input(&release));
+
+        let enter = |modifiers| key(KeyCode::Enter, modifiers, KeyEventKind::Press);
+        assert!(!is_newline(&enter(KeyModifiers::NONE)));
+        assert!(is_newline(&enter(KeyModifiers::CONTROL)));
+        assert!(is_newline(&enter(KeyModifiers::SHIFT)));
+        let ctrl_j = key(
+            KeyCode::Char('j'),
+            KeyModifiers::CONTROL,
+            KeyEventKind::Press,
+        );
+        assert!(is_newline(&ctrl_j));
+        // AltGr, as Windows reports it, types characters
+        let alt_gr = key(
+            KeyCode::Char('@'),
+            KeyModifiers::CONTROL | KeyModifiers::ALT,
+            KeyEventKind::Press,
+        );
+        assert!(!is_newline(&alt_gr));
+    }
+
+    #[test]
+    fn test_ascii_glyphs_draw_only_ascii() {
+        i18n::set_language(Some("en"));
+        let mut state = AppState::new();
+        state.glyphs = &ASCII;
+        state.loading = false;
+        for name in ["Water plants", "Call Bob"] {
+            let mut t = Task::new(name, &HashMap::new());
+            t.calendar_href = "/cal/a/".to_string();
+            state.store.add_task(t);
+        }
+        state.group_by = GroupBy::Priority;
+        state.refresh_filtered_view();
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|f| draw(f, &mut state)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let text: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("Water plants"));
+        assert!(text.contains("+--"));
+        assert!(text.is_ascii(), "non-ASCII output: {}", text);
+    }
+}
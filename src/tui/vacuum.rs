@@ -0,0 +1,89 @@
+// File: ./src/tui/vacuum.rs
+// `cfait vacuum`: drops old done tasks from the local copies and rewrites them, along with
+// deleting the copies of calendars that no longer exist
+use crate::backup;
+use crate::cache::{self, Cache};
+use crate::config::Config;
+use crate::paths::AppPaths;
+use crate::storage::{LOCAL_CALENDAR_HREF, LocalStorage};
+use anyhow::{Result, anyhow};
+use std::fs;
+
+pub const USAGE: &str = "cfait vacuum [--days <N>]   (drop old done tasks from local storage)";
+
+/// Bytes taken by the mirror and the local tasks.
+fn disk_usage() -> u64 {
+    let cache_files = AppPaths::get_cache_dir()
+        .ok()
+        .and_then(|dir| fs::read_dir(dir).ok())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|meta| meta.len());
+    let local = LocalStorage::get_path()
+        .and_then(|path| fs::metadata(path).ok())
+        .map(|meta| meta.len());
+    cache_files.chain(local).sum()
+}
+
+pub fn run(args: &[String]) -> Result<()> {
+    let mut days = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--days" => {
+                days = Some(
+                    iter.next()
+                        .and_then(|v| v.parse().ok())
+                        .ok_or_else(|| anyhow!("--days needs a number\nUsage: {}", USAGE))?,
+                );
+            }
+            "--config" => {
+                iter.next(); // Already applied by AppPaths::init_from_args
+            }
+            other => return Err(anyhow!("Unknown argument: {}\nUsage: {}", other, USAGE)),
+        }
+    }
+    let days = days.or(Config::load().ok().and_then(|c| c.prune_completed_days));
+
+    let before = disk_usage();
+    let name = backup::snapshot(Some("vacuum"))?;
+    let calendars = Cache::load_calendars()?;
+    let mut dropped = 0;
+    for cal in calendars.iter().filter(|c| c.href != LOCAL_CALENDAR_HREF) {
+        let (tasks, token) = Cache::load(&cal.href)?;
+        let mut pruned = Cache::load_pruned(&cal.href);
+        if tasks.is_empty() && pruned.is_empty() {
+            continue;
+        }
+        let count = tasks.len();
+        let kept = cache::apply_pruning(tasks, days, &mut pruned);
+        dropped += count - kept.len();
+        // Rewritten even when nothing goes, which also upgrades older formats
+        Cache::save(&cal.href, &kept, token)?;
+        Cache::save_pruned(&cal.href, &pruned)?;
+    }
+    if let Some(days) = days {
+        dropped += LocalStorage::prune(days)?;
+    } else if LocalStorage::get_path().is_some_and(|p| p.exists()) {
+        LocalStorage::save(&LocalStorage::load()?)?;
+    }
+    let hrefs: Vec<String> = calendars.into_iter().map(|c| c.href).collect();
+    let orphans = Cache::remove_orphans(&hrefs)?;
+    let after = disk_usage();
+
+    if days.is_none() {
+        println!("No prune_completed_days in the config (or --days): no tasks dropped.");
+    }
+    println!(
+        "Dropped {} done tasks and {} files of removed calendars: {} KiB -> {} KiB \
+         (backed up as {}).",
+        dropped,
+        orphans,
+        before / 1024,
+        after / 1024,
+        name
+    );
+    Ok(())
+}
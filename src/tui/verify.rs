@@ -0,0 +1,91 @@
+// File: ./src/tui/verify.rs
+// `cfait verify`: compares the local copy of the calendars with the server and optionally
+// repairs it, e.g. when deleted tasks keep coming back
+use crate::client::RustyClient;
+use crate::config::Config;
+use crate::storage::LOCAL_CALENDAR_HREF;
+use anyhow::{Result, anyhow};
+
+pub const USAGE: &str =
+    "cfait verify [--calendar <name|href>] [--sample <N>] [--repair]   (check the local copy)";
+
+pub async fn run(args: &[String]) -> Result<()> {
+    let mut calendar = None;
+    let mut sample = None;
+    let mut repair = false;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--calendar" => {
+                calendar = Some(
+                    iter.next()
+                        .ok_or_else(|| anyhow!("--calendar needs a name\nUsage: {}", USAGE))?,
+                );
+            }
+            "--sample" => {
+                sample = Some(
+                    iter.next()
+                        .and_then(|v| v.parse().ok())
+                        .ok_or_else(|| anyhow!("--sample needs a number\nUsage: {}", USAGE))?,
+                );
+            }
+            "--repair" => repair = true,
+            "--config" => {
+                iter.next(); // Already applied by AppPaths::init_from_args
+            }
+            other => return Err(anyhow!("Unknown argument: {}\nUsage: {}", other, USAGE)),
+        }
+    }
+
+    let config = Config::load()?;
+    let (client, calendars, _, _, warning) = RustyClient::connect_with_fallback(config)
+        .await
+        .map_err(|e| anyhow!(e))?;
+    if let Some(w) = warning {
+        return Err(anyhow!("{}: the server is needed to compare with.", w));
+    }
+    let targets: Vec<_> = calendars
+        .iter()
+        .filter(|c| c.href != LOCAL_CALENDAR_HREF && !client.is_sync_disabled(&c.href))
+        .filter(|c| calendar.is_none_or(|w| c.name == *w || c.href == *w))
+        .collect();
+    if let Some(wanted) = calendar
+        && targets.is_empty()
+    {
+        return Err(anyhow!("Unknown calendar '{}'", wanted));
+    }
+
+    let mut drifted = 0;
+    for cal in targets {
+        let report = match client.verify_calendar(&cal.href, sample, repair).await {
+            Ok(report) => report,
+            Err(e) => {
+                eprintln!("{}: could not verify ({})", cal.name, e);
+                continue;
+            }
+        };
+        println!(
+            "{}: {} resources, {} downloaded, {} problems",
+            cal.name,
+            report.listed,
+            report.downloaded,
+            report.findings.len()
+        );
+        for finding in &report.findings {
+            let summary = if finding.summary.is_empty() {
+                finding.href.as_str()
+            } else {
+                finding.summary.as_str()
+            };
+            println!("  {}: {}", summary, finding.drift.describe());
+        }
+        if report.repaired {
+            println!("  Repaired the local copy.");
+        }
+        drifted += report.findings.len();
+    }
+    if drifted > 0 && !repair {
+        println!("Run again with --repair to replace the local copy with the server's.");
+    }
+    Ok(())
+}
@@ -1,80 +1,118 @@
 // File: src/tui/view.rs
+use crate::cache;
 use crate::color_utils;
-use crate::store::UNCATEGORIZED_ID;
+use crate::config::TuiColumn;
+use crate::demo;
+use crate::i18n::tr;
+use crate::model::item::{BADGE_NAME_LEN, DESCRIPTION_PREVIEW_LEN};
+use crate::model::priority::{self, Priority};
+use crate::model::recurrence::{Frequency, describe_rrule};
+use crate::model::{alias_preview, due};
+use crate::storage::LOCAL_CALENDAR_HREF;
+use crate::store::{self, GroupBy, ListRow, NotStarted, UNCATEGORIZED_ID, Urgency};
 use crate::tui::action::SidebarMode;
-use crate::tui::state::{AppState, Focus, InputMode};
+use crate::tui::state::{AppState, Focus, InputMode, RecurrenceStep};
+use chrono::{Local, Utc};
 
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
+    widgets::{
+        Block, Borders, Clear, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Wrap,
+    },
 };
 
 pub fn draw(f: &mut Frame, state: &mut AppState) {
+    let glyphs = state.glyphs;
     let full_help_text = vec![
         Line::from(vec![
             Span::styled(
-                " GLOBAL ",
+                tr("help.section_global"),
                 Style::default()
                     .fg(Color::Cyan)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::raw(" Tab:Switch Focus  ?:Toggle Help  q:Quit"),
+            Span::raw(tr("help.global")),
         ]),
         Line::from(vec![
             Span::styled(
-                " NAVIGATION ",
+                tr("help.section_navigation"),
                 Style::default()
                     .fg(Color::Yellow)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::raw(" j/k:Up/Down  PgUp/PgDn:Scroll"),
+            Span::raw(tr("help.navigation")),
         ]),
         Line::from(vec![
             Span::styled(
-                " TASKS ",
+                tr("help.section_tasks"),
                 Style::default()
                     .fg(Color::Green)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::raw(" a:Add  e:Edit Title  E:Edit Desc  d:Delete  Space:Toggle Done"),
+            Span::raw(tr("help.tasks_1")),
         ]),
         Line::from(vec![
             Span::styled("       ", Style::default()), // Indent alignment
-            Span::raw("s:Start/Pause  x:Cancel  M:Move  r:Sync  X:Export(Local)"),
+            Span::raw(tr("help.tasks_2")),
+        ]),
+        Line::from(vec![
+            Span::styled("       ", Style::default()), // Indent alignment
+            Span::raw(tr("help.tasks_3")),
+        ]),
+        Line::from(vec![
+            Span::styled("       ", Style::default()), // Indent alignment
+            Span::raw(tr("help.tasks_4")),
         ]),
         Line::from(vec![
             Span::styled(
-                " ORGANIZATION ",
+                tr("help.section_organization"),
                 Style::default()
                     .fg(Color::Magenta)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::raw(
-                " +/-:Priority  </>:Indent  y:Yank  b:Block(w/Yank)  c:Child(w/Yank)  C:NewChild",
-            ),
+            Span::raw(tr("help.organization_1")),
+        ]),
+        Line::from(vec![
+            Span::styled("              ", Style::default()), // Indent alignment
+            Span::raw(tr("help.organization_2")),
         ]),
         Line::from(vec![
             Span::styled(
-                " VIEW & FILTER ",
+                tr("help.section_view"),
                 Style::default()
                     .fg(Color::Blue)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::raw(" /:Search  H:Hide Completed  1:Cal View  2:Tag View"),
+            Span::raw(tr("help.view_1")),
+        ]),
+        Line::from(vec![
+            Span::styled("               ", Style::default()), // Indent alignment
+            Span::raw(tr("help.view_2")),
+        ]),
+        Line::from(vec![
+            Span::styled("               ", Style::default()), // Indent alignment
+            Span::raw(tr("help.view_3")),
+        ]),
+        Line::from(vec![
+            Span::styled("               ", Style::default()), // Indent alignment
+            Span::raw(tr("help.view_4")),
         ]),
         Line::from(vec![
             Span::styled(
-                " SIDEBAR ",
+                tr("help.section_sidebar"),
                 Style::default()
                     .fg(Color::LightCyan)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::raw(
-                " Enter:Select/Toggle  Space:Toggle Visibility  *:Show/Clear All  Right:Focus(Solo)",
-            ),
+            Span::raw(tr("help.sidebar_1")),
+        ]),
+        Line::from(vec![
+            Span::styled("          ", Style::default()), // Indent alignment
+            Span::raw(tr("help.sidebar_2")),
         ]),
     ];
 
@@ -110,11 +148,18 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
 
     let (sidebar_title, sidebar_items) = match state.sidebar_mode {
         SidebarMode::Calendars => {
+            let now = Utc::now();
             let items: Vec<ListItem> = state
-                .calendars
-                .iter()
-                .filter(|c| !state.disabled_calendars.contains(&c.href))
+                .get_filtered_calendars()
+                .into_iter()
                 .map(|c| {
+                    if state.disabled_calendars.contains(&c.href) {
+                        let gray = Style::default().fg(Color::DarkGray);
+                        return ListItem::new(Line::from(vec![
+                            Span::styled("  [-]", gray),
+                            Span::styled(format!(" {} (not synced)", c.name), gray),
+                        ]));
+                    }
                     let is_target = Some(&c.href) == state.active_cal_href.as_ref();
                     let is_visible = !state.hidden_calendars.contains(&c.href);
 
@@ -142,17 +187,49 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
                         Span::styled("]", cal_color_style),
                     ];
 
+                    // Not refreshed by the last syncs: what is shown may be out of date
+                    let synced = state.sync_times.get(&c.href).copied();
+                    let is_stale = c.href != LOCAL_CALENDAR_HREF && cache::is_stale(synced, now);
+
                     let text_style = if is_target {
                         Style::default()
                             .fg(Color::Yellow)
                             .add_modifier(Modifier::BOLD)
-                    } else if !is_visible {
+                    } else if !is_visible || is_stale {
                         Style::default().fg(Color::DarkGray)
                     } else {
                         Style::default()
                     };
 
                     spans.push(Span::styled(format!(" {}", c.name), text_style));
+                    if let Some(stats) = state.calendar_stats.get(&c.href)
+                        && stats.open > 0
+                    {
+                        spans.push(Span::styled(
+                            format!(" ({})", stats.label()),
+                            Style::default().fg(Color::Gray),
+                        ));
+                    }
+                    if is_stale {
+                        let ago = synced.map_or("never synced".to_string(), |at| {
+                            format!("synced {}", cache::format_ago(at, now))
+                        });
+                        spans.push(Span::styled(
+                            format!(" ({})", ago),
+                            Style::default().fg(Color::DarkGray),
+                        ));
+                    }
+                    if c.is_read_only {
+                        spans.push(Span::styled(
+                            " (read-only)",
+                            Style::default().fg(Color::DarkGray),
+                        ));
+                    } else if c.is_shared {
+                        spans.push(Span::styled(
+                            " (shared)",
+                            Style::default().fg(Color::DarkGray),
+                        ));
+                    }
 
                     ListItem::new(Line::from(spans))
                 })
@@ -199,12 +276,32 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
             };
             (format!(" Tags [2] ({}) ", logic), items)
         }
+        SidebarMode::Agenda => {
+            let today = Local::now().date_naive();
+            let items: Vec<ListItem> = state
+                .agenda
+                .iter()
+                .map(|day| {
+                    let style = match day.date {
+                        None if !day.tasks.is_empty() => Style::default().fg(Color::LightRed),
+                        _ if day.tasks.is_empty() => Style::default().fg(Color::DarkGray),
+                        _ => Style::default(),
+                    };
+                    ListItem::new(Line::from(Span::styled(
+                        format!(" {} ({})", day.label(today), day.tasks.len()),
+                        style,
+                    )))
+                })
+                .collect();
+            (tr("tui.agenda").to_string(), items)
+        }
     };
 
     let sidebar = List::new(sidebar_items)
         .block(
             Block::default()
                 .borders(Borders::ALL)
+                .border_set(glyphs.border)
                 .title(sidebar_title)
                 .border_style(sidebar_style),
         )
@@ -218,15 +315,30 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
     // --- Task List ---
     let list_inner_width = main_chunks[0].width.saturating_sub(2) as usize;
 
+    let now = Utc::now();
+    // The calendar column only tells something when the list mixes calendars
+    let mixed_calendars = store::mixes_calendars(&state.tasks);
     let task_items: Vec<ListItem> = state
         .tasks
         .iter()
         .map(|t| {
             let is_blocked = state.store.is_blocked(t);
+            let band_color =
+                priority::band_color(t.priority).and_then(|hex| color_utils::parse_hex_to_u8(&hex));
             let base_style = if is_blocked {
                 Style::default().fg(Color::DarkGray)
-            } else {
+            } else if color_utils::underline_priority() {
                 match t.priority {
+                    Priority::None => Style::default(),
+                    p if priority::is_urgent(p) => Style::default()
+                        .add_modifier(Modifier::UNDERLINED)
+                        .add_modifier(Modifier::BOLD),
+                    _ => Style::default().add_modifier(Modifier::UNDERLINED),
+                }
+            } else if let Some((r, g, b)) = band_color.or(color_utils::priority_color(t.priority)) {
+                Style::default().fg(Color::Rgb(r, g, b))
+            } else {
+                match t.priority.value() {
                     // 1: Critical -> Red
                     1 => Style::default().fg(Color::Red),
                     // 2: Urgent -> Orange-Red
@@ -249,6 +361,15 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
                 }
             };
 
+            // Not started yet: greyed out when so configured (hidden ones are filtered out)
+            let base_style = if state.not_started == NotStarted::Dim && store::not_started(t, now) {
+                Style::default()
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::ITALIC)
+            } else {
+                base_style
+            };
+
             // Bracket Color logic
             let mut bracket_style = Style::default();
             if let Some(cal) = state.calendars.iter().find(|c| c.href == t.calendar_href)
@@ -263,16 +384,27 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
 
             let due_str = t
                 .due
-                .map(|d| format!(" ({})", d.format("%d/%m")))
+                .map(|d| format!("({})", due::label(d, state.due_display)))
                 .unwrap_or_default();
-            let dur_str = t.format_duration_short();
+            // Tiers of their own, whatever the priority: bold for today and overdue
+            let urgency = store::urgency(t, now, state.due_soon_days);
+            let due_style = match color_utils::urgency_color(urgency) {
+                Some((r, g, b)) if !is_blocked => {
+                    let style = base_style.fg(Color::Rgb(r, g, b));
+                    if urgency >= Urgency::Today {
+                        style.add_modifier(Modifier::BOLD)
+                    } else {
+                        style
+                    }
+                }
+                _ => base_style,
+            };
             let show_indent = state.active_cal_href.is_some() && state.mode != InputMode::Searching;
             let indent = if show_indent {
                 "  ".repeat(t.depth)
             } else {
                 "".to_string()
             };
-            let recur_str = if t.rrule.is_some() { " (R)" } else { "" };
 
             // Alias Hiding Logic
             let mut hidden_tags = std::collections::HashSet::new();
@@ -297,72 +429,174 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
                 .filter(|c| !hidden_tags.contains(*c))
                 .collect();
 
-            // Layout Calculation
-            let tags_str_len: usize = visible_cats.iter().map(|c| c.len() + 2).sum();
-
-            // Manually calc length because we are building spans manually
-            let raw_text = format!(
-                "[{}] {}{}{}{}{}",
-                inner_char,
-                if is_blocked { "[B] " } else { " " },
-                t.summary,
-                dur_str,
-                due_str,
-                recur_str
-            );
+            // Columns in the configured order, space-separated; tags and the calendar are
+            // aligned to the right edge
+            let mut left: Vec<Span> = Vec::new();
+            let mut right: Vec<Span> = Vec::new();
+            for column in &state.tui_columns {
+                let spans: Vec<Span> = match column {
+                    TuiColumn::Checkbox => vec![
+                        Span::styled("[", bracket_style),
+                        Span::styled(inner_char, base_style),
+                        Span::styled("]", bracket_style),
+                    ],
+                    // Blank on unprioritized tasks, to keep what follows aligned
+                    TuiColumn::Priority => vec![Span::styled(
+                        match t.priority {
+                            Priority::None => "  ".to_string(),
+                            p => format!("!{}", p),
+                        },
+                        base_style,
+                    )],
+                    TuiColumn::Title => {
+                        let mut title = Vec::new();
+                        if is_blocked {
+                            title.push(Span::raw("[B] "));
+                        }
+                        title.push(Span::styled(t.summary.clone(), base_style));
+                        if t.hidden_subtasks > 0 {
+                            title.push(Span::styled(
+                                format!(" [+{}]", t.hidden_subtasks),
+                                base_style,
+                            ));
+                        }
+                        title
+                    }
+                    TuiColumn::Duration => {
+                        vec![Span::styled(t.format_duration_short(), base_style)]
+                    }
+                    TuiColumn::Due => vec![Span::styled(due_str.clone(), due_style)],
+                    TuiColumn::Recurrence if t.rrule.is_some() => {
+                        vec![Span::styled("(R)", base_style)]
+                    }
+                    TuiColumn::Tags => visible_cats
+                        .iter()
+                        .enumerate()
+                        .flat_map(|(i, cat)| {
+                            let (r, g, b) = color_utils::generate_color(cat);
+                            let color =
+                                Color::Rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8);
+                            [
+                                Span::raw(if i == 0 { "" } else { " " }),
+                                Span::styled(format!("#{}", cat), Style::default().fg(color)),
+                            ]
+                        })
+                        .collect(),
+                    TuiColumn::Calendar if mixed_calendars => state
+                        .calendars
+                        .iter()
+                        .find(|c| c.href == t.calendar_href)
+                        .map(|c| {
+                            let name = c.short_name(BADGE_NAME_LEN, glyphs.ellipsis);
+                            vec![Span::styled(format!("@{}", name), bracket_style)]
+                        })
+                        .unwrap_or_default(),
+                    TuiColumn::Recurrence | TuiColumn::Calendar => Vec::new(),
+                };
+                if spans.iter().all(|s| s.content.is_empty()) {
+                    continue;
+                }
+                let side = match column {
+                    TuiColumn::Tags | TuiColumn::Calendar => &mut right,
+                    _ => &mut left,
+                };
+                if !side.is_empty() {
+                    side.push(Span::raw(" "));
+                }
+                side.extend(spans);
+            }
 
-            // "  " indent + brackets + inner + etc
-            let total_len = indent.len() + raw_text.len() + tags_str_len;
-            let padding_len = list_inner_width.saturating_sub(total_len);
-            let padding = " ".repeat(padding_len);
-
-            // Construct spans for colorful brackets
-            let mut spans = vec![
-                Span::raw(indent),
-                Span::styled("[", bracket_style),
-                Span::styled(inner_char, base_style),
-                Span::styled("]", bracket_style),
-                Span::raw(if is_blocked { " [B] " } else { " " }),
-                Span::styled(
-                    format!("{}{}{}{}", t.summary, dur_str, due_str, recur_str),
-                    base_style,
-                ),
-                Span::raw(padding),
-            ];
-
-            for cat in visible_cats {
-                let (r, g, b) = color_utils::generate_color(cat);
-                let color = Color::Rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8);
-                spans.push(Span::styled(
-                    format!(" #{}", cat),
-                    Style::default().fg(color),
-                ));
+            let width = |spans: &[Span]| spans.iter().map(Span::width).sum::<usize>();
+            let used = indent.len() + width(&left) + width(&right) + 1;
+            let padding = " ".repeat(list_inner_width.saturating_sub(used));
+            let mut spans = vec![Span::raw(indent)];
+            spans.extend(left);
+            spans.push(Span::raw(padding));
+            if !right.is_empty() {
+                spans.push(Span::raw(" "));
+                spans.extend(right);
             }
             ListItem::new(Line::from(spans))
         })
         .collect();
 
+    let list_name = match state.sidebar_mode {
+        SidebarMode::Agenda => state
+            .cal_state
+            .selected()
+            .and_then(|i| state.agenda.get(i))
+            .map_or("Agenda".to_string(), |d| d.label(Local::now().date_naive())),
+        _ if state.next_actions => "Next actions".to_string(),
+        _ => "Tasks".to_string(),
+    };
     let mut title = if state.loading {
-        " Tasks (Loading...) ".to_string()
+        format!(" {} (Loading...) ", list_name)
     } else {
-        format!(" Tasks ({}) ", state.tasks.len())
+        format!(" {} ({}) ", list_name, state.tasks.len())
     };
-    if state.unsynced_changes {
-        title.push_str(" [UNSYNCED] ");
+    let overdue = state.store.overdue_count(&state.disabled_calendars, now);
+    if overdue > 0 {
+        title.push_str(&format!("[{} overdue] ", overdue));
+    }
+    if state.group_by != GroupBy::None && state.sidebar_mode != SidebarMode::Agenda {
+        title.push_str(&format!("[by {}] ", state.group_by));
+    }
+    if demo::is_active() {
+        title.push_str("[DEMO] ");
+    }
+    if state.sync_status.is_synced() {
+        if state.sync_status.last_sync.is_some() {
+            title.push_str(&format!(" [{}] ", state.sync_status.label()));
+        }
+    } else {
+        title.push_str(&format!(" [UNSYNCED: {}] ", state.sync_status.pending));
     }
 
     let main_style = if state.active_focus == Focus::Main {
         Style::default().fg(Color::Yellow)
-    } else if state.unsynced_changes {
+    } else if !state.sync_status.is_synced() {
         Style::default().fg(Color::LightRed)
     } else {
         Style::default()
     };
 
-    let task_list = List::new(task_items)
+    let list_items: Vec<ListItem> = if state.rows.is_empty() {
+        task_items
+    } else {
+        state
+            .rows
+            .iter()
+            .map(|row| match row {
+                ListRow::Group {
+                    label,
+                    count,
+                    collapsed,
+                    ..
+                } => ListItem::new(Line::from(Span::styled(
+                    format!(
+                        "{} {} ({})",
+                        if *collapsed {
+                            glyphs.collapsed
+                        } else {
+                            glyphs.expanded
+                        },
+                        label,
+                        count
+                    ),
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ))),
+                ListRow::Task(i) => task_items[*i].clone(),
+            })
+            .collect()
+    };
+
+    let task_list = List::new(list_items)
         .block(
             Block::default()
                 .borders(Borders::ALL)
+                .border_set(glyphs.border)
                 .title(title)
                 .border_style(main_style),
         )
@@ -377,8 +611,27 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
     // Details
     let mut full_details = String::new();
     if let Some(task) = state.get_selected_task() {
+        // The list may show the due date relative to today: the full one goes here
+        if let Some(due) = task.due {
+            full_details.push_str(&format!("Due {}\n\n", due::full(due)));
+        }
         if !task.description.is_empty() {
-            full_details.push_str(&task.description);
+            // Long notes are cut until the pane has the focus
+            match task.description_preview(DESCRIPTION_PREVIEW_LEN) {
+                Some(preview) if state.active_focus != Focus::Details => {
+                    full_details.push_str(preview);
+                    full_details.push_str(glyphs.ellipsis);
+                    full_details.push_str("\n[w: read the whole description]");
+                }
+                _ => full_details.push_str(&task.description),
+            }
+            full_details.push_str("\n\n");
+        }
+        if let Some(rrule) = &task.rrule {
+            full_details.push_str(&format!("Repeats {}", describe_rrule(rrule)));
+            if task.recur_from_completion {
+                full_details.push_str(" (from completion)");
+            }
             full_details.push_str("\n\n");
         }
         if !task.dependencies.is_empty() {
@@ -392,16 +645,55 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
                 let check = if is_done { "[x]" } else { "[ ]" };
                 full_details.push_str(&format!(" {} {}\n", check, name));
             }
+            full_details.push('\n');
+        }
+        // All of them, also those folded away or filtered out of the list
+        let children = state.store.children(&task.uid);
+        if !children.is_empty() {
+            full_details.push_str("[Subtasks]:\n");
+            for child in children {
+                let check = if child.status.is_done() { "[x]" } else { "[ ]" };
+                full_details.push_str(&format!(" {} {}\n", check, child.summary));
+            }
         }
     }
     if full_details.is_empty() {
         full_details = "No details.".to_string();
     }
 
+    // Scrolled with j/k once focused (w); kept within the text
+    let details_area = main_chunks[1];
+    let rows = wrapped_rows(&full_details, details_area.width.saturating_sub(2) as usize);
+    let max_scroll = rows.saturating_sub(details_area.height.saturating_sub(2) as usize);
+    state.details_scroll = state.details_scroll.min(max_scroll as u16);
+    let details_style = if state.active_focus == Focus::Details {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
     let details = Paragraph::new(full_details)
         .wrap(Wrap { trim: true })
-        .block(Block::default().borders(Borders::ALL).title(" Details "));
-    f.render_widget(details, main_chunks[1]);
+        .scroll((state.details_scroll, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(glyphs.border)
+                .title(tr("tui.details"))
+                .border_style(details_style),
+        );
+    f.render_widget(details, details_area);
+    if max_scroll > 0 {
+        let mut scrollbar_state =
+            ScrollbarState::new(max_scroll).position(state.details_scroll as usize);
+        f.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight).symbols(glyphs.scrollbar),
+            details_area.inner(Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut scrollbar_state,
+        );
+    }
 
     // Footer
     let footer_area = v_chunks[1];
@@ -411,16 +703,75 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
         InputMode::Creating
         | InputMode::Editing
         | InputMode::Searching
-        | InputMode::EditingDescription => {
+        | InputMode::EditingDescription
+        | InputMode::Rescheduling
+        | InputMode::EditingRecurrence
+        | InputMode::EditingDefaults
+        | InputMode::EditingAlias
+        | InputMode::ReviewRescheduling
+        | InputMode::TriageEditing => {
             // ... Input Mode Rendering logic ...
             let (mut title_str, prefix, color) = match state.mode {
                 InputMode::Searching => (" Search ".to_string(), "/ ", Color::Green),
                 InputMode::Editing => (" Edit Title ".to_string(), "> ", Color::Magenta),
-                InputMode::EditingDescription => {
-                    (" Edit Description ".to_string(), "📝 ", Color::Blue)
+                InputMode::Rescheduling => (
+                    " Reschedule Occurrence (date) ".to_string(),
+                    "@ ",
+                    Color::LightMagenta,
+                ),
+                InputMode::EditingDescription => (
+                    " Edit Description ".to_string(),
+                    glyphs.description,
+                    Color::Blue,
+                ),
+                InputMode::EditingDefaults => {
+                    let cal_name = state
+                        .calendars
+                        .iter()
+                        .find(|c| Some(&c.href) == state.active_cal_href.as_ref())
+                        .map(|c| c.name.as_str())
+                        .unwrap_or("calendar");
+                    (
+                        format!(" Defaults for {} (!priority #tags ~duration) ", cal_name),
+                        glyphs.defaults,
+                        Color::LightBlue,
+                    )
                 }
+                InputMode::EditingRecurrence => {
+                    let draft = &state.recurrence_draft;
+                    let title = match state.recurrence_step {
+                        RecurrenceStep::Frequency => {
+                            " Repeat: (d)aily (w)eekly (m)onthly (y)early (n)one ".to_string()
+                        }
+                        RecurrenceStep::Interval => {
+                            format!(" Every how many {}? ", draft.freq.unit())
+                        }
+                        RecurrenceStep::Days if draft.freq == Frequency::Weekly => {
+                            " On which days? (e.g. mon,wed; empty for any) ".to_string()
+                        }
+                        RecurrenceStep::Days => {
+                            " Day of month? (1-31; empty to follow due date) ".to_string()
+                        }
+                    };
+                    (title, glyphs.recurrence, Color::LightCyan)
+                }
+                InputMode::ReviewRescheduling => {
+                    (" New Due Date ".to_string(), "@ ", Color::LightMagenta)
+                }
+                InputMode::TriageEditing => (
+                    " Tags and Dates (#tag @due ^start !priority ~duration) ".to_string(),
+                    "> ",
+                    Color::LightGreen,
+                ),
+                InputMode::EditingAlias => (
+                    " Alias (#alias=#tag1,#tag2) ".to_string(),
+                    "# ",
+                    Color::LightGreen,
+                ),
                 InputMode::Creating => {
-                    if state.creating_child_of.is_some() {
+                    if state.creating_note {
+                        (" Create Note ".to_string(), "> ", Color::LightBlue)
+                    } else if state.creating_child_of.is_some() {
                         (" Create Child Task ".to_string(), "> ", Color::LightYellow)
                     } else {
                         (" Create Task ".to_string(), "> ", Color::Yellow)
@@ -433,22 +784,34 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
                 && state.input_buffer.starts_with('#'))
                 || (state.mode == InputMode::Creating
                     && state.input_buffer.starts_with('#')
-                    && state.creating_child_of.is_none());
+                    && state.creating_child_of.is_none()
+                    && !state.creating_note);
 
             if show_tag_hint {
                 title_str.push_str(" [Enter to jump to tag] ");
             }
+            if matches!(state.mode, InputMode::Creating | InputMode::Editing)
+                && !state.creating_note
+                && let Some(preview) = alias_preview(&state.input_buffer, &state.tag_aliases)
+            {
+                title_str.push_str(&format!(" [{}] ", preview));
+            }
 
             let input_text = format!("{}{}", prefix, state.input_buffer);
             let input = Paragraph::new(input_text)
                 .style(Style::default().fg(color))
-                .block(Block::default().borders(Borders::ALL).title(title_str))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_set(glyphs.border)
+                        .title(title_str),
+                )
                 .wrap(Wrap { trim: false });
             f.render_widget(input, footer_area);
 
             // Cursor rendering
             let cursor_x =
-                footer_area.x + 1 + prefix.chars().count() as u16 + state.cursor_position as u16;
+                footer_area.x + 1 + Span::raw(prefix).width() as u16 + state.cursor_position as u16;
             f.set_cursor_position((
                 cursor_x.min(footer_area.x + footer_area.width - 2),
                 footer_area.y + 1,
@@ -457,7 +820,12 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
         _ => {
             if state.show_full_help {
                 let p = Paragraph::new(full_help_text)
-                    .block(Block::default().borders(Borders::ALL).title(" Help "))
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .border_set(glyphs.border)
+                            .title(tr("help.title")),
+                    )
                     .wrap(Wrap { trim: false });
                 f.render_widget(p, footer_area);
             } else {
@@ -466,16 +834,19 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
                     .block(
                         Block::default()
                             .borders(Borders::LEFT | Borders::TOP | Borders::BOTTOM)
-                            .title(" Status "),
+                            .border_set(glyphs.border)
+                            .title(tr("help.status")),
                     );
                 let help_str = match state.active_focus {
-                    Focus::Sidebar => "Ret:Select Space:Vis *:All Tab:Tasks".to_string(),
-                    Focus::Main => "a:Add e:Edit Spc:Done d:Del /:Find".to_string(),
+                    Focus::Sidebar => tr("help.short_sidebar"),
+                    Focus::Main => tr("help.short_main"),
+                    Focus::Details => tr("help.short_details"),
                 };
                 let help = Paragraph::new(help_str).alignment(Alignment::Right).block(
                     Block::default()
                         .borders(Borders::RIGHT | Borders::TOP | Borders::BOTTOM)
-                        .title(" Actions "),
+                        .border_set(glyphs.border)
+                        .title(tr("help.actions")),
                 );
 
                 let chunks = Layout::default()
@@ -489,7 +860,16 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
     }
 
     // Popup logic for Move/Export (simplified)
-    if state.mode == InputMode::Moving {
+    if matches!(
+        state.mode,
+        InputMode::Moving | InputMode::Duplicating | InputMode::TriageFiling
+    ) {
+        let title = match (state.mode, state.duplicate_with_subtree) {
+            (InputMode::Moving, _) => tr("tui.move_task"),
+            (InputMode::TriageFiling, _) => " File In ",
+            (_, false) => tr("tui.duplicate_task"),
+            (_, true) => tr("tui.duplicate_subtree"),
+        };
         let area = centered_rect(60, 50, f.area());
         let items: Vec<ListItem> = state
             .move_targets
@@ -497,11 +877,307 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
             .map(|c| ListItem::new(c.name.as_str()))
             .collect();
         let popup = List::new(items)
-            .block(Block::default().borders(Borders::ALL).title(" Move Task "))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_set(glyphs.border)
+                    .title(title),
+            )
             .highlight_style(Style::default().bg(Color::Blue));
         f.render_widget(Clear, area);
         f.render_stateful_widget(popup, area, &mut state.move_selection_state);
     }
+
+    if (state.mode == InputMode::Reviewing || state.mode == InputMode::ReviewRescheduling)
+        && let Some(task) = state
+            .review_queue
+            .first()
+            .and_then(|uid| state.store.get_task(uid))
+    {
+        let position = state.review_total - state.review_queue.len() + 1;
+        let title = format!(" Review {}/{} ", position, state.review_total);
+        let mut lines = vec![
+            Line::from(Span::styled(
+                task.summary.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+        ];
+        let calendar = state
+            .calendars
+            .iter()
+            .find(|c| c.href == task.calendar_href);
+        if let Some(cal) = calendar {
+            lines.push(Line::from(format!("Calendar: {}", cal.name)));
+        }
+        let changed = match task.last_modified.or(task.created) {
+            Some(d) => format!(
+                "Last changed: {} ({} weeks ago)",
+                d.with_timezone(&Local).format("%Y-%m-%d"),
+                (chrono::Utc::now() - d).num_weeks()
+            ),
+            None => "Last changed: unknown".to_string(),
+        };
+        lines.push(Line::from(changed));
+        if let Some(due) = task.due {
+            lines.push(Line::from(format!("Due: {}", due::full(due))));
+        }
+        if !task.categories.is_empty() {
+            let tags: Vec<String> = task.categories.iter().map(|c| format!("#{}", c)).collect();
+            lines.push(Line::from(format!("Tags: {}", tags.join(" "))));
+        }
+        if !task.description.is_empty() {
+            let preview = task.description_preview(DESCRIPTION_PREVIEW_LEN);
+            lines.push(Line::from(""));
+            lines.extend(
+                preview
+                    .unwrap_or(&task.description)
+                    .lines()
+                    .map(|l| Line::from(l.to_string())),
+            );
+            if preview.is_some() {
+                lines.push(Line::from(glyphs.ellipsis));
+            }
+        }
+        let area = centered_rect(60, 50, f.area());
+        let popup = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(glyphs.border)
+                .title(title),
+        );
+        f.render_widget(Clear, area);
+        f.render_widget(popup, area);
+    }
+
+    if (state.mode == InputMode::Triaging || state.mode == InputMode::TriageEditing)
+        && let Some(task) = state
+            .triage_queue
+            .first()
+            .and_then(|uid| state.store.get_task(uid))
+    {
+        let position = state.triage_total - state.triage_queue.len() + 1;
+        let title = format!(" Inbox {}/{} ", position, state.triage_total);
+        let mut lines = vec![
+            Line::from(Span::styled(
+                task.summary.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+        ];
+        if let Some(created) = task.created {
+            let created = created.with_timezone(&Local).format("%Y-%m-%d %H:%M");
+            lines.push(Line::from(format!("Added: {}", created)));
+        }
+        if let Some(due) = task.due {
+            lines.push(Line::from(format!("Due: {}", due::full(due))));
+        }
+        if !task.categories.is_empty() {
+            let tags: Vec<String> = task.categories.iter().map(|c| format!("#{}", c)).collect();
+            lines.push(Line::from(format!("Tags: {}", tags.join(" "))));
+        }
+        if !task.description.is_empty() {
+            let preview = task.description_preview(DESCRIPTION_PREVIEW_LEN);
+            lines.push(Line::from(""));
+            lines.extend(
+                preview
+                    .unwrap_or(&task.description)
+                    .lines()
+                    .map(|l| Line::from(l.to_string())),
+            );
+            if preview.is_some() {
+                lines.push(Line::from(glyphs.ellipsis));
+            }
+        }
+        let area = centered_rect(60, 50, f.area());
+        let popup = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(glyphs.border)
+                .title(title),
+        );
+        f.render_widget(Clear, area);
+        f.render_widget(popup, area);
+    }
+
+    if state.mode == InputMode::ManagingAliases || state.mode == InputMode::EditingAlias {
+        let area = centered_rect(60, 50, f.area());
+        let items: Vec<ListItem> = state
+            .alias_keys()
+            .iter()
+            .map(|key| {
+                let tags: Vec<String> = state.tag_aliases[key]
+                    .iter()
+                    .map(|t| format!("#{}", t))
+                    .collect();
+                ListItem::new(format!("#{} {} {}", key, glyphs.arrow, tags.join(" ")))
+            })
+            .collect();
+        let title = if items.is_empty() {
+            " Tag Aliases (none yet, a to add) "
+        } else {
+            " Tag Aliases "
+        };
+        let popup = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_set(glyphs.border)
+                    .title(title),
+            )
+            .highlight_style(Style::default().bg(Color::Blue));
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(popup, area, &mut state.alias_selection_state);
+    }
+
+    if state.mode == InputMode::PreviewingSync {
+        let area = centered_rect(80, 70, f.area());
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(area);
+        let items: Vec<ListItem> = state
+            .pending_changes
+            .iter()
+            .map(|c| {
+                let mark = if c.held { "[held]" } else { "[push]" };
+                let style = if c.held {
+                    Style::default().fg(Color::DarkGray)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(format!("{} {:<6} {}", mark, c.kind, c.summary)).style(style)
+            })
+            .collect();
+        let title = format!(" Unsynced Changes ({}) ", state.pending_changes.len());
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_set(glyphs.border)
+                    .title(title),
+            )
+            .highlight_style(Style::default().bg(Color::Blue));
+
+        let lines: Vec<Line> = state
+            .pending_state
+            .selected()
+            .and_then(|i| state.pending_changes.get(i))
+            .map(|c| {
+                c.lines
+                    .iter()
+                    .map(|l| {
+                        let color = if l.starts_with('+') {
+                            Color::Green
+                        } else {
+                            Color::Red
+                        };
+                        Line::from(Span::styled(l.clone(), Style::default().fg(color)))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let diff = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(glyphs.border)
+                .title(" Diff "),
+        );
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, chunks[0], &mut state.pending_state);
+        f.render_widget(diff, chunks[1]);
+    }
+
+    if state.mode == InputMode::ViewingWireLog {
+        let area = centered_rect(80, 70, f.area());
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(area);
+        let items: Vec<ListItem> = state
+            .wire_entries
+            .iter()
+            .map(|e| {
+                let style = if e.is_failure() {
+                    Style::default().fg(Color::Red)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(e.summary()).style(style)
+            })
+            .collect();
+        let title = format!(" Requests ({}) ", state.wire_entries.len());
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_set(glyphs.border)
+                    .title(title),
+            )
+            .highlight_style(Style::default().bg(Color::Blue));
+
+        let lines: Vec<Line> = state
+            .wire_state
+            .selected()
+            .and_then(|i| state.wire_entries.get(i))
+            .map(|e| e.lines().into_iter().map(Line::from).collect())
+            .unwrap_or_default();
+        let exchange = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(glyphs.border)
+                .title(" Exchange "),
+        );
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, chunks[0], &mut state.wire_state);
+        f.render_widget(exchange, chunks[1]);
+    }
+
+    if state.mode == InputMode::Normal && state.shows_fetch_failures() {
+        let area = centered_rect(60, 40, f.area());
+        let mut lines = vec![
+            Line::from("These calendars could not be fetched, their last synced copy is shown:"),
+            Line::from(""),
+        ];
+        let red = Style::default().fg(Color::Red);
+        lines.extend(
+            state
+                .fetch_failures
+                .iter()
+                .map(|l| Line::from(Span::styled(l.clone(), red))),
+        );
+        let popup = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(glyphs.border)
+                .title(" Sync failed (Esc to close) "),
+        );
+        f.render_widget(Clear, area);
+        f.render_widget(popup, area);
+    }
+}
+
+/// Rows `text` takes once wrapped by words at `width`, as `Wrap` does (words longer than the
+/// width are cut). Counts characters, not their display width.
+fn wrapped_rows(text: &str, width: usize) -> usize {
+    let width = width.max(1);
+    text.lines()
+        .map(|line| {
+            let (mut rows, mut col) = (1, 0);
+            for word in line.split_whitespace().map(|w| w.chars().count()) {
+                if col > 0 && col + 1 + word > width {
+                    rows += 1;
+                    col = 0;
+                }
+                col += if col == 0 { word } else { word + 1 };
+                if col > width {
+                    rows += (col - 1) / width;
+                    col = (col - 1) % width + 1;
+                }
+            }
+            rows
+        })
+        .sum()
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
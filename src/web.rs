@@ -0,0 +1,127 @@
+// File: ./src/web.rs
+// Storage for the browser build (wasm32): the calendar list and each calendar's tasks and sync
+// token, as JSON in IndexedDB, the same data the native `Cache` keeps on disk. IndexedDB is
+// asynchronous, hence async methods where `Cache` has blocking ones.
+use crate::model::{CalendarListEntry, Task};
+use crate::store::TaskStore;
+use anyhow::{Result, anyhow};
+use rexie::{ObjectStore, Rexie, TransactionMode};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+
+const DB_NAME: &str = "cfait";
+const DB_VERSION: u32 = 1;
+/// One record per calendar, keyed by href
+const TASKS_STORE: &str = "tasks";
+/// A single record holding the calendar list
+const CALENDARS_STORE: &str = "calendars";
+const CALENDARS_KEY: &str = "list";
+
+#[derive(Serialize, Deserialize)]
+struct CalendarCache {
+    sync_token: Option<String>,
+    tasks: Vec<Task>,
+}
+
+/// rexie errors wrap JS values, which anyhow errors cannot hold
+fn idb_error(e: rexie::Error) -> anyhow::Error {
+    anyhow!("IndexedDB: {}", e)
+}
+
+pub struct IdbStorage {
+    db: Rexie,
+}
+
+impl IdbStorage {
+    /// Opens (and on first use creates) the database of this origin.
+    pub async fn open() -> Result<Self> {
+        let db = Rexie::builder(DB_NAME)
+            .version(DB_VERSION)
+            .add_object_store(ObjectStore::new(TASKS_STORE))
+            .add_object_store(ObjectStore::new(CALENDARS_STORE))
+            .build()
+            .await
+            .map_err(idb_error)?;
+        Ok(Self { db })
+    }
+
+    async fn get(&self, store: &str, key: &str) -> Result<Option<String>> {
+        let tx = self
+            .db
+            .transaction(&[store], TransactionMode::ReadOnly)
+            .map_err(idb_error)?;
+        let value = tx
+            .store(store)
+            .map_err(idb_error)?
+            .get(JsValue::from_str(key))
+            .await
+            .map_err(idb_error)?;
+        tx.done().await.map_err(idb_error)?;
+        Ok(value.and_then(|v| v.as_string()))
+    }
+
+    async fn put(&self, store: &str, key: &str, json: String) -> Result<()> {
+        let tx = self
+            .db
+            .transaction(&[store], TransactionMode::ReadWrite)
+            .map_err(idb_error)?;
+        tx.store(store)
+            .map_err(idb_error)?
+            .put(&JsValue::from_str(&json), Some(&JsValue::from_str(key)))
+            .await
+            .map_err(idb_error)?;
+        tx.done().await.map_err(idb_error)?;
+        Ok(())
+    }
+
+    pub async fn save(&self, key: &str, tasks: &[Task], sync_token: Option<String>) -> Result<()> {
+        let data = CalendarCache {
+            sync_token,
+            tasks: tasks.to_vec(),
+        };
+        self.put(TASKS_STORE, key, serde_json::to_string(&data)?)
+            .await
+    }
+
+    pub async fn load(&self, key: &str) -> Result<(Vec<Task>, Option<String>)> {
+        match self.get(TASKS_STORE, key).await? {
+            Some(json) => {
+                let cache: CalendarCache = serde_json::from_str(&json)?;
+                Ok((cache.tasks, cache.sync_token))
+            }
+            None => Ok((vec![], None)),
+        }
+    }
+
+    pub async fn save_calendars(&self, cals: &[CalendarListEntry]) -> Result<()> {
+        self.put(CALENDARS_STORE, CALENDARS_KEY, serde_json::to_string(cals)?)
+            .await
+    }
+
+    pub async fn load_calendars(&self) -> Result<Vec<CalendarListEntry>> {
+        match self.get(CALENDARS_STORE, CALENDARS_KEY).await? {
+            Some(json) => Ok(serde_json::from_str(&json)?),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// A store holding every saved calendar, as the native UIs fill theirs from the cache.
+    pub async fn load_store(&self) -> Result<TaskStore> {
+        let mut store = TaskStore::new();
+        for cal in self.load_calendars().await? {
+            let (tasks, _) = self.load(&cal.href).await?;
+            store.insert(cal.href, tasks);
+        }
+        Ok(store)
+    }
+
+    /// Saves every calendar of `store`, keeping their sync tokens. The store's own writes go to
+    /// the disk cache, which the browser build lacks, so the frontend calls this after changes.
+    pub async fn save_store(&self, store: &TaskStore) -> Result<()> {
+        for (href, tasks) in &store.calendars {
+            let (_, token) = self.load(href).await?;
+            self.save(href, tasks, token).await?;
+        }
+        Ok(())
+    }
+}
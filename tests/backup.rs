@@ -0,0 +1,43 @@
+// File: ./tests/backup.rs
+use cfait::backup;
+use cfait::journal::{Action, Journal};
+use cfait::model::Task;
+use cfait::storage::LocalStorage;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+#[test]
+fn test_snapshot_and_restore() {
+    let temp_dir = env::temp_dir().join(format!("cfait_test_backup_{}", std::process::id()));
+    let _ = fs::create_dir_all(&temp_dir);
+    unsafe {
+        env::set_var("CFAIT_TEST_DIR", &temp_dir);
+    }
+
+    LocalStorage::save(&[Task::new("Keep me", &HashMap::new())]).unwrap();
+    let name = backup::snapshot(None).unwrap();
+    assert_eq!(backup::snapshot_if_due().unwrap(), None);
+
+    // A bad operation wipes the local tasks and queues a change
+    LocalStorage::save(&[]).unwrap();
+    Journal::push(Action::Create(Task::new("Stray", &HashMap::new()))).unwrap();
+
+    backup::restore(&name).unwrap();
+    let restored = LocalStorage::load().unwrap();
+    let journal = Journal::load();
+    let names = backup::list().unwrap();
+    let escape = backup::restore("../escape");
+
+    unsafe {
+        env::remove_var("CFAIT_TEST_DIR");
+    }
+    let _ = fs::remove_dir_all(&temp_dir);
+
+    assert_eq!(restored.len(), 1);
+    assert_eq!(restored[0].summary, "Keep me");
+    assert!(journal.is_empty());
+    // The state before the restore was kept too
+    assert!(names[0].ends_with("-pre-restore"));
+    assert!(escape.is_err());
+}
@@ -0,0 +1,142 @@
+// File: ./tests/faults.rs
+// Journal replay against a failing server: `cargo test --features faults`.
+#![cfg(feature = "faults")]
+use cfait::client::RustyClient;
+use cfait::client::faults::{self, Faults};
+use cfait::journal::{Action, Journal};
+use cfait::model::Task;
+use http::{Method, StatusCode};
+use mockito::Server;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::sync::Mutex;
+
+// The faults and the test directory are process-wide
+static TEST_MUTEX: Mutex<()> = Mutex::new(());
+
+fn setup_env(suffix: &str) -> std::path::PathBuf {
+    let temp_dir = env::temp_dir().join(format!(
+        "cfait_test_faults_{}_{}",
+        suffix,
+        std::process::id()
+    ));
+    let _ = fs::create_dir_all(&temp_dir);
+    unsafe {
+        env::set_var("CFAIT_TEST_DIR", &temp_dir);
+    }
+    if let Some(p) = Journal::get_path()
+        && p.exists()
+    {
+        let _ = fs::remove_file(p);
+    }
+    temp_dir
+}
+
+fn teardown(path: std::path::PathBuf) {
+    faults::set(Faults::default());
+    unsafe {
+        env::remove_var("CFAIT_TEST_DIR");
+    }
+    let _ = fs::remove_dir_all(path);
+}
+
+fn new_task() -> Task {
+    let mut task = Task::new("T", &HashMap::new());
+    task.uid = "task".to_string();
+    task.calendar_href = "/cal/".to_string();
+    task
+}
+
+#[tokio::test]
+async fn test_offline_then_replayed() {
+    let _guard = TEST_MUTEX.lock().unwrap();
+    let temp_dir = setup_env("offline");
+
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("PUT", "/cal/task.ics")
+        .with_status(201)
+        .create_async()
+        .await;
+    let client = RustyClient::new(&server.url(), "u", "p", true).unwrap();
+    Journal::push(Action::Create(new_task())).unwrap();
+
+    // No answer at all: the change waits in the queue
+    faults::set(Faults::offline());
+    assert!(client.sync_journal().await.is_err());
+    assert_eq!(Journal::load().queue.len(), 1);
+
+    // Back online: it is pushed once
+    faults::set(Faults::default());
+    let res = client.sync_journal().await;
+    assert!(res.is_ok(), "Sync failed: {:?}", res.err());
+    mock.assert();
+    assert!(Journal::load().is_empty());
+
+    teardown(temp_dir);
+}
+
+#[tokio::test]
+async fn test_429_is_waited_out() {
+    let _guard = TEST_MUTEX.lock().unwrap();
+    let temp_dir = setup_env("429");
+
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("PUT", "/cal/task.ics")
+        .with_status(201)
+        .create_async()
+        .await;
+    let client = RustyClient::new(&server.url(), "u", "p", true).unwrap();
+    Journal::push(Action::Create(new_task())).unwrap();
+
+    // Every other PUT is refused, starting with the first: its retry goes through
+    faults::set(Faults {
+        fail_percent: 50,
+        status: Some(StatusCode::TOO_MANY_REQUESTS),
+        only: vec![Method::PUT],
+        ..Default::default()
+    });
+    let res = client.sync_journal().await;
+    assert!(res.is_ok(), "Sync failed: {:?}", res.err());
+    mock.assert();
+    assert!(Journal::load().is_empty());
+
+    teardown(temp_dir);
+}
+
+#[tokio::test]
+async fn test_412_queues_a_conflict_copy() {
+    let _guard = TEST_MUTEX.lock().unwrap();
+    let temp_dir = setup_env("412");
+
+    let server = Server::new_async().await;
+    let url = server.url();
+    let client = RustyClient::new(&url, "u", "p", true).unwrap();
+    let mut task = new_task();
+    task.href = format!("{}/cal/task.ics", url);
+    task.etag = "\"123\"".to_string();
+    Journal::push(Action::Update(task)).unwrap();
+
+    // The update is refused as changed on the server, and nothing to merge with is cached:
+    // a copy is queued in its place, itself refused until the server accepts PUTs again
+    faults::set(Faults {
+        fail_percent: 100,
+        status: Some(StatusCode::PRECONDITION_FAILED),
+        only: vec![Method::PUT],
+        ..Default::default()
+    });
+    assert!(client.sync_journal().await.is_err());
+    let queue = Journal::load().queue;
+    assert_eq!(queue.len(), 1);
+    match &queue[0] {
+        Action::Create(copy) => {
+            assert_eq!(copy.summary, "T (Conflict Copy)");
+            assert_ne!(copy.uid, "task");
+        }
+        other => panic!("Expected a conflict copy, got {:?}", other),
+    }
+
+    teardown(temp_dir);
+}
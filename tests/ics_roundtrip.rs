@@ -0,0 +1,173 @@
+// File: ./tests/ics_roundtrip.rs
+use cfait::model::item::ItemKind;
+use cfait::model::{Duration, Priority, Reminder, Task, TaskStatus};
+use chrono::{DateTime, TimeZone, Utc};
+use proptest::prelude::*;
+use std::collections::BTreeSet;
+
+const ETAG: &str = "\"etag-1\"";
+const HREF: &str = "/cal/test/item.ics";
+const CAL_HREF: &str = "/cal/test/";
+
+/// Words with the characters TEXT values must escape (RFC 5545 3.3.11) and non-ASCII ones,
+/// separated by spaces or newlines.
+fn text() -> impl Strategy<Value = String> {
+    "[A-Za-z0-9,;:\\\\äéßœ€日本語🦀]{1,12}([ \n][A-Za-z0-9,;:\\\\äéßœ€日本語🦀]{1,12}){0,6}"
+}
+
+/// Tag-like values, non-empty and without surrounding whitespace (from_ics trims them).
+fn category() -> impl Strategy<Value = String> {
+    "[a-zä日][a-z0-9:_,;\\\\ \néß€日本-]{0,10}[a-z0-9ä日]"
+}
+
+fn date() -> impl Strategy<Value = DateTime<Utc>> {
+    // 2000-01-01 .. 2100-01-01, second precision (what to_ics writes)
+    (946_684_800i64..4_102_444_800i64).prop_map(|ts| Utc.timestamp_opt(ts, 0).unwrap())
+}
+
+type Dates = (
+    Option<DateTime<Utc>>,
+    Option<DateTime<Utc>>,
+    Option<DateTime<Utc>>,
+    Option<DateTime<Utc>>,
+    Option<DateTime<Utc>>,
+);
+
+/// DUE, DTSTART, CREATED, LAST-MODIFIED, COMPLETED
+fn dates() -> impl Strategy<Value = Dates> {
+    let d = || proptest::option::of(date());
+    (d(), d(), d(), d(), d())
+}
+
+fn status() -> impl Strategy<Value = TaskStatus> {
+    prop_oneof![
+        Just(TaskStatus::NeedsAction),
+        Just(TaskStatus::InProcess),
+        Just(TaskStatus::Completed),
+        Just(TaskStatus::Cancelled),
+    ]
+}
+
+fn rrule() -> impl Strategy<Value = Option<(String, bool)>> {
+    let rule = prop_oneof![
+        Just("FREQ=DAILY".to_string()),
+        Just("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE".to_string()),
+        Just("FREQ=MONTHLY;BYMONTHDAY=15".to_string()),
+        Just("FREQ=YEARLY;COUNT=3".to_string()),
+    ];
+    proptest::option::of((rule, any::<bool>()))
+}
+
+fn reminder() -> impl Strategy<Value = Reminder> {
+    prop_oneof![
+        date().prop_map(Reminder::At),
+        (-10_000i64..10_000).prop_map(Reminder::BeforeDue),
+        (-10_000i64..10_000).prop_map(Reminder::BeforeStart),
+    ]
+}
+
+type Extras = (
+    Vec<Reminder>,
+    BTreeSet<DateTime<Utc>>,
+    bool,
+    Option<i64>,
+    ItemKind,
+);
+
+/// VALARMs, EXDATEs, the Nextcloud list state and VTODO or VJOURNAL: the parts to_ics writes
+/// by hand.
+fn extras() -> impl Strategy<Value = Extras> {
+    (
+        proptest::collection::vec(reminder(), 0..4),
+        proptest::collection::btree_set(date(), 0..4),
+        any::<bool>(),
+        proptest::option::of(any::<i64>()),
+        prop_oneof![4 => Just(ItemKind::Todo), 1 => Just(ItemKind::Journal)],
+    )
+}
+
+fn uid() -> impl Strategy<Value = String> {
+    "[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}"
+}
+
+prop_compose! {
+    fn arb_task()(
+        uid in uid(),
+        summary in text(),
+        description in prop_oneof![Just(String::new()), text()],
+        status in status(),
+        dates in dates(),
+        estimated_duration in proptest::option::of(1u32..100_000),
+        priority in 0u8..=9,
+        parent_uid in proptest::option::of(uid()),
+        dependencies in proptest::collection::btree_set(uid(), 0..4),
+        categories in proptest::collection::btree_set(category(), 0..5),
+        rrule in rrule(),
+        extras in extras(),
+    ) -> Task {
+        let (due, dtstart, created, last_modified, completed) = dates;
+        let (reminders, exdates, hide_subtasks, sort_order, kind) = extras;
+        let mut task = Task::new("", &Default::default());
+        task.uid = uid;
+        task.summary = summary;
+        task.description = description;
+        task.status = status;
+        task.due = due;
+        task.dtstart = dtstart;
+        task.estimated_duration = estimated_duration.map(Duration::from_minutes);
+        task.priority = Priority::from(priority);
+        task.dependencies = dependencies
+            .into_iter()
+            .filter(|d| Some(d) != parent_uid.as_ref())
+            .collect();
+        task.parent_uid = parent_uid;
+        // from_ics returns categories sorted and deduplicated
+        task.categories = categories.into_iter().collect();
+        if let Some((rule, from_done)) = rrule {
+            task.rrule = Some(rule);
+            task.recur_from_completion = from_done;
+            // EXDATEs only go with an RRULE; from_ics returns them sorted and deduplicated
+            task.exdates = exdates.into_iter().collect();
+        }
+        task.hide_subtasks = hide_subtasks;
+        task.sort_order = sort_order;
+        // from_ics keeps the first of identical alarms
+        for reminder in reminders {
+            if !task.reminders.contains(&reminder) {
+                task.reminders.push(reminder);
+            }
+        }
+        task.created = created;
+        task.last_modified = last_modified;
+        task.completed = completed;
+        task.kind = kind;
+        if task.is_note() {
+            // A VJOURNAL has none of the VTODO-only properties
+            task.status = TaskStatus::NeedsAction;
+            task.due = None;
+            task.completed = None;
+            task.estimated_duration = None;
+            task.priority = Priority::None;
+            task.reminders.clear();
+        }
+        task.etag = ETAG.to_string();
+        task.href = HREF.to_string();
+        task.calendar_href = CAL_HREF.to_string();
+        task
+    }
+}
+
+proptest! {
+    #[test]
+    fn roundtrip_preserves_task(task in arb_task()) {
+        let ics = task.to_ics();
+        let parsed = Task::from_ics(&ics, ETAG.to_string(), HREF.to_string(), CAL_HREF.to_string())
+            .map_err(|e| TestCaseError::fail(format!("{}\n{}", e, ics)))?;
+        prop_assert_eq!(parsed, task, "ICS was:\n{}", ics);
+    }
+
+    #[test]
+    fn from_ics_never_panics_on_garbage(raw in "(BEGIN:VCALENDAR\r\n)?(BEGIN:VTODO\r\n)?[ -~\r\n]{0,400}") {
+        let _ = Task::from_ics(&raw, String::new(), String::new(), String::new());
+    }
+}
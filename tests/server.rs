@@ -0,0 +1,71 @@
+// File: ./tests/server.rs
+use cfait::client::RustyClient;
+use cfait::config::Config;
+use cfait::server::{INVALID_PARAMS, METHOD_NOT_FOUND, PARSE_ERROR, Responder, Server};
+use serde_json::{Value, json};
+use std::env;
+use std::fs;
+
+async fn call(server: &mut Server, method: &str, params: Value) -> Value {
+    let line = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+    server.respond(&line.to_string()).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_json_rpc_on_local_calendar() {
+    let temp_dir = env::temp_dir().join(format!("cfait_test_server_{}", std::process::id()));
+    let _ = fs::create_dir_all(&temp_dir);
+    unsafe {
+        env::set_var("CFAIT_TEST_DIR", &temp_dir);
+    }
+
+    // No URL: offline client, everything goes to the Local calendar
+    let client = RustyClient::new("", "", "", false).unwrap();
+    let mut server = Server::new(client, vec![], Config::default());
+    server.sync(None).await.unwrap();
+
+    let cals = call(&mut server, "calendars", Value::Null).await;
+    assert_eq!(cals["result"][0]["name"], "Local");
+
+    let created = call(
+        &mut server,
+        "create",
+        json!({"text": "Water plants !1 #home"}),
+    )
+    .await;
+    let uid = created["result"]["uid"].as_str().unwrap().to_string();
+    assert_eq!(created["result"]["summary"], "Water plants");
+
+    let found = call(&mut server, "tasks", json!({"query": "#home"})).await;
+    assert_eq!(found["result"].as_array().unwrap().len(), 1);
+
+    let toggled = call(&mut server, "toggle", json!({"uid": uid})).await;
+    assert_eq!(toggled["result"]["status"], "Completed");
+    let open = call(&mut server, "tasks", Value::Null).await;
+    assert!(open["result"].as_array().unwrap().is_empty());
+
+    let deleted = call(&mut server, "delete", json!({"uid": uid})).await;
+    assert_eq!(deleted["result"], true);
+    let missing = call(&mut server, "delete", json!({"uid": uid})).await;
+    assert!(
+        missing["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("Unknown task")
+    );
+
+    // Protocol errors
+    let bad = call(&mut server, "create", json!({})).await;
+    assert_eq!(bad["error"]["code"], INVALID_PARAMS);
+    let unknown = call(&mut server, "explode", Value::Null).await;
+    assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+    let garbage = server.respond("{not json").await.unwrap();
+    assert_eq!(garbage["error"]["code"], PARSE_ERROR);
+    let notification = json!({"jsonrpc": "2.0", "method": "calendars"}).to_string();
+    assert!(server.respond(&notification).await.is_none());
+
+    let _ = fs::remove_dir_all(&temp_dir);
+    unsafe {
+        env::remove_var("CFAIT_TEST_DIR");
+    }
+}
@@ -1,7 +1,10 @@
 // File: ./tests/sync_edge_cases.rs
+use cfait::cache::{Cache, Discovery};
 use cfait::client::RustyClient;
+use cfait::config::Config;
 use cfait::journal::{Action, Journal};
-use cfait::model::Task;
+use cfait::model::{CalendarListEntry, Task};
+use chrono::{Duration, Utc};
 use mockito::Server;
 use std::collections::HashMap;
 use std::env;
@@ -121,3 +124,118 @@ async fn test_sync_500_keeps_item_in_queue() {
 
     teardown(temp_dir);
 }
+
+#[test]
+fn test_invalidation_keeps_local_changes() {
+    let _guard = TEST_MUTEX.lock().unwrap();
+    let temp_dir = setup_env("invalidate");
+
+    let task = |uid: &str| {
+        let mut t = Task::new(uid, &HashMap::new());
+        t.uid = uid.to_string();
+        t.calendar_href = "/cal/".to_string();
+        t.href = format!("/cal/{}.ics", uid);
+        t.etag = "\"1\"".to_string();
+        t
+    };
+    Cache::save("/cal/", &[task("a"), task("b")], Some("token".to_string())).unwrap();
+    Cache::mark_synced("/cal/").unwrap();
+    Journal::push(Action::Update(task("b"))).unwrap();
+    let client = RustyClient::new("", "", "", false).unwrap();
+
+    // One resource: the rest of the mirror stays, the token goes so the calendar is listed
+    assert!(client.invalidate_resource("/cal/", "/cal/a.ics").unwrap());
+    assert!(!client.invalidate_resource("/cal/", "/cal/a.ics").unwrap());
+    let (tasks, token) = Cache::load("/cal/").unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].uid, "b");
+    assert_eq!(token, None);
+
+    // The whole calendar, then everything: the offline queue is untouched
+    client.invalidate_calendar("/cal/").unwrap();
+    assert!(Cache::load("/cal/").unwrap().0.is_empty());
+    assert!(!Cache::sync_times().contains_key("/cal/"));
+    Cache::save("/other/", &[task("c")], None).unwrap();
+    assert!(client.invalidate_all().unwrap() > 0);
+    assert!(Cache::load("/other/").unwrap().0.is_empty());
+    assert_eq!(Journal::load().queue.len(), 1);
+
+    teardown(temp_dir);
+}
+
+#[tokio::test]
+async fn test_startup_falls_back_to_cached_calendars() {
+    let _guard = TEST_MUTEX.lock().unwrap();
+    let temp_dir = setup_env("startup");
+
+    let cached = CalendarListEntry {
+        name: "Work".to_string(),
+        href: "/cal/work/".to_string(),
+        color: None,
+        is_shared: false,
+        is_read_only: false,
+    };
+    Cache::save_calendars(std::slice::from_ref(&cached)).unwrap();
+
+    // Built without a request; the server then refuses to list anything
+    let server = Server::new_async().await;
+    let config = Config {
+        url: server.url(),
+        username: "u".to_string(),
+        password: "p".to_string(),
+        disabled_calendars: vec!["/cal/old/".to_string()],
+        ..Default::default()
+    };
+    let client = RustyClient::from_config(&config).unwrap();
+    assert!(client.is_sync_disabled("/cal/old/"));
+
+    let (calendars, warning) = client.calendars_or_cached().await.unwrap();
+    assert_eq!(calendars.len(), 1);
+    assert_eq!(calendars[0].href, cached.href);
+    assert_eq!(warning.as_deref(), Some("Offline Mode"));
+
+    teardown(temp_dir);
+}
+
+#[tokio::test]
+async fn test_startup_skips_recent_discovery() {
+    let _guard = TEST_MUTEX.lock().unwrap();
+    let temp_dir = setup_env("discovery");
+
+    let server = Server::new_async().await;
+    let account = format!("{} u", server.url());
+    let discovered = CalendarListEntry {
+        name: "Work".to_string(),
+        href: "/cal/work/".to_string(),
+        color: None,
+        is_shared: false,
+        is_read_only: false,
+    };
+    let discovery = Discovery {
+        account: account.clone(),
+        principal: "/principals/u/".to_string(),
+        home_sets: vec!["/cal/".to_string()],
+        calendars: vec![discovered],
+        discovered_at: Utc::now(),
+    };
+    assert!(!discovery.is_fresh(&account, Utc::now() + Duration::days(2)));
+    assert!(!discovery.is_fresh("https://other.example.com u", Utc::now()));
+    Cache::save_discovery(&discovery).unwrap();
+
+    // The server isn't asked (no mock answers): the saved calendars stand in
+    let client = RustyClient::new(&server.url(), "u", "p", true).unwrap();
+    let (calendars, warning) = client.calendars_or_cached().await.unwrap();
+    assert_eq!(calendars.len(), 1);
+    assert_eq!(warning, None);
+
+    // Another user looks everything up again
+    let other = RustyClient::new(&server.url(), "v", "p", true).unwrap();
+    let (_, warning) = other.calendars_or_cached().await.unwrap();
+    assert_eq!(warning.as_deref(), Some("Offline Mode"));
+
+    // A full re-download forgets it too
+    Cache::invalidate_all().unwrap();
+    assert!(Cache::load_discovery().is_none());
+
+    teardown(temp_dir);
+}